@@ -432,9 +432,15 @@ pub fn new_null_array(data_type: &DataType, length: usize) -> ArrayRef {
                 vec![new_empty_array(value.as_ref()).data().clone()],
             ))
         }
-        DataType::Decimal(_, _) => {
-            unimplemented!("Creating null Decimal array not yet supported")
-        }
+        DataType::Decimal(_, _) => make_array(ArrayData::new(
+            data_type.clone(),
+            length,
+            Some(length),
+            Some(MutableBuffer::new_null(length).into()),
+            0,
+            vec![Buffer::from(vec![0u8; 16 * length])],
+            vec![],
+        )),
     }
 }
 
@@ -595,6 +601,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_null_decimal() {
+        let array = new_null_array(&DataType::Decimal(10, 2), 9);
+        let a = array.as_any().downcast_ref::<DecimalArray>().unwrap();
+        assert_eq!(a.len(), 9);
+        assert_eq!(a.precision(), 10);
+        assert_eq!(a.scale(), 2);
+        for i in 0..9 {
+            assert!(a.is_null(i));
+        }
+    }
+
     #[test]
     fn test_null_variable_sized() {
         let array = new_null_array(&DataType::Utf8, 9);
@@ -670,6 +670,66 @@ impl DecimalArray {
         let data = builder.build();
         Self::from(data)
     }
+
+    /// Creates a [DecimalArray] with the given `precision` and `scale` from an
+    /// iterator of optional unscaled values, where `None` denotes a null slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use arrow::array::DecimalArray;
+    /// // [1.23, null, -4.50]
+    /// let array = DecimalArray::from_sparse_iter(vec![Some(123), None, Some(-450)], 5, 2);
+    /// assert_eq!("-4.50", array.value_as_string(2));
+    /// ```
+    pub fn from_sparse_iter<I>(iter: I, precision: usize, scale: usize) -> Self
+    where
+        I: IntoIterator<Item = Option<i128>>,
+    {
+        let mut len = 0;
+        let mut null_buf = MutableBuffer::new(0);
+        let mut values = MutableBuffer::new(0);
+        for item in iter {
+            if len % 8 == 0 {
+                null_buf.push(0u8);
+            }
+            match item {
+                Some(value) => {
+                    bit_util::set_bit(null_buf.as_slice_mut(), len);
+                    values.extend_from_slice(&value.to_le_bytes());
+                }
+                None => values.extend_zeros(16),
+            }
+            len += 1;
+        }
+
+        let data = ArrayData::builder(DataType::Decimal(precision, scale))
+            .len(len)
+            .add_buffer(values.into())
+            .null_bit_buffer(null_buf.into())
+            .build();
+        Self::from(data)
+    }
+
+    /// Returns the element at index `i` formatted with `scale` digits after the
+    /// decimal point, e.g. `-4.50` for the unscaled value `-450` and a scale of 2.
+    pub fn value_as_string(&self, i: usize) -> String {
+        let value = self.value(i);
+        let digits = value.unsigned_abs().to_string();
+        let digits = if self.scale == 0 {
+            digits
+        } else {
+            let digits = format!("{:0>width$}", digits, width = self.scale + 1);
+            let (integral, fractional) = digits.split_at(digits.len() - self.scale);
+            format!("{}.{}", integral, fractional)
+        };
+        if value < 0 {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+
     pub fn precision(&self) -> usize {
         self.precision
     }
@@ -1138,6 +1198,30 @@ mod tests {
         assert_eq!(16, decimal_array.value_length());
     }
 
+    #[test]
+    fn test_decimal_array_from_sparse_iter() {
+        let array = DecimalArray::from_sparse_iter(
+            vec![Some(8_887_000_000), None, Some(-5), Some(0)],
+            23,
+            6,
+        );
+        assert_eq!(&DataType::Decimal(23, 6), array.data_type());
+        assert_eq!(4, array.len());
+        assert_eq!(1, array.null_count());
+        assert!(array.is_null(1));
+        assert_eq!(8_887_000_000, array.value(0));
+        assert_eq!("8887.000000", array.value_as_string(0));
+        assert_eq!("-0.000005", array.value_as_string(2));
+        assert_eq!("0.000000", array.value_as_string(3));
+
+        let sliced = array.slice(2, 2);
+        let sliced = sliced.as_any().downcast_ref::<DecimalArray>().unwrap();
+        assert_eq!(-5, sliced.value(0));
+
+        let integral = DecimalArray::from_sparse_iter(vec![Some(-42)], 10, 0);
+        assert_eq!("-42", integral.value_as_string(0));
+    }
+
     #[test]
     fn test_decimal_array_fmt_debug() {
         let values: [u8; 32] = [
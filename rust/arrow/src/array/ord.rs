@@ -51,6 +51,12 @@ where
     Box::new(move |i, j| left.value(i).cmp(&right.value(j)))
 }
 
+fn compare_decimal<'a>(left: &'a Array, right: &'a Array) -> DynComparator<'a> {
+    let left = left.as_any().downcast_ref::<DecimalArray>().unwrap();
+    let right = right.as_any().downcast_ref::<DecimalArray>().unwrap();
+    Box::new(move |i, j| left.value(i).cmp(&right.value(j)))
+}
+
 fn compare_boolean<'a>(left: &'a Array, right: &'a Array) -> DynComparator<'a> {
     let left = left.as_any().downcast_ref::<BooleanArray>().unwrap();
     let right = right.as_any().downcast_ref::<BooleanArray>().unwrap();
@@ -190,6 +196,7 @@ pub fn build_compare<'a>(left: &'a Array, right: &'a Array) -> Result<DynCompara
         (Duration(Nanosecond), Duration(Nanosecond)) => {
             compare_primitives::<DurationNanosecondType>(left, right)
         }
+        (Decimal(_, _), Decimal(_, _)) => compare_decimal(left, right),
         (Utf8, Utf8) => compare_string::<i32>(left, right),
         (LargeUtf8, LargeUtf8) => compare_string::<i64>(left, right),
         (
@@ -264,6 +271,18 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_decimal() -> Result<()> {
+        let array1 = DecimalArray::from_sparse_iter(vec![Some(-150), Some(5)], 5, 2);
+        let array2 = DecimalArray::from_sparse_iter(vec![Some(-100)], 5, 2);
+
+        let cmp = build_compare(&array1, &array2)?;
+
+        assert_eq!(Ordering::Less, (cmp)(0, 0));
+        assert_eq!(Ordering::Greater, (cmp)(1, 0));
+        Ok(())
+    }
+
     #[test]
     fn test_f64() -> Result<()> {
         let array = Float64Array::from(vec![1.0, 2.0]);
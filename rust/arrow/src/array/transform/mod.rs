@@ -211,7 +211,9 @@ fn build_extend(array: &ArrayData) -> Extend {
             _ => unreachable!(),
         },
         DataType::Struct(_) => structure::build_extend(array),
-        DataType::FixedSizeBinary(_) => fixed_binary::build_extend(array),
+        DataType::FixedSizeBinary(_) | DataType::Decimal(_, _) => {
+            fixed_binary::build_extend(array)
+        }
         DataType::Float16 => unreachable!(),
         /*
         DataType::FixedSizeList(_, _) => {}
@@ -260,7 +262,9 @@ fn build_extend_nulls(data_type: &DataType) -> ExtendNulls {
             _ => unreachable!(),
         },
         DataType::Struct(_) => structure::extend_nulls,
-        DataType::FixedSizeBinary(_) => fixed_binary::extend_nulls,
+        DataType::FixedSizeBinary(_) | DataType::Decimal(_, _) => {
+            fixed_binary::extend_nulls
+        }
         DataType::Float16 => unreachable!(),
         /*
         DataType::FixedSizeList(_, _) => {}
@@ -313,7 +317,8 @@ impl<'a> MutableArrayData<'a> {
             | DataType::LargeUtf8
             | DataType::LargeBinary
             | DataType::Interval(_)
-            | DataType::FixedSizeBinary(_) => vec![],
+            | DataType::FixedSizeBinary(_)
+            | DataType::Decimal(_, _) => vec![],
             DataType::List(_) | DataType::LargeList(_) => {
                 let childs = arrays
                     .iter()
@@ -33,7 +33,7 @@ use crate::buffer::MutableBuffer;
 use crate::compute::kernels::arity::unary;
 use crate::compute::util::combine_option_bitmap;
 use crate::datatypes;
-use crate::datatypes::ArrowNumericType;
+use crate::datatypes::{ArrowNumericType, DECIMAL_MAX_PRECISION};
 use crate::error::{ArrowError, Result};
 use crate::{array::*, util::bit_util};
 use num::traits::Pow;
@@ -748,10 +748,168 @@ where
     return math_divide_scalar(&array, divisor);
 }
 
+/// Helper function to perform a fallible math lambda function on the unscaled values
+/// of two decimal arrays, creating a `Decimal(precision, scale)` array. If either
+/// left or right value is null then the output value is also null.
+///
+/// # Errors
+///
+/// This function errors if:
+/// * the arrays have different lengths
+/// * `op` errors
+/// * a result does not fit `precision` digits
+fn decimal_math_op<F>(
+    left: &DecimalArray,
+    right: &DecimalArray,
+    precision: usize,
+    scale: usize,
+    op: F,
+) -> Result<DecimalArray>
+where
+    F: Fn(i128, i128) -> Result<i128>,
+{
+    if left.len() != right.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot perform math operation on arrays of different length".to_string(),
+        ));
+    }
+    let max = 10_i128.pow(precision as u32);
+
+    let values = (0..left.len())
+        .map(|i| {
+            if left.is_null(i) || right.is_null(i) {
+                return Ok(None);
+            }
+            match op(left.value(i), right.value(i))? {
+                value if value > -max && value < max => Ok(Some(value)),
+                _ => Err(decimal_overflow(precision, scale)),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(DecimalArray::from_sparse_iter(values, precision, scale))
+}
+
+fn decimal_overflow(precision: usize, scale: usize) -> ArrowError {
+    ArrowError::ComputeError(format!(
+        "Overflow happened on a result of type Decimal({}, {})",
+        precision, scale
+    ))
+}
+
+fn check_same_scale(left: &DecimalArray, right: &DecimalArray) -> Result<()> {
+    if left.scale() != right.scale() {
+        return Err(ArrowError::ComputeError(format!(
+            "Cannot perform math operation on decimals of scales {} and {}",
+            left.scale(),
+            right.scale()
+        )));
+    }
+    Ok(())
+}
+
+/// Perform `left + right` operation on two decimal arrays of the same scale `s`. The
+/// result is of type `Decimal(min(max(p1, p2) + 1, 38), s)`, where `p1` and `p2` are
+/// the precisions of `left` and `right`. If either left or right value is null then
+/// the result is also null.
+pub fn add_decimal(left: &DecimalArray, right: &DecimalArray) -> Result<DecimalArray> {
+    check_same_scale(left, right)?;
+    let precision =
+        (left.precision().max(right.precision()) + 1).min(DECIMAL_MAX_PRECISION);
+    let scale = left.scale();
+    decimal_math_op(left, right, precision, scale, |a, b| {
+        a.checked_add(b)
+            .ok_or_else(|| decimal_overflow(precision, scale))
+    })
+}
+
+/// Perform `left - right` operation on two decimal arrays of the same scale `s`. The
+/// result is of type `Decimal(min(max(p1, p2) + 1, 38), s)`, where `p1` and `p2` are
+/// the precisions of `left` and `right`. If either left or right value is null then
+/// the result is also null.
+pub fn subtract_decimal(
+    left: &DecimalArray,
+    right: &DecimalArray,
+) -> Result<DecimalArray> {
+    check_same_scale(left, right)?;
+    let precision =
+        (left.precision().max(right.precision()) + 1).min(DECIMAL_MAX_PRECISION);
+    let scale = left.scale();
+    decimal_math_op(left, right, precision, scale, |a, b| {
+        a.checked_sub(b)
+            .ok_or_else(|| decimal_overflow(precision, scale))
+    })
+}
+
+/// Perform `left * right` operation on two decimal arrays of types `Decimal(p1, s1)`
+/// and `Decimal(p2, s2)`. The result is of type `Decimal(min(p1 + p2 + 1, 38), s1 + s2)`.
+/// If either left or right value is null then the result is also null.
+pub fn multiply_decimal(
+    left: &DecimalArray,
+    right: &DecimalArray,
+) -> Result<DecimalArray> {
+    let precision = (left.precision() + right.precision() + 1).min(DECIMAL_MAX_PRECISION);
+    let scale = left.scale() + right.scale();
+    if scale > DECIMAL_MAX_PRECISION {
+        return Err(decimal_overflow(precision, scale));
+    }
+    decimal_math_op(left, right, precision, scale, |a, b| {
+        a.checked_mul(b)
+            .ok_or_else(|| decimal_overflow(precision, scale))
+    })
+}
+
+/// Perform `left / right` operation on two decimal arrays of types `Decimal(p1, s1)`
+/// and `Decimal(p2, s2)`. The result is of type `Decimal(p, s)` where
+/// `s = min(max(6, s1 + p2 + 1), 38)` and `p = min(p1 - s1 + s2 + s, 38)`, and the
+/// quotient is truncated to `s` digits. If either left or right value is null then the
+/// result is also null. If any right hand value is zero then the result of this
+/// operation will be `Err(ArrowError::DivideByZero)`.
+pub fn divide_decimal(left: &DecimalArray, right: &DecimalArray) -> Result<DecimalArray> {
+    let invalid_type = || {
+        ArrowError::ComputeError(format!(
+            "Cannot divide Decimal({}, {}) by Decimal({}, {})",
+            left.precision(),
+            left.scale(),
+            right.precision(),
+            right.scale()
+        ))
+    };
+    let scale = left
+        .scale()
+        .checked_add(right.precision())
+        .and_then(|scale| scale.checked_add(1))
+        .ok_or_else(invalid_type)?
+        .clamp(6, DECIMAL_MAX_PRECISION);
+    let precision = left
+        .precision()
+        .checked_sub(left.scale())
+        .and_then(|digits| digits.checked_add(right.scale()))
+        .and_then(|digits| digits.checked_add(scale))
+        .ok_or_else(invalid_type)?
+        .min(DECIMAL_MAX_PRECISION);
+    let exponent = scale
+        .checked_sub(left.scale())
+        .and_then(|exponent| exponent.checked_add(right.scale()))
+        .ok_or_else(invalid_type)?;
+    let factor = 10_i128
+        .checked_pow(exponent as u32)
+        .ok_or_else(|| decimal_overflow(precision, scale))?;
+    decimal_math_op(left, right, precision, scale, |a, b| {
+        if b == 0 {
+            return Err(ArrowError::DivideByZero);
+        }
+        a.checked_mul(factor)
+            .map(|a| a / b)
+            .ok_or_else(|| decimal_overflow(precision, scale))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::array::Int32Array;
+    use crate::datatypes::DataType;
 
     #[test]
     fn test_primitive_array_add() {
@@ -1006,4 +1164,81 @@ mod tests {
         let expected = Float64Array::from(vec![Some(1.0), None, Some(9.0)]);
         assert_eq!(expected, actual);
     }
+
+    fn decimal_strings(array: &DecimalArray) -> Vec<Option<String>> {
+        (0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    None
+                } else {
+                    Some(array.value_as_string(i))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decimal_array_add_subtract() {
+        let a = DecimalArray::from_sparse_iter(vec![Some(12345), None, Some(-99)], 5, 2);
+        let b = DecimalArray::from_sparse_iter(vec![Some(99999), Some(1), Some(1)], 5, 2);
+
+        let c = add_decimal(&a, &b).unwrap();
+        assert_eq!(&DataType::Decimal(6, 2), c.data_type());
+        assert_eq!(
+            vec![Some("1123.44".to_string()), None, Some("-0.98".to_string())],
+            decimal_strings(&c)
+        );
+
+        let c = subtract_decimal(&a, &b).unwrap();
+        assert_eq!(&DataType::Decimal(6, 2), c.data_type());
+        assert_eq!(
+            vec![Some("-876.54".to_string()), None, Some("-1.00".to_string())],
+            decimal_strings(&c)
+        );
+
+        let d = DecimalArray::from_sparse_iter(vec![Some(1), Some(1), Some(1)], 5, 3);
+        assert!(add_decimal(&a, &d).is_err());
+    }
+
+    #[test]
+    fn test_decimal_array_multiply() {
+        let a = DecimalArray::from_sparse_iter(vec![Some(150), None, Some(-25)], 5, 2);
+        let b = DecimalArray::from_sparse_iter(vec![Some(3), Some(1), Some(15)], 3, 1);
+
+        let c = multiply_decimal(&a, &b).unwrap();
+        assert_eq!(&DataType::Decimal(9, 3), c.data_type());
+        assert_eq!(
+            vec![Some("0.450".to_string()), None, Some("-0.375".to_string())],
+            decimal_strings(&c)
+        );
+
+        let big = DecimalArray::from_sparse_iter(vec![Some(10_i128.pow(20))], 38, 0);
+        assert!(multiply_decimal(&big, &big).is_err());
+    }
+
+    #[test]
+    fn test_decimal_array_divide() {
+        let a = DecimalArray::from_sparse_iter(vec![Some(1000), None, Some(-200)], 5, 2);
+        let b = DecimalArray::from_sparse_iter(vec![Some(3), Some(1), Some(8)], 2, 0);
+
+        let c = divide_decimal(&a, &b).unwrap();
+        assert_eq!(&DataType::Decimal(9, 6), c.data_type());
+        assert_eq!(
+            vec![
+                Some("3.333333".to_string()),
+                None,
+                Some("-0.250000".to_string())
+            ],
+            decimal_strings(&c)
+        );
+
+        let zero = DecimalArray::from_sparse_iter(vec![Some(0), None, Some(1)], 2, 0);
+        let c = divide_decimal(&a, &zero);
+        assert!(matches!(c, Err(ArrowError::DivideByZero)));
+
+        // the scale of the dividend is greater than its precision
+        let invalid = DecimalArray::from_sparse_iter(vec![Some(1)], 2, 5);
+        let c = divide_decimal(&invalid, &b);
+        assert!(matches!(c, Err(ArrowError::ComputeError(_))));
+    }
 }
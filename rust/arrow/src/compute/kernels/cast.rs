@@ -88,6 +88,16 @@ pub fn can_cast_types(from_type: &DataType, to_type: &DataType) -> bool {
         (Dictionary(_, value_type), _) => can_cast_types(value_type, to_type),
         (_, Dictionary(_, value_type)) => can_cast_types(from_type, value_type),
 
+        (Decimal(_, _), Decimal(_, _)) => true,
+        (Decimal(_, _), _) => {
+            DataType::is_numeric(to_type) || to_type == &Utf8 || to_type == &LargeUtf8
+        }
+        (_, Decimal(_, _)) => {
+            DataType::is_numeric(from_type)
+                || from_type == &Utf8
+                || from_type == &LargeUtf8
+        }
+
         (_, Boolean) => DataType::is_numeric(from_type),
         (Boolean, _) => DataType::is_numeric(to_type) || to_type == &Utf8,
 
@@ -256,6 +266,9 @@ pub fn can_cast_types(from_type: &DataType, to_type: &DataType) -> bool {
 /// * Time32 and Time64: precision lost when going to higher interval
 /// * Timestamp and Date{32|64}: precision lost when going to higher interval
 /// * Temporal to/from backing primitive: zero-copy with data type change
+/// * Decimal to Decimal: the value is rounded (half away from zero) to the new scale
+/// * Numeric and Utf8 to Decimal: values that do not fit the precision return null
+/// * Decimal to integer: the fractional part is truncated
 ///
 /// Unsupported Casts
 /// * To or from `StructArray`
@@ -281,6 +294,9 @@ pub fn cast(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
 /// * Time32 and Time64: precision lost when going to higher interval
 /// * Timestamp and Date{32|64}: precision lost when going to higher interval
 /// * Temporal to/from backing primitive: zero-copy with data type change
+/// * Decimal to Decimal: the value is rounded (half away from zero) to the new scale
+/// * Numeric and Utf8 to Decimal: values that do not fit the precision return null
+/// * Decimal to integer: the fractional part is truncated
 ///
 /// Unsupported Casts
 /// * To or from `StructArray`
@@ -389,6 +405,99 @@ pub fn cast_with_options(
                 from_type, to_type,
             ))),
         },
+        (Decimal(_, _), Decimal(precision, scale)) => {
+            cast_decimal_to_decimal(array, *precision, *scale, cast_options)
+        }
+        (Decimal(_, _), _) => match to_type {
+            UInt8 => cast_decimal_to_integer::<UInt8Type>(array, cast_options),
+            UInt16 => cast_decimal_to_integer::<UInt16Type>(array, cast_options),
+            UInt32 => cast_decimal_to_integer::<UInt32Type>(array, cast_options),
+            UInt64 => cast_decimal_to_integer::<UInt64Type>(array, cast_options),
+            Int8 => cast_decimal_to_integer::<Int8Type>(array, cast_options),
+            Int16 => cast_decimal_to_integer::<Int16Type>(array, cast_options),
+            Int32 => cast_decimal_to_integer::<Int32Type>(array, cast_options),
+            Int64 => cast_decimal_to_integer::<Int64Type>(array, cast_options),
+            Float32 => cast_decimal_to_float::<Float32Type>(array),
+            Float64 => cast_decimal_to_float::<Float64Type>(array),
+            Utf8 => cast_decimal_to_string::<i32>(array),
+            LargeUtf8 => cast_decimal_to_string::<i64>(array),
+            _ => Err(ArrowError::CastError(format!(
+                "Casting from {:?} to {:?} not supported",
+                from_type, to_type,
+            ))),
+        },
+        (_, Decimal(precision, scale)) => match from_type {
+            UInt8 => cast_integer_to_decimal::<UInt8Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            UInt16 => cast_integer_to_decimal::<UInt16Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            UInt32 => cast_integer_to_decimal::<UInt32Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            UInt64 => cast_integer_to_decimal::<UInt64Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            Int8 => cast_integer_to_decimal::<Int8Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            Int16 => cast_integer_to_decimal::<Int16Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            Int32 => cast_integer_to_decimal::<Int32Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            Int64 => cast_integer_to_decimal::<Int64Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            Float32 => cast_float_to_decimal::<Float32Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            Float64 => cast_float_to_decimal::<Float64Type>(
+                array,
+                *precision,
+                *scale,
+                cast_options,
+            ),
+            Utf8 => {
+                cast_string_to_decimal::<i32>(array, *precision, *scale, cast_options)
+            }
+            LargeUtf8 => {
+                cast_string_to_decimal::<i64>(array, *precision, *scale, cast_options)
+            }
+            _ => Err(ArrowError::CastError(format!(
+                "Casting from {:?} to {:?} not supported",
+                from_type, to_type,
+            ))),
+        },
         (_, Boolean) => match from_type {
             UInt8 => cast_numeric_to_bool::<UInt8Type>(array),
             UInt16 => cast_numeric_to_bool::<UInt16Type>(array),
@@ -1005,6 +1114,257 @@ where
     unsafe { PrimitiveArray::<R>::from_trusted_len_iter(iter) }
 }
 
+/// Returns `10^exponent`, the factor between unscaled decimal values whose
+/// scales are `exponent` apart, or `None` if it does not fit in an `i128`.
+fn pow_10(exponent: usize) -> Option<i128> {
+    10_i128.checked_pow(exponent as u32)
+}
+
+/// Divides `value` by `10^exponent`, rounding half away from zero.
+fn div_pow_10_round(value: i128, exponent: usize) -> i128 {
+    match pow_10(exponent) {
+        Some(divisor) => {
+            let quotient = value / divisor;
+            let remainder = value % divisor;
+            if remainder.unsigned_abs() * 2 >= divisor as u128 {
+                quotient + value.signum()
+            } else {
+                quotient
+            }
+        }
+        // the divisor is larger than any `i128`
+        None => 0,
+    }
+}
+
+/// Creates a [DecimalArray] of type `Decimal(precision, scale)` whose slot `i`
+/// is the unscaled value `op(i)`, or null if the slot of `array` is null.
+/// A value that is `None` or does not fit `precision` digits becomes null with
+/// safe cast options and an error otherwise.
+fn cast_to_decimal<F>(
+    array: &ArrayRef,
+    precision: usize,
+    scale: usize,
+    cast_options: &CastOptions,
+    op: F,
+) -> Result<ArrayRef>
+where
+    F: Fn(usize) -> Option<i128>,
+{
+    if precision == 0 || precision > DECIMAL_MAX_PRECISION || scale > precision {
+        return Err(ArrowError::CastError(format!(
+            "Cannot cast to invalid type Decimal({}, {})",
+            precision, scale
+        )));
+    }
+    let max = pow_10(precision).unwrap();
+
+    let values = (0..array.len())
+        .map(|i| {
+            if array.is_null(i) {
+                return Ok(None);
+            }
+            match op(i) {
+                Some(value) if value > -max && value < max => Ok(Some(value)),
+                _ if cast_options.safe => Ok(None),
+                _ => Err(ArrowError::CastError(format!(
+                    "Cannot cast value at index {} of {:?} to Decimal({}, {})",
+                    i,
+                    array.data_type(),
+                    precision,
+                    scale
+                ))),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Arc::new(DecimalArray::from_sparse_iter(
+        values, precision, scale,
+    )))
+}
+
+/// Cast a decimal array to another precision and scale
+fn cast_decimal_to_decimal(
+    array: &ArrayRef,
+    precision: usize,
+    scale: usize,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef> {
+    let from = array.as_any().downcast_ref::<DecimalArray>().unwrap();
+    let from_scale = from.scale();
+    cast_to_decimal(array, precision, scale, cast_options, |i| {
+        let value = from.value(i);
+        if scale >= from_scale {
+            value.checked_mul(pow_10(scale - from_scale)?)
+        } else {
+            Some(div_pow_10_round(value, from_scale - scale))
+        }
+    })
+}
+
+/// Cast an integer array to a decimal array
+fn cast_integer_to_decimal<T>(
+    array: &ArrayRef,
+    precision: usize,
+    scale: usize,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef>
+where
+    T: ArrowNumericType,
+    T::Native: ToPrimitive,
+{
+    let from = array.as_any().downcast_ref::<PrimitiveArray<T>>().unwrap();
+    cast_to_decimal(array, precision, scale, cast_options, |i| {
+        from.value(i).to_i128()?.checked_mul(pow_10(scale)?)
+    })
+}
+
+/// Cast a floating point array to a decimal array, rounding half away from zero
+fn cast_float_to_decimal<T>(
+    array: &ArrayRef,
+    precision: usize,
+    scale: usize,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef>
+where
+    T: ArrowNumericType,
+    T::Native: ToPrimitive,
+{
+    let from = array.as_any().downcast_ref::<PrimitiveArray<T>>().unwrap();
+    let factor = 10_f64.powi(scale as i32);
+    cast_to_decimal(array, precision, scale, cast_options, |i| {
+        (from.value(i).to_f64()? * factor).round().to_i128()
+    })
+}
+
+/// Cast a string array to a decimal array
+fn cast_string_to_decimal<Offset: StringOffsetSizeTrait>(
+    array: &ArrayRef,
+    precision: usize,
+    scale: usize,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef> {
+    let from = array
+        .as_any()
+        .downcast_ref::<GenericStringArray<Offset>>()
+        .unwrap();
+    cast_to_decimal(array, precision, scale, cast_options, |i| {
+        parse_decimal(from.value(i), scale)
+    })
+}
+
+/// Parses a string such as `-12.345` into its unscaled decimal value at
+/// `scale`, rounding half away from zero any digits beyond `scale`.
+fn parse_decimal(value: &str, scale: usize) -> Option<i128> {
+    let value = value.trim();
+    let (negative, digits) = match value.as_bytes().first()? {
+        b'-' => (true, &value[1..]),
+        b'+' => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let (integral, fractional) = match digits.find('.') {
+        Some(point) => (&digits[..point], &digits[point + 1..]),
+        None => (digits, ""),
+    };
+    if (integral.is_empty() && fractional.is_empty())
+        || !integral
+            .bytes()
+            .chain(fractional.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let fractional_digits = fractional
+        .bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(scale);
+    let mut unscaled: i128 = 0;
+    for digit in integral.bytes().chain(fractional_digits) {
+        unscaled = unscaled
+            .checked_mul(10)?
+            .checked_add((digit - b'0') as i128)?;
+    }
+    if fractional.len() > scale && fractional.as_bytes()[scale] >= b'5' {
+        unscaled = unscaled.checked_add(1)?;
+    }
+    Some(if negative { -unscaled } else { unscaled })
+}
+
+/// Cast a decimal array to an integer array, truncating the fractional part.
+/// Values that do not fit the integer type become null with safe cast options
+/// and an error otherwise.
+fn cast_decimal_to_integer<T>(
+    array: &ArrayRef,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef>
+where
+    T: ArrowNumericType,
+    T::Native: NumCast,
+{
+    let from = array.as_any().downcast_ref::<DecimalArray>().unwrap();
+    let divisor = pow_10(from.scale()).unwrap();
+
+    let values = (0..from.len())
+        .map(|i| {
+            if from.is_null(i) {
+                return Ok(None);
+            }
+            match num::cast::cast::<i128, T::Native>(from.value(i) / divisor) {
+                Some(value) => Ok(Some(value)),
+                None if cast_options.safe => Ok(None),
+                None => Err(ArrowError::CastError(format!(
+                    "Cannot cast value {} to {:?}",
+                    from.value_as_string(i),
+                    T::DATA_TYPE
+                ))),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Arc::new(values.into_iter().collect::<PrimitiveArray<T>>()))
+}
+
+/// Cast a decimal array to a floating point array
+#[allow(clippy::unnecessary_wraps)]
+fn cast_decimal_to_float<T>(array: &ArrayRef) -> Result<ArrayRef>
+where
+    T: ArrowNumericType,
+    T::Native: NumCast,
+{
+    let from = array.as_any().downcast_ref::<DecimalArray>().unwrap();
+    let divisor = 10_f64.powi(from.scale() as i32);
+
+    let values = (0..from.len()).map(|i| {
+        if from.is_null(i) {
+            None
+        } else {
+            num::cast::cast::<f64, T::Native>(from.value(i) as f64 / divisor)
+        }
+    });
+
+    Ok(Arc::new(values.collect::<PrimitiveArray<T>>()))
+}
+
+/// Cast a decimal array to a string array
+#[allow(clippy::unnecessary_wraps)]
+fn cast_decimal_to_string<OffsetSize>(array: &ArrayRef) -> Result<ArrayRef>
+where
+    OffsetSize: StringOffsetSizeTrait,
+{
+    let from = array.as_any().downcast_ref::<DecimalArray>().unwrap();
+
+    let values = (0..from.len()).map(|i| {
+        if from.is_null(i) {
+            None
+        } else {
+            Some(from.value_as_string(i))
+        }
+    });
+
+    Ok(Arc::new(values.collect::<GenericStringArray<OffsetSize>>()))
+}
+
 /// Cast numeric types to Utf8
 #[allow(clippy::unnecessary_wraps)]
 fn cast_numeric_to_string<FROM, OffsetSize>(array: &ArrayRef) -> Result<ArrayRef>
@@ -2246,6 +2606,123 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cast_decimal_to_decimal() {
+        let array = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(12345), None, Some(-12355), Some(99999)],
+            5,
+            2,
+        )) as ArrayRef;
+
+        // widen the scale
+        let b = cast(&array, &DataType::Decimal(7, 4)).unwrap();
+        assert_eq!(
+            array_to_strings(&b),
+            vec!["123.4500", "null", "-123.5500", "999.9900"]
+        );
+
+        // narrow the scale, rounding half away from zero
+        let b = cast(&array, &DataType::Decimal(4, 1)).unwrap();
+        assert_eq!(
+            array_to_strings(&b),
+            vec!["123.5", "null", "-123.6", "null"]
+        );
+
+        // the value does not fit the precision
+        let options = CastOptions { safe: false };
+        let b = cast_with_options(&array, &DataType::Decimal(4, 1), &options);
+        assert!(b.is_err());
+
+        let b = cast(&array, &DataType::Decimal(39, 1));
+        assert!(b.is_err());
+    }
+
+    #[test]
+    fn test_cast_decimal_to_numeric() {
+        let array = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(12345), None, Some(-12399), Some(1_000_000)],
+            9,
+            2,
+        )) as ArrayRef;
+
+        assert_eq!(
+            get_cast_values::<Int64Type>(&array, &DataType::Int64),
+            vec!["123", "null", "-123", "10000"]
+        );
+        assert_eq!(
+            get_cast_values::<Int8Type>(&array, &DataType::Int8),
+            vec!["123", "null", "-123", "null"]
+        );
+        assert_eq!(
+            get_cast_values::<UInt32Type>(&array, &DataType::UInt32),
+            vec!["123", "null", "null", "10000"]
+        );
+        assert_eq!(
+            get_cast_values::<Float64Type>(&array, &DataType::Float64),
+            vec!["123.45", "null", "-123.99", "10000.0"]
+        );
+
+        let b = cast(&array, &DataType::Utf8).unwrap();
+        assert_eq!(
+            array_to_strings(&b),
+            vec!["123.45", "null", "-123.99", "10000.00"]
+        );
+    }
+
+    #[test]
+    fn test_cast_numeric_to_decimal() {
+        let array =
+            Arc::new(Int32Array::from(vec![Some(5), None, Some(-1000)])) as ArrayRef;
+        let b = cast(&array, &DataType::Decimal(5, 2)).unwrap();
+        assert_eq!(b.data_type(), &DataType::Decimal(5, 2));
+        assert_eq!(array_to_strings(&b), vec!["5.00", "null", "null"]);
+
+        let array = Arc::new(Float64Array::from(vec![
+            Some(1.005),
+            Some(-2.5),
+            None,
+            Some(f64::NAN),
+            Some(1e10),
+        ])) as ArrayRef;
+        let b = cast(&array, &DataType::Decimal(10, 1)).unwrap();
+        assert_eq!(
+            array_to_strings(&b),
+            vec!["1.0", "-2.5", "null", "null", "null"]
+        );
+
+        let options = CastOptions { safe: false };
+        let b = cast_with_options(&array, &DataType::Decimal(10, 1), &options);
+        assert!(b.is_err());
+    }
+
+    #[test]
+    fn test_cast_string_to_decimal() {
+        let array = Arc::new(StringArray::from(vec![
+            Some("123.456"),
+            Some("-0.5"),
+            Some("+7"),
+            Some(".25"),
+            Some(" 12 "),
+            Some("1.2.3"),
+            Some("abc"),
+            Some("-"),
+            None,
+            Some("123456"),
+        ])) as ArrayRef;
+        let b = cast(&array, &DataType::Decimal(6, 2)).unwrap();
+        assert_eq!(
+            array_to_strings(&b),
+            vec![
+                "123.46", "-0.50", "7.00", "0.25", "12.00", "null", "null", "null",
+                "null", "null"
+            ]
+        );
+
+        let array = Arc::new(LargeStringArray::from(vec!["-1.5"])) as ArrayRef;
+        let b = cast(&array, &DataType::Decimal(2, 0)).unwrap();
+        assert_eq!(array_to_strings(&b), vec!["-2"]);
+    }
+
     #[test]
     fn test_cast_from_f64() {
         let f64_values: Vec<f64> = vec![
@@ -3640,6 +4117,11 @@ mod tests {
             Arc::new(UInt64Array::from(vec![1, 2])),
             Arc::new(Float32Array::from(vec![1.0, 2.0])),
             Arc::new(Float64Array::from(vec![1.0, 2.0])),
+            Arc::new(DecimalArray::from_sparse_iter(
+                vec![Some(100), Some(-250)],
+                10,
+                2,
+            )),
             Arc::new(TimestampSecondArray::from_vec(vec![1000, 2000], None)),
             Arc::new(TimestampMillisecondArray::from_vec(vec![1000, 2000], None)),
             Arc::new(TimestampMicrosecondArray::from_vec(vec![1000, 2000], None)),
@@ -3796,6 +4278,8 @@ mod tests {
             Float16,
             Float32,
             Float64,
+            Decimal(10, 2),
+            Decimal(5, 0),
             Timestamp(TimeUnit::Second, None),
             Timestamp(TimeUnit::Millisecond, None),
             Timestamp(TimeUnit::Microsecond, None),
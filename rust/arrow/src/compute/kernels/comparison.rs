@@ -543,6 +543,89 @@ pub fn gt_eq_utf8_scalar<OffsetSize: StringOffsetSizeTrait>(
 
 /// Helper function to perform boolean lambda function on values from two arrays using
 /// SIMD.
+fn check_decimal_scales(left: &DecimalArray, right: &DecimalArray) -> Result<()> {
+    if left.scale() != right.scale() {
+        return Err(ArrowError::ComputeError(format!(
+            "Cannot perform comparison operation on decimals of scales {} and {}",
+            left.scale(),
+            right.scale()
+        )));
+    }
+    Ok(())
+}
+
+/// Perform `left == right` operation on two [`DecimalArray`]s of the same scale.
+pub fn eq_decimal(left: &DecimalArray, right: &DecimalArray) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a == b)
+}
+
+/// Perform `left == right` operation on a [`DecimalArray`] and the unscaled value of a
+/// scalar of the same scale.
+pub fn eq_decimal_scalar(left: &DecimalArray, right: i128) -> Result<BooleanArray> {
+    compare_op_scalar!(left, right, |a, b| a == b)
+}
+
+/// Perform `left != right` operation on two [`DecimalArray`]s of the same scale.
+pub fn neq_decimal(left: &DecimalArray, right: &DecimalArray) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a != b)
+}
+
+/// Perform `left != right` operation on a [`DecimalArray`] and the unscaled value of a
+/// scalar of the same scale.
+pub fn neq_decimal_scalar(left: &DecimalArray, right: i128) -> Result<BooleanArray> {
+    compare_op_scalar!(left, right, |a, b| a != b)
+}
+
+/// Perform `left < right` operation on two [`DecimalArray`]s of the same scale.
+pub fn lt_decimal(left: &DecimalArray, right: &DecimalArray) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a < b)
+}
+
+/// Perform `left < right` operation on a [`DecimalArray`] and the unscaled value of a
+/// scalar of the same scale.
+pub fn lt_decimal_scalar(left: &DecimalArray, right: i128) -> Result<BooleanArray> {
+    compare_op_scalar!(left, right, |a, b| a < b)
+}
+
+/// Perform `left <= right` operation on two [`DecimalArray`]s of the same scale.
+pub fn lt_eq_decimal(left: &DecimalArray, right: &DecimalArray) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a <= b)
+}
+
+/// Perform `left <= right` operation on a [`DecimalArray`] and the unscaled value of a
+/// scalar of the same scale.
+pub fn lt_eq_decimal_scalar(left: &DecimalArray, right: i128) -> Result<BooleanArray> {
+    compare_op_scalar!(left, right, |a, b| a <= b)
+}
+
+/// Perform `left > right` operation on two [`DecimalArray`]s of the same scale.
+pub fn gt_decimal(left: &DecimalArray, right: &DecimalArray) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a > b)
+}
+
+/// Perform `left > right` operation on a [`DecimalArray`] and the unscaled value of a
+/// scalar of the same scale.
+pub fn gt_decimal_scalar(left: &DecimalArray, right: i128) -> Result<BooleanArray> {
+    compare_op_scalar!(left, right, |a, b| a > b)
+}
+
+/// Perform `left >= right` operation on two [`DecimalArray`]s of the same scale.
+pub fn gt_eq_decimal(left: &DecimalArray, right: &DecimalArray) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a >= b)
+}
+
+/// Perform `left >= right` operation on a [`DecimalArray`] and the unscaled value of a
+/// scalar of the same scale.
+pub fn gt_eq_decimal_scalar(left: &DecimalArray, right: i128) -> Result<BooleanArray> {
+    compare_op_scalar!(left, right, |a, b| a >= b)
+}
+
 #[cfg(simd)]
 fn simd_compare_op<T, SIMD_OP, SCALAR_OP>(
     left: &PrimitiveArray<T>,
@@ -1036,6 +1119,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decimal_array_comparison() {
+        let a = DecimalArray::from_sparse_iter(vec![Some(-150), Some(100), None], 5, 2);
+        let b =
+            DecimalArray::from_sparse_iter(vec![Some(-100), Some(100), Some(1)], 7, 2);
+
+        assert_eq!(
+            BooleanArray::from(vec![Some(false), Some(true), None]),
+            eq_decimal(&a, &b).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(true), Some(false), None]),
+            neq_decimal(&a, &b).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(true), Some(false), None]),
+            lt_decimal(&a, &b).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(true), Some(true), None]),
+            lt_eq_decimal(&a, &b).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(false), Some(false), None]),
+            gt_decimal(&a, &b).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(false), Some(true), None]),
+            gt_eq_decimal(&a, &b).unwrap()
+        );
+
+        let c = DecimalArray::from_sparse_iter(vec![Some(1), Some(1), Some(1)], 5, 3);
+        assert!(eq_decimal(&a, &c).is_err());
+    }
+
+    #[test]
+    fn test_decimal_array_comparison_scalar() {
+        let a = DecimalArray::from_sparse_iter(vec![Some(-150), Some(100), None], 5, 2);
+
+        assert_eq!(
+            BooleanArray::from(vec![Some(false), Some(true), None]),
+            eq_decimal_scalar(&a, 100).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(true), Some(false), None]),
+            neq_decimal_scalar(&a, 100).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(true), Some(false), None]),
+            lt_decimal_scalar(&a, 100).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(true), Some(true), None]),
+            lt_eq_decimal_scalar(&a, 100).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(false), Some(false), None]),
+            gt_decimal_scalar(&a, 100).unwrap()
+        );
+        assert_eq!(
+            BooleanArray::from(vec![Some(false), Some(true), None]),
+            gt_eq_decimal_scalar(&a, 100).unwrap()
+        );
+    }
+
     #[test]
     fn test_primitive_array_eq_with_slice() {
         let a = Int32Array::from(vec![6, 7, 8, 8, 10]);
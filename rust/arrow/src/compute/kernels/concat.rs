@@ -184,6 +184,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_concat_decimal_array_slices() -> Result<()> {
+        let input_1 =
+            DecimalArray::from_sparse_iter(vec![Some(-1), Some(2), None], 10, 2)
+                .slice(1, 2);
+        let input_2 = DecimalArray::from_sparse_iter(vec![Some(103), None], 10, 2);
+        let arr = concat(&[input_1.as_ref(), &input_2])?;
+
+        let expected_output = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(2), None, Some(103), None],
+            10,
+            2,
+        )) as ArrayRef;
+
+        assert_eq!(&arr, &expected_output);
+
+        Ok(())
+    }
+
     #[test]
     fn test_concat_boolean_primitive_arrays() -> Result<()> {
         let arr = concat(&[
@@ -258,6 +258,7 @@ pub fn sort_to_indices(
             )
         }
         DataType::Utf8 => sort_string(values, v, n, &options, limit),
+        DataType::Decimal(_, _) => sort_decimal(values, v, n, &options, limit),
         DataType::List(field) => match field.data_type() {
             DataType::Int8 => sort_list::<i32, Int8Type>(values, v, n, &options, limit),
             DataType::Int16 => sort_list::<i32, Int16Type>(values, v, n, &options, limit),
@@ -564,6 +565,50 @@ fn sort_string(
     )
 }
 
+/// Sort decimals
+fn sort_decimal(
+    values: &ArrayRef,
+    value_indices: Vec<u32>,
+    null_indices: Vec<u32>,
+    options: &SortOptions,
+    limit: Option<usize>,
+) -> Result<UInt32Array> {
+    let values = values.as_any().downcast_ref::<DecimalArray>().unwrap();
+
+    let mut valids = value_indices
+        .into_iter()
+        .map(|index| (index, values.value(index as usize)))
+        .collect::<Vec<(u32, i128)>>();
+    let mut nulls = null_indices;
+    let descending = options.descending;
+    let mut len = values.len();
+    let nulls_len = nulls.len();
+
+    if let Some(limit) = limit {
+        len = limit.min(len);
+    }
+    if !descending {
+        sort_by(&mut valids, len - nulls_len, |a, b| cmp(a.1, b.1));
+    } else {
+        sort_by(&mut valids, len - nulls_len, |a, b| cmp(a.1, b.1).reverse());
+        // reverse to keep a stable ordering
+        nulls.reverse();
+    }
+    // collect the order of valid tuplies
+    let mut valid_indices: Vec<u32> = valids.iter().map(|tuple| tuple.0).collect();
+
+    if options.nulls_first {
+        nulls.append(&mut valid_indices);
+        nulls.truncate(len);
+        return Ok(UInt32Array::from(nulls));
+    }
+
+    // no need to sort nulls as they are in the correct order already
+    valid_indices.append(&mut nulls);
+    valid_indices.truncate(len);
+    Ok(UInt32Array::from(valid_indices))
+}
+
 /// Sort dictionary encoded strings
 fn sort_string_dictionary<T: ArrowDictionaryKeyType>(
     values: &ArrayRef,
@@ -1628,6 +1673,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sort_to_indices_decimals() {
+        let values: ArrayRef = Arc::new(DecimalArray::from_sparse_iter(
+            vec![None, Some(250), Some(-100), None, Some(1), Some(250)],
+            5,
+            2,
+        ));
+
+        let output = sort_to_indices(&values, None, None).unwrap();
+        assert_eq!(output, UInt32Array::from(vec![0, 3, 2, 4, 1, 5]));
+
+        let options = Some(SortOptions {
+            descending: true,
+            nulls_first: false,
+        });
+        let output = sort_to_indices(&values, options, None).unwrap();
+        assert_eq!(output, UInt32Array::from(vec![1, 5, 4, 2, 3, 0]));
+
+        let output = sort_to_indices(&values, options, Some(3)).unwrap();
+        assert_eq!(output, UInt32Array::from(vec![1, 5, 4]));
+    }
+
     #[test]
     fn test_sort_strings() {
         test_sort_string_arrays(
@@ -170,6 +170,10 @@ where
         DataType::Duration(TimeUnit::Nanosecond) => {
             downcast_take!(DurationNanosecondType, values, indices)
         }
+        DataType::Decimal(_, _) => {
+            let values = values.as_any().downcast_ref::<DecimalArray>().unwrap();
+            Ok(Arc::new(take_decimal(values, indices)?))
+        }
         DataType::Utf8 => {
             let values = values
                 .as_any()
@@ -184,6 +188,20 @@ where
                 .unwrap();
             Ok(Arc::new(take_string::<i64, _>(values, indices)?))
         }
+        DataType::Binary => {
+            let values = values
+                .as_any()
+                .downcast_ref::<GenericBinaryArray<i32>>()
+                .unwrap();
+            Ok(Arc::new(take_binary::<i32, _>(values, indices)?))
+        }
+        DataType::LargeBinary => {
+            let values = values
+                .as_any()
+                .downcast_ref::<GenericBinaryArray<i64>>()
+                .unwrap();
+            Ok(Arc::new(take_binary::<i64, _>(values, indices)?))
+        }
         DataType::List(_) => {
             let values = values
                 .as_any()
@@ -449,6 +467,63 @@ where
     Ok(PrimitiveArray::<T>::from(data))
 }
 
+/// `take` implementation for decimal arrays
+fn take_decimal<IndexType>(
+    values: &DecimalArray,
+    indices: &PrimitiveArray<IndexType>,
+) -> Result<DecimalArray>
+where
+    IndexType: ArrowNumericType,
+    IndexType::Native: ToPrimitive,
+{
+    let taken = (0..indices.len())
+        .map(|i| {
+            if indices.is_null(i) {
+                return Ok(None);
+            }
+            let index = maybe_usize::<IndexType>(indices.value(i))?;
+            Ok(if values.is_null(index) {
+                None
+            } else {
+                Some(values.value(index))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(DecimalArray::from_sparse_iter(
+        taken,
+        values.precision(),
+        values.scale(),
+    ))
+}
+
+/// `take` implementation for binary arrays
+fn take_binary<OffsetSize, IndexType>(
+    values: &GenericBinaryArray<OffsetSize>,
+    indices: &PrimitiveArray<IndexType>,
+) -> Result<GenericBinaryArray<OffsetSize>>
+where
+    OffsetSize: BinaryOffsetSizeTrait,
+    IndexType: ArrowNumericType,
+    IndexType::Native: ToPrimitive,
+{
+    let taken = (0..indices.len())
+        .map(|i| {
+            if indices.is_null(i) {
+                return Ok(None);
+            }
+            let index = maybe_usize::<IndexType>(indices.value(i))?;
+            Ok(if values.is_null(index) {
+                None
+            } else {
+                Some(values.value(index))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(taken.into_iter().collect())
+}
+
 /// `take` implementation for boolean arrays
 fn take_boolean<IndexType>(
     values: &BooleanArray,
@@ -863,6 +938,23 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_take_decimal() {
+        let index = UInt32Array::from(vec![Some(3), None, Some(1), Some(3), Some(2)]);
+        let values = DecimalArray::from_sparse_iter(
+            vec![Some(0), None, Some(-250), Some(12345), None],
+            10,
+            3,
+        );
+        let output = take(&values, &index, None).unwrap();
+        let expected = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(12345), None, None, Some(12345), Some(-250)],
+            10,
+            3,
+        )) as ArrayRef;
+        assert_eq!(&output, &expected);
+    }
+
     #[test]
     fn test_take_primitive() {
         let index = UInt32Array::from(vec![Some(3), None, Some(1), Some(3), Some(2)]);
@@ -1132,6 +1224,45 @@ mod tests {
         _test_take_string::<LargeStringArray>()
     }
 
+    fn _test_take_binary<'a, K: 'static>()
+    where
+        K: Array + PartialEq + From<Vec<Option<&'a [u8]>>>,
+    {
+        let index = UInt32Array::from(vec![Some(3), None, Some(1), Some(3), Some(4)]);
+
+        let array = K::from(vec![
+            Some(&b"one"[..]),
+            None,
+            Some(&b"three"[..]),
+            Some(&b"four"[..]),
+            Some(&b""[..]),
+        ]);
+        let actual = take(&array, &index, None).unwrap();
+        assert_eq!(actual.len(), index.len());
+
+        let actual = actual.as_any().downcast_ref::<K>().unwrap();
+
+        let expected = K::from(vec![
+            Some(&b"four"[..]),
+            None,
+            None,
+            Some(&b"four"[..]),
+            Some(&b""[..]),
+        ]);
+
+        assert_eq!(actual, &expected);
+    }
+
+    #[test]
+    fn test_take_binary() {
+        _test_take_binary::<BinaryArray>()
+    }
+
+    #[test]
+    fn test_take_large_binary() {
+        _test_take_binary::<LargeBinaryArray>()
+    }
+
     macro_rules! test_take_list {
         ($offset_type:ty, $list_data_type:ident, $list_array_type:ident) => {{
             // Construct a value array, [[0,0,0], [-1,-2,-1], [2,3]]
@@ -131,6 +131,10 @@ pub enum DataType {
     Decimal(usize, usize),
 }
 
+/// The maximum precision of a `Decimal` value, whose unscaled value is stored in
+/// a 128-bit integer.
+pub const DECIMAL_MAX_PRECISION: usize = 38;
+
 /// An absolute length of time in seconds, milliseconds, microseconds or nanoseconds.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum TimeUnit {
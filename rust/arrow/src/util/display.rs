@@ -206,6 +206,17 @@ pub fn array_value_to_string(column: &array::ArrayRef, row: usize) -> Result<Str
         DataType::Binary => make_string_hex!(array::BinaryArray, column, row),
         DataType::LargeBinary => make_string_hex!(array::LargeBinaryArray, column, row),
         DataType::Boolean => make_string!(array::BooleanArray, column, row),
+        DataType::Decimal(_, _) => {
+            let array = column
+                .as_any()
+                .downcast_ref::<array::DecimalArray>()
+                .unwrap();
+            if array.is_null(row) {
+                Ok("".to_string())
+            } else {
+                Ok(array.value_as_string(row))
+            }
+        }
         DataType::Int8 => make_string!(array::Int8Array, column, row),
         DataType::Int16 => make_string!(array::Int16Array, column, row),
         DataType::Int32 => make_string!(array::Int32Array, column, row),
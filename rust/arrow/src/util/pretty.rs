@@ -105,10 +105,10 @@ fn create_column(field: &str, columns: &[ArrayRef]) -> Result<Table> {
 mod tests {
     use crate::{
         array::{
-            self, new_null_array, Array, Date32Array, Date64Array, PrimitiveBuilder,
-            StringBuilder, StringDictionaryBuilder, Time32MillisecondArray,
-            Time32SecondArray, Time64MicrosecondArray, Time64NanosecondArray,
-            TimestampMicrosecondArray, TimestampMillisecondArray,
+            self, new_null_array, Array, Date32Array, Date64Array, DecimalArray,
+            PrimitiveBuilder, StringBuilder, StringDictionaryBuilder,
+            Time32MillisecondArray, Time32SecondArray, Time64MicrosecondArray,
+            Time64NanosecondArray, TimestampMicrosecondArray, TimestampMillisecondArray,
             TimestampNanosecondArray, TimestampSecondArray,
         },
         datatypes::{DataType, Field, Int32Type, Schema},
@@ -418,4 +418,38 @@ mod tests {
         ];
         check_datetime!(Time64NanosecondArray, 11111111, expected);
     }
+
+    #[test]
+    fn test_pretty_format_decimal() -> Result<()> {
+        let array = DecimalArray::from_sparse_iter(
+            vec![Some(101), None, Some(-5), Some(20000)],
+            7,
+            2,
+        );
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "f",
+            array.data_type().clone(),
+            true,
+        )]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(array)])?;
+
+        let table = pretty_format_batches(&[batch])?;
+
+        let expected = vec![
+            "+--------+",
+            "| f      |",
+            "+--------+",
+            "| 1.01   |",
+            "|        |",
+            "| -0.05  |",
+            "| 200.00 |",
+            "+--------+",
+        ];
+
+        let actual: Vec<&str> = table.lines().collect();
+
+        assert_eq!(expected, actual, "Actual result:\n{}", table);
+
+        Ok(())
+    }
 }
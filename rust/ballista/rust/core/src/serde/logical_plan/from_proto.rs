@@ -234,13 +234,17 @@ impl TryInto<LogicalPlan> for &protobuf::LogicalPlanNode {
                     protobuf::JoinType::Inner => JoinType::Inner,
                     protobuf::JoinType::Left => JoinType::Left,
                     protobuf::JoinType::Right => JoinType::Right,
+                    protobuf::JoinType::Full => JoinType::Full,
+                    protobuf::JoinType::Semi => JoinType::Semi,
+                    protobuf::JoinType::Anti => JoinType::Anti,
                 };
                 LogicalPlanBuilder::from(&convert_box_required!(join.left)?)
-                    .join(
+                    .join_detailed(
                         &convert_box_required!(join.right)?,
                         join_type,
                         &left_keys,
                         &right_keys,
+                        join.null_equals_null,
                     )?
                     .build()
                     .map_err(|e| e.into())
@@ -813,11 +817,33 @@ impl TryInto<Expr> for &protobuf::LogicalExprNode {
                     protobuf::AggregateFunction::Sum => AggregateFunction::Sum,
                     protobuf::AggregateFunction::Avg => AggregateFunction::Avg,
                     protobuf::AggregateFunction::Count => AggregateFunction::Count,
+                    protobuf::AggregateFunction::Stddev => AggregateFunction::Stddev,
+                    protobuf::AggregateFunction::StddevPop => AggregateFunction::StddevPop,
+                    protobuf::AggregateFunction::Variance => AggregateFunction::Variance,
+                    protobuf::AggregateFunction::VariancePop => AggregateFunction::VariancePop,
+                    protobuf::AggregateFunction::Covariance => AggregateFunction::Covariance,
+                    protobuf::AggregateFunction::CovariancePop => {
+                        AggregateFunction::CovariancePop
+                    }
+                    protobuf::AggregateFunction::Correlation => AggregateFunction::Correlation,
+                    protobuf::AggregateFunction::ApproxDistinct => {
+                        AggregateFunction::ApproxDistinct
+                    }
+                    protobuf::AggregateFunction::ApproxPercentileCont => {
+                        AggregateFunction::ApproxPercentileCont
+                    }
+                    protobuf::AggregateFunction::ApproxMedian => AggregateFunction::ApproxMedian,
+                    protobuf::AggregateFunction::ArrayAgg => AggregateFunction::ArrayAgg,
+                    protobuf::AggregateFunction::StringAgg => AggregateFunction::StringAgg,
                 };
 
                 Ok(Expr::AggregateFunction {
                     fun,
-                    args: vec![parse_required_expr(&expr.expr)?],
+                    args: expr
+                        .expr
+                        .iter()
+                        .map(|e| e.try_into())
+                        .collect::<Result<Vec<_>, _>>()?,
                     distinct: false, //TODO
                 })
             }
@@ -782,6 +782,7 @@ impl TryInto<protobuf::LogicalPlanNode> for &LogicalPlan {
                 right,
                 on,
                 join_type,
+                null_equals_null,
                 ..
             } => {
                 let left: protobuf::LogicalPlanNode = left.as_ref().try_into()?;
@@ -790,6 +791,9 @@ impl TryInto<protobuf::LogicalPlanNode> for &LogicalPlan {
                     JoinType::Inner => protobuf::JoinType::Inner,
                     JoinType::Left => protobuf::JoinType::Left,
                     JoinType::Right => protobuf::JoinType::Right,
+                    JoinType::Full => protobuf::JoinType::Full,
+                    JoinType::Semi => protobuf::JoinType::Semi,
+                    JoinType::Anti => protobuf::JoinType::Anti,
                 };
                 let left_join_column = on.iter().map(|on| on.0.to_owned()).collect();
                 let right_join_column = on.iter().map(|on| on.1.to_owned()).collect();
@@ -800,6 +804,7 @@ impl TryInto<protobuf::LogicalPlanNode> for &LogicalPlan {
                         join_type: join_type.into(),
                         left_join_column,
                         right_join_column,
+                        null_equals_null: *null_equals_null,
                     }))),
                 })
             }
@@ -917,6 +922,15 @@ impl TryInto<protobuf::LogicalPlanNode> for &LogicalPlan {
                     ))),
                 })
             }
+            LogicalPlan::Window { .. } => Err(BallistaError::NotImplemented(
+                "Window is not supported by ballista".to_owned(),
+            )),
+            LogicalPlan::Analyze { .. } => Err(BallistaError::NotImplemented(
+                "Analyze is not supported by ballista".to_owned(),
+            )),
+            LogicalPlan::CrossJoin { .. } => Err(BallistaError::NotImplemented(
+                "CrossJoin is not supported by ballista".to_owned(),
+            )),
             LogicalPlan::Extension { .. } => unimplemented!(),
             // _ => Err(BallistaError::General(format!(
             //     "logical plan to_proto {:?}",
@@ -986,12 +1000,44 @@ impl TryInto<protobuf::LogicalExprNode> for &Expr {
                     AggregateFunction::Sum => protobuf::AggregateFunction::Sum,
                     AggregateFunction::Avg => protobuf::AggregateFunction::Avg,
                     AggregateFunction::Count => protobuf::AggregateFunction::Count,
+                    AggregateFunction::Stddev => protobuf::AggregateFunction::Stddev,
+                    AggregateFunction::StddevPop => protobuf::AggregateFunction::StddevPop,
+                    AggregateFunction::Variance => protobuf::AggregateFunction::Variance,
+                    AggregateFunction::VariancePop => {
+                        protobuf::AggregateFunction::VariancePop
+                    }
+                    AggregateFunction::Covariance => protobuf::AggregateFunction::Covariance,
+                    AggregateFunction::CovariancePop => {
+                        protobuf::AggregateFunction::CovariancePop
+                    }
+                    AggregateFunction::Correlation => {
+                        protobuf::AggregateFunction::Correlation
+                    }
+                    AggregateFunction::ApproxDistinct => {
+                        protobuf::AggregateFunction::ApproxDistinct
+                    }
+                    AggregateFunction::ApproxPercentileCont => {
+                        protobuf::AggregateFunction::ApproxPercentileCont
+                    }
+                    AggregateFunction::ApproxMedian => {
+                        protobuf::AggregateFunction::ApproxMedian
+                    }
+                    AggregateFunction::ArrayAgg => protobuf::AggregateFunction::ArrayAgg,
+                    AggregateFunction::StringAgg => protobuf::AggregateFunction::StringAgg,
+                    AggregateFunction::Grouping => {
+                        return Err(BallistaError::General(
+                            "GROUPING is not supported by ballista".to_owned(),
+                        ))
+                    }
                 };
 
-                let arg = &args[0];
+                let expr = args
+                    .iter()
+                    .map(|e| e.try_into())
+                    .collect::<Result<Vec<protobuf::LogicalExprNode>, BallistaError>>()?;
                 let aggregate_expr = Box::new(protobuf::AggregateExprNode {
                     aggr_function: aggr_function.into(),
-                    expr: Some(Box::new(arg.try_into()?)),
+                    expr,
                 });
                 Ok(protobuf::LogicalExprNode {
                     expr_type: Some(ExprType::AggregateExpr(aggregate_expr)),
@@ -1015,6 +1061,17 @@ impl TryInto<protobuf::LogicalExprNode> for &Expr {
             }
             Expr::ScalarUDF { .. } => unimplemented!(),
             Expr::AggregateUDF { .. } => unimplemented!(),
+            Expr::WindowFunction { .. } => Err(BallistaError::NotImplemented(
+                "Window functions are not supported by ballista".to_owned(),
+            )),
+            Expr::Exists { .. } | Expr::InSubquery { .. } | Expr::ScalarSubquery(_) => {
+                Err(BallistaError::NotImplemented(
+                    "Subqueries are not supported by ballista".to_owned(),
+                ))
+            }
+            Expr::GroupingSet(_) => Err(BallistaError::NotImplemented(
+                "Grouping sets are not supported by ballista".to_owned(),
+            )),
             Expr::Not(expr) => {
                 let expr = Box::new(protobuf::Not {
                     expr: Some(Box::new(expr.as_ref().try_into()?)),
@@ -227,13 +227,18 @@ impl TryInto<Arc<dyn ExecutionPlan>> for &protobuf::PhysicalPlanNode {
                 for (expr, name) in &logical_agg_expr {
                     match expr {
                         Expr::AggregateFunction { fun, args, .. } => {
-                            let arg = df_planner
-                                .create_physical_expr(&args[0], &physical_schema, &ctx_state)
-                                .map_err(|e| BallistaError::General(format!("{:?}", e)))?;
+                            let args = args
+                                .iter()
+                                .map(|arg| {
+                                    df_planner
+                                        .create_physical_expr(arg, &physical_schema, &ctx_state)
+                                        .map_err(|e| BallistaError::General(format!("{:?}", e)))
+                                })
+                                .collect::<Result<Vec<_>, _>>()?;
                             physical_aggr_expr.push(create_aggregate_expr(
                                 &fun,
                                 false,
-                                &[arg],
+                                &args,
                                 &physical_schema,
                                 name.to_string(),
                             )?);
@@ -272,6 +277,9 @@ impl TryInto<Arc<dyn ExecutionPlan>> for &protobuf::PhysicalPlanNode {
                     protobuf::JoinType::Inner => JoinType::Inner,
                     protobuf::JoinType::Left => JoinType::Left,
                     protobuf::JoinType::Right => JoinType::Right,
+                    protobuf::JoinType::Full => JoinType::Full,
+                    protobuf::JoinType::Semi => JoinType::Semi,
+                    protobuf::JoinType::Anti => JoinType::Anti,
                 };
                 Ok(Arc::new(HashJoinExec::try_new(
                     left, right, &on, &join_type,
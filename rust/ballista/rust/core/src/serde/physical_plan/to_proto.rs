@@ -47,7 +47,10 @@ use datafusion::{
 
 use datafusion::physical_plan::{
     empty::EmptyExec,
-    expressions::{Avg, BinaryExpr, Column, Sum},
+    expressions::{
+        ApproxDistinct, ApproxPercentileCont, ArrayAgg, Avg, BinaryExpr, Column, Correlation, Covariance, StatsType, Stddev, StringAgg, Sum,
+        Variance,
+    },
     Partitioning,
 };
 use datafusion::physical_plan::{AggregateExpr, ExecutionPlan, PhysicalExpr};
@@ -130,6 +133,9 @@ impl TryInto<protobuf::PhysicalPlanNode> for Arc<dyn ExecutionPlan> {
                 JoinType::Inner => protobuf::JoinType::Inner,
                 JoinType::Left => protobuf::JoinType::Left,
                 JoinType::Right => protobuf::JoinType::Right,
+                JoinType::Full => protobuf::JoinType::Full,
+                JoinType::Semi => protobuf::JoinType::Semi,
+                JoinType::Anti => protobuf::JoinType::Anti,
             };
             Ok(protobuf::PhysicalPlanNode {
                 physical_plan_type: Some(PhysicalPlanType::HashJoin(Box::new(
@@ -138,10 +144,16 @@ impl TryInto<protobuf::PhysicalPlanNode> for Arc<dyn ExecutionPlan> {
                         right: Some(Box::new(right)),
                         on,
                         join_type: join_type.into(),
+                        null_equals_null: exec.null_equals_null(),
                     },
                 ))),
             })
         } else if let Some(exec) = plan.downcast_ref::<HashAggregateExec>() {
+            if exec.grouping_sets().is_some() {
+                return Err(BallistaError::General(
+                    "Grouping sets are not supported by ballista".to_owned(),
+                ));
+            }
             let groups = exec
                 .group_expr()
                 .iter()
@@ -359,6 +371,44 @@ impl TryInto<protobuf::LogicalExprNode> for Arc<dyn AggregateExpr> {
             Ok(protobuf::AggregateFunction::Sum.into())
         } else if self.as_any().downcast_ref::<Count>().is_some() {
             Ok(protobuf::AggregateFunction::Count.into())
+        } else if let Some(expr) = self.as_any().downcast_ref::<Variance>() {
+            Ok(match expr.stats_type() {
+                StatsType::Sample => protobuf::AggregateFunction::Variance,
+                StatsType::Population => protobuf::AggregateFunction::VariancePop,
+            }
+            .into())
+        } else if let Some(expr) = self.as_any().downcast_ref::<Stddev>() {
+            Ok(match expr.stats_type() {
+                StatsType::Sample => protobuf::AggregateFunction::Stddev,
+                StatsType::Population => protobuf::AggregateFunction::StddevPop,
+            }
+            .into())
+        } else if let Some(expr) = self.as_any().downcast_ref::<Covariance>() {
+            Ok(match expr.stats_type() {
+                StatsType::Sample => protobuf::AggregateFunction::Covariance,
+                StatsType::Population => protobuf::AggregateFunction::CovariancePop,
+            }
+            .into())
+        } else if self.as_any().downcast_ref::<Correlation>().is_some() {
+            Ok(protobuf::AggregateFunction::Correlation.into())
+        } else if self.as_any().downcast_ref::<ApproxDistinct>().is_some() {
+            Ok(protobuf::AggregateFunction::ApproxDistinct.into())
+        } else if self.as_any().downcast_ref::<ApproxPercentileCont>().is_some() {
+            // the percentile is the second expression
+            Ok(protobuf::AggregateFunction::ApproxPercentileCont.into())
+        } else if let Some(expr) = self.as_any().downcast_ref::<ArrayAgg>() {
+            // the sort options of the ordering are not serialized
+            if expr.ordering().is_empty() {
+                Ok(protobuf::AggregateFunction::ArrayAgg.into())
+            } else {
+                Err(BallistaError::NotImplemented(format!(
+                    "Ordered aggregate function not supported: {:?}",
+                    self
+                )))
+            }
+        } else if self.as_any().downcast_ref::<StringAgg>().is_some() {
+            // the separator is the second expression
+            Ok(protobuf::AggregateFunction::StringAgg.into())
         } else {
             Err(BallistaError::NotImplemented(format!(
                 "Aggregate function not supported: {:?}",
@@ -374,7 +424,7 @@ impl TryInto<protobuf::LogicalExprNode> for Arc<dyn AggregateExpr> {
             expr_type: Some(protobuf::logical_expr_node::ExprType::AggregateExpr(
                 Box::new(protobuf::AggregateExprNode {
                     aggr_function,
-                    expr: Some(Box::new(expressions[0].clone())),
+                    expr: expressions,
                 }),
             )),
         })
@@ -1359,6 +1359,11 @@ mod tests {
         run_query(1).await
     }
 
+    #[tokio::test]
+    async fn run_q2() -> Result<()> {
+        run_query(2).await
+    }
+
     #[tokio::test]
     async fn run_q3() -> Result<()> {
         run_query(3).await
@@ -1374,11 +1379,21 @@ mod tests {
         run_query(6).await
     }
 
+    #[tokio::test]
+    async fn run_q9() -> Result<()> {
+        run_query(9).await
+    }
+
     #[tokio::test]
     async fn run_q10() -> Result<()> {
         run_query(10).await
     }
 
+    #[tokio::test]
+    async fn run_q11() -> Result<()> {
+        run_query(11).await
+    }
+
     #[tokio::test]
     async fn run_q12() -> Result<()> {
         run_query(12).await
@@ -1389,6 +1404,26 @@ mod tests {
         run_query(14).await
     }
 
+    #[tokio::test]
+    async fn run_q16() -> Result<()> {
+        run_query(16).await
+    }
+
+    #[tokio::test]
+    async fn run_q17() -> Result<()> {
+        run_query(17).await
+    }
+
+    #[tokio::test]
+    async fn run_q18() -> Result<()> {
+        run_query(18).await
+    }
+
+    #[tokio::test]
+    async fn run_q19() -> Result<()> {
+        run_query(19).await
+    }
+
     /// Specialised String representation
     fn col_str(column: &ArrayRef, row_index: usize) -> String {
         if column.is_null(row_index) {
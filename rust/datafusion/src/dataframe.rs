@@ -18,10 +18,12 @@
 //! DataFrame API for building and executing query plans.
 
 use crate::arrow::record_batch::RecordBatch;
-use crate::error::Result;
+use crate::error::{DataFusionError, Result};
 use crate::logical_plan::{
     DFSchema, Expr, FunctionRegistry, JoinType, LogicalPlan, Partitioning,
 };
+use crate::physical_plan::file_sink::FileWriteOptions;
+use parquet::file::properties::WriterProperties;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -37,6 +39,12 @@ use async_trait::async_trait;
 ///
 /// The query can be executed by calling the `collect` method.
 ///
+/// Unlike the other methods, the methods writing the results to files (`write_csv`,
+/// `write_parquet`, `write_json` and `write`) have default implementations, that
+/// return a `NotImplemented` error: they were added to the trait after it was
+/// published, and the defaults keep the existing implementations of the trait
+/// compiling.
+///
 /// ```
 /// # use datafusion::prelude::*;
 /// # use datafusion::error::Result;
@@ -236,6 +244,106 @@ pub trait DataFrame: Send + Sync {
     /// ```
     async fn collect_partitioned(&self) -> Result<Vec<Vec<RecordBatch>>>;
 
+    /// Executes this DataFrame and writes each partition of the results to a
+    /// CSV file of the new directory `path`. Returns a `NotImplemented` error
+    /// unless implemented.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let path = dir.path().join("example").to_str().unwrap().to_string();
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// df.write_csv(&path).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn write_csv(&self, _path: &str) -> Result<()> {
+        Err(DataFusionError::NotImplemented(
+            "Writing CSV files is not supported by this DataFrame".to_string(),
+        ))
+    }
+
+    /// Executes this DataFrame and writes each partition of the results to a
+    /// Parquet file of the new directory `path`. Returns a `NotImplemented`
+    /// error unless implemented.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let path = dir.path().join("example").to_str().unwrap().to_string();
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// df.write_parquet(&path, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn write_parquet(
+        &self,
+        _path: &str,
+        _writer_properties: Option<WriterProperties>,
+    ) -> Result<()> {
+        Err(DataFusionError::NotImplemented(
+            "Writing Parquet files is not supported by this DataFrame".to_string(),
+        ))
+    }
+
+    /// Executes this DataFrame and writes each partition of the results to a
+    /// newline-delimited JSON file of the new directory `path`. Returns a
+    /// `NotImplemented` error unless implemented.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let path = dir.path().join("example").to_str().unwrap().to_string();
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// df.write_json(&path).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn write_json(&self, _path: &str) -> Result<()> {
+        Err(DataFusionError::NotImplemented(
+            "Writing JSON files is not supported by this DataFrame".to_string(),
+        ))
+    }
+
+    /// Executes this DataFrame and writes each partition of the results to a
+    /// file of the new directory `path`, with the format and compression of
+    /// `options`. Returns a `NotImplemented` error unless implemented.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::physical_plan::file_sink::{FileCompression, FileWriteOptions};
+    /// # use datafusion::datasource::FileFormat;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let path = dir.path().join("example").to_str().unwrap().to_string();
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let options = FileWriteOptions::new(FileFormat::NdJson)
+    ///     .compression(FileCompression::Gzip);
+    /// df.write(&path, options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn write(&self, _path: &str, _options: FileWriteOptions) -> Result<()> {
+        Err(DataFusionError::NotImplemented(
+            "Writing files is not supported by this DataFrame".to_string(),
+        ))
+    }
+
     /// Returns the schema describing the output of this DataFrame in terms of columns returned,
     /// where each column has a name, data type, and nullability attribute.
 
@@ -39,15 +39,19 @@ use std::string::String;
 use std::sync::Arc;
 
 use crate::datasource::datasource::Statistics;
+use crate::datasource::object_store::local::LocalFileSystem;
+use crate::datasource::object_store::{self, ObjectStore};
 use crate::datasource::TableProvider;
 use crate::error::{DataFusionError, Result};
 use crate::logical_plan::Expr;
 use crate::physical_plan::csv::CsvExec;
 pub use crate::physical_plan::csv::CsvReadOptions;
-use crate::physical_plan::{common, ExecutionPlan};
+use crate::physical_plan::ExecutionPlan;
 
 /// Represents a CSV file with a provided schema
 pub struct CsvFile {
+    /// The store the files are read from
+    object_store: Arc<dyn ObjectStore>,
     /// Path to a single CSV file or a directory containing one of more CSV files
     path: String,
     schema: SchemaRef,
@@ -60,11 +64,23 @@ pub struct CsvFile {
 impl CsvFile {
     /// Attempt to initialize a new `CsvFile` from a file path
     pub fn try_new(path: &str, options: CsvReadOptions) -> Result<Self> {
+        Self::try_new_with_object_store(Arc::new(LocalFileSystem), path, options)
+    }
+
+    /// Attempt to initialize a new `CsvFile` from a path in the given object store
+    pub fn try_new_with_object_store(
+        object_store: Arc<dyn ObjectStore>,
+        path: &str,
+        options: CsvReadOptions,
+    ) -> Result<Self> {
         let schema = Arc::new(match options.schema {
             Some(s) => s.clone(),
             None => {
-                let mut filenames: Vec<String> = vec![];
-                common::build_file_list(path, &mut filenames, options.file_extension)?;
+                let filenames = object_store::list_files(
+                    &object_store,
+                    path,
+                    options.file_extension,
+                )?;
                 if filenames.is_empty() {
                     return Err(DataFusionError::Plan(format!(
                         "No files found at {path} with file extension {file_extension}",
@@ -72,11 +88,12 @@ impl CsvFile {
                         file_extension = options.file_extension
                     )));
                 }
-                CsvExec::try_infer_schema(&filenames, &options)?
+                CsvExec::try_infer_schema(&object_store, &filenames, &options)?
             }
         });
 
         Ok(Self {
+            object_store,
             path: String::from(path),
             schema,
             has_header: options.has_header,
@@ -123,7 +140,8 @@ impl TableProvider for CsvFile {
         _filters: &[Expr],
         limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(CsvExec::try_new(
+        Ok(Arc::new(CsvExec::try_new_with_object_store(
+            self.object_store.clone(),
             &self.path,
             CsvReadOptions::new()
                 .schema(&self.schema)
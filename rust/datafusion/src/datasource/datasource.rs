@@ -21,7 +21,7 @@ use std::any::Any;
 use std::sync::Arc;
 
 use crate::error::Result;
-use crate::logical_plan::Expr;
+use crate::logical_plan::{Expr, Operator};
 use crate::physical_plan::ExecutionPlan;
 use crate::{arrow::datatypes::SchemaRef, scalar::ScalarValue};
 
@@ -37,7 +37,7 @@ pub struct Statistics {
     pub column_statistics: Option<Vec<ColumnStatistics>>,
 }
 /// This table statistics are estimates about column
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct ColumnStatistics {
     /// Number of null values on column
     pub null_count: Option<usize>,
@@ -49,6 +49,119 @@ pub struct ColumnStatistics {
     pub distinct_count: Option<usize>,
 }
 
+/// Selectivity of an equality predicate whose column has no known distinct count
+pub(crate) const DEFAULT_EQUALITY_SELECTIVITY: f64 = 0.1;
+/// Selectivity of a range predicate whose column has no known min/max values
+pub(crate) const DEFAULT_RANGE_SELECTIVITY: f64 = 1.0 / 3.0;
+/// Selectivity of any other predicate
+pub(crate) const DEFAULT_SELECTIVITY: f64 = 0.2;
+
+/// Estimated statistics of a column, used by the logical and physical plans to
+/// estimate the selectivity of predicates
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ColumnEstimate {
+    /// Estimated number of distinct values
+    pub distinct_count: Option<f64>,
+    /// Minimum value, for numeric and temporal columns
+    pub min_value: Option<f64>,
+    /// Maximum value, for numeric and temporal columns
+    pub max_value: Option<f64>,
+    /// Fraction of the rows that are null
+    pub null_fraction: Option<f64>,
+}
+
+impl ColumnEstimate {
+    /// The estimate of a column with `statistics`, out of `num_rows` rows
+    pub fn from_statistics(
+        statistics: &ColumnStatistics,
+        num_rows: Option<usize>,
+    ) -> Self {
+        Self {
+            distinct_count: statistics.distinct_count.map(|d| d as f64),
+            min_value: statistics.min_value.as_ref().and_then(scalar_to_f64),
+            max_value: statistics.max_value.as_ref().and_then(scalar_to_f64),
+            null_fraction: match (statistics.null_count, num_rows) {
+                (Some(null_count), Some(num_rows)) if num_rows > 0 => {
+                    Some(null_count as f64 / num_rows as f64)
+                }
+                _ => None,
+            },
+        }
+    }
+
+    /// The fraction of the rows for which `<column> <op> <value>` is true,
+    /// where `value` is the numeric value of a literal
+    pub fn comparison_selectivity(&self, op: Operator, value: Option<f64>) -> f64 {
+        let eq = match value {
+            // the value is outside of the range of the column
+            Some(v)
+                if matches!(self.min_value, Some(min) if v < min)
+                    || matches!(self.max_value, Some(max) if v > max) =>
+            {
+                0.0
+            }
+            _ => self
+                .distinct_count
+                .map_or(DEFAULT_EQUALITY_SELECTIVITY, |d| 1.0 / d.max(1.0)),
+        };
+        match op {
+            Operator::Eq => eq,
+            Operator::NotEq => 1.0 - eq,
+            Operator::Lt | Operator::LtEq => self.range_selectivity(None, value),
+            Operator::Gt | Operator::GtEq => self.range_selectivity(value, None),
+            _ => DEFAULT_SELECTIVITY,
+        }
+    }
+
+    /// The fraction of the range of the column that lies between `low` and `high`
+    pub fn range_selectivity(&self, low: Option<f64>, high: Option<f64>) -> f64 {
+        match (self.min_value, self.max_value) {
+            (Some(min), Some(max)) if low.is_some() || high.is_some() => {
+                let low = low.map_or(min, |low| low.max(min));
+                let high = high.map_or(max, |high| high.min(max));
+                if high < low {
+                    0.0
+                } else if max > min {
+                    (high - low) / (max - min)
+                } else {
+                    1.0
+                }
+            }
+            _ => DEFAULT_RANGE_SELECTIVITY,
+        }
+    }
+
+    /// The fraction of the rows that are null
+    pub fn null_selectivity(&self) -> f64 {
+        self.null_fraction.unwrap_or(DEFAULT_EQUALITY_SELECTIVITY)
+    }
+}
+
+/// Converts numeric and temporal values to f64, for interpolating ranges
+pub(crate) fn scalar_to_f64(value: &ScalarValue) -> Option<f64> {
+    match value {
+        ScalarValue::Float32(v) => v.map(|v| v as f64),
+        ScalarValue::Float64(v) => *v,
+        ScalarValue::Decimal128(v, _, scale) => {
+            v.map(|v| v as f64 / 10f64.powi(*scale as i32))
+        }
+        ScalarValue::Int8(v) => v.map(|v| v as f64),
+        ScalarValue::Int16(v) => v.map(|v| v as f64),
+        ScalarValue::Int32(v) => v.map(|v| v as f64),
+        ScalarValue::Int64(v) => v.map(|v| v as f64),
+        ScalarValue::UInt8(v) => v.map(|v| v as f64),
+        ScalarValue::UInt16(v) => v.map(|v| v as f64),
+        ScalarValue::UInt32(v) => v.map(|v| v as f64),
+        ScalarValue::UInt64(v) => v.map(|v| v as f64),
+        ScalarValue::Date32(v) => v.map(|v| v as f64),
+        ScalarValue::Date64(v)
+        | ScalarValue::TimeMillisecond(v)
+        | ScalarValue::TimeMicrosecond(v)
+        | ScalarValue::TimeNanosecond(v) => v.map(|v| v as f64),
+        _ => None,
+    }
+}
+
 /// Indicates whether and how a filter expression can be handled by a
 /// TableProvider for table scans.
 #[derive(Debug, Clone)]
@@ -0,0 +1,146 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Line-delimited JSON data source
+//!
+//! This data source allows files containing one JSON object per line to be used
+//! as input for queries.
+//!
+//! Example:
+//!
+//! ```
+//! use datafusion::datasource::TableProvider;
+//! use datafusion::datasource::json::{NdJsonFile, NdJsonReadOptions};
+//!
+//! let jsondata = NdJsonFile::try_new(
+//!     "tests/jsons/1.json",
+//!     NdJsonReadOptions::new(),
+//! ).unwrap();
+//! let schema = jsondata.schema();
+//! ```
+
+use arrow::datatypes::SchemaRef;
+use std::any::Any;
+use std::string::String;
+use std::sync::Arc;
+
+use crate::datasource::datasource::Statistics;
+use crate::datasource::object_store::local::LocalFileSystem;
+use crate::datasource::object_store::{self, ObjectStore};
+use crate::datasource::TableProvider;
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::Expr;
+use crate::physical_plan::json::NdJsonExec;
+pub use crate::physical_plan::json::NdJsonReadOptions;
+use crate::physical_plan::ExecutionPlan;
+
+/// Represents a line-delimited JSON file with a provided or inferred schema
+pub struct NdJsonFile {
+    /// The store the files are read from
+    object_store: Arc<dyn ObjectStore>,
+    /// Path to a single JSON file or a directory containing one of more JSON files
+    path: String,
+    schema: SchemaRef,
+    file_extension: String,
+    statistics: Statistics,
+}
+
+impl NdJsonFile {
+    /// Attempt to initialize a new `NdJsonFile` from a file path
+    pub fn try_new(path: &str, options: NdJsonReadOptions) -> Result<Self> {
+        Self::try_new_with_object_store(Arc::new(LocalFileSystem), path, options)
+    }
+
+    /// Attempt to initialize a new `NdJsonFile` from a path in the given object store
+    pub fn try_new_with_object_store(
+        object_store: Arc<dyn ObjectStore>,
+        path: &str,
+        options: NdJsonReadOptions,
+    ) -> Result<Self> {
+        let schema = Arc::new(match options.schema {
+            Some(s) => s.clone(),
+            None => {
+                let filenames = object_store::list_files(
+                    &object_store,
+                    path,
+                    options.file_extension,
+                )?;
+                if filenames.is_empty() {
+                    return Err(DataFusionError::Plan(format!(
+                        "No files found at {path} with file extension {file_extension}",
+                        path = path,
+                        file_extension = options.file_extension
+                    )));
+                }
+                NdJsonExec::try_infer_schema(&object_store, &filenames, &options)?
+            }
+        });
+
+        Ok(Self {
+            object_store,
+            path: String::from(path),
+            schema,
+            file_extension: String::from(options.file_extension),
+            statistics: Statistics::default(),
+        })
+    }
+
+    /// Get the path for the JSON file(s) represented by this NdJsonFile instance
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the file extension for the JSON file(s) represented by this NdJsonFile instance
+    pub fn file_extension(&self) -> &str {
+        &self.file_extension
+    }
+}
+
+impl TableProvider for NdJsonFile {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+        _filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(NdJsonExec::try_new_with_object_store(
+            self.object_store.clone(),
+            &self.path,
+            NdJsonReadOptions::new()
+                .schema(&self.schema)
+                .file_extension(self.file_extension.as_str()),
+            projection.clone(),
+            limit
+                .map(|l| std::cmp::min(l, batch_size))
+                .unwrap_or(batch_size),
+            limit,
+        )?))
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.statistics.clone()
+    }
+}
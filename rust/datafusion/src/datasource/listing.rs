@@ -0,0 +1,771 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Listing data source for Hive-style partitioned directories
+//!
+//! A listing table reads all files below a root directory laid out as
+//! `root/key1=value1/key2=value2/file`. The partition keys are exposed as
+//! additional `Utf8` columns after the columns of the files, and filters on them
+//! are used to skip whole directories when scanning.
+//!
+//! Example:
+//!
+//! ```no_run
+//! use datafusion::datasource::TableProvider;
+//! use datafusion::datasource::listing::{FileFormat, ListingOptions, ListingTable};
+//!
+//! let table = ListingTable::try_new(
+//!     "/data/events",
+//!     ListingOptions::new(FileFormat::Parquet),
+//! ).unwrap();
+//! // e.g. the file columns followed by `date` and `region`
+//! let schema = table.schema();
+//! ```
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanArray, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use crate::catalog::catalog::MemoryCatalogList;
+use crate::datasource::datasource::{Statistics, TableProviderFilterPushDown};
+use crate::datasource::object_store::local::LocalFileSystem;
+use crate::datasource::object_store::{self, ObjectStore, ObjectStoreRegistry};
+use crate::datasource::TableProvider;
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::{ExecutionConfig, ExecutionContextState};
+use crate::logical_plan::{combine_filters, Expr};
+use crate::optimizer::utils::expr_to_column_names;
+use crate::physical_plan::csv::{CsvExec, CsvReadOptions};
+use crate::physical_plan::empty::EmptyExec;
+use crate::physical_plan::expressions::{Column, Literal};
+use crate::physical_plan::json::{NdJsonExec, NdJsonReadOptions};
+use crate::physical_plan::parquet::ParquetExec;
+use crate::physical_plan::planner::DefaultPhysicalPlanner;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::union::UnionExec;
+use crate::physical_plan::{ColumnarValue, ExecutionPlan, PhysicalExpr};
+use crate::scalar::ScalarValue;
+
+/// The format of the files of a listing table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileFormat {
+    /// Comma separated values
+    Csv {
+        /// Do the files have a header row?
+        has_header: bool,
+        /// The column delimiter
+        delimiter: u8,
+    },
+    /// Apache Parquet columnar storage
+    Parquet,
+    /// Newline-delimited JSON
+    NdJson,
+}
+
+impl FileFormat {
+    /// The file extension used for files of this format
+    pub fn default_extension(&self) -> &'static str {
+        match self {
+            FileFormat::Csv { .. } => ".csv",
+            FileFormat::Parquet => ".parquet",
+            FileFormat::NdJson => ".json",
+        }
+    }
+}
+
+/// Options for creating a [`ListingTable`]
+#[derive(Debug, Clone)]
+pub struct ListingOptions {
+    /// The format of the files
+    pub format: FileFormat,
+    /// Only files with this extension are selected. Defaults to the extension of
+    /// the format.
+    pub file_extension: String,
+    /// An optional schema of the files, without the partition columns. If None,
+    /// it is inferred from the files.
+    pub schema: Option<SchemaRef>,
+    /// Max number of concurrent file reads for Parquet. Defaults to 1.
+    pub max_concurrency: usize,
+}
+
+impl ListingOptions {
+    /// Create listing options for files of the given format with default presets
+    pub fn new(format: FileFormat) -> Self {
+        Self {
+            format,
+            file_extension: format.default_extension().to_owned(),
+            schema: None,
+            max_concurrency: 1,
+        }
+    }
+
+    /// Specify the file extension for file selection
+    pub fn file_extension(mut self, file_extension: &str) -> Self {
+        self.file_extension = file_extension.to_owned();
+        self
+    }
+
+    /// Specify the schema of the files
+    pub fn schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Configure the number of concurrent Parquet file reads
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+}
+
+/// A leaf directory of a listing table together with its partition values
+#[derive(Debug, Clone)]
+struct ListingPartition {
+    /// Path of the directory containing the files of this partition
+    path: String,
+    /// One value per partition column
+    values: Vec<String>,
+}
+
+/// A table made of all the files below a directory, partitioned by `key=value`
+/// directory names
+pub struct ListingTable {
+    /// The store the files are read from
+    object_store: Arc<dyn ObjectStore>,
+    /// Path of the root directory
+    path: String,
+    options: ListingOptions,
+    /// Schema of the files, without the partition columns
+    file_schema: SchemaRef,
+    /// The names of the partition columns, outermost directory first
+    partition_columns: Vec<String>,
+    /// The leaf directories, ordered by path
+    partitions: Vec<ListingPartition>,
+    /// Schema of the table: the file columns followed by the partition columns
+    schema: SchemaRef,
+    statistics: Statistics,
+}
+
+impl ListingTable {
+    /// Attempt to initialize a new `ListingTable` by listing the files below `path`
+    pub fn try_new(path: &str, options: ListingOptions) -> Result<Self> {
+        Self::try_new_with_object_store(Arc::new(LocalFileSystem), path, options)
+    }
+
+    /// Attempt to initialize a new `ListingTable` by listing the files below `path`
+    /// in the given object store
+    pub fn try_new_with_object_store(
+        object_store: Arc<dyn ObjectStore>,
+        path: &str,
+        options: ListingOptions,
+    ) -> Result<Self> {
+        let filenames =
+            object_store::list_files(&object_store, path, &options.file_extension)?;
+        if filenames.is_empty() {
+            return Err(DataFusionError::Plan(format!(
+                "No files found at {path} with file extension {file_extension}",
+                path = path,
+                file_extension = options.file_extension
+            )));
+        }
+
+        let (partition_columns, partitions) = discover_partitions(path, &filenames)?;
+
+        let file_schema = match &options.schema {
+            Some(schema) => schema.clone(),
+            None => Arc::new(infer_schema(object_store.clone(), &filenames, &options)?),
+        };
+
+        let mut fields = file_schema.fields().clone();
+        for name in &partition_columns {
+            if file_schema.field_with_name(name).is_ok() {
+                return Err(DataFusionError::Plan(format!(
+                    "Partition column {} conflicts with a column of the files at {}",
+                    name, path
+                )));
+            }
+            fields.push(Field::new(name, DataType::Utf8, false));
+        }
+
+        Ok(Self {
+            object_store,
+            path: path.to_owned(),
+            options,
+            file_schema,
+            partition_columns,
+            partitions,
+            schema: Arc::new(Schema::new(fields)),
+            statistics: Statistics::default(),
+        })
+    }
+
+    /// Get the path of the root directory of this table
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the names of the partition columns of this table
+    pub fn partition_columns(&self) -> &[String] {
+        &self.partition_columns
+    }
+
+    /// Get the schema of the files of this table, without the partition columns
+    pub fn file_schema(&self) -> SchemaRef {
+        self.file_schema.clone()
+    }
+
+    /// Whether `expr` only references partition columns, so that it can be
+    /// evaluated against the partition values alone
+    fn is_partition_filter(&self, expr: &Expr) -> bool {
+        let mut columns = HashSet::new();
+        if expr_to_column_names(expr, &mut columns).is_err() {
+            return false;
+        }
+        !columns.is_empty() && columns.iter().all(|c| self.partition_columns.contains(c))
+    }
+
+    /// Returns the partitions whose values may satisfy all the `filters`.
+    ///
+    /// The filters are evaluated against a batch with one row of partition values
+    /// per partition, similar to how row groups are pruned in `ParquetExec`.
+    /// Partitions are only skipped when a filter evaluates to false or null;
+    /// filters that cannot be evaluated do not prune anything.
+    fn prune_partitions(&self, filters: &[&Expr]) -> Vec<&ListingPartition> {
+        let mask = match combine_filters(
+            &filters.iter().map(|f| (*f).clone()).collect::<Vec<_>>(),
+        ) {
+            Some(predicate) => self.evaluate_partition_predicate(&predicate).ok(),
+            None => None,
+        };
+        match mask
+            .as_ref()
+            .and_then(|a| a.as_any().downcast_ref::<BooleanArray>())
+        {
+            Some(mask) => self
+                .partitions
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask.is_valid(*i) && mask.value(*i))
+                .map(|(_, p)| p)
+                .collect(),
+            None => self.partitions.iter().collect(),
+        }
+    }
+
+    fn evaluate_partition_predicate(&self, predicate: &Expr) -> Result<ArrayRef> {
+        let schema = Arc::new(Schema::new(
+            self.partition_columns
+                .iter()
+                .map(|name| Field::new(name, DataType::Utf8, false))
+                .collect(),
+        ));
+        let columns = (0..self.partition_columns.len())
+            .map(|i| {
+                Arc::new(StringArray::from(
+                    self.partitions
+                        .iter()
+                        .map(|p| p.values[i].as_str())
+                        .collect::<Vec<_>>(),
+                )) as ArrayRef
+            })
+            .collect();
+        let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+        let execution_context_state = ExecutionContextState {
+            catalog_list: Arc::new(MemoryCatalogList::new()),
+            scalar_functions: HashMap::new(),
+            var_provider: HashMap::new(),
+            aggregate_functions: HashMap::new(),
+            config: ExecutionConfig::new(),
+            object_store_registry: Arc::new(ObjectStoreRegistry::new()),
+        };
+        let predicate = DefaultPhysicalPlanner::default().create_physical_expr(
+            predicate,
+            &schema,
+            &execution_context_state,
+        )?;
+        match predicate.evaluate(&batch)? {
+            ColumnarValue::Array(array) if array.data_type() == &DataType::Boolean => {
+                Ok(array)
+            }
+            ColumnarValue::Array(_) => Err(DataFusionError::Plan(
+                "partition predicate didn't return a boolean array".to_string(),
+            )),
+            ColumnarValue::Scalar(_) => Err(DataFusionError::Plan(
+                "partition predicate didn't return an array".to_string(),
+            )),
+        }
+    }
+
+    /// Create the plan scanning the files of a single directory
+    fn scan_files(
+        &self,
+        path: &str,
+        projection: Vec<usize>,
+        predicate: Option<Expr>,
+        batch_size: usize,
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let file_extension = self.options.file_extension.as_str();
+        Ok(match self.options.format {
+            FileFormat::Csv {
+                has_header,
+                delimiter,
+            } => Arc::new(CsvExec::try_new_with_object_store(
+                self.object_store.clone(),
+                path,
+                CsvReadOptions::new()
+                    .schema(&self.file_schema)
+                    .has_header(has_header)
+                    .delimiter(delimiter)
+                    .file_extension(file_extension),
+                Some(projection),
+                batch_size,
+                limit,
+            )?),
+            FileFormat::Parquet => {
+                Arc::new(ParquetExec::try_from_path_with_object_store(
+                    self.object_store.clone(),
+                    path,
+                    Some(projection),
+                    predicate,
+                    batch_size,
+                    self.options.max_concurrency,
+                    limit,
+                )?)
+            }
+            FileFormat::NdJson => Arc::new(NdJsonExec::try_new_with_object_store(
+                self.object_store.clone(),
+                path,
+                NdJsonReadOptions::new()
+                    .schema(&self.file_schema)
+                    .file_extension(file_extension),
+                Some(projection),
+                batch_size,
+                limit,
+            )?),
+        })
+    }
+}
+
+impl TableProvider for ListingTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn supports_filter_pushdown(
+        &self,
+        filter: &Expr,
+    ) -> Result<TableProviderFilterPushDown> {
+        if self.is_partition_filter(filter) || self.options.format == FileFormat::Parquet
+        {
+            Ok(TableProviderFilterPushDown::Inexact)
+        } else {
+            Ok(TableProviderFilterPushDown::Unsupported)
+        }
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let num_file_columns = self.file_schema.fields().len();
+        let projection = projection
+            .clone()
+            .unwrap_or_else(|| (0..self.schema.fields().len()).collect());
+        let projected_schema = Arc::new(Schema::new(
+            projection
+                .iter()
+                .map(|i| self.schema.field(*i).clone())
+                .collect(),
+        ));
+
+        // the files need to produce at least one column to carry the row count
+        let mut file_projection: Vec<usize> = projection
+            .iter()
+            .filter(|i| **i < num_file_columns)
+            .cloned()
+            .collect();
+        if file_projection.is_empty() {
+            file_projection.push(0);
+        }
+
+        let (partition_filters, file_filters): (Vec<&Expr>, Vec<&Expr>) =
+            filters.iter().partition(|f| self.is_partition_filter(f));
+        let predicate =
+            combine_filters(&file_filters.into_iter().cloned().collect::<Vec<_>>());
+        let batch_size = limit
+            .map(|l| std::cmp::min(l, batch_size))
+            .unwrap_or(batch_size);
+
+        let inputs = self
+            .prune_partitions(&partition_filters)
+            .into_iter()
+            .map(|partition| {
+                let input = self.scan_files(
+                    &partition.path,
+                    file_projection.clone(),
+                    predicate.clone(),
+                    batch_size,
+                    limit,
+                )?;
+                let expr = projection
+                    .iter()
+                    .map(|i| {
+                        let name = self.schema.field(*i).name().clone();
+                        let expr: Arc<dyn PhysicalExpr> = if *i < num_file_columns {
+                            Arc::new(Column::new(&name))
+                        } else {
+                            let value = &partition.values[*i - num_file_columns];
+                            Arc::new(Literal::new(ScalarValue::Utf8(Some(value.clone()))))
+                        };
+                        (expr, name)
+                    })
+                    .collect();
+                Ok(Arc::new(ProjectionExec::try_new(expr, input)?)
+                    as Arc<dyn ExecutionPlan>)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if inputs.is_empty() {
+            Ok(Arc::new(EmptyExec::new(false, projected_schema)))
+        } else {
+            Ok(Arc::new(UnionExec::new(inputs)))
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.statistics.clone()
+    }
+}
+
+/// Find the partition columns and leaf directories from the paths of the files
+/// below `root`. All files must be nested in directories named `key=value` with
+/// the same keys in the same order.
+fn discover_partitions(
+    root: &str,
+    filenames: &[String],
+) -> Result<(Vec<String>, Vec<ListingPartition>)> {
+    let mut partition_columns: Option<Vec<String>> = None;
+    let mut partitions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for filename in filenames {
+        let file = Path::new(filename);
+        let dir = file.parent().unwrap_or_else(|| Path::new(root));
+        let relative = dir.strip_prefix(root).map_err(|_| {
+            DataFusionError::Plan(format!("File {} is not below {}", filename, root))
+        })?;
+
+        let mut keys = vec![];
+        let mut values = vec![];
+        for segment in relative.iter() {
+            let segment = segment.to_str().ok_or_else(|| {
+                DataFusionError::Plan(format!("Invalid path {}", filename))
+            })?;
+            match segment.split_once('=') {
+                Some((key, value)) if !key.is_empty() => {
+                    keys.push(key.to_owned());
+                    values.push(unescape_partition_value(value).ok_or_else(|| {
+                        DataFusionError::Plan(format!(
+                            "Invalid partition value {} of {}",
+                            value, filename
+                        ))
+                    })?);
+                }
+                _ => {
+                    return Err(DataFusionError::Plan(format!(
+                        "Directory {} of {} is not of the form key=value",
+                        segment, filename
+                    )))
+                }
+            }
+        }
+
+        match &partition_columns {
+            None => partition_columns = Some(keys),
+            Some(expected) if *expected != keys => {
+                return Err(DataFusionError::Plan(format!(
+                    "File {} is partitioned by {:?} but other files are partitioned by {:?}",
+                    filename, keys, expected
+                )))
+            }
+            _ => {}
+        }
+
+        let path = if relative.as_os_str().is_empty() {
+            root.to_owned()
+        } else {
+            dir.to_str()
+                .ok_or_else(|| {
+                    DataFusionError::Plan(format!("Invalid path {}", filename))
+                })?
+                .to_owned()
+        };
+        partitions.insert(path, values);
+    }
+
+    let partitions = partitions
+        .into_iter()
+        .map(|(path, values)| ListingPartition { path, values })
+        .collect();
+    Ok((partition_columns.unwrap_or_default(), partitions))
+}
+
+/// Decodes the `%XX` escapes of a partition value, with which Hive writes the
+/// characters that are not allowed in paths, e.g. `:` as `%3A`. A `%` that is not
+/// followed by two hexadecimal digits is kept as is. Returns None when the decoded
+/// value is not valid UTF-8.
+fn unescape_partition_value(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match (bytes[i], bytes.get(i + 1), bytes.get(i + 2)) {
+            (b'%', Some(high), Some(low))
+                if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() =>
+            {
+                u8::from_str_radix(&value[i + 1..i + 3], 16).ok()
+            }
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Infer the schema of the files, without the partition columns
+fn infer_schema(
+    object_store: Arc<dyn ObjectStore>,
+    filenames: &[String],
+    options: &ListingOptions,
+) -> Result<Schema> {
+    match options.format {
+        FileFormat::Csv {
+            has_header,
+            delimiter,
+        } => CsvExec::try_infer_schema(
+            &object_store,
+            filenames,
+            &CsvReadOptions::new()
+                .has_header(has_header)
+                .delimiter(delimiter),
+        ),
+        FileFormat::Parquet => {
+            let filenames: Vec<&str> = filenames.iter().map(|f| f.as_str()).collect();
+            let exec = ParquetExec::try_from_files_with_object_store(
+                object_store,
+                &filenames,
+                None,
+                None,
+                0,
+                1,
+                None,
+            )?;
+            Ok(exec.schema().as_ref().clone())
+        }
+        FileFormat::NdJson => NdJsonExec::try_infer_schema(
+            &object_store,
+            filenames,
+            &NdJsonReadOptions::new(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{col, lit};
+    use crate::physical_plan::collect;
+    use std::fs;
+
+    fn create_table_dir(files: &[(&str, &str)]) -> Result<tempfile::TempDir> {
+        let dir = tempfile::tempdir()?;
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(path, content)?;
+        }
+        Ok(dir)
+    }
+
+    fn csv_options() -> ListingOptions {
+        ListingOptions::new(FileFormat::Csv {
+            has_header: true,
+            delimiter: b',',
+        })
+    }
+
+    fn partitioned_dir() -> Result<tempfile::TempDir> {
+        create_table_dir(&[
+            ("date=2021-01-01/region=eu/1.csv", "a,b\n1,x\n2,y\n"),
+            ("date=2021-01-01/region=us/1.csv", "a,b\n3,z\n"),
+            ("date=2021-01-02/region=eu/1.csv", "a,b\n4,x\n"),
+            ("date=2021-01-02/region=eu/2.csv", "a,b\n5,y\n"),
+            ("date=2021-01-02/region=eu/_SUCCESS", ""),
+        ])
+    }
+
+    #[test]
+    fn discover_partition_columns() -> Result<()> {
+        let dir = partitioned_dir()?;
+        let table = ListingTable::try_new(dir.path().to_str().unwrap(), csv_options())?;
+        assert_eq!(vec!["date", "region"], table.partition_columns());
+        assert_eq!(3, table.partitions.len());
+
+        let fields: Vec<String> = table
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| format!("{}: {:?}", f.name(), f.data_type()))
+            .collect();
+        assert_eq!(
+            vec!["a: Int64", "b: Utf8", "date: Utf8", "region: Utf8"],
+            fields
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unpartitioned_directory() -> Result<()> {
+        let dir = create_table_dir(&[("1.csv", "a\n1\n"), ("2.csv", "a\n2\n")])?;
+        let table = ListingTable::try_new(dir.path().to_str().unwrap(), csv_options())?;
+        assert!(table.partition_columns().is_empty());
+        assert_eq!(1, table.partitions.len());
+        assert_eq!(1, table.schema().fields().len());
+        Ok(())
+    }
+
+    #[test]
+    fn inconsistent_partitions() -> Result<()> {
+        let dir = create_table_dir(&[
+            ("date=2021-01-01/1.csv", "a\n1\n"),
+            ("region=eu/1.csv", "a\n2\n"),
+        ])?;
+        let err = ListingTable::try_new(dir.path().to_str().unwrap(), csv_options())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("other files are partitioned by"));
+
+        let dir = create_table_dir(&[("data/1.csv", "a\n1\n")])?;
+        let err = ListingTable::try_new(dir.path().to_str().unwrap(), csv_options())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("is not of the form key=value"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scan_with_partition_columns() -> Result<()> {
+        let dir = partitioned_dir()?;
+        let table = ListingTable::try_new(dir.path().to_str().unwrap(), csv_options())?;
+
+        let exec = table.scan(&Some(vec![3, 0]), 1024, &[], None)?;
+        assert_eq!(4, exec.output_partitioning().partition_count());
+        let batches = collect(exec).await?;
+        let mut rows = vec![];
+        for batch in &batches {
+            assert_eq!("region", batch.schema().field(0).name());
+            let region = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            for i in 0..batch.num_rows() {
+                rows.push(region.value(i).to_owned());
+            }
+        }
+        rows.sort();
+        assert_eq!(vec!["eu", "eu", "eu", "eu", "us"], rows);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn prune_partitions_with_filters() -> Result<()> {
+        let dir = partitioned_dir()?;
+        let table = ListingTable::try_new(dir.path().to_str().unwrap(), csv_options())?;
+
+        let filter = col("region").eq(lit("eu"));
+        assert!(matches!(
+            table.supports_filter_pushdown(&filter)?,
+            TableProviderFilterPushDown::Inexact
+        ));
+        let exec = table.scan(&None, 1024, &[filter], None)?;
+        assert_eq!(3, exec.output_partitioning().partition_count());
+
+        let filters = vec![
+            col("region").eq(lit("eu")),
+            col("date").gt(lit("2021-01-01")),
+            // filters on file columns do not prune directories
+            col("a").lt(lit(0i64)),
+        ];
+        let exec = table.scan(&Some(vec![1]), 1024, &filters, None)?;
+        assert_eq!(2, exec.output_partitioning().partition_count());
+        let batches = collect(exec).await?;
+        assert_eq!(2, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+
+        let exec = table.scan(&Some(vec![2]), 1024, &[col("date").eq(lit("x"))], None)?;
+        assert_eq!("date", exec.schema().field(0).name());
+        let batches = collect(exec).await?;
+        assert_eq!(0, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unescape_partition_values() -> Result<()> {
+        let dir = create_table_dir(&[
+            ("date=2021-01-01%3A00/1.csv", "a\n1\n"),
+            ("date=2021-01-01%3a30/1.csv", "a\n2\n"),
+            ("date=100%25%/1.csv", "a\n3\n"),
+        ])?;
+        let table = ListingTable::try_new(dir.path().to_str().unwrap(), csv_options())?;
+        let mut values = table
+            .partitions
+            .iter()
+            .map(|p| p.values[0].as_str())
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(vec!["100%%", "2021-01-01:00", "2021-01-01:30"], values);
+
+        let filter = col("date").eq(lit("2021-01-01:00"));
+        let exec = table.scan(&Some(vec![0]), 1024, &[filter], None)?;
+        assert_eq!(1, exec.output_partitioning().partition_count());
+        let batches = collect(exec).await?;
+        assert_eq!(1, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+        Ok(())
+    }
+}
@@ -22,7 +22,7 @@
 use futures::StreamExt;
 use log::debug;
 use std::any::Any;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use arrow::datatypes::{Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
@@ -38,51 +38,11 @@ use crate::{
     physical_plan::{repartition::RepartitionExec, Partitioning},
 };
 
-use super::datasource::ColumnStatistics;
-
 /// In-memory table
 pub struct MemTable {
     schema: SchemaRef,
-    batches: Vec<Vec<RecordBatch>>,
-    statistics: Statistics,
-}
-
-// Calculates statistics based on partitions
-fn calculate_statistics(
-    schema: &SchemaRef,
-    partitions: &[Vec<RecordBatch>],
-) -> Statistics {
-    let num_rows: usize = partitions
-        .iter()
-        .flat_map(|batches| batches.iter().map(RecordBatch::num_rows))
-        .sum();
-
-    let mut null_count: Vec<usize> = vec![0; schema.fields().len()];
-    for partition in partitions.iter() {
-        for batch in partition {
-            for (i, array) in batch.columns().iter().enumerate() {
-                null_count[i] += array.null_count();
-            }
-        }
-    }
-
-    let column_statistics = Some(
-        null_count
-            .iter()
-            .map(|null_count| ColumnStatistics {
-                null_count: Some(*null_count),
-                distinct_count: None,
-                max_value: None,
-                min_value: None,
-            })
-            .collect(),
-    );
-
-    Statistics {
-        num_rows: Some(num_rows),
-        total_byte_size: None,
-        column_statistics,
-    }
+    batches: RwLock<Vec<Vec<RecordBatch>>>,
+    statistics: RwLock<Statistics>,
 }
 
 impl MemTable {
@@ -93,13 +53,13 @@ impl MemTable {
             .flatten()
             .all(|batches| schema.contains(&batches.schema()))
         {
-            let statistics = calculate_statistics(&schema, &partitions);
+            let statistics = common::compute_record_batch_statistics(&partitions, &schema, None);
             debug!("MemTable statistics: {:?}", statistics);
 
             Ok(Self {
                 schema,
-                batches: partitions,
-                statistics,
+                batches: RwLock::new(partitions),
+                statistics: RwLock::new(statistics),
             })
         } else {
             Err(DataFusionError::Plan(
@@ -161,6 +121,68 @@ impl MemTable {
         }
         MemTable::try_new(schema.clone(), data)
     }
+
+    /// Appends record batches to this table. The columns of the batches
+    /// must have the types of the table's columns, in the same order.
+    ///
+    /// The batches are distributed round robin across the existing
+    /// partitions of the table, so that appending doesn't change how many
+    /// partitions a scan of the table produces.
+    pub fn insert(&self, batches: Vec<RecordBatch>) -> Result<()> {
+        let batches = batches
+            .into_iter()
+            .filter(|batch| batch.num_rows() > 0)
+            .map(|batch| self.conform_batch(batch))
+            .collect::<Result<Vec<_>>>()?;
+        if batches.is_empty() {
+            return Ok(());
+        }
+
+        let mut partitions = self.batches.write().unwrap();
+        if partitions.is_empty() {
+            partitions.push(batches);
+        } else {
+            let partition_count = partitions.len();
+            for (i, batch) in batches.into_iter().enumerate() {
+                partitions[i % partition_count].push(batch);
+            }
+        }
+        *self.statistics.write().unwrap() =
+            common::compute_record_batch_statistics(&partitions, &self.schema, None);
+        Ok(())
+    }
+
+    /// Checks that a batch can be stored in this table and relabels its
+    /// columns with the table's schema
+    fn conform_batch(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        if batch.num_columns() != self.schema.fields().len() {
+            return Err(DataFusionError::Execution(format!(
+                "Cannot insert {} columns into a table with {} columns",
+                batch.num_columns(),
+                self.schema.fields().len()
+            )));
+        }
+        for (field, column) in self.schema.fields().iter().zip(batch.columns()) {
+            if field.data_type() != column.data_type() {
+                return Err(DataFusionError::Execution(format!(
+                    "Cannot insert values of type {:?} into column '{}' of type {:?}",
+                    column.data_type(),
+                    field.name(),
+                    field.data_type()
+                )));
+            }
+            if !field.is_nullable() && column.null_count() > 0 {
+                return Err(DataFusionError::Execution(format!(
+                    "Cannot insert NULL into non-nullable column '{}'",
+                    field.name()
+                )));
+            }
+        }
+        Ok(RecordBatch::try_new(
+            self.schema.clone(),
+            batch.columns().to_vec(),
+        )?)
+    }
 }
 
 impl TableProvider for MemTable {
@@ -206,21 +228,31 @@ impl TableProvider for MemTable {
 
         let projected_schema = Arc::new(Schema::new(projected_columns?));
 
+        let batches = self.batches.read().unwrap();
+        // a table that has never been inserted into still scans as a
+        // single, empty, partition
+        let empty = vec![vec![]];
+        let partitions = if batches.is_empty() {
+            &empty
+        } else {
+            &*batches
+        };
         Ok(Arc::new(MemoryExec::try_new(
-            &self.batches.clone(),
+            partitions,
             projected_schema,
             projection.clone(),
         )?))
     }
 
     fn statistics(&self) -> Statistics {
-        self.statistics.clone()
+        self.statistics.read().unwrap().clone()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datasource::datasource::ColumnStatistics;
     use arrow::array::Int32Array;
     use arrow::datatypes::{DataType, Field, Schema};
     use futures::StreamExt;
@@ -469,4 +501,57 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_insert() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, true),
+        ]));
+        let provider = MemTable::try_new(schema.clone(), vec![])?;
+
+        // an empty table scans as a single empty partition
+        let exec = provider.scan(&None, 1024, &[], None)?;
+        assert_eq!(1, exec.output_partitioning().partition_count());
+        assert_eq!(0, common::collect(exec.execute(0).await?).await?.len());
+
+        // batches with another (nullable) schema are relabelled
+        let nullable_schema = Arc::new(Schema::new(vec![
+            Field::new("x", DataType::Int32, true),
+            Field::new("y", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            nullable_schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(Int32Array::from(vec![Some(4), None, Some(6)])),
+            ],
+        )?;
+        provider.insert(vec![batch.clone(), batch])?;
+
+        assert_eq!(provider.statistics().num_rows, Some(6));
+        let exec = provider.scan(&None, 1024, &[], None)?;
+        assert_eq!(1, exec.output_partitioning().partition_count());
+        let batches = common::collect(exec.execute(0).await?).await?;
+        assert_eq!(2, batches.len());
+        assert_eq!(schema, batches[0].schema());
+
+        // NULLs can't be stored in a non-nullable column
+        let batch = RecordBatch::try_new(
+            nullable_schema,
+            vec![
+                Arc::new(Int32Array::from(vec![None])),
+                Arc::new(Int32Array::from(vec![Some(1)])),
+            ],
+        )?;
+        match provider.insert(vec![batch]) {
+            Err(DataFusionError::Execution(e)) => {
+                assert_eq!("Cannot insert NULL into non-nullable column 'a'", e)
+            }
+            _ => panic!("MemTable::insert should have failed due to a NULL"),
+        }
+        assert_eq!(provider.statistics().num_rows, Some(6));
+
+        Ok(())
+    }
 }
@@ -20,9 +20,16 @@
 pub mod csv;
 pub mod datasource;
 pub mod empty;
+pub mod json;
+pub mod listing;
 pub mod memory;
+pub mod object_store;
 pub mod parquet;
+pub mod view;
 
 pub use self::csv::{CsvFile, CsvReadOptions};
 pub use self::datasource::TableProvider;
+pub use self::json::{NdJsonFile, NdJsonReadOptions};
+pub use self::listing::{FileFormat, ListingOptions, ListingTable};
 pub use self::memory::MemTable;
+pub use self::view::ViewTable;
@@ -0,0 +1,92 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object store for the local file system
+
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{FileMeta, FileMetaStream, ObjectReader, ObjectStore};
+use crate::error::{DataFusionError, Result};
+
+/// Object store for the files of the local file system
+#[derive(Debug, Clone, Copy)]
+pub struct LocalFileSystem;
+
+#[async_trait]
+impl ObjectStore for LocalFileSystem {
+    async fn list(&self, prefix: &str) -> Result<FileMetaStream> {
+        let mut files = vec![];
+        list_all(prefix, &mut files)?;
+        Ok(Box::pin(futures::stream::iter(files.into_iter().map(Ok))))
+    }
+
+    async fn head(&self, path: &str) -> Result<FileMeta> {
+        let metadata = fs::metadata(path)?;
+        Ok(FileMeta {
+            path: path.to_owned(),
+            size: metadata.len(),
+        })
+    }
+
+    fn file_reader(&self, file: FileMeta) -> Result<Arc<dyn ObjectReader>> {
+        Ok(Arc::new(LocalFileReader { file }))
+    }
+}
+
+/// Recursively collect the files at `path`
+fn list_all(path: &str, files: &mut Vec<FileMeta>) -> Result<()> {
+    let metadata = fs::metadata(path)?;
+    if metadata.is_file() {
+        files.push(FileMeta {
+            path: path.to_owned(),
+            size: metadata.len(),
+        });
+    } else {
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            let path = path
+                .to_str()
+                .ok_or_else(|| DataFusionError::Plan("Invalid path".to_string()))?;
+            list_all(path, files)?;
+        }
+    }
+    Ok(())
+}
+
+struct LocalFileReader {
+    file: FileMeta,
+}
+
+impl ObjectReader for LocalFileReader {
+    fn length(&self) -> u64 {
+        self.file.size
+    }
+
+    fn sync_chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> Result<Box<dyn Read + Send + Sync>> {
+        let mut file = File::open(&self.file.path)?;
+        file.seek(SeekFrom::Start(start))?;
+        Ok(Box::new(BufReader::new(file.take(length as u64))))
+    }
+}
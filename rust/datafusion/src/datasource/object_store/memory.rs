@@ -0,0 +1,152 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object store keeping its files in memory, mainly useful for tests
+
+use std::collections::BTreeMap;
+use std::io::{self, Cursor, Read};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+
+use super::{FileMeta, FileMetaStream, ObjectReader, ObjectStore};
+use crate::error::{DataFusionError, Result};
+
+/// Object store keeping the content of its files in memory.
+///
+/// Paths use `/` as separator; listing a path returns the file at that path or
+/// all the files below it.
+#[derive(Debug, Default)]
+pub struct InMemoryObjectStore {
+    files: RwLock<BTreeMap<String, Arc<Vec<u8>>>>,
+}
+
+impl InMemoryObjectStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the file at `path`
+    pub fn put(&self, path: &str, content: Vec<u8>) {
+        let mut files = self.files.write().unwrap();
+        files.insert(path.to_owned(), Arc::new(content));
+    }
+
+    /// Remove the file at `path`, returning whether it existed
+    pub fn delete(&self, path: &str) -> bool {
+        let mut files = self.files.write().unwrap();
+        files.remove(path).is_some()
+    }
+
+    fn get(&self, path: &str) -> Result<Arc<Vec<u8>>> {
+        let files = self.files.read().unwrap();
+        files.get(path).cloned().ok_or_else(|| {
+            DataFusionError::IoError(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("File {} not found", path),
+            ))
+        })
+    }
+}
+
+#[async_trait]
+impl ObjectStore for InMemoryObjectStore {
+    async fn list(&self, prefix: &str) -> Result<FileMetaStream> {
+        let dir = format!("{}/", prefix.trim_end_matches('/'));
+        let files = self.files.read().unwrap();
+        let files: Vec<Result<FileMeta>> = files
+            .iter()
+            .filter(|(path, _)| path.as_str() == prefix || path.starts_with(&dir))
+            .map(|(path, content)| {
+                Ok(FileMeta {
+                    path: path.clone(),
+                    size: content.len() as u64,
+                })
+            })
+            .collect();
+        Ok(Box::pin(futures::stream::iter(files)))
+    }
+
+    async fn head(&self, path: &str) -> Result<FileMeta> {
+        Ok(FileMeta {
+            path: path.to_owned(),
+            size: self.get(path)?.len() as u64,
+        })
+    }
+
+    fn file_reader(&self, file: FileMeta) -> Result<Arc<dyn ObjectReader>> {
+        Ok(Arc::new(InMemoryFileReader {
+            content: self.get(&file.path)?,
+        }))
+    }
+}
+
+struct InMemoryFileReader {
+    content: Arc<Vec<u8>>,
+}
+
+impl ObjectReader for InMemoryFileReader {
+    fn length(&self) -> u64 {
+        self.content.len() as u64
+    }
+
+    fn sync_chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> Result<Box<dyn Read + Send + Sync>> {
+        let start = (start as usize).min(self.content.len());
+        let end = start.saturating_add(length).min(self.content.len());
+        Ok(Box::new(Cursor::new(self.content[start..end].to_vec())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn list_and_read() -> Result<()> {
+        let store = InMemoryObjectStore::new();
+        store.put("data/a.csv", b"a\n1\n".to_vec());
+        store.put("data/sub/b.csv", b"a\n2\n".to_vec());
+        store.put("data2/c.csv", b"a\n3\n".to_vec());
+
+        let files: Vec<FileMeta> = store
+            .list("data")
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_>>()?;
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(vec!["data/a.csv", "data/sub/b.csv"], paths);
+
+        let mut content = String::new();
+        store
+            .file_reader(files[1].clone())?
+            .sync_chunk_reader(2, 10)?
+            .read_to_string(&mut content)?;
+        assert_eq!("2\n", content);
+
+        assert!(store.delete("data/a.csv"));
+        assert!(store.head("data/a.csv").await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object store abstraction used by the file based data sources
+//!
+//! The CSV, JSON and Parquet scans read their files through an [`ObjectStore`],
+//! so that they can be used with storage systems other than the local file
+//! system. Stores are registered in an [`ObjectStoreRegistry`] by URL scheme,
+//! and paths such as `s3://bucket/path` are resolved to the store registered for
+//! `s3`. Paths without a scheme use the local file system.
+
+pub mod local;
+pub mod memory;
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::io::Read;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+
+use crate::error::{DataFusionError, Result};
+use local::LocalFileSystem;
+
+/// The URL scheme of the local file system
+pub const LOCAL_SCHEME: &str = "file";
+
+/// Size of the chunks produced by the default [`ObjectReader::chunk_stream`]
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Metadata of a file stored in an [`ObjectStore`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileMeta {
+    /// Path of the file within the store, without the URL scheme
+    pub path: String,
+    /// Size of the file in bytes
+    pub size: u64,
+}
+
+/// Stream of file metadata returned by [`ObjectStore::list`]
+pub type FileMetaStream = Pin<Box<dyn Stream<Item = Result<FileMeta>> + Send + Sync>>;
+
+/// Stream of chunks of the content of a file
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Vec<u8>>> + Send + Sync>>;
+
+/// Reads the content of a single file of an [`ObjectStore`]
+#[async_trait]
+pub trait ObjectReader: Send + Sync {
+    /// Size of the file in bytes
+    fn length(&self) -> u64;
+
+    /// Get a blocking reader over `length` bytes of the file starting at `start`.
+    ///
+    /// The Arrow and Parquet readers are synchronous, so the scans use this from
+    /// a blocking context.
+    fn sync_chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> Result<Box<dyn Read + Send + Sync>>;
+
+    /// Get a blocking reader over the whole file
+    fn sync_reader(&self) -> Result<Box<dyn Read + Send + Sync>> {
+        self.sync_chunk_reader(0, self.length() as usize)
+    }
+
+    /// Get a stream over `length` bytes of the file starting at `start`.
+    ///
+    /// The default implementation reads chunks from [`Self::sync_chunk_reader`];
+    /// stores with an asynchronous client should override it.
+    async fn chunk_stream(&self, start: u64, length: usize) -> Result<ByteStream> {
+        let reader = self.sync_chunk_reader(start, length)?;
+        Ok(Box::pin(futures::stream::iter(ChunkIterator { reader })))
+    }
+}
+
+/// A storage system for the files read by the file based data sources
+#[async_trait]
+pub trait ObjectStore: Debug + Send + Sync {
+    /// Returns the metadata of the file at `prefix`, or of all the files below
+    /// `prefix` if it is a directory
+    async fn list(&self, prefix: &str) -> Result<FileMetaStream>;
+
+    /// Returns the metadata of the file at `path`
+    async fn head(&self, path: &str) -> Result<FileMeta>;
+
+    /// Returns a reader for the content of `file`
+    fn file_reader(&self, file: FileMeta) -> Result<Arc<dyn ObjectReader>>;
+}
+
+/// Lists the files at `path` that have the extension `file_extension`, ordered
+/// by path.
+///
+/// Plans are created synchronously, so this blocks on the listing of the store.
+pub fn list_files(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &str,
+    file_extension: &str,
+) -> Result<Vec<String>> {
+    let object_store = object_store.clone();
+    let path = path.to_owned();
+    let file_extension = file_extension.to_owned();
+    let mut filenames = block_on(async move {
+        let file_extension = file_extension.as_str();
+        object_store
+            .list(&path)
+            .await?
+            .filter_map(|file| async move {
+                match file {
+                    Ok(file) if !file.path.ends_with(file_extension) => None,
+                    file => Some(file.map(|f| f.path)),
+                }
+            })
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+    })?;
+    filenames.sort();
+    Ok(filenames)
+}
+
+/// Returns the metadata of the file at `path`, blocking on the store
+pub fn head_file(object_store: &Arc<dyn ObjectStore>, path: &str) -> Result<FileMeta> {
+    let object_store = object_store.clone();
+    let path = path.to_owned();
+    block_on(async move { object_store.head(&path).await })
+}
+
+/// Opens a blocking reader over the whole file at `path`
+pub fn open_file(
+    object_store: &Arc<dyn ObjectStore>,
+    path: &str,
+) -> Result<Box<dyn Read + Send + Sync>> {
+    let file = head_file(object_store, path)?;
+    object_store.file_reader(file)?.sync_reader()
+}
+
+/// Runs a future of an object store to completion from synchronous code, such as
+/// the creation of a plan.
+///
+/// Within a tokio runtime, the future runs on a blocking thread of that runtime, so
+/// that the stores whose client is tied to the runtime can make progress while the
+/// calling thread waits. Outside of a runtime, it runs on the calling thread.
+fn block_on<T, F>(future: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T>> + Send + 'static,
+{
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            let request = handle.spawn_blocking(move || {
+                tokio::runtime::Handle::current().block_on(future)
+            });
+            futures::executor::block_on(request).map_err(|e| {
+                DataFusionError::Execution(format!(
+                    "The request to the object store failed: {}",
+                    e
+                ))
+            })?
+        }
+        Err(_) => futures::executor::block_on(future),
+    }
+}
+
+/// Reads a blocking reader in chunks of [`CHUNK_SIZE`] bytes
+struct ChunkIterator {
+    reader: Box<dyn Read + Send + Sync>,
+}
+
+impl Iterator for ChunkIterator {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+        match (&mut self.reader)
+            .take(CHUNK_SIZE as u64)
+            .read_to_end(&mut chunk)
+        {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(chunk)),
+            Err(e) => Some(Err(DataFusionError::IoError(e))),
+        }
+    }
+}
+
+/// A registry of [`ObjectStore`]s keyed on URL scheme.
+///
+/// The local file system is registered for the `file` scheme by default.
+pub struct ObjectStoreRegistry {
+    /// The object stores, by URL scheme
+    pub object_stores: RwLock<HashMap<String, Arc<dyn ObjectStore>>>,
+}
+
+impl ObjectStoreRegistry {
+    /// Create a registry containing the local file system
+    pub fn new() -> Self {
+        let mut object_stores: HashMap<String, Arc<dyn ObjectStore>> = HashMap::new();
+        object_stores.insert(LOCAL_SCHEME.to_string(), Arc::new(LocalFileSystem));
+        Self {
+            object_stores: RwLock::new(object_stores),
+        }
+    }
+
+    /// Register a store for the given URL scheme, returning the store previously
+    /// registered for that scheme, if any
+    pub fn register_store(
+        &self,
+        scheme: String,
+        store: Arc<dyn ObjectStore>,
+    ) -> Option<Arc<dyn ObjectStore>> {
+        let mut stores = self.object_stores.write().unwrap();
+        stores.insert(scheme, store)
+    }
+
+    /// Get the store registered for the given URL scheme
+    pub fn get(&self, scheme: &str) -> Option<Arc<dyn ObjectStore>> {
+        let stores = self.object_stores.read().unwrap();
+        stores.get(scheme).cloned()
+    }
+
+    /// Resolve `uri` to the store registered for its scheme and the path of the
+    /// file within that store. URIs without a scheme refer to the local file
+    /// system.
+    pub fn get_by_uri<'a>(
+        &self,
+        uri: &'a str,
+    ) -> Result<(Arc<dyn ObjectStore>, &'a str)> {
+        let (scheme, path) = uri.split_once("://").unwrap_or((LOCAL_SCHEME, uri));
+        let store = self.get(scheme).ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "No object store is registered for scheme '{}' of {}",
+                scheme, uri
+            ))
+        })?;
+        Ok((store, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::memory::InMemoryObjectStore;
+    use super::*;
+
+    #[test]
+    fn registry_resolves_uris() -> Result<()> {
+        let registry = ObjectStoreRegistry::new();
+        let (_, path) = registry.get_by_uri("/tmp/data.csv")?;
+        assert_eq!("/tmp/data.csv", path);
+        let (_, path) = registry.get_by_uri("file:///tmp/data.csv")?;
+        assert_eq!("/tmp/data.csv", path);
+
+        let err = registry.get_by_uri("mem://bucket/data.csv").err().unwrap();
+        assert!(err.to_string().contains("No object store is registered"));
+
+        let store = Arc::new(InMemoryObjectStore::new());
+        store.put("bucket/data.csv", b"a\n1\n".to_vec());
+        assert!(registry.register_store("mem".to_string(), store).is_none());
+        let (store, path) = registry.get_by_uri("mem://bucket/data.csv")?;
+        assert_eq!("bucket/data.csv", path);
+        assert_eq!(vec!["bucket/data.csv"], list_files(&store, path, ".csv")?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_files_within_runtime() -> Result<()> {
+        let store = InMemoryObjectStore::new();
+        store.put("a/2.csv", b"a\n".to_vec());
+        store.put("a/1.csv", vec![]);
+        store.put("a/1.json", vec![]);
+        let store: Arc<dyn ObjectStore> = Arc::new(store);
+
+        assert_eq!(vec!["a/1.csv", "a/2.csv"], list_files(&store, "a", ".csv")?);
+        assert_eq!(2, head_file(&store, "a/2.csv")?.size);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn chunk_stream() -> Result<()> {
+        let store = InMemoryObjectStore::new();
+        let content: Vec<u8> = (0..CHUNK_SIZE + 10).map(|i| i as u8).collect();
+        store.put("data.bin", content.clone());
+
+        let file = store.head("data.bin").await?;
+        assert_eq!(content.len() as u64, file.size);
+        let reader = store.file_reader(file)?;
+        let chunks: Vec<Vec<u8>> = reader
+            .chunk_stream(5, CHUNK_SIZE + 1)
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_>>()?;
+        assert_eq!(2, chunks.len());
+        assert_eq!(CHUNK_SIZE, chunks[0].len());
+        assert_eq!(chunks.concat(), content[5..CHUNK_SIZE + 6].to_vec());
+        Ok(())
+    }
+}
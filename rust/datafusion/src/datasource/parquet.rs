@@ -24,6 +24,8 @@ use std::sync::Arc;
 use arrow::datatypes::*;
 
 use crate::datasource::datasource::Statistics;
+use crate::datasource::object_store::local::LocalFileSystem;
+use crate::datasource::object_store::ObjectStore;
 use crate::datasource::TableProvider;
 use crate::error::Result;
 use crate::logical_plan::{combine_filters, Expr};
@@ -34,6 +36,7 @@ use super::datasource::TableProviderFilterPushDown;
 
 /// Table-based representation of a `ParquetFile`.
 pub struct ParquetTable {
+    object_store: Arc<dyn ObjectStore>,
     path: String,
     schema: SchemaRef,
     statistics: Statistics,
@@ -43,12 +46,30 @@ pub struct ParquetTable {
 impl ParquetTable {
     /// Attempt to initialize a new `ParquetTable` from a file path.
     pub fn try_new(path: &str, max_concurrency: usize) -> Result<Self> {
-        let parquet_exec = ParquetExec::try_from_path(path, None, None, 0, 1, None)?;
+        Self::try_new_with_object_store(Arc::new(LocalFileSystem), path, max_concurrency)
+    }
+
+    /// Attempt to initialize a new `ParquetTable` from a path in the given object store.
+    pub fn try_new_with_object_store(
+        object_store: Arc<dyn ObjectStore>,
+        path: &str,
+        max_concurrency: usize,
+    ) -> Result<Self> {
+        let parquet_exec = ParquetExec::try_from_path_with_object_store(
+            object_store.clone(),
+            path,
+            None,
+            None,
+            0,
+            1,
+            None,
+        )?;
         let schema = parquet_exec.schema();
         Ok(Self {
+            object_store,
             path: path.to_string(),
             schema,
-            statistics: parquet_exec.statistics().to_owned(),
+            statistics: parquet_exec.statistics(),
             max_concurrency,
         })
     }
@@ -86,7 +107,8 @@ impl TableProvider for ParquetTable {
         limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let predicate = combine_filters(filters);
-        Ok(Arc::new(ParquetExec::try_from_path(
+        Ok(Arc::new(ParquetExec::try_from_path_with_object_store(
+            self.object_store.clone(),
             &self.path,
             projection.clone(),
             predicate,
@@ -106,12 +128,14 @@ impl TableProvider for ParquetTable {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datasource::object_store::memory::InMemoryObjectStore;
     use arrow::array::{
         BinaryArray, BooleanArray, Float32Array, Float64Array, Int32Array,
         TimestampNanosecondArray,
     };
     use arrow::record_batch::RecordBatch;
     use futures::StreamExt;
+    use parquet::arrow::ArrowWriter;
 
     #[tokio::test]
     async fn read_small_batches() -> Result<()> {
@@ -327,6 +351,36 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn read_from_object_store() -> Result<()> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("c1", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("1.parquet");
+        let mut writer =
+            ArrowWriter::try_new(std::fs::File::create(&path)?, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let store = Arc::new(InMemoryObjectStore::new());
+        store.put("data/1.parquet", std::fs::read(&path)?);
+        let table = ParquetTable::try_new_with_object_store(store, "data", 1)?;
+        assert_eq!(table.statistics().num_rows, Some(3));
+
+        let batch = get_first_batch(Arc::new(table), &None).await?;
+        let array = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(&[1, 2, 3], array.values());
+        Ok(())
+    }
+
     fn load_table(name: &str) -> Result<Arc<dyn TableProvider>> {
         let testdata = arrow::util::test_util::parquet_test_data();
         let filename = format!("{}/{}", testdata, name);
@@ -0,0 +1,163 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! View data source which uses a LogicalPlan as its input.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+
+use crate::catalog::catalog::MemoryCatalogList;
+use crate::datasource::datasource::Statistics;
+use crate::datasource::object_store::ObjectStoreRegistry;
+use crate::datasource::TableProvider;
+use crate::error::Result;
+use crate::execution::context::{ExecutionConfig, ExecutionContextState};
+use crate::logical_plan::{col, Expr, LogicalPlan, LogicalPlanBuilder};
+use crate::physical_plan::planner::DefaultPhysicalPlanner;
+use crate::physical_plan::{ExecutionPlan, PhysicalPlanner};
+
+/// A view, whose rows are produced by running the logical plan of its
+/// defining query each time it is scanned.
+///
+/// The SQL planner inlines the plan of a view into the queries that
+/// reference it, so that the view is optimized as part of each query.
+pub struct ViewTable {
+    /// The logical plan of the query that defines the view
+    logical_plan: LogicalPlan,
+    /// The schema of the view
+    schema: SchemaRef,
+}
+
+impl ViewTable {
+    /// Create a new view from the logical plan of its defining query
+    pub fn new(logical_plan: LogicalPlan) -> Self {
+        let schema = Arc::new(logical_plan.schema().as_ref().to_owned().into());
+        Self {
+            logical_plan,
+            schema,
+        }
+    }
+
+    /// The logical plan of the query that defines the view
+    pub fn logical_plan(&self) -> &LogicalPlan {
+        &self.logical_plan
+    }
+
+    /// The logical plan of a scan of the view, with the given projection and
+    /// limit applied to its defining query
+    pub fn scan_plan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        limit: Option<usize>,
+    ) -> Result<LogicalPlan> {
+        let mut builder = LogicalPlanBuilder::from(&self.logical_plan);
+        if let Some(projection) = projection {
+            let fields = self.schema.fields();
+            builder =
+                builder.project(projection.iter().map(|i| col(fields[*i].name())))?;
+        }
+        if let Some(limit) = limit {
+            builder = builder.limit(limit)?;
+        }
+        builder.build()
+    }
+}
+
+impl TableProvider for ViewTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Plans the view without the state of a context. The physical planner
+    /// instead plans the views of a query with the state of the context that
+    /// runs the query, see [`ViewTable::scan_plan`].
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+        _filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let plan = self.scan_plan(projection, limit)?;
+        let ctx_state = ExecutionContextState {
+            catalog_list: Arc::new(MemoryCatalogList::new()),
+            scalar_functions: HashMap::new(),
+            var_provider: HashMap::new(),
+            aggregate_functions: HashMap::new(),
+            config: ExecutionConfig::new().with_batch_size(batch_size),
+            object_store_registry: Arc::new(ObjectStoreRegistry::new()),
+        };
+        DefaultPhysicalPlanner::default().create_physical_plan(&plan, &ctx_state)
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::MemTable;
+    use crate::physical_plan::collect;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+
+    #[tokio::test]
+    async fn scan_view_with_projection_and_limit() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(Int32Array::from(vec![4, 5, 6])),
+            ],
+        )?;
+        let table = MemTable::try_new(schema, vec![vec![batch]])?;
+        let plan = LogicalPlanBuilder::scan("t", Arc::new(table), None)?
+            .filter(col("a").gt(crate::logical_plan::lit(1)))?
+            .build()?;
+
+        let view = ViewTable::new(plan);
+        assert_eq!(2, view.schema().fields().len());
+
+        let exec = view.scan(&Some(vec![1]), 1024, &[], Some(1))?;
+        let batches = collect(exec).await?;
+        assert_eq!(1, batches.len());
+        assert_eq!(1, batches[0].num_columns());
+        assert_eq!(1, batches[0].num_rows());
+        let b = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(5, b.value(0));
+
+        Ok(())
+    }
+}
@@ -22,11 +22,10 @@ use crate::{
         information_schema::CatalogWithInformationSchema,
     },
     optimizer::hash_build_probe_order::HashBuildProbeOrder,
+    optimizer::join_reorder::JoinReorder,
     physical_optimizer::optimizer::PhysicalOptimizerRule,
 };
 use log::debug;
-use std::fs;
-use std::path::Path;
 use std::string::String;
 use std::sync::Arc;
 use std::{
@@ -34,10 +33,7 @@ use std::{
     sync::Mutex,
 };
 
-use futures::{StreamExt, TryStreamExt};
-use tokio::task::{self, JoinHandle};
-
-use arrow::csv;
+use arrow::datatypes::Schema;
 
 use crate::catalog::{
     catalog::{CatalogProvider, MemoryCatalogProvider},
@@ -45,34 +41,40 @@ use crate::catalog::{
     ResolvedTableReference, TableReference,
 };
 use crate::datasource::csv::CsvFile;
+use crate::datasource::json::{NdJsonFile, NdJsonReadOptions};
+use crate::datasource::listing::FileFormat;
+use crate::datasource::object_store::{ObjectStore, ObjectStoreRegistry};
 use crate::datasource::parquet::ParquetTable;
-use crate::datasource::TableProvider;
+use crate::datasource::{MemTable, TableProvider, ViewTable};
 use crate::error::{DataFusionError, Result};
 use crate::execution::dataframe_impl::DataFrameImpl;
 use crate::logical_plan::{
     FunctionRegistry, LogicalPlan, LogicalPlanBuilder, ToDFSchema,
 };
+use crate::optimizer::common_subexpr_eliminate::CommonSubexprEliminate;
 use crate::optimizer::constant_folding::ConstantFolding;
 use crate::optimizer::filter_push_down::FilterPushDown;
 use crate::optimizer::limit_push_down::LimitPushDown;
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::projection_push_down::ProjectionPushDown;
+use crate::optimizer::simplify_expressions::SimplifyExpressions;
+use crate::optimizer::subquery_decorrelation::SubqueryDecorrelation;
 use crate::physical_optimizer::coalesce_batches::CoalesceBatches;
 use crate::physical_optimizer::merge_exec::AddMergeExec;
 use crate::physical_optimizer::repartition::Repartition;
 
 use crate::physical_plan::csv::CsvReadOptions;
+use crate::physical_plan::file_sink::{FileSinkExec, FileWriteOptions};
 use crate::physical_plan::planner::DefaultPhysicalPlanner;
 use crate::physical_plan::udf::ScalarUDF;
-use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::PhysicalPlanner;
+use crate::physical_plan::{collect, ExecutionPlan};
 use crate::sql::{
     parser::{DFParser, FileType},
     planner::{ContextProvider, SqlToRel},
 };
 use crate::variable::{VarProvider, VarType};
 use crate::{dataframe::DataFrame, physical_plan::udaf::AggregateUDF};
-use parquet::arrow::ArrowWriter;
 use parquet::file::properties::WriterProperties;
 
 /// ExecutionContext is the main interface for executing queries with DataFusion. The context
@@ -157,11 +159,19 @@ impl ExecutionContext {
                 var_provider: HashMap::new(),
                 aggregate_functions: HashMap::new(),
                 config,
+                object_store_registry: Arc::new(ObjectStoreRegistry::new()),
             })),
         }
     }
 
     /// Creates a dataframe that will execute a SQL query.
+    ///
+    /// Statements that create tables or views (`CREATE EXTERNAL TABLE`,
+    /// `CREATE TABLE` and `CREATE VIEW`) register them with this context
+    /// immediately. The rows of `INSERT INTO` and `CREATE TABLE ... AS
+    /// SELECT` are only inserted when the returned dataframe is executed,
+    /// and the table created by `CREATE TABLE ... AS SELECT` is registered
+    /// once its rows are inserted.
     pub fn sql(&mut self, sql: &str) -> Result<Arc<dyn DataFrame>> {
         let plan = self.create_logical_plan(sql)?;
         match plan {
@@ -188,12 +198,43 @@ impl ExecutionContext {
                     let plan = LogicalPlanBuilder::empty(false).build()?;
                     Ok(Arc::new(DataFrameImpl::new(self.state.clone(), &plan)))
                 }
-                _ => Err(DataFusionError::NotImplemented(format!(
-                    "Unsupported file type {:?}.",
-                    file_type
+                FileType::NdJson => {
+                    // without column definitions the schema is inferred from the data
+                    let schema: Schema = schema.as_ref().to_owned().into();
+                    let mut options = NdJsonReadOptions::new();
+                    if !schema.fields().is_empty() {
+                        options = options.schema(&schema);
+                    }
+                    self.register_json(name, location, options)?;
+                    let plan = LogicalPlanBuilder::empty(false).build()?;
+                    Ok(Arc::new(DataFrameImpl::new(self.state.clone(), &plan)))
+                }
+            },
+
+            LogicalPlan::CreateMemoryTable { name, input } => match input.as_ref() {
+                // column definitions declare the nullability of the columns
+                LogicalPlan::EmptyRelation { schema, .. } => {
+                    let schema: Schema = schema.as_ref().to_owned().into();
+                    let table = MemTable::try_new(Arc::new(schema), vec![])?;
+                    self.register_table(name.as_str(), Arc::new(table))?;
+                    let plan = LogicalPlanBuilder::empty(false).build()?;
+                    Ok(Arc::new(DataFrameImpl::new(self.state.clone(), &plan)))
+                }
+                // the table created by a query is registered by the physical plan,
+                // once the rows of the query are inserted
+                _ => Ok(Arc::new(DataFrameImpl::new(
+                    self.state.clone(),
+                    &self.optimize(&LogicalPlan::CreateMemoryTable { name, input })?,
                 ))),
             },
 
+            LogicalPlan::CreateView { name, input } => {
+                let view = ViewTable::new(input.as_ref().clone());
+                self.register_table(name.as_str(), Arc::new(view))?;
+                let plan = LogicalPlanBuilder::empty(false).build()?;
+                Ok(Arc::new(DataFrameImpl::new(self.state.clone(), &plan)))
+            }
+
             plan => Ok(Arc::new(DataFrameImpl::new(
                 self.state.clone(),
                 &self.optimize(&plan)?,
@@ -268,23 +309,32 @@ impl ExecutionContext {
         filename: &str,
         options: CsvReadOptions,
     ) -> Result<Arc<dyn DataFrame>> {
-        Ok(Arc::new(DataFrameImpl::new(
-            self.state.clone(),
-            &LogicalPlanBuilder::scan_csv(&filename, options, None)?.build()?,
-        )))
+        let (object_store, path) = self.object_store(filename)?;
+        let provider = CsvFile::try_new_with_object_store(object_store, path, options)?;
+        self.read_table(Arc::new(provider))
+    }
+
+    /// Creates a DataFrame for reading a line-delimited JSON data source.
+    pub fn read_json(
+        &mut self,
+        filename: &str,
+        options: NdJsonReadOptions,
+    ) -> Result<Arc<dyn DataFrame>> {
+        let (object_store, path) = self.object_store(filename)?;
+        let provider =
+            NdJsonFile::try_new_with_object_store(object_store, path, options)?;
+        self.read_table(Arc::new(provider))
     }
 
     /// Creates a DataFrame for reading a Parquet data source.
     pub fn read_parquet(&mut self, filename: &str) -> Result<Arc<dyn DataFrame>> {
-        Ok(Arc::new(DataFrameImpl::new(
-            self.state.clone(),
-            &LogicalPlanBuilder::scan_parquet(
-                &filename,
-                None,
-                self.state.lock().unwrap().config.concurrency,
-            )?
-            .build()?,
-        )))
+        let (object_store, path) = self.object_store(filename)?;
+        let provider = ParquetTable::try_new_with_object_store(
+            object_store,
+            path,
+            self.state.lock().unwrap().config.concurrency,
+        )?;
+        self.read_table(Arc::new(provider))
     }
 
     /// Creates a DataFrame for reading a custom TableProvider.
@@ -315,21 +365,68 @@ impl ExecutionContext {
         filename: &str,
         options: CsvReadOptions,
     ) -> Result<()> {
-        self.register_table(name, Arc::new(CsvFile::try_new(filename, options)?))?;
+        let (object_store, path) = self.object_store(filename)?;
+        let table = CsvFile::try_new_with_object_store(object_store, path, options)?;
+        self.register_table(name, Arc::new(table))?;
+        Ok(())
+    }
+
+    /// Registers a line-delimited JSON data source so that it can be referenced from
+    /// SQL statements executed against this context.
+    pub fn register_json(
+        &mut self,
+        name: &str,
+        filename: &str,
+        options: NdJsonReadOptions,
+    ) -> Result<()> {
+        let (object_store, path) = self.object_store(filename)?;
+        let table = NdJsonFile::try_new_with_object_store(object_store, path, options)?;
+        self.register_table(name, Arc::new(table))?;
         Ok(())
     }
 
     /// Registers a Parquet data source so that it can be referenced from SQL statements
     /// executed against this context.
     pub fn register_parquet(&mut self, name: &str, filename: &str) -> Result<()> {
-        let table = ParquetTable::try_new(
-            &filename,
+        let (object_store, path) = self.object_store(filename)?;
+        let table = ParquetTable::try_new_with_object_store(
+            object_store,
+            path,
             self.state.lock().unwrap().config.concurrency,
         )?;
         self.register_table(name, Arc::new(table))?;
         Ok(())
     }
 
+    /// Registers an object store for the given URL scheme, so that paths such as
+    /// `scheme://path` given to this context are read from it.
+    ///
+    /// Returns the store previously registered for the scheme, if any.
+    pub fn register_object_store(
+        &self,
+        scheme: impl Into<String>,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Option<Arc<dyn ObjectStore>> {
+        self.state
+            .lock()
+            .unwrap()
+            .object_store_registry
+            .register_store(scheme.into(), object_store)
+    }
+
+    /// Resolves `uri` to the object store registered for its scheme and the path
+    /// within that store
+    pub fn object_store<'a>(
+        &self,
+        uri: &'a str,
+    ) -> Result<(Arc<dyn ObjectStore>, &'a str)> {
+        self.state
+            .lock()
+            .unwrap()
+            .object_store_registry
+            .get_by_uri(uri)
+    }
+
     /// Registers a named catalog using a custom `CatalogProvider` so that
     /// it can be referenced from SQL statements executed against this
     /// context.
@@ -481,35 +578,11 @@ impl ExecutionContext {
         plan: Arc<dyn ExecutionPlan>,
         path: String,
     ) -> Result<()> {
-        // create directory to contain the CSV files (one per partition)
-        let fs_path = Path::new(&path);
-        match fs::create_dir(fs_path) {
-            Ok(()) => {
-                let mut tasks = vec![];
-                for i in 0..plan.output_partitioning().partition_count() {
-                    let plan = plan.clone();
-                    let filename = format!("part-{}.csv", i);
-                    let path = fs_path.join(&filename);
-                    let file = fs::File::create(path)?;
-                    let mut writer = csv::Writer::new(file);
-                    let stream = plan.execute(i).await?;
-                    let handle: JoinHandle<Result<()>> = task::spawn(async move {
-                        stream
-                            .map(|batch| writer.write(&batch?))
-                            .try_collect()
-                            .await
-                            .map_err(DataFusionError::from)
-                    });
-                    tasks.push(handle);
-                }
-                futures::future::join_all(tasks).await;
-                Ok(())
-            }
-            Err(e) => Err(DataFusionError::Execution(format!(
-                "Could not create directory {}: {:?}",
-                path, e
-            ))),
-        }
+        let format = FileFormat::Csv {
+            has_header: true,
+            delimiter: b',',
+        };
+        write_files(plan, path, FileWriteOptions::new(format)).await
     }
 
     /// Executes a query and writes the results to a partitioned Parquet file.
@@ -519,43 +592,33 @@ impl ExecutionContext {
         path: String,
         writer_properties: Option<WriterProperties>,
     ) -> Result<()> {
-        // create directory to contain the Parquet files (one per partition)
-        let fs_path = Path::new(&path);
-        match fs::create_dir(fs_path) {
-            Ok(()) => {
-                let mut tasks = vec![];
-                for i in 0..plan.output_partitioning().partition_count() {
-                    let plan = plan.clone();
-                    let filename = format!("part-{}.parquet", i);
-                    let path = fs_path.join(&filename);
-                    let file = fs::File::create(path)?;
-                    let mut writer = ArrowWriter::try_new(
-                        file.try_clone().unwrap(),
-                        plan.schema(),
-                        writer_properties.clone(),
-                    )?;
-                    let stream = plan.execute(i).await?;
-                    let handle: JoinHandle<Result<()>> = task::spawn(async move {
-                        stream
-                            .map(|batch| writer.write(&batch?))
-                            .try_collect()
-                            .await
-                            .map_err(DataFusionError::from)?;
-                        writer.close().map_err(DataFusionError::from).map(|_| ())
-                    });
-                    tasks.push(handle);
-                }
-                futures::future::join_all(tasks).await;
-                Ok(())
-            }
-            Err(e) => Err(DataFusionError::Execution(format!(
-                "Could not create directory {}: {:?}",
-                path, e
-            ))),
-        }
+        let mut options = FileWriteOptions::new(FileFormat::Parquet);
+        options.writer_properties = writer_properties;
+        write_files(plan, path, options).await
+    }
+
+    /// Executes a query and writes the results to a partitioned
+    /// newline-delimited JSON file.
+    pub async fn write_json(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        path: String,
+    ) -> Result<()> {
+        write_files(plan, path, FileWriteOptions::new(FileFormat::NdJson)).await
     }
 }
 
+/// Executes a query and writes each partition of the results to a file of a
+/// new directory
+pub(crate) async fn write_files(
+    plan: Arc<dyn ExecutionPlan>,
+    path: String,
+    options: FileWriteOptions,
+) -> Result<()> {
+    collect(Arc::new(FileSinkExec::new(plan, path, options))).await?;
+    Ok(())
+}
+
 impl From<Arc<Mutex<ExecutionContextState>>> for ExecutionContext {
     fn from(state: Arc<Mutex<ExecutionContextState>>) -> Self {
         ExecutionContext { state }
@@ -626,6 +689,16 @@ pub struct ExecutionConfig {
     /// Should DataFusion repartition data using the join keys to execute joins in parallel
     /// using the provided `concurrency` level
     pub repartition_joins: bool,
+    /// Number of bytes of memory that each partition of a sort, or all the partitions of
+    /// an aggregation, may use before they spill to disk, or `None` for no limit
+    pub memory_limit: Option<usize>,
+    /// Number of rows of the build side of a join above which the join is executed as a
+    /// sort-merge join of both sides, or `None` to only use sort-merge joins when both
+    /// sides are already sorted on the join keys
+    pub sort_merge_join_rows: Option<usize>,
+    /// Maximum number of rows of a limit of a sort that is executed by keeping only the
+    /// first rows of each partition, instead of sorting all of them
+    pub top_k_rows: usize,
 }
 
 impl ExecutionConfig {
@@ -635,11 +708,15 @@ impl ExecutionConfig {
             concurrency: num_cpus::get(),
             batch_size: 8192,
             optimizers: vec![
+                Arc::new(SubqueryDecorrelation::new()),
                 Arc::new(ConstantFolding::new()),
+                Arc::new(SimplifyExpressions::new()),
                 Arc::new(ProjectionPushDown::new()),
                 Arc::new(FilterPushDown::new()),
+                Arc::new(JoinReorder::new()),
                 Arc::new(HashBuildProbeOrder::new()),
                 Arc::new(LimitPushDown::new()),
+                Arc::new(CommonSubexprEliminate::new()),
             ],
             physical_optimizers: vec![
                 Arc::new(Repartition::new()),
@@ -652,6 +729,9 @@ impl ExecutionConfig {
             create_default_catalog_and_schema: true,
             information_schema: false,
             repartition_joins: true,
+            memory_limit: None,
+            sort_merge_join_rows: None,
+            top_k_rows: 10_000,
         }
     }
 
@@ -726,6 +806,26 @@ impl ExecutionConfig {
         self.repartition_joins = enabled;
         self
     }
+
+    /// Customize the memory limit of sorts and aggregations, above which they spill to disk
+    pub fn with_memory_limit(mut self, n: usize) -> Self {
+        self.memory_limit = Some(n);
+        self
+    }
+
+    /// Customize the number of rows of the build side of a join above which it is
+    /// executed as a sort-merge join
+    pub fn with_sort_merge_join_rows(mut self, n: usize) -> Self {
+        self.sort_merge_join_rows = Some(n);
+        self
+    }
+
+    /// Customize the maximum number of rows of a limit of a sort that is executed
+    /// without sorting all of the rows
+    pub fn with_top_k_rows(mut self, n: usize) -> Self {
+        self.top_k_rows = n;
+        self
+    }
 }
 
 /// Execution context for registering data sources and executing queries
@@ -741,6 +841,8 @@ pub struct ExecutionContextState {
     pub aggregate_functions: HashMap<String, Arc<AggregateUDF>>,
     /// Context configuration
     pub config: ExecutionConfig,
+    /// Object stores used to read files, by URL scheme
+    pub object_store_registry: Arc<ObjectStoreRegistry>,
 }
 
 impl ExecutionContextState {
@@ -753,7 +855,7 @@ impl ExecutionContextState {
             .resolve(&self.config.default_catalog, &self.config.default_schema)
     }
 
-    fn schema_for_ref<'a>(
+    pub(crate) fn schema_for_ref<'a>(
         &'a self,
         table_ref: impl Into<TableReference<'a>>,
     ) -> Result<Arc<dyn SchemaProvider>> {
@@ -825,6 +927,7 @@ impl FunctionRegistry for ExecutionContextState {
 mod tests {
 
     use super::*;
+    use crate::datasource::object_store::memory::InMemoryObjectStore;
     use crate::physical_plan::functions::make_scalar_function;
     use crate::physical_plan::{collect, collect_partitioned};
     use crate::test;
@@ -840,7 +943,7 @@ mod tests {
     use arrow::array::{
         Array, ArrayRef, BinaryArray, DictionaryArray, Float64Array, Int32Array,
         Int64Array, LargeBinaryArray, LargeStringArray, StringArray,
-        TimestampNanosecondArray,
+        TimestampNanosecondArray, UInt32Array,
     };
     use arrow::compute::add;
     use arrow::datatypes::*;
@@ -1200,6 +1303,249 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn window_ranking() -> Result<()> {
+        let results = execute(
+            "SELECT c1, c2, \
+            ROW_NUMBER() OVER (PARTITION BY c1 ORDER BY c2 DESC) AS rn, \
+            RANK() OVER (ORDER BY c1) AS rank, \
+            DENSE_RANK() OVER (ORDER BY c1) AS dense_rank \
+            FROM test WHERE c2 < 4",
+            4,
+        )
+        .await?;
+        assert_eq!(results.len(), 1);
+
+        let expected = vec![
+            "+----+----+----+------+------------+",
+            "| c1 | c2 | rn | rank | dense_rank |",
+            "+----+----+----+------+------------+",
+            "| 0  | 1  | 3  | 1    | 1          |",
+            "| 0  | 2  | 2  | 1    | 1          |",
+            "| 0  | 3  | 1  | 1    | 1          |",
+            "| 1  | 1  | 3  | 4    | 2          |",
+            "| 1  | 2  | 2  | 4    | 2          |",
+            "| 1  | 3  | 1  | 4    | 2          |",
+            "| 2  | 1  | 3  | 7    | 3          |",
+            "| 2  | 2  | 2  | 7    | 3          |",
+            "| 2  | 3  | 1  | 7    | 3          |",
+            "| 3  | 1  | 3  | 10   | 4          |",
+            "| 3  | 2  | 2  | 10   | 4          |",
+            "| 3  | 3  | 1  | 10   | 4          |",
+            "+----+----+----+------+------------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn window_frames() -> Result<()> {
+        let results = execute(
+            "SELECT c1, c2, \
+            SUM(c2) OVER (PARTITION BY c1 ORDER BY c2 ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) AS s, \
+            SUM(c2) OVER (PARTITION BY c1) AS total, \
+            LAG(c2) OVER (PARTITION BY c1 ORDER BY c2) AS prev, \
+            FIRST_VALUE(c2) OVER (PARTITION BY c1 ORDER BY c2 DESC) AS first \
+            FROM test WHERE c1 < 2 AND c2 < 4",
+            4,
+        )
+        .await?;
+        assert_eq!(results.len(), 1);
+
+        let expected = vec![
+            "+----+----+---+-------+------+-------+",
+            "| c1 | c2 | s | total | prev | first |",
+            "+----+----+---+-------+------+-------+",
+            "| 0  | 1  | 1 | 6     |      | 3     |",
+            "| 0  | 2  | 3 | 6     | 1    | 3     |",
+            "| 0  | 3  | 5 | 6     | 2    | 3     |",
+            "| 1  | 1  | 1 | 6     |      | 3     |",
+            "| 1  | 2  | 3 | 6     | 1    | 3     |",
+            "| 1  | 3  | 5 | 6     | 2    | 3     |",
+            "+----+----+---+-------+------+-------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn window_empty() -> Result<()> {
+        // The predicate on this query purposely generates no results
+        let results = execute(
+            "SELECT c1, ROW_NUMBER() OVER (ORDER BY c2) FROM test WHERE c1 > 100000",
+            4,
+        )
+        .await?;
+        assert_eq!(results.len(), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn in_subquery() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut ctx = create_subquery_ctx(&tmp_dir)?;
+
+        let sql =
+            "SELECT c1, COUNT(c2) FROM test WHERE c1 IN (SELECT a FROM t) GROUP BY c1";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let expected = vec![
+            "+----+-----------+",
+            "| c1 | COUNT(c2) |",
+            "+----+-----------+",
+            "| 0  | 10        |",
+            "| 1  | 10        |",
+            "+----+-----------+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        let sql = "SELECT a, b FROM t WHERE a NOT IN (SELECT c1 FROM test)";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let expected = vec![
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 5 | 4 |",
+            "+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn exists_subquery() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut ctx = create_subquery_ctx(&tmp_dir)?;
+
+        let sql = "SELECT a, b FROM t \
+            WHERE EXISTS (SELECT * FROM test WHERE c1 = a AND c2 > 5)";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let expected = vec![
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 0 | 1 |",
+            "| 1 | 2 |",
+            "| 1 | 3 |",
+            "+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        let sql = "SELECT a, b FROM t \
+            WHERE NOT EXISTS (SELECT * FROM test WHERE c1 = a) AND b > 0";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let expected = vec![
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 5 | 4 |",
+            "+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scalar_subquery() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut ctx = create_subquery_ctx(&tmp_dir)?;
+
+        let sql = "SELECT a, (SELECT MAX(c2) FROM test WHERE c1 = a) AS m FROM t";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let expected = vec![
+            "+---+----+",
+            "| a | m  |",
+            "+---+----+",
+            "| 0 | 10 |",
+            "| 1 | 10 |",
+            "| 1 | 10 |",
+            "| 5 |    |",
+            "+---+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        let sql = "SELECT a, b FROM t WHERE b > (SELECT AVG(c1) FROM test)";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let expected = vec![
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 1 | 2 |",
+            "| 1 | 3 |",
+            "| 5 | 4 |",
+            "+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        // the count of the rows without a matching group is 0
+        let sql = "SELECT a, (SELECT COUNT(c2) FROM test WHERE c1 = a) AS n FROM t";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let expected = vec![
+            "+---+----+",
+            "| a | n  |",
+            "+---+----+",
+            "| 0 | 10 |",
+            "| 1 | 10 |",
+            "| 1 | 10 |",
+            "| 5 | 0  |",
+            "+---+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        // the subquery produces several rows for each row of `t`
+        let sql = "SELECT a, (SELECT c2 FROM test WHERE c1 = a) AS n FROM t";
+        let err = plan_and_collect(&mut ctx, sql).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Scalar subqueries must be aggregates"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn not_in_subquery_with_nulls() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        for (name, column, values) in &[
+            ("n", "x", vec![Some(1), Some(2), None]),
+            ("m", "y", vec![Some(2), None]),
+        ] {
+            let schema =
+                Arc::new(Schema::new(vec![Field::new(column, DataType::Int32, true)]));
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(values.clone()))],
+            )?;
+            let provider = MemTable::try_new(schema, vec![vec![batch]])?;
+            ctx.register_table(*name, Arc::new(provider))?;
+        }
+
+        // the subquery produces a null value
+        let sql = "SELECT x FROM n WHERE x NOT IN (SELECT y FROM m)";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let num_rows: usize = results.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(0, num_rows);
+
+        // null values are not in a subquery that produces any value
+        let sql = "SELECT x FROM n WHERE x NOT IN (SELECT y FROM m WHERE y IS NOT NULL)";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let expected = vec!["+---+", "| x |", "+---+", "| 1 |", "+---+"];
+        assert_batches_sorted_eq!(expected, &results);
+
+        // all values are not in an empty subquery
+        let sql = "SELECT x FROM n WHERE x NOT IN (SELECT y FROM m WHERE y > 5)";
+        let results = plan_and_collect(&mut ctx, sql).await?;
+        let expected = vec![
+            "+---+", "| x |", "+---+", "| 1 |", "| 2 |", "|   |", "+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &results);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn aggregate() -> Result<()> {
         let results = execute("SELECT SUM(c1), SUM(c2) FROM test", 4).await?;
@@ -2054,6 +2400,35 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn write_json_results() -> Result<()> {
+        // create partitioned input file and context
+        let tmp_dir = TempDir::new()?;
+        let ctx = create_ctx(&tmp_dir, 4)?;
+
+        // execute a simple query and write the results to JSON
+        let out_dir = tmp_dir.as_ref().to_str().unwrap().to_string() + "/out";
+        let logical_plan = ctx.create_logical_plan("SELECT c1, c2 FROM test")?;
+        let logical_plan = ctx.optimize(&logical_plan)?;
+        let physical_plan = ctx.create_physical_plan(&logical_plan)?;
+        ctx.write_json(physical_plan, out_dir.clone()).await?;
+
+        // create a new context and verify that the results were saved to a
+        // partitioned json file
+        let mut ctx = ExecutionContext::new();
+        ctx.register_json("allparts", &out_dir, NdJsonReadOptions::new())?;
+        let allparts = plan_and_collect(&mut ctx, "SELECT c1, c2 FROM allparts").await?;
+        let allparts_count: usize = allparts.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(allparts_count, 40);
+
+        // the output directory must not exist yet
+        let logical_plan = ctx.create_logical_plan("SELECT c1 FROM allparts")?;
+        let physical_plan = ctx.create_physical_plan(&logical_plan)?;
+        assert!(ctx.write_json(physical_plan, out_dir).await.is_err());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn query_csv_with_custom_partition_extension() -> Result<()> {
         let tmp_dir = TempDir::new()?;
@@ -2883,6 +3258,45 @@ mod tests {
         assert_batches_sorted_eq!(expected, &result);
     }
 
+    #[tokio::test]
+    async fn read_from_registered_object_store() -> Result<()> {
+        let store = Arc::new(InMemoryObjectStore::new());
+        store.put("bucket/t/1.csv", b"c1,c2\n1,a\n2,b\n".to_vec());
+        store.put("bucket/t/2.csv", b"c1,c2\n3,c\n".to_vec());
+        store.put(
+            "bucket/events.json",
+            b"{\"c1\": 10}\n{\"c1\": 20}\n".to_vec(),
+        );
+
+        let mut ctx = ExecutionContext::new();
+        let err = ctx
+            .register_csv("t", "mem://bucket/t", CsvReadOptions::new())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("No object store is registered"));
+
+        assert!(ctx.register_object_store("mem", store).is_none());
+        ctx.register_csv("t", "mem://bucket/t", CsvReadOptions::new())?;
+        let results =
+            plan_and_collect(&mut ctx, "SELECT SUM(c1), COUNT(c2) FROM t").await?;
+        let expected = [
+            "+---------+-----------+",
+            "| SUM(c1) | COUNT(c2) |",
+            "+---------+-----------+",
+            "| 6       | 3         |",
+            "+---------+-----------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let results = ctx
+            .read_json("mem://bucket/events.json", NdJsonReadOptions::new())?
+            .collect()
+            .await?;
+        let expected = ["+----+", "| c1 |", "+----+", "| 10 |", "| 20 |", "+----+"];
+        assert_batches_eq!(expected, &results);
+        Ok(())
+    }
+
     struct MyPhysicalPlanner {}
 
     impl PhysicalPlanner for MyPhysicalPlanner {
@@ -2980,6 +3394,27 @@ mod tests {
         Ok(schema)
     }
 
+    /// Register a small table "t" alongside the partitioned CSV file "test"
+    fn create_subquery_ctx(tmp_dir: &TempDir) -> Result<ExecutionContext> {
+        let mut ctx = create_ctx(tmp_dir, 4)?;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(vec![0, 1, 1, 5])),
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+            ],
+        )?;
+        let provider = MemTable::try_new(schema, vec![vec![batch]])?;
+        ctx.register_table("t", Arc::new(provider))?;
+
+        Ok(ctx)
+    }
+
     /// Generate a partitioned CSV file and register it with an execution context
     fn create_ctx(tmp_dir: &TempDir, partition_count: usize) -> Result<ExecutionContext> {
         let mut ctx =
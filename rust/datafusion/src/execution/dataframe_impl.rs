@@ -20,16 +20,19 @@
 use std::sync::{Arc, Mutex};
 
 use crate::arrow::record_batch::RecordBatch;
+use crate::datasource::FileFormat;
 use crate::error::Result;
-use crate::execution::context::{ExecutionContext, ExecutionContextState};
+use crate::execution::context::{write_files, ExecutionContext, ExecutionContextState};
 use crate::logical_plan::{
     col, DFSchema, Expr, FunctionRegistry, JoinType, LogicalPlan, LogicalPlanBuilder,
     Partitioning,
 };
+use crate::physical_plan::file_sink::FileWriteOptions;
 use crate::{
     dataframe::*,
     physical_plan::{collect, collect_partitioned},
 };
+use parquet::file::properties::WriterProperties;
 
 use async_trait::async_trait;
 
@@ -150,6 +153,39 @@ impl DataFrame for DataFrameImpl {
         Ok(collect_partitioned(plan).await?)
     }
 
+    async fn write_csv(&self, path: &str) -> Result<()> {
+        let format = FileFormat::Csv {
+            has_header: true,
+            delimiter: b',',
+        };
+        self.write(path, FileWriteOptions::new(format)).await
+    }
+
+    async fn write_parquet(
+        &self,
+        path: &str,
+        writer_properties: Option<WriterProperties>,
+    ) -> Result<()> {
+        let mut options = FileWriteOptions::new(FileFormat::Parquet);
+        options.writer_properties = writer_properties;
+        self.write(path, options).await
+    }
+
+    async fn write_json(&self, path: &str) -> Result<()> {
+        self.write(path, FileWriteOptions::new(FileFormat::NdJson))
+            .await
+    }
+
+    // Convert the logical plan represented by this DataFrame into a physical plan and
+    // write its partitions to files
+    async fn write(&self, path: &str, options: FileWriteOptions) -> Result<()> {
+        let state = self.ctx_state.lock().unwrap().clone();
+        let ctx = ExecutionContext::from(Arc::new(Mutex::new(state)));
+        let plan = ctx.optimize(&self.plan)?;
+        let plan = ctx.create_physical_plan(&plan)?;
+        write_files(plan, path.to_string(), options).await
+    }
+
     /// Returns the schema from the logical plan
     fn schema(&self) -> &DFSchema {
         self.plan.schema()
@@ -20,20 +20,24 @@
 use std::{collections::HashMap, sync::Arc};
 
 use arrow::{
-    datatypes::{Schema, SchemaRef},
+    datatypes::{DataType, Schema, SchemaRef},
     record_batch::RecordBatch,
 };
 
 use crate::datasource::TableProvider;
 use crate::error::{DataFusionError, Result};
 use crate::{
-    datasource::{empty::EmptyTable, parquet::ParquetTable, CsvFile, MemTable},
+    datasource::{
+        empty::EmptyTable, parquet::ParquetTable, CsvFile, MemTable, NdJsonFile,
+        NdJsonReadOptions,
+    },
     prelude::CsvReadOptions,
 };
 
 use super::dfschema::ToDFSchema;
 use super::{
-    col, exprlist_to_fields, Expr, JoinType, LogicalPlan, PlanType, StringifiedPlan,
+    col, exprlist_to_fields, grouping_set_to_exprlist, Expr, JoinType, LogicalPlan,
+    PlanType, StringifiedPlan, GROUPING_ID_COLUMN,
 };
 use crate::logical_plan::{DFField, DFSchema, DFSchemaRef, Partitioning};
 use std::collections::HashSet;
@@ -116,6 +120,16 @@ impl LogicalPlanBuilder {
         Self::scan("", provider, projection)
     }
 
+    /// Scan a line-delimited JSON data source
+    pub fn scan_json(
+        path: &str,
+        options: NdJsonReadOptions,
+        projection: Option<Vec<usize>>,
+    ) -> Result<Self> {
+        let provider = Arc::new(NdJsonFile::try_new(path, options)?);
+        Self::scan("", provider, projection)
+    }
+
     /// Scan a Parquet data source
     pub fn scan_parquet(
         path: &str,
@@ -248,6 +262,19 @@ impl LogicalPlanBuilder {
         join_type: JoinType,
         left_keys: &[&str],
         right_keys: &[&str],
+    ) -> Result<Self> {
+        self.join_detailed(right, join_type, left_keys, right_keys, false)
+    }
+
+    /// Apply a join, where null values of the join columns match each other
+    /// if `null_equals_null` is true
+    pub fn join_detailed(
+        &self,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        left_keys: &[&str],
+        right_keys: &[&str],
+        null_equals_null: bool,
     ) -> Result<Self> {
         if left_keys.len() != right_keys.len() {
             Err(DataFusionError::Plan(
@@ -266,11 +293,22 @@ impl LogicalPlanBuilder {
                 right: Arc::new(right.clone()),
                 on,
                 join_type,
+                null_equals_null,
                 schema: DFSchemaRef::new(join_schema),
             }))
         }
     }
 
+    /// Apply a cross join
+    pub fn cross_join(&self, right: &LogicalPlan) -> Result<Self> {
+        let schema = self.plan.schema().join(right.schema())?;
+        Ok(Self::from(&LogicalPlan::CrossJoin {
+            left: Arc::new(self.plan.clone()),
+            right: Arc::new(right.clone()),
+            schema: DFSchemaRef::new(schema),
+        }))
+    }
+
     /// Repartition
     pub fn repartition(&self, partitioning_scheme: Partitioning) -> Result<Self> {
         Ok(Self::from(&LogicalPlan::Repartition {
@@ -282,6 +320,11 @@ impl LogicalPlanBuilder {
     /// Apply an aggregate: grouping on the `group_expr` expressions
     /// and calculating `aggr_expr` aggregates for each distinct
     /// value of the `group_expr`;
+    ///
+    /// `group_expr` can also be a single [`Expr::GroupingSet`], in which case
+    /// the groups of every grouping set are computed. The grouping columns are
+    /// then followed by a `__grouping_id` column identifying the grouping set
+    /// of each group.
     pub fn aggregate(
         &self,
         group_expr: impl IntoIterator<Item = Expr>,
@@ -290,12 +333,28 @@ impl LogicalPlanBuilder {
         let group_expr = group_expr.into_iter().collect::<Vec<Expr>>();
         let aggr_expr = aggr_expr.into_iter().collect::<Vec<Expr>>();
 
-        let all_expr = group_expr.iter().chain(aggr_expr.iter());
-
-        validate_unique_names("Aggregations", all_expr.clone(), self.plan.schema())?;
-
-        let aggr_schema =
-            DFSchema::new(exprlist_to_fields(all_expr, self.plan.schema())?)?;
+        let grouping_columns = grouping_set_to_exprlist(&group_expr)?;
+        let all_expr = grouping_columns.iter().chain(aggr_expr.iter());
+
+        validate_unique_names("Aggregations", all_expr, self.plan.schema())?;
+
+        let mut fields = exprlist_to_fields(&grouping_columns, self.plan.schema())?;
+        if let [Expr::GroupingSet(grouping_set)] = group_expr.as_slice() {
+            grouping_set.sets()?;
+            // the expressions that are not part of a grouping set are null
+            fields = fields
+                .into_iter()
+                .map(|f| DFField::new(None, f.name(), f.data_type().clone(), true))
+                .collect();
+            fields.push(DFField::new(
+                None,
+                GROUPING_ID_COLUMN,
+                DataType::UInt32,
+                false,
+            ));
+        }
+        fields.extend(exprlist_to_fields(&aggr_expr, self.plan.schema())?);
+        let aggr_schema = DFSchema::new(fields)?;
 
         Ok(Self::from(&LogicalPlan::Aggregate {
             input: Arc::new(self.plan.clone()),
@@ -305,6 +364,26 @@ impl LogicalPlanBuilder {
         }))
     }
 
+    /// Apply window functions: every input column is kept and one column is
+    /// appended for each of the `window_expr` expressions.
+    pub fn window(&self, window_expr: impl IntoIterator<Item = Expr>) -> Result<Self> {
+        let window_expr = window_expr.into_iter().collect::<Vec<Expr>>();
+
+        validate_unique_names("Windows", window_expr.iter(), self.plan.schema())?;
+
+        let mut window_fields = self.plan.schema().fields().clone();
+        window_fields.extend_from_slice(&exprlist_to_fields(
+            window_expr.iter(),
+            self.plan.schema(),
+        )?);
+
+        Ok(Self::from(&LogicalPlan::Window {
+            input: Arc::new(self.plan.clone()),
+            window_expr,
+            schema: DFSchemaRef::new(DFSchema::new(window_fields)?),
+        }))
+    }
+
     /// Create an expression to represent the explanation of the plan
     pub fn explain(&self, verbose: bool) -> Result<Self> {
         let stringified_plans = vec![StringifiedPlan::new(
@@ -337,7 +416,7 @@ fn build_join_schema(
     join_type: &JoinType,
 ) -> Result<DFSchema> {
     let fields: Vec<DFField> = match join_type {
-        JoinType::Inner | JoinType::Left => {
+        JoinType::Inner | JoinType::Left | JoinType::Full => {
             // remove right-side join keys if they have the same names as the left-side
             let duplicate_keys = &on
                 .iter()
@@ -373,6 +452,8 @@ fn build_join_schema(
             // left then right
             left_fields.chain(right_fields).cloned().collect()
         }
+        // only the left side is returned
+        JoinType::Semi | JoinType::Anti => left.fields().clone(),
     };
     DFSchema::new(fields)
 }
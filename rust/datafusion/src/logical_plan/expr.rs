@@ -27,9 +27,10 @@ use aggregates::{AccumulatorFunctionImplementation, StateTypeFunction};
 use arrow::{compute::can_cast_types, datatypes::DataType};
 
 use crate::error::{DataFusionError, Result};
-use crate::logical_plan::{DFField, DFSchema};
+use crate::logical_plan::{window_frames, DFField, DFSchema, LogicalPlan, Subquery};
 use crate::physical_plan::{
     aggregates, expressions::binary_operator_data_type, functions, udf::ScalarUDF,
+    window_functions,
 };
 use crate::{physical_plan::udaf::AggregateUDF, scalar::ScalarValue};
 use functions::{ReturnTypeFunction, ScalarFunctionImplementation, Signature};
@@ -190,6 +191,19 @@ pub enum Expr {
         /// Whether this is a DISTINCT aggregation or not
         distinct: bool,
     },
+    /// Represents the call of a window function with arguments.
+    WindowFunction {
+        /// Name of the function
+        fun: window_functions::WindowFunction,
+        /// List of expressions to feed to the functions as arguments
+        args: Vec<Expr>,
+        /// List of partition by expressions
+        partition_by: Vec<Expr>,
+        /// List of order by expressions
+        order_by: Vec<Expr>,
+        /// Window frame
+        window_frame: Option<window_frames::WindowFrame>,
+    },
     /// aggregate function
     AggregateUDF {
         /// The function
@@ -206,10 +220,160 @@ pub enum Expr {
         /// Whether the expression is negated
         negated: bool,
     },
+    /// Returns whether the subquery produces at least one row.
+    Exists {
+        /// The subquery
+        subquery: Subquery,
+        /// Whether the expression is negated
+        negated: bool,
+    },
+    /// Returns whether the values produced by the subquery contain the expr value.
+    InSubquery {
+        /// The expression to compare
+        expr: Box<Expr>,
+        /// The subquery, producing a single column
+        subquery: Subquery,
+        /// Whether the expression is negated
+        negated: bool,
+    },
+    /// The single value produced by a subquery, or null if it produces no rows.
+    ScalarSubquery(Subquery),
+    /// The grouping sets of an aggregate, which must be its only grouping expression.
+    GroupingSet(GroupingSet),
     /// Represents a reference to all fields in a schema.
     Wildcard,
 }
 
+/// The grouping sets of a `GROUP BY` clause, whose groups are computed in a
+/// single aggregation. The expressions that are not part of a grouping set
+/// are null in its groups.
+#[derive(Clone, PartialEq)]
+pub enum GroupingSet {
+    /// `ROLLUP (a, b)`: the grouping sets `(a, b)`, `(a)` and `()`
+    Rollup(Vec<Expr>),
+    /// `CUBE (a, b)`: the grouping sets `(a, b)`, `(a)`, `(b)` and `()`
+    Cube(Vec<Expr>),
+    /// `GROUPING SETS ((a), (a, b), ())`: the listed grouping sets
+    GroupingSets(Vec<Vec<Expr>>),
+}
+
+impl GroupingSet {
+    /// The distinct expressions of the grouping sets, in order of appearance.
+    /// They are the grouping columns of the aggregate.
+    pub fn distinct_expr(&self) -> Vec<Expr> {
+        let mut distinct: Vec<Expr> = vec![];
+        for expr in self.exprs() {
+            if !distinct.contains(expr) {
+                distinct.push(expr.clone());
+            }
+        }
+        distinct
+    }
+
+    /// All the expressions of the grouping sets, in order of appearance
+    pub fn exprs(&self) -> Vec<&Expr> {
+        match self {
+            GroupingSet::Rollup(exprs) | GroupingSet::Cube(exprs) => {
+                exprs.iter().collect()
+            }
+            GroupingSet::GroupingSets(sets) => sets.iter().flatten().collect(),
+        }
+    }
+
+    /// Returns a copy of this grouping set whose expressions, in the order of
+    /// [`GroupingSet::exprs`], are replaced by `exprs`
+    pub fn with_new_exprs(&self, exprs: Vec<Expr>) -> Self {
+        match self {
+            GroupingSet::Rollup(_) => GroupingSet::Rollup(exprs),
+            GroupingSet::Cube(_) => GroupingSet::Cube(exprs),
+            GroupingSet::GroupingSets(sets) => {
+                let mut exprs = exprs.into_iter();
+                GroupingSet::GroupingSets(
+                    sets.iter()
+                        .map(|set| exprs.by_ref().take(set.len()).collect())
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    /// The grouping sets, as the indices in [`GroupingSet::distinct_expr`] of
+    /// their expressions. Sets listed more than once are only returned once.
+    pub fn sets(&self) -> Result<Vec<Vec<usize>>> {
+        let distinct = self.distinct_expr();
+        let index = |expr: &Expr| distinct.iter().position(|e| e == expr).unwrap();
+        let sets = match self {
+            GroupingSet::Rollup(exprs) => (0..=exprs.len())
+                .rev()
+                .map(|len| exprs[..len].iter().map(index).collect())
+                .collect::<Vec<Vec<_>>>(),
+            GroupingSet::Cube(exprs) => {
+                if exprs.len() > MAX_GROUPING_EXPRS {
+                    return Err(DataFusionError::Plan(format!(
+                        "CUBE supports at most {} expressions",
+                        MAX_GROUPING_EXPRS
+                    )));
+                }
+                // the subsets are enumerated by the bits of their mask, the
+                // first expression being the most significant bit
+                (0..1usize << exprs.len())
+                    .rev()
+                    .map(|mask| {
+                        (0..exprs.len())
+                            .filter(|i| mask & (1 << (exprs.len() - 1 - i)) != 0)
+                            .map(|i| index(&exprs[i]))
+                            .collect()
+                    })
+                    .collect()
+            }
+            GroupingSet::GroupingSets(sets) => sets
+                .iter()
+                .map(|set| set.iter().map(index).collect())
+                .collect(),
+        };
+        let mut distinct_sets: Vec<Vec<usize>> = vec![];
+        for mut set in sets {
+            set.sort_unstable();
+            set.dedup();
+            if !distinct_sets.contains(&set) {
+                distinct_sets.push(set);
+            }
+        }
+        if distinct.len() > MAX_GROUPING_EXPRS {
+            return Err(DataFusionError::Plan(format!(
+                "Grouping sets support at most {} distinct expressions",
+                MAX_GROUPING_EXPRS
+            )));
+        }
+        Ok(distinct_sets)
+    }
+}
+
+/// The maximum number of distinct expressions of grouping sets, which is the
+/// number of bits of the grouping id
+pub const MAX_GROUPING_EXPRS: usize = 32;
+
+/// The name of the column of an aggregate with grouping sets that identifies
+/// the grouping set of every group. Its bit `n - 1 - i`, where `n` is the number
+/// of distinct grouping expressions, is set when the expression `i` is not
+/// part of the grouping set.
+pub const GROUPING_ID_COLUMN: &str = "__grouping_id";
+
+/// Expands the grouping expressions of an aggregate into its grouping columns,
+/// which are the distinct expressions of the grouping sets if it has any.
+pub fn grouping_set_to_exprlist(group_expr: &[Expr]) -> Result<Vec<Expr>> {
+    match group_expr {
+        [Expr::GroupingSet(grouping_set)] => Ok(grouping_set.distinct_expr()),
+        _ if group_expr.iter().any(|e| matches!(e, Expr::GroupingSet(_))) => {
+            Err(DataFusionError::Plan(
+                "A grouping set must be the only grouping expression of an aggregate"
+                    .to_string(),
+            ))
+        }
+        _ => Ok(group_expr.to_vec()),
+    }
+}
+
 impl Expr {
     /// Returns the [arrow::datatypes::DataType] of the expression based on [arrow::datatypes::Schema].
     ///
@@ -245,12 +409,21 @@ impl Expr {
                 functions::return_type(fun, &data_types)
             }
             Expr::AggregateFunction { fun, args, .. } => {
+                // the sort expressions order the values and are not arguments
                 let data_types = args
                     .iter()
+                    .filter(|e| !matches!(e, Expr::Sort { .. }))
                     .map(|e| e.get_type(schema))
                     .collect::<Result<Vec<_>>>()?;
                 aggregates::return_type(fun, &data_types)
             }
+            Expr::WindowFunction { fun, args, .. } => {
+                let data_types = args
+                    .iter()
+                    .map(|e| e.get_type(schema))
+                    .collect::<Result<Vec<_>>>()?;
+                window_functions::return_type(fun, &data_types)
+            }
             Expr::AggregateUDF { fun, args, .. } => {
                 let data_types = args
                     .iter()
@@ -274,6 +447,15 @@ impl Expr {
             Expr::Sort { ref expr, .. } => expr.get_type(schema),
             Expr::Between { .. } => Ok(DataType::Boolean),
             Expr::InList { .. } => Ok(DataType::Boolean),
+            Expr::Exists { .. } => Ok(DataType::Boolean),
+            Expr::InSubquery { .. } => Ok(DataType::Boolean),
+            Expr::ScalarSubquery(subquery) => {
+                Ok(subquery.subquery.schema().field(0).data_type().clone())
+            }
+            Expr::GroupingSet(_) => Err(DataFusionError::Internal(
+                "Grouping sets are only valid as the grouping expression of an aggregate"
+                    .to_owned(),
+            )),
             Expr::Wildcard => Err(DataFusionError::Internal(
                 "Wildcard expressions are not valid in a logical query plan".to_owned(),
             )),
@@ -317,6 +499,7 @@ impl Expr {
             Expr::ScalarFunction { .. } => Ok(true),
             Expr::ScalarUDF { .. } => Ok(true),
             Expr::AggregateFunction { .. } => Ok(true),
+            Expr::WindowFunction { .. } => Ok(true),
             Expr::AggregateUDF { .. } => Ok(true),
             Expr::Not(expr) => expr.nullable(input_schema),
             Expr::Negative(expr) => expr.nullable(input_schema),
@@ -330,6 +513,10 @@ impl Expr {
             Expr::Sort { ref expr, .. } => expr.nullable(input_schema),
             Expr::Between { ref expr, .. } => expr.nullable(input_schema),
             Expr::InList { ref expr, .. } => expr.nullable(input_schema),
+            Expr::Exists { .. } => Ok(false),
+            Expr::InSubquery { ref expr, .. } => expr.nullable(input_schema),
+            Expr::ScalarSubquery(_) => Ok(true),
+            Expr::GroupingSet(_) => Ok(true),
             Expr::Wildcard => Err(DataFusionError::Internal(
                 "Wildcard expressions are not valid in a logical query plan".to_owned(),
             )),
@@ -574,6 +761,22 @@ impl Expr {
             Expr::AggregateFunction { args, .. } => args
                 .iter()
                 .try_fold(visitor, |visitor, arg| arg.accept(visitor)),
+            Expr::WindowFunction {
+                args,
+                partition_by,
+                order_by,
+                ..
+            } => {
+                let visitor = args
+                    .iter()
+                    .try_fold(visitor, |visitor, arg| arg.accept(visitor))?;
+                let visitor = partition_by
+                    .iter()
+                    .try_fold(visitor, |visitor, arg| arg.accept(visitor))?;
+                order_by
+                    .iter()
+                    .try_fold(visitor, |visitor, arg| arg.accept(visitor))
+            }
             Expr::AggregateUDF { args, .. } => args
                 .iter()
                 .try_fold(visitor, |visitor, arg| arg.accept(visitor)),
@@ -582,6 +785,12 @@ impl Expr {
                 list.iter()
                     .try_fold(visitor, |visitor, arg| arg.accept(visitor))
             }
+            Expr::InSubquery { expr, .. } => expr.accept(visitor),
+            Expr::Exists { .. } | Expr::ScalarSubquery(_) => Ok(visitor),
+            Expr::GroupingSet(grouping_set) => grouping_set
+                .exprs()
+                .into_iter()
+                .try_fold(visitor, |visitor, expr| expr.accept(visitor)),
             Expr::Wildcard => Ok(visitor),
         }?;
 
@@ -713,6 +922,19 @@ impl Expr {
                 fun,
                 distinct,
             },
+            Expr::WindowFunction {
+                args,
+                fun,
+                partition_by,
+                order_by,
+                window_frame,
+            } => Expr::WindowFunction {
+                args: rewrite_vec(args, rewriter)?,
+                fun,
+                partition_by: rewrite_vec(partition_by, rewriter)?,
+                order_by: rewrite_vec(order_by, rewriter)?,
+                window_frame,
+            },
             Expr::AggregateUDF { args, fun } => Expr::AggregateUDF {
                 args: rewrite_vec(args, rewriter)?,
                 fun,
@@ -726,6 +948,23 @@ impl Expr {
                 list,
                 negated,
             },
+            Expr::Exists { subquery, negated } => Expr::Exists { subquery, negated },
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Expr::InSubquery {
+                expr: rewrite_boxed(expr, rewriter)?,
+                subquery,
+                negated,
+            },
+            Expr::ScalarSubquery(subquery) => Expr::ScalarSubquery(subquery),
+            Expr::GroupingSet(grouping_set) => {
+                let exprs = grouping_set.exprs().into_iter().cloned().collect();
+                Expr::GroupingSet(
+                    grouping_set.with_new_exprs(rewrite_vec(exprs, rewriter)?),
+                )
+            }
             Expr::Wildcard => Expr::Wildcard,
         };
 
@@ -995,6 +1234,44 @@ pub fn count_distinct(expr: Expr) -> Expr {
     }
 }
 
+/// Create an expression to represent the array_agg() aggregate function, which
+/// collects the values of `expr` into a list ordered by the `Expr::Sort`
+/// expressions `order_by`, e.g. `col("a").sort(true, false)`.
+pub fn array_agg(expr: Expr, order_by: Vec<Expr>) -> Expr {
+    let mut args = vec![expr];
+    args.extend(order_by);
+    Expr::AggregateFunction {
+        fun: aggregates::AggregateFunction::ArrayAgg,
+        distinct: false,
+        args,
+    }
+}
+
+/// Create an expression to represent the string_agg() aggregate function, which
+/// concatenates the values of `expr` separated by the literal `separator`
+pub fn string_agg(expr: Expr, separator: Expr) -> Expr {
+    Expr::AggregateFunction {
+        fun: aggregates::AggregateFunction::StringAgg,
+        distinct: false,
+        args: vec![expr, separator],
+    }
+}
+
+/// Create a grouping expression for the grouping sets `ROLLUP (exprs)`
+pub fn rollup(exprs: Vec<Expr>) -> Expr {
+    Expr::GroupingSet(GroupingSet::Rollup(exprs))
+}
+
+/// Create a grouping expression for the grouping sets `CUBE (exprs)`
+pub fn cube(exprs: Vec<Expr>) -> Expr {
+    Expr::GroupingSet(GroupingSet::Cube(exprs))
+}
+
+/// Create a grouping expression for the grouping sets `GROUPING SETS (sets)`
+pub fn grouping_sets(sets: Vec<Vec<Expr>>) -> Expr {
+    Expr::GroupingSet(GroupingSet::GroupingSets(sets))
+}
+
 /// Create an in_list expression
 pub fn in_list(expr: Expr, list: Vec<Expr>, negated: bool) -> Expr {
     Expr::InList {
@@ -1004,6 +1281,28 @@ pub fn in_list(expr: Expr, list: Vec<Expr>, negated: bool) -> Expr {
     }
 }
 
+/// Create an EXISTS subquery expression
+pub fn exists(subquery: LogicalPlan, negated: bool) -> Expr {
+    Expr::Exists {
+        subquery: Subquery::new(subquery),
+        negated,
+    }
+}
+
+/// Create an IN subquery expression
+pub fn in_subquery(expr: Expr, subquery: LogicalPlan, negated: bool) -> Expr {
+    Expr::InSubquery {
+        expr: Box::new(expr),
+        subquery: Subquery::new(subquery),
+        negated,
+    }
+}
+
+/// Create a scalar subquery expression
+pub fn scalar_subquery(subquery: LogicalPlan) -> Expr {
+    Expr::ScalarSubquery(Subquery::new(subquery))
+}
+
 /// Trait for converting a type to a [`Literal`] literal expression.
 pub trait Literal {
     /// convert the value to a Literal expression
@@ -1177,12 +1476,20 @@ fn fmt_function(
     distinct: bool,
     args: &[Expr],
 ) -> fmt::Result {
+    // the sort expressions of ordered aggregates come after the arguments
+    let (order_by, args): (Vec<&Expr>, Vec<&Expr>) = args
+        .iter()
+        .partition(|arg| matches!(arg, Expr::Sort { .. }));
     let args: Vec<String> = args.iter().map(|arg| format!("{:?}", arg)).collect();
     let distinct_str = match distinct {
         true => "DISTINCT ",
         false => "",
     };
-    write!(f, "{}({}{})", fun, distinct_str, args.join(", "))
+    write!(f, "{}({}{}", fun, distinct_str, args.join(", "))?;
+    if !order_by.is_empty() {
+        write!(f, " ORDER BY {:?}", order_by)?;
+    }
+    write!(f, ")")
 }
 
 impl fmt::Debug for Expr {
@@ -1251,6 +1558,25 @@ impl fmt::Debug for Expr {
                 ref args,
                 ..
             } => fmt_function(f, &fun.to_string(), *distinct, args),
+            Expr::WindowFunction {
+                fun,
+                args,
+                partition_by,
+                order_by,
+                window_frame,
+            } => {
+                fmt_function(f, &fun.to_string(), false, args)?;
+                if !partition_by.is_empty() {
+                    write!(f, " PARTITION BY {:?}", partition_by)?;
+                }
+                if !order_by.is_empty() {
+                    write!(f, " ORDER BY {:?}", order_by)?;
+                }
+                if let Some(window_frame) = window_frame {
+                    write!(f, " {}", window_frame)?;
+                }
+                Ok(())
+            }
             Expr::AggregateUDF { fun, ref args, .. } => {
                 fmt_function(f, &fun.name, false, args)
             }
@@ -1277,17 +1603,50 @@ impl fmt::Debug for Expr {
                     write!(f, "{:?} IN ({:?})", expr, list)
                 }
             }
+            Expr::Exists { subquery, negated } => {
+                if *negated {
+                    write!(f, "NOT EXISTS ({:?})", subquery)
+                } else {
+                    write!(f, "EXISTS ({:?})", subquery)
+                }
+            }
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => {
+                if *negated {
+                    write!(f, "{:?} NOT IN ({:?})", expr, subquery)
+                } else {
+                    write!(f, "{:?} IN ({:?})", expr, subquery)
+                }
+            }
+            Expr::ScalarSubquery(subquery) => write!(f, "({:?})", subquery),
+            Expr::GroupingSet(grouping_set) => write!(f, "{:?}", grouping_set),
             Expr::Wildcard => write!(f, "*"),
         }
     }
 }
 
+impl fmt::Debug for GroupingSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GroupingSet::Rollup(exprs) => write!(f, "ROLLUP ({:?})", exprs),
+            GroupingSet::Cube(exprs) => write!(f, "CUBE ({:?})", exprs),
+            GroupingSet::GroupingSets(sets) => write!(f, "GROUPING SETS ({:?})", sets),
+        }
+    }
+}
+
 fn create_function_name(
     fun: &str,
     distinct: bool,
     args: &[Expr],
     input_schema: &DFSchema,
 ) -> Result<String> {
+    // the sort expressions of ordered aggregates come after the arguments
+    let (order_by, args): (Vec<&Expr>, Vec<&Expr>) =
+        args.iter().partition(|e| matches!(e, Expr::Sort { .. }));
     let names: Vec<String> = args
         .iter()
         .map(|e| create_name(e, input_schema))
@@ -1296,7 +1655,39 @@ fn create_function_name(
         true => "DISTINCT ",
         false => "",
     };
-    Ok(format!("{}({}{})", fun, distinct_str, names.join(",")))
+    let mut name = format!("{}({}{}", fun, distinct_str, names.join(","));
+    if !order_by.is_empty() {
+        name += &format!(
+            " ORDER BY [{}]",
+            create_sort_names(&order_by, input_schema)?
+        );
+    }
+    Ok(name + ")")
+}
+
+/// Returns the readable names of the sort expressions `order_by`
+fn create_sort_names(order_by: &[&Expr], input_schema: &DFSchema) -> Result<String> {
+    let names = order_by
+        .iter()
+        .map(|e| match e {
+            Expr::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => Ok(format!(
+                "{} {} {}",
+                create_name(expr, input_schema)?,
+                if *asc { "ASC" } else { "DESC" },
+                if *nulls_first {
+                    "NULLS FIRST"
+                } else {
+                    "NULLS LAST"
+                }
+            )),
+            _ => create_name(e, input_schema),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(names.join(", "))
 }
 
 /// Returns a readable name of an expression based on the input schema.
@@ -1366,6 +1757,34 @@ fn create_name(e: &Expr, input_schema: &DFSchema) -> Result<String> {
             args,
             ..
         } => create_function_name(&fun.to_string(), *distinct, args, input_schema),
+        Expr::WindowFunction {
+            fun,
+            args,
+            partition_by,
+            order_by,
+            window_frame,
+        } => {
+            let mut name =
+                create_function_name(&fun.to_string(), false, args, input_schema)?;
+            if !partition_by.is_empty() {
+                let partition_by = partition_by
+                    .iter()
+                    .map(|e| create_name(e, input_schema))
+                    .collect::<Result<Vec<_>>>()?;
+                name += &format!(" PARTITION BY [{}]", partition_by.join(", "));
+            }
+            if !order_by.is_empty() {
+                let order_by = order_by.iter().collect::<Vec<_>>();
+                name += &format!(
+                    " ORDER BY [{}]",
+                    create_sort_names(&order_by, input_schema)?
+                );
+            }
+            if let Some(window_frame) = window_frame {
+                name += &format!(" {}", window_frame);
+            }
+            Ok(name)
+        }
         Expr::AggregateUDF { fun, args } => {
             let mut names = Vec::with_capacity(args.len());
             for e in args {
@@ -1386,6 +1805,42 @@ fn create_name(e: &Expr, input_schema: &DFSchema) -> Result<String> {
                 Ok(format!("{} IN ({:?})", expr, list))
             }
         }
+        Expr::Exists { negated, .. } => {
+            if *negated {
+                Ok("NOT EXISTS (<subquery>)".to_string())
+            } else {
+                Ok("EXISTS (<subquery>)".to_string())
+            }
+        }
+        Expr::InSubquery { expr, negated, .. } => {
+            let expr = create_name(expr, input_schema)?;
+            if *negated {
+                Ok(format!("{} NOT IN (<subquery>)", expr))
+            } else {
+                Ok(format!("{} IN (<subquery>)", expr))
+            }
+        }
+        Expr::ScalarSubquery(_) => Ok("(<subquery>)".to_string()),
+        Expr::GroupingSet(grouping_set) => {
+            let names = |exprs: &[Expr]| {
+                exprs
+                    .iter()
+                    .map(|e| create_name(e, input_schema))
+                    .collect::<Result<Vec<_>>>()
+                    .map(|names| names.join(", "))
+            };
+            match grouping_set {
+                GroupingSet::Rollup(exprs) => Ok(format!("ROLLUP ({})", names(exprs)?)),
+                GroupingSet::Cube(exprs) => Ok(format!("CUBE ({})", names(exprs)?)),
+                GroupingSet::GroupingSets(sets) => {
+                    let sets = sets
+                        .iter()
+                        .map(|set| Ok(format!("({})", names(set)?)))
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(format!("GROUPING SETS ({})", sets.join(", ")))
+                }
+            }
+        }
         other => Err(DataFusionError::NotImplemented(format!(
             "Physical plan does not support logical expression {:?}",
             other
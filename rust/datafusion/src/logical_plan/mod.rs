@@ -29,22 +29,25 @@ mod extension;
 mod operators;
 mod plan;
 mod registry;
+pub mod window_frames;
 pub use builder::LogicalPlanBuilder;
 pub use dfschema::{DFField, DFSchema, DFSchemaRef, ToDFSchema};
 pub use display::display_schema;
 pub use expr::{
-    abs, acos, and, array, ascii, asin, atan, avg, binary_expr, bit_length, btrim, case,
-    ceil, character_length, chr, col, combine_filters, concat, concat_ws, cos, count,
-    count_distinct, create_udaf, create_udf, exp, exprlist_to_fields, floor, in_list,
-    initcap, left, length, lit, ln, log10, log2, lower, lpad, ltrim, max, md5, min,
-    octet_length, or, regexp_match, regexp_replace, repeat, replace, reverse, right,
-    round, rpad, rtrim, sha224, sha256, sha384, sha512, signum, sin, split_part, sqrt,
-    starts_with, strpos, substr, sum, tan, to_hex, translate, trim, trunc, upper, when,
-    Expr, ExprRewriter, ExpressionVisitor, Literal, Recursion,
+    abs, acos, and, array, array_agg, ascii, asin, atan, avg, binary_expr, bit_length,
+    btrim, case, ceil, character_length, chr, col, combine_filters, concat, concat_ws,
+    cos, count, count_distinct, create_udaf, create_udf, cube, exists, exp,
+    exprlist_to_fields, floor, grouping_set_to_exprlist, grouping_sets, in_list,
+    in_subquery, initcap, left, length, lit, ln, log10, log2, lower, lpad, ltrim, max,
+    md5, min, octet_length, or, regexp_match, regexp_replace, repeat, replace, reverse,
+    right, rollup, round, rpad, rtrim, scalar_subquery, sha224, sha256, sha384, sha512,
+    signum, sin, split_part, sqrt, starts_with, string_agg, strpos, substr, sum, tan,
+    to_hex, translate, trim, trunc, upper, when, Expr, ExprRewriter, ExpressionVisitor,
+    GroupingSet, Literal, Recursion, GROUPING_ID_COLUMN, MAX_GROUPING_EXPRS,
 };
 pub use extension::UserDefinedLogicalNode;
 pub use operators::Operator;
 pub use plan::{
-    JoinType, LogicalPlan, Partitioning, PlanType, PlanVisitor, StringifiedPlan,
+    JoinType, LogicalPlan, Partitioning, PlanType, PlanVisitor, StringifiedPlan, Subquery,
 };
 pub use registry::FunctionRegistry;
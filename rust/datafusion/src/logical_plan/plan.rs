@@ -26,6 +26,7 @@ use std::{
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 
 use crate::datasource::TableProvider;
+use crate::physical_plan::file_sink::FileWriteOptions;
 use crate::sql::parser::FileType;
 
 use super::expr::Expr;
@@ -45,6 +46,12 @@ pub enum JoinType {
     Left,
     /// Right join
     Right,
+    /// Full outer join
+    Full,
+    /// Left semi join: the rows of the left side that have a match on the right side
+    Semi,
+    /// Left anti join: the rows of the left side that have no match on the right side
+    Anti,
 }
 
 /// A LogicalPlan represents the different types of relational
@@ -81,6 +88,18 @@ pub enum LogicalPlan {
         /// The incoming logical plan
         input: Arc<LogicalPlan>,
     },
+    /// Evaluates window functions (e.g. `ROW_NUMBER() OVER (...)`) over its
+    /// input. The output contains every input column followed by one
+    /// column per window function, and has the same number of rows as
+    /// the input.
+    Window {
+        /// The incoming logical plan
+        input: Arc<LogicalPlan>,
+        /// The window function expressions
+        window_expr: Vec<Expr>,
+        /// The schema description of the window output
+        schema: DFSchemaRef,
+    },
     /// Aggregates its input based on a set of grouping and aggregate
     /// expressions (e.g. SUM).
     Aggregate {
@@ -110,6 +129,18 @@ pub enum LogicalPlan {
         on: Vec<(String, String)>,
         /// Join type
         join_type: JoinType,
+        /// Whether null values of the join columns match each other, e.g. for
+        /// set operations
+        null_equals_null: bool,
+        /// The output schema, containing fields from the left and right inputs
+        schema: DFSchemaRef,
+    },
+    /// Apply Cross Join to two logical plans
+    CrossJoin {
+        /// Left input
+        left: Arc<LogicalPlan>,
+        /// Right input
+        right: Arc<LogicalPlan>,
         /// The output schema, containing fields from the left and right inputs
         schema: DFSchemaRef,
     },
@@ -171,6 +202,44 @@ pub enum LogicalPlan {
         /// Whether the CSV file contains a header
         has_header: bool,
     },
+    /// Creates an in-memory table from the results of a query.
+    CreateMemoryTable {
+        /// The table name
+        name: String,
+        /// The logical plan of the query that populates the table
+        input: Arc<LogicalPlan>,
+    },
+    /// Creates a view, stored as the logical plan of its query.
+    CreateView {
+        /// The view name
+        name: String,
+        /// The logical plan of the query that defines the view
+        input: Arc<LogicalPlan>,
+    },
+    /// Appends the results of a query to an in-memory table, producing
+    /// the number of rows inserted.
+    Insert {
+        /// The name of the table
+        table_name: String,
+        /// The table the rows are appended to
+        table: Arc<dyn TableProvider>,
+        /// The rows to insert, projected to the schema of the table
+        input: Arc<LogicalPlan>,
+        /// The output schema of the insert (a single `count` column)
+        schema: DFSchemaRef,
+    },
+    /// Writes the results of a query to files, one per partition,
+    /// producing the number of rows written.
+    CopyTo {
+        /// The rows to write
+        input: Arc<LogicalPlan>,
+        /// Path of the directory to write the files to
+        path: String,
+        /// The format and compression of the files
+        options: FileWriteOptions,
+        /// The output schema of the copy (a single `count` column)
+        schema: DFSchemaRef,
+    },
     /// Produces a relation with string representations of
     /// various parts of the plan
     Explain {
@@ -183,6 +252,17 @@ pub enum LogicalPlan {
         /// The output schema of the explain (2 columns of text)
         schema: DFSchemaRef,
     },
+    /// Runs the input plan and produces a relation with the string
+    /// representation of its physical plan, annotated with the metrics
+    /// recorded by each operator
+    Analyze {
+        /// Should extra (detailed) metrics be included?
+        verbose: bool,
+        /// The logical plan that is being EXPLAIN ANALYZE'd
+        input: Arc<LogicalPlan>,
+        /// The output schema of the explain (2 columns of text)
+        schema: DFSchemaRef,
+    },
     /// Extension operator defined outside of DataFusion
     Extension {
         /// The runtime extension operator
@@ -200,13 +280,20 @@ impl LogicalPlan {
             } => &projected_schema,
             LogicalPlan::Projection { schema, .. } => &schema,
             LogicalPlan::Filter { input, .. } => input.schema(),
+            LogicalPlan::Window { schema, .. } => &schema,
             LogicalPlan::Aggregate { schema, .. } => &schema,
             LogicalPlan::Sort { input, .. } => input.schema(),
             LogicalPlan::Join { schema, .. } => &schema,
+            LogicalPlan::CrossJoin { schema, .. } => &schema,
             LogicalPlan::Repartition { input, .. } => input.schema(),
             LogicalPlan::Limit { input, .. } => input.schema(),
             LogicalPlan::CreateExternalTable { schema, .. } => &schema,
+            LogicalPlan::CreateMemoryTable { input, .. } => input.schema(),
+            LogicalPlan::CreateView { input, .. } => input.schema(),
+            LogicalPlan::Insert { schema, .. } => schema,
+            LogicalPlan::CopyTo { schema, .. } => schema,
             LogicalPlan::Explain { schema, .. } => &schema,
+            LogicalPlan::Analyze { schema, .. } => schema,
             LogicalPlan::Extension { node } => &node.schema(),
             LogicalPlan::Union { schema, .. } => &schema,
         }
@@ -218,8 +305,12 @@ impl LogicalPlan {
             LogicalPlan::TableScan {
                 projected_schema, ..
             } => vec![&projected_schema],
-            LogicalPlan::Aggregate { input, schema, .. }
-            | LogicalPlan::Projection { input, schema, .. } => {
+            LogicalPlan::Window { input, schema, .. }
+            | LogicalPlan::Aggregate { input, schema, .. }
+            | LogicalPlan::Projection { input, schema, .. }
+            | LogicalPlan::Analyze { input, schema, .. }
+            | LogicalPlan::Insert { input, schema, .. }
+            | LogicalPlan::CopyTo { input, schema, .. } => {
                 let mut schemas = input.all_schemas();
                 schemas.insert(0, &schema);
                 schemas
@@ -229,6 +320,11 @@ impl LogicalPlan {
                 right,
                 schema,
                 ..
+            }
+            | LogicalPlan::CrossJoin {
+                left,
+                right,
+                schema,
             } => {
                 let mut schemas = left.all_schemas();
                 schemas.extend(right.all_schemas());
@@ -245,7 +341,9 @@ impl LogicalPlan {
             LogicalPlan::Limit { input, .. }
             | LogicalPlan::Repartition { input, .. }
             | LogicalPlan::Sort { input, .. }
-            | LogicalPlan::Filter { input, .. } => input.all_schemas(),
+            | LogicalPlan::Filter { input, .. }
+            | LogicalPlan::CreateMemoryTable { input, .. }
+            | LogicalPlan::CreateView { input, .. } => input.all_schemas(),
         }
     }
 
@@ -257,6 +355,16 @@ impl LogicalPlan {
         ]))
     }
 
+    /// Returns the (fixed) output schema for plans that store rows, such as
+    /// inserts: the number of rows stored
+    pub fn count_schema() -> SchemaRef {
+        SchemaRef::new(Schema::new(vec![Field::new(
+            "count",
+            DataType::UInt64,
+            false,
+        )]))
+    }
+
     /// returns all expressions (non-recursively) in the current
     /// logical plan node. This does not include expressions in any
     /// children
@@ -271,6 +379,7 @@ impl LogicalPlan {
                 Partitioning::Hash(expr, _) => expr.clone(),
                 _ => vec![],
             },
+            LogicalPlan::Window { window_expr, .. } => window_expr.clone(),
             LogicalPlan::Aggregate {
                 group_expr,
                 aggr_expr,
@@ -288,9 +397,15 @@ impl LogicalPlan {
             // plans without expressions
             LogicalPlan::TableScan { .. }
             | LogicalPlan::EmptyRelation { .. }
+            | LogicalPlan::CrossJoin { .. }
             | LogicalPlan::Limit { .. }
             | LogicalPlan::CreateExternalTable { .. }
-            | LogicalPlan::Explain { .. } => vec![],
+            | LogicalPlan::CreateMemoryTable { .. }
+            | LogicalPlan::CreateView { .. }
+            | LogicalPlan::Insert { .. }
+            | LogicalPlan::CopyTo { .. }
+            | LogicalPlan::Explain { .. }
+            | LogicalPlan::Analyze { .. } => vec![],
             LogicalPlan::Union { .. } => {
                 vec![]
             }
@@ -304,10 +419,17 @@ impl LogicalPlan {
             LogicalPlan::Projection { input, .. } => vec![input],
             LogicalPlan::Filter { input, .. } => vec![input],
             LogicalPlan::Repartition { input, .. } => vec![input],
+            LogicalPlan::Window { input, .. } => vec![input],
             LogicalPlan::Aggregate { input, .. } => vec![input],
             LogicalPlan::Sort { input, .. } => vec![input],
             LogicalPlan::Join { left, right, .. } => vec![left, right],
+            LogicalPlan::CrossJoin { left, right, .. } => vec![left, right],
             LogicalPlan::Limit { input, .. } => vec![input],
+            LogicalPlan::Analyze { input, .. } => vec![input],
+            LogicalPlan::CreateMemoryTable { input, .. } => vec![input],
+            LogicalPlan::CreateView { input, .. } => vec![input],
+            LogicalPlan::Insert { input, .. } => vec![input],
+            LogicalPlan::CopyTo { input, .. } => vec![input],
             LogicalPlan::Extension { node } => node.inputs(),
             LogicalPlan::Union { inputs, .. } => inputs.iter().collect(),
             // plans without inputs
@@ -394,9 +516,11 @@ impl LogicalPlan {
             LogicalPlan::Projection { input, .. } => input.accept(visitor)?,
             LogicalPlan::Filter { input, .. } => input.accept(visitor)?,
             LogicalPlan::Repartition { input, .. } => input.accept(visitor)?,
+            LogicalPlan::Window { input, .. } => input.accept(visitor)?,
             LogicalPlan::Aggregate { input, .. } => input.accept(visitor)?,
             LogicalPlan::Sort { input, .. } => input.accept(visitor)?,
-            LogicalPlan::Join { left, right, .. } => {
+            LogicalPlan::Join { left, right, .. }
+            | LogicalPlan::CrossJoin { left, right, .. } => {
                 left.accept(visitor)? && right.accept(visitor)?
             }
             LogicalPlan::Union { inputs, .. } => {
@@ -408,6 +532,11 @@ impl LogicalPlan {
                 true
             }
             LogicalPlan::Limit { input, .. } => input.accept(visitor)?,
+            LogicalPlan::Analyze { input, .. } => input.accept(visitor)?,
+            LogicalPlan::CreateMemoryTable { input, .. }
+            | LogicalPlan::CreateView { input, .. }
+            | LogicalPlan::Insert { input, .. }
+            | LogicalPlan::CopyTo { input, .. } => input.accept(visitor)?,
             LogicalPlan::Extension { node } => {
                 for input in node.inputs() {
                     if !input.accept(visitor)? {
@@ -645,6 +774,11 @@ impl LogicalPlan {
                         predicate: ref expr,
                         ..
                     } => write!(f, "Filter: {:?}", expr),
+                    LogicalPlan::Window {
+                        ref window_expr, ..
+                    } => {
+                        write!(f, "WindowAggr: windowExpr=[{:?}]", window_expr)
+                    }
                     LogicalPlan::Aggregate {
                         ref group_expr,
                         ref aggr_expr,
@@ -664,11 +798,26 @@ impl LogicalPlan {
                         }
                         Ok(())
                     }
-                    LogicalPlan::Join { on: ref keys, .. } => {
+                    LogicalPlan::Join {
+                        on: ref keys,
+                        join_type,
+                        ..
+                    } => {
                         let join_expr: Vec<String> =
                             keys.iter().map(|(l, r)| format!("{} = {}", l, r)).collect();
-                        write!(f, "Join: {}", join_expr.join(", "))
+                        match join_type {
+                            JoinType::Inner => {
+                                write!(f, "Join: {}", join_expr.join(", "))
+                            }
+                            _ => write!(
+                                f,
+                                "{:?} Join: {}",
+                                join_type,
+                                join_expr.join(", ")
+                            ),
+                        }
                     }
+                    LogicalPlan::CrossJoin { .. } => write!(f, "CrossJoin:"),
                     LogicalPlan::Repartition {
                         partitioning_scheme,
                         ..
@@ -693,7 +842,24 @@ impl LogicalPlan {
                     LogicalPlan::CreateExternalTable { ref name, .. } => {
                         write!(f, "CreateExternalTable: {:?}", name)
                     }
+                    LogicalPlan::CreateMemoryTable { ref name, .. } => {
+                        write!(f, "CreateMemoryTable: {:?}", name)
+                    }
+                    LogicalPlan::CreateView { ref name, .. } => {
+                        write!(f, "CreateView: {:?}", name)
+                    }
+                    LogicalPlan::Insert { ref table_name, .. } => {
+                        write!(f, "Insert: {:?}", table_name)
+                    }
+                    LogicalPlan::CopyTo {
+                        ref path,
+                        ref options,
+                        ..
+                    } => {
+                        write!(f, "CopyTo: {:?} format={:?}", path, options.format)
+                    }
                     LogicalPlan::Explain { .. } => write!(f, "Explain"),
+                    LogicalPlan::Analyze { .. } => write!(f, "Analyze"),
                     LogicalPlan::Union { .. } => write!(f, "Union"),
                     LogicalPlan::Extension { ref node } => node.fmt_for_explain(f),
                 }
@@ -709,6 +875,40 @@ impl fmt::Debug for LogicalPlan {
     }
 }
 
+/// A subquery used as an expression, e.g. in `EXISTS (SELECT ...)` or
+/// `x IN (SELECT ...)`.
+///
+/// The subquery may reference columns of the enclosing query in its
+/// `WHERE` clause, in which case it is *correlated*.
+#[derive(Clone)]
+pub struct Subquery {
+    /// The plan of the subquery
+    pub subquery: Arc<LogicalPlan>,
+}
+
+impl Subquery {
+    /// Create a new subquery from its logical plan
+    pub fn new(plan: LogicalPlan) -> Self {
+        Self {
+            subquery: Arc::new(plan),
+        }
+    }
+}
+
+impl PartialEq for Subquery {
+    fn eq(&self, other: &Self) -> bool {
+        // `LogicalPlan` does not implement `PartialEq`, so subqueries are only
+        // equal to their clones
+        Arc::ptr_eq(&self.subquery, &other.subquery)
+    }
+}
+
+impl fmt::Debug for Subquery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<subquery>")
+    }
+}
+
 /// Represents which type of plan
 #[derive(Debug, Clone, PartialEq)]
 pub enum PlanType {
@@ -0,0 +1,447 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Common subexpression elimination rule computes subexpressions that are used more than
+//! once within a plan node a single time, in a projection below that node.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::logical_plan::{
+    col, Expr, ExprRewriter, ExpressionVisitor, LogicalPlan, LogicalPlanBuilder,
+    Recursion,
+};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+
+/// Optimizer that evaluates repeated subexpressions of a `Projection`, `Filter` or
+/// `Aggregate` only once.
+///
+/// For example, the projection of `SELECT a + b, (a + b) * 2 FROM t` is rewritten so that
+/// `a + b` is read from a projection below it that computes it once:
+///
+/// ```text
+/// Projection: #a Plus b, #a Plus b Multiply Int64(2)
+///   Projection: #a Plus #b AS a Plus b
+///     ...
+/// ```
+///
+/// Because the new column is named after the expression it replaces, the output schema
+/// of the rewritten node does not change.
+///
+/// Only deterministic expressions are shared: scalar UDFs (whose volatility is
+/// unknown), aggregates, window functions and subqueries are never extracted, and
+/// neither are expressions that only appear in a branch of a `CASE`, as these may not
+/// be safe to evaluate for every row.
+pub struct CommonSubexprEliminate {}
+
+impl CommonSubexprEliminate {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for CommonSubexprEliminate {
+    fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        match plan {
+            LogicalPlan::Projection {
+                expr,
+                input,
+                schema,
+            } => {
+                let input = self.optimize(input)?;
+                let (expr, input) = match eliminate(expr, &input, false)? {
+                    Some((expr, input)) => (expr, input),
+                    None => (expr.clone(), input),
+                };
+                Ok(LogicalPlan::Projection {
+                    expr,
+                    input: Arc::new(input),
+                    schema: schema.clone(),
+                })
+            }
+            LogicalPlan::Filter { predicate, input } => {
+                let input = self.optimize(input)?;
+                match eliminate(std::slice::from_ref(predicate), &input, true)? {
+                    Some((predicate, new_input)) => {
+                        // the filter passes the extra columns through, so restore the
+                        // original columns above it
+                        let columns = input
+                            .schema()
+                            .fields()
+                            .iter()
+                            .map(|f| col(f.name()))
+                            .collect::<Vec<_>>();
+                        LogicalPlanBuilder::from(&new_input)
+                            .filter(predicate[0].clone())?
+                            .project(columns)?
+                            .build()
+                    }
+                    None => Ok(LogicalPlan::Filter {
+                        predicate: predicate.clone(),
+                        input: Arc::new(input),
+                    }),
+                }
+            }
+            LogicalPlan::Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                schema,
+            } => {
+                let input = self.optimize(input)?;
+                let all_expr = group_expr
+                    .iter()
+                    .chain(aggr_expr.iter())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let (mut group_expr, input) = match eliminate(&all_expr, &input, false)? {
+                    Some((expr, input)) => (expr, input),
+                    None => (all_expr, input),
+                };
+                let aggr_expr = group_expr.split_off(group_expr.len() - aggr_expr.len());
+                Ok(LogicalPlan::Aggregate {
+                    input: Arc::new(input),
+                    group_expr,
+                    aggr_expr,
+                    schema: schema.clone(),
+                })
+            }
+            // Rest: recurse into plan, apply optimization where possible
+            LogicalPlan::Window { .. }
+            | LogicalPlan::Repartition { .. }
+            | LogicalPlan::CreateExternalTable { .. }
+            | LogicalPlan::CreateMemoryTable { .. }
+            | LogicalPlan::CreateView { .. }
+            | LogicalPlan::Insert { .. }
+            | LogicalPlan::CopyTo { .. }
+            | LogicalPlan::Extension { .. }
+            | LogicalPlan::Sort { .. }
+            | LogicalPlan::Explain { .. }
+            | LogicalPlan::Analyze { .. }
+            | LogicalPlan::Limit { .. }
+            | LogicalPlan::Union { .. }
+            | LogicalPlan::Join { .. }
+            | LogicalPlan::CrossJoin { .. } => utils::optimize_children(self, plan),
+            LogicalPlan::TableScan { .. } | LogicalPlan::EmptyRelation { .. } => {
+                Ok(plan.clone())
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "common_subexpr_eliminate"
+    }
+}
+
+/// Finds the subexpressions of `exprs` that occur more than once and computes them in a
+/// projection on top of `input`. Returns the rewritten expressions and the new input,
+/// or `None` if there is nothing to share. When `keep_all_columns` is false, the new
+/// projection only passes through the input columns the rewritten expressions use.
+fn eliminate(
+    exprs: &[Expr],
+    input: &LogicalPlan,
+    keep_all_columns: bool,
+) -> Result<Option<(Vec<Expr>, LogicalPlan)>> {
+    let input_schema = input.schema();
+
+    let mut counter = SubexprCounter::default();
+    for expr in exprs {
+        counter = expr.accept(counter)?;
+    }
+
+    // name the new columns after the expression they compute, so that rewritten
+    // expressions keep their names
+    let mut common = HashMap::new();
+    for (key, expr) in &counter.exprs {
+        if counter.counts[key] < 2 {
+            continue;
+        }
+        let name = expr.name(input_schema)?;
+        if input_schema.field_with_unqualified_name(&name).is_err() {
+            common.insert(key.clone(), name);
+        }
+    }
+    if common.is_empty() {
+        return Ok(None);
+    }
+
+    let mut rewriter = SubexprRewriter {
+        common: &common,
+        used: HashSet::new(),
+        stack: vec![],
+        replacing: 0,
+    };
+    let new_exprs = exprs
+        .iter()
+        .map(|e| e.clone().rewrite(&mut rewriter))
+        .collect::<Result<Vec<_>>>()?;
+    let used = rewriter.used;
+
+    let mut columns = HashSet::new();
+    if !keep_all_columns {
+        utils::exprlist_to_column_names(&new_exprs, &mut columns)?;
+    }
+    let mut projection = input_schema
+        .fields()
+        .iter()
+        .filter(|f| keep_all_columns || columns.contains(f.name()))
+        .map(|f| col(f.name()))
+        .collect::<Vec<_>>();
+    projection.extend(
+        counter
+            .exprs
+            .into_iter()
+            .filter(|(key, _)| used.contains(key))
+            .map(|(key, expr)| expr.alias(&common[&key])),
+    );
+
+    let new_input = LogicalPlanBuilder::from(input)
+        .project(projection)?
+        .build()?;
+    Ok(Some((new_exprs, new_input)))
+}
+
+/// Key identifying equal expressions
+fn expr_key(expr: &Expr) -> String {
+    format!("{:?}", expr)
+}
+
+/// Counts the occurrences of every subexpression that can be shared
+#[derive(Default)]
+struct SubexprCounter {
+    /// Candidate subexpressions in the order they are first seen
+    exprs: Vec<(String, Expr)>,
+    /// Number of occurrences of each candidate
+    counts: HashMap<String, usize>,
+    /// For each expression being visited, whether it is deterministic so far
+    stack: Vec<bool>,
+    /// Number of `CASE` expressions being visited
+    conditional: usize,
+}
+
+impl ExpressionVisitor for SubexprCounter {
+    fn pre_visit(mut self, expr: &Expr) -> Result<Recursion<Self>> {
+        match expr {
+            Expr::Exists { .. } | Expr::InSubquery { .. } | Expr::ScalarSubquery(_) => {
+                if let Some(parent) = self.stack.last_mut() {
+                    *parent = false;
+                }
+                return Ok(Recursion::Stop(self));
+            }
+            Expr::Case { .. } => self.conditional += 1,
+            _ => {}
+        }
+        self.stack.push(true);
+        Ok(Recursion::Continue(self))
+    }
+
+    fn post_visit(mut self, expr: &Expr) -> Result<Self> {
+        if let Expr::Case { .. } = expr {
+            self.conditional -= 1;
+        }
+        let deterministic = self.stack.pop().unwrap_or(false)
+            && !matches!(
+                expr,
+                Expr::ScalarUDF { .. }
+                    | Expr::AggregateFunction { .. }
+                    | Expr::AggregateUDF { .. }
+                    | Expr::WindowFunction { .. }
+            );
+        if !deterministic {
+            if let Some(parent) = self.stack.last_mut() {
+                *parent = false;
+            }
+        } else if self.conditional == 0 && is_candidate(expr) {
+            let key = expr_key(expr);
+            let count = self.counts.entry(key.clone()).or_insert(0);
+            if *count == 0 {
+                self.exprs.push((key, expr.clone()));
+            }
+            *count += 1;
+        }
+        Ok(self)
+    }
+}
+
+/// Whether computing `expr` once is worth a new column
+fn is_candidate(expr: &Expr) -> bool {
+    !matches!(
+        expr,
+        Expr::Alias(..)
+            | Expr::Column(_)
+            | Expr::ScalarVariable(_)
+            | Expr::Literal(_)
+            | Expr::Sort { .. }
+            | Expr::GroupingSet(_)
+            | Expr::Wildcard
+    )
+}
+
+/// Replaces the outermost occurrences of common subexpressions with their columns
+struct SubexprRewriter<'a> {
+    /// Key and column name of the common subexpressions
+    common: &'a HashMap<String, String>,
+    /// Keys of the common subexpressions that were replaced
+    used: HashSet<String>,
+    /// For each expression being rewritten, the key it is replaced by, if any
+    stack: Vec<Option<String>>,
+    /// Number of expressions being rewritten that will be replaced
+    replacing: usize,
+}
+
+impl<'a> ExprRewriter for SubexprRewriter<'a> {
+    fn pre_visit(&mut self, expr: &Expr) -> Result<bool> {
+        let key = expr_key(expr);
+        if self.replacing == 0 && self.common.contains_key(&key) {
+            self.replacing += 1;
+            self.stack.push(Some(key));
+        } else {
+            self.stack.push(None);
+        }
+        Ok(true)
+    }
+
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        match self.stack.pop().flatten() {
+            Some(key) => {
+                self.replacing -= 1;
+                let column = col(&self.common[&key]);
+                self.used.insert(key);
+                Ok(column)
+            }
+            None => Ok(expr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{lit, sum, when};
+    use crate::test::*;
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let rule = CommonSubexprEliminate::new();
+        let optimized_plan = rule.optimize(plan).expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+        assert_eq!(optimized_plan.schema(), plan.schema());
+    }
+
+    #[test]
+    fn projection_common_subexpr() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![
+                col("a") + col("b"),
+                (col("a") + col("b")) * lit(2),
+                col("c"),
+            ])?
+            .build()?;
+
+        let expected = "Projection: #a Plus b, #a Plus b Multiply Int32(2), #c\
+        \n  Projection: #c, #a Plus #b AS a Plus b\
+        \n    TableScan: test projection=None";
+
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn filter_common_subexpr() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(
+                (col("a") + col("b"))
+                    .gt(lit(10))
+                    .and((col("a") + col("b")).lt(lit(20))),
+            )?
+            .build()?;
+
+        let expected = "Projection: #a, #b, #c\
+        \n  Filter: #a Plus b Gt Int32(10) And #a Plus b Lt Int32(20)\
+        \n    Projection: #a, #b, #c, #a Plus #b AS a Plus b\
+        \n      TableScan: test projection=None";
+
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_common_subexpr() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .aggregate(
+                vec![col("a") * col("b")],
+                vec![sum(col("a") * col("b") + col("c"))],
+            )?
+            .build()?;
+
+        let expected =
+            "Aggregate: groupBy=[[#a Multiply b]], aggr=[[SUM(#a Multiply b Plus #c)]]\
+        \n  Projection: #c, #a Multiply #b AS a Multiply b\
+        \n    TableScan: test projection=None";
+
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn outermost_common_subexpr() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        // only the largest repeated expression is computed
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![
+                ((col("a") + col("b")) * col("c")).alias("x"),
+                ((col("a") + col("b")) * col("c")).alias("y"),
+            ])?
+            .build()?;
+
+        let expected = "Projection: #a Plus b Multiply c AS x, #a Plus b Multiply c AS y\
+        \n  Projection: #a Plus #b Multiply #c AS a Plus b Multiply c\
+        \n    TableScan: test projection=None";
+
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn no_common_subexpr() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        // columns, literals and expressions only inside CASE branches are not shared
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![
+                col("a"),
+                col("a") + lit(1),
+                when(col("b").gt(lit(0)), col("c") / col("b")).end()?,
+                when(col("b").lt(lit(0)), col("c") / col("b")).end()?,
+            ])?
+            .build()?;
+
+        let expected = format!("{:?}", plan);
+        assert_optimized_plan_eq(&plan, &expected);
+        Ok(())
+    }
+}
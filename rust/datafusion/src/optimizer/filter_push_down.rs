@@ -15,8 +15,8 @@
 //! Filter Push Down optimizer rule ensures that filters are applied as early as possible in the plan
 
 use crate::datasource::datasource::TableProviderFilterPushDown;
-use crate::logical_plan::{and, LogicalPlan};
-use crate::logical_plan::{DFSchema, Expr};
+use crate::logical_plan::{and, JoinType, LogicalPlan};
+use crate::logical_plan::{DFSchema, Expr, GROUPING_ID_COLUMN};
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::utils;
 use crate::{error::Result, logical_plan::Operator};
@@ -87,6 +87,7 @@ fn get_join_predicates<'a>(
     state: &'a State,
     left: &DFSchema,
     right: &DFSchema,
+    join_type: &JoinType,
 ) -> (
     Vec<&'a HashSet<String>>,
     Vec<&'a HashSet<String>>,
@@ -118,22 +119,29 @@ fn get_join_predicates<'a>(
         })
         .collect::<Vec<_>>();
 
+    // predicates cannot be pushed to the side of an outer join that is padded with nulls
+    let push_to_left = matches!(
+        join_type,
+        JoinType::Inner | JoinType::Left | JoinType::Semi | JoinType::Anti
+    );
+    let push_to_right = matches!(join_type, JoinType::Inner | JoinType::Right);
+
     let pushable_to_left = filters
         .iter()
-        .filter(|(_, (columns, left, _))| left.len() == columns.len())
+        .filter(|(_, (columns, left, _))| push_to_left && left.len() == columns.len())
         .map(|((_, b), _)| *b)
         .collect();
     let pushable_to_right = filters
         .iter()
-        .filter(|(_, (columns, _, right))| right.len() == columns.len())
+        .filter(|(_, (columns, _, right))| push_to_right && right.len() == columns.len())
         .map(|((_, b), _)| *b)
         .collect();
     let keep = filters
         .iter()
         .filter(|(_, (columns, left, right))| {
             // predicates whose columns are not in only one side of the join need to remain
-            let all_in_left = left.len() == columns.len();
-            let all_in_right = right.len() == columns.len();
+            let all_in_left = push_to_left && left.len() == columns.len();
+            let all_in_right = push_to_right && right.len() == columns.len();
             !all_in_left && !all_in_right
         })
         .map(|((ref a, ref b), _)| (a, b))
@@ -231,6 +239,40 @@ fn split_members<'a>(predicate: &'a Expr, predicates: &mut Vec<&'a Expr>) {
     }
 }
 
+/// pushes the predicates of `state` to the sides of a join where possible
+fn optimize_join(
+    mut state: State,
+    plan: &LogicalPlan,
+    left: &LogicalPlan,
+    right: &LogicalPlan,
+    join_type: &JoinType,
+) -> Result<LogicalPlan> {
+    let (pushable_to_left, pushable_to_right, keep) =
+        get_join_predicates(&state, left.schema(), right.schema(), join_type);
+
+    let mut left_state = state.clone();
+    left_state.filters = keep_filters(&left_state.filters, &pushable_to_left);
+    let left = optimize(left, left_state)?;
+
+    let mut right_state = state.clone();
+    right_state.filters = keep_filters(&right_state.filters, &pushable_to_right);
+    let right = optimize(right, right_state)?;
+
+    // create a new Join with the new `left` and `right`
+    let expr = plan.expressions();
+    let plan = utils::from_plan(plan, &expr, &[left, right])?;
+
+    if keep.0.is_empty() {
+        Ok(plan)
+    } else {
+        // wrap the join on the filter whose predicates must be kept
+        let plan = add_filter(plan, &keep.0);
+        state.filters = remove_filters(&state.filters, &keep.1);
+
+        Ok(plan)
+    }
+}
+
 fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
     match plan {
         LogicalPlan::Filter { input, predicate } => {
@@ -282,7 +324,10 @@ fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
             utils::from_plan(&plan, &expr, &[new_input])
         }
         LogicalPlan::Aggregate {
-            input, aggr_expr, ..
+            input,
+            group_expr,
+            aggr_expr,
+            ..
         } => {
             // An aggregate's aggreagate columns are _not_ filter-commutable => collect these:
             // * columns whose aggregation expression depends on
@@ -298,6 +343,13 @@ fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
                 .collect::<Result<HashSet<_>>>()?;
             used_columns.extend(agg_columns);
 
+            // the grouping columns of grouping sets are null in some groups, and
+            // are thus not filter-commutable either
+            if let [Expr::GroupingSet(_)] = group_expr.as_slice() {
+                utils::exprlist_to_column_names(group_expr, &mut used_columns)?;
+                used_columns.insert(GROUPING_ID_COLUMN.to_string());
+            }
+
             issue_filters(state, used_columns, plan)
         }
         LogicalPlan::Sort { .. } => {
@@ -314,31 +366,15 @@ fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
                 .collect::<HashSet<_>>();
             issue_filters(state, used_columns, plan)
         }
-        LogicalPlan::Join { left, right, .. } => {
-            let (pushable_to_left, pushable_to_right, keep) =
-                get_join_predicates(&state, &left.schema(), &right.schema());
-
-            let mut left_state = state.clone();
-            left_state.filters = keep_filters(&left_state.filters, &pushable_to_left);
-            let left = optimize(left, left_state)?;
-
-            let mut right_state = state.clone();
-            right_state.filters = keep_filters(&right_state.filters, &pushable_to_right);
-            let right = optimize(right, right_state)?;
-
-            // create a new Join with the new `left` and `right`
-            let expr = plan.expressions();
-            let plan = utils::from_plan(&plan, &expr, &[left, right])?;
-
-            if keep.0.is_empty() {
-                Ok(plan)
-            } else {
-                // wrap the join on the filter whose predicates must be kept
-                let plan = add_filter(plan, &keep.0);
-                state.filters = remove_filters(&state.filters, &keep.1);
-
-                Ok(plan)
-            }
+        LogicalPlan::Join {
+            left,
+            right,
+            join_type,
+            ..
+        } => optimize_join(state, plan, left, right, join_type),
+        // a cross join behaves like an inner join without keys
+        LogicalPlan::CrossJoin { left, right, .. } => {
+            optimize_join(state, plan, left, right, &JoinType::Inner)
         }
         LogicalPlan::TableScan {
             source,
@@ -434,7 +470,9 @@ mod tests {
     use super::*;
     use crate::datasource::datasource::Statistics;
     use crate::datasource::TableProvider;
-    use crate::logical_plan::{lit, sum, DFSchema, Expr, LogicalPlanBuilder, Operator};
+    use crate::logical_plan::{
+        and, lit, sum, DFSchema, Expr, LogicalPlanBuilder, Operator,
+    };
     use crate::physical_plan::ExecutionPlan;
     use crate::test::*;
     use crate::{logical_plan::col, prelude::JoinType};
@@ -922,6 +960,62 @@ mod tests {
         Ok(())
     }
 
+    /// filters on the non-preserved side of an outer join must not be pushed down
+    #[test]
+    fn filter_left_join_on_one_side() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let left = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![col("a"), col("b")])?
+            .build()?;
+        let right = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![col("a"), col("c")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&left)
+            .join(&right, JoinType::Left, &["a"], &["a"])?
+            .filter(col("b").lt_eq(lit(1i64)))?
+            .filter(col("c").is_null())?
+            .build()?;
+
+        let expected = "\
+        Filter: #c IS NULL\
+        \n  Left Join: a = a\
+        \n    Projection: #a, #b\
+        \n      Filter: #b LtEq Int64(1)\
+        \n        TableScan: test projection=None\
+        \n    Projection: #a, #c\
+        \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    /// verifies that a predicate on one side of a cross join is pushed to that side,
+    /// while a predicate on both sides is kept above the join
+    #[test]
+    fn filter_cross_join() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let left = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![col("a"), col("b")])?
+            .build()?;
+        let right = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![col("c")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&left)
+            .cross_join(&right)?
+            .filter(and(col("a").lt(col("c")), col("c").lt_eq(lit(1i64))))?
+            .build()?;
+
+        let expected = "\
+        Filter: #a Lt #c\
+        \n  CrossJoin:\
+        \n    Projection: #a, #b\
+        \n      TableScan: test projection=None\
+        \n    Projection: #c\
+        \n      Filter: #c LtEq Int64(1)\
+        \n        TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
     struct PushDownProvider {
         pub filter_support: TableProviderFilterPushDown,
     }
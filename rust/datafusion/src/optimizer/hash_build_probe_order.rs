@@ -37,7 +37,7 @@ use super::utils;
 pub struct HashBuildProbeOrder {}
 
 // Gets exact number of rows, if known by the statistics of the underlying
-fn get_num_rows(logical_plan: &LogicalPlan) -> Option<usize> {
+pub(crate) fn get_num_rows(logical_plan: &LogicalPlan) -> Option<usize> {
     match logical_plan {
         LogicalPlan::TableScan { source, .. } => source.statistics().num_rows,
         LogicalPlan::EmptyRelation {
@@ -67,17 +67,30 @@ fn get_num_rows(logical_plan: &LogicalPlan) -> Option<usize> {
             // we cannot predict the cardinality of the join output
             None
         }
+        LogicalPlan::CrossJoin { left, right, .. } => {
+            // number of rows of the cross join is the product of both inputs
+            match (get_num_rows(left), get_num_rows(right)) {
+                (Some(l), Some(r)) => Some(l * r),
+                _ => None,
+            }
+        }
         LogicalPlan::Repartition { .. } => {
             // we cannot predict how rows will be repartitioned
             None
         }
         // the following operators are special cases and not querying data
         LogicalPlan::CreateExternalTable { .. } => None,
+        LogicalPlan::CreateMemoryTable { .. } => None,
+        LogicalPlan::CreateView { .. } => None,
+        LogicalPlan::Insert { .. } => None,
+        LogicalPlan::CopyTo { .. } => None,
         LogicalPlan::Explain { .. } => None,
+        LogicalPlan::Analyze { .. } => None,
         // we do not support estimating rows with extensions yet
         LogicalPlan::Extension { .. } => None,
         // the following operators do not modify row count in any way
         LogicalPlan::Projection { input, .. } => get_num_rows(input),
+        LogicalPlan::Window { input, .. } => get_num_rows(input),
         LogicalPlan::Sort { input, .. } => get_num_rows(input),
         // Add number of rows of below plans
         LogicalPlan::Union { inputs, .. } => {
@@ -111,11 +124,12 @@ impl OptimizerRule for HashBuildProbeOrder {
                 right,
                 on,
                 join_type,
+                null_equals_null,
                 schema,
             } => {
                 let left = self.optimize(left)?;
                 let right = self.optimize(right)?;
-                if should_swap_join_order(&left, &right) {
+                if supports_swap(*join_type) && should_swap_join_order(&left, &right) {
                     // Swap left and right, change join type and (equi-)join key order
                     Ok(LogicalPlan::Join {
                         left: Arc::new(right),
@@ -125,6 +139,7 @@ impl OptimizerRule for HashBuildProbeOrder {
                             .map(|(l, r)| (r.to_string(), l.to_string()))
                             .collect(),
                         join_type: swap_join_type(*join_type),
+                        null_equals_null: *null_equals_null,
                         schema: schema.clone(),
                     })
                 } else {
@@ -134,12 +149,37 @@ impl OptimizerRule for HashBuildProbeOrder {
                         right: Arc::new(right),
                         on: on.clone(),
                         join_type: *join_type,
+                        null_equals_null: *null_equals_null,
+                        schema: schema.clone(),
+                    })
+                }
+            }
+            LogicalPlan::CrossJoin {
+                left,
+                right,
+                schema,
+            } => {
+                let left = self.optimize(left)?;
+                let right = self.optimize(right)?;
+                if should_swap_join_order(&left, &right) {
+                    // Swap left and right
+                    Ok(LogicalPlan::CrossJoin {
+                        left: Arc::new(right),
+                        right: Arc::new(left),
+                        schema: schema.clone(),
+                    })
+                } else {
+                    // Keep join as is
+                    Ok(LogicalPlan::CrossJoin {
+                        left: Arc::new(left),
+                        right: Arc::new(right),
                         schema: schema.clone(),
                     })
                 }
             }
             // Rest: recurse into plan, apply optimization where possible
             LogicalPlan::Projection { .. }
+            | LogicalPlan::Window { .. }
             | LogicalPlan::Aggregate { .. }
             | LogicalPlan::TableScan { .. }
             | LogicalPlan::Limit { .. }
@@ -148,7 +188,12 @@ impl OptimizerRule for HashBuildProbeOrder {
             | LogicalPlan::EmptyRelation { .. }
             | LogicalPlan::Sort { .. }
             | LogicalPlan::CreateExternalTable { .. }
+            | LogicalPlan::CreateMemoryTable { .. }
+            | LogicalPlan::CreateView { .. }
+            | LogicalPlan::Insert { .. }
+            | LogicalPlan::CopyTo { .. }
             | LogicalPlan::Explain { .. }
+            | LogicalPlan::Analyze { .. }
             | LogicalPlan::Union { .. }
             | LogicalPlan::Extension { .. } => {
                 let expr = plan.expressions();
@@ -173,11 +218,21 @@ impl HashBuildProbeOrder {
     }
 }
 
+/// Semi and anti joins only return the rows of their left side, so their
+/// inputs cannot be swapped
+fn supports_swap(join_type: JoinType) -> bool {
+    !matches!(join_type, JoinType::Semi | JoinType::Anti)
+}
+
 fn swap_join_type(join_type: JoinType) -> JoinType {
     match join_type {
         JoinType::Inner => JoinType::Inner,
         JoinType::Left => JoinType::Right,
         JoinType::Right => JoinType::Left,
+        JoinType::Full => JoinType::Full,
+        JoinType::Semi | JoinType::Anti => {
+            unreachable!("semi and anti joins cannot be swapped")
+        }
     }
 }
 
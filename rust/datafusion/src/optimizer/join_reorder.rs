@@ -0,0 +1,963 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Optimizer rule that reorders chains of inner joins so that the
+//! intermediate results are as small as possible. The cardinality of
+//! the joined relations is estimated from the `Statistics` of the
+//! underlying `TableProvider`s and propagated through filters,
+//! projections, aggregates and joins.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::datasource::datasource::{
+    scalar_to_f64, ColumnEstimate, DEFAULT_EQUALITY_SELECTIVITY,
+    DEFAULT_RANGE_SELECTIVITY, DEFAULT_SELECTIVITY,
+};
+use crate::error::Result;
+use crate::logical_plan::{Expr, JoinType, LogicalPlan, LogicalPlanBuilder, Operator};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+use crate::scalar::ScalarValue;
+
+/// Estimated number of rows and column statistics of a (logical) plan
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PlanEstimate {
+    /// Estimated number of rows
+    pub num_rows: f64,
+    /// Column estimates by (unqualified) column name
+    pub columns: HashMap<String, ColumnEstimate>,
+}
+
+impl PlanEstimate {
+    fn new(num_rows: f64) -> Self {
+        Self {
+            num_rows,
+            columns: HashMap::new(),
+        }
+    }
+
+    /// The estimated number of distinct values of a column, which is at
+    /// most the number of rows
+    fn distinct_count(&self, name: &str) -> Option<f64> {
+        self.columns
+            .get(name)
+            .and_then(|c| c.distinct_count)
+            .map(|d| d.min(self.num_rows).max(1.0))
+    }
+
+    /// The estimate of a column, unknown if the column has no estimate
+    fn column(&self, name: &str) -> ColumnEstimate {
+        ColumnEstimate {
+            distinct_count: self.distinct_count(name),
+            ..self.columns.get(name).cloned().unwrap_or_default()
+        }
+    }
+
+    /// Scale the number of rows by `selectivity`, capping the distinct
+    /// counts of all columns by the new number of rows
+    fn scale(&mut self, selectivity: f64) {
+        self.num_rows *= selectivity.clamp(0.0, 1.0);
+        let num_rows = self.num_rows;
+        self.columns.values_mut().for_each(|c| {
+            c.distinct_count = c.distinct_count.map(|d| d.min(num_rows));
+        });
+    }
+}
+
+/// Estimates the number of rows and the column statistics of a plan.
+/// Returns `None` if the statistics of one of the scanned tables, or the
+/// effect of one of the operators, are unknown.
+pub(crate) fn estimate_plan(plan: &LogicalPlan) -> Option<PlanEstimate> {
+    match plan {
+        LogicalPlan::TableScan { source, limit, .. } => {
+            let statistics = source.statistics();
+            let mut estimate = PlanEstimate::new(statistics.num_rows? as f64);
+            if let Some(column_statistics) = &statistics.column_statistics {
+                let schema = source.schema();
+                for (field, stats) in schema.fields().iter().zip(column_statistics) {
+                    let column =
+                        ColumnEstimate::from_statistics(stats, statistics.num_rows);
+                    estimate.columns.insert(field.name().clone(), column);
+                }
+            }
+            if let Some(limit) = limit {
+                estimate.num_rows = estimate.num_rows.min(*limit as f64);
+            }
+            Some(estimate)
+        }
+        LogicalPlan::EmptyRelation {
+            produce_one_row, ..
+        } => Some(PlanEstimate::new(if *produce_one_row { 1.0 } else { 0.0 })),
+        LogicalPlan::Projection { expr, input, .. } => {
+            let input_estimate = estimate_plan(input)?;
+            let mut estimate = PlanEstimate::new(input_estimate.num_rows);
+            for e in expr {
+                let (name, column) = match e {
+                    Expr::Column(name) => (name, name),
+                    Expr::Alias(inner, alias) => match inner.as_ref() {
+                        Expr::Column(name) => (alias, name),
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                if let Some(c) = input_estimate.columns.get(column) {
+                    estimate.columns.insert(name.clone(), c.clone());
+                }
+            }
+            Some(estimate)
+        }
+        LogicalPlan::Filter { predicate, input } => {
+            let mut estimate = estimate_plan(input)?;
+            apply_predicate(predicate, &mut estimate);
+            Some(estimate)
+        }
+        LogicalPlan::Aggregate {
+            input, group_expr, ..
+        } => {
+            let input_estimate = estimate_plan(input)?;
+            let mut estimate = PlanEstimate::new(1.0);
+            for e in group_expr {
+                let name = e.name(input.schema()).ok()?;
+                // the number of groups is bounded by the number of input rows,
+                // in particular if the distinct count of a key is unknown
+                let distinct_count = input_estimate
+                    .distinct_count(&name)
+                    .unwrap_or(input_estimate.num_rows);
+                estimate.num_rows *= distinct_count;
+                if let Some(c) = input_estimate.columns.get(&name) {
+                    estimate.columns.insert(name, c.clone());
+                }
+            }
+            if !group_expr.is_empty() {
+                estimate.scale(input_estimate.num_rows / estimate.num_rows.max(1.0));
+            }
+            Some(estimate)
+        }
+        LogicalPlan::Join {
+            left,
+            right,
+            on,
+            join_type,
+            ..
+        } => {
+            let left = estimate_plan(left)?;
+            let right = estimate_plan(right)?;
+            Some(estimate_join(&left, &right, on, *join_type))
+        }
+        LogicalPlan::CrossJoin { left, right, .. } => {
+            let left = estimate_plan(left)?;
+            let right = estimate_plan(right)?;
+            Some(estimate_join(&left, &right, &[], JoinType::Inner))
+        }
+        LogicalPlan::Limit { n, input } => {
+            let mut estimate = estimate_plan(input)?;
+            if estimate.num_rows > 0.0 {
+                estimate.scale(*n as f64 / estimate.num_rows);
+            }
+            Some(estimate)
+        }
+        LogicalPlan::Union { inputs, .. } => {
+            let num_rows = inputs
+                .iter()
+                .map(|input| estimate_plan(input).map(|e| e.num_rows))
+                .sum::<Option<f64>>()?;
+            Some(PlanEstimate::new(num_rows))
+        }
+        // the following operators do not modify the rows
+        LogicalPlan::Sort { input, .. }
+        | LogicalPlan::Repartition { input, .. }
+        | LogicalPlan::Window { input, .. } => estimate_plan(input),
+        // the following operators are special cases and not querying data
+        LogicalPlan::CreateExternalTable { .. }
+        | LogicalPlan::CreateMemoryTable { .. }
+        | LogicalPlan::CreateView { .. }
+        | LogicalPlan::Insert { .. }
+        | LogicalPlan::CopyTo { .. }
+        | LogicalPlan::Explain { .. }
+        | LogicalPlan::Analyze { .. } => None,
+        // we do not support estimating extensions yet
+        LogicalPlan::Extension { .. } => None,
+    }
+}
+
+/// Estimates the result of joining two relations on the given columns.
+///
+/// For an equijoin, every value of the key with the fewest distinct values
+/// is assumed to match a value of the other key, so the number of rows is
+/// `left * right / max(distinct(left_key), distinct(right_key))`. A key
+/// with an unknown distinct count is assumed to be unique.
+fn estimate_join(
+    left: &PlanEstimate,
+    right: &PlanEstimate,
+    on: &[(String, String)],
+    join_type: JoinType,
+) -> PlanEstimate {
+    let mut inner_rows = left.num_rows * right.num_rows;
+    for (l, r) in on {
+        let left_distinct = left.distinct_count(l).unwrap_or(left.num_rows);
+        let right_distinct = right.distinct_count(r).unwrap_or(right.num_rows);
+        inner_rows /= left_distinct.max(right_distinct).max(1.0);
+    }
+
+    let num_rows = match join_type {
+        JoinType::Inner => inner_rows,
+        JoinType::Left => inner_rows.max(left.num_rows),
+        JoinType::Right => inner_rows.max(right.num_rows),
+        JoinType::Full => inner_rows.max(left.num_rows).max(right.num_rows),
+        JoinType::Semi => inner_rows.min(left.num_rows),
+        JoinType::Anti => left.num_rows,
+    };
+
+    let mut estimate = PlanEstimate::new(num_rows);
+    estimate.columns.extend(left.columns.clone());
+    if !matches!(join_type, JoinType::Semi | JoinType::Anti) {
+        for (name, column) in &right.columns {
+            estimate
+                .columns
+                .entry(name.clone())
+                .or_insert_with(|| column.clone());
+        }
+    }
+    // only the values present on both sides of the keys remain
+    for (l, r) in on {
+        if let (Some(ld), Some(rd)) = (left.distinct_count(l), right.distinct_count(r)) {
+            if let Some(c) = estimate.columns.get_mut(l) {
+                c.distinct_count = Some(ld.min(rd));
+            }
+        }
+    }
+    estimate.scale(1.0);
+    estimate
+}
+
+/// converts "A AND B AND C" => [A, B, C]
+fn split_members<'a>(predicate: &'a Expr, predicates: &mut Vec<&'a Expr>) {
+    match predicate {
+        Expr::BinaryExpr {
+            right,
+            op: Operator::And,
+            left,
+        } => {
+            split_members(left, predicates);
+            split_members(right, predicates);
+        }
+        other => predicates.push(other),
+    }
+}
+
+/// Reduces the estimated number of rows by the selectivity of `predicate`,
+/// and narrows the statistics of the columns it restricts
+fn apply_predicate(predicate: &Expr, estimate: &mut PlanEstimate) {
+    let mut conjuncts = vec![];
+    split_members(predicate, &mut conjuncts);
+    for conjunct in conjuncts {
+        let selectivity = selectivity(conjunct, estimate);
+        if let Some((name, op, value)) = column_comparison(conjunct) {
+            if let Some(c) = estimate.columns.get_mut(name) {
+                match (op, value) {
+                    (Operator::Eq, Some(v)) => {
+                        c.distinct_count = Some(1.0);
+                        c.min_value = Some(v);
+                        c.max_value = Some(v);
+                    }
+                    (Operator::Eq, None) => c.distinct_count = Some(1.0),
+                    (Operator::Lt, Some(v)) | (Operator::LtEq, Some(v)) => {
+                        c.max_value = Some(c.max_value.map_or(v, |max| max.min(v)));
+                    }
+                    (Operator::Gt, Some(v)) | (Operator::GtEq, Some(v)) => {
+                        c.min_value = Some(c.min_value.map_or(v, |min| min.max(v)));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        estimate.scale(selectivity);
+    }
+}
+
+/// Estimates the fraction of the rows for which `predicate` is true
+fn selectivity(predicate: &Expr, estimate: &PlanEstimate) -> f64 {
+    match predicate {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        } => selectivity(left, estimate) * selectivity(right, estimate),
+        Expr::BinaryExpr {
+            left,
+            op: Operator::Or,
+            right,
+        } => {
+            let l = selectivity(left, estimate);
+            let r = selectivity(right, estimate);
+            l + r - l * r
+        }
+        Expr::Not(expr) => 1.0 - selectivity(expr, estimate),
+        Expr::BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        } => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(l), Expr::Column(r)) => {
+                match (estimate.distinct_count(l), estimate.distinct_count(r)) {
+                    (Some(l), Some(r)) => 1.0 / l.max(r),
+                    (Some(d), None) | (None, Some(d)) => 1.0 / d,
+                    (None, None) => DEFAULT_EQUALITY_SELECTIVITY,
+                }
+            }
+            _ => comparison_selectivity(predicate, estimate),
+        },
+        Expr::BinaryExpr { .. } => comparison_selectivity(predicate, estimate),
+        Expr::IsNull(expr) => null_fraction(expr, estimate),
+        Expr::IsNotNull(expr) => 1.0 - null_fraction(expr, estimate),
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => {
+            let s = match (expr.as_ref(), literal_to_f64(low), literal_to_f64(high)) {
+                (Expr::Column(name), Some(low), Some(high)) => estimate
+                    .column(name)
+                    .range_selectivity(Some(low), Some(high)),
+                _ => DEFAULT_RANGE_SELECTIVITY,
+            };
+            if *negated {
+                1.0 - s
+            } else {
+                s
+            }
+        }
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => {
+            let eq = match expr.as_ref() {
+                Expr::Column(name) => estimate
+                    .distinct_count(name)
+                    .map_or(DEFAULT_EQUALITY_SELECTIVITY, |d| 1.0 / d),
+                _ => DEFAULT_EQUALITY_SELECTIVITY,
+            };
+            let s = (eq * list.len() as f64).min(1.0);
+            if *negated {
+                1.0 - s
+            } else {
+                s
+            }
+        }
+        Expr::Literal(ScalarValue::Boolean(Some(true))) => 1.0,
+        Expr::Literal(ScalarValue::Boolean(_)) => 0.0,
+        Expr::Alias(expr, _) => selectivity(expr, estimate),
+        _ => DEFAULT_SELECTIVITY,
+    }
+}
+
+/// The selectivity of comparing a column with a literal
+fn comparison_selectivity(predicate: &Expr, estimate: &PlanEstimate) -> f64 {
+    match column_comparison(predicate) {
+        Some((name, op, value)) => {
+            estimate.column(name).comparison_selectivity(op, value)
+        }
+        None => DEFAULT_SELECTIVITY,
+    }
+}
+
+fn null_fraction(expr: &Expr, estimate: &PlanEstimate) -> f64 {
+    match expr {
+        Expr::Column(name) => estimate.column(name).null_selectivity(),
+        _ => DEFAULT_EQUALITY_SELECTIVITY,
+    }
+}
+
+/// Matches `<column> <op> <literal>` and `<literal> <op> <column>`, returning
+/// the column, the operator as if the column was on the left, and the
+/// literal if it is numeric
+fn column_comparison(expr: &Expr) -> Option<(&str, Operator, Option<f64>)> {
+    match expr {
+        Expr::BinaryExpr { left, op, right } => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(name), Expr::Literal(_)) => {
+                Some((name, *op, literal_to_f64(right)))
+            }
+            (Expr::Literal(_), Expr::Column(name)) => {
+                let op = match op {
+                    Operator::Lt => Operator::Gt,
+                    Operator::LtEq => Operator::GtEq,
+                    Operator::Gt => Operator::Lt,
+                    Operator::GtEq => Operator::LtEq,
+                    op => *op,
+                };
+                Some((name, op, literal_to_f64(left)))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn literal_to_f64(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Literal(value) => scalar_to_f64(value),
+        _ => None,
+    }
+}
+
+/// JoinReorder reorders chains of inner joins (and cross joins) based on
+/// the estimated cardinality of the joined relations.
+///
+/// Starting with the pair of relations whose join is estimated to be the
+/// smallest, it greedily joins the relation that keeps the intermediate
+/// result smallest, preferring relations connected by a join condition over
+/// cross joins. The smaller side of every join is used as its build (left)
+/// side. The new order is only used if its estimated cost, the sum of the
+/// intermediate result sizes, is lower than that of the original order. If
+/// the statistics of one of the relations are unknown, the order stays the
+/// same.
+pub struct JoinReorder {}
+
+impl JoinReorder {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for JoinReorder {
+    fn name(&self) -> &str {
+        "join_reorder"
+    }
+
+    fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        if is_chain_join(plan) {
+            let mut leaves = vec![];
+            let mut conditions = vec![];
+            flatten_join_chain(plan, &mut leaves, &mut conditions);
+            let leaves = leaves
+                .into_iter()
+                .map(|leaf| self.optimize(leaf))
+                .collect::<Result<Vec<_>>>()?;
+
+            let plan = replace_leaves(plan, &mut leaves.iter())?;
+            return Ok(reorder_joins(&plan, &leaves, &conditions).unwrap_or(plan));
+        }
+
+        // apply the optimization to all inputs of the plan
+        let expr = plan.expressions();
+        let new_inputs = plan
+            .inputs()
+            .iter()
+            .map(|plan| self.optimize(plan))
+            .collect::<Result<Vec<_>>>()?;
+
+        utils::from_plan(plan, &expr, &new_inputs)
+    }
+}
+
+/// Whether the plan is a join that can be freely reordered with other
+/// joins of the same kind
+fn is_chain_join(plan: &LogicalPlan) -> bool {
+    matches!(
+        plan,
+        LogicalPlan::Join {
+            join_type: JoinType::Inner,
+            null_equals_null: false,
+            ..
+        } | LogicalPlan::CrossJoin { .. }
+    )
+}
+
+/// Collects the relations joined by a chain of inner and cross joins, as
+/// well as their join conditions
+fn flatten_join_chain<'a>(
+    plan: &'a LogicalPlan,
+    leaves: &mut Vec<&'a LogicalPlan>,
+    conditions: &mut Vec<(String, String)>,
+) {
+    match plan {
+        LogicalPlan::Join {
+            left, right, on, ..
+        } if is_chain_join(plan) => {
+            flatten_join_chain(left, leaves, conditions);
+            flatten_join_chain(right, leaves, conditions);
+            conditions.extend(on.iter().cloned());
+        }
+        LogicalPlan::CrossJoin { left, right, .. } => {
+            flatten_join_chain(left, leaves, conditions);
+            flatten_join_chain(right, leaves, conditions);
+        }
+        _ => leaves.push(plan),
+    }
+}
+
+/// Rebuilds a chain of joins with new leaves, in the order of
+/// `flatten_join_chain`
+fn replace_leaves<'a>(
+    plan: &LogicalPlan,
+    leaves: &mut impl Iterator<Item = &'a LogicalPlan>,
+) -> Result<LogicalPlan> {
+    if is_chain_join(plan) {
+        let new_inputs = plan
+            .inputs()
+            .iter()
+            .map(|input| replace_leaves(input, leaves))
+            .collect::<Result<Vec<_>>>()?;
+        utils::from_plan(plan, &plan.expressions(), &new_inputs)
+    } else {
+        // flatten_join_chain visits exactly as many leaves
+        Ok(leaves.next().unwrap().clone())
+    }
+}
+
+/// A set of joined relations
+struct JoinedRelations {
+    plan: LogicalPlan,
+    estimate: PlanEstimate,
+    /// The sum of the estimated sizes of the joins in `plan`
+    cost: f64,
+}
+
+impl JoinedRelations {
+    /// The join conditions that connect these relations with `other`, with
+    /// their columns on the side of the join they belong to
+    fn conditions_with<'a>(
+        &self,
+        other: &JoinedRelations,
+        conditions: &'a [(String, String)],
+    ) -> Vec<(usize, (&'a str, &'a str))> {
+        let left_schema = self.plan.schema();
+        let right_schema = other.plan.schema();
+        let has = |schema: &crate::logical_plan::DFSchemaRef, name: &str| {
+            schema.field_with_unqualified_name(name).is_ok()
+        };
+        conditions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (l, r))| {
+                if has(left_schema, l) && has(right_schema, r) {
+                    Some((i, (l.as_str(), r.as_str())))
+                } else if has(left_schema, r) && has(right_schema, l) {
+                    Some((i, (r.as_str(), l.as_str())))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Estimates the size of joining these relations with `other`
+    fn estimate_join_with(
+        &self,
+        other: &JoinedRelations,
+        conditions: &[(String, String)],
+    ) -> (PlanEstimate, bool) {
+        let on = self
+            .conditions_with(other, conditions)
+            .into_iter()
+            .map(|(_, (l, r))| (l.to_string(), r.to_string()))
+            .collect::<Vec<_>>();
+        let estimate =
+            estimate_join(&self.estimate, &other.estimate, &on, JoinType::Inner);
+        (estimate, !on.is_empty())
+    }
+
+    /// Joins these relations with `other`, using the smaller side as the
+    /// build side. Fails if the relations cannot be joined, e.g. because
+    /// they have columns with the same names that are not join keys.
+    fn join(
+        self,
+        other: JoinedRelations,
+        conditions: &[(String, String)],
+        used_conditions: &mut HashSet<usize>,
+    ) -> Result<JoinedRelations> {
+        let (left, right) = if other.estimate.num_rows < self.estimate.num_rows {
+            (other, self)
+        } else {
+            (self, other)
+        };
+
+        let mut left_keys = vec![];
+        let mut right_keys = vec![];
+        for (i, (l, r)) in left.conditions_with(&right, conditions) {
+            used_conditions.insert(i);
+            if !left_keys.iter().zip(&right_keys).any(|k| k == (&l, &r)) {
+                left_keys.push(l);
+                right_keys.push(r);
+            }
+        }
+
+        let builder = LogicalPlanBuilder::from(&left.plan);
+        let plan = if left_keys.is_empty() {
+            builder.cross_join(&right.plan)?.build()?
+        } else {
+            builder
+                .join(&right.plan, JoinType::Inner, &left_keys, &right_keys)?
+                .build()?
+        };
+        let on = left_keys
+            .iter()
+            .zip(&right_keys)
+            .map(|(l, r)| (l.to_string(), r.to_string()))
+            .collect::<Vec<_>>();
+        let estimate =
+            estimate_join(&left.estimate, &right.estimate, &on, JoinType::Inner);
+        let cost = left.cost + right.cost + estimate.num_rows;
+        Ok(JoinedRelations {
+            plan,
+            estimate,
+            cost,
+        })
+    }
+}
+
+/// The sum of the estimated sizes of the joins of a chain
+fn chain_cost(plan: &LogicalPlan) -> Option<f64> {
+    if is_chain_join(plan) {
+        let inputs_cost = plan
+            .inputs()
+            .into_iter()
+            .map(chain_cost)
+            .sum::<Option<f64>>()?;
+        Some(inputs_cost + estimate_plan(plan)?.num_rows)
+    } else {
+        Some(0.0)
+    }
+}
+
+/// Greedily reorders a chain of joins. Returns `None` if the statistics
+/// are not known, or if the order of the chain is already the best.
+fn reorder_joins(
+    plan: &LogicalPlan,
+    leaves: &[LogicalPlan],
+    conditions: &[(String, String)],
+) -> Option<LogicalPlan> {
+    // the order of two relations is left to HashBuildProbeOrder
+    if leaves.len() < 3 {
+        return None;
+    }
+    let original_cost = chain_cost(plan)?;
+
+    let mut remaining = leaves
+        .iter()
+        .map(|leaf| {
+            Some(JoinedRelations {
+                plan: leaf.clone(),
+                estimate: estimate_plan(leaf)?,
+                cost: 0.0,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    // start with the smallest join of two connected relations
+    let mut best: Option<(usize, usize, f64)> = None;
+    for i in 0..remaining.len() {
+        for j in i + 1..remaining.len() {
+            let (estimate, connected) =
+                remaining[i].estimate_join_with(&remaining[j], conditions);
+            let smaller = match best {
+                Some((_, _, rows)) => estimate.num_rows < rows,
+                None => true,
+            };
+            if connected && smaller {
+                best = Some((i, j, estimate.num_rows));
+            }
+        }
+    }
+    let (i, j, _) = best?;
+    let mut used_conditions = HashSet::new();
+    let right = remaining.remove(j);
+    let left = remaining.remove(i);
+    let mut joined = left.join(right, conditions, &mut used_conditions).ok()?;
+
+    while !remaining.is_empty() {
+        // prefer the connected relation that results in the smallest join,
+        // and otherwise cross join the smallest relation
+        let next = remaining
+            .iter()
+            .enumerate()
+            .map(|(i, relations)| {
+                let (estimate, connected) =
+                    joined.estimate_join_with(relations, conditions);
+                (i, !connected, estimate.num_rows)
+            })
+            .min_by(|(_, a_cross, a_rows), (_, b_cross, b_rows)| {
+                a_cross
+                    .cmp(b_cross)
+                    .then(a_rows.partial_cmp(b_rows).unwrap_or(Ordering::Equal))
+            })
+            .map(|(i, _, _)| i)?;
+        let relations = remaining.remove(next);
+        joined = joined
+            .join(relations, conditions, &mut used_conditions)
+            .ok()?;
+    }
+
+    // all join conditions must still be applied
+    if used_conditions.len() < conditions.len() || joined.cost >= original_cost {
+        return None;
+    }
+
+    // restore the original order of the columns
+    let schema = plan.schema();
+    if joined.plan.schema().fields() == schema.fields() {
+        Some(joined.plan)
+    } else {
+        Some(LogicalPlan::Projection {
+            expr: schema
+                .fields()
+                .iter()
+                .map(|f| Expr::Column(f.name().clone()))
+                .collect(),
+            input: Arc::new(joined.plan),
+            schema: schema.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::datasource::{ColumnStatistics, Statistics};
+    use crate::datasource::TableProvider;
+    use crate::logical_plan::{col, lit};
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+    /// A table with statistics, whose columns are all integers
+    struct StatisticsTable {
+        schema: SchemaRef,
+        statistics: Statistics,
+    }
+
+    impl StatisticsTable {
+        /// Columns are given by name, distinct count and max value, their
+        /// min value is 1
+        fn scan(
+            name: &str,
+            num_rows: usize,
+            columns: &[(&str, usize, i64)],
+        ) -> LogicalPlan {
+            let schema = Arc::new(Schema::new(
+                columns
+                    .iter()
+                    .map(|(name, _, _)| Field::new(name, DataType::Int64, false))
+                    .collect(),
+            ));
+            let column_statistics = columns
+                .iter()
+                .map(|(_, distinct_count, max)| ColumnStatistics {
+                    null_count: Some(0),
+                    min_value: Some(ScalarValue::Int64(Some(1))),
+                    max_value: Some(ScalarValue::Int64(Some(*max))),
+                    distinct_count: Some(*distinct_count),
+                })
+                .collect();
+            let table = StatisticsTable {
+                schema,
+                statistics: Statistics {
+                    num_rows: Some(num_rows),
+                    total_byte_size: None,
+                    column_statistics: Some(column_statistics),
+                },
+            };
+            LogicalPlanBuilder::scan(name, Arc::new(table), None)
+                .unwrap()
+                .build()
+                .unwrap()
+        }
+    }
+
+    impl TableProvider for StatisticsTable {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+
+        fn scan(
+            &self,
+            _projection: &Option<Vec<usize>>,
+            _batch_size: usize,
+            _filters: &[Expr],
+            _limit: Option<usize>,
+        ) -> Result<Arc<dyn crate::physical_plan::ExecutionPlan>> {
+            unimplemented!()
+        }
+
+        fn statistics(&self) -> Statistics {
+            self.statistics.clone()
+        }
+    }
+
+    fn optimize(plan: &LogicalPlan) -> LogicalPlan {
+        JoinReorder::new()
+            .optimize(plan)
+            .expect("failed to optimize plan")
+    }
+
+    #[test]
+    fn estimate_filter() -> Result<()> {
+        let scan = StatisticsTable::scan("t", 1000, &[("a", 100, 100), ("b", 10, 10)]);
+        let estimate = |predicate: Expr| -> Result<f64> {
+            let plan = LogicalPlanBuilder::from(&scan).filter(predicate)?.build()?;
+            Ok(estimate_plan(&plan).unwrap().num_rows.round())
+        };
+
+        assert_eq!(10.0, estimate(col("a").eq(lit(5i64)))?);
+        assert_eq!(0.0, estimate(col("a").eq(lit(500i64)))?);
+        assert_eq!(253.0, estimate(col("a").gt(lit(75i64)))?);
+        assert_eq!(242.0, estimate(lit(25i64).gt_eq(col("a")))?);
+        assert_eq!(
+            1.0,
+            estimate(col("a").eq(lit(5i64)).and(col("b").eq(lit(1i64))))?
+        );
+        assert_eq!(
+            109.0,
+            estimate(col("a").eq(lit(5i64)).or(col("b").eq(lit(1i64))))?
+        );
+        assert_eq!(0.0, estimate(col("a").is_null())?);
+
+        // the distinct count of a column is capped by the number of rows
+        let plan = LogicalPlanBuilder::from(&scan)
+            .filter(col("b").eq(lit(1i64)))?
+            .build()?;
+        let estimate = estimate_plan(&plan).unwrap();
+        assert_eq!(Some(100.0), estimate.distinct_count("a"));
+        assert_eq!(Some(1.0), estimate.distinct_count("b"));
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_aggregate_and_join() -> Result<()> {
+        let orders = StatisticsTable::scan(
+            "orders",
+            1000,
+            &[("o_id", 1000, 1000), ("o_cust", 50, 50)],
+        );
+        let customers = StatisticsTable::scan("customers", 100, &[("c_id", 100, 100)]);
+
+        let plan = LogicalPlanBuilder::from(&orders)
+            .aggregate(vec![col("o_cust")], vec![])?
+            .build()?;
+        assert_eq!(50.0, estimate_plan(&plan).unwrap().num_rows);
+
+        let plan = LogicalPlanBuilder::from(&orders)
+            .join(&customers, JoinType::Inner, &["o_cust"], &["c_id"])?
+            .build()?;
+        let estimate = estimate_plan(&plan).unwrap();
+        assert_eq!(1000.0, estimate.num_rows);
+        assert_eq!(Some(50.0), estimate.distinct_count("o_cust"));
+
+        let plan = LogicalPlanBuilder::from(&orders)
+            .cross_join(&customers)?
+            .build()?;
+        assert_eq!(100_000.0, estimate_plan(&plan).unwrap().num_rows);
+        Ok(())
+    }
+
+    #[test]
+    fn reorder_star_join() -> Result<()> {
+        let fact = StatisticsTable::scan(
+            "fact",
+            100_000,
+            &[("f_d1", 1000, 1000), ("f_d2", 10, 10), ("f_d3", 100, 100)],
+        );
+        let d1 = StatisticsTable::scan("d1", 1000, &[("d1_id", 1000, 1000)]);
+        let d2 = StatisticsTable::scan("d2", 10, &[("d2_id", 10, 10)]);
+        let d3 = StatisticsTable::scan("d3", 100, &[("d3_id", 100, 100)]);
+
+        // only few rows of d1 remain, so it should be joined first
+        let d1 = LogicalPlanBuilder::from(&d1)
+            .filter(col("d1_id").lt_eq(lit(10i64)))?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&fact)
+            .join(&d2, JoinType::Inner, &["f_d2"], &["d2_id"])?
+            .join(&d3, JoinType::Inner, &["f_d3"], &["d3_id"])?
+            .join(&d1, JoinType::Inner, &["f_d1"], &["d1_id"])?
+            .build()?;
+
+        let expected = "\
+        Projection: #f_d1, #f_d2, #f_d3, #d2_id, #d3_id, #d1_id\
+        \n  Join: d3_id = f_d3\
+        \n    TableScan: d3 projection=None\
+        \n    Join: d2_id = f_d2\
+        \n      TableScan: d2 projection=None\
+        \n      Join: d1_id = f_d1\
+        \n        Filter: #d1_id LtEq Int64(10)\
+        \n          TableScan: d1 projection=None\
+        \n        TableScan: fact projection=None";
+        let optimized = optimize(&plan);
+        assert_eq!(expected, format!("{:?}", optimized));
+        assert_eq!(plan.schema(), optimized.schema());
+        Ok(())
+    }
+
+    #[test]
+    fn reorder_avoids_cross_join() -> Result<()> {
+        let a = StatisticsTable::scan("a", 1000, &[("a_id", 1000, 1000)]);
+        let b = StatisticsTable::scan("b", 1000, &[("b_id", 1000, 1000)]);
+        let c =
+            StatisticsTable::scan("c", 1000, &[("c_a", 1000, 1000), ("c_b", 1000, 1000)]);
+
+        // as planned by `SELECT * FROM a, b, c WHERE a_id = c_a AND b_id = c_b`
+        let plan = LogicalPlanBuilder::from(&a)
+            .cross_join(&b)?
+            .join(&c, JoinType::Inner, &["a_id", "b_id"], &["c_a", "c_b"])?
+            .build()?;
+
+        let expected = "\
+        Projection: #a_id, #b_id, #c_a, #c_b\
+        \n  Join: c_b = b_id\
+        \n    Join: a_id = c_a\
+        \n      TableScan: a projection=None\
+        \n      TableScan: c projection=None\
+        \n    TableScan: b projection=None";
+        let optimized = optimize(&plan);
+        assert_eq!(expected, format!("{:?}", optimized));
+        assert_eq!(plan.schema(), optimized.schema());
+        Ok(())
+    }
+
+    #[test]
+    fn keep_order_without_statistics() -> Result<()> {
+        let a = StatisticsTable::scan("a", 1000, &[("a_id", 1000, 1000)]);
+        let b = StatisticsTable::scan("b", 1000, &[("b_id", 1000, 1000)]);
+        let c =
+            StatisticsTable::scan("c", 1000, &[("c_a", 1000, 1000), ("c_b", 1000, 1000)]);
+
+        // the number of rows of c is unknown
+        let c = match c {
+            LogicalPlan::TableScan { source, .. } => {
+                let table = source.as_any().downcast_ref::<StatisticsTable>().unwrap();
+                let table = StatisticsTable {
+                    schema: table.schema.clone(),
+                    statistics: Statistics::default(),
+                };
+                LogicalPlanBuilder::scan("c", Arc::new(table), None)?.build()?
+            }
+            _ => unreachable!(),
+        };
+        let plan = LogicalPlanBuilder::from(&a)
+            .cross_join(&b)?
+            .join(&c, JoinType::Inner, &["a_id", "b_id"], &["c_a", "c_b"])?
+            .build()?;
+        assert_eq!(format!("{:?}", plan), format!("{:?}", optimize(&plan)));
+        Ok(())
+    }
+}
@@ -19,7 +19,10 @@
 //! loaded into memory
 
 use crate::error::Result;
-use crate::logical_plan::{DFField, DFSchema, DFSchemaRef, LogicalPlan, ToDFSchema};
+use crate::logical_plan::{
+    DFField, DFSchema, DFSchemaRef, Expr, LogicalPlan, LogicalPlanBuilder, ToDFSchema,
+    GROUPING_ID_COLUMN,
+};
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::utils;
 use arrow::datatypes::Schema;
@@ -155,6 +158,7 @@ fn optimize_plan(
             right,
             on,
             join_type,
+            null_equals_null,
             schema,
         } => {
             for (l, r) in on {
@@ -177,9 +181,60 @@ fn optimize_plan(
 
                 join_type: *join_type,
                 on: on.clone(),
+                null_equals_null: *null_equals_null,
                 schema: schema.clone(),
             })
         }
+        LogicalPlan::CrossJoin {
+            left,
+            right,
+            schema,
+        } => Ok(LogicalPlan::CrossJoin {
+            left: Arc::new(optimize_plan(optimizer, left, &new_required_columns, true)?),
+            right: Arc::new(optimize_plan(
+                optimizer,
+                right,
+                &new_required_columns,
+                true,
+            )?),
+            schema: schema.clone(),
+        }),
+        LogicalPlan::Window {
+            schema,
+            window_expr,
+            input,
+            ..
+        } => {
+            // window:
+            // * remove any window expression that is not required
+            // * construct the new set of required columns
+            let mut new_window_expr = Vec::new();
+            window_expr.iter().try_for_each(|expr| {
+                let name = &expr.name(&schema)?;
+
+                if required_columns.contains(name) {
+                    new_window_expr.push(expr.clone());
+                    new_required_columns.insert(name.clone());
+
+                    // add to the new set of required columns
+                    utils::expr_to_column_names(expr, &mut new_required_columns)
+                } else {
+                    Ok(())
+                }
+            })?;
+
+            let new_input =
+                optimize_plan(optimizer, &input, &new_required_columns, true)?;
+
+            // none of the window expressions are used: the window is not needed
+            if new_window_expr.is_empty() {
+                return Ok(new_input);
+            }
+
+            LogicalPlanBuilder::from(&new_input)
+                .window(new_window_expr)?
+                .build()
+        }
         LogicalPlan::Aggregate {
             schema,
             input,
@@ -192,6 +247,10 @@ fn optimize_plan(
             // * construct the new set of required columns
 
             utils::exprlist_to_column_names(group_expr, &mut new_required_columns)?;
+            // the grouping id identifies the groups of grouping sets
+            if let [Expr::GroupingSet(_)] = group_expr.as_slice() {
+                new_required_columns.insert(GROUPING_ID_COLUMN.to_string());
+            }
 
             // Gather all columns needed for expressions in this Aggregate
             let mut new_aggr_expr = Vec::new();
@@ -261,6 +320,43 @@ fn optimize_plan(
             let schema = schema.as_ref().to_owned().into();
             optimize_explain(optimizer, *verbose, &*plan, stringified_plans, &schema)
         }
+        LogicalPlan::Analyze {
+            verbose,
+            input,
+            schema,
+        } => {
+            // the analyzed plan produces all its columns
+            let required_columns = input
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.name().clone())
+                .collect::<HashSet<_>>();
+            Ok(LogicalPlan::Analyze {
+                verbose: *verbose,
+                input: Arc::new(optimize_plan(
+                    optimizer,
+                    input,
+                    &required_columns,
+                    false,
+                )?),
+                schema: schema.clone(),
+            })
+        }
+        LogicalPlan::CreateMemoryTable { input, .. }
+        | LogicalPlan::CreateView { input, .. }
+        | LogicalPlan::Insert { input, .. }
+        | LogicalPlan::CopyTo { input, .. } => {
+            // all the columns of the input are stored
+            let required_columns = input
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.name().clone())
+                .collect::<HashSet<_>>();
+            let new_input = optimize_plan(optimizer, input, &required_columns, false)?;
+            utils::from_plan(plan, &[], &[new_input])
+        }
         // all other nodes: Add any additional columns used by
         // expressions in this node to the list of required columns
         LogicalPlan::Limit { .. }
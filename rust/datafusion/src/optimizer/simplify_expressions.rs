@@ -0,0 +1,519 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Expression simplification rule evaluates constant expressions and rewrites expressions
+//! into simpler, equivalent forms.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use arrow::array::NullArray;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::catalog::catalog::MemoryCatalogList;
+use crate::datasource::object_store::ObjectStoreRegistry;
+use crate::error::Result;
+use crate::execution::context::{ExecutionConfig, ExecutionContextState};
+use crate::logical_plan::{
+    DFSchemaRef, Expr, ExprRewriter, ExpressionVisitor, LogicalPlan, Operator, Recursion,
+};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+use crate::physical_plan::planner::DefaultPhysicalPlanner;
+use crate::physical_plan::ColumnarValue;
+use crate::scalar::ScalarValue;
+
+/// Optimizer that simplifies expressions.
+///
+/// Recursively go through all expressions and:
+/// * evaluate expressions that do not reference any column, e.g. `1 + 2` to `3` or
+///   `concat('a', 'b')` to `'ab'`
+/// * simplify boolean expressions: `x AND x` and `x OR x` to `x`, `x AND true` and
+///   `x OR false` to `x`, `x AND false` to `false`, `x OR true` to `true`, `NOT NOT x`
+///   to `x`, and push `NOT` into comparisons and `IS [NOT] NULL`
+/// * propagate nulls: arithmetic and comparisons with a `NULL` operand are `NULL`, and
+///   `IS [NOT] NULL` on a non-nullable expression is constant
+/// * remove casts to the type the expression already has
+/// * replace `x BETWEEN low AND high` with constant bounds by `x >= low AND x <= high`
+/// * move literals to the right of comparisons, e.g. `5 < x` to `x > 5`, which is the
+///   form parquet row group pruning expects
+///
+/// Expressions whose name determines the output schema of their plan (projections,
+/// aggregates and window functions) are aliased to keep their original name.
+pub struct SimplifyExpressions {}
+
+impl SimplifyExpressions {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for SimplifyExpressions {
+    fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        match plan {
+            LogicalPlan::TableScan { .. } | LogicalPlan::EmptyRelation { .. } => {
+                return Ok(plan.clone())
+            }
+            LogicalPlan::Explain { .. } => return utils::optimize_children(self, plan),
+            _ => {}
+        }
+
+        let mut simplifier = Simplifier {
+            schemas: plan.all_schemas(),
+            state: None,
+        };
+
+        let new_inputs = plan
+            .inputs()
+            .iter()
+            .map(|plan| self.optimize(plan))
+            .collect::<Result<Vec<_>>>()?;
+
+        let keep_names = matches!(
+            plan,
+            LogicalPlan::Projection { .. }
+                | LogicalPlan::Aggregate { .. }
+                | LogicalPlan::Window { .. }
+        );
+        let expr = plan
+            .expressions()
+            .into_iter()
+            .map(|e| {
+                let new_e = e.clone().rewrite(&mut simplifier)?;
+                if keep_names {
+                    let input_schema = plan.inputs()[0].schema();
+                    let name = e.name(input_schema)?;
+                    if new_e.name(input_schema)? != name {
+                        return Ok(new_e.alias(&name));
+                    }
+                }
+                Ok(new_e)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        utils::from_plan(plan, &expr, &new_inputs)
+    }
+
+    fn name(&self) -> &str {
+        "simplify_expressions"
+    }
+}
+
+/// Rewrites a single expression node given its already simplified children
+struct Simplifier<'a> {
+    /// input schemas
+    schemas: Vec<&'a DFSchemaRef>,
+    /// state used to plan constant expressions, created on first use
+    state: Option<ExecutionContextState>,
+}
+
+impl<'a> Simplifier<'a> {
+    fn get_type(&self, expr: &Expr) -> Option<DataType> {
+        self.schemas
+            .iter()
+            .find_map(|schema| expr.get_type(schema).ok())
+    }
+
+    fn nullable(&self, expr: &Expr) -> Option<bool> {
+        self.schemas
+            .iter()
+            .find_map(|schema| expr.nullable(schema).ok())
+    }
+
+    /// Evaluates an expression that does not reference any column
+    fn evaluate(&mut self, expr: &Expr) -> Result<ScalarValue> {
+        let state = self.state.get_or_insert_with(|| ExecutionContextState {
+            catalog_list: Arc::new(MemoryCatalogList::new()),
+            scalar_functions: HashMap::new(),
+            var_provider: HashMap::new(),
+            aggregate_functions: HashMap::new(),
+            config: ExecutionConfig::new(),
+            object_store_registry: Arc::new(ObjectStoreRegistry::new()),
+        });
+        // a single row to evaluate the expression against
+        let schema = Arc::new(Schema::new(vec![Field::new(".", DataType::Null, true)]));
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(NullArray::new(1))])?;
+
+        let physical_expr = DefaultPhysicalPlanner::default()
+            .create_physical_expr(expr, &schema, state)?;
+        match physical_expr.evaluate(&batch)? {
+            ColumnarValue::Scalar(value) => Ok(value),
+            ColumnarValue::Array(array) => ScalarValue::try_from_array(&array, 0),
+        }
+    }
+
+    /// Simplifies `expr`, returning `None` when no rule applies
+    fn simplify(&mut self, expr: &Expr) -> Option<Expr> {
+        if !matches!(expr, Expr::Literal(_) | Expr::Alias(..)) && is_constant(expr) {
+            // errors, e.g. a division by zero, are left to be raised at execution time
+            if let Ok(value) = self.evaluate(expr) {
+                return Some(Expr::Literal(value));
+            }
+        }
+
+        match expr {
+            Expr::BinaryExpr { left, op, right } => {
+                self.simplify_binary_expr(left, *op, right)
+            }
+            Expr::Not(inner) => match inner.as_ref() {
+                Expr::Not(expr) => Some(expr.as_ref().clone()),
+                Expr::IsNull(expr) => Some(Expr::IsNotNull(expr.clone())),
+                Expr::IsNotNull(expr) => Some(Expr::IsNull(expr.clone())),
+                Expr::BinaryExpr { left, op, right } => {
+                    negate_comparison(*op).map(|op| Expr::BinaryExpr {
+                        left: left.clone(),
+                        op,
+                        right: right.clone(),
+                    })
+                }
+                Expr::Between {
+                    expr,
+                    negated,
+                    low,
+                    high,
+                } => Some(Expr::Between {
+                    expr: expr.clone(),
+                    negated: !negated,
+                    low: low.clone(),
+                    high: high.clone(),
+                }),
+                _ => None,
+            },
+            Expr::IsNull(inner) if self.nullable(inner) == Some(false) => {
+                Some(Expr::Literal(ScalarValue::Boolean(Some(false))))
+            }
+            Expr::IsNotNull(inner) if self.nullable(inner) == Some(false) => {
+                Some(Expr::Literal(ScalarValue::Boolean(Some(true))))
+            }
+            Expr::Cast { expr, data_type } | Expr::TryCast { expr, data_type }
+                if self.get_type(expr).as_ref() == Some(data_type) =>
+            {
+                Some(expr.as_ref().clone())
+            }
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => match (low.as_ref(), high.as_ref()) {
+                (Expr::Literal(_), Expr::Literal(_)) => Some(if *negated {
+                    expr.as_ref()
+                        .clone()
+                        .lt(low.as_ref().clone())
+                        .or(expr.as_ref().clone().gt(high.as_ref().clone()))
+                } else {
+                    expr.as_ref()
+                        .clone()
+                        .gt_eq(low.as_ref().clone())
+                        .and(expr.as_ref().clone().lt_eq(high.as_ref().clone()))
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn simplify_binary_expr(
+        &self,
+        left: &Expr,
+        op: Operator,
+        right: &Expr,
+    ) -> Option<Expr> {
+        match op {
+            Operator::And => match (left, right) {
+                (_, _) if is_bool(left, false) || is_bool(right, false) => {
+                    Some(Expr::Literal(ScalarValue::Boolean(Some(false))))
+                }
+                (_, _) if is_bool(left, true) => Some(right.clone()),
+                (_, _) if is_bool(right, true) || left == right => Some(left.clone()),
+                _ => None,
+            },
+            Operator::Or => match (left, right) {
+                (_, _) if is_bool(left, true) || is_bool(right, true) => {
+                    Some(Expr::Literal(ScalarValue::Boolean(Some(true))))
+                }
+                (_, _) if is_bool(left, false) => Some(right.clone()),
+                (_, _) if is_bool(right, false) || left == right => Some(left.clone()),
+                _ => None,
+            },
+            _ if is_null(left) || is_null(right) => {
+                let expr = Expr::BinaryExpr {
+                    left: Box::new(left.clone()),
+                    op,
+                    right: Box::new(right.clone()),
+                };
+                let data_type = self.get_type(&expr)?;
+                ScalarValue::try_from(&data_type).ok().map(Expr::Literal)
+            }
+            _ => match (left, right) {
+                (Expr::Literal(_), Expr::Literal(_)) => None,
+                (Expr::Literal(_), _) => swap_comparison(op).map(|op| Expr::BinaryExpr {
+                    left: Box::new(right.clone()),
+                    op,
+                    right: Box::new(left.clone()),
+                }),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl<'a> ExprRewriter for Simplifier<'a> {
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        let mut expr = expr;
+        // a simplification may enable another one on the same expression
+        while let Some(new_expr) = self.simplify(&expr) {
+            expr = new_expr;
+        }
+        Ok(expr)
+    }
+}
+
+/// Whether `expr` is the literal boolean `value`
+fn is_bool(expr: &Expr, value: bool) -> bool {
+    matches!(expr, Expr::Literal(ScalarValue::Boolean(Some(v))) if *v == value)
+}
+
+/// Whether `expr` is a null literal
+fn is_null(expr: &Expr) -> bool {
+    matches!(expr, Expr::Literal(value) if value.is_null())
+}
+
+/// Returns the operator `op'` such that `a op' b` is `NOT (a op b)`
+fn negate_comparison(op: Operator) -> Option<Operator> {
+    match op {
+        Operator::Eq => Some(Operator::NotEq),
+        Operator::NotEq => Some(Operator::Eq),
+        Operator::Lt => Some(Operator::GtEq),
+        Operator::LtEq => Some(Operator::Gt),
+        Operator::Gt => Some(Operator::LtEq),
+        Operator::GtEq => Some(Operator::Lt),
+        Operator::Like => Some(Operator::NotLike),
+        Operator::NotLike => Some(Operator::Like),
+        _ => None,
+    }
+}
+
+/// Returns the operator `op'` such that `b op' a` is `a op b`
+fn swap_comparison(op: Operator) -> Option<Operator> {
+    match op {
+        Operator::Eq | Operator::NotEq => Some(op),
+        Operator::Lt => Some(Operator::Gt),
+        Operator::LtEq => Some(Operator::GtEq),
+        Operator::Gt => Some(Operator::Lt),
+        Operator::GtEq => Some(Operator::LtEq),
+        _ => None,
+    }
+}
+
+/// Whether `expr` can be evaluated without any input
+fn is_constant(expr: &Expr) -> bool {
+    struct ConstantVisitor {
+        constant: bool,
+    }
+
+    impl ExpressionVisitor for ConstantVisitor {
+        fn pre_visit(mut self, expr: &Expr) -> Result<Recursion<Self>> {
+            match expr {
+                Expr::Column(_)
+                | Expr::ScalarVariable(_)
+                | Expr::ScalarUDF { .. }
+                | Expr::AggregateFunction { .. }
+                | Expr::AggregateUDF { .. }
+                | Expr::WindowFunction { .. }
+                | Expr::Sort { .. }
+                | Expr::Wildcard
+                | Expr::Exists { .. }
+                | Expr::InSubquery { .. }
+                | Expr::ScalarSubquery(_)
+                | Expr::GroupingSet(_) => {
+                    self.constant = false;
+                    Ok(Recursion::Stop(self))
+                }
+                _ => Ok(Recursion::Continue(self)),
+            }
+        }
+    }
+
+    expr.accept(ConstantVisitor { constant: true })
+        .map(|visitor| visitor.constant)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{col, lit, sum, DFField, DFSchema, LogicalPlanBuilder};
+    use crate::physical_plan::functions::BuiltinScalarFunction;
+    use crate::test::*;
+
+    fn expr_test_schema() -> DFSchemaRef {
+        Arc::new(
+            DFSchema::new(vec![
+                DFField::new(None, "c1", DataType::Utf8, true),
+                DFField::new(None, "c2", DataType::Boolean, true),
+                DFField::new(None, "c3", DataType::Int64, false),
+            ])
+            .unwrap(),
+        )
+    }
+
+    fn simplify(expr: Expr) -> Expr {
+        let schema = expr_test_schema();
+        let mut simplifier = Simplifier {
+            schemas: vec![&schema],
+            state: None,
+        };
+        expr.rewrite(&mut simplifier).unwrap()
+    }
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let rule = SimplifyExpressions::new();
+        let optimized_plan = rule.optimize(plan).expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+        assert_eq!(optimized_plan.schema(), plan.schema());
+    }
+
+    #[test]
+    fn simplify_constant_expressions() {
+        assert_eq!(simplify(lit(1i64) + lit(2i64) * lit(3i64)), lit(7i64));
+        assert_eq!(
+            simplify(Expr::ScalarFunction {
+                fun: BuiltinScalarFunction::Concat,
+                args: vec![lit("a"), lit("b")],
+            }),
+            lit("ab")
+        );
+        assert_eq!(simplify(lit(1i64).lt(lit(2i64))), lit(true));
+        assert_eq!(
+            simplify(col("c3").gt(lit(10i64) - lit(5i64))),
+            col("c3").gt(lit(5i64))
+        );
+        // errors are left to execution time
+        assert_eq!(simplify(lit(1i64) / lit(0i64)), lit(1i64) / lit(0i64));
+    }
+
+    #[test]
+    fn simplify_boolean_expressions() {
+        assert_eq!(simplify(col("c2").and(col("c2"))), col("c2"));
+        assert_eq!(simplify(col("c2").or(col("c2"))), col("c2"));
+        assert_eq!(simplify(col("c2").and(lit(true))), col("c2"));
+        assert_eq!(simplify(lit(false).or(col("c2"))), col("c2"));
+        assert_eq!(simplify(col("c2").and(lit(false))), lit(false));
+        assert_eq!(simplify(col("c2").or(lit(true))), lit(true));
+        assert_eq!(simplify(col("c2").not().not()), col("c2"));
+        assert_eq!(
+            simplify(col("c3").lt(lit(1i64)).not()),
+            col("c3").gt_eq(lit(1i64))
+        );
+        assert_eq!(simplify(col("c1").is_null().not()), col("c1").is_not_null());
+        // x AND NULL is not NULL when x is false
+        assert_eq!(
+            simplify(col("c2").and(lit(ScalarValue::Boolean(None)))),
+            col("c2").and(lit(ScalarValue::Boolean(None)))
+        );
+    }
+
+    #[test]
+    fn simplify_nulls() {
+        assert_eq!(
+            simplify(col("c3") + lit(ScalarValue::Int64(None))),
+            lit(ScalarValue::Int64(None))
+        );
+        assert_eq!(
+            simplify(col("c3").eq(lit(ScalarValue::Int64(None)))),
+            lit(ScalarValue::Boolean(None))
+        );
+        assert_eq!(simplify(col("c3").is_null()), lit(false));
+        assert_eq!(simplify(col("c3").is_not_null()), lit(true));
+        assert_eq!(simplify(col("c1").is_null()), col("c1").is_null());
+    }
+
+    #[test]
+    fn simplify_casts_and_between() {
+        assert_eq!(
+            simplify(Expr::Cast {
+                expr: Box::new(col("c3")),
+                data_type: DataType::Int64
+            }),
+            col("c3")
+        );
+        assert_eq!(
+            simplify(Expr::Cast {
+                expr: Box::new(lit(1i32)),
+                data_type: DataType::Int64
+            }),
+            lit(1i64)
+        );
+        assert_eq!(
+            simplify(Expr::Between {
+                expr: Box::new(col("c3")),
+                negated: false,
+                low: Box::new(lit(1i64)),
+                high: Box::new(lit(5i64)),
+            }),
+            col("c3").gt_eq(lit(1i64)).and(col("c3").lt_eq(lit(5i64)))
+        );
+        assert_eq!(
+            simplify(Expr::Between {
+                expr: Box::new(col("c3")),
+                negated: true,
+                low: Box::new(lit(1i64)),
+                high: Box::new(lit(5i64)),
+            }),
+            col("c3").lt(lit(1i64)).or(col("c3").gt(lit(5i64)))
+        );
+    }
+
+    #[test]
+    fn normalize_comparisons() {
+        assert_eq!(simplify(lit(5i64).lt(col("c3"))), col("c3").gt(lit(5i64)));
+        assert_eq!(
+            simplify(lit(5i64).gt_eq(col("c3"))),
+            col("c3").lt_eq(lit(5i64))
+        );
+        assert_eq!(simplify(lit(5i64).eq(col("c3"))), col("c3").eq(lit(5i64)));
+    }
+
+    #[test]
+    fn simplify_plan() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(lit(5u32).lt(col("a")).and(lit(true)))?
+            .aggregate(
+                vec![col("b")],
+                vec![sum(col("c") * (lit(1u32) + lit(1u32)))],
+            )?
+            .project(vec![col("b"), lit(1i64) + lit(1i64)])?
+            .build()?;
+
+        // names of projected and aggregate expressions are kept
+        let expected = "Projection: #b, Int64(2) AS Int64(1) Plus Int64(1)\
+        \n  Aggregate: groupBy=[[#b]], aggr=[[SUM(#c Multiply UInt32(2)) AS SUM(c Multiply UInt32(1) Plus UInt32(1))]]\
+        \n    Filter: #a Gt UInt32(5)\
+        \n      TableScan: test projection=None";
+
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+}
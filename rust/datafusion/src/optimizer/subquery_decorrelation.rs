@@ -0,0 +1,983 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Subquery decorrelation optimizer rule rewrites `IN`, `EXISTS` and scalar subqueries as joins
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+use arrow::datatypes::DataType;
+
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::{
+    col, combine_filters, lit, when, DFSchema, Expr, ExprRewriter, JoinType, LogicalPlan,
+    LogicalPlanBuilder, Operator, Subquery,
+};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+use crate::physical_plan::aggregates::AggregateFunction;
+use crate::physical_plan::expressions::coercion::eq_coercion;
+use crate::scalar::ScalarValue;
+
+/// Subquery decorrelation optimizer rule rewrites the subqueries used as
+/// expressions in filters and projections as joins, so that they can be
+/// executed.
+///
+/// Subqueries may be *correlated*, i.e. reference columns of the enclosing
+/// query, through equality predicates in their `WHERE` clause. These
+/// predicates become the keys of the join:
+///
+/// ```text
+/// Filter: EXISTS (<subquery>)
+///   TableScan: t1
+///
+/// where <subquery> is
+///
+/// Projection: #d
+///   Filter: #d Eq #a And #e Gt Int64(10)
+///     TableScan: t2
+/// ```
+///
+/// is rewritten as
+///
+/// ```text
+/// Join: a = __subquery_0_0
+///   TableScan: t1
+///   Projection: #d AS __subquery_0_0
+///     Filter: #e Gt Int64(10)
+///       TableScan: t2
+/// ```
+///
+/// * `EXISTS` and `IN` become semi joins with the subquery, while `NOT EXISTS`
+///   and `NOT IN` become anti joins. `NOT IN` is null, and filters out the
+///   row, when the subquery produces a null value, or when the tested value
+///   is null and the subquery produces any value: nullable expressions add
+///   anti joins with the rows of the subquery in those cases.
+/// * scalar subqueries become left joins, grouping the subquery by its join
+///   keys when it is an aggregate, and are replaced by the joined value, or
+///   by the value of an empty group (e.g. 0 for `COUNT`) for unmatched rows.
+///   Subqueries that may produce more than one row for a row of the
+///   enclosing query are not supported.
+///
+/// Uncorrelated subqueries are joined on a constant key.
+///
+/// Subqueries used in other places, e.g. nested in a disjunction, are not
+/// rewritten and fail to execute.
+pub struct SubqueryDecorrelation {}
+
+impl OptimizerRule for SubqueryDecorrelation {
+    fn name(&self) -> &str {
+        "subquery_decorrelation"
+    }
+
+    fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        match plan {
+            LogicalPlan::Filter { predicate, input } => {
+                let input = self.optimize(input)?;
+                let mut joins = SubqueryJoins::new(self, &input);
+
+                let mut predicates = vec![];
+                split_members(predicate, &mut predicates);
+
+                let mut remaining = vec![];
+                for predicate in predicates {
+                    match normalize_negation(predicate) {
+                        Expr::Exists { subquery, negated } => {
+                            joins.join_exists(&subquery, negated)?;
+                        }
+                        Expr::InSubquery {
+                            expr,
+                            subquery,
+                            negated,
+                        } => {
+                            joins.join_in(&expr, &subquery, negated)?;
+                        }
+                        predicate => {
+                            remaining.push(predicate.rewrite(&mut joins)?);
+                        }
+                    }
+                }
+
+                // keep the filter as is when no subquery has been decorrelated
+                if joins.count == 0 {
+                    return utils::from_plan(plan, &plan.expressions(), &[input]);
+                }
+
+                let mut builder = LogicalPlanBuilder::from(&joins.plan);
+                if let Some(predicate) = combine_filters(&remaining) {
+                    builder = builder.filter(predicate)?;
+                }
+                // remove the columns added by the joins
+                if joins.plan.schema().fields().len() == input.schema().fields().len() {
+                    builder.build()
+                } else {
+                    builder.project(columns(input.schema()))?.build()
+                }
+            }
+            LogicalPlan::Projection { expr, input, .. } => {
+                let input = self.optimize(input)?;
+                let mut joins = SubqueryJoins::new(self, &input);
+
+                let new_expr = expr
+                    .iter()
+                    .map(|e| {
+                        let new_expr = e.clone().rewrite(&mut joins)?;
+                        // keep the name of the expressions that have been rewritten
+                        let name = e.name(input.schema())?;
+                        if new_expr.name(joins.plan.schema())? == name {
+                            Ok(new_expr)
+                        } else {
+                            Ok(new_expr.alias(&name))
+                        }
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                // keep the projection as is when no subquery has been decorrelated
+                if joins.count == 0 {
+                    return utils::from_plan(plan, &plan.expressions(), &[input]);
+                }
+
+                LogicalPlanBuilder::from(&joins.plan)
+                    .project(new_expr)?
+                    .build()
+            }
+            _ => utils::optimize_children(self, plan),
+        }
+    }
+}
+
+impl SubqueryDecorrelation {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// converts "A AND B AND C" => [A, B, C]
+fn split_members<'a>(predicate: &'a Expr, predicates: &mut Vec<&'a Expr>) {
+    match predicate {
+        Expr::BinaryExpr {
+            right,
+            op: Operator::And,
+            left,
+        } => {
+            split_members(left, predicates);
+            split_members(right, predicates);
+        }
+        other => predicates.push(other),
+    }
+}
+
+/// converts "NOT EXISTS (...)" => "EXISTS (...)" with `negated` set, and
+/// likewise for "NOT x IN (...)"
+fn normalize_negation(predicate: &Expr) -> Expr {
+    match predicate {
+        Expr::Not(expr) => match normalize_negation(expr) {
+            Expr::Exists { subquery, negated } => Expr::Exists {
+                subquery,
+                negated: !negated,
+            },
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Expr::InSubquery {
+                expr,
+                subquery,
+                negated: !negated,
+            },
+            _ => predicate.clone(),
+        },
+        _ => predicate.clone(),
+    }
+}
+
+/// Returns a column expression for each field of `schema`
+fn columns(schema: &DFSchema) -> Vec<Expr> {
+    schema.fields().iter().map(|f| col(f.name())).collect()
+}
+
+/// Returns whether all the columns referenced by `expr` are in `schema`
+fn is_resolved_by(expr: &Expr, schema: &DFSchema) -> Result<bool> {
+    let mut columns = HashSet::new();
+    utils::expr_to_column_names(expr, &mut columns)?;
+    Ok(columns
+        .iter()
+        .all(|c| schema.field_with_unqualified_name(c).is_ok()))
+}
+
+/// Returns whether `plan` references columns that none of its nodes provide,
+/// i.e. columns of an enclosing query
+fn has_outer_references(plan: &LogicalPlan) -> Result<bool> {
+    let inputs = plan.inputs();
+    if !inputs.is_empty() {
+        let mut fields = vec![];
+        for input in &inputs {
+            fields.extend_from_slice(input.schema().fields());
+        }
+        // inputs of a join may have fields with the same names
+        let input_schema = DFSchema::new(fields).unwrap_or_else(|_| DFSchema::empty());
+        for expr in plan.expressions() {
+            if !is_resolved_by(&expr, &input_schema)? {
+                return Ok(true);
+            }
+        }
+    }
+    for input in inputs {
+        if has_outer_references(input)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Returns `expr` cast from `from` to `to`, if they differ
+fn cast_to(expr: &Expr, from: &DataType, to: &DataType) -> Expr {
+    if from == to {
+        expr.clone()
+    } else {
+        Expr::Cast {
+            expr: Box::new(expr.clone()),
+            data_type: to.clone(),
+        }
+    }
+}
+
+/// A subquery whose correlated predicates have been extracted
+struct Decorrelated {
+    /// The expressions produced by the subquery
+    exprs: Vec<Expr>,
+    /// The input of `exprs`, without the correlated predicates
+    input: LogicalPlan,
+    /// (column of the enclosing query, expression over `input`) pairs that
+    /// must be equal
+    correlated: Vec<(String, Expr)>,
+    /// Whether the subquery is an aggregate without `GROUP BY`, that produces
+    /// a row even when none of its input rows match
+    ungrouped_aggregate: bool,
+}
+
+/// Extracts the correlated predicates of a subquery, that must be of the form
+/// `outer_column = inner_expression` in its `WHERE` clause.
+fn decorrelate(subquery: &Subquery) -> Result<Decorrelated> {
+    let (exprs, input) = match subquery.subquery.as_ref() {
+        LogicalPlan::Projection { expr, input, .. } => (expr.clone(), input.as_ref()),
+        plan => (columns(plan.schema()), plan),
+    };
+    let ungrouped_aggregate = matches!(
+        input,
+        LogicalPlan::Aggregate { group_expr, .. } if group_expr.is_empty()
+    );
+    let (input, correlated) = extract_correlated_predicates(input)?;
+
+    let mut unresolved = has_outer_references(&input)?;
+    for expr in &exprs {
+        unresolved = unresolved || !is_resolved_by(expr, input.schema())?;
+    }
+    if unresolved {
+        return Err(DataFusionError::NotImplemented(
+            "Correlated subqueries can only reference the enclosing query \
+            in the equality predicates of their WHERE clause"
+                .to_string(),
+        ));
+    }
+
+    Ok(Decorrelated {
+        exprs,
+        input,
+        correlated,
+        ungrouped_aggregate,
+    })
+}
+
+fn extract_correlated_predicates(
+    plan: &LogicalPlan,
+) -> Result<(LogicalPlan, Vec<(String, Expr)>)> {
+    match plan {
+        LogicalPlan::Filter { predicate, input } => {
+            let input_schema = input.schema();
+            let mut predicates = vec![];
+            split_members(predicate, &mut predicates);
+
+            let mut remaining = vec![];
+            let mut correlated = vec![];
+            for predicate in predicates {
+                if is_resolved_by(predicate, input_schema)? {
+                    remaining.push(predicate.clone());
+                    continue;
+                }
+                match predicate {
+                    Expr::BinaryExpr {
+                        left,
+                        op: Operator::Eq,
+                        right,
+                    } => match (left.as_ref(), right.as_ref()) {
+                        (Expr::Column(outer), inner) | (inner, Expr::Column(outer))
+                            if input_schema
+                                .field_with_unqualified_name(outer)
+                                .is_err()
+                                && is_resolved_by(inner, input_schema)? =>
+                        {
+                            correlated.push((outer.clone(), inner.clone()));
+                        }
+                        _ => {
+                            return Err(DataFusionError::NotImplemented(format!(
+                                "Unsupported correlated predicate in subquery: {:?}",
+                                predicate
+                            )))
+                        }
+                    },
+                    _ => {
+                        return Err(DataFusionError::NotImplemented(format!(
+                            "Unsupported correlated predicate in subquery: {:?}",
+                            predicate
+                        )))
+                    }
+                }
+            }
+
+            let plan = match combine_filters(&remaining) {
+                Some(predicate) => {
+                    LogicalPlanBuilder::from(input).filter(predicate)?.build()?
+                }
+                None => input.as_ref().clone(),
+            };
+            Ok((plan, correlated))
+        }
+        LogicalPlan::Aggregate {
+            input,
+            group_expr,
+            aggr_expr,
+            ..
+        } => {
+            let (input, correlated) = extract_correlated_predicates(input)?;
+            if correlated.is_empty() {
+                return Ok((plan.clone(), correlated));
+            }
+
+            // aggregate the rows matching each row of the enclosing query separately
+            let mut group_expr = group_expr.clone();
+            for (_, inner) in &correlated {
+                if !group_expr.contains(inner) {
+                    group_expr.push(inner.clone());
+                }
+            }
+            let correlated = correlated
+                .into_iter()
+                .map(|(outer, inner)| Ok((outer, col(&inner.name(input.schema())?))))
+                .collect::<Result<Vec<_>>>()?;
+
+            let plan = LogicalPlanBuilder::from(&input)
+                .aggregate(group_expr, aggr_expr.clone())?
+                .build()?;
+            Ok((plan, correlated))
+        }
+        _ => Ok((plan.clone(), vec![])),
+    }
+}
+
+/// Returns whether `plan` produces at most one row for each value of the
+/// columns `keys`
+fn produces_one_row_per_key(plan: &LogicalPlan, keys: &[String]) -> Result<bool> {
+    match plan {
+        LogicalPlan::Aggregate {
+            input, group_expr, ..
+        } => {
+            for expr in group_expr {
+                if !keys.contains(&expr.name(input.schema())?) {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        LogicalPlan::Filter { input, .. } | LogicalPlan::Sort { input, .. } => {
+            produces_one_row_per_key(input, keys)
+        }
+        LogicalPlan::Limit { n, input } => {
+            Ok((*n <= 1 && keys.is_empty()) || produces_one_row_per_key(input, keys)?)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Replaces the columns of an aggregate by the values it produces for an
+/// empty group, i.e. 0 for `COUNT` and null otherwise
+struct EmptyGroupRewriter<'a> {
+    /// The aggregate
+    input: &'a LogicalPlan,
+    /// Whether a `COUNT` column has been replaced
+    has_count: bool,
+}
+
+impl ExprRewriter for EmptyGroupRewriter<'_> {
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        let name = match expr {
+            Expr::Column(name) => name,
+            expr => return Ok(expr),
+        };
+        if let LogicalPlan::Aggregate {
+            input, aggr_expr, ..
+        } = self.input
+        {
+            for aggr in aggr_expr {
+                if aggr.name(input.schema())? != name {
+                    continue;
+                }
+                if matches!(
+                    aggr,
+                    Expr::AggregateFunction {
+                        fun: AggregateFunction::Count,
+                        ..
+                    } | Expr::AggregateFunction {
+                        fun: AggregateFunction::ApproxDistinct,
+                        ..
+                    }
+                ) {
+                    self.has_count = true;
+                    return Ok(lit(0u64));
+                }
+            }
+        }
+        let field = self.input.schema().field_with_unqualified_name(&name)?;
+        Ok(Expr::Literal(ScalarValue::try_from(field.data_type())?))
+    }
+}
+
+/// Joins the subqueries of an expression to the input of that expression
+struct SubqueryJoins<'a> {
+    rule: &'a SubqueryDecorrelation,
+    /// The input joined with the subqueries
+    plan: LogicalPlan,
+    /// The number of subqueries joined so far, used to generate unique names
+    count: usize,
+}
+
+impl<'a> SubqueryJoins<'a> {
+    fn new(rule: &'a SubqueryDecorrelation, input: &LogicalPlan) -> Self {
+        Self {
+            rule,
+            plan: input.clone(),
+            count: 0,
+        }
+    }
+
+    /// Returns a unique name for the `i`-th column added by the next join
+    fn column_name(&self, i: usize) -> String {
+        format!("__subquery_{}_{}", self.count, i)
+    }
+
+    /// Builds the `on` clause of a join between `self.plan` and the expressions
+    /// `(outer, inner)` of `keys`, and the projection of the subquery keys with
+    /// unique names. Outer expressions that are not columns, or that must be
+    /// cast to the type of the inner expressions, are projected too.
+    fn join_keys(
+        &mut self,
+        keys: &[(Expr, Expr)],
+        input: &LogicalPlan,
+    ) -> Result<(Vec<(String, String)>, Vec<Expr>)> {
+        let mut on = vec![];
+        let mut inner_exprs = vec![];
+        let mut outer_exprs = vec![];
+        for (i, (outer, inner)) in keys.iter().enumerate() {
+            // join keys must have the same type on both sides
+            let outer_type = outer.get_type(self.plan.schema())?;
+            let inner_type = inner.get_type(input.schema())?;
+            let key_type = eq_coercion(&outer_type, &inner_type).ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "Cannot compare {:?} with subquery of type {:?}",
+                    outer_type, inner_type
+                ))
+            })?;
+
+            let inner_name = self.column_name(2 * i);
+            let outer_name = match outer {
+                Expr::Column(name) if outer_type == key_type => name.clone(),
+                _ => {
+                    let outer_name = self.column_name(2 * i + 1);
+                    outer_exprs
+                        .push(cast_to(outer, &outer_type, &key_type).alias(&outer_name));
+                    outer_name
+                }
+            };
+
+            on.push((outer_name, inner_name.clone()));
+            inner_exprs.push(cast_to(inner, &inner_type, &key_type).alias(&inner_name));
+        }
+
+        if !outer_exprs.is_empty() {
+            let mut expr = columns(self.plan.schema());
+            expr.extend(outer_exprs);
+            self.plan = LogicalPlanBuilder::from(&self.plan)
+                .project(expr)?
+                .build()?;
+        }
+        Ok((on, inner_exprs))
+    }
+
+    /// Joins `self.plan` with the inner expressions of `keys` over `input`,
+    /// projecting `values` over `input` too, and returns the names of the
+    /// projected values
+    fn join(
+        &mut self,
+        mut keys: Vec<(Expr, Expr)>,
+        values: &[Expr],
+        input: &LogicalPlan,
+        join_type: JoinType,
+    ) -> Result<Vec<String>> {
+        if keys.is_empty() {
+            keys.push((lit(true), lit(true)));
+        }
+        let (on, mut inner_exprs) = self.join_keys(&keys, input)?;
+
+        // keys use even indices, see `join_keys`
+        let value_names = (0..values.len())
+            .map(|i| self.column_name(2 * keys.len() + i))
+            .collect::<Vec<_>>();
+        for (value, name) in values.iter().zip(&value_names) {
+            inner_exprs.push(value.clone().alias(name));
+        }
+        let right = LogicalPlanBuilder::from(input)
+            .project(inner_exprs)?
+            .build()?;
+        let right = self.rule.optimize(&right)?;
+
+        let left_keys = on.iter().map(|(l, _)| l.as_str()).collect::<Vec<_>>();
+        let right_keys = on.iter().map(|(_, r)| r.as_str()).collect::<Vec<_>>();
+        self.plan = LogicalPlanBuilder::from(&self.plan)
+            .join(&right, join_type, &left_keys, &right_keys)?
+            .build()?;
+        self.count += 1;
+        Ok(value_names)
+    }
+
+    /// Semi or anti joins the keys of a decorrelated subquery, and optionally
+    /// one of its expressions, for `EXISTS` and `IN` subqueries
+    fn join_semi(
+        &mut self,
+        decorrelated: Decorrelated,
+        value: Option<&Expr>,
+        negated: bool,
+    ) -> Result<()> {
+        let correlated = decorrelated
+            .correlated
+            .iter()
+            .map(|(outer, inner)| (col(outer), inner.clone()))
+            .collect::<Vec<_>>();
+        let mut keys = correlated.clone();
+        if let Some(value) = value {
+            keys.push((value.clone(), decorrelated.exprs[0].clone()));
+        }
+        let join_type = if negated {
+            JoinType::Anti
+        } else {
+            JoinType::Semi
+        };
+        self.join(keys, &[], &decorrelated.input, join_type)?;
+
+        // `x NOT IN (...)` is null, and the row filtered out, when the
+        // subquery produces a null value, or when `x` is null and the
+        // subquery produces any value
+        if let (Some(value), true) = (value, negated) {
+            let inner = &decorrelated.exprs[0];
+            if inner.nullable(decorrelated.input.schema())? {
+                let nulls = LogicalPlanBuilder::from(&decorrelated.input)
+                    .filter(inner.clone().is_null())?
+                    .build()?;
+                self.join(correlated.clone(), &[], &nulls, JoinType::Anti)?;
+            }
+            if value.nullable(self.plan.schema())? {
+                let mut keys = correlated;
+                keys.push((value.clone().is_null(), lit(true)));
+                self.join(keys, &[], &decorrelated.input, JoinType::Anti)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Joins an `EXISTS` subquery
+    fn join_exists(&mut self, subquery: &Subquery, negated: bool) -> Result<()> {
+        self.join_semi(decorrelate(subquery)?, None, negated)
+    }
+
+    /// Joins an `IN` subquery
+    fn join_in(&mut self, expr: &Expr, subquery: &Subquery, negated: bool) -> Result<()> {
+        self.join_semi(decorrelate(subquery)?, Some(expr), negated)
+    }
+
+    /// Joins a scalar subquery, returning the expression of its value
+    fn join_scalar(&mut self, subquery: &Subquery) -> Result<Expr> {
+        let decorrelated = decorrelate(subquery)?;
+        let input_schema = decorrelated.input.schema();
+        let key_names = decorrelated
+            .correlated
+            .iter()
+            .map(|(_, inner)| inner.name(input_schema))
+            .collect::<Result<Vec<_>>>()?;
+        if !produces_one_row_per_key(&decorrelated.input, &key_names)? {
+            return Err(DataFusionError::NotImplemented(
+                "Scalar subqueries must be aggregates, that may only be grouped \
+                by the columns of their correlated predicates"
+                    .to_string(),
+            ));
+        }
+
+        let keys = decorrelated
+            .correlated
+            .iter()
+            .map(|(outer, inner)| (col(outer), inner.clone()))
+            .collect::<Vec<_>>();
+        let value = &decorrelated.exprs[0];
+        // the first key of the subquery, see `join_keys`
+        let key_name = self.column_name(0);
+        let value_names =
+            self.join(keys, &[value.clone()], &decorrelated.input, JoinType::Left)?;
+        let value_column = col(&value_names[0]);
+
+        if !decorrelated.ungrouped_aggregate || decorrelated.correlated.is_empty() {
+            return Ok(value_column);
+        }
+        // an aggregate without `GROUP BY` produces a row even for the rows of
+        // the enclosing query that match no row of the subquery, in which
+        // `COUNT` is 0 and the other aggregates are null
+        let mut empty_group = EmptyGroupRewriter {
+            input: &decorrelated.input,
+            has_count: false,
+        };
+        let empty_group_value = value.clone().rewrite(&mut empty_group)?;
+        if !empty_group.has_count {
+            return Ok(value_column);
+        }
+        // the key of a matching row is never null
+        when(col(&key_name).is_null(), empty_group_value).otherwise(value_column)
+    }
+}
+
+impl ExprRewriter for SubqueryJoins<'_> {
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        match expr {
+            Expr::ScalarSubquery(subquery) => self.join_scalar(&subquery),
+            expr => Ok(expr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{count, exists, in_subquery, scalar_subquery, sum};
+    use crate::test::*;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let rule = SubqueryDecorrelation::new();
+        let optimized_plan = rule.optimize(plan).expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+    }
+
+    fn test_subquery_table_scan() -> Result<LogicalPlan> {
+        let schema = Schema::new(vec![
+            Field::new("d", DataType::UInt32, false),
+            Field::new("e", DataType::Int64, true),
+            Field::new("f", DataType::UInt32, false),
+        ]);
+        LogicalPlanBuilder::scan_empty("sq", &schema, None)?.build()
+    }
+
+    #[test]
+    fn uncorrelated_in() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(col("f").gt(lit(10u32)))?
+            .project(vec![col("d")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(in_subquery(col("a"), subquery, false).and(col("b").lt(lit(5u32))))?
+            .build()?;
+
+        let expected = "\
+            Filter: #b Lt UInt32(5)\
+            \n  Semi Join: a = __subquery_0_0\
+            \n    TableScan: test projection=None\
+            \n    Projection: #d AS __subquery_0_0\
+            \n      Filter: #f Gt UInt32(10)\
+            \n        TableScan: sq projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn without_subqueries() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(col("b").lt(lit(5u32)))?
+            .project(vec![col("a").alias("x"), col("c")])?
+            .build()?;
+
+        // the nodes are kept as is, with the same schema
+        let optimized_plan = SubqueryDecorrelation::new().optimize(&plan)?;
+        assert!(Arc::ptr_eq(plan.schema(), optimized_plan.schema()));
+        assert_eq!(format!("{:?}", plan), format!("{:?}", optimized_plan));
+        Ok(())
+    }
+
+    #[test]
+    fn not_in_expression() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .project(vec![col("e")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(in_subquery(
+                Expr::BinaryExpr {
+                    left: Box::new(col("a")),
+                    op: Operator::Plus,
+                    right: Box::new(col("b")),
+                },
+                subquery,
+                true,
+            ))?
+            .build()?;
+
+        // rows are filtered out when the subquery produces a null value
+        let expected = "\
+            Projection: #a, #b, #c\
+            \n  Anti Join: __subquery_1_1 = __subquery_1_0\
+            \n    Projection: #a, #b, #c, #__subquery_0_1, Boolean(true) AS __subquery_1_1\
+            \n      Anti Join: __subquery_0_1 = __subquery_0_0\
+            \n        Projection: #a, #b, #c, CAST(#a Plus #b AS Int64) AS __subquery_0_1\
+            \n          TableScan: test projection=None\
+            \n        Projection: #e AS __subquery_0_0\
+            \n          TableScan: sq projection=None\
+            \n    Projection: Boolean(true) AS __subquery_1_0\
+            \n      Filter: #e IS NULL\
+            \n        TableScan: sq projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn not_in_nullable_value() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_table_scan()?)
+            .project(vec![col("a")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(in_subquery(col("e"), subquery, true))?
+            .build()?;
+
+        // null values are filtered out when the subquery produces any value
+        let expected = "\
+            Projection: #d, #e, #f\
+            \n  Anti Join: __subquery_1_1 = __subquery_1_0\
+            \n    Projection: #d, #e, #f, #e IS NULL AS __subquery_1_1\
+            \n      Anti Join: e = __subquery_0_0\
+            \n        TableScan: sq projection=None\
+            \n        Projection: CAST(#a AS Int64) AS __subquery_0_0\
+            \n          TableScan: test projection=None\
+            \n    Projection: Boolean(true) AS __subquery_1_0\
+            \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn correlated_exists() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(col("d").eq(col("a")).and(col("f").gt(lit(10u32))))?
+            .project(vec![col("e")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(exists(subquery, false))?
+            .project(vec![col("a")])?
+            .build()?;
+
+        let expected = "\
+            Projection: #a\
+            \n  Semi Join: a = __subquery_0_0\
+            \n    TableScan: test projection=None\
+            \n    Projection: #d AS __subquery_0_0\
+            \n      Filter: #f Gt UInt32(10)\
+            \n        TableScan: sq projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn correlated_not_exists() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(col("a").eq(col("d")))?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(Expr::Not(Box::new(exists(subquery, false))))?
+            .build()?;
+
+        let expected = "\
+            Anti Join: a = __subquery_0_0\
+            \n  TableScan: test projection=None\
+            \n  Projection: #d AS __subquery_0_0\
+            \n    TableScan: sq projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn uncorrelated_exists() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(col("f").gt(lit(10u32)))?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(exists(subquery, false))?
+            .build()?;
+
+        let expected = "\
+            Projection: #a, #b, #c\
+            \n  Semi Join: __subquery_0_1 = __subquery_0_0\
+            \n    Projection: #a, #b, #c, Boolean(true) AS __subquery_0_1\
+            \n      TableScan: test projection=None\
+            \n    Projection: Boolean(true) AS __subquery_0_0\
+            \n      Filter: #f Gt UInt32(10)\
+            \n        TableScan: sq projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn correlated_scalar_aggregate_in_filter() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(col("d").eq(col("a")))?
+            .aggregate(vec![], vec![sum(col("e"))])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(col("b").gt(scalar_subquery(subquery)))?
+            .build()?;
+
+        let expected = "\
+            Projection: #a, #b, #c\
+            \n  Filter: #b Gt #__subquery_0_2\
+            \n    Left Join: a = __subquery_0_0\
+            \n      TableScan: test projection=None\
+            \n      Projection: #d AS __subquery_0_0, #SUM(e) AS __subquery_0_2\
+            \n        Aggregate: groupBy=[[#d]], aggr=[[SUM(#e)]]\
+            \n          TableScan: sq projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn correlated_scalar_count() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(col("d").eq(col("a")))?
+            .aggregate(vec![], vec![count(col("e"))])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .project(vec![col("a"), scalar_subquery(subquery)])?
+            .build()?;
+
+        // the count is 0 for the rows without a matching group
+        let expected = "\
+            Projection: #a, CASE WHEN #__subquery_0_0 IS NULL THEN UInt64(0) \
+            ELSE #__subquery_0_2 END AS (<subquery>)\
+            \n  Left Join: a = __subquery_0_0\
+            \n    TableScan: test projection=None\
+            \n    Projection: #d AS __subquery_0_0, #COUNT(e) AS __subquery_0_2\
+            \n      Aggregate: groupBy=[[#d]], aggr=[[COUNT(#e)]]\
+            \n        TableScan: sq projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_scalar_subquery() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(col("d").eq(col("a")))?
+            .project(vec![col("e")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .project(vec![col("a"), scalar_subquery(subquery)])?
+            .build()?;
+
+        // the subquery may produce several rows for each row of `test`
+        let rule = SubqueryDecorrelation::new();
+        let err = rule.optimize(&plan).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Scalar subqueries must be aggregates"));
+        Ok(())
+    }
+
+    #[test]
+    fn uncorrelated_scalar_in_projection() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .aggregate(vec![], vec![count(col("d"))])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .project(vec![col("a"), scalar_subquery(subquery)])?
+            .build()?;
+
+        let expected = "\
+            Projection: #a, #__subquery_0_2 AS (<subquery>)\
+            \n  Left Join: __subquery_0_1 = __subquery_0_0\
+            \n    Projection: #a, #b, #c, Boolean(true) AS __subquery_0_1\
+            \n      TableScan: test projection=None\
+            \n    Projection: Boolean(true) AS __subquery_0_0, #COUNT(d) AS __subquery_0_2\
+            \n      Aggregate: groupBy=[[]], aggr=[[COUNT(#d)]]\
+            \n        TableScan: sq projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn nested_subqueries() -> Result<()> {
+        let inner = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(col("c").eq(col("f")))?
+            .project(vec![col("b")])?
+            .build()?;
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(exists(inner, false))?
+            .project(vec![col("d")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(in_subquery(col("a"), subquery, false))?
+            .build()?;
+
+        let expected = "\
+            Semi Join: a = __subquery_0_0\
+            \n  TableScan: test projection=None\
+            \n  Projection: #d AS __subquery_0_0\
+            \n    Semi Join: f = __subquery_0_0\
+            \n      TableScan: sq projection=None\
+            \n      Projection: #c AS __subquery_0_0\
+            \n        TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_correlated_predicate() -> Result<()> {
+        let subquery = LogicalPlanBuilder::from(&test_subquery_table_scan()?)
+            .filter(col("d").gt(col("a")))?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(exists(subquery, false))?
+            .build()?;
+
+        let rule = SubqueryDecorrelation::new();
+        let err = rule.optimize(&plan).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported correlated predicate in subquery"));
+        Ok(())
+    }
+}
@@ -78,8 +78,13 @@ impl ExpressionVisitor for ColumnNameVisitor<'_> {
             Expr::ScalarFunction { .. } => {}
             Expr::ScalarUDF { .. } => {}
             Expr::AggregateFunction { .. } => {}
+            Expr::WindowFunction { .. } => {}
             Expr::AggregateUDF { .. } => {}
             Expr::InList { .. } => {}
+            Expr::Exists { .. } => {}
+            Expr::InSubquery { .. } => {}
+            Expr::ScalarSubquery(_) => {}
+            Expr::GroupingSet(_) => {}
             Expr::Wildcard => {}
         }
         Ok(Recursion::Continue(self))
@@ -184,6 +189,11 @@ pub fn from_plan(
                 input: Arc::new(inputs[0].clone()),
             }),
         },
+        LogicalPlan::Window { schema, .. } => Ok(LogicalPlan::Window {
+            input: Arc::new(inputs[0].clone()),
+            window_expr: expr.to_vec(),
+            schema: schema.clone(),
+        }),
         LogicalPlan::Aggregate {
             group_expr, schema, ..
         } => Ok(LogicalPlan::Aggregate {
@@ -199,6 +209,7 @@ pub fn from_plan(
         LogicalPlan::Join {
             join_type,
             on,
+            null_equals_null,
             schema,
             ..
         } => Ok(LogicalPlan::Join {
@@ -206,12 +217,57 @@ pub fn from_plan(
             right: Arc::new(inputs[1].clone()),
             join_type: *join_type,
             on: on.clone(),
+            null_equals_null: *null_equals_null,
+            schema: schema.clone(),
+        }),
+        LogicalPlan::CrossJoin { schema, .. } => Ok(LogicalPlan::CrossJoin {
+            left: Arc::new(inputs[0].clone()),
+            right: Arc::new(inputs[1].clone()),
             schema: schema.clone(),
         }),
         LogicalPlan::Limit { n, .. } => Ok(LogicalPlan::Limit {
             n: *n,
             input: Arc::new(inputs[0].clone()),
         }),
+        LogicalPlan::Analyze {
+            verbose, schema, ..
+        } => Ok(LogicalPlan::Analyze {
+            verbose: *verbose,
+            input: Arc::new(inputs[0].clone()),
+            schema: schema.clone(),
+        }),
+        LogicalPlan::CreateMemoryTable { name, .. } => {
+            Ok(LogicalPlan::CreateMemoryTable {
+                name: name.clone(),
+                input: Arc::new(inputs[0].clone()),
+            })
+        }
+        LogicalPlan::CreateView { name, .. } => Ok(LogicalPlan::CreateView {
+            name: name.clone(),
+            input: Arc::new(inputs[0].clone()),
+        }),
+        LogicalPlan::Insert {
+            table_name,
+            table,
+            schema,
+            ..
+        } => Ok(LogicalPlan::Insert {
+            table_name: table_name.clone(),
+            table: table.clone(),
+            input: Arc::new(inputs[0].clone()),
+            schema: schema.clone(),
+        }),
+        LogicalPlan::CopyTo {
+            path,
+            options,
+            schema,
+            ..
+        } => Ok(LogicalPlan::CopyTo {
+            input: Arc::new(inputs[0].clone()),
+            path: path.clone(),
+            options: options.clone(),
+            schema: schema.clone(),
+        }),
         LogicalPlan::Extension { node } => Ok(LogicalPlan::Extension {
             node: node.from_template(expr, inputs),
         }),
@@ -239,6 +295,17 @@ pub fn expr_sub_expressions(expr: &Expr) -> Result<Vec<Expr>> {
         Expr::ScalarFunction { args, .. } => Ok(args.clone()),
         Expr::ScalarUDF { args, .. } => Ok(args.clone()),
         Expr::AggregateFunction { args, .. } => Ok(args.clone()),
+        Expr::WindowFunction {
+            args,
+            partition_by,
+            order_by,
+            ..
+        } => {
+            let mut expr_list = args.clone();
+            expr_list.extend(partition_by.clone());
+            expr_list.extend(order_by.clone());
+            Ok(expr_list)
+        }
         Expr::AggregateUDF { args, .. } => Ok(args.clone()),
         Expr::Case {
             expr,
@@ -284,6 +351,12 @@ pub fn expr_sub_expressions(expr: &Expr) -> Result<Vec<Expr>> {
             }
             Ok(expr_list)
         }
+        Expr::InSubquery { expr, .. } => Ok(vec![expr.as_ref().to_owned()]),
+        Expr::Exists { .. } => Ok(vec![]),
+        Expr::ScalarSubquery(_) => Ok(vec![]),
+        Expr::GroupingSet(grouping_set) => {
+            Ok(grouping_set.exprs().into_iter().cloned().collect())
+        }
         Expr::Wildcard { .. } => Err(DataFusionError::Internal(
             "Wildcard expressions are not valid in a logical query plan".to_owned(),
         )),
@@ -315,6 +388,23 @@ pub fn rewrite_expression(expr: &Expr, expressions: &[Expr]) -> Result<Expr> {
             args: expressions.to_vec(),
             distinct: *distinct,
         }),
+        Expr::WindowFunction {
+            fun,
+            args,
+            partition_by,
+            window_frame,
+            ..
+        } => {
+            let partition_index = args.len();
+            let order_index = partition_index + partition_by.len();
+            Ok(Expr::WindowFunction {
+                fun: fun.clone(),
+                args: expressions[..partition_index].to_vec(),
+                partition_by: expressions[partition_index..order_index].to_vec(),
+                order_by: expressions[order_index..].to_vec(),
+                window_frame: *window_frame,
+            })
+        }
         Expr::AggregateUDF { fun, .. } => Ok(Expr::AggregateUDF {
             fun: fun.clone(),
             args: expressions.to_vec(),
@@ -400,6 +490,18 @@ pub fn rewrite_expression(expr: &Expr, expressions: &[Expr]) -> Result<Expr> {
             }
         }
         Expr::InList { .. } => Ok(expr.clone()),
+        Expr::InSubquery {
+            subquery, negated, ..
+        } => Ok(Expr::InSubquery {
+            expr: Box::new(expressions[0].clone()),
+            subquery: subquery.clone(),
+            negated: *negated,
+        }),
+        Expr::Exists { .. } => Ok(expr.clone()),
+        Expr::ScalarSubquery(_) => Ok(expr.clone()),
+        Expr::GroupingSet(grouping_set) => Ok(Expr::GroupingSet(
+            grouping_set.with_new_exprs(expressions.to_vec()),
+        )),
         Expr::Wildcard { .. } => Err(DataFusionError::Internal(
             "Wildcard expressions are not valid in a logical query plan".to_owned(),
         )),
@@ -22,8 +22,8 @@ use super::optimizer::PhysicalOptimizerRule;
 use crate::{
     error::Result,
     physical_plan::{
-        coalesce_batches::CoalesceBatchesExec, filter::FilterExec,
-        hash_join::HashJoinExec, repartition::RepartitionExec,
+        coalesce_batches::CoalesceBatchesExec, cross_join::CrossJoinExec,
+        filter::FilterExec, hash_join::HashJoinExec, repartition::RepartitionExec,
     },
 };
 use std::sync::Arc;
@@ -58,6 +58,7 @@ impl PhysicalOptimizerRule for CoalesceBatches {
         // See https://issues.apache.org/jira/browse/ARROW-11068
         let wrap_in_coalesce = plan_any.downcast_ref::<FilterExec>().is_some()
             || plan_any.downcast_ref::<HashJoinExec>().is_some()
+            || plan_any.downcast_ref::<CrossJoinExec>().is_some()
             || plan_any.downcast_ref::<RepartitionExec>().is_some();
 
         //TODO we should also do this for HashAggregateExec but we need to update tests
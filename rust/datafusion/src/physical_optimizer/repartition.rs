@@ -20,7 +20,8 @@ use std::sync::Arc;
 
 use super::optimizer::PhysicalOptimizerRule;
 use crate::physical_plan::{
-    empty::EmptyExec, repartition::RepartitionExec, ExecutionPlan,
+    empty::EmptyExec, repartition::RepartitionExec,
+    sort_preserving_merge::SortPreservingMergeExec, ExecutionPlan,
 };
 use crate::physical_plan::{Distribution, Partitioning::*};
 use crate::{error::Result, execution::context::ExecutionConfig};
@@ -37,6 +38,7 @@ impl Repartition {
 
 fn optimize_concurrency(
     concurrency: usize,
+    batch_size: usize,
     requires_single_partition: bool,
     plan: Arc<dyn ExecutionPlan>,
 ) -> Result<Arc<dyn ExecutionPlan>> {
@@ -46,13 +48,21 @@ fn optimize_concurrency(
         // leaf node - don't replace children
         plan.clone()
     } else {
+        // the partitions merged by a SortPreservingMergeExec must stay sorted
+        let keep_partitioning = plan.required_child_distribution()
+            == Distribution::SinglePartition
+            || plan
+                .as_any()
+                .downcast_ref::<SortPreservingMergeExec>()
+                .is_some();
         let children = plan
             .children()
             .iter()
             .map(|child| {
                 optimize_concurrency(
                     concurrency,
-                    plan.required_child_distribution() == Distribution::SinglePartition,
+                    batch_size,
+                    keep_partitioning,
                     child.clone(),
                 )
             })
@@ -73,7 +83,11 @@ fn optimize_concurrency(
     // But also not very useful to inlude
     let is_empty_exec = plan.as_any().downcast_ref::<EmptyExec>().is_some();
 
-    if perform_repartition && !requires_single_partition && !is_empty_exec {
+    // batches are distributed as they are, so an input that fits into a single
+    // batch would only end up in one of the partitions
+    let is_tiny = matches!(new_plan.statistics().num_rows, Some(n) if n <= batch_size);
+
+    if perform_repartition && !requires_single_partition && !is_empty_exec && !is_tiny {
         Ok(Arc::new(RepartitionExec::try_new(
             new_plan,
             RoundRobinBatch(concurrency),
@@ -93,7 +107,7 @@ impl PhysicalOptimizerRule for Repartition {
         if config.concurrency == 1 {
             Ok(plan)
         } else {
-            optimize_concurrency(config.concurrency, true, plan)
+            optimize_concurrency(config.concurrency, config.batch_size, true, plan)
         }
     }
 
@@ -107,6 +121,7 @@ mod tests {
 
     use super::*;
     use crate::datasource::datasource::Statistics;
+    use crate::datasource::object_store::local::LocalFileSystem;
     use crate::physical_plan::parquet::{ParquetExec, ParquetPartition};
     use crate::physical_plan::projection::ProjectionExec;
 
@@ -115,6 +130,7 @@ mod tests {
         let parquet_project = ProjectionExec::try_new(
             vec![],
             Arc::new(ParquetExec::new(
+                Arc::new(LocalFileSystem),
                 vec![ParquetPartition {
                     filenames: vec!["x".to_string()],
                     statistics: Statistics::default(),
@@ -151,6 +167,7 @@ mod tests {
             Arc::new(ProjectionExec::try_new(
                 vec![],
                 Arc::new(ParquetExec::new(
+                    Arc::new(LocalFileSystem),
                     vec![ParquetPartition {
                         filenames: vec!["x".to_string()],
                         statistics: Statistics::default(),
@@ -183,4 +200,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn no_repartition_of_tiny_input() -> Result<()> {
+        let parquet_project = ProjectionExec::try_new(
+            vec![],
+            Arc::new(ParquetExec::new(
+                Arc::new(LocalFileSystem),
+                vec![ParquetPartition {
+                    filenames: vec!["x".to_string()],
+                    statistics: Statistics {
+                        num_rows: Some(100),
+                        total_byte_size: None,
+                        column_statistics: None,
+                    },
+                }],
+                Schema::empty(),
+                None,
+                None,
+                2048,
+                None,
+            )),
+        )?;
+
+        let optimizer = Repartition {};
+
+        let optimized = optimizer.optimize(
+            Arc::new(parquet_project),
+            &ExecutionConfig::new().with_concurrency(10),
+        )?;
+
+        // the 100 rows fit into a single batch
+        assert!(optimized.children()[0]
+            .as_any()
+            .downcast_ref::<ParquetExec>()
+            .is_some());
+
+        Ok(())
+    }
 }
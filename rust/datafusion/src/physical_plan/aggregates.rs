@@ -34,8 +34,9 @@ use super::{
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::distinct_expressions;
 use crate::physical_plan::expressions;
-use arrow::datatypes::{DataType, Schema};
-use expressions::{avg_return_type, sum_return_type};
+use crate::scalar::ScalarValue;
+use arrow::datatypes::{DataType, Field, Schema};
+use expressions::{avg_return_type, sum_return_type, PhysicalSortExpr, StatsType};
 use std::{fmt, str::FromStr, sync::Arc};
 
 /// the implementation of an aggregate function
@@ -60,12 +61,53 @@ pub enum AggregateFunction {
     Max,
     /// avg
     Avg,
+    /// sample standard deviation
+    Stddev,
+    /// population standard deviation
+    StddevPop,
+    /// sample variance
+    Variance,
+    /// population variance
+    VariancePop,
+    /// sample covariance
+    Covariance,
+    /// population covariance
+    CovariancePop,
+    /// correlation
+    Correlation,
+    /// approximate number of distinct values
+    ApproxDistinct,
+    /// approximate continuous percentile
+    ApproxPercentileCont,
+    /// approximate median
+    ApproxMedian,
+    /// list of the values
+    ArrayAgg,
+    /// concatenation of the values
+    StringAgg,
+    /// whether the arguments are aggregated away in the grouping set of a group
+    Grouping,
 }
 
 impl fmt::Display for AggregateFunction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // uppercase of the debug.
-        write!(f, "{}", format!("{:?}", self).to_uppercase())
+        match self {
+            AggregateFunction::StddevPop => write!(f, "STDDEV_POP"),
+            AggregateFunction::Variance => write!(f, "VAR_SAMP"),
+            AggregateFunction::VariancePop => write!(f, "VAR_POP"),
+            AggregateFunction::Covariance => write!(f, "COVAR_SAMP"),
+            AggregateFunction::CovariancePop => write!(f, "COVAR_POP"),
+            AggregateFunction::Correlation => write!(f, "CORR"),
+            AggregateFunction::ApproxDistinct => write!(f, "APPROX_DISTINCT"),
+            AggregateFunction::ApproxPercentileCont => {
+                write!(f, "APPROX_PERCENTILE_CONT")
+            }
+            AggregateFunction::ApproxMedian => write!(f, "APPROX_MEDIAN"),
+            AggregateFunction::ArrayAgg => write!(f, "ARRAY_AGG"),
+            AggregateFunction::StringAgg => write!(f, "STRING_AGG"),
+            // uppercase of the debug.
+            _ => write!(f, "{}", format!("{:?}", self).to_uppercase()),
+        }
     }
 }
 
@@ -78,6 +120,19 @@ impl FromStr for AggregateFunction {
             "count" => AggregateFunction::Count,
             "avg" => AggregateFunction::Avg,
             "sum" => AggregateFunction::Sum,
+            "stddev" | "stddev_samp" => AggregateFunction::Stddev,
+            "stddev_pop" => AggregateFunction::StddevPop,
+            "var" | "var_samp" | "variance" => AggregateFunction::Variance,
+            "var_pop" => AggregateFunction::VariancePop,
+            "covar" | "covar_samp" => AggregateFunction::Covariance,
+            "covar_pop" => AggregateFunction::CovariancePop,
+            "corr" => AggregateFunction::Correlation,
+            "approx_distinct" => AggregateFunction::ApproxDistinct,
+            "approx_percentile_cont" => AggregateFunction::ApproxPercentileCont,
+            "approx_median" => AggregateFunction::ApproxMedian,
+            "array_agg" => AggregateFunction::ArrayAgg,
+            "string_agg" => AggregateFunction::StringAgg,
+            "grouping" => AggregateFunction::Grouping,
             _ => {
                 return Err(DataFusionError::Plan(format!(
                     "There is no built-in function named {}",
@@ -94,13 +149,31 @@ pub fn return_type(fun: &AggregateFunction, arg_types: &[DataType]) -> Result<Da
     // or the execution panics.
 
     // verify that this is a valid set of data types for this function
-    data_types(arg_types, &signature(fun))?;
+    data_types(arg_types, &signature(fun, arg_types))?;
 
     match fun {
         AggregateFunction::Count => Ok(DataType::UInt64),
         AggregateFunction::Max | AggregateFunction::Min => Ok(arg_types[0].clone()),
         AggregateFunction::Sum => sum_return_type(&arg_types[0]),
         AggregateFunction::Avg => avg_return_type(&arg_types[0]),
+        AggregateFunction::Stddev
+        | AggregateFunction::StddevPop
+        | AggregateFunction::Variance
+        | AggregateFunction::VariancePop
+        | AggregateFunction::Covariance
+        | AggregateFunction::CovariancePop
+        | AggregateFunction::Correlation => Ok(DataType::Float64),
+        AggregateFunction::ApproxDistinct => Ok(DataType::UInt64),
+        AggregateFunction::ApproxPercentileCont | AggregateFunction::ApproxMedian => {
+            Ok(DataType::Float64)
+        }
+        AggregateFunction::ArrayAgg => Ok(DataType::List(Box::new(Field::new(
+            "item",
+            arg_types[0].clone(),
+            true,
+        )))),
+        AggregateFunction::StringAgg => Ok(DataType::Utf8),
+        AggregateFunction::Grouping => Ok(DataType::Int32),
     }
 }
 
@@ -113,14 +186,44 @@ pub fn create_aggregate_expr(
     input_schema: &Schema,
     name: String,
 ) -> Result<Arc<dyn AggregateExpr>> {
-    // coerce
-    let arg = coerce(args, input_schema, &signature(fun))?[0].clone();
+    create_ordered_aggregate_expr(fun, distinct, args, &[], input_schema, name)
+}
+
+/// Create a physical aggregate expression whose input values are sorted by
+/// `ordering`, which only ARRAY_AGG supports.
+/// This function errors when `args`' can't be coerced to a valid argument type of the function.
+pub fn create_ordered_aggregate_expr(
+    fun: &AggregateFunction,
+    distinct: bool,
+    args: &[Arc<dyn PhysicalExpr>],
+    ordering: &[PhysicalSortExpr],
+    input_schema: &Schema,
+    name: String,
+) -> Result<Arc<dyn AggregateExpr>> {
+    // GROUPING is computed from the grouping id of the aggregate by the SQL planner
+    if *fun == AggregateFunction::Grouping {
+        return Err(DataFusionError::NotImplemented(
+            "GROUPING is only supported in the SELECT list and HAVING clause of SQL queries"
+                .to_string(),
+        ));
+    }
+
+    if !ordering.is_empty() && *fun != AggregateFunction::ArrayAgg {
+        return Err(DataFusionError::Plan(format!(
+            "ORDER BY is not supported in {} aggregations",
+            fun
+        )));
+    }
 
     let arg_types = args
         .iter()
         .map(|e| e.data_type(input_schema))
         .collect::<Result<Vec<_>>>()?;
 
+    // coerce
+    let coerced_args = coerce(args, input_schema, &signature(fun, &arg_types))?;
+    let arg = coerced_args[0].clone();
+
     let return_type = return_type(&fun, &arg_types)?;
 
     Ok(match (fun, distinct) {
@@ -157,6 +260,116 @@ pub fn create_aggregate_expr(
                 "AVG(DISTINCT) aggregations are not available".to_string(),
             ));
         }
+        (AggregateFunction::Stddev, false) => Arc::new(expressions::Stddev::new(
+            arg,
+            name,
+            return_type,
+            StatsType::Sample,
+        )),
+        (AggregateFunction::StddevPop, false) => Arc::new(expressions::Stddev::new(
+            arg,
+            name,
+            return_type,
+            StatsType::Population,
+        )),
+        (AggregateFunction::Variance, false) => Arc::new(expressions::Variance::new(
+            arg,
+            name,
+            return_type,
+            StatsType::Sample,
+        )),
+        (AggregateFunction::VariancePop, false) => Arc::new(expressions::Variance::new(
+            arg,
+            name,
+            return_type,
+            StatsType::Population,
+        )),
+        (AggregateFunction::Covariance, false) => Arc::new(expressions::Covariance::new(
+            arg,
+            coerced_args[1].clone(),
+            name,
+            return_type,
+            StatsType::Sample,
+        )),
+        (AggregateFunction::CovariancePop, false) => {
+            Arc::new(expressions::Covariance::new(
+                arg,
+                coerced_args[1].clone(),
+                name,
+                return_type,
+                StatsType::Population,
+            ))
+        }
+        (AggregateFunction::Correlation, false) => {
+            Arc::new(expressions::Correlation::new(
+                arg,
+                coerced_args[1].clone(),
+                name,
+                return_type,
+            ))
+        }
+        // duplicates do not change the estimate
+        (AggregateFunction::ApproxDistinct, _) => {
+            Arc::new(expressions::ApproxDistinct::new(arg, name, return_type))
+        }
+        (AggregateFunction::ApproxPercentileCont, false) => {
+            Arc::new(expressions::ApproxPercentileCont::try_new(
+                arg,
+                coerced_args[1].clone(),
+                name,
+                return_type,
+            )?)
+        }
+        (AggregateFunction::ApproxMedian, false) => {
+            Arc::new(expressions::ApproxPercentileCont::try_new(
+                arg,
+                expressions::lit(ScalarValue::Float64(Some(0.5))),
+                name,
+                return_type,
+            )?)
+        }
+        (AggregateFunction::ArrayAgg, false) => Arc::new(expressions::ArrayAgg::try_new(
+            arg,
+            ordering.to_vec(),
+            input_schema,
+            name,
+            arg_types[0].clone(),
+        )?),
+        (AggregateFunction::ArrayAgg, true) => {
+            if !ordering.is_empty() {
+                return Err(DataFusionError::NotImplemented(
+                    "ARRAY_AGG(DISTINCT) aggregations with ORDER BY are not available"
+                        .to_string(),
+                ));
+            }
+            Arc::new(expressions::DistinctArrayAgg::new(
+                arg,
+                name,
+                arg_types[0].clone(),
+            ))
+        }
+        (AggregateFunction::StringAgg, false) => {
+            Arc::new(expressions::StringAgg::try_new(
+                arg,
+                coerced_args[1].clone(),
+                name,
+                return_type,
+            )?)
+        }
+        (AggregateFunction::StringAgg, true) => {
+            Arc::new(expressions::DistinctStringAgg::try_new(
+                arg,
+                coerced_args[1].clone(),
+                name,
+                return_type,
+            )?)
+        }
+        (_, true) => {
+            return Err(DataFusionError::NotImplemented(format!(
+                "{}(DISTINCT) aggregations are not available",
+                fun
+            )));
+        }
     })
 }
 
@@ -173,19 +386,59 @@ static NUMERICS: &[DataType] = &[
     DataType::Float64,
 ];
 
-/// the signatures supported by the function `fun`.
-fn signature(fun: &AggregateFunction) -> Signature {
+/// the signatures supported by the function `fun` when called with `arg_types`.
+fn signature(fun: &AggregateFunction, arg_types: &[DataType]) -> Signature {
     // note: the physical expression must accept the type returned by this function or the execution panics.
+    // decimals of any precision and scale are accepted as they are
+    let decimals = arg_types
+        .iter()
+        .filter(|t| matches!(t, DataType::Decimal(_, _)))
+        .cloned();
     match fun {
         AggregateFunction::Count => Signature::Any(1),
         AggregateFunction::Min | AggregateFunction::Max => {
             let mut valid = vec![DataType::Utf8, DataType::LargeUtf8];
             valid.extend_from_slice(NUMERICS);
+            valid.extend(decimals);
             Signature::Uniform(1, valid)
         }
         AggregateFunction::Avg | AggregateFunction::Sum => {
-            Signature::Uniform(1, NUMERICS.to_vec())
+            let mut valid = NUMERICS.to_vec();
+            valid.extend(decimals);
+            Signature::Uniform(1, valid)
+        }
+        // statistical aggregates are computed on f64
+        AggregateFunction::Stddev
+        | AggregateFunction::StddevPop
+        | AggregateFunction::Variance
+        | AggregateFunction::VariancePop => {
+            Signature::Uniform(1, vec![DataType::Float64])
+        }
+        AggregateFunction::Covariance
+        | AggregateFunction::CovariancePop
+        | AggregateFunction::Correlation => {
+            Signature::Uniform(2, vec![DataType::Float64])
+        }
+        // the values are hashed, other types are counted as strings
+        AggregateFunction::ApproxDistinct => {
+            let mut valid = vec![DataType::Boolean, DataType::Utf8];
+            valid.extend(
+                NUMERICS
+                    .iter()
+                    .filter(|t| !matches!(t, DataType::Float32 | DataType::Float64))
+                    .cloned(),
+            );
+            valid.extend(decimals);
+            Signature::Uniform(1, valid)
         }
+        AggregateFunction::ApproxPercentileCont => {
+            Signature::Uniform(2, vec![DataType::Float64])
+        }
+        AggregateFunction::ApproxMedian => Signature::Uniform(1, vec![DataType::Float64]),
+        AggregateFunction::ArrayAgg => Signature::Any(1),
+        // the values are concatenated as strings
+        AggregateFunction::StringAgg => Signature::Uniform(2, vec![DataType::Utf8]),
+        AggregateFunction::Grouping => Signature::Any(arg_types.len().max(1)),
     }
 }
 
@@ -237,9 +490,125 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_decimal_return_type() -> Result<()> {
+        let decimal = [DataType::Decimal(10, 2)];
+        let observed = return_type(&AggregateFunction::Min, &decimal)?;
+        assert_eq!(DataType::Decimal(10, 2), observed);
+
+        let observed = return_type(&AggregateFunction::Sum, &decimal)?;
+        assert_eq!(DataType::Decimal(20, 2), observed);
+
+        let observed = return_type(&AggregateFunction::Avg, &decimal)?;
+        assert_eq!(DataType::Decimal(14, 6), observed);
+
+        let observed =
+            return_type(&AggregateFunction::Sum, &[DataType::Decimal(35, 30)])?;
+        assert_eq!(DataType::Decimal(38, 30), observed);
+        Ok(())
+    }
+
     #[test]
     fn test_avg_no_utf8() {
         let observed = return_type(&AggregateFunction::Avg, &[DataType::Utf8]);
         assert!(observed.is_err());
     }
+
+    #[test]
+    fn test_statistical_return_type() -> Result<()> {
+        let observed = return_type(&AggregateFunction::Stddev, &[DataType::Int32])?;
+        assert_eq!(DataType::Float64, observed);
+
+        let observed =
+            return_type(&AggregateFunction::VariancePop, &[DataType::Float32])?;
+        assert_eq!(DataType::Float64, observed);
+
+        let observed = return_type(
+            &AggregateFunction::Correlation,
+            &[DataType::UInt8, DataType::Float64],
+        )?;
+        assert_eq!(DataType::Float64, observed);
+
+        assert!(return_type(&AggregateFunction::Variance, &[DataType::Utf8]).is_err());
+        assert!(return_type(&AggregateFunction::Covariance, &[DataType::Int64]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_statistical_names() -> Result<()> {
+        for (name, fun) in &[
+            ("stddev_samp", AggregateFunction::Stddev),
+            ("stddev_pop", AggregateFunction::StddevPop),
+            ("var_samp", AggregateFunction::Variance),
+            ("var_pop", AggregateFunction::VariancePop),
+            ("covar_samp", AggregateFunction::Covariance),
+            ("covar_pop", AggregateFunction::CovariancePop),
+            ("corr", AggregateFunction::Correlation),
+        ] {
+            assert_eq!(fun, &AggregateFunction::from_str(name)?);
+        }
+        assert_eq!("STDDEV", AggregateFunction::Stddev.to_string());
+        assert_eq!("VAR_POP", AggregateFunction::VariancePop.to_string());
+        assert_eq!("COVAR_SAMP", AggregateFunction::Covariance.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_array_agg_string_agg_return_type() -> Result<()> {
+        let observed = return_type(&AggregateFunction::ArrayAgg, &[DataType::Float64])?;
+        assert_eq!(
+            DataType::List(Box::new(Field::new("item", DataType::Float64, true))),
+            observed
+        );
+
+        let observed = return_type(
+            &AggregateFunction::StringAgg,
+            &[DataType::Int32, DataType::Utf8],
+        )?;
+        assert_eq!(DataType::Utf8, observed);
+
+        assert!(return_type(
+            &AggregateFunction::ArrayAgg,
+            &[DataType::Int32, DataType::Int32]
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ordered_aggregate_expr() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let args = vec![expressions::col("a")];
+        let ordering = vec![PhysicalSortExpr {
+            expr: expressions::col("a"),
+            options: arrow::compute::SortOptions::default(),
+        }];
+
+        let agg = create_ordered_aggregate_expr(
+            &AggregateFunction::ArrayAgg,
+            false,
+            &args,
+            &ordering,
+            &schema,
+            "agg".to_string(),
+        )?;
+        assert_eq!(2, agg.expressions().len());
+        assert_eq!(2, agg.state_fields()?.len());
+
+        for (fun, distinct) in &[
+            (AggregateFunction::Sum, false),
+            (AggregateFunction::ArrayAgg, true),
+        ] {
+            let agg = create_ordered_aggregate_expr(
+                fun,
+                *distinct,
+                &args,
+                &ordering,
+                &schema,
+                "agg".to_string(),
+            );
+            assert!(agg.is_err());
+        }
+        Ok(())
+    }
 }
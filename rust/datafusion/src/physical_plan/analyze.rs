@@ -0,0 +1,197 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the EXPLAIN ANALYZE operator
+
+use std::any::Any;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::datasource::datasource::Statistics;
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::{
+    common::SizedRecordBatchStream, merge::MergeExec, Distribution, ExecutionPlan,
+    Partitioning,
+};
+use arrow::{array::StringBuilder, datatypes::SchemaRef, record_batch::RecordBatch};
+use futures::StreamExt;
+
+use super::SendableRecordBatchStream;
+use async_trait::async_trait;
+
+/// EXPLAIN ANALYZE execution plan operator. This operator runs its input to
+/// completion, discarding its rows, and then produces the physical plan of
+/// the input annotated with the metrics recorded by each operator.
+#[derive(Debug, Clone)]
+pub struct AnalyzeExec {
+    /// Should extra (detailed) metrics be included?
+    verbose: bool,
+    /// The input plan, that is run
+    input: Arc<dyn ExecutionPlan>,
+    /// The schema that this exec plan node outputs
+    schema: SchemaRef,
+}
+
+impl AnalyzeExec {
+    /// Create a new AnalyzeExec
+    pub fn new(verbose: bool, input: Arc<dyn ExecutionPlan>, schema: SchemaRef) -> Self {
+        AnalyzeExec {
+            verbose,
+            input,
+            schema,
+        }
+    }
+
+    /// Should extra (detailed) metrics be included?
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for AnalyzeExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    /// The input is run as a whole, whatever its partitioning
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::UnspecifiedDistribution
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(AnalyzeExec::new(
+                self.verbose,
+                children[0].clone(),
+                self.schema.clone(),
+            ))),
+            _ => Err(DataFusionError::Internal(
+                "AnalyzeExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "AnalyzeExec invalid partition {}",
+                partition
+            )));
+        }
+
+        let start = Instant::now();
+        let mut stream = MergeExec::new(self.input.clone()).execute(0).await?;
+        let mut output_rows = 0;
+        while let Some(batch) = stream.next().await {
+            output_rows += batch?.num_rows();
+        }
+        let duration = start.elapsed();
+
+        let mut type_builder = StringBuilder::new(3);
+        let mut plan_builder = StringBuilder::new(3);
+
+        type_builder.append_value("Plan with Metrics")?;
+        plan_builder.append_value(&annotated_plan(self.input.as_ref()))?;
+        if self.verbose {
+            type_builder.append_value("Output Rows")?;
+            plan_builder.append_value(&output_rows.to_string())?;
+            type_builder.append_value("Duration")?;
+            plan_builder.append_value(&format!("{:?}", duration))?;
+        }
+
+        let record_batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(type_builder.finish()),
+                Arc::new(plan_builder.finish()),
+            ],
+        )?;
+
+        Ok(Box::pin(SizedRecordBatchStream::new(
+            self.schema.clone(),
+            vec![Arc::new(record_batch)],
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        let num_rows = if self.verbose { 3 } else { 1 };
+        Statistics {
+            num_rows: Some(num_rows),
+            total_byte_size: None,
+            column_statistics: None,
+        }
+    }
+}
+
+/// Returns the physical plan as an indented tree, with the metrics of each
+/// operator sorted by name, e.g.
+///
+/// ```text
+/// ProjectionExec
+///   FilterExec: metrics=[elapsed_compute=12.5µs, input_rows=4, output_rows=2]
+///     MemoryExec
+/// ```
+pub fn annotated_plan(plan: &dyn ExecutionPlan) -> String {
+    let mut output = String::new();
+    write_annotated_plan(plan, 0, &mut output);
+    output
+}
+
+fn write_annotated_plan(plan: &dyn ExecutionPlan, indent: usize, output: &mut String) {
+    // the name of the operator is the name of its type, that starts its
+    // debug representation
+    let debug = format!("{:?}", plan);
+    let name = debug
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default();
+    write!(output, "{:indent$}{}", "", name, indent = indent * 2).unwrap();
+
+    let mut metrics = plan.metrics().into_iter().collect::<Vec<_>>();
+    if !metrics.is_empty() {
+        metrics.sort_by(|a, b| a.0.cmp(&b.0));
+        let metrics = metrics
+            .iter()
+            .map(|(name, metric)| format!("{}={}", name, metric))
+            .collect::<Vec<_>>();
+        write!(output, ": metrics=[{}]", metrics.join(", ")).unwrap();
+    }
+    output.push('\n');
+
+    for child in plan.children() {
+        write_annotated_plan(child.as_ref(), indent + 1, output);
+    }
+}
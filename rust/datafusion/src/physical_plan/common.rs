@@ -23,9 +23,10 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::datasource::datasource::{ColumnStatistics, Statistics};
 use crate::error::{DataFusionError, Result};
 
-use arrow::datatypes::SchemaRef;
+use arrow::datatypes::{Schema, SchemaRef};
 use arrow::error::Result as ArrowResult;
 use arrow::record_batch::RecordBatch;
 use futures::{Stream, TryStreamExt};
@@ -102,3 +103,48 @@ pub fn build_file_list(dir: &str, filenames: &mut Vec<String>, ext: &str) -> Res
     }
     Ok(())
 }
+
+/// Computes the statistics of partitions of record batches, for the columns
+/// with the given indices, or for all columns of `schema`
+pub fn compute_record_batch_statistics(
+    partitions: &[Vec<RecordBatch>],
+    schema: &Schema,
+    projection: Option<&[usize]>,
+) -> Statistics {
+    let all_columns: Vec<usize>;
+    let projection = match projection {
+        Some(projection) => projection,
+        None => {
+            all_columns = (0..schema.fields().len()).collect();
+            &all_columns
+        }
+    };
+
+    let mut num_rows = 0;
+    let mut total_byte_size = 0;
+    let mut null_counts = vec![0; projection.len()];
+    for batch in partitions.iter().flatten() {
+        num_rows += batch.num_rows();
+        for (null_count, i) in null_counts.iter_mut().zip(projection) {
+            let array = batch.column(*i);
+            total_byte_size += array.get_array_memory_size();
+            *null_count += array.null_count();
+        }
+    }
+
+    let column_statistics = null_counts
+        .into_iter()
+        .map(|null_count| ColumnStatistics {
+            null_count: Some(null_count),
+            distinct_count: None,
+            max_value: None,
+            min_value: None,
+        })
+        .collect();
+
+    Statistics {
+        num_rows: Some(num_rows),
+        total_byte_size: Some(total_byte_size),
+        column_statistics: Some(column_statistics),
+    }
+}
@@ -0,0 +1,519 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the cross join plan, a nested loop join that loads the left side in memory
+//! and combines each of its rows with the rows of every right partition, optionally
+//! keeping only the combined rows matching a filter.
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use arrow::array::{ArrayRef, BooleanArray, UInt32Array};
+use arrow::compute::{self, filter_record_batch};
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::{ready, Stream, StreamExt, TryStreamExt};
+use log::debug;
+use tokio::sync::Mutex;
+
+use super::{
+    ExecutionPlan, Partitioning, PhysicalExpr, RecordBatchStream,
+    SendableRecordBatchStream,
+};
+use crate::datasource::datasource::{ColumnStatistics, Statistics};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::coalesce_batches::concat_batches;
+use crate::physical_plan::filter::selectivity;
+use crate::physical_plan::merge::MergeExec;
+
+/// Cross join execution plan, which combines every row of its left side with every row
+/// of its right side. The left side is collected into a single batch that is shared by
+/// all the right partitions.
+#[derive(Debug)]
+pub struct CrossJoinExec {
+    /// left (build) side which is loaded in memory
+    left: Arc<dyn ExecutionPlan>,
+    /// right (probe) side which is combined with every row of the left side
+    right: Arc<dyn ExecutionPlan>,
+    /// Optional filter applied to the combined rows. It must evaluate to a boolean value.
+    filter: Option<Arc<dyn PhysicalExpr>>,
+    /// The schema once the join is applied
+    schema: SchemaRef,
+    /// Build-side
+    build_side: Arc<Mutex<Option<Arc<RecordBatch>>>>,
+}
+
+impl CrossJoinExec {
+    /// Create a new [CrossJoinExec] of `left` and `right`
+    pub fn new(left: Arc<dyn ExecutionPlan>, right: Arc<dyn ExecutionPlan>) -> Self {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let fields = left_schema
+            .fields()
+            .iter()
+            .chain(right_schema.fields().iter())
+            .cloned()
+            .collect();
+
+        CrossJoinExec {
+            left,
+            right,
+            filter: None,
+            schema: Arc::new(Schema::new(fields)),
+            build_side: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Tries to set the filter evaluated against the combined rows of the join.
+    /// # Error
+    /// This function errors when the filter does not evaluate to a boolean value.
+    pub fn with_filter(mut self, filter: Arc<dyn PhysicalExpr>) -> Result<Self> {
+        match filter.data_type(&self.schema)? {
+            DataType::Boolean => {
+                self.filter = Some(filter);
+                Ok(self)
+            }
+            other => Err(DataFusionError::Plan(format!(
+                "Join filter must return boolean values, not {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// left (build) side which is loaded in memory
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
+
+    /// right (probe) side which is combined with every row of the left side
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
+    }
+
+    /// Optional filter applied to the combined rows
+    pub fn filter(&self) -> Option<&Arc<dyn PhysicalExpr>> {
+        self.filter.as_ref()
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for CrossJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            2 => {
+                let join = CrossJoinExec::new(children[0].clone(), children[1].clone());
+                Ok(Arc::new(match &self.filter {
+                    Some(filter) => join.with_filter(filter.clone())?,
+                    None => join,
+                }))
+            }
+            _ => Err(DataFusionError::Internal(
+                "CrossJoinExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.right.output_partitioning()
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        // we only want to collect the left side once
+        let left_data = {
+            let mut build_side = self.build_side.lock().await;
+
+            match build_side.as_ref() {
+                Some(batch) => batch.clone(),
+                None => {
+                    let start = Instant::now();
+
+                    // merge all left parts into a single stream
+                    let merge = MergeExec::new(self.left.clone());
+                    let stream = merge.execute(0).await?;
+
+                    let (batches, num_rows) = stream
+                        .try_fold((Vec::new(), 0), |mut acc, batch| async {
+                            acc.1 += batch.num_rows();
+                            acc.0.push(batch);
+                            Ok(acc)
+                        })
+                        .await?;
+
+                    // Merge all batches into a single batch, so we
+                    // can directly index into the arrays
+                    let single_batch = Arc::new(concat_batches(
+                        &self.left.schema(),
+                        &batches,
+                        num_rows,
+                    )?);
+
+                    *build_side = Some(single_batch.clone());
+
+                    debug!(
+                        "Built build-side of cross join containing {} rows in {} ms",
+                        num_rows,
+                        start.elapsed().as_millis()
+                    );
+
+                    single_batch
+                }
+            }
+        };
+
+        Ok(Box::pin(CrossJoinStream {
+            schema: self.schema.clone(),
+            filter: self.filter.clone(),
+            left_data,
+            right: self.right.execute(partition).await?,
+            right_batch: None,
+            left_index: 0,
+            num_input_batches: 0,
+            num_input_rows: 0,
+            num_output_batches: 0,
+            num_output_rows: 0,
+            join_time: 0,
+        }))
+    }
+
+    fn statistics(&self) -> Statistics {
+        let left = self.left.statistics();
+        let right = self.right.statistics();
+        // every null of one side appears once per row of the other side
+        let columns = |stats: Statistics, other_rows: Option<usize>| {
+            stats.column_statistics.map(|columns| {
+                columns
+                    .into_iter()
+                    .map(|c| ColumnStatistics {
+                        null_count: c.null_count.zip(other_rows).map(|(n, r)| n * r),
+                        ..c
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+        let (left_rows, right_rows) = (left.num_rows, right.num_rows);
+        let total_byte_size = match (
+            left.total_byte_size,
+            right.total_byte_size,
+            left_rows,
+            right_rows,
+        ) {
+            (Some(l), Some(r), Some(left_rows), Some(right_rows)) => {
+                Some(l * right_rows + r * left_rows)
+            }
+            _ => None,
+        };
+        let statistics = Statistics {
+            num_rows: left_rows.zip(right_rows).map(|(l, r)| l * r),
+            total_byte_size,
+            column_statistics: columns(left, right_rows)
+                .zip(columns(right, left_rows))
+                .map(|(mut l, r)| {
+                    l.extend(r);
+                    l
+                }),
+        };
+        match &self.filter {
+            Some(filter) => {
+                let selectivity = selectivity(filter.as_ref(), &self.schema, &statistics);
+                let scale = |n: usize| (n as f64 * selectivity).ceil() as usize;
+                Statistics {
+                    num_rows: statistics.num_rows.map(scale),
+                    total_byte_size: statistics.total_byte_size.map(scale),
+                    column_statistics: statistics.column_statistics,
+                }
+            }
+            None => statistics,
+        }
+    }
+}
+
+/// A stream that issues [RecordBatch]es as they arrive from the right side of the join,
+/// combining them with one row of the left side at a time.
+struct CrossJoinStream {
+    /// Input schema
+    schema: Arc<Schema>,
+    /// Optional filter applied to the combined rows
+    filter: Option<Arc<dyn PhysicalExpr>>,
+    /// The left side, collected into a single batch
+    left_data: Arc<RecordBatch>,
+    /// The right (probe) stream
+    right: SendableRecordBatchStream,
+    /// The current right batch
+    right_batch: Option<RecordBatch>,
+    /// The next row of the left side to combine with the current right batch
+    left_index: usize,
+    /// number of input batches
+    num_input_batches: usize,
+    /// number of input rows
+    num_input_rows: usize,
+    /// number of batches produced
+    num_output_batches: usize,
+    /// number of rows produced
+    num_output_rows: usize,
+    /// total time for joining probe-side batches to the build-side batches
+    join_time: usize,
+}
+
+impl RecordBatchStream for CrossJoinStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Combines the row `left_index` of `left` with all the rows of `right`, keeping only the
+/// rows matching the optional `filter`.
+fn build_batch(
+    left_index: usize,
+    left: &RecordBatch,
+    right: &RecordBatch,
+    schema: &SchemaRef,
+    filter: Option<&Arc<dyn PhysicalExpr>>,
+) -> ArrowResult<RecordBatch> {
+    let indices = UInt32Array::from(vec![left_index as u32; right.num_rows()]);
+    let mut columns = left
+        .columns()
+        .iter()
+        .map(|array| compute::take(array.as_ref(), &indices, None))
+        .collect::<ArrowResult<Vec<ArrayRef>>>()?;
+    columns.extend_from_slice(right.columns());
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+    match filter {
+        Some(filter) => {
+            let mask = filter
+                .evaluate(&batch)
+                .map(|v| v.into_array(batch.num_rows()))
+                .map_err(DataFusionError::into_arrow_external_error)?;
+            let mask = mask
+                .as_any()
+                .downcast_ref::<BooleanArray>()
+                .ok_or_else(|| {
+                    DataFusionError::Internal(
+                        "Join filter evaluated to non-boolean value".to_string(),
+                    )
+                    .into_arrow_external_error()
+                })?;
+            filter_record_batch(&batch, mask)
+        }
+        None => Ok(batch),
+    }
+}
+
+impl Stream for CrossJoinStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(right_batch) = &self.right_batch {
+                if self.left_index < self.left_data.num_rows() {
+                    let start = Instant::now();
+                    let result = build_batch(
+                        self.left_index,
+                        &self.left_data,
+                        right_batch,
+                        &self.schema,
+                        self.filter.as_ref(),
+                    );
+                    self.left_index += 1;
+                    self.join_time += start.elapsed().as_millis() as usize;
+                    match result {
+                        // skip the rows of the left side without a match
+                        Ok(batch) if batch.num_rows() == 0 => continue,
+                        Ok(batch) => {
+                            self.num_output_batches += 1;
+                            self.num_output_rows += batch.num_rows();
+                            return Poll::Ready(Some(Ok(batch)));
+                        }
+                        Err(e) => return Poll::Ready(Some(Err(e))),
+                    }
+                }
+            }
+
+            match ready!(self.right.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    self.num_input_batches += 1;
+                    self.num_input_rows += batch.num_rows();
+                    self.right_batch = Some(batch);
+                    self.left_index = 0;
+                }
+                other => {
+                    debug!(
+                        "Processed {} probe-side input batches containing {} rows and \
+                        produced {} output batches containing {} rows in {} ms",
+                        self.num_input_batches,
+                        self.num_input_rows,
+                        self.num_output_batches,
+                        self.num_output_rows,
+                        self.join_time
+                    );
+                    return Poll::Ready(other);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::{binary, col};
+    use crate::{
+        assert_batches_sorted_eq,
+        logical_plan::Operator,
+        physical_plan::{common, memory::MemoryExec},
+        test::{build_table_i32, columns},
+    };
+
+    fn memory_exec(partitions: Vec<Vec<RecordBatch>>) -> Arc<dyn ExecutionPlan> {
+        let schema = partitions[0][0].schema();
+        Arc::new(MemoryExec::try_new(&partitions, schema, None).unwrap())
+    }
+
+    #[tokio::test]
+    async fn cross_join() -> Result<()> {
+        let left = memory_exec(vec![vec![build_table_i32(
+            ("a1", &vec![1, 2]),
+            ("b1", &vec![4, 5]),
+            ("c1", &vec![7, 8]),
+        )]]);
+        let right = memory_exec(vec![vec![build_table_i32(
+            ("a2", &vec![10, 20, 30]),
+            ("b2", &vec![1, 2, 3]),
+            ("c2", &vec![70, 80, 90]),
+        )]]);
+
+        let join = CrossJoinExec::new(left, right);
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "b2", "c2"]);
+
+        let stream = join.execute(0).await?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 1  | 70 |",
+            "| 1  | 4  | 7  | 20 | 2  | 80 |",
+            "| 1  | 4  | 7  | 30 | 3  | 90 |",
+            "| 2  | 5  | 8  | 10 | 1  | 70 |",
+            "| 2  | 5  | 8  | 20 | 2  | 80 |",
+            "| 2  | 5  | 8  | 30 | 3  | 90 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cross_join_with_filter_multi_partition() -> Result<()> {
+        // the left side is collected from all its partitions
+        let left = memory_exec(vec![
+            vec![build_table_i32(
+                ("a1", &vec![1, 2]),
+                ("b1", &vec![4, 5]),
+                ("c1", &vec![7, 8]),
+            )],
+            vec![build_table_i32(
+                ("a1", &vec![3]),
+                ("b1", &vec![6]),
+                ("c1", &vec![9]),
+            )],
+        ]);
+        let right = memory_exec(vec![
+            vec![build_table_i32(
+                ("a2", &vec![10, 20]),
+                ("b2", &vec![3, 5]),
+                ("c2", &vec![70, 80]),
+            )],
+            vec![build_table_i32(
+                ("a2", &vec![30]),
+                ("b2", &vec![6]),
+                ("c2", &vec![90]),
+            )],
+        ]);
+
+        // b1 < b2
+        let join = CrossJoinExec::new(left, right);
+        let filter = binary(col("b1"), Operator::Lt, col("b2"), &join.schema())?;
+        let join = join.with_filter(filter)?;
+        assert_eq!(join.output_partitioning().partition_count(), 2);
+
+        let mut batches = vec![];
+        for partition in 0..2 {
+            let stream = join.execute(partition).await?;
+            batches.extend(common::collect(stream).await?);
+        }
+
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b2 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 20 | 5  | 80 |",
+            "| 1  | 4  | 7  | 30 | 6  | 90 |",
+            "| 2  | 5  | 8  | 30 | 6  | 90 |",
+            "+----+----+----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cross_join_with_non_boolean_filter() {
+        let left = memory_exec(vec![vec![build_table_i32(
+            ("a1", &vec![1]),
+            ("b1", &vec![4]),
+            ("c1", &vec![7]),
+        )]]);
+        let right = memory_exec(vec![vec![build_table_i32(
+            ("a2", &vec![1]),
+            ("b2", &vec![4]),
+            ("c2", &vec![7]),
+        )]]);
+
+        let result = CrossJoinExec::new(left, right).with_filter(col("a1"));
+        assert!(result.is_err());
+    }
+}
@@ -18,14 +18,17 @@
 //! Execution plan for reading CSV files
 
 use std::any::Any;
-use std::fs::File;
+use std::io::Read;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use crate::datasource::datasource::Statistics;
+use crate::datasource::object_store::local::LocalFileSystem;
+use crate::datasource::object_store::{self, ObjectStore};
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::ExecutionPlan;
-use crate::physical_plan::{common, Partitioning};
+use crate::physical_plan::Partitioning;
 use arrow::csv;
 use arrow::datatypes::{Schema, SchemaRef};
 use arrow::error::Result as ArrowResult;
@@ -109,6 +112,8 @@ impl<'a> CsvReadOptions<'a> {
 /// Execution plan for scanning a CSV file
 #[derive(Debug, Clone)]
 pub struct CsvExec {
+    /// The store the files are read from
+    object_store: Arc<dyn ObjectStore>,
     /// Path to directory containing partitioned CSV files with the same schema
     path: String,
     /// The individual files under path
@@ -139,11 +144,30 @@ impl CsvExec {
         projection: Option<Vec<usize>>,
         batch_size: usize,
         limit: Option<usize>,
+    ) -> Result<Self> {
+        Self::try_new_with_object_store(
+            Arc::new(LocalFileSystem),
+            path,
+            options,
+            projection,
+            batch_size,
+            limit,
+        )
+    }
+
+    /// Create a new execution plan for reading a set of CSV files from the given
+    /// object store
+    pub fn try_new_with_object_store(
+        object_store: Arc<dyn ObjectStore>,
+        path: &str,
+        options: CsvReadOptions,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+        limit: Option<usize>,
     ) -> Result<Self> {
         let file_extension = String::from(options.file_extension);
 
-        let mut filenames: Vec<String> = vec![];
-        common::build_file_list(path, &mut filenames, file_extension.as_str())?;
+        let filenames = object_store::list_files(&object_store, path, &file_extension)?;
         if filenames.is_empty() {
             return Err(DataFusionError::Execution(format!(
                 "No files found at {path} with file extension {file_extension}",
@@ -154,7 +178,7 @@ impl CsvExec {
 
         let schema = match options.schema {
             Some(s) => s.clone(),
-            None => CsvExec::try_infer_schema(&filenames, &options)?,
+            None => CsvExec::try_infer_schema(&object_store, &filenames, &options)?,
         };
 
         let projected_schema = match &projection {
@@ -163,6 +187,7 @@ impl CsvExec {
         };
 
         Ok(Self {
+            object_store,
             path: path.to_string(),
             filenames,
             schema: Arc::new(schema),
@@ -176,6 +201,11 @@ impl CsvExec {
         })
     }
 
+    /// The store the files are read from
+    pub fn object_store(&self) -> Arc<dyn ObjectStore> {
+        self.object_store.clone()
+    }
+
     /// Path to directory containing partitioned CSV files with the same schema
     pub fn path(&self) -> &str {
         &self.path
@@ -221,17 +251,33 @@ impl CsvExec {
         self.limit
     }
 
-    /// Infer schema for given CSV dataset
+    /// Infer schema for given CSV dataset, reading the files in the given order
+    /// until `schema_infer_max_records` records have been read
     pub fn try_infer_schema(
+        object_store: &Arc<dyn ObjectStore>,
         filenames: &[String],
         options: &CsvReadOptions,
     ) -> Result<Schema> {
-        Ok(csv::infer_schema_from_files(
-            filenames,
-            options.delimiter,
-            Some(options.schema_infer_max_records),
-            options.has_header,
-        )?)
+        let mut schemas = vec![];
+        let mut records_to_read = options.schema_infer_max_records;
+        for filename in filenames {
+            let mut reader = object_store::open_file(object_store, filename)?;
+            let (schema, records_read) = csv::reader::infer_reader_schema(
+                &mut reader,
+                options.delimiter,
+                Some(records_to_read),
+                options.has_header,
+            )?;
+            if records_read == 0 {
+                continue;
+            }
+            schemas.push(schema);
+            records_to_read -= records_read;
+            if records_to_read == 0 {
+                break;
+            }
+        }
+        Ok(Schema::try_merge(schemas)?)
     }
 }
 
@@ -272,8 +318,10 @@ impl ExecutionPlan for CsvExec {
     }
 
     async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        let file = self.object_store.head(&self.filenames[partition]).await?;
+        let reader = self.object_store.file_reader(file)?.sync_reader()?;
         Ok(Box::pin(CsvStream::try_new(
-            &self.filenames[partition],
+            reader,
             self.schema.clone(),
             self.has_header,
             self.delimiter,
@@ -282,18 +330,23 @@ impl ExecutionPlan for CsvExec {
             self.limit,
         )?))
     }
+
+    fn statistics(&self) -> Statistics {
+        // the files are not read before execution
+        Statistics::default()
+    }
 }
 
 /// Iterator over batches
 struct CsvStream {
     /// Arrow CSV reader
-    reader: csv::Reader<File>,
+    reader: csv::Reader<Box<dyn Read + Send + Sync>>,
 }
 
 impl CsvStream {
     /// Create an iterator for a CSV file
     pub fn try_new(
-        filename: &str,
+        file: Box<dyn Read + Send + Sync>,
         schema: SchemaRef,
         has_header: bool,
         delimiter: Option<u8>,
@@ -301,7 +354,6 @@ impl CsvStream {
         batch_size: usize,
         limit: Option<usize>,
     ) -> Result<Self> {
-        let file = File::open(filename)?;
         let start_line = if has_header { 1 } else { 0 };
         let bounds = limit.map(|x| (0, x + start_line));
 
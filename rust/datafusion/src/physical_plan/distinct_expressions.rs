@@ -189,6 +189,18 @@ impl Accumulator for DistinctCountAccumulator {
             ))),
         }
     }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self
+                .values
+                .iter()
+                .map(|values| {
+                    std::mem::size_of_val(values)
+                        + values.0.iter().map(|v| v.size()).sum::<usize>()
+                })
+                .sum::<usize>()
+    }
 }
 
 #[cfg(test)]
@@ -197,8 +209,8 @@ mod tests {
 
     use arrow::array::ArrayRef;
     use arrow::array::{
-        Int16Array, Int32Array, Int64Array, Int8Array, ListArray, UInt16Array,
-        UInt32Array, UInt64Array, UInt8Array,
+        Int16Array, Int32Array, Int64Array, Int8Array, ListArray, StringArray,
+        UInt16Array, UInt32Array, UInt64Array, UInt8Array,
     };
     use arrow::array::{Int32Builder, ListBuilder, UInt64Builder};
     use arrow::datatypes::DataType;
@@ -554,4 +566,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn distinct_count_size_grows() -> Result<()> {
+        let agg = DistinctCount::new(
+            vec![DataType::Utf8],
+            vec![],
+            String::from("__col_name__"),
+            DataType::UInt64,
+        );
+        let mut accum = agg.create_accumulator()?;
+        let size = accum.size();
+
+        let values: ArrayRef = Arc::new(StringArray::from(vec!["a", "b", "c", "d", "e"]));
+        accum.update_batch(&[values])?;
+        assert!(accum.size() > size);
+
+        Ok(())
+    }
 }
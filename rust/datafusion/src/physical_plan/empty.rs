@@ -20,6 +20,7 @@
 use std::any::Any;
 use std::sync::Arc;
 
+use crate::datasource::datasource::Statistics;
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::memory::MemoryStream;
 use crate::physical_plan::{Distribution, ExecutionPlan, Partitioning};
@@ -120,6 +121,15 @@ impl ExecutionPlan for EmptyExec {
             None,
         )?))
     }
+
+    fn statistics(&self) -> Statistics {
+        let num_rows = if self.produce_one_row { 1 } else { 0 };
+        Statistics {
+            num_rows: Some(num_rows),
+            total_byte_size: None,
+            column_statistics: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -20,6 +20,7 @@
 use std::any::Any;
 use std::sync::Arc;
 
+use crate::datasource::datasource::Statistics;
 use crate::error::{DataFusionError, Result};
 use crate::{
     logical_plan::StringifiedPlan,
@@ -122,4 +123,12 @@ impl ExecutionPlan for ExplainExec {
             vec![Arc::new(record_batch)],
         )))
     }
+
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            num_rows: Some(self.stringified_plans.len()),
+            total_byte_size: None,
+            column_statistics: None,
+        }
+    }
 }
@@ -0,0 +1,233 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the APPROX_DISTINCT aggregate expression
+
+use std::any::Any;
+use std::sync::Arc;
+
+use ahash::RandomState;
+use arrow::array::{Array, ArrayRef, BinaryArray};
+use arrow::datatypes::{DataType, Field};
+
+use super::format_state_name;
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::hash_join::create_hashes;
+use crate::physical_plan::hyperloglog::HyperLogLog;
+use crate::physical_plan::{Accumulator, AggregateExpr, PhysicalExpr};
+use crate::scalar::ScalarValue;
+
+/// Fixed hash function of the values, so that the sketches of different
+/// partitions can be merged
+const RANDOM_STATE: RandomState = RandomState::with_seeds(
+    0x243f_6a88_85a3_08d3,
+    0x1319_8a2e_0370_7344,
+    0xa409_3822_299f_31d0,
+    0x082e_fa98_ec4e_6c89,
+);
+
+/// APPROX_DISTINCT aggregate expression, which estimates the number of distinct
+/// non-null values with a HyperLogLog sketch
+#[derive(Debug)]
+pub struct ApproxDistinct {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl ApproxDistinct {
+    /// Create a new APPROX_DISTINCT aggregate function
+    pub fn new(expr: Arc<dyn PhysicalExpr>, name: String, data_type: DataType) -> Self {
+        Self {
+            name,
+            data_type,
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for ApproxDistinct {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "hll_registers"),
+            DataType::Binary,
+            false,
+        )])
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ApproxDistinctAccumulator::new()))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+}
+
+/// An accumulator to estimate the number of distinct values
+#[derive(Debug)]
+pub struct ApproxDistinctAccumulator {
+    hll: HyperLogLog,
+}
+
+impl ApproxDistinctAccumulator {
+    /// Creates a new, empty `ApproxDistinctAccumulator`
+    pub fn new() -> Self {
+        Self {
+            hll: HyperLogLog::new(),
+        }
+    }
+}
+
+impl Default for ApproxDistinctAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Accumulator for ApproxDistinctAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Binary(Some(self.hll.to_bytes()))])
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        self.update_batch(&[values[0].to_array()])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        let mut hashes = vec![0; values.len()];
+        create_hashes(std::slice::from_ref(values), &RANDOM_STATE, &mut hashes)?;
+        hashes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| values.is_valid(*i))
+            .for_each(|(_, hash)| self.hll.add_hash(*hash));
+        Ok(())
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        match &states[0] {
+            ScalarValue::Binary(Some(bytes)) => {
+                self.hll.merge(&HyperLogLog::try_from_bytes(bytes)?);
+                Ok(())
+            }
+            other => Err(DataFusionError::Internal(format!(
+                "Unexpected state of APPROX_DISTINCT: {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let states = states[0]
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "Unexpected state of APPROX_DISTINCT: {:?}",
+                    states[0].data_type()
+                ))
+            })?;
+        (0..states.len())
+            .filter(|i| states.is_valid(*i))
+            .try_for_each(|i| {
+                self.hll
+                    .merge(&HyperLogLog::try_from_bytes(states.value(i))?);
+                Ok(())
+            })
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::UInt64(Some(self.hll.count())))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.hll) + self.hll.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::expressions::tests::aggregate;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    fn approx_distinct(array: ArrayRef) -> Result<ScalarValue> {
+        let schema = Schema::new(vec![Field::new("a", array.data_type().clone(), true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![array])?;
+        let agg = Arc::new(ApproxDistinct::new(
+            col("a"),
+            "bla".to_string(),
+            DataType::UInt64,
+        ));
+        aggregate(&batch, agg)
+    }
+
+    #[test]
+    fn approx_distinct_values() -> Result<()> {
+        let array = Arc::new(StringArray::from(vec![
+            Some("a"),
+            None,
+            Some("b"),
+            Some("a"),
+            None,
+            Some("c"),
+        ]));
+        assert_eq!(ScalarValue::from(3_u64), approx_distinct(array)?);
+
+        let array = Arc::new(Int64Array::from(
+            (0..10_000).map(|i| i % 5_000).collect::<Vec<i64>>(),
+        ));
+        match approx_distinct(array)? {
+            ScalarValue::UInt64(Some(count)) => {
+                assert!((4900..5100).contains(&count), "{}", count)
+            }
+            other => panic!("unexpected count {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn approx_distinct_merge() -> Result<()> {
+        let mut merged = ApproxDistinctAccumulator::new();
+        for values in &[vec![1_i64, 2, 3], vec![2, 3, 4], vec![]] {
+            let mut partial = ApproxDistinctAccumulator::new();
+            partial.update_batch(&[Arc::new(Int64Array::from(values.clone()))])?;
+            let state = partial
+                .state()?
+                .iter()
+                .map(|v| v.to_array())
+                .collect::<Vec<_>>();
+            merged.merge_batch(&state)?;
+        }
+        assert_eq!(ScalarValue::from(4_u64), merged.evaluate()?);
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the APPROX_PERCENTILE_CONT and APPROX_MEDIAN aggregate expressions
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BinaryArray, Float64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::{format_state_name, lit};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::tdigest::{TDigest, DEFAULT_COMPRESSION};
+use crate::physical_plan::{Accumulator, AggregateExpr, ColumnarValue, PhysicalExpr};
+use crate::scalar::ScalarValue;
+
+/// APPROX_PERCENTILE_CONT aggregate expression, which estimates a percentile of
+/// its input with a t-digest. APPROX_MEDIAN is its 0.5 percentile.
+#[derive(Debug)]
+pub struct ApproxPercentileCont {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+    percentile: f64,
+}
+
+impl ApproxPercentileCont {
+    /// Create a new APPROX_PERCENTILE_CONT aggregate function of a Float64 `expr`.
+    /// `percentile` must be a constant expression between 0 and 1.
+    pub fn try_new(
+        expr: Arc<dyn PhysicalExpr>,
+        percentile: Arc<dyn PhysicalExpr>,
+        name: String,
+        data_type: DataType,
+    ) -> Result<Self> {
+        // a constant expression does not need any column to be evaluated
+        let batch = RecordBatch::new_empty(Arc::new(Schema::empty()));
+        let percentile = match percentile.evaluate(&batch) {
+            Ok(ColumnarValue::Scalar(ScalarValue::Float64(Some(p))))
+                if (0.0..=1.0).contains(&p) =>
+            {
+                p
+            }
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "The percentile of APPROX_PERCENTILE_CONT must be a literal between 0 and 1, got {}",
+                    percentile
+                )))
+            }
+        };
+        Ok(Self {
+            name,
+            data_type,
+            expr,
+            percentile,
+        })
+    }
+
+    /// The percentile to estimate
+    pub fn percentile(&self) -> f64 {
+        self.percentile
+    }
+}
+
+impl AggregateExpr for ApproxPercentileCont {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "tdigest"),
+            DataType::Binary,
+            false,
+        )])
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ApproxPercentileAccumulator::new(self.percentile)))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        // the percentile is part of the expressions so that it is visible in plans
+        vec![
+            self.expr.clone(),
+            lit(ScalarValue::Float64(Some(self.percentile))),
+        ]
+    }
+}
+
+/// An accumulator to estimate a percentile of its input
+#[derive(Debug)]
+pub struct ApproxPercentileAccumulator {
+    digest: TDigest,
+    percentile: f64,
+}
+
+impl ApproxPercentileAccumulator {
+    /// Creates a new, empty `ApproxPercentileAccumulator` of the given percentile
+    pub fn new(percentile: f64) -> Self {
+        Self {
+            digest: TDigest::new(DEFAULT_COMPRESSION),
+            percentile,
+        }
+    }
+}
+
+impl Accumulator for ApproxPercentileAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Binary(Some(self.digest.to_bytes()))])
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        match &values[0] {
+            ScalarValue::Float64(Some(value)) => self.digest.add(*value),
+            ScalarValue::Float64(None) => {}
+            other => {
+                return Err(DataFusionError::Internal(format!(
+                    "APPROX_PERCENTILE_CONT expects Float64 values, got {:?}",
+                    other
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = values[0]
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "APPROX_PERCENTILE_CONT expects Float64 values, got {:?}",
+                    values[0].data_type()
+                ))
+            })?;
+        values.iter().flatten().for_each(|v| self.digest.add(v));
+        Ok(())
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        match &states[0] {
+            ScalarValue::Binary(Some(bytes)) => {
+                self.digest.merge(&TDigest::try_from_bytes(bytes)?);
+                Ok(())
+            }
+            other => Err(DataFusionError::Internal(format!(
+                "Unexpected state of APPROX_PERCENTILE_CONT: {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let states = states[0]
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "Unexpected state of APPROX_PERCENTILE_CONT: {:?}",
+                    states[0].data_type()
+                ))
+            })?;
+        (0..states.len())
+            .filter(|i| states.is_valid(*i))
+            .try_for_each(|i| {
+                self.digest
+                    .merge(&TDigest::try_from_bytes(states.value(i))?);
+                Ok(())
+            })
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Float64(self.digest.quantile(self.percentile)))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.digest)
+            + self.digest.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::tests::aggregate;
+    use crate::physical_plan::expressions::{col, lit};
+
+    fn approx_percentile(
+        values: Vec<Option<f64>>,
+        percentile: f64,
+    ) -> Result<ScalarValue> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Float64, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Float64Array::from(values))],
+        )?;
+        let agg = Arc::new(ApproxPercentileCont::try_new(
+            col("a"),
+            lit(ScalarValue::from(percentile)),
+            "bla".to_string(),
+            DataType::Float64,
+        )?);
+        aggregate(&batch, agg)
+    }
+
+    #[test]
+    fn approx_percentile_cont() -> Result<()> {
+        let values = vec![Some(4.0), None, Some(1.0), Some(3.0), Some(2.0)];
+        assert_eq!(
+            ScalarValue::from(2.5),
+            approx_percentile(values.clone(), 0.5)?
+        );
+        assert_eq!(
+            ScalarValue::from(3.25),
+            approx_percentile(values.clone(), 0.75)?
+        );
+        assert_eq!(
+            ScalarValue::Float64(None),
+            approx_percentile(vec![None], 0.5)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_percentile() {
+        for percentile in &[lit(ScalarValue::from(1.5)), col("a")] {
+            let agg = ApproxPercentileCont::try_new(
+                col("a"),
+                percentile.clone(),
+                "bla".to_string(),
+                DataType::Float64,
+            );
+            assert!(agg.is_err());
+        }
+    }
+
+    #[test]
+    fn approx_percentile_merge() -> Result<()> {
+        let mut merged = ApproxPercentileAccumulator::new(0.1);
+        for values in &[vec![5.0, 1.0], vec![], vec![3.0, 2.0, 4.0]] {
+            let mut partial = ApproxPercentileAccumulator::new(0.1);
+            partial.update_batch(&[Arc::new(Float64Array::from(values.clone()))])?;
+            merged.merge(&partial.state()?)?;
+        }
+        assert_eq!(ScalarValue::from(1.4), merged.evaluate()?);
+        Ok(())
+    }
+}
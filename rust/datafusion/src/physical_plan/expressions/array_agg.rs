@@ -0,0 +1,547 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the ARRAY_AGG aggregate expressions
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use ahash::RandomState;
+use arrow::compute::{lexsort_to_indices, sort_to_indices, SortColumn, SortOptions};
+use arrow::datatypes::{DataType, Field, Schema};
+
+use super::{format_state_name, PhysicalSortExpr};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::group_scalar::GroupByScalar;
+use crate::physical_plan::{Accumulator, AggregateExpr, PhysicalExpr};
+use crate::scalar::ScalarValue;
+
+/// The type of a list of values of type `data_type`
+fn list_type(data_type: &DataType) -> DataType {
+    DataType::List(Box::new(Field::new("item", data_type.clone(), true)))
+}
+
+/// The values of a list state
+fn list_values(state: &ScalarValue) -> Result<&[ScalarValue]> {
+    match state {
+        ScalarValue::List(Some(values), _) => Ok(values),
+        ScalarValue::List(None, _) => Ok(&[]),
+        other => Err(DataFusionError::Internal(format!(
+            "Unexpected accumulator state {:?}",
+            other
+        ))),
+    }
+}
+
+/// ARRAY_AGG aggregate expression, which collects its input values, nulls
+/// included, into a list
+#[derive(Debug)]
+pub struct ArrayAgg {
+    name: String,
+    input_data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+    ordering: Vec<PhysicalSortExpr>,
+    ordering_data_types: Vec<DataType>,
+}
+
+impl ArrayAgg {
+    /// Create a new ARRAY_AGG aggregate function of `expr`, whose values are of
+    /// type `input_data_type`. The values are ordered by `ordering`, or are in no
+    /// particular order if it is empty.
+    pub fn try_new(
+        expr: Arc<dyn PhysicalExpr>,
+        ordering: Vec<PhysicalSortExpr>,
+        input_schema: &Schema,
+        name: String,
+        input_data_type: DataType,
+    ) -> Result<Self> {
+        let ordering_data_types = ordering
+            .iter()
+            .map(|e| e.expr.data_type(input_schema))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            name,
+            input_data_type,
+            expr,
+            ordering,
+            ordering_data_types,
+        })
+    }
+
+    /// The ordering of the values in the list
+    pub fn ordering(&self) -> &[PhysicalSortExpr] {
+        &self.ordering
+    }
+}
+
+impl AggregateExpr for ArrayAgg {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(
+            &self.name,
+            list_type(&self.input_data_type),
+            true,
+        ))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        let mut fields = vec![Field::new(
+            &format_state_name(&self.name, "array_agg"),
+            list_type(&self.input_data_type),
+            true,
+        )];
+        // the values of the ordering expressions, to order the merged lists
+        fields.extend(self.ordering_data_types.iter().enumerate().map(
+            |(i, data_type)| {
+                Field::new(
+                    &format_state_name(&self.name, &format!("ordering_{}", i)),
+                    list_type(data_type),
+                    true,
+                )
+            },
+        ));
+        Ok(fields)
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ArrayAggAccumulator::new(
+            self.input_data_type.clone(),
+            self.ordering_data_types
+                .iter()
+                .cloned()
+                .zip(self.ordering.iter().map(|e| e.options))
+                .collect(),
+        )))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        let mut expressions = vec![self.expr.clone()];
+        expressions.extend(self.ordering.iter().map(|e| e.expr.clone()));
+        expressions
+    }
+}
+
+/// An accumulator to collect values into a list
+#[derive(Debug)]
+pub struct ArrayAggAccumulator {
+    values: Vec<ScalarValue>,
+    /// the values of every ordering expression, in the order of `values`
+    ordering_values: Vec<Vec<ScalarValue>>,
+    data_type: DataType,
+    /// the type and the sort options of every ordering expression
+    ordering: Vec<(DataType, SortOptions)>,
+}
+
+impl ArrayAggAccumulator {
+    /// Creates a new, empty `ArrayAggAccumulator` of values of type `data_type`,
+    /// ordered by the values of the given types and options
+    pub fn new(data_type: DataType, ordering: Vec<(DataType, SortOptions)>) -> Self {
+        Self {
+            values: vec![],
+            ordering_values: vec![vec![]; ordering.len()],
+            data_type,
+            ordering,
+        }
+    }
+}
+
+impl Accumulator for ArrayAggAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        let mut state = vec![ScalarValue::List(
+            Some(self.values.clone()),
+            self.data_type.clone(),
+        )];
+        state.extend(self.ordering_values.iter().zip(self.ordering.iter()).map(
+            |(values, (data_type, _))| {
+                ScalarValue::List(Some(values.clone()), data_type.clone())
+            },
+        ));
+        Ok(state)
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        self.values.push(values[0].clone());
+        self.ordering_values
+            .iter_mut()
+            .zip(values[1..].iter())
+            .for_each(|(ordering_values, value)| ordering_values.push(value.clone()));
+        Ok(())
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        self.values.extend_from_slice(list_values(&states[0])?);
+        self.ordering_values
+            .iter_mut()
+            .zip(states[1..].iter())
+            .try_for_each(|(ordering_values, state)| {
+                ordering_values.extend_from_slice(list_values(state)?);
+                Ok(())
+            })
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        if self.values.is_empty() {
+            return Ok(ScalarValue::List(None, self.data_type.clone()));
+        }
+        if self.ordering.is_empty() {
+            return Ok(ScalarValue::List(
+                Some(self.values.clone()),
+                self.data_type.clone(),
+            ));
+        }
+
+        let columns = self
+            .ordering_values
+            .iter()
+            .zip(self.ordering.iter())
+            .map(|(values, (data_type, options))| {
+                Ok(SortColumn {
+                    values: ScalarValue::iter_to_array(values, data_type)?,
+                    options: Some(*options),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let indices = lexsort_to_indices(&columns, None)?;
+        Ok(ScalarValue::List(
+            Some(
+                indices
+                    .values()
+                    .iter()
+                    .map(|i| self.values[*i as usize].clone())
+                    .collect(),
+            ),
+            self.data_type.clone(),
+        ))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.values.iter().map(|v| v.size()).sum::<usize>()
+            + self
+                .ordering_values
+                .iter()
+                .flatten()
+                .map(|v| v.size())
+                .sum::<usize>()
+    }
+}
+
+/// Expression for an ARRAY_AGG(DISTINCT) aggregation, which collects the distinct
+/// values of its input, in ascending order, into a list
+#[derive(Debug)]
+pub struct DistinctArrayAgg {
+    name: String,
+    input_data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl DistinctArrayAgg {
+    /// Create a new ARRAY_AGG(DISTINCT) aggregate function of `expr`, whose
+    /// values are of type `input_data_type`
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: String,
+        input_data_type: DataType,
+    ) -> Self {
+        Self {
+            name,
+            input_data_type,
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for DistinctArrayAgg {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(
+            &self.name,
+            list_type(&self.input_data_type),
+            true,
+        ))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "distinct_array_agg"),
+            list_type(&self.input_data_type),
+            true,
+        )])
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(DistinctArrayAggAccumulator::new(
+            self.input_data_type.clone(),
+        )))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+}
+
+/// An accumulator to collect the distinct values into a list
+#[derive(Debug)]
+pub struct DistinctArrayAggAccumulator {
+    /// the distinct values, `None` being the null value
+    values: HashSet<Option<GroupByScalar>, RandomState>,
+    data_type: DataType,
+}
+
+impl DistinctArrayAggAccumulator {
+    /// Creates a new, empty `DistinctArrayAggAccumulator` of values of type
+    /// `data_type`
+    pub fn new(data_type: DataType) -> Self {
+        Self {
+            values: HashSet::default(),
+            data_type,
+        }
+    }
+
+    fn scalar_values(&self) -> Result<Vec<ScalarValue>> {
+        self.values
+            .iter()
+            .map(|value| match value {
+                Some(value) => Ok(ScalarValue::from(value)),
+                None => ScalarValue::try_from(&self.data_type),
+            })
+            .collect()
+    }
+
+    /// The distinct values, in ascending order with the null value last
+    pub(super) fn sorted_values(&self) -> Result<Vec<ScalarValue>> {
+        let values = self.scalar_values()?;
+        let options = SortOptions {
+            descending: false,
+            nulls_first: false,
+        };
+        let indices = sort_to_indices(
+            &ScalarValue::iter_to_array(&values, &self.data_type)?,
+            Some(options),
+            None,
+        )?;
+        Ok(indices
+            .values()
+            .iter()
+            .map(|i| values[*i as usize].clone())
+            .collect())
+    }
+}
+
+impl Accumulator for DistinctArrayAggAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::List(
+            Some(self.scalar_values()?),
+            self.data_type.clone(),
+        )])
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        let value = match &values[0] {
+            value if value.is_null() => None,
+            value => Some(GroupByScalar::try_from(value)?),
+        };
+        self.values.insert(value);
+        Ok(())
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        list_values(&states[0])?
+            .iter()
+            .try_for_each(|value| self.update(std::slice::from_ref(value)))
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        if self.values.is_empty() {
+            return Ok(ScalarValue::List(None, self.data_type.clone()));
+        }
+        Ok(ScalarValue::List(
+            Some(self.sorted_values()?),
+            self.data_type.clone(),
+        ))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self
+                .values
+                .iter()
+                .map(|v| match v {
+                    Some(v) => v.size(),
+                    None => std::mem::size_of::<GroupByScalar>(),
+                })
+                .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::expressions::tests::aggregate;
+    use arrow::array::{ArrayRef, Float64Array, Int32Array, StringArray};
+    use arrow::record_batch::RecordBatch;
+
+    fn batch() -> Result<RecordBatch> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        Ok(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec![
+                    Some("x"),
+                    None,
+                    Some("y"),
+                    Some("x"),
+                ])),
+                Arc::new(Int32Array::from(vec![Some(3), Some(1), None, Some(2)])),
+            ],
+        )?)
+    }
+
+    fn utf8_list(values: Vec<Option<&str>>) -> ScalarValue {
+        ScalarValue::List(
+            Some(
+                values
+                    .into_iter()
+                    .map(|v| ScalarValue::Utf8(v.map(|v| v.to_string())))
+                    .collect(),
+            ),
+            DataType::Utf8,
+        )
+    }
+
+    #[test]
+    fn array_agg() -> Result<()> {
+        let batch = batch()?;
+        let agg = Arc::new(ArrayAgg::try_new(
+            col("a"),
+            vec![],
+            &batch.schema(),
+            "bla".to_string(),
+            DataType::Utf8,
+        )?);
+        assert_eq!(
+            utf8_list(vec![Some("x"), None, Some("y"), Some("x")]),
+            aggregate(&batch, agg)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn array_agg_ordered() -> Result<()> {
+        let batch = batch()?;
+        let ordering = vec![PhysicalSortExpr {
+            expr: col("b"),
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+        let agg = Arc::new(ArrayAgg::try_new(
+            col("a"),
+            ordering,
+            &batch.schema(),
+            "bla".to_string(),
+            DataType::Utf8,
+        )?);
+        assert_eq!(
+            utf8_list(vec![Some("y"), Some("x"), Some("x"), None]),
+            aggregate(&batch, agg)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn array_agg_ordered_merge() -> Result<()> {
+        let ordering = vec![(DataType::Float64, SortOptions::default())];
+        let values = Arc::new(Int32Array::from(vec![1, 2, 3, 4])) as ArrayRef;
+        let keys = Arc::new(Float64Array::from(vec![0.4, 0.3, 0.2, 0.1])) as ArrayRef;
+
+        let mut merged = ArrayAggAccumulator::new(DataType::Int32, ordering.clone());
+        for (offset, len) in &[(0, 3), (3, 1), (4, 0)] {
+            let mut partial = ArrayAggAccumulator::new(DataType::Int32, ordering.clone());
+            partial.update_batch(&[
+                values.slice(*offset, *len),
+                keys.slice(*offset, *len),
+            ])?;
+            let state = partial
+                .state()?
+                .iter()
+                .map(|v| v.to_array())
+                .collect::<Vec<_>>();
+            merged.merge_batch(&state)?;
+        }
+        assert_eq!(
+            ScalarValue::List(
+                Some((1..=4).rev().map(|v| ScalarValue::Int32(Some(v))).collect()),
+                DataType::Int32
+            ),
+            merged.evaluate()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_array_agg() -> Result<()> {
+        let batch = batch()?;
+        let agg = Arc::new(DistinctArrayAgg::new(
+            col("a"),
+            "bla".to_string(),
+            DataType::Utf8,
+        ));
+        assert_eq!(
+            utf8_list(vec![Some("x"), Some("y"), None]),
+            aggregate(&batch, agg)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_array_agg_merge() -> Result<()> {
+        let mut merged = DistinctArrayAggAccumulator::new(DataType::Int32);
+        for values in &[vec![3, 1, 3], vec![2, 1], vec![]] {
+            let mut partial = DistinctArrayAggAccumulator::new(DataType::Int32);
+            partial.update_batch(&[Arc::new(Int32Array::from(values.clone()))])?;
+            merged.merge(&partial.state()?)?;
+        }
+        assert_eq!(
+            ScalarValue::List(
+                Some((1..=3).map(|v| ScalarValue::Int32(Some(v))).collect()),
+                DataType::Int32
+            ),
+            merged.evaluate()?
+        );
+        assert_eq!(
+            ScalarValue::List(None, DataType::Int32),
+            DistinctArrayAggAccumulator::new(DataType::Int32).evaluate()?
+        );
+        Ok(())
+    }
+}
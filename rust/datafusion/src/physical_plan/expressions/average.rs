@@ -25,7 +25,7 @@ use crate::error::{DataFusionError, Result};
 use crate::physical_plan::{Accumulator, AggregateExpr, PhysicalExpr};
 use crate::scalar::ScalarValue;
 use arrow::compute;
-use arrow::datatypes::DataType;
+use arrow::datatypes::{DataType, DECIMAL_MAX_PRECISION};
 use arrow::{
     array::{ArrayRef, UInt64Array},
     datatypes::Field,
@@ -55,6 +55,11 @@ pub fn avg_return_type(arg_type: &DataType) -> Result<DataType> {
         | DataType::UInt64
         | DataType::Float32
         | DataType::Float64 => Ok(DataType::Float64),
+        // averages of decimals get 4 more fractional digits, like in Spark
+        DataType::Decimal(precision, scale) => Ok(DataType::Decimal(
+            (precision + 4).min(DECIMAL_MAX_PRECISION),
+            (scale + 4).min(DECIMAL_MAX_PRECISION),
+        )),
         other => Err(DataFusionError::Plan(format!(
             "AVG does not support {:?}",
             other
@@ -74,6 +79,15 @@ impl Avg {
     }
 }
 
+/// The type of the sum kept in the state of an average of type `data_type`: decimals
+/// are summed at the scale of the average, all other types as f64
+fn avg_sum_type(data_type: &DataType) -> DataType {
+    match data_type {
+        DataType::Decimal(_, scale) => DataType::Decimal(DECIMAL_MAX_PRECISION, *scale),
+        _ => DataType::Float64,
+    }
+}
+
 impl AggregateExpr for Avg {
     /// Return a reference to Any that can be used for downcasting
     fn as_any(&self) -> &dyn Any {
@@ -81,7 +95,7 @@ impl AggregateExpr for Avg {
     }
 
     fn field(&self) -> Result<Field> {
-        Ok(Field::new(&self.name, DataType::Float64, true))
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
     }
 
     fn state_fields(&self) -> Result<Vec<Field>> {
@@ -93,17 +107,14 @@ impl AggregateExpr for Avg {
             ),
             Field::new(
                 &format_state_name(&self.name, "sum"),
-                DataType::Float64,
+                avg_sum_type(&self.data_type),
                 true,
             ),
         ])
     }
 
     fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
-        Ok(Box::new(AvgAccumulator::try_new(
-            // avg is f64
-            &DataType::Float64,
-        )?))
+        Ok(Box::new(AvgAccumulator::try_new(&self.data_type)?))
     }
 
     fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
@@ -117,14 +128,16 @@ pub struct AvgAccumulator {
     // sum is used for null
     sum: ScalarValue,
     count: u64,
+    data_type: DataType,
 }
 
 impl AvgAccumulator {
-    /// Creates a new `AvgAccumulator`
+    /// Creates a new `AvgAccumulator` of an average of type `datatype`
     pub fn try_new(datatype: &DataType) -> Result<Self> {
         Ok(Self {
-            sum: ScalarValue::try_from(datatype)?,
+            sum: ScalarValue::try_from(&avg_sum_type(datatype))?,
             count: 0,
+            data_type: datatype.clone(),
         })
     }
 }
@@ -180,8 +193,31 @@ impl Accumulator for AvgAccumulator {
             ScalarValue::Float64(e) => {
                 Ok(ScalarValue::Float64(e.map(|f| f / self.count as f64)))
             }
+            ScalarValue::Decimal128(e, _, scale) => {
+                // the quotient is rounded half away from zero
+                let count = self.count as i128;
+                let value = e.map(|v| {
+                    let (quotient, remainder) = (v / count, v % count);
+                    if 2 * remainder.abs() >= count {
+                        quotient + v.signum()
+                    } else {
+                        quotient
+                    }
+                });
+                let precision = match self.data_type {
+                    DataType::Decimal(precision, _) => precision,
+                    _ => DECIMAL_MAX_PRECISION,
+                };
+                if matches!(value, Some(v) if v.abs() >= 10_i128.pow(precision as u32)) {
+                    return Err(DataFusionError::Execution(format!(
+                        "Overflow happened on an average of type Decimal({}, {})",
+                        precision, scale
+                    )));
+                }
+                Ok(ScalarValue::Decimal128(value, precision, scale))
+            }
             _ => Err(DataFusionError::Internal(
-                "Sum should be f64 on average".to_string(),
+                "Sum should be f64 or decimal on average".to_string(),
             )),
         }
     }
@@ -237,6 +273,36 @@ mod tests {
         )
     }
 
+    #[test]
+    fn avg_decimal() -> Result<()> {
+        let a: ArrayRef = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(100), None, Some(200), Some(201)],
+            5,
+            2,
+        ));
+        // (1.00 + 2.00 + 2.01) / 3 = 1.67
+        generic_test_op!(
+            a,
+            DataType::Decimal(5, 2),
+            Avg,
+            ScalarValue::Decimal128(Some(1_670_000), 9, 6),
+            DataType::Decimal(9, 6)
+        )
+    }
+
+    #[test]
+    fn avg_decimal_all_nulls() -> Result<()> {
+        let a: ArrayRef =
+            Arc::new(DecimalArray::from_sparse_iter(vec![None, None], 5, 2));
+        generic_test_op!(
+            a,
+            DataType::Decimal(5, 2),
+            Avg,
+            ScalarValue::Decimal128(None, 9, 6),
+            DataType::Decimal(9, 6)
+        )
+    }
+
     #[test]
     fn avg_u32() -> Result<()> {
         let a: ArrayRef =
@@ -19,10 +19,16 @@ use std::{any::Any, sync::Arc};
 
 use arrow::array::*;
 use arrow::compute::kernels::arithmetic::{
-    add, divide, divide_scalar, multiply, subtract,
+    add, add_decimal, divide, divide_decimal, divide_scalar, multiply, multiply_decimal,
+    subtract, subtract_decimal,
 };
 use arrow::compute::kernels::boolean::{and_kleene, or_kleene};
 use arrow::compute::kernels::comparison::{eq, gt, gt_eq, lt, lt_eq, neq};
+use arrow::compute::kernels::comparison::{
+    eq_decimal, eq_decimal_scalar, gt_decimal, gt_decimal_scalar, gt_eq_decimal,
+    gt_eq_decimal_scalar, lt_decimal, lt_decimal_scalar, lt_eq_decimal,
+    lt_eq_decimal_scalar, neq_decimal, neq_decimal_scalar,
+};
 use arrow::compute::kernels::comparison::{
     eq_scalar, gt_eq_scalar, gt_scalar, lt_eq_scalar, lt_scalar, neq_scalar,
 };
@@ -34,7 +40,7 @@ use arrow::compute::kernels::comparison::{
     eq_utf8_scalar, gt_eq_utf8_scalar, gt_utf8_scalar, lt_eq_utf8_scalar, lt_utf8_scalar,
     neq_utf8_scalar,
 };
-use arrow::datatypes::{DataType, Schema, TimeUnit};
+use arrow::datatypes::{DataType, Schema, TimeUnit, DECIMAL_MAX_PRECISION};
 use arrow::record_batch::RecordBatch;
 
 use crate::error::{DataFusionError, Result};
@@ -121,6 +127,42 @@ macro_rules! compute_utf8_op_scalar {
     }};
 }
 
+/// Invoke a compute kernel on a pair of decimal arrays
+macro_rules! compute_decimal_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
+        let ll = $LEFT
+            .as_any()
+            .downcast_ref::<DecimalArray>()
+            .expect("compute_op failed to downcast array");
+        let rr = $RIGHT
+            .as_any()
+            .downcast_ref::<DecimalArray>()
+            .expect("compute_op failed to downcast array");
+        Ok(Arc::new(paste::expr! {[<$OP _decimal>]}(&ll, &rr)?))
+    }};
+}
+
+/// Invoke a compute kernel on a decimal array and a scalar value
+macro_rules! compute_decimal_op_scalar {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
+        let ll = $LEFT
+            .as_any()
+            .downcast_ref::<DecimalArray>()
+            .expect("compute_op failed to downcast array");
+        if let ScalarValue::Decimal128(Some(decimal_value), _, _) = $RIGHT {
+            Ok(Arc::new(paste::expr! {[<$OP _decimal_scalar>]}(
+                &ll,
+                decimal_value,
+            )?))
+        } else {
+            Err(DataFusionError::Internal(format!(
+                "compute_decimal_op_scalar failed to cast literal value {}",
+                $RIGHT
+            )))
+        }
+    }};
+}
+
 /// Invoke a compute kernel on a data array and a scalar value
 macro_rules! compute_op_scalar {
     ($LEFT:expr, $RIGHT:expr, $OP:ident, $DT:ident) => {{
@@ -203,6 +245,7 @@ macro_rules! binary_primitive_array_op {
             DataType::UInt64 => compute_op!($LEFT, $RIGHT, $OP, UInt64Array),
             DataType::Float32 => compute_op!($LEFT, $RIGHT, $OP, Float32Array),
             DataType::Float64 => compute_op!($LEFT, $RIGHT, $OP, Float64Array),
+            DataType::Decimal(_, _) => compute_decimal_op!($LEFT, $RIGHT, $OP),
             other => Err(DataFusionError::Internal(format!(
                 "Data type {:?} not supported for binary operation on primitive arrays",
                 other
@@ -252,6 +295,7 @@ macro_rules! binary_array_op_scalar {
             DataType::UInt64 => compute_op_scalar!($LEFT, $RIGHT, $OP, UInt64Array),
             DataType::Float32 => compute_op_scalar!($LEFT, $RIGHT, $OP, Float32Array),
             DataType::Float64 => compute_op_scalar!($LEFT, $RIGHT, $OP, Float64Array),
+            DataType::Decimal(_, _) => compute_decimal_op_scalar!($LEFT, $RIGHT, $OP),
             DataType::Utf8 => compute_utf8_op_scalar!($LEFT, $RIGHT, $OP, StringArray),
             DataType::Timestamp(TimeUnit::Nanosecond, None) => {
                 compute_op_scalar!($LEFT, $RIGHT, $OP, TimestampNanosecondArray)
@@ -284,6 +328,7 @@ macro_rules! binary_array_op {
             DataType::UInt64 => compute_op!($LEFT, $RIGHT, $OP, UInt64Array),
             DataType::Float32 => compute_op!($LEFT, $RIGHT, $OP, Float32Array),
             DataType::Float64 => compute_op!($LEFT, $RIGHT, $OP, Float64Array),
+            DataType::Decimal(_, _) => compute_decimal_op!($LEFT, $RIGHT, $OP),
             DataType::Utf8 => compute_utf8_op!($LEFT, $RIGHT, $OP, StringArray),
             DataType::Timestamp(TimeUnit::Nanosecond, None) => {
                 compute_op!($LEFT, $RIGHT, $OP, TimestampNanosecondArray)
@@ -388,6 +433,12 @@ pub fn binary_operator_data_type(
         | Operator::Gt
         | Operator::GtEq
         | Operator::LtEq => Ok(DataType::Boolean),
+        // math operations on decimals widen the precision and scale of the common type
+        Operator::Plus | Operator::Minus | Operator::Divide | Operator::Multiply
+            if matches!(common_type, DataType::Decimal(_, _)) =>
+        {
+            decimal_op_data_type(&common_type, op)
+        }
         // math operations return the same value as the common coerced type
         Operator::Plus | Operator::Minus | Operator::Divide | Operator::Multiply => {
             Ok(common_type)
@@ -398,6 +449,46 @@ pub fn binary_operator_data_type(
     }
 }
 
+/// Returns the type of applying the math operator `op` to two decimals of type
+/// `common_type`. This MUST match the types returned by arrow's decimal kernels.
+fn decimal_op_data_type(common_type: &DataType, op: &Operator) -> Result<DataType> {
+    let (precision, scale) = match common_type {
+        DataType::Decimal(precision, scale) => (*precision, *scale),
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "Expected a decimal type, got {:?}",
+                other
+            )))
+        }
+    };
+    let (precision, scale) = match op {
+        Operator::Plus | Operator::Minus => {
+            ((precision + 1).min(DECIMAL_MAX_PRECISION), scale)
+        }
+        Operator::Multiply => ((2 * precision + 1).min(DECIMAL_MAX_PRECISION), 2 * scale),
+        Operator::Divide => {
+            let result_scale = (scale + precision + 1).clamp(6, DECIMAL_MAX_PRECISION);
+            (
+                (precision + result_scale).min(DECIMAL_MAX_PRECISION),
+                result_scale,
+            )
+        }
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "{} is not a math operator",
+                other
+            )))
+        }
+    };
+    if scale > precision {
+        return Err(DataFusionError::Plan(format!(
+            "The result of '{:?} {} {:?}' exceeds the maximum decimal precision of {}",
+            common_type, op, common_type, DECIMAL_MAX_PRECISION
+        )));
+    }
+    Ok(DataType::Decimal(precision, scale))
+}
+
 impl PhysicalExpr for BinaryExpr {
     /// Return a reference to Any that can be used for downcasting
     fn as_any(&self) -> &dyn Any {
@@ -451,7 +542,10 @@ impl PhysicalExpr for BinaryExpr {
                     Operator::NotLike => {
                         binary_string_array_op_scalar!(array, scalar.clone(), nlike)
                     }
-                    Operator::Divide => {
+                    // there is no scalar kernel to divide decimals
+                    Operator::Divide
+                        if !matches!(array.data_type(), DataType::Decimal(_, _)) =>
+                    {
                         binary_primitive_array_op_scalar!(array, scalar.clone(), divide)
                     }
                     // if scalar operation is not supported - fallback to array implementation
@@ -572,7 +666,7 @@ mod tests {
 
     use super::*;
     use crate::error::Result;
-    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::expressions::{col, Literal};
 
     // Create a binary expression without coercion. Used here when we do not want to coerce the expressions
     // to valid types. Usage can result in an execution (after plan) error.
@@ -964,6 +1058,75 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn decimal_op() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Decimal(5, 2), true),
+            Field::new("b", DataType::Decimal(4, 1), true),
+            Field::new("c", DataType::Int8, true),
+        ]);
+        let a = DecimalArray::from_sparse_iter(vec![Some(1250), None, Some(-300)], 5, 2);
+        let b = DecimalArray::from_sparse_iter(vec![Some(25), Some(10), Some(15)], 4, 1);
+        let c = Int8Array::from(vec![Some(2), Some(3), None]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(a), Arc::new(b), Arc::new(c)],
+        )?;
+
+        let cases = vec![
+            (
+                col("a"),
+                Operator::Plus,
+                col("b"),
+                DataType::Decimal(6, 2),
+                "15.00\n\n-1.50",
+            ),
+            (
+                col("a"),
+                Operator::Minus,
+                col("b"),
+                DataType::Decimal(6, 2),
+                "10.00\n\n-4.50",
+            ),
+            (
+                col("a"),
+                Operator::Multiply,
+                col("c"),
+                DataType::Decimal(11, 4),
+                "25.0000\n\n",
+            ),
+            (
+                col("a"),
+                Operator::Divide,
+                col("b"),
+                DataType::Decimal(13, 8),
+                "5.00000000\n\n-2.00000000",
+            ),
+        ];
+        for (l, op, r, expected_type, expected) in cases {
+            let expr = binary(l, op, r, &schema)?;
+            assert_eq!(expr.data_type(&schema)?, expected_type);
+            let result = expr.evaluate(&batch)?.into_array(batch.num_rows());
+            assert_eq!(result.data_type(), &expected_type);
+            assert_eq!(array_to_string(&result)?, expected);
+        }
+
+        // comparisons, with both arrays and literals
+        let lit_value = Arc::new(Literal::new(ScalarValue::Decimal128(Some(20), 4, 1)));
+        let cases = vec![
+            (col("a"), Operator::Gt, col("b"), "true\n\nfalse"),
+            (col("a"), Operator::Eq, col("c"), "false\n\n"),
+            (col("a"), Operator::Lt, lit_value.clone(), "false\n\ntrue"),
+            (lit_value, Operator::LtEq, col("b"), "true\nfalse\nfalse"),
+        ];
+        for (l, op, r, expected) in cases {
+            let expr = binary(l, op, r, &schema)?;
+            let result = expr.evaluate(&batch)?.into_array(batch.num_rows());
+            assert_eq!(array_to_string(&result)?, expected);
+        }
+        Ok(())
+    }
+
     fn apply_arithmetic<T: ArrowNumericType>(
         schema: SchemaRef,
         data: Vec<ArrayRef>,
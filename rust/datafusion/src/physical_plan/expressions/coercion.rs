@@ -17,7 +17,7 @@
 
 //! Coercion rules used to coerce types to match existing expressions' implementations
 
-use arrow::datatypes::DataType;
+use arrow::datatypes::{DataType, DECIMAL_MAX_PRECISION};
 
 /// Determine if a DataType is signed numeric or not
 pub fn is_signed_numeric(dt: &DataType) -> bool {
@@ -102,12 +102,55 @@ pub fn temporal_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<Dat
     }
 }
 
+/// Returns the `Decimal` type able to represent every value of the integer type `dt`
+fn integer_decimal_type(dt: &DataType) -> Option<DataType> {
+    use arrow::datatypes::DataType::*;
+    match dt {
+        Int8 | UInt8 => Some(Decimal(3, 0)),
+        Int16 | UInt16 => Some(Decimal(5, 0)),
+        Int32 | UInt32 => Some(Decimal(10, 0)),
+        Int64 => Some(Decimal(19, 0)),
+        UInt64 => Some(Decimal(20, 0)),
+        _ => None,
+    }
+}
+
+/// Coercion rules for Decimals: the decimal type that both lhs and rhs can be casted
+/// to while keeping all of their integer and fractional digits (up to the maximum
+/// precision). Integers are coerced to decimals and floats to `Float64`.
+pub fn decimal_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<DataType> {
+    use arrow::datatypes::DataType::*;
+    match (lhs_type, rhs_type) {
+        (Decimal(p1, s1), Decimal(p2, s2)) => {
+            let scale = *s1.max(s2);
+            let integer_digits = (p1 - s1).max(p2 - s2);
+            Some(Decimal(
+                (integer_digits + scale).min(DECIMAL_MAX_PRECISION),
+                scale,
+            ))
+        }
+        (Decimal(_, _), Float16 | Float32 | Float64)
+        | (Float16 | Float32 | Float64, Decimal(_, _)) => Some(Float64),
+        (Decimal(_, _), other) => {
+            integer_decimal_type(other).and_then(|t| decimal_coercion(lhs_type, &t))
+        }
+        (other, Decimal(_, _)) => {
+            integer_decimal_type(other).and_then(|t| decimal_coercion(&t, rhs_type))
+        }
+        _ => None,
+    }
+}
+
 /// Coercion rule for numerical types: The type that both lhs and rhs
 /// can be casted to for numerical calculation, while maintaining
 /// maximum precision
 pub fn numerical_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<DataType> {
     use arrow::datatypes::DataType::*;
 
+    if matches!(lhs_type, Decimal(_, _)) || matches!(rhs_type, Decimal(_, _)) {
+        return decimal_coercion(lhs_type, rhs_type);
+    }
+
     // error on any non-numeric type
     if !is_numeric(lhs_type) || !is_numeric(rhs_type) {
         return None;
@@ -205,4 +248,28 @@ mod tests {
         let rhs_type = Dictionary(Box::new(Int8), Box::new(Utf8));
         assert_eq!(dictionary_coercion(&lhs_type, &rhs_type), Some(Utf8));
     }
+
+    #[test]
+    fn test_decimal_coercion() {
+        use DataType::*;
+
+        assert_eq!(
+            numerical_coercion(&Decimal(10, 2), &Decimal(5, 4)),
+            Some(Decimal(12, 4))
+        );
+        assert_eq!(
+            numerical_coercion(&Decimal(38, 10), &Decimal(38, 20)),
+            Some(Decimal(38, 20))
+        );
+        assert_eq!(
+            numerical_coercion(&Int32, &Decimal(5, 2)),
+            Some(Decimal(12, 2))
+        );
+        assert_eq!(
+            numerical_coercion(&Decimal(5, 2), &Int8),
+            Some(Decimal(5, 2))
+        );
+        assert_eq!(numerical_coercion(&Decimal(5, 2), &Float32), Some(Float64));
+        assert_eq!(numerical_coercion(&Decimal(5, 2), &Utf8), None);
+    }
 }
@@ -0,0 +1,319 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the correlation aggregate expression
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::physical_plan::{Accumulator, AggregateExpr, PhysicalExpr};
+use crate::scalar::ScalarValue;
+use arrow::array::{Array, ArrayRef, Float64Array, UInt64Array};
+use arrow::datatypes::{DataType, Field};
+
+use super::covariance::CovarianceAccumulator;
+use super::format_state_name;
+use super::variance::{as_state_array, float64_value, StatsType, VarianceAccumulator};
+
+/// CORR aggregate expression, the Pearson correlation coefficient of its inputs
+#[derive(Debug)]
+pub struct Correlation {
+    name: String,
+    expr1: Arc<dyn PhysicalExpr>,
+    expr2: Arc<dyn PhysicalExpr>,
+    data_type: DataType,
+}
+
+impl Correlation {
+    /// Create a new correlation aggregate function. Both inputs must be of type
+    /// Float64.
+    pub fn new(
+        expr1: Arc<dyn PhysicalExpr>,
+        expr2: Arc<dyn PhysicalExpr>,
+        name: String,
+        data_type: DataType,
+    ) -> Self {
+        Self {
+            name,
+            expr1,
+            expr2,
+            data_type,
+        }
+    }
+}
+
+impl AggregateExpr for Correlation {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![
+            Field::new(
+                &format_state_name(&self.name, "count"),
+                DataType::UInt64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "mean1"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "mean2"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "algo_const"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "m2_1"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "m2_2"),
+                DataType::Float64,
+                true,
+            ),
+        ])
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(CorrelationAccumulator::new()))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr1.clone(), self.expr2.clone()]
+    }
+}
+
+/// An accumulator to compute the correlation of its two inputs. Rows where either
+/// input is null are ignored.
+///
+/// The state is the one of the covariance, plus the sums of squared differences
+/// to the mean of each input, so that it can be merged like theirs.
+#[derive(Debug)]
+pub struct CorrelationAccumulator {
+    covariance: CovarianceAccumulator,
+    variance1: VarianceAccumulator,
+    variance2: VarianceAccumulator,
+}
+
+impl CorrelationAccumulator {
+    /// Creates a new, empty `CorrelationAccumulator`
+    pub fn new() -> Self {
+        Self {
+            covariance: CovarianceAccumulator::new(StatsType::Population),
+            variance1: VarianceAccumulator::new(StatsType::Population),
+            variance2: VarianceAccumulator::new(StatsType::Population),
+        }
+    }
+
+    fn update_values(&mut self, value1: f64, value2: f64) {
+        self.covariance.update_values(value1, value2);
+        self.variance1.update_value(value1);
+        self.variance2.update_value(value2);
+    }
+
+    fn merge_state(&mut self, count: u64, means: (f64, f64), m2s: (f64, f64), c: f64) {
+        self.covariance.merge_state(count, means.0, means.1, c);
+        self.variance1.merge_state(count, means.0, m2s.0);
+        self.variance2.merge_state(count, means.1, m2s.1);
+    }
+}
+
+impl Default for CorrelationAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Accumulator for CorrelationAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        let mut state = self.covariance.state()?;
+        state.push(ScalarValue::from(self.variance1.m2()));
+        state.push(ScalarValue::from(self.variance2.m2()));
+        Ok(state)
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        if let (Some(value1), Some(value2)) =
+            (float64_value(&values[0])?, float64_value(&values[1])?)
+        {
+            self.update_values(value1, value2);
+        }
+        Ok(())
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        CovarianceAccumulator::for_each_pair(values, |value1, value2| {
+            self.update_values(value1, value2)
+        })
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        let count = match &states[0] {
+            ScalarValue::UInt64(Some(count)) => *count,
+            _ => 0,
+        };
+        let values = states[1..]
+            .iter()
+            .map(|v| Ok(float64_value(v)?.unwrap_or(0.0)))
+            .collect::<Result<Vec<_>>>()?;
+        self.merge_state(
+            count,
+            (values[0], values[1]),
+            (values[3], values[4]),
+            values[2],
+        );
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let counts = as_state_array::<UInt64Array>(&states[0])?;
+        let values = states[1..]
+            .iter()
+            .map(as_state_array::<Float64Array>)
+            .collect::<Result<Vec<_>>>()?;
+        (0..counts.len())
+            .filter(|&i| counts.is_valid(i))
+            .for_each(|i| {
+                self.merge_state(
+                    counts.value(i),
+                    (values[0].value(i), values[1].value(i)),
+                    (values[3].value(i), values[4].value(i)),
+                    values[2].value(i),
+                )
+            });
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        let denominator = (self.variance1.m2() * self.variance2.m2()).sqrt();
+        // the correlation is undefined when either input is constant
+        Ok(ScalarValue::Float64(
+            if self.covariance.count() < 2 || denominator == 0.0 {
+                None
+            } else {
+                Some(self.covariance.algo_const() / denominator)
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::expressions::tests::aggregate;
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    fn correlation(
+        values1: Vec<Option<f64>>,
+        values2: Vec<Option<f64>>,
+    ) -> Result<ScalarValue> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Float64, true),
+            Field::new("b", DataType::Float64, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from(values1)),
+                Arc::new(Float64Array::from(values2)),
+            ],
+        )?;
+        let agg = Arc::new(Correlation::new(
+            col("a"),
+            col("b"),
+            "bla".to_string(),
+            DataType::Float64,
+        ));
+        aggregate(&batch, agg)
+    }
+
+    #[test]
+    fn correlation_linear() -> Result<()> {
+        let actual = correlation(
+            vec![Some(1.0), Some(2.0), None, Some(3.0)],
+            vec![Some(-2.0), Some(-4.0), Some(1.0), Some(-6.0)],
+        )?;
+        match actual {
+            ScalarValue::Float64(Some(v)) => assert!((v + 1.0).abs() < 1e-9),
+            other => panic!("unexpected correlation {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn correlation_undefined() -> Result<()> {
+        // constant input
+        let actual = correlation(
+            vec![Some(1.0), Some(2.0), Some(3.0)],
+            vec![Some(5.0), Some(5.0), Some(5.0)],
+        )?;
+        assert_eq!(ScalarValue::Float64(None), actual);
+
+        // a single pair
+        let actual = correlation(vec![Some(1.0), None], vec![Some(5.0), Some(6.0)])?;
+        assert_eq!(ScalarValue::Float64(None), actual);
+        Ok(())
+    }
+
+    #[test]
+    fn correlation_merge() -> Result<()> {
+        let values1 = Arc::new(Float64Array::from(vec![1.0, 2.0, 4.0, 7.0])) as ArrayRef;
+        let values2 = Arc::new(Float64Array::from(vec![3.0, 1.0, 4.0, 8.0])) as ArrayRef;
+
+        let mut whole = CorrelationAccumulator::new();
+        whole.update_batch(&[values1.clone(), values2.clone()])?;
+
+        let mut merged = CorrelationAccumulator::new();
+        for (offset, len) in &[(0, 1), (1, 3)] {
+            let mut partial = CorrelationAccumulator::new();
+            partial.update_batch(&[
+                values1.slice(*offset, *len),
+                values2.slice(*offset, *len),
+            ])?;
+            let state = partial
+                .state()?
+                .iter()
+                .map(|v| v.to_array())
+                .collect::<Vec<_>>();
+            merged.merge_batch(&state)?;
+        }
+
+        match (whole.evaluate()?, merged.evaluate()?) {
+            (ScalarValue::Float64(Some(a)), ScalarValue::Float64(Some(b))) => {
+                assert!((a - b).abs() < 1e-9)
+            }
+            other => panic!("unexpected correlations {:?}", other),
+        }
+        Ok(())
+    }
+}
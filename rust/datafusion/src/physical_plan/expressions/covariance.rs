@@ -0,0 +1,352 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the covariance aggregate expression
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::physical_plan::{Accumulator, AggregateExpr, PhysicalExpr};
+use crate::scalar::ScalarValue;
+use arrow::array::{Array, ArrayRef, Float64Array, UInt64Array};
+use arrow::datatypes::{DataType, Field};
+
+use super::format_state_name;
+use super::variance::{as_state_array, float64_value, StatsType};
+
+/// COVAR_SAMP and COVAR_POP aggregate expression
+#[derive(Debug)]
+pub struct Covariance {
+    name: String,
+    expr1: Arc<dyn PhysicalExpr>,
+    expr2: Arc<dyn PhysicalExpr>,
+    data_type: DataType,
+    stats_type: StatsType,
+}
+
+impl Covariance {
+    /// Create a new covariance aggregate function. Both inputs must be of type
+    /// Float64.
+    pub fn new(
+        expr1: Arc<dyn PhysicalExpr>,
+        expr2: Arc<dyn PhysicalExpr>,
+        name: String,
+        data_type: DataType,
+        stats_type: StatsType,
+    ) -> Self {
+        Self {
+            name,
+            expr1,
+            expr2,
+            data_type,
+            stats_type,
+        }
+    }
+
+    /// Whether this is the sample or the population statistic
+    pub fn stats_type(&self) -> StatsType {
+        self.stats_type
+    }
+}
+
+impl AggregateExpr for Covariance {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![
+            Field::new(
+                &format_state_name(&self.name, "count"),
+                DataType::UInt64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "mean1"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "mean2"),
+                DataType::Float64,
+                true,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "algo_const"),
+                DataType::Float64,
+                true,
+            ),
+        ])
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(CovarianceAccumulator::new(self.stats_type)))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr1.clone(), self.expr2.clone()]
+    }
+}
+
+/// An accumulator to compute the covariance of its two inputs. Rows where either
+/// input is null are ignored.
+///
+/// Like for the variance, the means and the co-moment (`algo_const`, the sum of
+/// the products of the differences to the means) are updated with an online
+/// algorithm and partial states are merged with the pairwise algorithm, which are
+/// numerically stable.
+#[derive(Debug)]
+pub struct CovarianceAccumulator {
+    count: u64,
+    mean1: f64,
+    mean2: f64,
+    algo_const: f64,
+    stats_type: StatsType,
+}
+
+impl CovarianceAccumulator {
+    /// Creates a new, empty `CovarianceAccumulator`
+    pub fn new(stats_type: StatsType) -> Self {
+        Self {
+            count: 0,
+            mean1: 0.0,
+            mean2: 0.0,
+            algo_const: 0.0,
+            stats_type,
+        }
+    }
+
+    /// Number of pairs of values seen
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Sum of the products of the differences between the values and their means
+    pub fn algo_const(&self) -> f64 {
+        self.algo_const
+    }
+
+    /// Adds a pair of values to the accumulator
+    pub(super) fn update_values(&mut self, value1: f64, value2: f64) {
+        self.count += 1;
+        let delta1 = value1 - self.mean1;
+        self.mean1 += delta1 / self.count as f64;
+        self.mean2 += (value2 - self.mean2) / self.count as f64;
+        self.algo_const += delta1 * (value2 - self.mean2);
+    }
+
+    /// Merges the state of another accumulator into this one
+    pub(super) fn merge_state(
+        &mut self,
+        count: u64,
+        mean1: f64,
+        mean2: f64,
+        algo_const: f64,
+    ) {
+        if count == 0 {
+            return;
+        }
+        let new_count = self.count + count;
+        let delta1 = mean1 - self.mean1;
+        let delta2 = mean2 - self.mean2;
+        self.mean1 += delta1 * count as f64 / new_count as f64;
+        self.mean2 += delta2 * count as f64 / new_count as f64;
+        self.algo_const += algo_const
+            + delta1 * delta2 * self.count as f64 * count as f64 / new_count as f64;
+        self.count = new_count;
+    }
+
+    /// Applies `f` to every pair of non-null values of `values`
+    pub(super) fn for_each_pair(
+        values: &[ArrayRef],
+        mut f: impl FnMut(f64, f64),
+    ) -> Result<()> {
+        let values1 = as_state_array::<Float64Array>(&values[0])?;
+        let values2 = as_state_array::<Float64Array>(&values[1])?;
+        (0..values1.len())
+            .filter(|&i| values1.is_valid(i) && values2.is_valid(i))
+            .for_each(|i| f(values1.value(i), values2.value(i)));
+        Ok(())
+    }
+
+    /// Returns the covariance, or `None` if there are not enough values
+    fn covariance(&self) -> Option<f64> {
+        match self.stats_type {
+            StatsType::Sample if self.count > 1 => {
+                Some(self.algo_const / (self.count - 1) as f64)
+            }
+            StatsType::Population if self.count > 0 => {
+                Some(self.algo_const / self.count as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Accumulator for CovarianceAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![
+            ScalarValue::from(self.count),
+            ScalarValue::from(self.mean1),
+            ScalarValue::from(self.mean2),
+            ScalarValue::from(self.algo_const),
+        ])
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        if let (Some(value1), Some(value2)) =
+            (float64_value(&values[0])?, float64_value(&values[1])?)
+        {
+            self.update_values(value1, value2);
+        }
+        Ok(())
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        Self::for_each_pair(values, |value1, value2| self.update_values(value1, value2))
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        let count = match &states[0] {
+            ScalarValue::UInt64(Some(count)) => *count,
+            _ => 0,
+        };
+        let mean1 = float64_value(&states[1])?.unwrap_or(0.0);
+        let mean2 = float64_value(&states[2])?.unwrap_or(0.0);
+        let algo_const = float64_value(&states[3])?.unwrap_or(0.0);
+        self.merge_state(count, mean1, mean2, algo_const);
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let counts = as_state_array::<UInt64Array>(&states[0])?;
+        let means1 = as_state_array::<Float64Array>(&states[1])?;
+        let means2 = as_state_array::<Float64Array>(&states[2])?;
+        let algo_consts = as_state_array::<Float64Array>(&states[3])?;
+        (0..counts.len())
+            .filter(|&i| counts.is_valid(i))
+            .for_each(|i| {
+                self.merge_state(
+                    counts.value(i),
+                    means1.value(i),
+                    means2.value(i),
+                    algo_consts.value(i),
+                )
+            });
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Float64(self.covariance()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::expressions::tests::aggregate;
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    fn batch() -> Result<RecordBatch> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Float64, true),
+            Field::new("b", DataType::Float64, true),
+        ]);
+        Ok(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from(vec![
+                    Some(1.0),
+                    Some(2.0),
+                    Some(3.0),
+                    None,
+                    Some(4.0),
+                ])),
+                Arc::new(Float64Array::from(vec![
+                    Some(2.0),
+                    Some(4.0),
+                    Some(7.0),
+                    Some(5.0),
+                    None,
+                ])),
+            ],
+        )?)
+    }
+
+    fn assert_close(expected: f64, actual: ScalarValue) {
+        match actual {
+            ScalarValue::Float64(Some(actual)) => {
+                assert!(
+                    (expected - actual).abs() < 1e-9,
+                    "{} != {}",
+                    expected,
+                    actual
+                )
+            }
+            other => panic!("expected {}, got {:?}", expected, other),
+        }
+    }
+
+    #[test]
+    fn covariance() -> Result<()> {
+        let batch = batch()?;
+
+        // pairs (1, 2), (2, 4), (3, 7): the co-moment is 5
+        let agg = Arc::new(Covariance::new(
+            col("a"),
+            col("b"),
+            "bla".to_string(),
+            DataType::Float64,
+            StatsType::Sample,
+        ));
+        assert_close(2.5, aggregate(&batch, agg)?);
+
+        let agg = Arc::new(Covariance::new(
+            col("a"),
+            col("b"),
+            "bla".to_string(),
+            DataType::Float64,
+            StatsType::Population,
+        ));
+        assert_close(5.0 / 3.0, aggregate(&batch, agg)?);
+        Ok(())
+    }
+
+    #[test]
+    fn covariance_merge() -> Result<()> {
+        let batch = batch()?;
+        let values = batch.columns();
+
+        let mut merged = CovarianceAccumulator::new(StatsType::Sample);
+        for i in 0..batch.num_rows() {
+            let mut partial = CovarianceAccumulator::new(StatsType::Sample);
+            partial.update_batch(&[values[0].slice(i, 1), values[1].slice(i, 1)])?;
+            merged.merge(&partial.state()?)?;
+        }
+        assert_eq!(3, merged.count());
+        assert_close(2.5, merged.evaluate()?);
+        Ok(())
+    }
+}
@@ -28,9 +28,9 @@ use arrow::compute;
 use arrow::datatypes::DataType;
 use arrow::{
     array::{
-        ArrayRef, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
-        Int8Array, LargeStringArray, StringArray, UInt16Array, UInt32Array, UInt64Array,
-        UInt8Array,
+        Array, ArrayRef, DecimalArray, Float32Array, Float64Array, Int16Array,
+        Int32Array, Int64Array, Int8Array, LargeStringArray, StringArray, UInt16Array,
+        UInt32Array, UInt64Array, UInt8Array,
     },
     datatypes::Field,
 };
@@ -108,6 +108,18 @@ macro_rules! typed_min_max_batch {
     }};
 }
 
+// Statically-typed version of min/max(array) -> ScalarValue for decimal types.
+macro_rules! typed_min_max_batch_decimal {
+    ($VALUES:expr, $PRECISION:expr, $SCALE:expr, $OP:ident) => {{
+        let array = $VALUES.as_any().downcast_ref::<DecimalArray>().unwrap();
+        let value = (0..array.len())
+            .filter(|i| array.is_valid(*i))
+            .map(|i| array.value(i))
+            .reduce(|a, b| a.$OP(b));
+        ScalarValue::Decimal128(value, *$PRECISION, *$SCALE)
+    }};
+}
+
 // Statically-typed version of min/max(array) -> ScalarValue  for non-string types.
 // this is a macro to support both operations (min and max).
 macro_rules! min_max_batch {
@@ -128,6 +140,9 @@ macro_rules! min_max_batch {
             DataType::UInt32 => typed_min_max_batch!($VALUES, UInt32Array, UInt32, $OP),
             DataType::UInt16 => typed_min_max_batch!($VALUES, UInt16Array, UInt16, $OP),
             DataType::UInt8 => typed_min_max_batch!($VALUES, UInt8Array, UInt8, $OP),
+            DataType::Decimal(precision, scale) => {
+                typed_min_max_batch_decimal!($VALUES, precision, scale, $OP)
+            }
             other => {
                 // This should have been handled before
                 return Err(DataFusionError::Internal(format!(
@@ -223,6 +238,18 @@ macro_rules! min_max {
             (ScalarValue::Int8(lhs), ScalarValue::Int8(rhs)) => {
                 typed_min_max!(lhs, rhs, Int8, $OP)
             }
+            (
+                ScalarValue::Decimal128(lhs, precision, scale),
+                ScalarValue::Decimal128(rhs, _, _),
+            ) => {
+                let value = match (lhs, rhs) {
+                    (None, None) => None,
+                    (Some(a), None) => Some(*a),
+                    (None, Some(b)) => Some(*b),
+                    (Some(a), Some(b)) => Some((*a).$OP(*b)),
+                };
+                ScalarValue::Decimal128(value, *precision, *scale)
+            }
             (ScalarValue::Utf8(lhs), ScalarValue::Utf8(rhs)) => {
                 typed_min_max_string!(lhs, rhs, Utf8, $OP)
             }
@@ -292,6 +319,10 @@ impl Accumulator for MaxAccumulator {
     fn evaluate(&self) -> Result<ScalarValue> {
         Ok(self.max.clone())
     }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.max) + self.max.size()
+    }
 }
 
 /// MIN aggregate expression
@@ -389,6 +420,10 @@ impl Accumulator for MinAccumulator {
     fn evaluate(&self) -> Result<ScalarValue> {
         Ok(self.min.clone())
     }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.min) + self.min.size()
+    }
 }
 
 #[cfg(test)]
@@ -424,6 +459,38 @@ mod tests {
         )
     }
 
+    #[test]
+    fn max_decimal() -> Result<()> {
+        let a: ArrayRef = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(-500), None, Some(125), Some(-1)],
+            5,
+            2,
+        ));
+        generic_test_op!(
+            a,
+            DataType::Decimal(5, 2),
+            Max,
+            ScalarValue::Decimal128(Some(125), 5, 2),
+            DataType::Decimal(5, 2)
+        )
+    }
+
+    #[test]
+    fn min_decimal() -> Result<()> {
+        let a: ArrayRef = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(-500), None, Some(125), Some(-1)],
+            5,
+            2,
+        ));
+        generic_test_op!(
+            a,
+            DataType::Decimal(5, 2),
+            Min,
+            ScalarValue::Decimal128(Some(-500), 5, 2),
+            DataType::Decimal(5, 2)
+        )
+    }
+
     #[test]
     fn max_utf8() -> Result<()> {
         let a: ArrayRef = Arc::new(StringArray::from(vec!["d", "a", "c", "b"]));
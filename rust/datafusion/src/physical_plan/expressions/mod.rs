@@ -25,14 +25,19 @@ use crate::physical_plan::PhysicalExpr;
 use arrow::compute::kernels::sort::{SortColumn, SortOptions};
 use arrow::record_batch::RecordBatch;
 
+mod approx_distinct;
+mod approx_percentile_cont;
+mod array_agg;
 mod average;
 #[macro_use]
 mod binary;
 mod case;
 mod cast;
-mod coercion;
+pub(crate) mod coercion;
 mod column;
+mod correlation;
 mod count;
+mod covariance;
 mod in_list;
 mod is_not_null;
 mod is_null;
@@ -41,15 +46,24 @@ mod min_max;
 mod negative;
 mod not;
 mod nullif;
+mod string_agg;
 mod sum;
 mod try_cast;
+mod variance;
 
+pub use approx_distinct::{ApproxDistinct, ApproxDistinctAccumulator};
+pub use approx_percentile_cont::{ApproxPercentileAccumulator, ApproxPercentileCont};
+pub use array_agg::{
+    ArrayAgg, ArrayAggAccumulator, DistinctArrayAgg, DistinctArrayAggAccumulator,
+};
 pub use average::{avg_return_type, Avg, AvgAccumulator};
 pub use binary::{binary, binary_operator_data_type, BinaryExpr};
 pub use case::{case, CaseExpr};
 pub use cast::{cast, cast_with_options, CastExpr};
 pub use column::{col, Column};
+pub use correlation::{Correlation, CorrelationAccumulator};
 pub use count::Count;
+pub use covariance::{Covariance, CovarianceAccumulator};
 pub use in_list::{in_list, InListExpr};
 pub use is_not_null::{is_not_null, IsNotNullExpr};
 pub use is_null::{is_null, IsNullExpr};
@@ -58,8 +72,12 @@ pub use min_max::{Max, Min};
 pub use negative::{negative, NegativeExpr};
 pub use not::{not, NotExpr};
 pub use nullif::{nullif_func, SUPPORTED_NULLIF_TYPES};
+pub use string_agg::{
+    DistinctStringAgg, DistinctStringAggAccumulator, StringAgg, StringAggAccumulator,
+};
 pub use sum::{sum_return_type, Sum};
 pub use try_cast::{try_cast, TryCastExpr};
+pub use variance::{StatsType, Stddev, StddevAccumulator, Variance, VarianceAccumulator};
 /// returns the name of the state
 pub fn format_state_name(name: &str, state_name: &str) -> String {
     format!("{}[{}]", name, state_name)
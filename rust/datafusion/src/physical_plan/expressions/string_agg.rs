@@ -0,0 +1,407 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the STRING_AGG aggregate expressions
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::array_agg::DistinctArrayAggAccumulator;
+use super::{format_state_name, lit};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::{Accumulator, AggregateExpr, ColumnarValue, PhysicalExpr};
+use crate::scalar::ScalarValue;
+
+/// Evaluates the separator of STRING_AGG, which must be a constant string
+fn separator_value(separator: &Arc<dyn PhysicalExpr>) -> Result<String> {
+    // a constant expression does not need any column to be evaluated
+    let batch = RecordBatch::new_empty(Arc::new(Schema::empty()));
+    match separator.evaluate(&batch) {
+        Ok(ColumnarValue::Scalar(ScalarValue::Utf8(Some(separator)))) => Ok(separator),
+        _ => Err(DataFusionError::Plan(format!(
+            "The separator of STRING_AGG must be a literal string, got {}",
+            separator
+        ))),
+    }
+}
+
+/// STRING_AGG aggregate expression, which concatenates the non-null values of
+/// its input, separated by a constant separator, in no particular order
+#[derive(Debug)]
+pub struct StringAgg {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+    separator: String,
+}
+
+impl StringAgg {
+    /// Create a new STRING_AGG aggregate function of a Utf8 `expr`. `separator`
+    /// must be a constant string expression.
+    pub fn try_new(
+        expr: Arc<dyn PhysicalExpr>,
+        separator: Arc<dyn PhysicalExpr>,
+        name: String,
+        data_type: DataType,
+    ) -> Result<Self> {
+        Ok(Self {
+            name,
+            data_type,
+            expr,
+            separator: separator_value(&separator)?,
+        })
+    }
+
+    /// The separator of the values
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+}
+
+impl AggregateExpr for StringAgg {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "string_agg"),
+            DataType::Utf8,
+            true,
+        )])
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(StringAggAccumulator::new(self.separator.clone())))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        // the separator is part of the expressions so that it is visible in plans
+        vec![
+            self.expr.clone(),
+            lit(ScalarValue::Utf8(Some(self.separator.clone()))),
+        ]
+    }
+}
+
+/// An accumulator to concatenate strings
+#[derive(Debug)]
+pub struct StringAggAccumulator {
+    value: Option<String>,
+    separator: String,
+}
+
+impl StringAggAccumulator {
+    /// Creates a new, empty `StringAggAccumulator` of the given separator
+    pub fn new(separator: String) -> Self {
+        Self {
+            value: None,
+            separator,
+        }
+    }
+
+    fn append(&mut self, value: &str) {
+        match &mut self.value {
+            Some(current) => {
+                current.push_str(&self.separator);
+                current.push_str(value);
+            }
+            None => self.value = Some(value.to_string()),
+        }
+    }
+}
+
+impl Accumulator for StringAggAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Utf8(self.value.clone())])
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        match &values[0] {
+            ScalarValue::Utf8(Some(value)) => self.append(value),
+            ScalarValue::Utf8(None) => {}
+            other => {
+                return Err(DataFusionError::Internal(format!(
+                    "STRING_AGG expects Utf8 values, got {:?}",
+                    other
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = values[0]
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "STRING_AGG expects Utf8 values, got {:?}",
+                    values[0].data_type()
+                ))
+            })?;
+        values.iter().flatten().for_each(|v| self.append(v));
+        Ok(())
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        // the state is the concatenation of a part of the values
+        self.update(states)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Utf8(self.value.clone()))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.value.as_ref().map(|v| v.capacity()).unwrap_or(0)
+            + self.separator.capacity()
+    }
+}
+
+/// Expression for a STRING_AGG(DISTINCT) aggregation, which concatenates the
+/// distinct non-null values of its input in ascending order
+#[derive(Debug)]
+pub struct DistinctStringAgg {
+    name: String,
+    data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+    separator: String,
+}
+
+impl DistinctStringAgg {
+    /// Create a new STRING_AGG(DISTINCT) aggregate function of a Utf8 `expr`.
+    /// `separator` must be a constant string expression.
+    pub fn try_new(
+        expr: Arc<dyn PhysicalExpr>,
+        separator: Arc<dyn PhysicalExpr>,
+        name: String,
+        data_type: DataType,
+    ) -> Result<Self> {
+        Ok(Self {
+            name,
+            data_type,
+            expr,
+            separator: separator_value(&separator)?,
+        })
+    }
+
+    /// The separator of the values
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+}
+
+impl AggregateExpr for DistinctStringAgg {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "distinct_string_agg"),
+            DataType::List(Box::new(Field::new("item", DataType::Utf8, true))),
+            true,
+        )])
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(DistinctStringAggAccumulator::new(
+            self.separator.clone(),
+        )))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![
+            self.expr.clone(),
+            lit(ScalarValue::Utf8(Some(self.separator.clone()))),
+        ]
+    }
+}
+
+/// An accumulator to concatenate distinct strings. Its state is the list of the
+/// distinct values.
+#[derive(Debug)]
+pub struct DistinctStringAggAccumulator {
+    values: DistinctArrayAggAccumulator,
+    separator: String,
+}
+
+impl DistinctStringAggAccumulator {
+    /// Creates a new, empty `DistinctStringAggAccumulator` of the given separator
+    pub fn new(separator: String) -> Self {
+        Self {
+            values: DistinctArrayAggAccumulator::new(DataType::Utf8),
+            separator,
+        }
+    }
+}
+
+impl Accumulator for DistinctStringAggAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        self.values.state()
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        if values[0].is_null() {
+            return Ok(());
+        }
+        self.values.update(values)
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        self.values.merge(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        let values = self
+            .values
+            .sorted_values()?
+            .into_iter()
+            .map(|value| match value {
+                ScalarValue::Utf8(Some(value)) => Ok(value),
+                other => Err(DataFusionError::Internal(format!(
+                    "STRING_AGG expects Utf8 values, got {:?}",
+                    other
+                ))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ScalarValue::Utf8(if values.is_empty() {
+            None
+        } else {
+            Some(values.join(&self.separator))
+        }))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.values)
+            + self.values.size()
+            + self.separator.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::expressions::tests::aggregate;
+
+    fn batch(values: Vec<Option<&str>>) -> Result<RecordBatch> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        Ok(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(StringArray::from(values))],
+        )?)
+    }
+
+    fn separator() -> Arc<dyn PhysicalExpr> {
+        lit(ScalarValue::Utf8(Some(", ".to_string())))
+    }
+
+    #[test]
+    fn string_agg() -> Result<()> {
+        let batch = batch(vec![Some("b"), None, Some("a"), Some("b")])?;
+        let agg = Arc::new(StringAgg::try_new(
+            col("a"),
+            separator(),
+            "bla".to_string(),
+            DataType::Utf8,
+        )?);
+        assert_eq!(
+            ScalarValue::Utf8(Some("b, a, b".to_string())),
+            aggregate(&batch, agg)?
+        );
+
+        let batch = self::batch(vec![None])?;
+        let agg = Arc::new(StringAgg::try_new(
+            col("a"),
+            separator(),
+            "bla".to_string(),
+            DataType::Utf8,
+        )?);
+        assert_eq!(ScalarValue::Utf8(None), aggregate(&batch, agg)?);
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_string_agg() -> Result<()> {
+        let batch = batch(vec![Some("b"), None, Some("a"), Some("b")])?;
+        let agg = Arc::new(DistinctStringAgg::try_new(
+            col("a"),
+            separator(),
+            "bla".to_string(),
+            DataType::Utf8,
+        )?);
+        assert_eq!(
+            ScalarValue::Utf8(Some("a, b".to_string())),
+            aggregate(&batch, agg)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_separator() {
+        for separator in &[lit(ScalarValue::Utf8(None)), col("a")] {
+            let agg = StringAgg::try_new(
+                col("a"),
+                separator.clone(),
+                "bla".to_string(),
+                DataType::Utf8,
+            );
+            assert!(agg.is_err());
+        }
+    }
+
+    #[test]
+    fn string_agg_merge() -> Result<()> {
+        let mut merged = StringAggAccumulator::new("-".to_string());
+        for values in &[vec!["a", "b"], vec![], vec!["c"]] {
+            let mut partial = StringAggAccumulator::new("-".to_string());
+            partial.update_batch(&[Arc::new(StringArray::from(values.clone()))])?;
+            let state = partial
+                .state()?
+                .iter()
+                .map(|v| v.to_array())
+                .collect::<Vec<_>>();
+            merged.merge_batch(&state)?;
+        }
+        assert_eq!(
+            ScalarValue::Utf8(Some("a-b-c".to_string())),
+            merged.evaluate()?
+        );
+        Ok(())
+    }
+}
@@ -25,11 +25,12 @@ use crate::error::{DataFusionError, Result};
 use crate::physical_plan::{Accumulator, AggregateExpr, PhysicalExpr};
 use crate::scalar::ScalarValue;
 use arrow::compute;
-use arrow::datatypes::DataType;
+use arrow::datatypes::{DataType, DECIMAL_MAX_PRECISION};
 use arrow::{
     array::{
-        ArrayRef, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
-        Int8Array, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+        Array, ArrayRef, DecimalArray, Float32Array, Float64Array, Int16Array,
+        Int32Array, Int64Array, Int8Array, UInt16Array, UInt32Array, UInt64Array,
+        UInt8Array,
     },
     datatypes::Field,
 };
@@ -56,6 +57,11 @@ pub fn sum_return_type(arg_type: &DataType) -> Result<DataType> {
         }
         DataType::Float32 => Ok(DataType::Float32),
         DataType::Float64 => Ok(DataType::Float64),
+        // sums of decimals get 10 more integer digits, like in Spark
+        DataType::Decimal(precision, scale) => Ok(DataType::Decimal(
+            (precision + 10).min(DECIMAL_MAX_PRECISION),
+            *scale,
+        )),
         other => Err(DataFusionError::Plan(format!(
             "SUM does not support type \"{:?}\"",
             other
@@ -129,9 +135,68 @@ macro_rules! typed_sum_delta_batch {
     }};
 }
 
+// sums a decimal array, erroring when the sum overflows the return type of a sum
+fn sum_decimal_batch(values: &ArrayRef) -> Result<ScalarValue> {
+    let (precision, scale) = match sum_return_type(values.data_type())? {
+        DataType::Decimal(precision, scale) => (precision, scale),
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "Sum of a decimal array is not expected to be of type {:?}",
+                other
+            )))
+        }
+    };
+    let array = values.as_any().downcast_ref::<DecimalArray>().unwrap();
+    let mut sum = None;
+    for i in 0..array.len() {
+        if array.is_valid(i) {
+            sum = sum_decimal(&sum, &Some(array.value(i)), 0, precision, scale)?;
+        }
+    }
+    Ok(ScalarValue::Decimal128(sum, precision, scale))
+}
+
+// returns the sum of two unscaled decimal values, where `rhs` is `rescale` digits less
+// precise than `lhs`, erroring when the result does not fit `precision` digits.
+fn sum_decimal(
+    lhs: &Option<i128>,
+    rhs: &Option<i128>,
+    rescale: usize,
+    precision: usize,
+    scale: usize,
+) -> Result<Option<i128>> {
+    let overflow = || {
+        DataFusionError::Execution(format!(
+            "Overflow happened on a sum of type Decimal({}, {})",
+            precision, scale
+        ))
+    };
+    let rhs = match rhs {
+        Some(v) => Some(
+            10_i128
+                .checked_pow(rescale as u32)
+                .and_then(|factor| v.checked_mul(factor))
+                .ok_or_else(overflow)?,
+        ),
+        None => None,
+    };
+    let sum = match (lhs, rhs) {
+        (None, None) => return Ok(None),
+        (Some(a), None) => *a,
+        (None, Some(b)) => b,
+        (Some(a), Some(b)) => a.checked_add(b).ok_or_else(overflow)?,
+    };
+    let max = 10_i128.pow(precision as u32);
+    if sum <= -max || sum >= max {
+        return Err(overflow());
+    }
+    Ok(Some(sum))
+}
+
 // sums the array and returns a ScalarValue of its corresponding type.
 pub(super) fn sum_batch(values: &ArrayRef) -> Result<ScalarValue> {
     Ok(match values.data_type() {
+        DataType::Decimal(_, _) => sum_decimal_batch(values)?,
         DataType::Float64 => typed_sum_delta_batch!(values, Float64Array, Float64),
         DataType::Float32 => typed_sum_delta_batch!(values, Float32Array, Float32),
         DataType::Int64 => typed_sum_delta_batch!(values, Int64Array, Int64),
@@ -226,6 +291,15 @@ pub(super) fn sum(lhs: &ScalarValue, rhs: &ScalarValue) -> Result<ScalarValue> {
         (ScalarValue::Int64(lhs), ScalarValue::Int8(rhs)) => {
             typed_sum!(lhs, rhs, Int64, i64)
         }
+        // decimals keep the precision and scale of lhs, rescaling rhs to it
+        (
+            ScalarValue::Decimal128(lhs, precision, scale),
+            ScalarValue::Decimal128(rhs, _, rhs_scale),
+        ) if scale >= rhs_scale => ScalarValue::Decimal128(
+            sum_decimal(lhs, rhs, scale - rhs_scale, *precision, *scale)?,
+            *precision,
+            *scale,
+        ),
         e => {
             return Err(DataFusionError::Internal(format!(
                 "Sum is not expected to receive a scalar {:?}",
@@ -356,6 +430,62 @@ mod tests {
         )
     }
 
+    #[test]
+    fn sum_decimal() -> Result<()> {
+        let a: ArrayRef = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(125), None, Some(-300), Some(1000)],
+            5,
+            2,
+        ));
+        generic_test_op!(
+            a,
+            DataType::Decimal(5, 2),
+            Sum,
+            ScalarValue::Decimal128(Some(825), 15, 2),
+            DataType::Decimal(15, 2)
+        )
+    }
+
+    #[test]
+    fn sum_decimal_with_integer_digits() -> Result<()> {
+        // the sum has 10 more integer digits than its input
+        let a: ArrayRef = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(99_999), Some(1)],
+            5,
+            2,
+        ));
+        generic_test_op!(
+            a,
+            DataType::Decimal(5, 2),
+            Sum,
+            ScalarValue::Decimal128(Some(100_000), 15, 2),
+            DataType::Decimal(15, 2)
+        )
+    }
+
+    #[test]
+    fn sum_decimal_overflow() -> Result<()> {
+        let max = 10_i128.pow(DECIMAL_MAX_PRECISION as u32) - 1;
+        let a: ArrayRef = Arc::new(DecimalArray::from_sparse_iter(
+            vec![Some(max), Some(1)],
+            DECIMAL_MAX_PRECISION,
+            0,
+        ));
+        let data_type = DataType::Decimal(DECIMAL_MAX_PRECISION, 0);
+        let schema = Schema::new(vec![Field::new("a", data_type.clone(), false)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![a])?;
+        let agg = Arc::new(Sum::new(col("a"), "bla".to_string(), data_type));
+        let err = aggregate(&batch, agg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Execution error: Overflow happened on a sum of type Decimal({}, 0)",
+                DECIMAL_MAX_PRECISION
+            )
+        );
+        Ok(())
+    }
+
     fn aggregate(
         batch: &RecordBatch,
         agg: Arc<dyn AggregateExpr>,
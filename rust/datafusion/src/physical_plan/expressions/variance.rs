@@ -0,0 +1,449 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the variance and standard deviation aggregate expressions
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::{Accumulator, AggregateExpr, PhysicalExpr};
+use crate::scalar::ScalarValue;
+use arrow::array::{Array, ArrayRef, Float64Array, UInt64Array};
+use arrow::datatypes::{DataType, Field};
+
+use super::format_state_name;
+
+/// Whether a statistic is computed for a sample or for the whole population
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsType {
+    /// Sample statistic, divided by `n - 1`
+    Sample,
+    /// Population statistic, divided by `n`
+    Population,
+}
+
+/// VAR_SAMP and VAR_POP aggregate expression
+#[derive(Debug)]
+pub struct Variance {
+    name: String,
+    expr: Arc<dyn PhysicalExpr>,
+    data_type: DataType,
+    stats_type: StatsType,
+}
+
+impl Variance {
+    /// Create a new variance aggregate function. The input must be of type
+    /// Float64.
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: String,
+        data_type: DataType,
+        stats_type: StatsType,
+    ) -> Self {
+        Self {
+            name,
+            expr,
+            data_type,
+            stats_type,
+        }
+    }
+
+    /// Whether this is the sample or the population statistic
+    pub fn stats_type(&self) -> StatsType {
+        self.stats_type
+    }
+}
+
+impl AggregateExpr for Variance {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(variance_state_fields(&self.name))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(VarianceAccumulator::new(self.stats_type)))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+}
+
+/// STDDEV and STDDEV_POP aggregate expression
+#[derive(Debug)]
+pub struct Stddev {
+    name: String,
+    expr: Arc<dyn PhysicalExpr>,
+    data_type: DataType,
+    stats_type: StatsType,
+}
+
+impl Stddev {
+    /// Create a new standard deviation aggregate function. The input must be of
+    /// type Float64.
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: String,
+        data_type: DataType,
+        stats_type: StatsType,
+    ) -> Self {
+        Self {
+            name,
+            expr,
+            data_type,
+            stats_type,
+        }
+    }
+
+    /// Whether this is the sample or the population statistic
+    pub fn stats_type(&self) -> StatsType {
+        self.stats_type
+    }
+}
+
+impl AggregateExpr for Stddev {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(variance_state_fields(&self.name))
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(StddevAccumulator {
+            variance: VarianceAccumulator::new(self.stats_type),
+        }))
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+}
+
+fn variance_state_fields(name: &str) -> Vec<Field> {
+    vec![
+        Field::new(&format_state_name(name, "count"), DataType::UInt64, true),
+        Field::new(&format_state_name(name, "mean"), DataType::Float64, true),
+        Field::new(&format_state_name(name, "m2"), DataType::Float64, true),
+    ]
+}
+
+/// Returns the `Float64` value of a scalar, or `None` if it is null
+pub(super) fn float64_value(value: &ScalarValue) -> Result<Option<f64>> {
+    match value {
+        ScalarValue::Float64(v) => Ok(*v),
+        other => Err(DataFusionError::Internal(format!(
+            "Statistical aggregates expect Float64 values, got {:?}",
+            other
+        ))),
+    }
+}
+
+/// Downcasts an array of the state of a statistical aggregate
+pub(super) fn as_state_array<T: 'static>(array: &ArrayRef) -> Result<&T> {
+    array.as_any().downcast_ref::<T>().ok_or_else(|| {
+        DataFusionError::Internal(format!(
+            "Unexpected state of a statistical aggregate: {:?}",
+            array.data_type()
+        ))
+    })
+}
+
+/// An accumulator to compute the variance of its input.
+///
+/// The running mean and the sum of squared differences to it (`m2`) are updated
+/// with Welford's online algorithm, and partial states are merged with the
+/// parallel algorithm of Chan et al., which are both numerically stable.
+#[derive(Debug)]
+pub struct VarianceAccumulator {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    stats_type: StatsType,
+}
+
+impl VarianceAccumulator {
+    /// Creates a new, empty `VarianceAccumulator`
+    pub fn new(stats_type: StatsType) -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            stats_type,
+        }
+    }
+
+    /// Number of values seen
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Sum of the squared differences between the values and their mean
+    pub fn m2(&self) -> f64 {
+        self.m2
+    }
+
+    /// Adds a value to the accumulator
+    pub(super) fn update_value(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Merges the state of another accumulator into this one
+    pub(super) fn merge_state(&mut self, count: u64, mean: f64, m2: f64) {
+        if count == 0 {
+            return;
+        }
+        let new_count = self.count + count;
+        let delta = mean - self.mean;
+        self.mean += delta * count as f64 / new_count as f64;
+        self.m2 +=
+            m2 + delta * delta * self.count as f64 * count as f64 / new_count as f64;
+        self.count = new_count;
+    }
+
+    /// Returns the variance, or `None` if there are not enough values
+    fn variance(&self) -> Option<f64> {
+        match self.stats_type {
+            StatsType::Sample if self.count > 1 => {
+                Some(self.m2 / (self.count - 1) as f64)
+            }
+            StatsType::Population if self.count > 0 => Some(self.m2 / self.count as f64),
+            _ => None,
+        }
+    }
+}
+
+impl Accumulator for VarianceAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![
+            ScalarValue::from(self.count),
+            ScalarValue::from(self.mean),
+            ScalarValue::from(self.m2),
+        ])
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        if let Some(value) = float64_value(&values[0])? {
+            self.update_value(value);
+        }
+        Ok(())
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = as_state_array::<Float64Array>(&values[0])?;
+        (0..values.len())
+            .filter(|&i| values.is_valid(i))
+            .for_each(|i| self.update_value(values.value(i)));
+        Ok(())
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        let count = match &states[0] {
+            ScalarValue::UInt64(Some(count)) => *count,
+            _ => 0,
+        };
+        let mean = float64_value(&states[1])?.unwrap_or(0.0);
+        let m2 = float64_value(&states[2])?.unwrap_or(0.0);
+        self.merge_state(count, mean, m2);
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let counts = as_state_array::<UInt64Array>(&states[0])?;
+        let means = as_state_array::<Float64Array>(&states[1])?;
+        let m2s = as_state_array::<Float64Array>(&states[2])?;
+        (0..counts.len())
+            .filter(|&i| counts.is_valid(i))
+            .for_each(|i| {
+                self.merge_state(counts.value(i), means.value(i), m2s.value(i))
+            });
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Float64(self.variance()))
+    }
+}
+
+/// An accumulator to compute the standard deviation of its input
+#[derive(Debug)]
+pub struct StddevAccumulator {
+    variance: VarianceAccumulator,
+}
+
+impl Accumulator for StddevAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        self.variance.state()
+    }
+
+    fn update(&mut self, values: &[ScalarValue]) -> Result<()> {
+        self.variance.update(values)
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        self.variance.update_batch(values)
+    }
+
+    fn merge(&mut self, states: &[ScalarValue]) -> Result<()> {
+        self.variance.merge(states)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.variance.merge_batch(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Float64(
+            self.variance.variance().map(|variance| variance.sqrt()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::expressions::tests::aggregate;
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    fn batch(values: Vec<Option<f64>>) -> Result<RecordBatch> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Float64, true)]);
+        Ok(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Float64Array::from(values))],
+        )?)
+    }
+
+    fn assert_close(expected: f64, actual: ScalarValue) {
+        match actual {
+            ScalarValue::Float64(Some(actual)) => {
+                assert!(
+                    (expected - actual).abs() < 1e-9,
+                    "{} != {}",
+                    expected,
+                    actual
+                )
+            }
+            other => panic!("expected {}, got {:?}", expected, other),
+        }
+    }
+
+    #[test]
+    fn variance() -> Result<()> {
+        let batch = batch(vec![Some(1.0), Some(2.0), None, Some(3.0), Some(4.0)])?;
+
+        let agg = Arc::new(Variance::new(
+            col("a"),
+            "bla".to_string(),
+            DataType::Float64,
+            StatsType::Sample,
+        ));
+        assert_close(5.0 / 3.0, aggregate(&batch, agg)?);
+
+        let agg = Arc::new(Variance::new(
+            col("a"),
+            "bla".to_string(),
+            DataType::Float64,
+            StatsType::Population,
+        ));
+        assert_close(1.25, aggregate(&batch, agg)?);
+
+        let agg = Arc::new(Stddev::new(
+            col("a"),
+            "bla".to_string(),
+            DataType::Float64,
+            StatsType::Population,
+        ));
+        assert_close(1.25_f64.sqrt(), aggregate(&batch, agg)?);
+        Ok(())
+    }
+
+    #[test]
+    fn variance_of_too_few_values() -> Result<()> {
+        let batch = batch(vec![Some(1.0), None])?;
+
+        let agg = Arc::new(Stddev::new(
+            col("a"),
+            "bla".to_string(),
+            DataType::Float64,
+            StatsType::Sample,
+        ));
+        assert_eq!(ScalarValue::Float64(None), aggregate(&batch, agg)?);
+
+        let agg = Arc::new(Variance::new(
+            col("a"),
+            "bla".to_string(),
+            DataType::Float64,
+            StatsType::Population,
+        ));
+        assert_close(0.0, aggregate(&batch, agg)?);
+        Ok(())
+    }
+
+    #[test]
+    fn variance_merge() -> Result<()> {
+        // large offsets would lose all precision with the naive sum of squares
+        let values = (0..100).map(|i| 1e9 + (i % 7) as f64).collect::<Vec<_>>();
+
+        let mut whole = VarianceAccumulator::new(StatsType::Sample);
+        whole.update_batch(&[Arc::new(Float64Array::from(values.clone()))])?;
+
+        let mut merged = VarianceAccumulator::new(StatsType::Sample);
+        for chunk in values.chunks(30) {
+            let mut partial = VarianceAccumulator::new(StatsType::Sample);
+            partial.update_batch(&[Arc::new(Float64Array::from(chunk.to_vec()))])?;
+            let state = partial
+                .state()?
+                .iter()
+                .map(|v| v.to_array())
+                .collect::<Vec<_>>();
+            merged.merge_batch(&state)?;
+        }
+        // an empty partial state does not change the result
+        merged.merge(&VarianceAccumulator::new(StatsType::Sample).state()?)?;
+
+        // the values are 0 to 6 with an offset, 0 and 1 appearing 15 times
+        let expected = 404.75 / 99.0;
+        for variance in &[whole.evaluate()?, merged.evaluate()?] {
+            match variance {
+                ScalarValue::Float64(Some(v)) => assert!((v - expected).abs() < 1e-6),
+                other => panic!("unexpected variance {:?}", other),
+            }
+        }
+        assert_eq!(100, merged.count());
+        Ok(())
+    }
+}
@@ -0,0 +1,448 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the file sink operator, that writes each partition of its input
+//! to a file of a directory
+
+use std::any::Any;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::datasource::datasource::Statistics;
+use crate::datasource::listing::FileFormat;
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::LogicalPlan;
+use crate::physical_plan::{
+    common::SizedRecordBatchStream, Distribution, ExecutionPlan, Partitioning,
+};
+use arrow::record_batch::RecordBatch;
+use arrow::{array::UInt64Array, csv, datatypes::SchemaRef, json};
+use flate2::write::GzEncoder;
+use futures::StreamExt;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use tokio::sync::mpsc::{channel, Receiver};
+use tokio::task::{self, JoinHandle};
+
+use super::SendableRecordBatchStream;
+use async_trait::async_trait;
+
+/// Compression of the CSV and newline-delimited JSON files written by a
+/// [`FileSinkExec`]. Parquet files are compressed as set by their
+/// `WriterProperties`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileCompression {
+    /// Files are not compressed
+    Uncompressed,
+    /// Files are gzip compressed, and have a `.gz` extension
+    Gzip,
+}
+
+impl FileCompression {
+    /// The extension appended to the names of files with this compression
+    fn file_extension(&self) -> &'static str {
+        match self {
+            FileCompression::Uncompressed => "",
+            FileCompression::Gzip => ".gz",
+        }
+    }
+}
+
+/// Options of the files written by a [`FileSinkExec`]
+#[derive(Debug, Clone)]
+pub struct FileWriteOptions {
+    /// The format of the files
+    pub format: FileFormat,
+    /// The compression of CSV and newline-delimited JSON files
+    pub compression: FileCompression,
+    /// The properties of Parquet files, such as their compression
+    pub writer_properties: Option<WriterProperties>,
+}
+
+impl FileWriteOptions {
+    /// Create options to write uncompressed files of `format`, and Parquet
+    /// files with the default writer properties
+    pub fn new(format: FileFormat) -> Self {
+        Self {
+            format,
+            compression: FileCompression::Uncompressed,
+            writer_properties: None,
+        }
+    }
+
+    /// Specify the compression of CSV and newline-delimited JSON files
+    pub fn compression(mut self, compression: FileCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Specify the properties of Parquet files
+    pub fn writer_properties(mut self, writer_properties: WriterProperties) -> Self {
+        self.writer_properties = Some(writer_properties);
+        self
+    }
+
+    /// The extension of the files written with these options
+    pub fn file_extension(&self) -> String {
+        match self.format {
+            FileFormat::Parquet => self.format.default_extension().to_string(),
+            _ => format!(
+                "{}{}",
+                self.format.default_extension(),
+                self.compression.file_extension()
+            ),
+        }
+    }
+}
+
+/// Execution plan that writes each partition of its input to a file
+/// `part-{partition}.{extension}` of a new directory, and then produces a
+/// single row with the number of rows written.
+#[derive(Debug, Clone)]
+pub struct FileSinkExec {
+    /// The plan producing the rows to write
+    input: Arc<dyn ExecutionPlan>,
+    /// Path of the directory to create and write the files to
+    path: String,
+    /// The format and compression of the files
+    options: FileWriteOptions,
+}
+
+impl FileSinkExec {
+    /// Create a new FileSinkExec
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        path: impl Into<String>,
+        options: FileWriteOptions,
+    ) -> Self {
+        Self {
+            input,
+            path: path.into(),
+            options,
+        }
+    }
+
+    /// Path of the directory the files are written to
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The format and compression of the files
+    pub fn options(&self) -> &FileWriteOptions {
+        &self.options
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for FileSinkExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        LogicalPlan::count_schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    /// Each partition of the input is written to its own file
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::UnspecifiedDistribution
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(FileSinkExec::new(
+                children[0].clone(),
+                self.path.clone(),
+                self.options.clone(),
+            ))),
+            _ => Err(DataFusionError::Internal(
+                "FileSinkExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "FileSinkExec invalid partition {}",
+                partition
+            )));
+        }
+
+        // the files are created and written on the blocking threads of the runtime
+        let path = self.path.clone();
+        task::spawn_blocking(move || fs::create_dir(&path))
+            .await
+            .map_err(|e| DataFusionError::Execution(e.to_string()))?
+            .map_err(|e| {
+                DataFusionError::Execution(format!(
+                    "Could not create directory {}: {:?}",
+                    self.path, e
+                ))
+            })?;
+
+        let dir = Path::new(&self.path);
+        let extension = self.options.file_extension();
+        let mut tasks = vec![];
+        for i in 0..self.input.output_partitioning().partition_count() {
+            let path = dir.join(format!("part-{}{}", i, extension));
+            let stream = self.input.execute(i).await?;
+            let options = self.options.clone();
+            let handle: JoinHandle<Result<usize>> =
+                task::spawn(async move { write_file(stream, path, options).await });
+            tasks.push(handle);
+        }
+
+        let mut count = 0;
+        for result in futures::future::join_all(tasks).await {
+            count += result.map_err(|e| DataFusionError::Execution(e.to_string()))??;
+        }
+
+        let record_batch = RecordBatch::try_new(
+            self.schema(),
+            vec![Arc::new(UInt64Array::from(vec![count as u64]))],
+        )?;
+        Ok(Box::pin(SizedRecordBatchStream::new(
+            self.schema(),
+            vec![Arc::new(record_batch)],
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        // a single row with the number of rows written
+        Statistics {
+            num_rows: Some(1),
+            total_byte_size: None,
+            column_statistics: None,
+        }
+    }
+}
+
+/// Writes all the batches of a stream to a new file, returning the number of
+/// rows written. The batches are sent to a blocking thread that writes them.
+async fn write_file(
+    mut stream: SendableRecordBatchStream,
+    path: PathBuf,
+    options: FileWriteOptions,
+) -> Result<usize> {
+    let (sender, receiver) = channel(2);
+    let schema = stream.schema();
+    let writer =
+        task::spawn_blocking(move || write_batches(receiver, &path, schema, &options));
+    while let Some(batch) = stream.next().await {
+        // the writer stops receiving on errors, that it returns
+        if sender.send(batch?).await.is_err() {
+            break;
+        }
+    }
+    drop(sender);
+    writer
+        .await
+        .map_err(|e| DataFusionError::Execution(e.to_string()))?
+}
+
+/// Writes the batches received to a new file, returning the number of rows
+/// written
+fn write_batches(
+    mut receiver: Receiver<RecordBatch>,
+    path: &Path,
+    schema: SchemaRef,
+    options: &FileWriteOptions,
+) -> Result<usize> {
+    let file = File::create(path)?;
+    let mut count = 0;
+    match options.format {
+        FileFormat::Parquet => {
+            let mut writer =
+                ArrowWriter::try_new(file, schema, options.writer_properties.clone())?;
+            while let Some(batch) = receiver.blocking_recv() {
+                count += batch.num_rows();
+                writer.write(&batch)?;
+            }
+            writer.close()?;
+        }
+        FileFormat::Csv {
+            has_header,
+            delimiter,
+        } => {
+            let mut file = CompressedFile::new(file, options.compression);
+            {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(has_header)
+                    .with_delimiter(delimiter)
+                    .build(&mut file);
+                while let Some(batch) = receiver.blocking_recv() {
+                    count += batch.num_rows();
+                    writer.write(&batch)?;
+                }
+            }
+            file.finish()?;
+        }
+        FileFormat::NdJson => {
+            let mut file = CompressedFile::new(file, options.compression);
+            {
+                let mut writer = json::LineDelimitedWriter::new(&mut file);
+                while let Some(batch) = receiver.blocking_recv() {
+                    count += batch.num_rows();
+                    writer.write_batches(&[batch])?;
+                }
+                writer.finish()?;
+            }
+            file.finish()?;
+        }
+    }
+    Ok(count)
+}
+
+/// A file that is written either as is or gzip compressed
+enum CompressedFile {
+    Uncompressed(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl CompressedFile {
+    fn new(file: File, compression: FileCompression) -> Self {
+        let file = BufWriter::new(file);
+        match compression {
+            FileCompression::Uncompressed => CompressedFile::Uncompressed(file),
+            FileCompression::Gzip => {
+                CompressedFile::Gzip(GzEncoder::new(file, flate2::Compression::default()))
+            }
+        }
+    }
+
+    /// Writes any remaining (compressed) bytes to the file
+    fn finish(self) -> io::Result<()> {
+        match self {
+            CompressedFile::Uncompressed(mut file) => file.flush(),
+            CompressedFile::Gzip(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for CompressedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            CompressedFile::Uncompressed(file) => file.write(buf),
+            CompressedFile::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            CompressedFile::Uncompressed(file) => file.flush(),
+            CompressedFile::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::memory::MemoryExec;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn input() -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec!["x", "y"])),
+            ],
+        )?;
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![batch.clone()], vec![batch]],
+            schema,
+            None,
+        )?))
+    }
+
+    async fn write(options: FileWriteOptions) -> Result<tempfile::TempDir> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("out");
+        let sink = FileSinkExec::new(input()?, path.to_str().unwrap(), options);
+        let batches = collect(Arc::new(sink)).await?;
+        let count = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(4, count.value(0));
+        Ok(dir)
+    }
+
+    #[tokio::test]
+    async fn write_csv_files() -> Result<()> {
+        let format = FileFormat::Csv {
+            has_header: false,
+            delimiter: b'|',
+        };
+        let dir = write(FileWriteOptions::new(format)).await?;
+        for i in 0..2 {
+            let path = dir.path().join(format!("out/part-{}.csv", i));
+            assert_eq!("1|x\n2|y\n", fs::read_to_string(path)?);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_gzip_json_files() -> Result<()> {
+        let options =
+            FileWriteOptions::new(FileFormat::NdJson).compression(FileCompression::Gzip);
+        let dir = write(options).await?;
+        let file = File::open(dir.path().join("out/part-1.json.gz"))?;
+        let mut content = String::new();
+        GzDecoder::new(file).read_to_string(&mut content)?;
+        assert_eq!("{\"a\":1,\"b\":\"x\"}\n{\"a\":2,\"b\":\"y\"}\n", content);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_to_existing_directory() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let sink = FileSinkExec::new(
+            input()?,
+            dir.path().to_str().unwrap(),
+            FileWriteOptions::new(FileFormat::Parquet),
+        );
+        assert!(collect(Arc::new(sink)).await.is_err());
+        Ok(())
+    }
+}
@@ -19,15 +19,26 @@
 //! include in its output batches.
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Instant;
 
 use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::datasource::datasource::{
+    scalar_to_f64, ColumnEstimate, ColumnStatistics, Statistics,
+    DEFAULT_EQUALITY_SELECTIVITY, DEFAULT_SELECTIVITY,
+};
 use crate::error::{DataFusionError, Result};
-use crate::physical_plan::{ExecutionPlan, Partitioning, PhysicalExpr};
+use crate::logical_plan::Operator;
+use crate::physical_plan::expressions::{
+    BinaryExpr, Column, IsNotNullExpr, IsNullExpr, Literal, NotExpr,
+};
+use crate::physical_plan::{ExecutionPlan, Partitioning, PhysicalExpr, SQLMetric};
 use arrow::array::BooleanArray;
 use arrow::compute::filter_record_batch;
+use arrow::datatypes::Schema;
 use arrow::datatypes::{DataType, SchemaRef};
 use arrow::error::Result as ArrowResult;
 use arrow::record_batch::RecordBatch;
@@ -44,6 +55,12 @@ pub struct FilterExec {
     predicate: Arc<dyn PhysicalExpr>,
     /// The input plan
     input: Arc<dyn ExecutionPlan>,
+    /// Number of rows read from the input
+    input_rows: Arc<SQLMetric>,
+    /// Number of rows that satisfy the predicate
+    output_rows: Arc<SQLMetric>,
+    /// Time spent evaluating the predicate and filtering the batches
+    elapsed_compute: Arc<SQLMetric>,
 }
 
 impl FilterExec {
@@ -56,6 +73,9 @@ impl FilterExec {
             DataType::Boolean => Ok(Self {
                 predicate,
                 input: input.clone(),
+                input_rows: SQLMetric::counter(),
+                output_rows: SQLMetric::counter(),
+                elapsed_compute: SQLMetric::time_nanos(),
             }),
             other => Err(DataFusionError::Plan(format!(
                 "Filter predicate must return boolean values, not {:?}",
@@ -117,8 +137,138 @@ impl ExecutionPlan for FilterExec {
             schema: self.input.schema().clone(),
             predicate: self.predicate.clone(),
             input: self.input.execute(partition).await?,
+            input_rows: self.input_rows.clone(),
+            output_rows: self.output_rows.clone(),
+            elapsed_compute: self.elapsed_compute.clone(),
         }))
     }
+
+    fn metrics(&self) -> HashMap<String, SQLMetric> {
+        let mut metrics = HashMap::new();
+        metrics.insert("input_rows".to_owned(), (*self.input_rows).clone());
+        metrics.insert("output_rows".to_owned(), (*self.output_rows).clone());
+        metrics.insert(
+            "elapsed_compute".to_owned(),
+            (*self.elapsed_compute).clone(),
+        );
+        metrics
+    }
+
+    /// The statistics of the input, with the number of rows reduced by the
+    /// estimated selectivity of the predicate
+    fn statistics(&self) -> Statistics {
+        let input_statistics = self.input.statistics();
+        let selectivity =
+            selectivity(self.predicate.as_ref(), &self.schema(), &input_statistics);
+        let scale = |n: usize| (n as f64 * selectivity).ceil() as usize;
+        let num_rows = input_statistics.num_rows.map(scale);
+        // the min and max values remain bounds of the remaining values
+        let column_statistics = input_statistics.column_statistics.map(|columns| {
+            columns
+                .into_iter()
+                .map(|c| ColumnStatistics {
+                    null_count: None,
+                    distinct_count: match (c.distinct_count, num_rows) {
+                        (Some(d), Some(n)) => Some(d.min(n)),
+                        (d, _) => d,
+                    },
+                    ..c
+                })
+                .collect()
+        });
+        Statistics {
+            num_rows,
+            total_byte_size: input_statistics.total_byte_size.map(scale),
+            column_statistics,
+        }
+    }
+}
+
+/// Estimates the fraction of the rows for which `predicate` is true, using
+/// the distinct counts, min/max values and null counts of the compared columns
+pub(crate) fn selectivity(
+    predicate: &dyn PhysicalExpr,
+    schema: &Schema,
+    statistics: &Statistics,
+) -> f64 {
+    let any = predicate.as_any();
+    if let Some(not) = any.downcast_ref::<NotExpr>() {
+        return 1.0 - selectivity(not.arg().as_ref(), schema, statistics);
+    }
+    if let Some(is_null) = any.downcast_ref::<IsNullExpr>() {
+        return null_fraction(is_null.arg().as_ref(), schema, statistics);
+    }
+    if let Some(is_not_null) = any.downcast_ref::<IsNotNullExpr>() {
+        return 1.0 - null_fraction(is_not_null.arg().as_ref(), schema, statistics);
+    }
+    let binary = match any.downcast_ref::<BinaryExpr>() {
+        Some(binary) => binary,
+        None => return DEFAULT_SELECTIVITY,
+    };
+    let (left, right) = (binary.left().as_ref(), binary.right().as_ref());
+    match binary.op() {
+        Operator::And => {
+            return selectivity(left, schema, statistics)
+                * selectivity(right, schema, statistics)
+        }
+        Operator::Or => {
+            let l = selectivity(left, schema, statistics);
+            let r = selectivity(right, schema, statistics);
+            return l + r - l * r;
+        }
+        _ => {}
+    }
+
+    // <column> <op> <literal>, with the operator as if the column was on the left
+    let (column, op, literal) = match (
+        column_estimate(left, schema, statistics),
+        right.as_any().downcast_ref::<Literal>(),
+        left.as_any().downcast_ref::<Literal>(),
+        column_estimate(right, schema, statistics),
+    ) {
+        (Some(column), Some(literal), _, _) => (column, *binary.op(), literal),
+        (_, _, Some(literal), Some(column)) => {
+            let op = match binary.op() {
+                Operator::Lt => Operator::Gt,
+                Operator::LtEq => Operator::GtEq,
+                Operator::Gt => Operator::Lt,
+                Operator::GtEq => Operator::LtEq,
+                op => *op,
+            };
+            (column, op, literal)
+        }
+        _ => return DEFAULT_SELECTIVITY,
+    };
+    column.comparison_selectivity(op, scalar_to_f64(literal.value()))
+}
+
+/// The estimate of `expr` if it is a column, unknown if the column has no
+/// statistics
+fn column_estimate(
+    expr: &dyn PhysicalExpr,
+    schema: &Schema,
+    statistics: &Statistics,
+) -> Option<ColumnEstimate> {
+    let column = expr.as_any().downcast_ref::<Column>()?;
+    let index = schema.index_of(column.name()).ok()?;
+    Some(
+        statistics
+            .column_statistics
+            .as_ref()
+            .and_then(|columns| columns.get(index))
+            .map(|c| ColumnEstimate::from_statistics(c, statistics.num_rows))
+            .unwrap_or_default(),
+    )
+}
+
+/// The fraction of the rows for which `expr` is null
+fn null_fraction(
+    expr: &dyn PhysicalExpr,
+    schema: &Schema,
+    statistics: &Statistics,
+) -> f64 {
+    column_estimate(expr, schema, statistics)
+        .map_or(DEFAULT_EQUALITY_SELECTIVITY, |c| c.null_selectivity())
 }
 
 /// The FilterExec streams wraps the input iterator and applies the predicate expression to
@@ -130,6 +280,12 @@ struct FilterExecStream {
     predicate: Arc<dyn PhysicalExpr>,
     /// The input partition to filter.
     input: SendableRecordBatchStream,
+    /// Number of rows read from the input
+    input_rows: Arc<SQLMetric>,
+    /// Number of rows that satisfy the predicate
+    output_rows: Arc<SQLMetric>,
+    /// Time spent evaluating the predicate and filtering the batches
+    elapsed_compute: Arc<SQLMetric>,
 }
 
 fn batch_filter(
@@ -163,7 +319,16 @@ impl Stream for FilterExecStream {
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         self.input.poll_next_unpin(cx).map(|x| match x {
-            Some(Ok(batch)) => Some(batch_filter(&batch, &self.predicate)),
+            Some(Ok(batch)) => {
+                let start = Instant::now();
+                let filtered = batch_filter(&batch, &self.predicate);
+                self.elapsed_compute.add_elapsed(start);
+                self.input_rows.add(batch.num_rows());
+                if let Ok(filtered) = &filtered {
+                    self.output_rows.add(filtered.num_rows());
+                }
+                Some(filtered)
+            }
             other => other,
         })
     }
@@ -184,12 +349,15 @@ impl RecordBatchStream for FilterExecStream {
 mod tests {
 
     use super::*;
+    use crate::datasource::datasource::DEFAULT_RANGE_SELECTIVITY;
     use crate::physical_plan::csv::{CsvExec, CsvReadOptions};
     use crate::physical_plan::expressions::*;
+    use crate::physical_plan::memory::MemoryExec;
     use crate::physical_plan::ExecutionPlan;
     use crate::scalar::ScalarValue;
     use crate::test;
     use crate::{logical_plan::Operator, physical_plan::collect};
+    use arrow::datatypes::Field;
     use std::iter::Iterator;
 
     #[tokio::test]
@@ -237,4 +405,85 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn predicate_selectivity() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let statistics = Statistics {
+            num_rows: Some(1000),
+            total_byte_size: None,
+            column_statistics: Some(vec![
+                ColumnStatistics {
+                    null_count: Some(100),
+                    min_value: Some(ScalarValue::Int32(Some(1))),
+                    max_value: Some(ScalarValue::Int32(Some(101))),
+                    distinct_count: Some(100),
+                },
+                ColumnStatistics::default(),
+            ]),
+        };
+        let a = |op: Operator, value: i32| {
+            binary(col("a"), op, lit(ScalarValue::Int32(Some(value))), &schema)
+        };
+        let estimate = |predicate: Arc<dyn PhysicalExpr>| {
+            let s = selectivity(predicate.as_ref(), &schema, &statistics);
+            (s * 10000.0).round() / 10000.0
+        };
+
+        assert_eq!(0.01, estimate(a(Operator::Eq, 5)?));
+        assert_eq!(0.99, estimate(a(Operator::NotEq, 5)?));
+        assert_eq!(0.0, estimate(a(Operator::Eq, 500)?));
+        assert_eq!(0.25, estimate(a(Operator::Gt, 76)?));
+        assert_eq!(
+            0.25,
+            estimate(binary(
+                lit(ScalarValue::Int32(Some(26))),
+                Operator::GtEq,
+                col("a"),
+                &schema
+            )?)
+        );
+        assert_eq!(
+            0.0025,
+            estimate(binary(
+                a(Operator::Eq, 5)?,
+                Operator::And,
+                a(Operator::Gt, 76)?,
+                &schema
+            )?)
+        );
+        assert_eq!(0.1, estimate(is_null(col("a"))?));
+        assert_eq!(0.9, estimate(is_not_null(col("a"))?));
+
+        // b has no statistics
+        let b_lt_5 = binary(
+            col("b"),
+            Operator::Lt,
+            lit(ScalarValue::Int32(Some(5))),
+            &schema,
+        )?;
+        assert_eq!(
+            (DEFAULT_RANGE_SELECTIVITY * 10000.0).round() / 10000.0,
+            estimate(b_lt_5)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn filter_statistics() -> Result<()> {
+        let batch = test::make_partition(100);
+        let schema = batch.schema();
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None)?);
+        let predicate = is_null(col("i"))?;
+        let filter = FilterExec::try_new(predicate, input)?;
+
+        // there are no nulls
+        let statistics = filter.statistics();
+        assert_eq!(Some(0), statistics.num_rows);
+        assert_eq!(Some(0), statistics.total_byte_size);
+        Ok(())
+    }
 }
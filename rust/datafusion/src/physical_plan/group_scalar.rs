@@ -42,6 +42,8 @@ pub(crate) enum GroupByScalar {
     TimeMicrosecond(i64),
     TimeNanosecond(i64),
     Date32(i32),
+    /// unscaled value, precision and scale, boxed to keep the enum small
+    Decimal128(Box<(i128, usize, usize)>),
 }
 
 impl TryFrom<&ScalarValue> for GroupByScalar {
@@ -68,6 +70,9 @@ impl TryFrom<&ScalarValue> for GroupByScalar {
             ScalarValue::TimeMicrosecond(Some(v)) => GroupByScalar::TimeMicrosecond(*v),
             ScalarValue::TimeNanosecond(Some(v)) => GroupByScalar::TimeNanosecond(*v),
             ScalarValue::Utf8(Some(v)) => GroupByScalar::Utf8(Box::new(v.clone())),
+            ScalarValue::Decimal128(Some(v), precision, scale) => {
+                GroupByScalar::Decimal128(Box::new((*v, *precision, *scale)))
+            }
             ScalarValue::Float32(None)
             | ScalarValue::Float64(None)
             | ScalarValue::Boolean(None)
@@ -79,7 +84,8 @@ impl TryFrom<&ScalarValue> for GroupByScalar {
             | ScalarValue::UInt16(None)
             | ScalarValue::UInt32(None)
             | ScalarValue::UInt64(None)
-            | ScalarValue::Utf8(None) => {
+            | ScalarValue::Utf8(None)
+            | ScalarValue::Decimal128(None, _, _) => {
                 return Err(DataFusionError::Internal(format!(
                     "Cannot convert a ScalarValue holding NULL ({:?})",
                     scalar_value
@@ -95,6 +101,21 @@ impl TryFrom<&ScalarValue> for GroupByScalar {
     }
 }
 
+impl GroupByScalar {
+    /// Number of bytes of memory held by this value, including the memory it
+    /// allocates
+    pub(crate) fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + match self {
+                GroupByScalar::Utf8(v) => std::mem::size_of::<String>() + v.capacity(),
+                GroupByScalar::Decimal128(_) => {
+                    std::mem::size_of::<(i128, usize, usize)>()
+                }
+                _ => 0,
+            }
+    }
+}
+
 impl From<&GroupByScalar> for ScalarValue {
     fn from(group_by_scalar: &GroupByScalar) -> Self {
         match group_by_scalar {
@@ -114,6 +135,10 @@ impl From<&GroupByScalar> for ScalarValue {
             GroupByScalar::TimeMicrosecond(v) => ScalarValue::TimeMicrosecond(Some(*v)),
             GroupByScalar::TimeNanosecond(v) => ScalarValue::TimeNanosecond(Some(*v)),
             GroupByScalar::Date32(v) => ScalarValue::Date32(Some(*v)),
+            GroupByScalar::Decimal128(v) => {
+                let (v, precision, scale) = **v;
+                ScalarValue::Decimal128(Some(v), precision, scale)
+            }
         }
     }
 }
@@ -18,54 +18,46 @@
 //! Defines the execution plan for the hash aggregate operation
 
 use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Instant;
 
 use ahash::RandomState;
 use futures::{
+    channel::mpsc,
     stream::{Stream, StreamExt},
-    Future,
+    Future, SinkExt,
 };
 
+use crate::datasource::datasource::{ColumnStatistics, Statistics};
 use crate::error::{DataFusionError, Result};
+use crate::logical_plan::{GROUPING_ID_COLUMN, MAX_GROUPING_EXPRS};
+use crate::physical_plan::expressions::{col, PhysicalSortExpr};
+use crate::physical_plan::memory::MemoryStream;
+use crate::physical_plan::sort::sort_batches;
+use crate::physical_plan::spill::{merge_sorted_runs, spill, SortedRun};
 use crate::physical_plan::{Accumulator, AggregateExpr};
-use crate::physical_plan::{Distribution, ExecutionPlan, Partitioning, PhysicalExpr};
+use crate::physical_plan::{
+    Distribution, ExecutionPlan, Partitioning, PhysicalExpr, SQLMetric,
+};
 
 use arrow::{
-    array::{Array, UInt32Builder},
+    array::{new_null_array, Array, ArrayRef, UInt32Array, UInt32Builder},
+    compute::{self, cast},
+    datatypes::{DataType, Field, Schema, SchemaRef},
     error::{ArrowError, Result as ArrowResult},
-};
-use arrow::{
-    array::{
-        ArrayRef, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
-        Int8Array, StringArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
-    },
-    compute,
-};
-use arrow::{
-    array::{BooleanArray, Date32Array, DictionaryArray},
-    compute::cast,
-    datatypes::{
-        ArrowDictionaryKeyType, ArrowNativeType, Int16Type, Int32Type, Int64Type,
-        Int8Type, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
-    },
-};
-use arrow::{
-    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
     record_batch::RecordBatch,
 };
 use hashbrown::HashMap;
-use ordered_float::OrderedFloat;
 use pin_project_lite::pin_project;
 
-use arrow::array::{
-    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
-};
 use async_trait::async_trait;
 
 use super::{
-    expressions::Column, group_scalar::GroupByScalar, RecordBatchStream,
-    SendableRecordBatchStream,
+    expressions::Column,
+    row_format::{decode_rows, Rows},
+    RecordBatchStream, SendableRecordBatchStream,
 };
 
 /// Hash aggregate modes
@@ -86,6 +78,9 @@ pub struct HashAggregateExec {
     group_expr: Vec<(Arc<dyn PhysicalExpr>, String)>,
     /// Aggregate expressions
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+    /// Grouping sets, as the indices of their grouping expressions, whose
+    /// groups are computed by a partial aggregate
+    grouping_sets: Option<Vec<Vec<usize>>>,
     /// Input plan, could be a partial aggregate or the input to the aggregate
     input: Arc<dyn ExecutionPlan>,
     /// Schema after the aggregate is applied
@@ -94,22 +89,87 @@ pub struct HashAggregateExec {
     /// same as input.schema() but for the final aggregate it will be the same as the input
     /// to the partial aggregate
     input_schema: SchemaRef,
+    /// Number of bytes of memory held by the groups of all the partitions above which
+    /// they are spilled to disk
+    memory_limit: Option<usize>,
+    /// Number of bytes of memory held by the groups of all the partitions
+    memory_used: Arc<AtomicUsize>,
+    /// Metrics recorded by the executions of the aggregate
+    metrics: AggregateMetrics,
+}
+
+/// The memory held by the groups of the partitions of a [HashAggregateExec],
+/// against its memory limit
+#[derive(Debug, Clone)]
+struct MemoryBudget {
+    /// Number of bytes of memory above which groups are spilled to disk
+    limit: Option<usize>,
+    /// Number of bytes of memory held by the groups of all the partitions
+    used: Arc<AtomicUsize>,
+    /// Number of partitions sharing the limit
+    partitions: usize,
+}
+
+impl MemoryBudget {
+    /// Records that the groups of a partition grew from `previous` to `size`
+    /// bytes, and returns whether the partition must spill its groups: when
+    /// the groups of all the partitions exceed the limit, the partitions whose
+    /// groups hold at least their share of the limit spill them
+    fn grow(&self, previous: usize, size: usize) -> bool {
+        let used = if size >= previous {
+            self.used.fetch_add(size - previous, Ordering::SeqCst) + size - previous
+        } else {
+            self.used.fetch_sub(previous - size, Ordering::SeqCst) - (previous - size)
+        };
+        matches!(self.limit, Some(limit) if used > limit && size * self.partitions >= limit)
+    }
+
+    /// Records that the groups of a partition holding `size` bytes were released
+    fn release(&self, size: usize) {
+        self.used.fetch_sub(size, Ordering::SeqCst);
+    }
+}
+
+/// Metrics recorded by [HashAggregateExec], shared by all its partitions
+#[derive(Debug, Clone)]
+struct AggregateMetrics {
+    /// Number of rows read from the input
+    input_rows: Arc<SQLMetric>,
+    /// Number of rows produced, one per group
+    output_rows: Arc<SQLMetric>,
+    /// Time spent evaluating the expressions and updating the accumulators
+    elapsed_compute: Arc<SQLMetric>,
+}
+
+impl AggregateMetrics {
+    fn new() -> Self {
+        Self {
+            input_rows: SQLMetric::counter(),
+            output_rows: SQLMetric::counter(),
+            elapsed_compute: SQLMetric::time_nanos(),
+        }
+    }
 }
 
 fn create_schema(
     input_schema: &Schema,
     group_expr: &[(Arc<dyn PhysicalExpr>, String)],
     aggr_expr: &[Arc<dyn AggregateExpr>],
+    grouping_sets: Option<&[Vec<usize>]>,
     mode: AggregateMode,
 ) -> Result<Schema> {
-    let mut fields = Vec::with_capacity(group_expr.len() + aggr_expr.len());
+    let mut fields = Vec::with_capacity(group_expr.len() + aggr_expr.len() + 1);
     for (expr, name) in group_expr {
         fields.push(Field::new(
             name,
             expr.data_type(&input_schema)?,
-            expr.nullable(&input_schema)?,
+            // the expressions that are not part of a grouping set are null
+            grouping_sets.is_some() || expr.nullable(&input_schema)?,
         ))
     }
+    if grouping_sets.is_some() {
+        fields.push(Field::new(GROUPING_ID_COLUMN, DataType::UInt32, false));
+    }
 
     match mode {
         AggregateMode::Partial => {
@@ -138,7 +198,56 @@ impl HashAggregateExec {
         input: Arc<dyn ExecutionPlan>,
         input_schema: SchemaRef,
     ) -> Result<Self> {
-        let schema = create_schema(&input.schema(), &group_expr, &aggr_expr, mode)?;
+        Self::try_new_with_grouping_sets(
+            mode,
+            group_expr,
+            aggr_expr,
+            None,
+            input,
+            input_schema,
+        )
+    }
+
+    /// Create a new hash aggregate execution plan computing the groups of the
+    /// `grouping_sets`, which are the indices in `group_expr` of their
+    /// expressions. Its grouping columns are followed by a `__grouping_id`
+    /// column identifying the grouping set of each group.
+    ///
+    /// Grouping sets are only supported by a partial aggregate. The final
+    /// aggregate groups on the grouping columns and the `__grouping_id`.
+    pub fn try_new_with_grouping_sets(
+        mode: AggregateMode,
+        group_expr: Vec<(Arc<dyn PhysicalExpr>, String)>,
+        aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+        grouping_sets: Option<Vec<Vec<usize>>>,
+        input: Arc<dyn ExecutionPlan>,
+        input_schema: SchemaRef,
+    ) -> Result<Self> {
+        if let Some(grouping_sets) = &grouping_sets {
+            if matches!(mode, AggregateMode::Final) {
+                return Err(DataFusionError::Internal(
+                    "Grouping sets are only supported by a partial aggregate".to_string(),
+                ));
+            }
+            if group_expr.len() > MAX_GROUPING_EXPRS
+                || grouping_sets
+                    .iter()
+                    .flatten()
+                    .any(|i| *i >= group_expr.len())
+            {
+                return Err(DataFusionError::Internal(
+                    "Invalid grouping sets of hash aggregate".to_string(),
+                ));
+            }
+        }
+
+        let schema = create_schema(
+            &input.schema(),
+            &group_expr,
+            &aggr_expr,
+            grouping_sets.as_deref(),
+            mode,
+        )?;
 
         let schema = Arc::new(schema);
 
@@ -146,12 +255,23 @@ impl HashAggregateExec {
             mode,
             group_expr,
             aggr_expr,
+            grouping_sets,
             input,
             schema,
             input_schema,
+            memory_limit: None,
+            memory_used: Arc::new(AtomicUsize::new(0)),
+            metrics: AggregateMetrics::new(),
         })
     }
 
+    /// Spill the groups to disk when the groups of all the partitions hold more than
+    /// `memory_limit` bytes of memory
+    pub fn with_memory_limit(mut self, memory_limit: Option<usize>) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
     /// Aggregation mode (full, partial)
     pub fn mode(&self) -> &AggregateMode {
         &self.mode
@@ -167,6 +287,11 @@ impl HashAggregateExec {
         &self.aggr_expr
     }
 
+    /// Grouping sets, as the indices of their grouping expressions
+    pub fn grouping_sets(&self) -> Option<&[Vec<usize>]> {
+        self.grouping_sets.as_deref()
+    }
+
     /// Input plan
     pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
         &self.input
@@ -176,6 +301,12 @@ impl HashAggregateExec {
     pub fn input_schema(&self) -> SchemaRef {
         self.input_schema.clone()
     }
+
+    /// Number of bytes of memory held by the groups of all the partitions above which
+    /// they are spilled to disk
+    pub fn memory_limit(&self) -> Option<usize> {
+        self.memory_limit
+    }
 }
 
 #[async_trait]
@@ -209,12 +340,13 @@ impl ExecutionPlan for HashAggregateExec {
         let input = self.input.execute(partition).await?;
         let group_expr = self.group_expr.iter().map(|x| x.0.clone()).collect();
 
-        if self.group_expr.is_empty() {
+        if self.group_expr.is_empty() && self.grouping_sets.is_none() {
             Ok(Box::pin(HashAggregateStream::new(
                 self.mode,
                 self.schema.clone(),
                 self.aggr_expr.clone(),
                 input,
+                self.metrics.clone(),
             )))
         } else {
             Ok(Box::pin(GroupedHashAggregateStream::new(
@@ -222,28 +354,110 @@ impl ExecutionPlan for HashAggregateExec {
                 self.schema.clone(),
                 group_expr,
                 self.aggr_expr.clone(),
+                self.grouping_sets.clone(),
                 input,
+                MemoryBudget {
+                    limit: self.memory_limit,
+                    used: self.memory_used.clone(),
+                    partitions: self.input.output_partitioning().partition_count(),
+                },
+                self.metrics.clone(),
             )))
         }
     }
 
+    fn metrics(&self) -> std::collections::HashMap<String, SQLMetric> {
+        let mut metrics = std::collections::HashMap::new();
+        metrics.insert("input_rows".to_owned(), (*self.metrics.input_rows).clone());
+        metrics.insert(
+            "output_rows".to_owned(),
+            (*self.metrics.output_rows).clone(),
+        );
+        metrics.insert(
+            "elapsed_compute".to_owned(),
+            (*self.metrics.elapsed_compute).clone(),
+        );
+        metrics
+    }
+
     fn with_new_children(
         &self,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         match children.len() {
-            1 => Ok(Arc::new(HashAggregateExec::try_new(
-                self.mode,
-                self.group_expr.clone(),
-                self.aggr_expr.clone(),
-                children[0].clone(),
-                self.input_schema.clone(),
-            )?)),
+            1 => Ok(Arc::new(
+                HashAggregateExec::try_new_with_grouping_sets(
+                    self.mode,
+                    self.group_expr.clone(),
+                    self.aggr_expr.clone(),
+                    self.grouping_sets.clone(),
+                    children[0].clone(),
+                    self.input_schema.clone(),
+                )?
+                .with_memory_limit(self.memory_limit),
+            )),
             _ => Err(DataFusionError::Internal(
                 "HashAggregateExec wrong number of children".to_string(),
             )),
         }
     }
+
+    /// Estimates the number of groups as the product of the distinct counts
+    /// of the group columns, bounded by the number of input rows
+    fn statistics(&self) -> Statistics {
+        let input_statistics = self.input.statistics();
+        // a partial aggregate produces the groups of every input partition,
+        // and of every grouping set
+        let partitions = match self.mode {
+            AggregateMode::Partial => self.input.output_partitioning().partition_count(),
+            AggregateMode::Final => 1,
+        } * self.grouping_sets.as_ref().map_or(1, Vec::len);
+        let group_statistics = self
+            .group_expr
+            .iter()
+            .map(|(expr, _)| {
+                let column = expr.as_any().downcast_ref::<Column>()?;
+                let index = self.input_schema.index_of(column.name()).ok()?;
+                input_statistics
+                    .column_statistics
+                    .as_ref()?
+                    .get(index)
+                    .cloned()
+            })
+            .collect::<Vec<_>>();
+
+        let num_rows = if self.group_expr.is_empty() {
+            Some(partitions)
+        } else {
+            let num_groups = group_statistics
+                .iter()
+                .map(|c| c.as_ref().and_then(|c| c.distinct_count))
+                .try_fold(1usize, |acc, d| Some(acc.saturating_mul(d?)))
+                .map(|groups| groups.saturating_mul(partitions));
+            match (num_groups, input_statistics.num_rows) {
+                (Some(groups), Some(input_rows)) => Some(groups.min(input_rows)),
+                (groups, input_rows) => groups.or(input_rows),
+            }
+        };
+
+        let column_statistics = group_statistics
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .chain(
+                // the aggregate columns, which are one or more per expression
+                vec![
+                    ColumnStatistics::default();
+                    self.schema.fields().len() - self.group_expr.len()
+                ],
+            )
+            .collect();
+
+        Statistics {
+            num_rows,
+            total_byte_size: None,
+            column_statistics: Some(column_statistics),
+        }
+    }
 }
 
 /*
@@ -275,40 +489,22 @@ pin_project! {
     struct GroupedHashAggregateStream {
         schema: SchemaRef,
         #[pin]
-        output: futures::channel::oneshot::Receiver<ArrowResult<RecordBatch>>,
-        finished: bool,
+        output: mpsc::Receiver<ArrowResult<RecordBatch>>,
     }
 }
 
+/// Updates the accumulators of the groups of `group_rows`, the group keys of a
+/// batch in the row format, with the values of the aggregation expressions,
+/// `aggr_input_values`, of the batch
 fn group_aggregate_batch(
     mode: &AggregateMode,
-    group_expr: &[Arc<dyn PhysicalExpr>],
+    group_rows: &Rows,
     aggr_expr: &[Arc<dyn AggregateExpr>],
-    batch: RecordBatch,
+    aggr_input_values: &[Vec<ArrayRef>],
     mut accumulators: Accumulators,
-    aggregate_expressions: &[Vec<Arc<dyn PhysicalExpr>>],
+    groups_size: &mut usize,
 ) -> Result<Accumulators> {
-    // evaluate the grouping expressions
-    let group_values = evaluate(group_expr, &batch)?;
-
-    // evaluate the aggregation expressions.
-    // We could evaluate them after the `take`, but since we need to evaluate all
-    // of them anyways, it is more performant to do it while they are together.
-    let aggr_input_values = evaluate_many(aggregate_expressions, &batch)?;
-
-    // create vector large enough to hold the grouping key
-    // this is an optimization to avoid allocating `key` on every row.
-    // it will be overwritten on every iteration of the loop below
-    let mut group_by_values = Vec::with_capacity(group_values.len());
-    for _ in 0..group_values.len() {
-        group_by_values.push(GroupByScalar::UInt32(0));
-    }
-
-    let mut group_by_values = group_by_values.into_boxed_slice();
-
-    let mut key = Vec::with_capacity(group_values.len());
-
-    // 1.1 construct the key from the group values
+    // 1.1 look up the key of the row
     // 1.2 construct the mapping key if it does not exist
     // 1.3 add the row' index to `indices`
 
@@ -318,18 +514,15 @@ fn group_aggregate_batch(
     // Keys received in this batch
     let mut batch_keys = vec![];
 
-    for row in 0..batch.num_rows() {
-        // 1.1
-        create_key(&group_values, row, &mut key)
-            .map_err(DataFusionError::into_arrow_external_error)?;
-
+    for (row, key) in group_rows.iter().enumerate() {
         accumulators
+            // 1.1
             .raw_entry_mut()
-            .from_key(&key)
+            .from_key(key)
             // 1.3
-            .and_modify(|_, (_, _, v)| {
+            .and_modify(|_, (_, v)| {
                 if v.is_empty() {
-                    batch_keys.push(key.clone())
+                    batch_keys.push(key)
                 };
                 v.push(row as u32)
             })
@@ -337,12 +530,9 @@ fn group_aggregate_batch(
             .or_insert_with(|| {
                 // We can safely unwrap here as we checked we can create an accumulator before
                 let accumulator_set = create_accumulators(aggr_expr).unwrap();
-                batch_keys.push(key.clone());
-                let _ = create_group_by_values(&group_values, row, &mut group_by_values);
-                (
-                    key.clone(),
-                    (group_by_values.clone(), accumulator_set, vec![row as u32]),
-                )
+                batch_keys.push(key);
+                *groups_size += group_memory_size(key, &accumulator_set);
+                (key.to_vec(), (accumulator_set, vec![row as u32]))
             });
     }
 
@@ -351,7 +541,7 @@ fn group_aggregate_batch(
     let mut offsets = vec![0];
     let mut offset_so_far = 0;
     for key in batch_keys.iter() {
-        let (_, _, indices) = accumulators.get_mut(key).unwrap();
+        let (_, indices) = accumulators.get_mut(*key).unwrap();
         batch_indices.append_slice(&indices)?;
         offset_so_far += indices.len();
         offsets.push(offset_so_far);
@@ -383,10 +573,10 @@ fn group_aggregate_batch(
     // 2.4 update / merge the accumulator with the values
     // 2.5 clear indices
     batch_keys
-        .iter_mut()
+        .iter()
         .zip(offsets.windows(2))
         .try_for_each(|(key, offsets)| {
-            let (_, accumulator_set, indices) = accumulators.get_mut(key).unwrap();
+            let (accumulator_set, indices) = accumulators.get_mut(*key).unwrap();
             // 2.2
             accumulator_set
                 .iter_mut()
@@ -403,12 +593,19 @@ fn group_aggregate_batch(
                             .collect::<Vec<ArrayRef>>(),
                     )
                 })
-                .try_for_each(|(accumulator, values)| match mode {
-                    AggregateMode::Partial => accumulator.update_batch(&values),
-                    AggregateMode::Final => {
-                        // note: the aggregation here is over states, not values, thus the merge
-                        accumulator.merge_batch(&values)
-                    }
+                .try_for_each(|(accumulator, values)| {
+                    let size = accumulator.size();
+                    let result = match mode {
+                        AggregateMode::Partial => accumulator.update_batch(&values),
+                        AggregateMode::Final => {
+                            // note: the aggregation here is over states, not values, thus the merge
+                            accumulator.merge_batch(&values)
+                        }
+                    };
+                    // accumulators may grow with the values they are updated with
+                    *groups_size =
+                        (*groups_size + accumulator.size()).saturating_sub(size);
+                    result
                 })
                 // 2.5
                 .and({
@@ -419,182 +616,65 @@ fn group_aggregate_batch(
     Ok(accumulators)
 }
 
-/// Appends a sequence of [u8] bytes for the value in `col[row]` to
-/// `vec` to be used as a key into the hash map for a dictionary type
-///
-/// Note that ideally, for dictionary encoded columns, we would be
-/// able to simply use the dictionary idicies themselves (no need to
-/// look up values) or possibly simply build the hash table entirely
-/// on the dictionary indexes.
-///
-/// This aproach would likely work (very) well for the common case,
-/// but it also has to to handle the case where the dictionary itself
-/// is not the same across all record batches (and thus indexes in one
-/// record batch may not correspond to the same index in another)
-fn dictionary_create_key_for_col<K: ArrowDictionaryKeyType>(
-    col: &ArrayRef,
-    row: usize,
-    vec: &mut Vec<u8>,
-) -> Result<()> {
-    let dict_col = col.as_any().downcast_ref::<DictionaryArray<K>>().unwrap();
-
-    // look up the index in the values dictionary
-    let keys_col = dict_col.keys_array();
-    let values_index = keys_col.value(row).to_usize().ok_or_else(|| {
-        DataFusionError::Internal(format!(
-            "Can not convert index to usize in dictionary of type creating group by value {:?}",
-            keys_col.data_type()
-        ))
-    })?;
-
-    create_key_for_col(&dict_col.values(), values_index, vec)
-}
-
-/// Appends a sequence of [u8] bytes for the value in `col[row]` to
-/// `vec` to be used as a key into the hash map
-fn create_key_for_col(col: &ArrayRef, row: usize, vec: &mut Vec<u8>) -> Result<()> {
-    match col.data_type() {
-        DataType::Boolean => {
-            let array = col.as_any().downcast_ref::<BooleanArray>().unwrap();
-            vec.extend_from_slice(&[array.value(row) as u8]);
-        }
-        DataType::Float32 => {
-            let array = col.as_any().downcast_ref::<Float32Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::Float64 => {
-            let array = col.as_any().downcast_ref::<Float64Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::UInt8 => {
-            let array = col.as_any().downcast_ref::<UInt8Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::UInt16 => {
-            let array = col.as_any().downcast_ref::<UInt16Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::UInt32 => {
-            let array = col.as_any().downcast_ref::<UInt32Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::UInt64 => {
-            let array = col.as_any().downcast_ref::<UInt64Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::Int8 => {
-            let array = col.as_any().downcast_ref::<Int8Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::Int16 => {
-            let array = col.as_any().downcast_ref::<Int16Array>().unwrap();
-            vec.extend(array.value(row).to_le_bytes().iter());
-        }
-        DataType::Int32 => {
-            let array = col.as_any().downcast_ref::<Int32Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::Int64 => {
-            let array = col.as_any().downcast_ref::<Int64Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::Timestamp(TimeUnit::Millisecond, None) => {
-            let array = col
-                .as_any()
-                .downcast_ref::<TimestampMillisecondArray>()
-                .unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::Timestamp(TimeUnit::Microsecond, None) => {
-            let array = col
-                .as_any()
-                .downcast_ref::<TimestampMicrosecondArray>()
-                .unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
-            let array = col
-                .as_any()
-                .downcast_ref::<TimestampNanosecondArray>()
-                .unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::Utf8 => {
-            let array = col.as_any().downcast_ref::<StringArray>().unwrap();
-            let value = array.value(row);
-            // store the size
-            vec.extend_from_slice(&value.len().to_le_bytes());
-            // store the string value
-            vec.extend_from_slice(value.as_bytes());
-        }
-        DataType::Date32 => {
-            let array = col.as_any().downcast_ref::<Date32Array>().unwrap();
-            vec.extend_from_slice(&array.value(row).to_le_bytes());
-        }
-        DataType::Dictionary(index_type, _) => match **index_type {
-            DataType::Int8 => {
-                dictionary_create_key_for_col::<Int8Type>(col, row, vec)?;
-            }
-            DataType::Int16 => {
-                dictionary_create_key_for_col::<Int16Type>(col, row, vec)?;
-            }
-            DataType::Int32 => {
-                dictionary_create_key_for_col::<Int32Type>(col, row, vec)?;
-            }
-            DataType::Int64 => {
-                dictionary_create_key_for_col::<Int64Type>(col, row, vec)?;
-            }
-            DataType::UInt8 => {
-                dictionary_create_key_for_col::<UInt8Type>(col, row, vec)?;
-            }
-            DataType::UInt16 => {
-                dictionary_create_key_for_col::<UInt16Type>(col, row, vec)?;
-            }
-            DataType::UInt32 => {
-                dictionary_create_key_for_col::<UInt32Type>(col, row, vec)?;
-            }
-            DataType::UInt64 => {
-                dictionary_create_key_for_col::<UInt64Type>(col, row, vec)?;
-            }
-            _ => return Err(DataFusionError::Internal(format!(
-                "Unsupported GROUP BY type (dictionary index type not supported creating key) {}",
-                col.data_type(),
-            ))),
-        },
-        _ => {
-            // This is internal because we should have caught this before.
-            return Err(DataFusionError::Internal(format!(
-                "Unsupported GROUP BY type creating key {}",
-                col.data_type(),
-            )));
-        }
-    }
-    Ok(())
-}
-
-/// Create a key `Vec<u8>` that is used as key for the hashmap
-pub(crate) fn create_key(
-    group_by_keys: &[ArrayRef],
-    row: usize,
-    vec: &mut Vec<u8>,
-) -> Result<()> {
-    vec.clear();
-    for col in group_by_keys {
-        create_key_for_col(col, row, vec)?
-    }
-    Ok(())
+/// Estimates the number of bytes of memory held by a group
+fn group_memory_size(key: &[u8], accumulators: &[AccumulatorItem]) -> usize {
+    key.len()
+        + accumulators
+            .iter()
+            .map(|accumulator| accumulator.size())
+            .sum::<usize>()
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn compute_grouped_hash_aggregate(
     mode: AggregateMode,
     schema: SchemaRef,
     group_expr: Vec<Arc<dyn PhysicalExpr>>,
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+    grouping_sets: Option<Vec<Vec<usize>>>,
     mut input: SendableRecordBatchStream,
-) -> ArrowResult<RecordBatch> {
+    memory: MemoryBudget,
+    metrics: AggregateMetrics,
+    sender: &mut mpsc::Sender<ArrowResult<RecordBatch>>,
+) -> Result<()> {
+    // sends a batch of groups, returning false when the plan is torn down
+    let send = |batch: ArrowResult<RecordBatch>| {
+        let mut sender = sender.clone();
+        let output_rows = metrics.output_rows.clone();
+        async move {
+            if let Ok(batch) = &batch {
+                output_rows.add(batch.num_rows());
+            }
+            sender.send(batch).await.is_ok()
+        }
+    };
+
+    // the expressions to merge the states of spilled groups
+    let merge_expressions = aggregate_expressions(&aggr_expr, &AggregateMode::Final)?;
     // the expressions to evaluate the batch, one vec of expressions per aggregation
-    let aggregate_expressions = aggregate_expressions(&aggr_expr, &mode)
-        .map_err(DataFusionError::into_arrow_external_error)?;
+    let aggregate_expressions = aggregate_expressions(&aggr_expr, &mode)?;
+    // the grouping columns, followed by the grouping id with grouping sets
+    let num_group_columns = group_expr.len() + grouping_sets.is_some() as usize;
+
+    // groups spilled to disk hold the state of their accumulators, as in
+    // the output of a partial aggregate, and are sorted on the group keys
+    let mut state_fields = schema.fields()[..num_group_columns].to_vec();
+    for expr in &aggr_expr {
+        state_fields.extend(expr.state_fields()?);
+    }
+    let state_schema = Arc::new(Schema::new(state_fields));
+    let state_group_expr = (0..num_group_columns)
+        .map(|i| col(state_schema.field(i).name()))
+        .collect::<Vec<_>>();
+    let state_sort_expr = state_group_expr
+        .iter()
+        .map(|expr| PhysicalSortExpr {
+            expr: expr.clone(),
+            options: Default::default(),
+        })
+        .collect::<Vec<_>>();
+    let mut runs: Vec<SortedRun> = vec![];
+    let mut max_rows = 0;
 
     // mapping key -> (set of accumulators, indices of the key in the batch)
     // * the indexes are updated at each row
@@ -604,32 +684,164 @@ async fn compute_grouped_hash_aggregate(
 
     // iterate over all input batches and update the accumulators
     let mut accumulators = Accumulators::default();
+    let mut groups_size = 0;
     while let Some(batch) = input.next().await {
         let batch = batch?;
+        let start = Instant::now();
+        metrics.input_rows.add(batch.num_rows());
+        max_rows = max_rows.max(batch.num_rows());
+        let previous_size = groups_size;
+        let group_values = evaluate(&group_expr, &batch)?;
+        // evaluate the aggregation expressions.
+        // We could evaluate them after the `take`, but since we need to evaluate all
+        // of them anyways, it is more performant to do it while they are together.
+        let aggr_input_values = evaluate_many(&aggregate_expressions, &batch)?;
+        for group_values in
+            grouping_set_values(group_values, grouping_sets.as_deref(), batch.num_rows())
+        {
+            let group_rows = Rows::try_new(&group_values)?;
+            accumulators = group_aggregate_batch(
+                &mode,
+                &group_rows,
+                &aggr_expr,
+                &aggr_input_values,
+                accumulators,
+                &mut groups_size,
+            )?;
+        }
+
+        if memory.grow(previous_size, groups_size) {
+            let state = create_batch_from_map(
+                &AggregateMode::Partial,
+                &accumulators,
+                num_group_columns,
+                &state_schema,
+            )?;
+            if let Some(sorted) =
+                sort_batches(&[state], &state_schema, &state_sort_expr, None)?
+            {
+                runs.push(spill(sorted, max_rows).await?);
+            }
+            accumulators = Accumulators::default();
+            memory.release(groups_size);
+            groups_size = 0;
+        }
+        metrics.elapsed_compute.add_elapsed(start);
+    }
+
+    if runs.is_empty() {
+        let start = Instant::now();
+        let batch =
+            create_batch_from_map(&mode, &accumulators, num_group_columns, &schema);
+        memory.release(groups_size);
+        metrics.elapsed_compute.add_elapsed(start);
+        send(batch).await;
+        return Ok(());
+    }
+
+    // merge the sorted runs of spilled groups, where the rows of a group are
+    // adjacent, and the groups in memory
+    let state = create_batch_from_map(
+        &AggregateMode::Partial,
+        &accumulators,
+        num_group_columns,
+        &state_schema,
+    )?;
+    memory.release(groups_size);
+    if let Some(sorted) = sort_batches(&[state], &state_schema, &state_sort_expr, None)? {
+        runs.push(Box::pin(MemoryStream::try_new(
+            vec![sorted],
+            state_schema.clone(),
+            None,
+        )?));
+    }
+    let mut accumulators = Accumulators::default();
+    let mut merged = merge_sorted_runs(runs, state_sort_expr, state_schema, max_rows);
+    while let Some(batch) = merged.next().await {
+        let batch = batch?;
+        let start = Instant::now();
+
+        let group_values = evaluate(&state_group_expr, &batch)?;
+        let group_rows = Rows::try_new(&group_values)?;
+        let aggr_input_values = evaluate_many(&merge_expressions, &batch)?;
         accumulators = group_aggregate_batch(
-            &mode,
-            &group_expr,
+            &AggregateMode::Final,
+            &group_rows,
             &aggr_expr,
-            batch,
+            &aggr_input_values,
             accumulators,
-            &aggregate_expressions,
-        )
-        .map_err(DataFusionError::into_arrow_external_error)?;
+            &mut 0,
+        )?;
+        // the last group of the batch may continue in the next batch
+        let last_group = accumulators.remove_entry(group_rows.row(batch.num_rows() - 1));
+        let batch =
+            create_batch_from_map(&mode, &accumulators, num_group_columns, &schema);
+        metrics.elapsed_compute.add_elapsed(start);
+        // If send fails, plan being torn down, there is no place to send
+        // the rest of the groups
+        if !send(batch).await {
+            return Ok(());
+        }
+
+        accumulators = Accumulators::default();
+        if let Some((key, group)) = last_group {
+            accumulators.insert(key, group);
+        }
     }
+    let batch = create_batch_from_map(&mode, &accumulators, num_group_columns, &schema);
+    send(batch).await;
+    Ok(())
+}
 
-    create_batch_from_map(&mode, &accumulators, group_expr.len(), &schema)
+/// Returns the grouping columns of every grouping set, computed from the values
+/// of the grouping expressions. The columns that are not part of a grouping set
+/// are null, and are followed by its grouping id, whose bit `n - 1 - i` is set
+/// when the expression `i` of `n` is not part of the set.
+fn grouping_set_values(
+    group_values: Vec<ArrayRef>,
+    grouping_sets: Option<&[Vec<usize>]>,
+    num_rows: usize,
+) -> Vec<Vec<ArrayRef>> {
+    let grouping_sets = match grouping_sets {
+        Some(grouping_sets) => grouping_sets,
+        None => return vec![group_values],
+    };
+    grouping_sets
+        .iter()
+        .map(|set| {
+            let mut grouping_id = 0u32;
+            let mut values = group_values
+                .iter()
+                .enumerate()
+                .map(|(i, array)| {
+                    if set.contains(&i) {
+                        array.clone()
+                    } else {
+                        grouping_id |= 1 << (group_values.len() - 1 - i);
+                        new_null_array(array.data_type(), num_rows)
+                    }
+                })
+                .collect::<Vec<_>>();
+            values.push(Arc::new(UInt32Array::from(vec![grouping_id; num_rows])));
+            values
+        })
+        .collect()
 }
 
 impl GroupedHashAggregateStream {
     /// Create a new HashAggregateStream
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         mode: AggregateMode,
         schema: SchemaRef,
         group_expr: Vec<Arc<dyn PhysicalExpr>>,
         aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+        grouping_sets: Option<Vec<Vec<usize>>>,
         input: SendableRecordBatchStream,
+        memory: MemoryBudget,
+        metrics: AggregateMetrics,
     ) -> Self {
-        let (tx, rx) = futures::channel::oneshot::channel();
+        let (mut tx, rx) = mpsc::channel(1);
 
         let schema_clone = schema.clone();
         tokio::spawn(async move {
@@ -638,23 +850,28 @@ impl GroupedHashAggregateStream {
                 schema_clone,
                 group_expr,
                 aggr_expr,
+                grouping_sets,
                 input,
+                memory,
+                metrics,
+                &mut tx,
             )
             .await;
-            tx.send(result)
+            if let Err(e) = result {
+                let e = match e {
+                    DataFusionError::ArrowError(e) => e,
+                    e => e.into_arrow_external_error(),
+                };
+                tx.send(Err(e)).await.ok();
+            }
         });
 
-        GroupedHashAggregateStream {
-            schema,
-            output: rx,
-            finished: false,
-        }
+        GroupedHashAggregateStream { schema, output: rx }
     }
 }
 
 type AccumulatorItem = Box<dyn Accumulator>;
-type Accumulators =
-    HashMap<Vec<u8>, (Box<[GroupByScalar]>, Vec<AccumulatorItem>, Vec<u32>), RandomState>;
+type Accumulators = HashMap<Vec<u8>, (Vec<AccumulatorItem>, Vec<u32>), RandomState>;
 
 impl Stream for GroupedHashAggregateStream {
     type Item = ArrowResult<RecordBatch>;
@@ -663,27 +880,7 @@ impl Stream for GroupedHashAggregateStream {
         self: std::pin::Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        if self.finished {
-            return Poll::Ready(None);
-        }
-
-        // is the output ready?
-        let this = self.project();
-        let output_poll = this.output.poll(cx);
-
-        match output_poll {
-            Poll::Ready(result) => {
-                *this.finished = true;
-
-                // check for error in receiving channel and unwrap actual result
-                let result = match result {
-                    Err(e) => Err(ArrowError::ExternalError(Box::new(e))), // error receiving
-                    Ok(result) => result,
-                };
-                Poll::Ready(Some(result))
-            }
-            Poll::Pending => Poll::Pending,
-        }
+        self.project().output.poll_next(cx)
     }
 }
 
@@ -762,6 +959,7 @@ async fn compute_hash_aggregate(
     schema: SchemaRef,
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
     mut input: SendableRecordBatchStream,
+    metrics: AggregateMetrics,
 ) -> ArrowResult<RecordBatch> {
     let mut accumulators = create_accumulators(&aggr_expr)
         .map_err(DataFusionError::into_arrow_external_error)?;
@@ -775,14 +973,21 @@ async fn compute_hash_aggregate(
     // future is ready when all batches are computed
     while let Some(batch) = input.next().await {
         let batch = batch?;
+        let start = Instant::now();
+        metrics.input_rows.add(batch.num_rows());
         aggregate_batch(&mode, &batch, &mut accumulators, &expressions)
             .map_err(DataFusionError::into_arrow_external_error)?;
+        metrics.elapsed_compute.add_elapsed(start);
     }
 
     // 2. convert values to a record batch
-    finalize_aggregation(&accumulators, &mode)
+    let start = Instant::now();
+    let batch = finalize_aggregation(&accumulators, &mode)
         .map(|columns| RecordBatch::try_new(schema.clone(), columns))
-        .map_err(DataFusionError::into_arrow_external_error)?
+        .map_err(DataFusionError::into_arrow_external_error)??;
+    metrics.elapsed_compute.add_elapsed(start);
+    metrics.output_rows.add(batch.num_rows());
+    Ok(batch)
 }
 
 impl HashAggregateStream {
@@ -792,13 +997,15 @@ impl HashAggregateStream {
         schema: SchemaRef,
         aggr_expr: Vec<Arc<dyn AggregateExpr>>,
         input: SendableRecordBatchStream,
+        metrics: AggregateMetrics,
     ) -> Self {
         let (tx, rx) = futures::channel::oneshot::channel();
 
         let schema_clone = schema.clone();
         tokio::spawn(async move {
             let result =
-                compute_hash_aggregate(mode, schema_clone, aggr_expr, input).await;
+                compute_hash_aggregate(mode, schema_clone, aggr_expr, input, metrics)
+                    .await;
             tx.send(result)
         });
 
@@ -899,81 +1106,44 @@ fn create_batch_from_map(
     num_group_expr: usize,
     output_schema: &Schema,
 ) -> ArrowResult<RecordBatch> {
-    // 1. for each key
-    // 2. create single-row ArrayRef with all group expressions
-    // 3. create single-row ArrayRef with all aggregate states or values
-    // 4. collect all in a vector per key of vec<ArrayRef>, vec[i][j]
-    // 5. concatenate the arrays over the second index [j] into a single vec<ArrayRef>.
-    let arrays = accumulators
-        .iter()
-        .map(|(_, (group_by_values, accumulator_set, _))| {
-            // 2.
-            let mut groups = (0..num_group_expr)
-                .map(|i| match &group_by_values[i] {
-                    GroupByScalar::Float32(n) => {
-                        Arc::new(Float32Array::from(vec![(*n).into()] as Vec<f32>))
-                            as ArrayRef
-                    }
-                    GroupByScalar::Float64(n) => {
-                        Arc::new(Float64Array::from(vec![(*n).into()] as Vec<f64>))
-                            as ArrayRef
-                    }
-                    GroupByScalar::Int8(n) => {
-                        Arc::new(Int8Array::from(vec![*n])) as ArrayRef
-                    }
-                    GroupByScalar::Int16(n) => Arc::new(Int16Array::from(vec![*n])),
-                    GroupByScalar::Int32(n) => Arc::new(Int32Array::from(vec![*n])),
-                    GroupByScalar::Int64(n) => Arc::new(Int64Array::from(vec![*n])),
-                    GroupByScalar::UInt8(n) => Arc::new(UInt8Array::from(vec![*n])),
-                    GroupByScalar::UInt16(n) => Arc::new(UInt16Array::from(vec![*n])),
-                    GroupByScalar::UInt32(n) => Arc::new(UInt32Array::from(vec![*n])),
-                    GroupByScalar::UInt64(n) => Arc::new(UInt64Array::from(vec![*n])),
-                    GroupByScalar::Utf8(str) => {
-                        Arc::new(StringArray::from(vec![&***str]))
-                    }
-                    GroupByScalar::Boolean(b) => Arc::new(BooleanArray::from(vec![*b])),
-                    GroupByScalar::TimeMillisecond(n) => {
-                        Arc::new(TimestampMillisecondArray::from(vec![*n]))
-                    }
-                    GroupByScalar::TimeMicrosecond(n) => {
-                        Arc::new(TimestampMicrosecondArray::from(vec![*n]))
-                    }
-                    GroupByScalar::TimeNanosecond(n) => {
-                        Arc::new(TimestampNanosecondArray::from_vec(vec![*n], None))
-                    }
-                    GroupByScalar::Date32(n) => Arc::new(Date32Array::from(vec![*n])),
-                })
-                .collect::<Vec<ArrayRef>>();
+    if accumulators.is_empty() {
+        return Ok(RecordBatch::new_empty(Arc::new(output_schema.to_owned())));
+    }
 
-            // 3.
-            groups.extend(
-                finalize_aggregation(accumulator_set, mode)
-                    .map_err(DataFusionError::into_arrow_external_error)?,
-            );
+    // 1. decode the group keys of all groups to the group columns
+    // 2. create single-row ArrayRef with all aggregate states or values
+    // 3. collect all in a vector per key of vec<ArrayRef>, vec[i][j]
+    // 4. concatenate the arrays over the second index [j] into a single vec<ArrayRef>.
+    // 1.
+    let group_types = output_schema.fields()[..num_group_expr]
+        .iter()
+        .map(|field| field.data_type().clone())
+        .collect::<Vec<_>>();
+    let mut columns =
+        decode_rows(accumulators.keys().map(|key| key.as_slice()), &group_types)
+            .map_err(DataFusionError::into_arrow_external_error)?;
 
-            Ok(groups)
+    let arrays = accumulators
+        .values()
+        // 2.
+        .map(|(accumulator_set, _)| {
+            finalize_aggregation(accumulator_set, mode)
+                .map_err(DataFusionError::into_arrow_external_error)
         })
-        // 4.
+        // 3.
         .collect::<ArrowResult<Vec<Vec<ArrayRef>>>>()?;
+    // 4.
+    columns.extend(concatenate(arrays)?);
 
-    let batch = if !arrays.is_empty() {
-        // 5.
-        let columns = concatenate(arrays)?;
-
-        // cast output if needed (e.g. for types like Dictionary where
-        // the intermediate GroupByScalar type was not the same as the
-        // output
-        let columns = columns
-            .iter()
-            .zip(output_schema.fields().iter())
-            .map(|(col, desired_field)| cast(col, desired_field.data_type()))
-            .collect::<ArrowResult<Vec<_>>>()?;
+    // cast output if needed (e.g. for types like Dictionary where
+    // the group keys are decoded to the value type of the dictionary)
+    let columns = columns
+        .iter()
+        .zip(output_schema.fields().iter())
+        .map(|(col, desired_field)| cast(col, desired_field.data_type()))
+        .collect::<ArrowResult<Vec<_>>>()?;
 
-        RecordBatch::try_new(Arc::new(output_schema.to_owned()), columns)?
-    } else {
-        RecordBatch::new_empty(Arc::new(output_schema.to_owned()))
-    };
-    Ok(batch)
+    RecordBatch::try_new(Arc::new(output_schema.to_owned()), columns)
 }
 
 fn create_accumulators(
@@ -1015,135 +1185,6 @@ fn finalize_aggregation(
     }
 }
 
-/// Extract the value in `col[row]` from a dictionary a GroupByScalar
-fn dictionary_create_group_by_value<K: ArrowDictionaryKeyType>(
-    col: &ArrayRef,
-    row: usize,
-) -> Result<GroupByScalar> {
-    let dict_col = col.as_any().downcast_ref::<DictionaryArray<K>>().unwrap();
-
-    // look up the index in the values dictionary
-    let keys_col = dict_col.keys_array();
-    let values_index = keys_col.value(row).to_usize().ok_or_else(|| {
-        DataFusionError::Internal(format!(
-            "Can not convert index to usize in dictionary of type creating group by value {:?}",
-            keys_col.data_type()
-        ))
-    })?;
-
-    create_group_by_value(&dict_col.values(), values_index)
-}
-
-/// Extract the value in `col[row]` as a GroupByScalar
-fn create_group_by_value(col: &ArrayRef, row: usize) -> Result<GroupByScalar> {
-    match col.data_type() {
-        DataType::Float32 => {
-            let array = col.as_any().downcast_ref::<Float32Array>().unwrap();
-            Ok(GroupByScalar::Float32(OrderedFloat::from(array.value(row))))
-        }
-        DataType::Float64 => {
-            let array = col.as_any().downcast_ref::<Float64Array>().unwrap();
-            Ok(GroupByScalar::Float64(OrderedFloat::from(array.value(row))))
-        }
-        DataType::UInt8 => {
-            let array = col.as_any().downcast_ref::<UInt8Array>().unwrap();
-            Ok(GroupByScalar::UInt8(array.value(row)))
-        }
-        DataType::UInt16 => {
-            let array = col.as_any().downcast_ref::<UInt16Array>().unwrap();
-            Ok(GroupByScalar::UInt16(array.value(row)))
-        }
-        DataType::UInt32 => {
-            let array = col.as_any().downcast_ref::<UInt32Array>().unwrap();
-            Ok(GroupByScalar::UInt32(array.value(row)))
-        }
-        DataType::UInt64 => {
-            let array = col.as_any().downcast_ref::<UInt64Array>().unwrap();
-            Ok(GroupByScalar::UInt64(array.value(row)))
-        }
-        DataType::Int8 => {
-            let array = col.as_any().downcast_ref::<Int8Array>().unwrap();
-            Ok(GroupByScalar::Int8(array.value(row)))
-        }
-        DataType::Int16 => {
-            let array = col.as_any().downcast_ref::<Int16Array>().unwrap();
-            Ok(GroupByScalar::Int16(array.value(row)))
-        }
-        DataType::Int32 => {
-            let array = col.as_any().downcast_ref::<Int32Array>().unwrap();
-            Ok(GroupByScalar::Int32(array.value(row)))
-        }
-        DataType::Int64 => {
-            let array = col.as_any().downcast_ref::<Int64Array>().unwrap();
-            Ok(GroupByScalar::Int64(array.value(row)))
-        }
-        DataType::Utf8 => {
-            let array = col.as_any().downcast_ref::<StringArray>().unwrap();
-            Ok(GroupByScalar::Utf8(Box::new(array.value(row).into())))
-        }
-        DataType::Boolean => {
-            let array = col.as_any().downcast_ref::<BooleanArray>().unwrap();
-            Ok(GroupByScalar::Boolean(array.value(row)))
-        }
-        DataType::Timestamp(TimeUnit::Millisecond, None) => {
-            let array = col
-                .as_any()
-                .downcast_ref::<TimestampMillisecondArray>()
-                .unwrap();
-            Ok(GroupByScalar::TimeMillisecond(array.value(row)))
-        }
-        DataType::Timestamp(TimeUnit::Microsecond, None) => {
-            let array = col
-                .as_any()
-                .downcast_ref::<TimestampMicrosecondArray>()
-                .unwrap();
-            Ok(GroupByScalar::TimeMicrosecond(array.value(row)))
-        }
-        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
-            let array = col
-                .as_any()
-                .downcast_ref::<TimestampNanosecondArray>()
-                .unwrap();
-            Ok(GroupByScalar::TimeNanosecond(array.value(row)))
-        }
-        DataType::Date32 => {
-            let array = col.as_any().downcast_ref::<Date32Array>().unwrap();
-            Ok(GroupByScalar::Date32(array.value(row)))
-        }
-        DataType::Dictionary(index_type, _) => match **index_type {
-            DataType::Int8 => dictionary_create_group_by_value::<Int8Type>(col, row),
-            DataType::Int16 => dictionary_create_group_by_value::<Int16Type>(col, row),
-            DataType::Int32 => dictionary_create_group_by_value::<Int32Type>(col, row),
-            DataType::Int64 => dictionary_create_group_by_value::<Int64Type>(col, row),
-            DataType::UInt8 => dictionary_create_group_by_value::<UInt8Type>(col, row),
-            DataType::UInt16 => dictionary_create_group_by_value::<UInt16Type>(col, row),
-            DataType::UInt32 => dictionary_create_group_by_value::<UInt32Type>(col, row),
-            DataType::UInt64 => dictionary_create_group_by_value::<UInt64Type>(col, row),
-            _ => Err(DataFusionError::NotImplemented(format!(
-                "Unsupported GROUP BY type (dictionary index type not supported) {}",
-                col.data_type(),
-            ))),
-        },
-        _ => Err(DataFusionError::NotImplemented(format!(
-            "Unsupported GROUP BY type {}",
-            col.data_type(),
-        ))),
-    }
-}
-
-/// Extract the values in `group_by_keys` arrow arrays into the target vector
-/// as GroupByScalar values
-pub(crate) fn create_group_by_values(
-    group_by_keys: &[ArrayRef],
-    row: usize,
-    vec: &mut Box<[GroupByScalar]>,
-) -> Result<()> {
-    for (i, col) in group_by_keys.iter().enumerate() {
-        vec[i] = create_group_by_value(col, row)?
-    }
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
 
@@ -1153,6 +1194,7 @@ mod tests {
     use crate::physical_plan::expressions::{col, Avg};
     use crate::{assert_batches_sorted_eq, physical_plan::common};
 
+    use crate::physical_plan::memory::MemoryExec;
     use crate::physical_plan::merge::MergeExec;
 
     /// some mock data to aggregates
@@ -1302,6 +1344,11 @@ mod tests {
             }
             Ok(Box::pin(stream))
         }
+
+        fn statistics(&self) -> Statistics {
+            let (_, batches) = some_data();
+            common::compute_record_batch_statistics(&[batches], &self.schema(), None)
+        }
     }
 
     /// A stream using the demo data. If inited as new, it will first yield to runtime before returning records
@@ -1361,4 +1408,158 @@ mod tests {
 
         check_aggregates(input).await
     }
+
+    #[tokio::test]
+    async fn aggregate_spill() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt32, true),
+            Field::new("b", DataType::Float64, false),
+        ]));
+        let batch = |a: Vec<Option<u32>>, b: Vec<f64>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(UInt32Array::from(a)),
+                    Arc::new(Float64Array::from(b)),
+                ],
+            )
+        };
+        let input = Arc::new(MemoryExec::try_new(
+            &[vec![
+                batch(vec![Some(4), Some(2), None], vec![1.0, 2.0, 3.0])?,
+                batch(vec![Some(3), Some(2), Some(4)], vec![4.0, 5.0, 6.0])?,
+                batch(vec![None, Some(1), Some(3)], vec![7.0, 8.0, 9.0])?,
+            ]],
+            schema.clone(),
+            None,
+        )?);
+
+        let groups: Vec<(Arc<dyn PhysicalExpr>, String)> =
+            vec![(col("a"), "a".to_string())];
+        let aggregates: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Avg::new(
+            col("b"),
+            "AVG(b)".to_string(),
+            DataType::Float64,
+        ))];
+
+        // spill the groups of every batch
+        let partial_aggregate = Arc::new(
+            HashAggregateExec::try_new(
+                AggregateMode::Partial,
+                groups.clone(),
+                aggregates.clone(),
+                input,
+                schema.clone(),
+            )?
+            .with_memory_limit(Some(1)),
+        );
+        let final_aggregate = Arc::new(
+            HashAggregateExec::try_new(
+                AggregateMode::Final,
+                vec![(col("a"), "a".to_string())],
+                aggregates,
+                partial_aggregate,
+                schema,
+            )?
+            .with_memory_limit(Some(1)),
+        );
+
+        let result = common::collect(final_aggregate.execute(0).await?).await?;
+
+        let expected = vec![
+            "+---+--------+",
+            "| a | AVG(b) |",
+            "+---+--------+",
+            "|   | 5      |",
+            "| 1 | 8      |",
+            "| 2 | 3.5    |",
+            "| 3 | 6.5    |",
+            "| 4 | 3.5    |",
+            "+---+--------+",
+        ];
+        assert_batches_sorted_eq!(expected, &result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn memory_budget_shared_by_partitions() {
+        let memory = MemoryBudget {
+            limit: Some(100),
+            used: Arc::new(AtomicUsize::new(0)),
+            partitions: 2,
+        };
+        // the groups of both partitions are within the limit
+        assert!(!memory.grow(0, 60));
+        // the partition holding less than its share of the limit keeps its groups
+        assert!(!memory.grow(0, 45));
+        // the partition holding more than its share spills
+        assert!(memory.grow(60, 70));
+        memory.release(70);
+        assert_eq!(memory.used.load(Ordering::SeqCst), 45);
+    }
+
+    #[tokio::test]
+    async fn aggregate_grouping_sets() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::UInt32, false),
+            Field::new("c", DataType::Float64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(vec![1, 1, 1, 2])),
+                Arc::new(UInt32Array::from(vec![1, 2, 2, 1])),
+                Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0, 6.0])),
+            ],
+        )?;
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None)?);
+
+        let groups: Vec<(Arc<dyn PhysicalExpr>, String)> =
+            vec![(col("a"), "a".to_string()), (col("b"), "b".to_string())];
+        let aggregates: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Avg::new(
+            col("c"),
+            "AVG(c)".to_string(),
+            DataType::Float64,
+        ))];
+
+        // ROLLUP (a, b)
+        let partial_aggregate = Arc::new(HashAggregateExec::try_new_with_grouping_sets(
+            AggregateMode::Partial,
+            groups,
+            aggregates.clone(),
+            Some(vec![vec![0, 1], vec![0], vec![]]),
+            input,
+            schema.clone(),
+        )?);
+        let final_aggregate = Arc::new(HashAggregateExec::try_new(
+            AggregateMode::Final,
+            ["a", "b", GROUPING_ID_COLUMN]
+                .iter()
+                .map(|name| (col(name), name.to_string()))
+                .collect(),
+            aggregates,
+            partial_aggregate,
+            schema,
+        )?);
+
+        let result = common::collect(final_aggregate.execute(0).await?).await?;
+
+        let expected = vec![
+            "+---+---+---------------+--------+",
+            "| a | b | __grouping_id | AVG(c) |",
+            "+---+---+---------------+--------+",
+            "| 1 | 1 | 0             | 1      |",
+            "| 1 | 2 | 0             | 2.5    |",
+            "| 2 | 1 | 0             | 6      |",
+            "| 1 |   | 1             | 2      |",
+            "| 2 |   | 1             | 6      |",
+            "|   |   | 3             | 3      |",
+            "+---+---+---------------+--------+",
+        ];
+        assert_batches_sorted_eq!(expected, &result);
+
+        Ok(())
+    }
 }
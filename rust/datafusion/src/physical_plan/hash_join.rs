@@ -23,20 +23,20 @@ use ahash::RandomState;
 
 use arrow::{
     array::{
-        ArrayData, ArrayRef, BooleanArray, LargeStringArray, PrimitiveArray,
+        new_null_array, ArrayData, ArrayRef, BooleanArray, DecimalArray, PrimitiveArray,
         TimestampMicrosecondArray, TimestampNanosecondArray, UInt32BufferBuilder,
         UInt32Builder, UInt64BufferBuilder, UInt64Builder,
     },
-    compute,
+    compute::{self, kernels::zip::zip},
     datatypes::{TimeUnit, UInt32Type, UInt64Type},
 };
 use smallvec::{smallvec, SmallVec};
+use std::any::Any;
 use std::time::Instant;
-use std::{any::Any, collections::HashSet};
 use std::{hash::Hasher, sync::Arc};
 
 use async_trait::async_trait;
-use futures::{Stream, StreamExt, TryStreamExt};
+use futures::{ready, Stream, StreamExt, TryStreamExt};
 use hashbrown::HashMap;
 use tokio::sync::Mutex;
 
@@ -53,12 +53,19 @@ use arrow::array::{
 
 use super::expressions::col;
 use super::{
-    hash_utils::{build_join_schema, check_join_is_valid, JoinOn, JoinType},
+    hash_utils::{
+        build_join_schema, check_join_is_valid, estimate_join_statistics, JoinOn,
+        JoinType,
+    },
     merge::MergeExec,
+    row_format::Rows,
 };
+use crate::datasource::datasource::Statistics;
 use crate::error::{DataFusionError, Result};
 
-use super::{ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream};
+use super::{
+    ExecutionPlan, Partitioning, RecordBatchStream, SQLMetric, SendableRecordBatchStream,
+};
 use crate::physical_plan::coalesce_batches::concat_batches;
 use log::debug;
 
@@ -66,7 +73,24 @@ use log::debug;
 // E.g. 1 -> [3, 6, 8] indicates that the column values map to rows 3, 6 and 8 for hash value 1
 // As the key is a hash value, we need to check possible hash collisions in the probe stage
 type JoinHashMap = HashMap<u64, SmallVec<[u64; 1]>, IdHashBuilder>;
-type JoinLeftData = Arc<(JoinHashMap, RecordBatch)>;
+// The hash map of the left side, with its rows and the rows of its join keys in the row
+// format, against which the join keys of the right side are compared
+type JoinLeftData = Arc<(JoinHashMap, RecordBatch, Rows)>;
+// The rows of a left side shared by all the right partitions that have been matched by
+// the right partitions joined so far, with the number of right partitions still joining
+type SharedLeftVisits = Arc<std::sync::Mutex<(Vec<bool>, usize)>>;
+
+/// The left side collected by a [`PartitionMode::CollectLeft`] join, shared by the
+/// right partitions of one execution of the join
+#[derive(Debug)]
+struct SharedLeftSide {
+    /// The hash map and rows of the left side
+    left_data: JoinLeftData,
+    /// The rows of the left side visited by the right partitions
+    visits: SharedLeftVisits,
+    /// Number of right partitions that started joining with this left side
+    started: usize,
+}
 
 /// join execution plan executes partitions in parallel and combines them into a set of
 /// partitions.
@@ -82,12 +106,40 @@ pub struct HashJoinExec {
     join_type: JoinType,
     /// The schema once the join is applied
     schema: SchemaRef,
-    /// Build-side
-    build_side: Arc<Mutex<Option<JoinLeftData>>>,
+    /// Build-side, collected once for all the right partitions of an execution
+    build_side: Arc<Mutex<Option<SharedLeftSide>>>,
     /// Shares the `RandomState` for the hashing algorithm
     random_state: RandomState,
     /// Partitioning mode to use
     mode: PartitionMode,
+    /// Whether null values of the join columns match each other
+    null_equals_null: bool,
+    /// Metrics recorded by the executions of the join
+    metrics: JoinMetrics,
+}
+
+/// Metrics recorded by [HashJoinExec], shared by all its partitions
+#[derive(Debug, Clone)]
+struct JoinMetrics {
+    /// Number of rows of the build side
+    build_rows: Arc<SQLMetric>,
+    /// Number of rows of the probe side
+    input_rows: Arc<SQLMetric>,
+    /// Number of rows produced
+    output_rows: Arc<SQLMetric>,
+    /// Time spent building the hash table and joining the probe side
+    elapsed_compute: Arc<SQLMetric>,
+}
+
+impl JoinMetrics {
+    fn new() -> Self {
+        Self {
+            build_rows: SQLMetric::counter(),
+            input_rows: SQLMetric::counter(),
+            output_rows: SQLMetric::counter(),
+            elapsed_compute: SQLMetric::time_nanos(),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -100,11 +152,11 @@ pub enum PartitionMode {
 }
 
 /// Information about the index and placement (left or right) of the columns
-struct ColumnIndex {
+pub(crate) struct ColumnIndex {
     /// Index of the column
-    index: usize,
+    pub(crate) index: usize,
     /// Whether the column is at the left or right side
-    is_left: bool,
+    pub(crate) is_left: bool,
 }
 
 impl HashJoinExec {
@@ -117,6 +169,7 @@ impl HashJoinExec {
         on: &JoinOn,
         join_type: &JoinType,
         partition_mode: PartitionMode,
+        null_equals_null: bool,
     ) -> Result<Self> {
         let left_schema = left.schema();
         let right_schema = right.schema();
@@ -145,6 +198,8 @@ impl HashJoinExec {
             build_side: Arc::new(Mutex::new(None)),
             random_state,
             mode: partition_mode,
+            null_equals_null,
+            metrics: JoinMetrics::new(),
         })
     }
 
@@ -168,35 +223,71 @@ impl HashJoinExec {
         &self.join_type
     }
 
-    /// Calculates column indices and left/right placement on input / output schemas and jointype
-    fn column_indices_from_schema(&self) -> ArrowResult<Vec<ColumnIndex>> {
-        let (primary_is_left, primary_schema, secondary_schema) = match self.join_type {
-            JoinType::Inner | JoinType::Left => {
-                (true, self.left.schema(), self.right.schema())
-            }
-            JoinType::Right => (false, self.right.schema(), self.left.schema()),
-        };
-        let mut column_indices = Vec::with_capacity(self.schema.fields().len());
-        for field in self.schema.fields() {
-            let (is_primary, index) = match primary_schema.index_of(field.name()) {
-                    Ok(i) => Ok((true, i)),
-                    Err(_) => {
-                        match secondary_schema.index_of(field.name()) {
-                            Ok(i) => Ok((false, i)),
-                            _ => Err(DataFusionError::Internal(
-                                format!("During execution, the column {} was not found in neither the left or right side of the join", field.name()).to_string()
-                            ))
-                        }
-                    }
-                }.map_err(DataFusionError::into_arrow_external_error)?;
+    /// Whether null values of the join columns match each other
+    pub fn null_equals_null(&self) -> bool {
+        self.null_equals_null
+    }
 
-            let is_left =
-                is_primary && primary_is_left || !is_primary && !primary_is_left;
-            column_indices.push(ColumnIndex { index, is_left });
-        }
+    /// Whether the join produces rows of the left side once all the right
+    /// side has been visited, while the left side is shared by all the right
+    /// partitions. The right partition that finishes last produces them.
+    fn has_shared_left_side_state(&self) -> bool {
+        self.join_type.visits_left_side() && self.mode == PartitionMode::CollectLeft
+    }
+}
+
+/// Output columns of the join keys that have the same name on both sides,
+/// with the index of the right column, for full joins
+pub(crate) fn coalesced_keys(
+    on: &JoinOn,
+    join_type: JoinType,
+    schema: &Schema,
+    right_schema: &Schema,
+) -> ArrowResult<Vec<(usize, usize)>> {
+    match join_type {
+        JoinType::Full => on
+            .iter()
+            .filter(|(l, r)| l == r)
+            .map(|(l, r)| Ok((schema.index_of(l)?, right_schema.index_of(r)?)))
+            .collect(),
+        _ => Ok(vec![]),
+    }
+}
+
+/// Calculates column indices and left/right placement on input / output schemas and jointype
+pub(crate) fn column_indices_from_schema(
+    join_type: JoinType,
+    left_schema: &SchemaRef,
+    right_schema: &SchemaRef,
+    schema: &Schema,
+) -> ArrowResult<Vec<ColumnIndex>> {
+    let (primary_is_left, primary_schema, secondary_schema) = match join_type {
+        JoinType::Inner
+        | JoinType::Left
+        | JoinType::Full
+        | JoinType::Semi
+        | JoinType::Anti => (true, left_schema, right_schema),
+        JoinType::Right => (false, right_schema, left_schema),
+    };
+    let mut column_indices = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+        let (is_primary, index) = match primary_schema.index_of(field.name()) {
+                Ok(i) => Ok((true, i)),
+                Err(_) => {
+                    match secondary_schema.index_of(field.name()) {
+                        Ok(i) => Ok((false, i)),
+                        _ => Err(DataFusionError::Internal(
+                            format!("During execution, the column {} was not found in neither the left or right side of the join", field.name()).to_string()
+                        ))
+                    }
+                }
+            }.map_err(DataFusionError::into_arrow_external_error)?;
 
-        Ok(column_indices)
+        let is_left = is_primary && primary_is_left || !is_primary && !primary_is_left;
+        column_indices.push(ColumnIndex { index, is_left });
     }
+
+    Ok(column_indices)
 }
 
 #[async_trait]
@@ -224,6 +315,7 @@ impl ExecutionPlan for HashJoinExec {
                 &self.on,
                 &self.join_type,
                 self.mode,
+                self.null_equals_null,
             )?)),
             _ => Err(DataFusionError::Internal(
                 "HashJoinExec wrong number of children".to_string(),
@@ -232,19 +324,37 @@ impl ExecutionPlan for HashJoinExec {
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        self.right.output_partitioning()
+        let right_partitioning = self.right.output_partitioning();
+        match self.join_type {
+            // semi and anti joins only produce columns of the left side
+            JoinType::Semi | JoinType::Anti => {
+                Partitioning::UnknownPartitioning(right_partitioning.partition_count())
+            }
+            _ => right_partitioning,
+        }
     }
 
     async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
         let on_left = self.on.iter().map(|on| on.0.clone()).collect::<Vec<_>>();
-        // we only want to compute the build side once for PartitionMode::CollectLeft
-        let left_data = {
+        // we only want to compute the build side once per execution for
+        // PartitionMode::CollectLeft
+        let (left_data, left_visits) = {
             match self.mode {
                 PartitionMode::CollectLeft => {
                     let mut build_side = self.build_side.lock().await;
 
-                    match build_side.as_ref() {
-                        Some(stream) => stream.clone(),
+                    // every execution of all the right partitions collects the left
+                    // side again, with its own visited rows
+                    let partitions = self.right.output_partitioning().partition_count();
+                    if matches!(&*build_side, Some(side) if side.started >= partitions) {
+                        *build_side = None;
+                    }
+
+                    match build_side.as_mut() {
+                        Some(side) => {
+                            side.started += 1;
+                            (side.left_data.clone(), Some(side.visits.clone()))
+                        }
                         None => {
                             let start = Instant::now();
 
@@ -252,45 +362,50 @@ impl ExecutionPlan for HashJoinExec {
                             let merge = MergeExec::new(self.left.clone());
                             let stream = merge.execute(0).await?;
 
-                            // This operation performs 2 steps at once:
+                            // This operation performs 3 steps at once:
                             // 1. creates a [JoinHashMap] of all batches from the stream
                             // 2. stores the batches in a vector.
+                            // 3. encodes the join keys of the batches in the row format
                             let initial = (
                                 JoinHashMap::with_hasher(IdHashBuilder {}),
                                 Vec::new(),
-                                0,
-                                Vec::new(),
+                                Rows::default(),
                             );
-                            let (hashmap, batches, num_rows, _) = stream
+                            let (hashmap, batches, rows) = stream
                                 .try_fold(initial, |mut acc, batch| async {
-                                    let hash = &mut acc.0;
-                                    let values = &mut acc.1;
-                                    let offset = acc.2;
-                                    acc.3.clear();
-                                    acc.3.resize(batch.num_rows(), 0);
+                                    let start = Instant::now();
                                     update_hash(
                                         &on_left,
                                         &batch,
-                                        hash,
-                                        offset,
+                                        &mut acc.0,
+                                        &mut acc.2,
                                         &self.random_state,
-                                        &mut acc.3,
                                     )
-                                    .unwrap();
-                                    acc.2 += batch.num_rows();
-                                    values.push(batch);
+                                    .map_err(
+                                        DataFusionError::into_arrow_external_error,
+                                    )?;
+                                    self.metrics.elapsed_compute.add_elapsed(start);
+                                    self.metrics.build_rows.add(batch.num_rows());
+                                    acc.1.push(batch);
                                     Ok(acc)
                                 })
                                 .await?;
+                            let num_rows = rows.num_rows();
 
                             // Merge all batches into a single batch, so we
                             // can directly index into the arrays
                             let single_batch =
                                 concat_batches(&self.left.schema(), &batches, num_rows)?;
 
-                            let left_side = Arc::new((hashmap, single_batch));
+                            let left_side = Arc::new((hashmap, single_batch, rows));
+                            let visits =
+                                Arc::new(std::sync::Mutex::new((vec![], partitions)));
 
-                            *build_side = Some(left_side.clone());
+                            *build_side = Some(SharedLeftSide {
+                                left_data: left_side.clone(),
+                                visits: visits.clone(),
+                                started: 1,
+                            });
 
                             debug!(
                             "Built build-side of hash join containing {} rows in {} ms",
@@ -298,7 +413,7 @@ impl ExecutionPlan for HashJoinExec {
                             start.elapsed().as_millis()
                         );
 
-                            left_side
+                            (left_side, Some(visits))
                         }
                     }
                 }
@@ -308,43 +423,40 @@ impl ExecutionPlan for HashJoinExec {
                     // Load 1 partition of left side in memory
                     let stream = self.left.execute(partition).await?;
 
-                    // This operation performs 2 steps at once:
+                    // This operation performs 3 steps at once:
                     // 1. creates a [JoinHashMap] of all batches from the stream
                     // 2. stores the batches in a vector.
+                    // 3. encodes the join keys of the batches in the row format
                     let initial = (
                         JoinHashMap::with_hasher(IdHashBuilder {}),
                         Vec::new(),
-                        0,
-                        Vec::new(),
+                        Rows::default(),
                     );
-                    let (hashmap, batches, num_rows, _) = stream
+                    let (hashmap, batches, rows) = stream
                         .try_fold(initial, |mut acc, batch| async {
-                            let hash = &mut acc.0;
-                            let values = &mut acc.1;
-                            let offset = acc.2;
-                            acc.3.clear();
-                            acc.3.resize(batch.num_rows(), 0);
+                            let start = Instant::now();
                             update_hash(
                                 &on_left,
                                 &batch,
-                                hash,
-                                offset,
+                                &mut acc.0,
+                                &mut acc.2,
                                 &self.random_state,
-                                &mut acc.3,
                             )
-                            .unwrap();
-                            acc.2 += batch.num_rows();
-                            values.push(batch);
+                            .map_err(DataFusionError::into_arrow_external_error)?;
+                            self.metrics.elapsed_compute.add_elapsed(start);
+                            self.metrics.build_rows.add(batch.num_rows());
+                            acc.1.push(batch);
                             Ok(acc)
                         })
                         .await?;
+                    let num_rows = rows.num_rows();
 
                     // Merge all batches into a single batch, so we
                     // can directly index into the arrays
                     let single_batch =
                         concat_batches(&self.left.schema(), &batches, num_rows)?;
 
-                    let left_side = Arc::new((hashmap, single_batch));
+                    let left_side = Arc::new((hashmap, single_batch, rows));
 
                     debug!(
                         "Built build-side {} of hash join containing {} rows in {} ms",
@@ -353,7 +465,7 @@ impl ExecutionPlan for HashJoinExec {
                         start.elapsed().as_millis()
                     );
 
-                    left_side
+                    (left_side, None)
                 }
             }
         };
@@ -362,36 +474,83 @@ impl ExecutionPlan for HashJoinExec {
         // over the right that uses this information to issue new batches.
 
         let stream = self.right.execute(partition).await?;
+        // the rows of the shared left side must be produced once, after all
+        // the right partitions have been visited
+        let shared_visits = if self.has_shared_left_side_state() {
+            left_visits
+        } else {
+            None
+        };
+        let visited_left_side = if self.join_type.visits_left_side() {
+            vec![false; left_data.1.num_rows()]
+        } else {
+            vec![]
+        };
         let on_right = self.on.iter().map(|on| on.1.clone()).collect::<Vec<_>>();
 
-        let column_indices = self.column_indices_from_schema()?;
+        let column_indices = column_indices_from_schema(
+            self.join_type,
+            &self.left.schema(),
+            &self.right.schema(),
+            &self.schema,
+        )?;
+        let coalesced_keys =
+            coalesced_keys(&self.on, self.join_type, &self.schema, &self.right.schema())?;
         Ok(Box::pin(HashJoinStream {
             schema: self.schema.clone(),
-            on_left,
             on_right,
             join_type: self.join_type,
             left_data,
             right: stream,
             column_indices,
+            coalesced_keys,
             num_input_batches: 0,
             num_input_rows: 0,
             num_output_batches: 0,
             num_output_rows: 0,
             join_time: 0,
             random_state: self.random_state.clone(),
+            null_equals_null: self.null_equals_null,
+            visited_left_side,
+            shared_visits,
+            is_exhausted: false,
+            metrics: self.metrics.clone(),
         }))
     }
+
+    fn metrics(&self) -> std::collections::HashMap<String, SQLMetric> {
+        let mut metrics = std::collections::HashMap::new();
+        metrics.insert("build_rows".to_owned(), (*self.metrics.build_rows).clone());
+        metrics.insert("input_rows".to_owned(), (*self.metrics.input_rows).clone());
+        metrics.insert(
+            "output_rows".to_owned(),
+            (*self.metrics.output_rows).clone(),
+        );
+        metrics.insert(
+            "elapsed_compute".to_owned(),
+            (*self.metrics.elapsed_compute).clone(),
+        );
+        metrics
+    }
+
+    fn statistics(&self) -> Statistics {
+        estimate_join_statistics(
+            (&self.left.schema(), self.left.statistics()),
+            (&self.right.schema(), self.right.statistics()),
+            &self.on,
+            &self.join_type,
+        )
+    }
 }
 
 /// Updates `hash` with new entries from [RecordBatch] evaluated against the expressions `on`,
-/// assuming that the [RecordBatch] corresponds to the `index`th
+/// appending the evaluated keys to `rows`, which holds the keys of the previous batches
 fn update_hash(
     on: &[String],
     batch: &RecordBatch,
     hash: &mut JoinHashMap,
-    offset: usize,
+    rows: &mut Rows,
     random_state: &RandomState,
-    hashes_buffer: &mut Vec<u64>,
 ) -> Result<()> {
     // evaluate the keys
     let keys_values = on
@@ -399,15 +558,16 @@ fn update_hash(
         .map(|name| Ok(col(name).evaluate(batch)?.into_array(batch.num_rows())))
         .collect::<Result<Vec<_>>>()?;
 
-    // update the hash map
-    let hash_values = create_hashes(&keys_values, &random_state, hashes_buffer)?;
+    let offset = rows.num_rows();
+    rows.append(&keys_values)?;
 
     // insert hashes to key of the hashmap
-    for (row, hash_value) in hash_values.iter().enumerate() {
+    for row in offset..rows.num_rows() {
+        let hash_value = hash_row(rows.row(row), random_state);
         hash.raw_entry_mut()
-            .from_key_hashed_nocheck(*hash_value, hash_value)
-            .and_modify(|_, v| v.push((row + offset) as u64))
-            .or_insert_with(|| (*hash_value, smallvec![(row + offset) as u64]));
+            .from_key_hashed_nocheck(hash_value, &hash_value)
+            .and_modify(|_, v| v.push(row as u64))
+            .or_insert_with(|| (hash_value, smallvec![row as u64]));
     }
     Ok(())
 }
@@ -416,8 +576,6 @@ fn update_hash(
 struct HashJoinStream {
     /// Input schema
     schema: Arc<Schema>,
-    /// columns from the left
-    on_left: Vec<String>,
     /// columns from the right used to compute the hash
     on_right: Vec<String>,
     /// type of the join
@@ -428,6 +586,9 @@ struct HashJoinStream {
     right: SendableRecordBatchStream,
     /// Information of index and left / right placement of columns
     column_indices: Vec<ColumnIndex>,
+    /// Output columns of the join keys that are taken from the right side
+    /// when the left side is null, with the index of the right column
+    coalesced_keys: Vec<(usize, usize)>,
     /// number of input batches
     num_input_batches: usize,
     /// number of input rows
@@ -440,6 +601,19 @@ struct HashJoinStream {
     join_time: usize,
     /// Random state used for hashing initialization
     random_state: RandomState,
+    /// Whether null values of the join columns match each other
+    null_equals_null: bool,
+    /// Keeps track of the left side rows that have been matched, for the
+    /// joins that produce rows of the left side once all the right side has
+    /// been visited
+    visited_left_side: Vec<bool>,
+    /// Visited rows of a left side shared by all the right partitions, merged
+    /// into by each partition once its right side has been visited
+    shared_visits: Option<SharedLeftVisits>,
+    /// True once the rows of the left side have been produced
+    is_exhausted: bool,
+    /// Metrics recorded by the join
+    metrics: JoinMetrics,
 }
 
 impl RecordBatchStream for HashJoinStream {
@@ -453,13 +627,14 @@ impl RecordBatchStream for HashJoinStream {
 /// # Error
 /// This function errors when:
 /// *
-fn build_batch_from_indices(
+pub(crate) fn build_batch_from_indices(
     schema: &Schema,
     left: &RecordBatch,
     right: &RecordBatch,
-    left_indices: UInt64Array,
-    right_indices: UInt32Array,
+    left_indices: &UInt64Array,
+    right_indices: &UInt32Array,
     column_indices: &[ColumnIndex],
+    coalesced_keys: &[(usize, usize)],
 ) -> ArrowResult<RecordBatch> {
     // build the columns of the new [RecordBatch]:
     // 1. pick whether the column is from the left or right
@@ -469,13 +644,24 @@ fn build_batch_from_indices(
     for column_index in column_indices {
         let array = if column_index.is_left {
             let array = left.column(column_index.index);
-            compute::take(array.as_ref(), &left_indices, None)?
+            compute::take(array.as_ref(), left_indices, None)?
         } else {
             let array = right.column(column_index.index);
-            compute::take(array.as_ref(), &right_indices, None)?
+            compute::take(array.as_ref(), right_indices, None)?
         };
         columns.push(array);
     }
+
+    // the keys shared by both sides are taken from the right for the rows
+    // without a left side
+    if !coalesced_keys.is_empty() && left_indices.null_count() > 0 {
+        let left_is_null = compute::is_null(left_indices)?;
+        for (i, right_index) in coalesced_keys {
+            let array = right.column(*right_index);
+            let right_keys = compute::take(array.as_ref(), right_indices, None)?;
+            columns[*i] = zip(&left_is_null, right_keys.as_ref(), columns[*i].as_ref())?;
+        }
+    }
     RecordBatch::try_new(Arc::new(schema.clone()), columns)
 }
 
@@ -483,31 +669,66 @@ fn build_batch_from_indices(
 fn build_batch(
     batch: &RecordBatch,
     left_data: &JoinLeftData,
-    on_left: &[String],
     on_right: &[String],
     join_type: JoinType,
     schema: &Schema,
     column_indices: &[ColumnIndex],
+    coalesced_keys: &[(usize, usize)],
     random_state: &RandomState,
-) -> ArrowResult<RecordBatch> {
+    null_equals_null: bool,
+) -> ArrowResult<(RecordBatch, UInt64Array)> {
     let (left_indices, right_indices) = build_join_indexes(
         &left_data,
         &batch,
         join_type,
-        on_left,
         on_right,
         random_state,
+        null_equals_null,
     )
-    .unwrap();
+    .map_err(DataFusionError::into_arrow_external_error)?;
 
     build_batch_from_indices(
         schema,
         &left_data.1,
         batch,
-        left_indices,
-        right_indices,
+        &left_indices,
+        &right_indices,
         column_indices,
+        coalesced_keys,
     )
+    .map(|batch| (batch, left_indices))
+}
+
+/// Returns a [RecordBatch] of the rows of the left side whose visited state
+/// is `visited`, with nulls for the columns of the right side.
+fn produce_from_left_side(
+    visited_left_side: &[bool],
+    visited: bool,
+    schema: &Schema,
+    column_indices: &[ColumnIndex],
+    left_data: &JoinLeftData,
+) -> ArrowResult<RecordBatch> {
+    let indices = visited_left_side
+        .iter()
+        .enumerate()
+        .filter(|(_, &v)| v == visited)
+        .map(|(i, _)| Some(i as u64))
+        .collect::<UInt64Array>();
+
+    let num_rows = indices.len();
+    let columns = column_indices
+        .iter()
+        .zip(schema.fields())
+        .map(|(column_index, field)| {
+            if column_index.is_left {
+                let array = left_data.1.column(column_index.index);
+                compute::take(array.as_ref(), &indices, None)
+            } else {
+                Ok(new_null_array(field.data_type(), num_rows))
+            }
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+    RecordBatch::try_new(Arc::new(schema.clone()), columns)
 }
 
 /// returns a vector with (index from left, index from right).
@@ -541,43 +762,51 @@ fn build_join_indexes(
     left_data: &JoinLeftData,
     right: &RecordBatch,
     join_type: JoinType,
-    left_on: &[String],
     right_on: &[String],
     random_state: &RandomState,
+    null_equals_null: bool,
 ) -> Result<(UInt64Array, UInt32Array)> {
     let keys_values = right_on
         .iter()
         .map(|name| Ok(col(name).evaluate(right)?.into_array(right.num_rows())))
         .collect::<Result<Vec<_>>>()?;
-    let left_join_values = left_on
-        .iter()
-        .map(|name| {
-            Ok(col(name)
-                .evaluate(&left_data.1)?
-                .into_array(left_data.1.num_rows()))
-        })
-        .collect::<Result<Vec<_>>>()?;
-    let hashes_buffer = &mut vec![0; keys_values[0].len()];
-    let hash_values = create_hashes(&keys_values, &random_state, hashes_buffer)?;
+    let rows = Rows::try_new(&keys_values)?;
     let left = &left_data.0;
+    let left_rows = &left_data.2;
+
+    // Rows whose keys have a null value never match, unless `null_equals_null` is
+    // true. Equal rows have null values at the same keys, so that checking the
+    // keys of the right row is enough
+    let is_matchable = |row: usize| {
+        null_equals_null
+            || keys_values.iter().all(|array| {
+                !matches!(array.data_type(), DataType::Null) && array.is_valid(row)
+            })
+    };
 
     match join_type {
-        JoinType::Inner => {
+        // the rows of the left side without a match, and those of semi and anti
+        // joins, are only known once all the right batches have been visited,
+        // see `HashJoinStream`
+        JoinType::Inner | JoinType::Left | JoinType::Semi | JoinType::Anti => {
             // Using a buffer builder to avoid slower normal builder
             let mut left_indices = UInt64BufferBuilder::new(0);
             let mut right_indices = UInt32BufferBuilder::new(0);
 
             // Visit all of the right rows
-            for (row, hash_value) in hash_values.iter().enumerate() {
+            for (row, key) in rows.iter().enumerate() {
+                if !is_matchable(row) {
+                    continue;
+                }
                 // Get the hash and find it in the build index
 
                 // For every item on the left and right we check if it matches
                 // This possibly contains rows with hash collisions,
                 // So we have to check here whether rows are equal or not
-                if let Some(indices) = left.get(hash_value) {
+                if let Some(indices) = left.get(&hash_row(key, random_state)) {
                     for &i in indices {
                         // Check hash collisions
-                        if equal_rows(i as usize, row, &left_join_values, &keys_values)? {
+                        if left_rows.row(i as usize) == key {
                             left_indices.append(i);
                             right_indices.append(row as u32);
                         }
@@ -598,71 +827,39 @@ fn build_join_indexes(
                 PrimitiveArray::<UInt32Type>::from(right),
             ))
         }
-        JoinType::Left => {
+        JoinType::Right | JoinType::Full => {
             let mut left_indices = UInt64Builder::new(0);
             let mut right_indices = UInt32Builder::new(0);
 
-            // Keep track of which item is visited in the build input
-            // TODO: this can be stored more efficiently with a marker
-            //       https://issues.apache.org/jira/browse/ARROW-11116
-            // TODO: Fix LEFT join with multiple right batches
-            //       https://issues.apache.org/jira/browse/ARROW-10971
-            let mut is_visited = HashSet::new();
-
-            // First visit all of the rows
-            for (row, hash_value) in hash_values.iter().enumerate() {
-                if let Some(indices) = left.get(hash_value) {
-                    for &i in indices {
-                        // Collision check
-                        if equal_rows(i as usize, row, &left_join_values, &keys_values)? {
-                            left_indices.append_value(i)?;
-                            right_indices.append_value(row as u32)?;
-                            is_visited.insert(i);
-                        }
-                    }
-                };
-            }
-            // Add the remaining left rows to the result set with None on the right side
-            for (_, indices) in left {
-                for i in indices.iter() {
-                    if !is_visited.contains(i) {
-                        left_indices.append_slice(&indices)?;
-                        right_indices.append_null()?;
-                    }
-                }
-            }
-            Ok((left_indices.finish(), right_indices.finish()))
-        }
-        JoinType::Right => {
-            let mut left_indices = UInt64Builder::new(0);
-            let mut right_indices = UInt32Builder::new(0);
-
-            for (row, hash_value) in hash_values.iter().enumerate() {
-                match left.get(hash_value) {
-                    Some(indices) => {
+            for (row, key) in rows.iter().enumerate() {
+                let mut is_matched = false;
+                if is_matchable(row) {
+                    if let Some(indices) = left.get(&hash_row(key, random_state)) {
                         for &i in indices {
-                            if equal_rows(
-                                i as usize,
-                                row,
-                                &left_join_values,
-                                &keys_values,
-                            )? {
+                            if left_rows.row(i as usize) == key {
                                 left_indices.append_value(i)?;
                                 right_indices.append_value(row as u32)?;
+                                is_matched = true;
                             }
                         }
                     }
-                    None => {
-                        // when no match, add the row with None for the left side
-                        left_indices.append_null()?;
-                        right_indices.append_value(row as u32)?;
-                    }
+                }
+                if !is_matched {
+                    // when no match, add the row with None for the left side
+                    left_indices.append_null()?;
+                    right_indices.append_value(row as u32)?;
                 }
             }
             Ok((left_indices.finish(), right_indices.finish()))
         }
     }
 }
+
+/// Hashes a row of join keys encoded in the row format
+fn hash_row(row: &[u8], random_state: &RandomState) -> u64 {
+    <[u8]>::get_hash(row, random_state)
+}
+
 use core::hash::BuildHasher;
 
 /// `Hasher` that returns the same `u64` value as a hash, to avoid re-hashing
@@ -702,58 +899,6 @@ fn combine_hashes(l: u64, r: u64) -> u64 {
     hash.wrapping_mul(37).wrapping_add(r)
 }
 
-macro_rules! equal_rows_elem {
-    ($array_type:ident, $l: ident, $r: ident, $left: ident, $right: ident) => {{
-        let left_array = $l.as_any().downcast_ref::<$array_type>().unwrap();
-        let right_array = $r.as_any().downcast_ref::<$array_type>().unwrap();
-
-        match (left_array.is_null($left), left_array.is_null($right)) {
-            (true, true) => true,
-            (false, false) => left_array.value($left) == right_array.value($right),
-            _ => false,
-        }
-    }};
-}
-
-/// Left and right row have equal values
-fn equal_rows(
-    left: usize,
-    right: usize,
-    left_arrays: &[ArrayRef],
-    right_arrays: &[ArrayRef],
-) -> Result<bool> {
-    let mut err = None;
-    let res = left_arrays
-        .iter()
-        .zip(right_arrays)
-        .all(|(l, r)| match l.data_type() {
-            DataType::Null => true,
-            DataType::Boolean => equal_rows_elem!(BooleanArray, l, r, left, right),
-            DataType::Int8 => equal_rows_elem!(Int8Array, l, r, left, right),
-            DataType::Int16 => equal_rows_elem!(Int16Array, l, r, left, right),
-            DataType::Int32 => equal_rows_elem!(Int32Array, l, r, left, right),
-            DataType::Int64 => equal_rows_elem!(Int64Array, l, r, left, right),
-            DataType::UInt8 => equal_rows_elem!(UInt8Array, l, r, left, right),
-            DataType::UInt16 => equal_rows_elem!(UInt16Array, l, r, left, right),
-            DataType::UInt32 => equal_rows_elem!(UInt32Array, l, r, left, right),
-            DataType::UInt64 => equal_rows_elem!(UInt64Array, l, r, left, right),
-            DataType::Timestamp(_, None) => {
-                equal_rows_elem!(Int64Array, l, r, left, right)
-            }
-            DataType::Utf8 => equal_rows_elem!(StringArray, l, r, left, right),
-            DataType::LargeUtf8 => equal_rows_elem!(LargeStringArray, l, r, left, right),
-            _ => {
-                // This is internal because we should have caught this before.
-                err = Some(Err(DataFusionError::Internal(
-                    "Unsupported data type in hasher".to_string(),
-                )));
-                false
-            }
-        });
-
-    err.unwrap_or(Ok(res))
-}
-
 macro_rules! hash_array {
     ($array_type:ident, $column: ident, $ty: ident, $hashes: ident, $random_state: ident) => {
         let array = $column.as_any().downcast_ref::<$array_type>().unwrap();
@@ -831,17 +976,94 @@ pub fn create_hashes<'a>(
             DataType::Utf8 => {
                 hash_array!(StringArray, col, str, hashes_buffer, random_state);
             }
+            DataType::Decimal(_, _) => {
+                hash_array!(DecimalArray, col, i128, hashes_buffer, random_state);
+            }
             _ => {
-                // This is internal because we should have caught this before.
-                return Err(DataFusionError::Internal(
-                    "Unsupported data type in hasher".to_string(),
-                ));
+                // the values of the other types are hashed through their row format
+                let rows = Rows::try_new(std::slice::from_ref(col))?;
+                for (row, hash) in rows.iter().zip(hashes_buffer.iter_mut()) {
+                    *hash = combine_hashes(hash_row(row, random_state), *hash);
+                }
             }
         }
     }
     Ok(hashes_buffer)
 }
 
+impl HashJoinStream {
+    /// Joins a batch of the right side with the left side
+    fn join_batch(&mut self, batch: &RecordBatch) -> ArrowResult<RecordBatch> {
+        let start = Instant::now();
+        let (output, left_indices) = build_batch(
+            batch,
+            &self.left_data,
+            &self.on_right,
+            self.join_type,
+            &self.schema,
+            &self.column_indices,
+            &self.coalesced_keys,
+            &self.random_state,
+            self.null_equals_null,
+        )?;
+        if self.join_type.visits_left_side() {
+            for i in left_indices.iter().flatten() {
+                self.visited_left_side[i as usize] = true;
+            }
+        }
+        self.num_input_batches += 1;
+        self.num_input_rows += batch.num_rows();
+        self.join_time += start.elapsed().as_millis() as usize;
+        self.metrics.input_rows.add(batch.num_rows());
+        self.metrics.elapsed_compute.add_elapsed(start);
+        Ok(output)
+    }
+
+    /// Merges the visited rows of the left side into the rows visited by the
+    /// other right partitions, and returns whether all the right partitions
+    /// have been visited, in which case this partition produces the left side
+    fn merge_shared_visits(&mut self) -> bool {
+        let shared = match &self.shared_visits {
+            Some(shared) => shared,
+            None => return true,
+        };
+        let mut shared = shared.lock().unwrap();
+        let (visited, remaining) = &mut *shared;
+        if visited.is_empty() {
+            *visited = std::mem::take(&mut self.visited_left_side);
+        } else {
+            visited
+                .iter_mut()
+                .zip(self.visited_left_side.iter())
+                .for_each(|(visited, &v)| *visited |= v);
+        }
+        *remaining = remaining.saturating_sub(1);
+        if *remaining == 0 {
+            self.visited_left_side = std::mem::take(visited);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Produces the rows of the left side once all the right side has been visited
+    fn produce_left_side(&mut self) -> ArrowResult<RecordBatch> {
+        let start = Instant::now();
+        // semi joins produce the matched rows, the other joins the unmatched ones
+        let visited = matches!(self.join_type, JoinType::Semi);
+        let output = produce_from_left_side(
+            &self.visited_left_side,
+            visited,
+            &self.schema,
+            &self.column_indices,
+            &self.left_data,
+        )?;
+        self.join_time += start.elapsed().as_millis() as usize;
+        self.metrics.elapsed_compute.add_elapsed(start);
+        Ok(output)
+    }
+}
+
 impl Stream for HashJoinStream {
     type Item = ArrowResult<RecordBatch>;
 
@@ -849,29 +1071,25 @@ impl Stream for HashJoinStream {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
-        self.right
-            .poll_next_unpin(cx)
-            .map(|maybe_batch| match maybe_batch {
-                Some(Ok(batch)) => {
-                    let start = Instant::now();
-                    let result = build_batch(
-                        &batch,
-                        &self.left_data,
-                        &self.on_left,
-                        &self.on_right,
-                        self.join_type,
-                        &self.schema,
-                        &self.column_indices,
-                        &self.random_state,
-                    );
-                    self.num_input_batches += 1;
-                    self.num_input_rows += batch.num_rows();
-                    if let Ok(ref batch) = result {
-                        self.join_time += start.elapsed().as_millis() as usize;
-                        self.num_output_batches += 1;
-                        self.num_output_rows += batch.num_rows();
+        loop {
+            let result = match ready!(self.right.poll_next_unpin(cx)) {
+                // semi and anti joins only produce rows once all the right
+                // side has been visited
+                Some(Ok(batch)) => match self.join_batch(&batch) {
+                    Ok(_)
+                        if matches!(self.join_type, JoinType::Semi | JoinType::Anti) =>
+                    {
+                        continue
+                    }
+                    result => Some(result),
+                },
+                None if self.join_type.visits_left_side() && !self.is_exhausted => {
+                    self.is_exhausted = true;
+                    if self.merge_shared_visits() {
+                        Some(self.produce_left_side())
+                    } else {
+                        None
                     }
-                    Some(result)
                 }
                 other => {
                     debug!(
@@ -885,7 +1103,14 @@ impl Stream for HashJoinStream {
                     );
                     other
                 }
-            })
+            };
+            if let Some(Ok(ref batch)) = result {
+                self.num_output_batches += 1;
+                self.num_output_rows += batch.num_rows();
+                self.metrics.output_rows.add(batch.num_rows());
+            }
+            return std::task::Poll::Ready(result);
+        }
     }
 }
 
@@ -898,6 +1123,7 @@ mod tests {
     };
 
     use super::*;
+    use arrow::datatypes::Field;
     use std::sync::Arc;
 
     fn build_table(
@@ -920,7 +1146,14 @@ mod tests {
             .iter()
             .map(|(l, r)| (l.to_string(), r.to_string()))
             .collect();
-        HashJoinExec::try_new(left, right, &on, join_type, PartitionMode::CollectLeft)
+        HashJoinExec::try_new(
+            left,
+            right,
+            &on,
+            join_type,
+            PartitionMode::CollectLeft,
+            false,
+        )
     }
 
     #[tokio::test]
@@ -1176,6 +1409,74 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn join_left_multi_partition() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 9]),
+        );
+        let batch1 =
+            build_table_i32(("a2", &vec![10]), ("b1", &vec![4]), ("c2", &vec![70]));
+        let batch2 = build_table_i32(
+            ("a2", &vec![20, 30]),
+            ("b1", &vec![5, 6]),
+            ("c2", &vec![80, 90]),
+        );
+        let empty = build_table_i32(("a2", &vec![]), ("b1", &vec![]), ("c2", &vec![]));
+        let schema = batch1.schema();
+        // the unmatched left rows are only known once all the right partitions are visited
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![batch1, batch2], vec![empty.clone()], vec![]],
+            schema.clone(),
+            None,
+        )?);
+        let on = &[("b1", "b1")];
+
+        let left_join = join(left.clone(), right, on, &JoinType::Left)?;
+        assert_eq!(left_join.output_partitioning().partition_count(), 3);
+
+        // only the last partition to finish produces the unmatched left rows
+        let mut batches = vec![];
+        for (partition, expected_rows) in [2, 0, 1].iter().enumerate() {
+            let stream = left_join.execute(partition).await?;
+            let partition_batches = common::collect(stream).await?;
+            let num_rows: usize = partition_batches.iter().map(|b| b.num_rows()).sum();
+            assert_eq!(num_rows, *expected_rows);
+            batches.extend(partition_batches);
+        }
+
+        let expected = vec![
+            "+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | c2 |",
+            "+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 70 |",
+            "| 2  | 5  | 8  | 20 | 80 |",
+            "| 3  | 7  | 9  |    |    |",
+            "+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // all the left rows are produced when the right side is empty
+        let right = Arc::new(MemoryExec::try_new(&[vec![empty]], schema, None)?);
+        let left_join = join(left, right, on, &JoinType::Left)?;
+        let stream = left_join.execute(0).await?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | c2 |",
+            "+----+----+----+----+----+",
+            "| 1  | 4  | 7  |    |    |",
+            "| 2  | 5  | 8  |    |    |",
+            "| 3  | 7  | 9  |    |    |",
+            "+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_right_one() -> Result<()> {
         let left = build_table(
@@ -1213,6 +1514,220 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn join_full_one() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = &[("b1", "b1")];
+
+        let join = join(left, right, on, &JoinType::Full)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "c2"]);
+
+        let stream = join.execute(0).await?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | c2 |",
+            "+----+----+----+----+----+",
+            "|    | 6  |    | 30 | 90 |",
+            "| 1  | 4  | 7  | 10 | 70 |",
+            "| 2  | 5  | 8  | 20 | 80 |",
+            "| 3  | 7  | 9  |    |    |",
+            "+----+----+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_semi() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 2, 3]),
+            ("b1", &vec![4, 5, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b1", &vec![4, 5, 6, 5]), // 5 is matched twice
+            ("c2", &vec![70, 80, 90, 100]),
+        );
+        let on = &[("b1", "b1")];
+
+        let join = join(left, right, on, &JoinType::Semi)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0).await?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 1  | 4  | 7  |",
+            "| 2  | 5  | 8  |",
+            "| 2  | 5  | 8  |",
+            "+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_semi_executed_twice() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 9]),
+        );
+        let batch1 =
+            build_table_i32(("a2", &vec![10]), ("b1", &vec![4]), ("c2", &vec![70]));
+        let batch2 =
+            build_table_i32(("a2", &vec![20]), ("b1", &vec![5]), ("c2", &vec![80]));
+        let schema = batch1.schema();
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![batch1], vec![batch2]],
+            schema,
+            None,
+        )?);
+        let on = &[("b1", "b1")];
+
+        let join = Arc::new(join(left, right, on, &JoinType::Semi)?);
+        assert!(matches!(
+            join.output_partitioning(),
+            Partitioning::UnknownPartitioning(2)
+        ));
+
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 1  | 4  | 7  |",
+            "| 2  | 5  | 8  |",
+            "+----+----+----+",
+        ];
+        // the visited rows of the left side are not carried over to the next execution
+        for _ in 0..2 {
+            let batches = crate::physical_plan::collect(join.clone()).await?;
+            assert_batches_sorted_eq!(expected, &batches);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_anti() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 2, 3]),
+            ("b1", &vec![4, 5, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b1", &vec![4, 5, 6, 5]),
+            ("c2", &vec![70, 80, 90, 100]),
+        );
+        let on = &[("b1", "b1")];
+
+        let join = join(left, right, on, &JoinType::Anti)?;
+
+        let columns = columns(&join.schema());
+        assert_eq!(columns, vec!["a1", "b1", "c1"]);
+
+        let stream = join.execute(0).await?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 3  | 7  | 9  |",
+            "+----+----+----+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_null_keys() -> Result<()> {
+        let table = |a: Vec<Option<i32>>, b: (&str, Vec<i32>)| {
+            let schema = Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new(b.0, DataType::Int32, false),
+            ]));
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(a)),
+                    Arc::new(Int32Array::from(b.1)),
+                ],
+            )
+            .unwrap();
+            Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+        };
+        let left = table(vec![Some(1), None], ("b", vec![1, 2]));
+        let right = table(vec![None, Some(1)], ("c", vec![3, 4]));
+
+        // null values never match
+        let join = join(left.clone(), right.clone(), &[("a", "a")], &JoinType::Inner)?;
+        let stream = join.execute(0).await?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+---+---+---+",
+            "| a | b | c |",
+            "+---+---+---+",
+            "| 1 | 1 | 4 |",
+            "+---+---+---+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // unless null values are considered equal
+        let on = vec![("a".to_string(), "a".to_string())];
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            &on,
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            true,
+        )?;
+        let stream = join.execute(0).await?;
+        let batches = common::collect(stream).await?;
+
+        let expected = vec![
+            "+---+---+---+",
+            "| a | b | c |",
+            "+---+---+---+",
+            "| 1 | 1 | 4 |",
+            "|   | 2 | 3 |",
+            "+---+---+---+",
+        ];
+
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
     #[test]
     fn join_with_hash_collision() -> Result<()> {
         let mut hashmap_left = HashMap::with_hasher(IdHashBuilder {});
@@ -1223,9 +1738,11 @@ mod tests {
         );
 
         let random_state = RandomState::new();
-        let hashes_buff = &mut vec![0; left.num_rows()];
-        let hashes =
-            create_hashes(&[left.columns()[0].clone()], &random_state, hashes_buff)?;
+        let rows = Rows::try_new(&[left.columns()[0].clone()])?;
+        let hashes = rows
+            .iter()
+            .map(|row| hash_row(row, &random_state))
+            .collect::<Vec<_>>();
 
         // Create hash collisions
         hashmap_left.insert(hashes[0], smallvec![0, 1]);
@@ -1237,14 +1754,14 @@ mod tests {
             ("c", &vec![30, 40]),
         );
 
-        let left_data = JoinLeftData::new((hashmap_left, left));
+        let left_data = JoinLeftData::new((hashmap_left, left, rows));
         let (l, r) = build_join_indexes(
             &left_data,
             &right,
             JoinType::Inner,
             &["a".to_string()],
-            &["a".to_string()],
             &random_state,
+            false,
         )?;
 
         let mut left_ids = UInt64Builder::new(0);
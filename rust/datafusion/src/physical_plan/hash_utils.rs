@@ -17,6 +17,7 @@
 
 //! Functionality used both on logical and physical plans
 
+use crate::datasource::datasource::{ColumnStatistics, Statistics};
 use crate::error::{DataFusionError, Result};
 use arrow::datatypes::{Field, Schema};
 use std::collections::HashSet;
@@ -30,6 +31,24 @@ pub enum JoinType {
     Left,
     /// Right
     Right,
+    /// Full
+    Full,
+    /// Semi
+    Semi,
+    /// Anti
+    Anti,
+}
+
+impl JoinType {
+    /// Whether the join produces rows of its left side once all its right
+    /// side has been visited, i.e. the left rows without a match for outer
+    /// and anti joins, and the left rows with a match for semi joins
+    pub fn visits_left_side(&self) -> bool {
+        matches!(
+            self,
+            JoinType::Left | JoinType::Full | JoinType::Semi | JoinType::Anti
+        )
+    }
 }
 
 /// The on clause of the join, as vector of (left, right) columns.
@@ -97,7 +116,7 @@ pub fn build_join_schema(
     join_type: &JoinType,
 ) -> Schema {
     let fields: Vec<Field> = match join_type {
-        JoinType::Inner | JoinType::Left => {
+        JoinType::Inner | JoinType::Left | JoinType::Full => {
             // remove right-side join keys if they have the same names as the left-side
             let duplicate_keys = &on
                 .iter()
@@ -133,14 +152,88 @@ pub fn build_join_schema(
             // left then right
             left_fields.chain(right_fields).cloned().collect()
         }
+        // only the left side is returned
+        JoinType::Semi | JoinType::Anti => left.fields().clone(),
     };
     Schema::new(fields)
 }
 
+/// Estimates the statistics of the output of an equijoin.
+///
+/// Every value of the join key with the fewest distinct values is assumed
+/// to match a value of the other key, so an inner join produces
+/// `left * right / max(distinct(left_key), distinct(right_key))` rows. A key
+/// with an unknown distinct count is assumed to be unique.
+pub fn estimate_join_statistics(
+    left: (&Schema, Statistics),
+    right: (&Schema, Statistics),
+    on: &JoinOn,
+    join_type: &JoinType,
+) -> Statistics {
+    let (left_schema, left) = left;
+    let (right_schema, right) = right;
+    let column = |schema: &Schema, stats: &Statistics, name: &str| {
+        let index = schema.index_of(name).ok()?;
+        stats.column_statistics.as_ref()?.get(index).cloned()
+    };
+
+    let num_rows = left.num_rows.zip(right.num_rows).map(|(l, r)| {
+        let mut inner_rows = l as f64 * r as f64;
+        for (left_key, right_key) in on {
+            let left_distinct = column(left_schema, &left, left_key)
+                .and_then(|c| c.distinct_count)
+                .unwrap_or(l);
+            let right_distinct = column(right_schema, &right, right_key)
+                .and_then(|c| c.distinct_count)
+                .unwrap_or(r);
+            inner_rows /= left_distinct.max(right_distinct).max(1) as f64;
+        }
+        let inner_rows = inner_rows.ceil() as usize;
+        match join_type {
+            JoinType::Inner => inner_rows,
+            JoinType::Left => inner_rows.max(l),
+            JoinType::Right => inner_rows.max(r),
+            JoinType::Full => inner_rows.max(l).max(r),
+            JoinType::Semi => inner_rows.min(l),
+            JoinType::Anti => l,
+        }
+    });
+
+    // the values of the columns stay within their bounds, but their nulls
+    // and distinct values change
+    let schema = build_join_schema(left_schema, right_schema, on, join_type);
+    let column_statistics = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let name = field.name();
+            let stats = match join_type {
+                JoinType::Right => column(right_schema, &right, name)
+                    .or_else(|| column(left_schema, &left, name)),
+                _ => column(left_schema, &left, name)
+                    .or_else(|| column(right_schema, &right, name)),
+            }
+            .unwrap_or_default();
+            ColumnStatistics {
+                null_count: None,
+                distinct_count: stats.distinct_count.zip(num_rows).map(|(d, n)| d.min(n)),
+                ..stats
+            }
+        })
+        .collect();
+
+    Statistics {
+        num_rows,
+        total_byte_size: None,
+        column_statistics: Some(column_statistics),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use arrow::datatypes::DataType;
 
     fn check(left: &[&str], right: &[&str], on: &[(&str, &str)]) -> Result<()> {
         let left = left.iter().map(|x| x.to_string()).collect::<HashSet<_>>();
@@ -198,4 +291,66 @@ mod tests {
 
         assert!(check(&left, &right, on).is_ok());
     }
+
+    #[test]
+    fn join_statistics() {
+        let left_schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]);
+        let right_schema = Schema::new(vec![Field::new("c", DataType::Int32, false)]);
+        let statistics =
+            |num_rows: usize, distinct_counts: &[Option<usize>]| Statistics {
+                num_rows: Some(num_rows),
+                total_byte_size: None,
+                column_statistics: Some(
+                    distinct_counts
+                        .iter()
+                        .map(|distinct_count| ColumnStatistics {
+                            distinct_count: *distinct_count,
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+            };
+        let estimate = |left: Statistics, right: Statistics, join_type: JoinType| {
+            estimate_join_statistics(
+                (&left_schema, left),
+                (&right_schema, right),
+                &[("b".to_string(), "c".to_string())],
+                &join_type,
+            )
+        };
+
+        // every value of b matches 10 rows of c
+        let join = estimate(
+            statistics(1000, &[None, Some(100)]),
+            statistics(100, &[Some(10)]),
+            JoinType::Inner,
+        );
+        assert_eq!(Some(1000), join.num_rows);
+        let distinct_counts = join
+            .column_statistics
+            .unwrap()
+            .iter()
+            .map(|c| c.distinct_count)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![None, Some(100), Some(10)], distinct_counts);
+
+        // c is unique when its distinct count is unknown
+        let join = estimate(
+            statistics(1000, &[None, None]),
+            statistics(100, &[None]),
+            JoinType::Inner,
+        );
+        assert_eq!(Some(100), join.num_rows);
+
+        // all rows of the left side are preserved
+        let join = estimate(
+            statistics(1000, &[None, None]),
+            statistics(100, &[None]),
+            JoinType::Left,
+        );
+        assert_eq!(Some(1000), join.num_rows);
+    }
 }
@@ -0,0 +1,164 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! HyperLogLog sketch, used to estimate the number of distinct values of a
+//! column in constant memory.
+//!
+//! See "HyperLogLog: the analysis of a near-optimal cardinality estimation
+//! algorithm" by Flajolet et al.
+
+use crate::error::{DataFusionError, Result};
+
+/// Number of bits of the hash used to select a register
+const PRECISION: u32 = 14;
+/// Number of registers
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+/// A HyperLogLog sketch of 2^14 registers, with a standard error of about 0.8%.
+///
+/// Values are added as 64 bits hashes, so that the hashing of every type of
+/// value is left to the caller. Two sketches built with the same hash function
+/// can be merged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Creates an empty sketch
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; NUM_REGISTERS],
+        }
+    }
+
+    /// Adds the hash of a value to the sketch
+    pub fn add_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // the bit marking the end of the remaining bits bounds the rank
+        let remaining = (hash << PRECISION) | (1 << (PRECISION - 1));
+        let rank = remaining.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Merges another sketch into this one
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        self.registers
+            .iter_mut()
+            .zip(other.registers.iter())
+            .for_each(|(register, other)| *register = (*register).max(*other));
+    }
+
+    /// Number of bytes of memory held by the sketch
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.registers.capacity()
+    }
+
+    /// Returns the estimated number of distinct values added to the sketch
+    pub fn count(&self) -> u64 {
+        let m = NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum::<f64>();
+        let estimate = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            // linear counting is more accurate for small cardinalities
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+
+    /// Serializes the sketch, to be merged with [`HyperLogLog::merge`] after
+    /// [`HyperLogLog::try_from_bytes`]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.registers.clone()
+    }
+
+    /// Deserializes a sketch serialized by [`HyperLogLog::to_bytes`]
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != NUM_REGISTERS {
+            return Err(DataFusionError::Internal(format!(
+                "Invalid HyperLogLog state of {} bytes",
+                bytes.len()
+            )));
+        }
+        Ok(Self {
+            registers: bytes.to_vec(),
+        })
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ahash::{CallHasher, RandomState};
+
+    fn sketch(values: impl Iterator<Item = u64>) -> HyperLogLog {
+        let random_state = RandomState::with_seeds(1, 2, 3, 4);
+        let mut hll = HyperLogLog::new();
+        values.for_each(|v| hll.add_hash(u64::get_hash(&v, &random_state)));
+        hll
+    }
+
+    fn assert_estimate(expected: u64, actual: u64) {
+        let error = (expected as f64 - actual as f64).abs() / expected as f64;
+        assert!(error < 0.03, "{} is not close to {}", actual, expected);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(0, HyperLogLog::new().count());
+    }
+
+    #[test]
+    fn small_cardinality() {
+        // duplicates are not counted
+        let hll = sketch((0..1000).chain(0..1000));
+        assert_estimate(1000, hll.count());
+
+        assert_eq!(1, sketch(vec![42; 100].into_iter()).count());
+    }
+
+    #[test]
+    fn large_cardinality() {
+        let hll = sketch(0..1_000_000);
+        assert_estimate(1_000_000, hll.count());
+    }
+
+    #[test]
+    fn merge() -> Result<()> {
+        let mut hll = sketch(0..60_000);
+        let other = HyperLogLog::try_from_bytes(&sketch(40_000..100_000).to_bytes())?;
+        hll.merge(&other);
+        assert_estimate(100_000, hll.count());
+
+        assert!(HyperLogLog::try_from_bytes(&[0, 1]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the INSERT INTO operator, that appends rows to an in-memory table
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::catalog::schema::SchemaProvider;
+use crate::datasource::datasource::Statistics;
+use crate::datasource::{MemTable, TableProvider};
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::LogicalPlan;
+use crate::physical_plan::{
+    common::{self, SizedRecordBatchStream},
+    merge::MergeExec,
+    Distribution, ExecutionPlan, Partitioning,
+};
+use arrow::{array::UInt64Array, datatypes::SchemaRef, record_batch::RecordBatch};
+
+use super::SendableRecordBatchStream;
+use async_trait::async_trait;
+
+/// INSERT INTO execution plan operator. This operator runs its input to
+/// completion, appends the rows to a [`MemTable`], and then produces a
+/// single row with the number of rows inserted.
+#[derive(Clone)]
+pub struct InsertExec {
+    /// The table the rows are appended to
+    table: Arc<dyn TableProvider>,
+    /// The plan producing the rows to insert
+    input: Arc<dyn ExecutionPlan>,
+    /// The schema and name to register the table with once its rows are
+    /// inserted, if it is created by the insert
+    registration: Option<(Arc<dyn SchemaProvider>, String)>,
+}
+
+impl InsertExec {
+    /// Create a new InsertExec, which fails unless `table` is a [`MemTable`]
+    pub fn try_new(
+        table: Arc<dyn TableProvider>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        if table.as_any().downcast_ref::<MemTable>().is_none() {
+            return Err(DataFusionError::NotImplemented(
+                "INSERT INTO is only supported for in-memory tables".to_string(),
+            ));
+        }
+        Ok(InsertExec {
+            table,
+            input,
+            registration: None,
+        })
+    }
+
+    /// Register the table as `name` in `schema` after the rows are inserted,
+    /// as done by `CREATE TABLE ... AS SELECT`
+    pub fn with_registration(
+        mut self,
+        schema: Arc<dyn SchemaProvider>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.registration = Some((schema, name.into()));
+        self
+    }
+
+    /// The table the rows are appended to
+    pub fn table(&self) -> &Arc<dyn TableProvider> {
+        &self.table
+    }
+}
+
+impl fmt::Debug for InsertExec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InsertExec")
+            .field("input", &self.input)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for InsertExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        LogicalPlan::count_schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    /// The input is inserted as a whole, whatever its partitioning
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::UnspecifiedDistribution
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(InsertExec {
+                input: children[0].clone(),
+                ..self.clone()
+            })),
+            _ => Err(DataFusionError::Internal(
+                "InsertExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "InsertExec invalid partition {}",
+                partition
+            )));
+        }
+
+        let stream = MergeExec::new(self.input.clone()).execute(0).await?;
+        let batches = common::collect(stream).await?;
+        let count: usize = batches.iter().map(RecordBatch::num_rows).sum();
+
+        // checked when the plan was created
+        let table = self.table.as_any().downcast_ref::<MemTable>().unwrap();
+        table.insert(batches)?;
+        if let Some((schema, name)) = &self.registration {
+            schema.register_table(name.clone(), self.table.clone())?;
+        }
+
+        let record_batch = RecordBatch::try_new(
+            self.schema(),
+            vec![Arc::new(UInt64Array::from(vec![count as u64]))],
+        )?;
+        Ok(Box::pin(SizedRecordBatchStream::new(
+            self.schema(),
+            vec![Arc::new(record_batch)],
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        // a single row with the number of rows written
+        Statistics {
+            num_rows: Some(1),
+            total_byte_size: None,
+            column_statistics: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::schema::MemorySchemaProvider;
+    use crate::datasource::empty::EmptyTable;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::memory::MemoryExec;
+    use arrow::array::{Int32Array, Int64Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[tokio::test]
+    async fn insert_into_mem_table() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let input = Arc::new(MemoryExec::try_new(
+            &[vec![batch.clone()], vec![batch]],
+            schema.clone(),
+            None,
+        )?);
+        let table = Arc::new(MemTable::try_new(schema.clone(), vec![])?);
+
+        let insert = Arc::new(InsertExec::try_new(table.clone(), input)?);
+        let batches = collect(insert).await?;
+        let count = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(6, count.value(0));
+        assert_eq!(Some(6), table.statistics().num_rows);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn insert_registers_table() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let catalog_schema = Arc::new(MemorySchemaProvider::new());
+        let table = Arc::new(MemTable::try_new(schema.clone(), vec![])?);
+
+        // rows that cannot be inserted do not register the table
+        let other_schema =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let batch = RecordBatch::try_new(
+            other_schema.clone(),
+            vec![Arc::new(Int64Array::from(vec![1]))],
+        )?;
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], other_schema, None)?);
+        let insert = InsertExec::try_new(table.clone(), input)?
+            .with_registration(catalog_schema.clone(), "t");
+        assert!(collect(Arc::new(insert)).await.is_err());
+        assert!(catalog_schema.table("t").is_none());
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None)?);
+        let insert = InsertExec::try_new(table, input)?
+            .with_registration(catalog_schema.clone(), "t");
+        collect(Arc::new(insert)).await?;
+        let registered = catalog_schema.table("t").unwrap();
+        assert_eq!(Some(3), registered.statistics().num_rows);
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_into_other_table() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let input = Arc::new(MemoryExec::try_new(&[], schema.clone(), None)?);
+        let table = Arc::new(EmptyTable::new(schema));
+        assert!(InsertExec::try_new(table, input).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,489 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan for reading line-delimited JSON files
+
+use std::any::Any;
+use std::io::{BufReader, Read};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::datasource::datasource::Statistics;
+use crate::datasource::object_store::local::LocalFileSystem;
+use crate::datasource::object_store::{self, ObjectStore};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::ExecutionPlan;
+use crate::physical_plan::Partitioning;
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::json;
+use arrow::record_batch::RecordBatch;
+use futures::Stream;
+
+use super::{RecordBatchStream, SendableRecordBatchStream};
+use async_trait::async_trait;
+
+/// Line-delimited JSON file read option
+#[derive(Copy, Clone)]
+pub struct NdJsonReadOptions<'a> {
+    /// An optional schema representing the JSON files. If None, the JSON reader will
+    /// try to infer it based on data in the files.
+    pub schema: Option<&'a Schema>,
+    /// Max number of rows to read from each JSON file for schema inference if needed.
+    /// Defaults to 1000.
+    pub schema_infer_max_records: usize,
+    /// File extension; only files with this extension are selected for data input.
+    /// Defaults to ".json".
+    pub file_extension: &'a str,
+}
+
+impl<'a> NdJsonReadOptions<'a> {
+    /// Create a JSON read option with default presets
+    pub fn new() -> Self {
+        Self {
+            schema: None,
+            schema_infer_max_records: 1000,
+            file_extension: ".json",
+        }
+    }
+
+    /// Specify the file extension for JSON file selection
+    pub fn file_extension(mut self, file_extension: &'a str) -> Self {
+        self.file_extension = file_extension;
+        self
+    }
+
+    /// Specify schema to use for JSON read
+    pub fn schema(mut self, schema: &'a Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Configure number of max records to read for schema inference
+    pub fn schema_infer_max_records(mut self, max_records: usize) -> Self {
+        self.schema_infer_max_records = max_records;
+        self
+    }
+}
+
+/// Execution plan for scanning line-delimited JSON files
+#[derive(Debug, Clone)]
+pub struct NdJsonExec {
+    /// The store the files are read from
+    object_store: Arc<dyn ObjectStore>,
+    /// Path to directory containing partitioned JSON files with the same schema
+    path: String,
+    /// The individual files under path
+    filenames: Vec<String>,
+    /// Schema representing the JSON files
+    schema: SchemaRef,
+    /// File extension
+    file_extension: String,
+    /// Optional projection for which columns to load
+    projection: Option<Vec<usize>>,
+    /// Schema after the projection has been applied
+    projected_schema: SchemaRef,
+    /// Batch size
+    batch_size: usize,
+    /// Limit in nr. of rows
+    limit: Option<usize>,
+}
+
+impl NdJsonExec {
+    /// Create a new execution plan for reading a set of JSON files
+    pub fn try_new(
+        path: &str,
+        options: NdJsonReadOptions,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+        limit: Option<usize>,
+    ) -> Result<Self> {
+        Self::try_new_with_object_store(
+            Arc::new(LocalFileSystem),
+            path,
+            options,
+            projection,
+            batch_size,
+            limit,
+        )
+    }
+
+    /// Create a new execution plan for reading a set of JSON files from the given
+    /// object store
+    pub fn try_new_with_object_store(
+        object_store: Arc<dyn ObjectStore>,
+        path: &str,
+        options: NdJsonReadOptions,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+        limit: Option<usize>,
+    ) -> Result<Self> {
+        let file_extension = String::from(options.file_extension);
+
+        let filenames = object_store::list_files(&object_store, path, &file_extension)?;
+        if filenames.is_empty() {
+            return Err(DataFusionError::Execution(format!(
+                "No files found at {path} with file extension {file_extension}",
+                path = path,
+                file_extension = file_extension.as_str()
+            )));
+        }
+
+        let schema = Arc::new(match options.schema {
+            Some(s) => s.clone(),
+            None => NdJsonExec::try_infer_schema(&object_store, &filenames, &options)?,
+        });
+
+        let projected_schema = match &projection {
+            None => schema.clone(),
+            Some(p) => Arc::new(Schema::new(
+                p.iter().map(|i| schema.field(*i).clone()).collect(),
+            )),
+        };
+
+        Ok(Self {
+            object_store,
+            path: path.to_string(),
+            filenames,
+            schema,
+            file_extension,
+            projection,
+            projected_schema,
+            batch_size,
+            limit,
+        })
+    }
+
+    /// The store the files are read from
+    pub fn object_store(&self) -> Arc<dyn ObjectStore> {
+        self.object_store.clone()
+    }
+
+    /// Path to directory containing partitioned JSON files with the same schema
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The individual files under path
+    pub fn filenames(&self) -> &[String] {
+        &self.filenames
+    }
+
+    /// File extension
+    pub fn file_extension(&self) -> &str {
+        &self.file_extension
+    }
+
+    /// Get the schema of the JSON files
+    pub fn file_schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Optional projection for which columns to load
+    pub fn projection(&self) -> Option<&Vec<usize>> {
+        self.projection.as_ref()
+    }
+
+    /// Batch size
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Limit
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Infer the schema of the given JSON files by merging the schema inferred
+    /// from each file
+    pub fn try_infer_schema(
+        object_store: &Arc<dyn ObjectStore>,
+        filenames: &[String],
+        options: &NdJsonReadOptions,
+    ) -> Result<Schema> {
+        let schemas = filenames
+            .iter()
+            .map(|filename| {
+                let file = object_store::open_file(object_store, filename)?;
+                let mut reader = BufReader::new(file);
+                Ok(json::reader::infer_json_schema(
+                    &mut reader,
+                    Some(options.schema_infer_max_records),
+                )?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Schema::try_merge(schemas)?)
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for NdJsonExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    /// Get the schema for this execution plan
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.filenames.len())
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        // this is a leaf node and has no children
+        vec![]
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if children.is_empty() {
+            Ok(Arc::new(self.clone()))
+        } else {
+            Err(DataFusionError::Internal(format!(
+                "Children cannot be replaced in {:?}",
+                self
+            )))
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        let file = self.object_store.head(&self.filenames[partition]).await?;
+        let reader = self.object_store.file_reader(file)?.sync_reader()?;
+        Ok(Box::pin(NdJsonStream::new(
+            reader,
+            self.schema.clone(),
+            self.projected_schema.clone(),
+            self.batch_size,
+            self.limit,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        // the files are not read before execution
+        Statistics::default()
+    }
+}
+
+/// Iterator over batches
+struct NdJsonStream {
+    /// Arrow JSON reader
+    reader: json::Reader<Box<dyn Read + Send + Sync>>,
+    /// Schema of the batches produced by this stream
+    schema: SchemaRef,
+    /// Number of rows that may still be produced, if limited
+    remaining: Option<usize>,
+}
+
+impl NdJsonStream {
+    /// Create an iterator for a JSON file
+    pub fn new(
+        file: Box<dyn Read + Send + Sync>,
+        file_schema: SchemaRef,
+        projected_schema: SchemaRef,
+        batch_size: usize,
+        limit: Option<usize>,
+    ) -> Self {
+        // the JSON reader projects by column name and builds the columns in file
+        // schema order, so the projection is passed in that order and the columns
+        // are reordered to follow `projected_schema` in `next_batch`
+        let projection = if projected_schema.fields().len() != file_schema.fields().len()
+        {
+            Some(
+                file_schema
+                    .fields()
+                    .iter()
+                    .filter(|f| projected_schema.field_with_name(f.name()).is_ok())
+                    .map(|f| f.name().clone())
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
+        Self {
+            reader: json::Reader::new(file, file_schema, batch_size, projection),
+            schema: projected_schema,
+            remaining: limit,
+        }
+    }
+
+    fn next_batch(&mut self) -> ArrowResult<Option<RecordBatch>> {
+        if self.remaining == Some(0) {
+            return Ok(None);
+        }
+        let batch = match self.reader.next()? {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+
+        let batch_schema = batch.schema();
+        let num_rows = match self.remaining {
+            Some(remaining) => remaining.min(batch.num_rows()),
+            None => batch.num_rows(),
+        };
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let index = batch_schema.index_of(field.name())?;
+                Ok(batch.column(index).slice(0, num_rows))
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        self.remaining = self.remaining.map(|remaining| remaining - num_rows);
+
+        RecordBatch::try_new(self.schema.clone(), columns).map(Some)
+    }
+}
+
+impl Stream for NdJsonStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.next_batch().transpose())
+    }
+}
+
+impl RecordBatchStream for NdJsonStream {
+    /// Get the schema
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::object_store::memory::InMemoryObjectStore;
+    use crate::physical_plan::common;
+    use arrow::array::Int64Array;
+    use arrow::datatypes::DataType;
+    use futures::StreamExt;
+
+    const TEST_DATA_BASE: &str = "tests/jsons";
+
+    #[tokio::test]
+    async fn nd_json_exec_infer_schema() -> Result<()> {
+        let path = format!("{}/1.json", TEST_DATA_BASE);
+        let exec =
+            NdJsonExec::try_new(&path, NdJsonReadOptions::new(), None, 1024, None)?;
+        let schema = exec.schema();
+        assert_eq!(4, schema.fields().len());
+        assert_eq!(&DataType::Int64, schema.field_with_name("a")?.data_type());
+        assert_eq!(&DataType::Float64, schema.field_with_name("b")?.data_type());
+        assert_eq!(&DataType::Boolean, schema.field_with_name("c")?.data_type());
+        assert_eq!(&DataType::Utf8, schema.field_with_name("d")?.data_type());
+
+        let batches = common::collect(exec.execute(0).await?).await?;
+        assert_eq!(1, batches.len());
+        assert_eq!(4, batches[0].num_rows());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn nd_json_exec_with_projection() -> Result<()> {
+        let path = format!("{}/1.json", TEST_DATA_BASE);
+        let exec = NdJsonExec::try_new(
+            &path,
+            NdJsonReadOptions::new(),
+            Some(vec![3, 0]),
+            1024,
+            None,
+        )?;
+        assert_eq!(4, exec.file_schema().fields().len());
+        assert_eq!(2, exec.schema().fields().len());
+
+        let mut stream = exec.execute(0).await?;
+        let batch = stream.next().await.unwrap()?;
+        let batch_schema = batch.schema();
+        assert_eq!("d", batch_schema.field(0).name());
+        assert_eq!("a", batch_schema.field(1).name());
+        let a = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(vec![1, -10, 2, 7], a.values().to_vec());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn nd_json_exec_with_limit() -> Result<()> {
+        let path = format!("{}/1.json", TEST_DATA_BASE);
+        let exec =
+            NdJsonExec::try_new(&path, NdJsonReadOptions::new(), None, 3, Some(5))?;
+        let batches = common::collect(exec.execute(0).await?).await?;
+        let rows: Vec<usize> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(vec![3, 1], rows);
+
+        let exec =
+            NdJsonExec::try_new(&path, NdJsonReadOptions::new(), None, 3, Some(2))?;
+        let batches = common::collect(exec.execute(0).await?).await?;
+        let rows: Vec<usize> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(vec![2], rows);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn nd_json_exec_from_object_store() -> Result<()> {
+        let store = Arc::new(InMemoryObjectStore::new());
+        store.put(
+            "logs/1.json",
+            std::fs::read(format!("{}/1.json", TEST_DATA_BASE))?,
+        );
+        store.put("logs/2.json", b"{\"a\": 3, \"e\": [1]}\n".to_vec());
+        store.put("logs/README.md", b"not json".to_vec());
+
+        let exec = NdJsonExec::try_new_with_object_store(
+            store,
+            "logs",
+            NdJsonReadOptions::new(),
+            None,
+            1024,
+            None,
+        )?;
+        assert_eq!(vec!["logs/1.json", "logs/2.json"], exec.filenames());
+        // the schemas of the files are merged
+        assert_eq!(5, exec.schema().fields().len());
+        let batches = common::collect(exec.execute(1).await?).await?;
+        assert_eq!(1, batches[0].num_rows());
+        Ok(())
+    }
+
+    #[test]
+    fn nd_json_exec_no_files() {
+        let err = NdJsonExec::try_new(
+            TEST_DATA_BASE,
+            NdJsonReadOptions::new().file_extension(".ndjson"),
+            None,
+            1024,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("No files found"));
+    }
+}
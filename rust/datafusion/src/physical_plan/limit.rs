@@ -25,6 +25,7 @@ use std::task::{Context, Poll};
 use futures::stream::Stream;
 use futures::stream::StreamExt;
 
+use crate::datasource::datasource::{ColumnStatistics, Statistics};
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::{Distribution, ExecutionPlan, Partitioning};
 use arrow::array::ArrayRef;
@@ -121,6 +122,10 @@ impl ExecutionPlan for GlobalLimitExec {
         let stream = self.input.execute(0).await?;
         Ok(Box::pin(LimitStream::new(stream, self.limit)))
     }
+
+    fn statistics(&self) -> Statistics {
+        limit_statistics(self.input.statistics(), self.limit)
+    }
 }
 
 /// LocalLimitExec applies a limit to a single partition
@@ -187,6 +192,37 @@ impl ExecutionPlan for LocalLimitExec {
         let stream = self.input.execute(partition).await?;
         Ok(Box::pin(LimitStream::new(stream, self.limit)))
     }
+
+    fn statistics(&self) -> Statistics {
+        // every partition is limited separately
+        let partitions = self.input.output_partitioning().partition_count();
+        limit_statistics(self.input.statistics(), self.limit * partitions)
+    }
+}
+
+/// The statistics of the input, truncated to at most `limit` rows. If the
+/// number of rows of the input is unknown, `limit` is used as the estimate.
+pub(crate) fn limit_statistics(input: Statistics, limit: usize) -> Statistics {
+    match input.num_rows {
+        Some(num_rows) if num_rows <= limit => input,
+        num_rows => Statistics {
+            num_rows: Some(limit),
+            total_byte_size: match (input.total_byte_size, num_rows) {
+                (Some(size), Some(num_rows)) => Some(size / num_rows * limit),
+                _ => None,
+            },
+            column_statistics: input.column_statistics.map(|columns| {
+                columns
+                    .into_iter()
+                    .map(|c| ColumnStatistics {
+                        null_count: c.null_count.map(|n| n.min(limit)),
+                        distinct_count: c.distinct_count.map(|d| d.min(limit)),
+                        ..c
+                    })
+                    .collect()
+            }),
+        },
+    }
 }
 
 /// Truncate a RecordBatch to maximum of n rows
@@ -271,6 +307,7 @@ mod tests {
     use super::*;
     use crate::physical_plan::common;
     use crate::physical_plan::csv::{CsvExec, CsvReadOptions};
+    use crate::physical_plan::memory::MemoryExec;
     use crate::physical_plan::merge::MergeExec;
     use crate::test;
 
@@ -335,4 +372,23 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn limit_statistics() -> Result<()> {
+        let batch = test::make_partition(100);
+        let schema = batch.schema();
+        let input = Arc::new(MemoryExec::try_new(
+            &[vec![batch.clone()], vec![batch]],
+            schema,
+            None,
+        )?);
+
+        let limit = GlobalLimitExec::new(input.clone(), 7);
+        assert_eq!(Some(7), limit.statistics().num_rows);
+        let limit = LocalLimitExec::new(input.clone(), 7);
+        assert_eq!(Some(14), limit.statistics().num_rows);
+        let limit = GlobalLimitExec::new(input, 1000);
+        assert_eq!(Some(200), limit.statistics().num_rows);
+        Ok(())
+    }
 }
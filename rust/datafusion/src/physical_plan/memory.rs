@@ -21,7 +21,10 @@ use std::any::Any;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
-use super::{ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream};
+use super::{
+    common, ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream,
+};
+use crate::datasource::datasource::Statistics;
 use crate::error::{DataFusionError, Result};
 use arrow::datatypes::SchemaRef;
 use arrow::error::Result as ArrowResult;
@@ -80,6 +83,14 @@ impl ExecutionPlan for MemoryExec {
             self.projection.clone(),
         )?))
     }
+
+    fn statistics(&self) -> Statistics {
+        common::compute_record_batch_statistics(
+            &self.partitions,
+            &self.schema,
+            self.projection.as_deref(),
+        )
+    }
 }
 
 impl MemoryExec {
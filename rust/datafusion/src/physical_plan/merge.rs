@@ -35,6 +35,7 @@ use arrow::{
 };
 
 use super::RecordBatchStream;
+use crate::datasource::datasource::Statistics;
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::Partitioning;
@@ -151,6 +152,10 @@ impl ExecutionPlan for MergeExec {
             }
         }
     }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
 }
 
 pin_project! {
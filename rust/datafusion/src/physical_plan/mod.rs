@@ -17,10 +17,14 @@
 
 //! Traits for physical query plan, supporting parallel execution for partitioned relations.
 
-use std::fmt::{Debug, Display};
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use std::{any::Any, pin::Pin};
 
+use crate::datasource::datasource::Statistics;
 use crate::execution::context::ExecutionContextState;
 use crate::logical_plan::LogicalPlan;
 use crate::{error::Result, scalar::ScalarValue};
@@ -57,6 +61,86 @@ pub trait PhysicalPlanner {
     ) -> Result<Arc<dyn ExecutionPlan>>;
 }
 
+/// Type of a [SQLMetric]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricType {
+    /// Simple counter, such as a number of rows
+    Counter,
+    /// Elapsed time, in nanoseconds
+    TimeNanos,
+}
+
+/// Metric recorded by an operator while it executes, such as the number of
+/// rows it produced or the time it spent computing. The metric is shared by
+/// all the partitions of the operator.
+#[derive(Debug)]
+pub struct SQLMetric {
+    /// Value of the metric
+    value: AtomicUsize,
+    /// Type of the metric
+    metric_type: MetricType,
+}
+
+impl SQLMetric {
+    /// Create a new counter, starting at 0
+    pub fn counter() -> Arc<Self> {
+        Arc::new(Self::new(MetricType::Counter))
+    }
+
+    /// Create a new metric of elapsed time, starting at 0
+    pub fn time_nanos() -> Arc<Self> {
+        Arc::new(Self::new(MetricType::TimeNanos))
+    }
+
+    fn new(metric_type: MetricType) -> Self {
+        Self {
+            value: AtomicUsize::new(0),
+            metric_type,
+        }
+    }
+
+    /// Add `n` to the metric
+    pub fn add(&self, n: usize) {
+        self.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Add the time elapsed since `start` to the metric
+    pub fn add_elapsed(&self, start: Instant) {
+        self.add(start.elapsed().as_nanos() as usize);
+    }
+
+    /// Current value of the metric
+    pub fn value(&self) -> usize {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Type of the metric
+    pub fn metric_type(&self) -> MetricType {
+        self.metric_type
+    }
+}
+
+impl Clone for SQLMetric {
+    /// Returns a snapshot of the metric
+    fn clone(&self) -> Self {
+        Self {
+            value: AtomicUsize::new(self.value()),
+            metric_type: self.metric_type,
+        }
+    }
+}
+
+impl Display for SQLMetric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.metric_type {
+            MetricType::Counter => write!(f, "{}", self.value()),
+            MetricType::TimeNanos => {
+                write!(f, "{:?}", Duration::from_nanos(self.value() as u64))
+            }
+        }
+    }
+}
+
 /// Partition-aware execution plan for a relation
 #[async_trait]
 pub trait ExecutionPlan: Debug + Send + Sync {
@@ -84,6 +168,21 @@ pub trait ExecutionPlan: Debug + Send + Sync {
 
     /// creates an iterator
     async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream>;
+
+    /// Returns the estimated statistics of the output of this plan, across
+    /// all of its partitions. Operators derive them from the statistics of
+    /// their inputs, so they become less accurate the deeper the plan is.
+    /// Plans that cannot estimate their output return unknown statistics.
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    /// Returns a snapshot of the metrics recorded so far by the executions
+    /// of this plan, by name. Operators that do not record metrics return
+    /// an empty map.
+    fn metrics(&self) -> HashMap<String, SQLMetric> {
+        HashMap::new()
+    }
 }
 
 /// Execute the [ExecutionPlan] and collect the results in memory
@@ -223,6 +322,30 @@ pub trait AggregateExpr: Send + Sync + Debug {
     fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>>;
 }
 
+/// A window expression that:
+/// * knows its resulting field
+/// * knows how to evaluate itself over all the rows of its input, producing
+///   one value per input row
+pub trait WindowExpr: Send + Sync + Debug {
+    /// Returns the window expression as [`Any`](std::any::Any) so that it can be
+    /// downcast to a specific implementation.
+    fn as_any(&self) -> &dyn Any;
+
+    /// the field of the final result of this window function.
+    fn field(&self) -> Result<Field>;
+
+    /// Human readable name such as `"MIN(c2)"` or `"RANK()"`. The default
+    /// implementation returns placeholder text.
+    fn name(&self) -> &str {
+        "WindowExpr: default name"
+    }
+
+    /// evaluate the window function over all the rows of `batch`. The
+    /// returned array has the same number of rows as `batch`, and its row `i`
+    /// holds the value of the window function for row `i` of `batch`.
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef>;
+}
+
 /// An accumulator represents a stateful object that lives throughout the evaluation of multiple rows and
 /// generically accumulates values. An accumulator knows how to:
 /// * update its state from inputs via `update`
@@ -271,12 +394,21 @@ pub trait Accumulator: Send + Sync + Debug {
 
     /// returns its value based on its current state.
     fn evaluate(&self) -> Result<ScalarValue>;
+
+    /// Number of bytes of memory held by the accumulator, including the memory
+    /// it allocates. Accumulators whose state grows with their input, such as
+    /// the ones of distinct values, should override it.
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
 }
 
 pub mod aggregates;
+pub mod analyze;
 pub mod array_expressions;
 pub mod coalesce_batches;
 pub mod common;
+pub mod cross_join;
 #[cfg(feature = "crypto_expressions")]
 pub mod crypto_expressions;
 pub mod csv;
@@ -285,12 +417,16 @@ pub mod distinct_expressions;
 pub mod empty;
 pub mod explain;
 pub mod expressions;
+pub mod file_sink;
 pub mod filter;
 pub mod functions;
 pub mod group_scalar;
 pub mod hash_aggregate;
 pub mod hash_join;
 pub mod hash_utils;
+pub mod hyperloglog;
+pub mod insert;
+pub mod json;
 pub mod limit;
 pub mod math_expressions;
 pub mod memory;
@@ -301,11 +437,20 @@ pub mod projection;
 #[cfg(feature = "regex_expressions")]
 pub mod regex_expressions;
 pub mod repartition;
+pub mod row_format;
 pub mod sort;
+pub mod sort_merge_join;
+pub mod sort_preserving_merge;
+pub mod sort_utils;
+pub mod spill;
 pub mod string_expressions;
+pub mod tdigest;
+pub mod topk;
 pub mod type_coercion;
 pub mod udaf;
 pub mod udf;
 #[cfg(feature = "unicode_expressions")]
 pub mod unicode_expressions;
 pub mod union;
+pub mod window_functions;
+pub mod windows;
@@ -18,7 +18,7 @@
 //! Execution plan for reading Parquet files
 
 use std::fmt;
-use std::fs::File;
+use std::io::Read;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::{
@@ -28,11 +28,15 @@ use std::{
 
 use super::{
     planner::DefaultPhysicalPlanner, ColumnarValue, PhysicalExpr, RecordBatchStream,
-    SendableRecordBatchStream,
+    SQLMetric, SendableRecordBatchStream,
 };
 use crate::{
     catalog::catalog::MemoryCatalogList,
-    physical_plan::{common, ExecutionPlan, Partitioning},
+    datasource::object_store::{
+        self, local::LocalFileSystem, FileMeta, ObjectReader, ObjectStore,
+        ObjectStoreRegistry,
+    },
+    physical_plan::{ExecutionPlan, Partitioning},
 };
 use crate::{
     error::{DataFusionError, Result},
@@ -51,9 +55,10 @@ use arrow::{
     buffer::MutableBuffer,
     datatypes::{DataType, Field, Schema, SchemaRef},
 };
+use parquet::errors::ParquetError;
 use parquet::file::{
     metadata::RowGroupMetaData,
-    reader::{FileReader, SerializedFileReader},
+    reader::{ChunkReader, FileReader, Length, SerializedFileReader},
     statistics::Statistics as ParquetStatistics,
 };
 
@@ -72,6 +77,8 @@ use futures::stream::{Stream, StreamExt};
 /// Execution plan for scanning one or more Parquet partitions
 #[derive(Debug, Clone)]
 pub struct ParquetExec {
+    /// The store the files are read from
+    object_store: Arc<dyn ObjectStore>,
     /// Parquet partitions to read
     partitions: Vec<ParquetPartition>,
     /// Schema after projection is applied
@@ -86,6 +93,29 @@ pub struct ParquetExec {
     predicate_builder: Option<RowGroupPredicateBuilder>,
     /// Optional limit of the number of rows
     limit: Option<usize>,
+    /// Metrics recorded by the executions of the scan
+    metrics: ParquetMetrics,
+}
+
+/// Metrics recorded by [ParquetExec], shared by all its partitions
+#[derive(Debug, Clone)]
+struct ParquetMetrics {
+    /// Number of rows read
+    output_rows: Arc<SQLMetric>,
+    /// Number of compressed bytes of the column chunks read
+    bytes_scanned: Arc<SQLMetric>,
+    /// Number of row groups skipped as their statistics do not satisfy the predicate
+    row_groups_pruned: Arc<SQLMetric>,
+}
+
+impl ParquetMetrics {
+    fn new() -> Self {
+        Self {
+            output_rows: SQLMetric::counter(),
+            bytes_scanned: SQLMetric::counter(),
+            row_groups_pruned: SQLMetric::counter(),
+        }
+    }
 }
 
 /// Represents one partition of a Parquet data set and this currently means one Parquet file.
@@ -115,11 +145,32 @@ impl ParquetExec {
         batch_size: usize,
         max_concurrency: usize,
         limit: Option<usize>,
+    ) -> Result<Self> {
+        Self::try_from_path_with_object_store(
+            Arc::new(LocalFileSystem),
+            path,
+            projection,
+            predicate,
+            batch_size,
+            max_concurrency,
+            limit,
+        )
+    }
+
+    /// Create a new Parquet reader execution plan based on the specified Parquet filename or
+    /// directory containing Parquet files in the given object store
+    pub fn try_from_path_with_object_store(
+        object_store: Arc<dyn ObjectStore>,
+        path: &str,
+        projection: Option<Vec<usize>>,
+        predicate: Option<Expr>,
+        batch_size: usize,
+        max_concurrency: usize,
+        limit: Option<usize>,
     ) -> Result<Self> {
         // build a list of filenames from the specified path, which could be a single file or
         // a directory containing one or more parquet files
-        let mut filenames: Vec<String> = vec![];
-        common::build_file_list(path, &mut filenames, ".parquet")?;
+        let filenames = object_store::list_files(&object_store, path, ".parquet")?;
         if filenames.is_empty() {
             Err(DataFusionError::Plan(format!(
                 "No Parquet files found at path {}",
@@ -130,7 +181,8 @@ impl ParquetExec {
                 .iter()
                 .map(|filename| filename.as_str())
                 .collect::<Vec<&str>>();
-            Self::try_from_files(
+            Self::try_from_files_with_object_store(
+                object_store,
                 &filenames,
                 projection,
                 predicate,
@@ -150,6 +202,28 @@ impl ParquetExec {
         batch_size: usize,
         max_concurrency: usize,
         limit: Option<usize>,
+    ) -> Result<Self> {
+        Self::try_from_files_with_object_store(
+            Arc::new(LocalFileSystem),
+            filenames,
+            projection,
+            predicate,
+            batch_size,
+            max_concurrency,
+            limit,
+        )
+    }
+
+    /// Create a new Parquet reader execution plan based on the specified list of Parquet
+    /// files in the given object store
+    pub fn try_from_files_with_object_store(
+        object_store: Arc<dyn ObjectStore>,
+        filenames: &[&str],
+        projection: Option<Vec<usize>>,
+        predicate: Option<Expr>,
+        batch_size: usize,
+        max_concurrency: usize,
+        limit: Option<usize>,
     ) -> Result<Self> {
         // build a list of Parquet partitions with statistics and gather all unique schemas
         // used in this data set
@@ -167,8 +241,9 @@ impl ParquetExec {
             let mut total_files = 0;
             for filename in &filenames {
                 total_files += 1;
-                let file = File::open(filename)?;
-                let file_reader = Arc::new(SerializedFileReader::new(file)?);
+                let file = object_store::head_file(&object_store, filename)?;
+                let file_reader =
+                    Arc::new(open_parquet_file(object_store.as_ref(), file)?);
                 let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
                 let meta_data = arrow_reader.get_metadata();
                 // collect all the unique schemas in this data set
@@ -241,6 +316,7 @@ impl ParquetExec {
         });
 
         Ok(Self::new(
+            object_store,
             partitions,
             schema,
             projection,
@@ -252,6 +328,7 @@ impl ParquetExec {
 
     /// Create a new Parquet reader execution plan with provided partitions and schema
     pub fn new(
+        object_store: Arc<dyn ObjectStore>,
         partitions: Vec<ParquetPartition>,
         schema: Schema,
         projection: Option<Vec<usize>>,
@@ -289,7 +366,7 @@ impl ParquetExec {
                 has_null_counts = true;
 
                 for &i in projection.iter() {
-                    null_counts[i] = part_nulls[i].unwrap_or(0);
+                    null_counts[i] += part_nulls[i].unwrap_or(0);
                 }
             }
         }
@@ -315,6 +392,7 @@ impl ParquetExec {
             column_statistics: column_stats,
         };
         Self {
+            object_store,
             partitions,
             schema: Arc::new(projected_schema),
             projection,
@@ -322,9 +400,15 @@ impl ParquetExec {
             batch_size,
             statistics,
             limit,
+            metrics: ParquetMetrics::new(),
         }
     }
 
+    /// The store the files are read from
+    pub fn object_store(&self) -> Arc<dyn ObjectStore> {
+        self.object_store.clone()
+    }
+
     /// Parquet partitions to read
     pub fn partitions(&self) -> &[ParquetPartition] {
         &self.partitions
@@ -339,11 +423,6 @@ impl ParquetExec {
     pub fn batch_size(&self) -> usize {
         self.batch_size
     }
-
-    /// Statistics for the data set (sum of statistics for all partitions)
-    pub fn statistics(&self) -> &Statistics {
-        &self.statistics
-    }
 }
 
 impl ParquetPartition {
@@ -400,6 +479,7 @@ impl RowGroupPredicateBuilder {
             var_provider: HashMap::new(),
             aggregate_functions: HashMap::new(),
             config: ExecutionConfig::new(),
+            object_store_registry: Arc::new(ObjectStoreRegistry::new()),
         };
         let predicate_expr = DefaultPhysicalPlanner::default().create_physical_expr(
             &logical_predicate_expr,
@@ -854,20 +934,31 @@ impl ExecutionPlan for ParquetExec {
             Receiver<ArrowResult<RecordBatch>>,
         ) = channel(2);
 
-        let filenames = self.partitions[partition].filenames.clone();
+        let object_store = self.object_store.clone();
+        // the files are opened on a blocking thread, once their metadata is known
+        let files = futures::future::try_join_all(
+            self.partitions[partition]
+                .filenames
+                .iter()
+                .map(|filename| object_store.head(filename)),
+        )
+        .await?;
         let projection = self.projection.clone();
         let predicate_builder = self.predicate_builder.clone();
         let batch_size = self.batch_size;
         let limit = self.limit;
+        let metrics = self.metrics.clone();
 
         task::spawn_blocking(move || {
             if let Err(e) = read_files(
-                &filenames,
+                object_store.as_ref(),
+                files,
                 &projection,
                 &predicate_builder,
                 batch_size,
                 response_tx,
                 limit,
+                &metrics,
             ) {
                 println!("Parquet reader thread terminated due to error: {:?}", e);
             }
@@ -878,6 +969,42 @@ impl ExecutionPlan for ParquetExec {
             inner: ReceiverStream::new(response_rx),
         }))
     }
+
+    fn metrics(&self) -> HashMap<String, SQLMetric> {
+        let mut metrics = HashMap::new();
+        metrics.insert(
+            "output_rows".to_owned(),
+            (*self.metrics.output_rows).clone(),
+        );
+        metrics.insert(
+            "bytes_scanned".to_owned(),
+            (*self.metrics.bytes_scanned).clone(),
+        );
+        metrics.insert(
+            "row_groups_pruned".to_owned(),
+            (*self.metrics.row_groups_pruned).clone(),
+        );
+        metrics
+    }
+
+    /// The sum of the statistics of all partitions, for the projected columns
+    fn statistics(&self) -> Statistics {
+        let column_statistics = self.statistics.column_statistics.as_ref().map(|c| {
+            self.projection
+                .iter()
+                .map(|i| c[*i].clone())
+                .collect::<Vec<_>>()
+        });
+        let num_rows = match (self.statistics.num_rows, self.limit) {
+            (Some(num_rows), Some(limit)) => Some(num_rows.min(limit)),
+            (num_rows, _) => num_rows,
+        };
+        Statistics {
+            num_rows,
+            total_byte_size: self.statistics.total_byte_size,
+            column_statistics,
+        }
+    }
 }
 
 fn send_result(
@@ -891,22 +1018,64 @@ fn send_result(
     Ok(())
 }
 
+/// Adapts an [`ObjectReader`] to the [`ChunkReader`] used by the Parquet reader
+struct ObjectReaderWrapper(Arc<dyn ObjectReader>);
+
+impl Length for ObjectReaderWrapper {
+    fn len(&self) -> u64 {
+        self.0.length()
+    }
+}
+
+impl ChunkReader for ObjectReaderWrapper {
+    type T = Box<dyn Read + Send + Sync>;
+
+    fn get_read(&self, start: u64, length: usize) -> parquet::errors::Result<Self::T> {
+        self.0
+            .sync_chunk_reader(start, length)
+            .map_err(|e| ParquetError::General(e.to_string()))
+    }
+}
+
+/// Open the Parquet file `file` in the given object store
+fn open_parquet_file(
+    object_store: &dyn ObjectStore,
+    file: FileMeta,
+) -> Result<SerializedFileReader<ObjectReaderWrapper>> {
+    let reader = ObjectReaderWrapper(object_store.file_reader(file)?);
+    Ok(SerializedFileReader::new(reader)?)
+}
+
 fn read_files(
-    filenames: &[String],
+    object_store: &dyn ObjectStore,
+    files: Vec<FileMeta>,
     projection: &[usize],
     predicate_builder: &Option<RowGroupPredicateBuilder>,
     batch_size: usize,
     response_tx: Sender<ArrowResult<RecordBatch>>,
     limit: Option<usize>,
+    metrics: &ParquetMetrics,
 ) -> Result<()> {
     let mut total_rows = 0;
-    'outer: for filename in filenames {
-        let file = File::open(&filename)?;
-        let mut file_reader = SerializedFileReader::new(file)?;
+    'outer: for file in files {
+        let filename = file.path.clone();
+        let mut file_reader = open_parquet_file(object_store, file)?;
         if let Some(predicate_builder) = predicate_builder {
+            let row_groups = file_reader.metadata().num_row_groups();
             let row_group_predicate = predicate_builder
                 .build_row_group_predicate(file_reader.metadata().row_groups());
             file_reader.filter_row_groups(&row_group_predicate);
+            metrics
+                .row_groups_pruned
+                .add(row_groups - file_reader.metadata().num_row_groups());
+        }
+        for row_group in file_reader.metadata().row_groups() {
+            let bytes = projection
+                .iter()
+                .filter(|i| **i < row_group.num_columns())
+                .map(|i| row_group.column(*i).compressed_size() as usize)
+                .sum();
+            metrics.bytes_scanned.add(bytes);
         }
         let mut arrow_reader = ParquetFileArrowReader::new(Arc::new(file_reader));
         let mut batch_reader = arrow_reader
@@ -916,6 +1085,7 @@ fn read_files(
                 Some(Ok(batch)) => {
                     //println!("ParquetExec got new batch from {}", filename);
                     total_rows += batch.num_rows();
+                    metrics.output_rows.add(batch.num_rows());
                     send_result(&response_tx, Ok(batch))?;
                     if limit.map(|l| total_rows >= l).unwrap_or(false) {
                         break 'outer;
@@ -21,34 +21,48 @@ use std::sync::Arc;
 
 use super::{
     aggregates, empty::EmptyExec, expressions::binary, functions,
-    hash_join::PartitionMode, udaf, union::UnionExec,
+    hash_join::PartitionMode, udaf, union::UnionExec, windows,
 };
+use crate::catalog::TableReference;
+use crate::datasource::{MemTable, ViewTable};
 use crate::error::{DataFusionError, Result};
 use crate::execution::context::ExecutionContextState;
 use crate::logical_plan::{
     DFSchema, Expr, LogicalPlan, Operator, Partitioning as LogicalPartitioning, PlanType,
-    StringifiedPlan, UserDefinedLogicalNode,
+    StringifiedPlan, UserDefinedLogicalNode, GROUPING_ID_COLUMN,
 };
+use crate::optimizer::hash_build_probe_order::get_num_rows;
+use crate::physical_plan::analyze::AnalyzeExec;
+use crate::physical_plan::cross_join::CrossJoinExec;
 use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::expressions;
 use crate::physical_plan::expressions::{CaseExpr, Column, Literal, PhysicalSortExpr};
+use crate::physical_plan::file_sink::FileSinkExec;
 use crate::physical_plan::filter::FilterExec;
 use crate::physical_plan::hash_aggregate::{AggregateMode, HashAggregateExec};
 use crate::physical_plan::hash_join::HashJoinExec;
+use crate::physical_plan::insert::InsertExec;
 use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
 use crate::physical_plan::projection::ProjectionExec;
 use crate::physical_plan::repartition::RepartitionExec;
 use crate::physical_plan::sort::SortExec;
+use crate::physical_plan::sort_merge_join::{self, SortMergeJoinExec};
+use crate::physical_plan::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::sort_utils;
+use crate::physical_plan::topk::TopKExec;
 use crate::physical_plan::udf;
+use crate::physical_plan::windows::WindowAggExec;
 use crate::physical_plan::{hash_utils, Partitioning};
-use crate::physical_plan::{AggregateExpr, ExecutionPlan, PhysicalExpr, PhysicalPlanner};
+use crate::physical_plan::{
+    AggregateExpr, ExecutionPlan, PhysicalExpr, PhysicalPlanner, WindowExpr,
+};
 use crate::prelude::JoinType;
 use crate::scalar::ScalarValue;
 use crate::variable::VarType;
 use arrow::compute::can_cast_types;
 
 use arrow::compute::SortOptions;
-use arrow::datatypes::{Schema, SchemaRef};
+use arrow::datatypes::{Field, Schema, SchemaRef};
 use expressions::col;
 use log::debug;
 
@@ -135,7 +149,44 @@ impl DefaultPhysicalPlanner {
                 filters,
                 limit,
                 ..
-            } => source.scan(projection, batch_size, filters, *limit),
+            } => match source.as_any().downcast_ref::<ViewTable>() {
+                // views are optimized and planned with the state of this context,
+                // rather than with the default state of `ViewTable::scan`
+                Some(view) => {
+                    let plan = ctx_state.config.optimizers.iter().try_fold(
+                        view.scan_plan(projection, *limit)?,
+                        |plan, optimizer| optimizer.optimize(&plan),
+                    )?;
+                    self.create_initial_plan(&plan, ctx_state)
+                }
+                None => source.scan(projection, batch_size, filters, *limit),
+            },
+            LogicalPlan::Window {
+                input, window_expr, ..
+            } => {
+                // window needs to operate on a single partition currently
+                let input_exec = self.create_initial_plan(input, ctx_state)?;
+                let input_schema = input_exec.schema();
+                let logical_input_schema = input.as_ref().schema();
+
+                let window_expr = window_expr
+                    .iter()
+                    .map(|e| {
+                        self.create_window_expr(
+                            e,
+                            &logical_input_schema,
+                            &input_schema,
+                            ctx_state,
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(Arc::new(WindowAggExec::try_new(
+                    window_expr,
+                    input_exec,
+                    input_schema,
+                )?))
+            }
             LogicalPlan::Aggregate {
                 input,
                 group_expr,
@@ -148,6 +199,15 @@ impl DefaultPhysicalPlanner {
                 let physical_input_schema = input_exec.as_ref().schema();
                 let logical_input_schema = input.as_ref().schema();
 
+                // grouping sets are computed by the partial aggregate, from the
+                // distinct expressions of the sets
+                let (group_expr, grouping_sets) = match group_expr.as_slice() {
+                    [Expr::GroupingSet(grouping_set)] => {
+                        (grouping_set.distinct_expr(), Some(grouping_set.sets()?))
+                    }
+                    _ => (group_expr.clone(), None),
+                };
+
                 let groups = group_expr
                     .iter()
                     .map(|e| {
@@ -173,30 +233,43 @@ impl DefaultPhysicalPlanner {
                     })
                     .collect::<Result<Vec<_>>>()?;
 
-                let initial_aggr = Arc::new(HashAggregateExec::try_new(
-                    AggregateMode::Partial,
-                    groups.clone(),
-                    aggregates.clone(),
-                    input_exec,
-                    input_schema.clone(),
-                )?);
+                let initial_aggr = Arc::new(
+                    HashAggregateExec::try_new_with_grouping_sets(
+                        AggregateMode::Partial,
+                        groups.clone(),
+                        aggregates.clone(),
+                        grouping_sets,
+                        input_exec,
+                        input_schema.clone(),
+                    )?
+                    .with_memory_limit(ctx_state.config.memory_limit),
+                );
 
-                let final_group: Vec<Arc<dyn PhysicalExpr>> =
-                    (0..groups.len()).map(|i| col(&groups[i].1)).collect();
+                // the final aggregate groups on the grouping columns of the partial
+                // aggregate, which are followed by the grouping id of grouping sets
+                let mut final_group = groups
+                    .iter()
+                    .map(|(_, name)| name.clone())
+                    .collect::<Vec<_>>();
+                if initial_aggr.grouping_sets().is_some() {
+                    final_group.push(GROUPING_ID_COLUMN.to_string());
+                }
 
                 // construct a second aggregation, keeping the final column name equal to the first aggregation
                 // and the expressions corresponding to the respective aggregate
-                Ok(Arc::new(HashAggregateExec::try_new(
-                    AggregateMode::Final,
-                    final_group
-                        .iter()
-                        .enumerate()
-                        .map(|(i, expr)| (expr.clone(), groups[i].1.clone()))
-                        .collect(),
-                    aggregates,
-                    initial_aggr,
-                    input_schema,
-                )?))
+                Ok(Arc::new(
+                    HashAggregateExec::try_new(
+                        AggregateMode::Final,
+                        final_group
+                            .into_iter()
+                            .map(|name| (col(&name), name))
+                            .collect(),
+                        aggregates,
+                        initial_aggr,
+                        input_schema,
+                    )?
+                    .with_memory_limit(ctx_state.config.memory_limit),
+                ))
             }
             LogicalPlan::Projection { input, expr, .. } => {
                 let input_exec = self.create_initial_plan(input, ctx_state)?;
@@ -219,6 +292,17 @@ impl DefaultPhysicalPlanner {
             LogicalPlan::Filter {
                 input, predicate, ..
             } => {
+                // the filter of a cross join is evaluated by the join itself, so
+                // that the rows of the cartesian product not matching it are
+                // never materialized
+                if let LogicalPlan::CrossJoin { left, right, .. } = input.as_ref() {
+                    let left = self.create_initial_plan(left, ctx_state)?;
+                    let right = self.create_initial_plan(right, ctx_state)?;
+                    let join = CrossJoinExec::new(left, right);
+                    let runtime_expr =
+                        self.create_physical_expr(predicate, &join.schema(), ctx_state)?;
+                    return Ok(Arc::new(join.with_filter(runtime_expr)?));
+                }
                 let input = self.create_initial_plan(input, ctx_state)?;
                 let input_schema = input.as_ref().schema();
                 let runtime_expr =
@@ -259,46 +343,89 @@ impl DefaultPhysicalPlanner {
             }
             LogicalPlan::Sort { expr, input, .. } => {
                 let input = self.create_initial_plan(input, ctx_state)?;
-                let input_schema = input.as_ref().schema();
-
-                let sort_expr = expr
-                    .iter()
-                    .map(|e| match e {
-                        Expr::Sort {
-                            expr,
-                            asc,
-                            nulls_first,
-                        } => self.create_physical_sort_expr(
-                            expr,
-                            &input_schema,
-                            SortOptions {
-                                descending: !*asc,
-                                nulls_first: *nulls_first,
-                            },
-                            ctx_state,
-                        ),
-                        _ => Err(DataFusionError::Plan(
-                            "Sort only accepts sort expressions".to_string(),
-                        )),
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-
-                Ok(Arc::new(SortExec::try_new(sort_expr, input)?))
+                let sort_expr =
+                    self.create_sort_exprs(expr, &input.as_ref().schema(), ctx_state)?;
+                self.create_sort_plan(sort_expr, input, ctx_state)
             }
             LogicalPlan::Join {
                 left,
                 right,
                 on: keys,
                 join_type,
+                null_equals_null,
                 ..
             } => {
+                let left_rows = get_num_rows(left);
                 let left = self.create_initial_plan(left, ctx_state)?;
                 let right = self.create_initial_plan(right, ctx_state)?;
                 let physical_join_type = match join_type {
                     JoinType::Inner => hash_utils::JoinType::Inner,
                     JoinType::Left => hash_utils::JoinType::Left,
                     JoinType::Right => hash_utils::JoinType::Right,
+                    JoinType::Full => hash_utils::JoinType::Full,
+                    JoinType::Semi => hash_utils::JoinType::Semi,
+                    JoinType::Anti => hash_utils::JoinType::Anti,
                 };
+
+                let left_on = keys.iter().map(|x| x.0.clone()).collect::<Vec<_>>();
+                let right_on = keys.iter().map(|x| x.1.clone()).collect::<Vec<_>>();
+                if sort_merge_join::supports_join_columns(&left.schema(), &left_on) {
+                    // both sides are already sorted on the join keys
+                    let sort_options = match (
+                        sort_merge_join::join_sort_options(&left, &left_on),
+                        sort_merge_join::join_sort_options(&right, &right_on),
+                    ) {
+                        (Some(l), Some(r))
+                            if l.iter().zip(&r).all(|(l, r)| {
+                                l.descending == r.descending
+                                    && l.nulls_first == r.nulls_first
+                            }) =>
+                        {
+                            Some((left.clone(), right.clone(), l))
+                        }
+                        // the build side is too large to be held in memory:
+                        // sort both sides, which may spill to disk
+                        _ if matches!(
+                            (left_rows, ctx_state.config.sort_merge_join_rows),
+                            (Some(rows), Some(threshold)) if rows > threshold
+                        ) =>
+                        {
+                            let sort = |input: Arc<dyn ExecutionPlan>,
+                                        on: &[String]|
+                             -> Result<Arc<dyn ExecutionPlan>> {
+                                let expr = on
+                                    .iter()
+                                    .map(|name| PhysicalSortExpr {
+                                        expr: col(name),
+                                        options: SortOptions::default(),
+                                    })
+                                    .collect();
+                                Ok(Arc::new(
+                                    SortExec::try_new(expr, input)?
+                                        .with_memory_limit(ctx_state.config.memory_limit),
+                                ))
+                            };
+                            Some((
+                                sort(left.clone(), &left_on)?,
+                                sort(right.clone(), &right_on)?,
+                                vec![SortOptions::default(); keys.len()],
+                            ))
+                        }
+                        _ => None,
+                    };
+                    if let Some((left, right, sort_options)) = sort_options {
+                        return Ok(Arc::new(SortMergeJoinExec::try_new(
+                            left,
+                            right,
+                            keys,
+                            &physical_join_type,
+                            sort_options,
+                            *null_equals_null,
+                            ctx_state.config.batch_size,
+                        )?));
+                    }
+                }
+
                 if ctx_state.config.concurrency > 1 && ctx_state.config.repartition_joins
                 {
                     let left_expr = keys.iter().map(|x| col(&x.0)).collect();
@@ -317,6 +444,7 @@ impl DefaultPhysicalPlanner {
                         &keys,
                         &physical_join_type,
                         PartitionMode::Partitioned,
+                        *null_equals_null,
                     )?))
                 } else {
                     Ok(Arc::new(HashJoinExec::try_new(
@@ -325,9 +453,15 @@ impl DefaultPhysicalPlanner {
                         &keys,
                         &physical_join_type,
                         PartitionMode::CollectLeft,
+                        *null_equals_null,
                     )?))
                 }
             }
+            LogicalPlan::CrossJoin { left, right, .. } => {
+                let left = self.create_initial_plan(left, ctx_state)?;
+                let right = self.create_initial_plan(right, ctx_state)?;
+                Ok(Arc::new(CrossJoinExec::new(left, right)))
+            }
             LogicalPlan::EmptyRelation {
                 produce_one_row,
                 schema,
@@ -337,7 +471,27 @@ impl DefaultPhysicalPlanner {
             ))),
             LogicalPlan::Limit { input, n, .. } => {
                 let limit = *n;
-                let input = self.create_initial_plan(input, ctx_state)?;
+
+                // a limit of a sort only needs to keep the first rows of each partition,
+                // unless they are too many to be kept in memory
+                let input = match input.as_ref() {
+                    LogicalPlan::Sort { expr, input } => {
+                        let input = self.create_initial_plan(input, ctx_state)?;
+                        let sort_expr = self.create_sort_exprs(
+                            expr,
+                            &input.as_ref().schema(),
+                            ctx_state,
+                        )?;
+                        if use_top_k(&input, &sort_expr, limit, ctx_state)? {
+                            return Ok(Arc::new(
+                                TopKExec::new(sort_expr, input, limit)
+                                    .with_memory_limit(ctx_state.config.memory_limit),
+                            ));
+                        }
+                        self.create_sort_plan(sort_expr, input, ctx_state)?
+                    }
+                    _ => self.create_initial_plan(input, ctx_state)?,
+                };
 
                 // GlobalLimitExec requires a single partition for input
                 let input = if input.output_partitioning().partition_count() == 1 {
@@ -359,6 +513,51 @@ impl DefaultPhysicalPlanner {
                     "Unsupported logical plan: CreateExternalTable".to_string(),
                 ))
             }
+            LogicalPlan::CreateMemoryTable { name, input } => {
+                // the table created by a query is only registered once its rows
+                // are inserted, and its columns are all nullable
+                let schema = Schema::new(
+                    input
+                        .schema()
+                        .fields()
+                        .iter()
+                        .map(|f| Field::new(f.name(), f.data_type().clone(), true))
+                        .collect(),
+                );
+                let table = Arc::new(MemTable::try_new(Arc::new(schema), vec![])?);
+                let table_ref = TableReference::from(name.as_str());
+                let schema_provider = ctx_state.schema_for_ref(table_ref)?;
+                let input = self.create_initial_plan(input, ctx_state)?;
+                Ok(Arc::new(
+                    InsertExec::try_new(table, input)?
+                        .with_registration(schema_provider, table_ref.table()),
+                ))
+            }
+            LogicalPlan::CreateView { .. } => {
+                // Like "CREATE EXTERNAL TABLE", this must be handled at a
+                // higher level, that registers the view
+                Err(DataFusionError::Internal(format!(
+                    "Unsupported logical plan: {:?}",
+                    logical_plan
+                )))
+            }
+            LogicalPlan::Insert { table, input, .. } => {
+                let input = self.create_initial_plan(input, ctx_state)?;
+                Ok(Arc::new(InsertExec::try_new(table.clone(), input)?))
+            }
+            LogicalPlan::CopyTo {
+                input,
+                path,
+                options,
+                ..
+            } => {
+                let input = self.create_initial_plan(input, ctx_state)?;
+                Ok(Arc::new(FileSinkExec::new(
+                    input,
+                    path.clone(),
+                    options.clone(),
+                )))
+            }
             LogicalPlan::Explain {
                 verbose,
                 plan,
@@ -385,6 +584,18 @@ impl DefaultPhysicalPlanner {
                     stringified_plans,
                 )))
             }
+            LogicalPlan::Analyze {
+                verbose,
+                input,
+                schema,
+            } => {
+                let input = self.create_initial_plan(input, ctx_state)?;
+                Ok(Arc::new(AnalyzeExec::new(
+                    *verbose,
+                    input,
+                    SchemaRef::new(schema.as_ref().to_owned().into()),
+                )))
+            }
             LogicalPlan::Extension { node } => {
                 let inputs = node
                     .inputs()
@@ -651,6 +862,78 @@ impl DefaultPhysicalPlanner {
         }
     }
 
+    /// Create a window expression from a logical expression
+    pub fn create_window_expr(
+        &self,
+        e: &Expr,
+        logical_input_schema: &DFSchema,
+        physical_input_schema: &Schema,
+        ctx_state: &ExecutionContextState,
+    ) -> Result<Arc<dyn WindowExpr>> {
+        // unpack aliased logical expressions, e.g. "sum(col) over () as total"
+        let (name, e) = match e {
+            Expr::Alias(sub_expr, alias) => (alias.clone(), sub_expr.as_ref()),
+            _ => (e.name(logical_input_schema)?, e),
+        };
+
+        match e {
+            Expr::WindowFunction {
+                fun,
+                args,
+                partition_by,
+                order_by,
+                window_frame,
+            } => {
+                let args = args
+                    .iter()
+                    .map(|e| {
+                        self.create_physical_expr(e, physical_input_schema, ctx_state)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let partition_by = partition_by
+                    .iter()
+                    .map(|e| {
+                        self.create_physical_expr(e, physical_input_schema, ctx_state)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let order_by = order_by
+                    .iter()
+                    .map(|e| match e {
+                        Expr::Sort {
+                            expr,
+                            asc,
+                            nulls_first,
+                        } => self.create_physical_sort_expr(
+                            expr,
+                            physical_input_schema,
+                            SortOptions {
+                                descending: !*asc,
+                                nulls_first: *nulls_first,
+                            },
+                            ctx_state,
+                        ),
+                        _ => Err(DataFusionError::Plan(
+                            "Sort only accepts sort expressions".to_string(),
+                        )),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                windows::create_window_expr(
+                    fun,
+                    name,
+                    &args,
+                    &partition_by,
+                    &order_by,
+                    *window_frame,
+                    physical_input_schema,
+                )
+            }
+            other => Err(DataFusionError::Internal(format!(
+                "Invalid window expression '{:?}'",
+                other
+            ))),
+        }
+    }
+
     /// Create an aggregate expression from a logical expression
     pub fn create_aggregate_expr(
         &self,
@@ -672,16 +955,24 @@ impl DefaultPhysicalPlanner {
                 args,
                 ..
             } => {
+                // the ordering of the input values is given by the sort expressions
+                let (ordering, args): (Vec<Expr>, Vec<Expr>) = args
+                    .iter()
+                    .cloned()
+                    .partition(|e| matches!(e, Expr::Sort { .. }));
                 let args = args
                     .iter()
                     .map(|e| {
                         self.create_physical_expr(e, physical_input_schema, ctx_state)
                     })
                     .collect::<Result<Vec<_>>>()?;
-                aggregates::create_aggregate_expr(
+                let ordering =
+                    self.create_sort_exprs(&ordering, physical_input_schema, ctx_state)?;
+                aggregates::create_ordered_aggregate_expr(
                     fun,
                     *distinct,
                     &args,
+                    &ordering,
                     physical_input_schema,
                     name,
                 )
@@ -716,6 +1007,90 @@ impl DefaultPhysicalPlanner {
             options,
         })
     }
+
+    /// Create a plan sorting `input` on `sort_expr`
+    fn create_sort_plan(
+        &self,
+        sort_expr: Vec<PhysicalSortExpr>,
+        input: Arc<dyn ExecutionPlan>,
+        ctx_state: &ExecutionContextState,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let sort = SortExec::try_new(sort_expr.clone(), input.clone())?
+            .with_memory_limit(ctx_state.config.memory_limit);
+        if input.output_partitioning().partition_count() > 1 {
+            // sort the partitions in parallel and merge them
+            Ok(Arc::new(SortPreservingMergeExec::new(
+                sort_expr,
+                Arc::new(sort.with_preserve_partitioning(true)),
+                ctx_state.config.batch_size,
+            )))
+        } else {
+            Ok(Arc::new(sort))
+        }
+    }
+
+    /// Create the physical sort expressions of the `Expr::Sort` expressions `expr`
+    fn create_sort_exprs(
+        &self,
+        expr: &[Expr],
+        input_schema: &Schema,
+        ctx_state: &ExecutionContextState,
+    ) -> Result<Vec<PhysicalSortExpr>> {
+        expr.iter()
+            .map(|e| match e {
+                Expr::Sort {
+                    expr,
+                    asc,
+                    nulls_first,
+                } => self.create_physical_sort_expr(
+                    expr,
+                    input_schema,
+                    SortOptions {
+                        descending: !*asc,
+                        nulls_first: *nulls_first,
+                    },
+                    ctx_state,
+                ),
+                _ => Err(DataFusionError::Plan(
+                    "Sort only accepts sort expressions".to_string(),
+                )),
+            })
+            .collect()
+    }
+}
+
+/// Whether the first `k` rows of `input` sorted on `sort_expr` can be computed by a
+/// [TopKExec], that keeps them in memory for each partition instead of sorting all
+/// the rows, which may spill to disk
+fn use_top_k(
+    input: &Arc<dyn ExecutionPlan>,
+    sort_expr: &[PhysicalSortExpr],
+    k: usize,
+    ctx_state: &ExecutionContextState,
+) -> Result<bool> {
+    if k > ctx_state.config.top_k_rows {
+        return Ok(false);
+    }
+    let schema = input.schema();
+    for e in sort_expr {
+        if !sort_utils::supports_type(&e.expr.data_type(&schema)?) {
+            return Ok(false);
+        }
+    }
+    // the estimated size of the rows must be within the memory limit
+    let statistics = input.statistics();
+    Ok(
+        match (
+            ctx_state.config.memory_limit,
+            statistics.total_byte_size,
+            statistics.num_rows,
+        ) {
+            (Some(limit), Some(size), Some(num_rows)) if num_rows > 0 => {
+                (size / num_rows).saturating_mul(k) <= limit
+            }
+            _ => true,
+        },
+    )
 }
 
 fn tuple_err<T, R>(value: (Result<T>, Result<R>)) -> Result<(T, R)> {
@@ -730,9 +1105,12 @@ fn tuple_err<T, R>(value: (Result<T>, Result<R>)) -> Result<(T, R)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datasource::datasource::Statistics;
+    use crate::datasource::object_store::ObjectStoreRegistry;
     use crate::physical_plan::{csv::CsvReadOptions, expressions, Partitioning};
     use crate::prelude::ExecutionConfig;
     use crate::scalar::ScalarValue;
+    use crate::test;
     use crate::{
         catalog::catalog::MemoryCatalogList,
         logical_plan::{DFField, DFSchema, DFSchemaRef},
@@ -753,6 +1131,7 @@ mod tests {
             var_provider: HashMap::new(),
             aggregate_functions: HashMap::new(),
             config: ExecutionConfig::new(),
+            object_store_registry: Arc::new(ObjectStoreRegistry::new()),
         }
     }
 
@@ -787,6 +1166,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn limit_of_sort() -> Result<()> {
+        let logical_plan = LogicalPlanBuilder::from(&test::test_table_scan()?)
+            .sort(vec![col("a").sort(false, true)])?
+            .limit(3)?
+            .build()?;
+
+        let plan = plan(&logical_plan)?;
+        match plan.as_any().downcast_ref::<TopKExec>() {
+            Some(top_k) => {
+                assert_eq!(3, top_k.k());
+                assert!(top_k.expr()[0].options.descending);
+            }
+            None => panic!("limit of a sort should be planned as a TopKExec"),
+        }
+
+        // too many rows to be kept in memory
+        let mut ctx_state = make_ctx_state();
+        ctx_state.config = ExecutionConfig::new().with_top_k_rows(2);
+        let plan = DefaultPhysicalPlanner::default()
+            .create_physical_plan(&logical_plan, &ctx_state)?;
+        assert!(plan.as_any().downcast_ref::<GlobalLimitExec>().is_some());
+        assert!(format!("{:?}", plan).contains("SortExec"));
+        Ok(())
+    }
+
     #[test]
     fn test_create_not() -> Result<()> {
         let schema = Schema::new(vec![Field::new("a", DataType::Boolean, true)]);
@@ -1080,6 +1485,10 @@ mod tests {
         async fn execute(&self, _partition: usize) -> Result<SendableRecordBatchStream> {
             unimplemented!("NoOpExecutionPlan::execute");
         }
+
+        fn statistics(&self) -> Statistics {
+            unimplemented!("NoOpExecutionPlan::statistics");
+        }
     }
 
     //  Produces an execution plan where the schema is mismatched from
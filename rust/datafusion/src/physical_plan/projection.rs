@@ -25,7 +25,9 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use crate::datasource::datasource::Statistics;
 use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::Column;
 use crate::physical_plan::{ExecutionPlan, Partitioning, PhysicalExpr};
 use arrow::datatypes::{Field, Schema, SchemaRef};
 use arrow::error::Result as ArrowResult;
@@ -130,6 +132,29 @@ impl ExecutionPlan for ProjectionExec {
             input: self.input.execute(partition).await?,
         }))
     }
+
+    fn statistics(&self) -> Statistics {
+        let input_statistics = self.input.statistics();
+        let input_schema = self.input.schema();
+        // only columns that are projected as they are keep their statistics
+        let column_statistics = input_statistics.column_statistics.map(|input_columns| {
+            self.expr
+                .iter()
+                .map(|(e, _)| {
+                    e.as_any()
+                        .downcast_ref::<Column>()
+                        .and_then(|c| input_schema.index_of(c.name()).ok())
+                        .map(|i| input_columns[i].clone())
+                        .unwrap_or_default()
+                })
+                .collect()
+        });
+        Statistics {
+            num_rows: input_statistics.num_rows,
+            total_byte_size: None,
+            column_statistics,
+        }
+    }
 }
 
 fn batch_project(
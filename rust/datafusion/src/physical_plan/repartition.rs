@@ -23,6 +23,7 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::{any::Any, collections::HashMap, vec};
 
+use crate::datasource::datasource::Statistics;
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::{ExecutionPlan, Partitioning};
 use arrow::record_batch::RecordBatch;
@@ -235,6 +236,10 @@ impl ExecutionPlan for RepartitionExec {
             input: UnboundedReceiverStream::new(channels.remove(&partition).unwrap().1),
         }))
     }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
 }
 
 impl RepartitionExec {
@@ -0,0 +1,690 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Encoding of the rows of several columns to a compact, normalized sequence
+//! of bytes per row.
+//!
+//! The encodings of two rows of columns of the same types are equal exactly
+//! when the values of the rows are equal, null values being equal to each
+//! other. Rows can thus be hashed and compared as bytes, e.g. the group keys
+//! of a hash aggregate or the join keys of a hash join, whatever the types of
+//! their columns.
+//!
+//! Every value starts with a byte, `0` for a null value, which is followed by
+//! nothing else, and `1` for a valid value, which is followed by:
+//!
+//! * the bytes of the value for fixed width types, where floats are
+//!   normalized so that `-0.0` equals `0.0` and all the `NaN`s are equal
+//! * one byte for booleans
+//! * the number of bytes as a `u64`, followed by the bytes, for strings and
+//!   binaries
+//! * the number of elements as a `u64`, followed by the encoded elements, for
+//!   lists, fixed size lists omitting the number of elements
+//! * the encoded fields for structs
+//! * the encoded value it refers to for dictionaries
+
+use std::convert::TryInto;
+
+use arrow::array::{
+    make_array, new_null_array, ArrayData, ArrayRef, BooleanBufferBuilder,
+};
+use arrow::buffer::Buffer;
+use arrow::datatypes::{ArrowNativeType, DataType, Field, IntervalUnit};
+use arrow::util::bit_util;
+
+use crate::error::{DataFusionError, Result};
+
+/// The rows of several columns, encoded in the row format
+#[derive(Debug, Clone)]
+pub struct Rows {
+    /// The encoded rows, one after the other
+    data: Vec<u8>,
+    /// The start of every row in `data`, followed by the end of the last one
+    offsets: Vec<usize>,
+}
+
+impl Default for Rows {
+    fn default() -> Self {
+        Self {
+            data: vec![],
+            offsets: vec![0],
+        }
+    }
+}
+
+impl Rows {
+    /// Encodes the rows of `columns`, which must all have the same length
+    pub fn try_new(columns: &[ArrayRef]) -> Result<Self> {
+        let mut rows = Self::default();
+        rows.append(columns)?;
+        Ok(rows)
+    }
+
+    /// Encodes the rows of `columns`, which must all have the same length,
+    /// after the existing rows
+    pub fn append(&mut self, columns: &[ArrayRef]) -> Result<()> {
+        let num_rows = columns.first().map(|column| column.len()).unwrap_or(0);
+        self.data.reserve(num_rows * columns.len() * 9);
+        self.offsets.reserve(num_rows);
+        for row in 0..num_rows {
+            for column in columns {
+                encode_value(column.data(), row, &mut self.data)?;
+            }
+            self.offsets.push(self.data.len());
+        }
+        Ok(())
+    }
+
+    /// Returns the number of rows
+    pub fn num_rows(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns the encoding of the row `i`
+    pub fn row(&self, i: usize) -> &[u8] {
+        &self.data[self.offsets[i]..self.offsets[i + 1]]
+    }
+
+    /// Returns an iterator over the encodings of the rows
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.num_rows()).map(move |i| self.row(i))
+    }
+}
+
+/// Decodes `rows`, encoded from columns of types `data_types`, back to
+/// columns. Dictionaries are decoded to arrays of their value type.
+pub fn decode_rows<'a>(
+    rows: impl IntoIterator<Item = &'a [u8]>,
+    data_types: &[DataType],
+) -> Result<Vec<ArrayRef>> {
+    let mut decoders = data_types
+        .iter()
+        .map(Decoder::try_new)
+        .collect::<Result<Vec<_>>>()?;
+    for mut row in rows {
+        for decoder in decoders.iter_mut() {
+            decoder.decode_value(&mut row)?;
+        }
+        if !row.is_empty() {
+            return Err(DataFusionError::Internal(
+                "Unexpected trailing bytes decoding a row".to_string(),
+            ));
+        }
+    }
+    decoders
+        .into_iter()
+        .map(|decoder| Ok(make_array(decoder.finish())))
+        .collect()
+}
+
+/// Appends the encoding of the value at index `i` of `data` to `out`
+fn encode_value(data: &ArrayData, i: usize, out: &mut Vec<u8>) -> Result<()> {
+    let data_type = data.data_type();
+    if matches!(data_type, DataType::Null) || data.is_null(i) {
+        out.push(0);
+        return Ok(());
+    }
+    out.push(1);
+
+    let index = data.offset() + i;
+    match data_type {
+        DataType::Boolean => {
+            let values = data.buffers()[0].as_slice();
+            out.push(bit_util::get_bit(values, index) as u8);
+        }
+        DataType::Float32 => {
+            let value =
+                f32::from_ne_bytes(fixed_width_value(data, index, 4).try_into().unwrap());
+            let value = if value.is_nan() {
+                f32::NAN
+            } else if value == 0.0 {
+                0.0
+            } else {
+                value
+            };
+            out.extend_from_slice(&value.to_ne_bytes());
+        }
+        DataType::Float64 => {
+            let value =
+                f64::from_ne_bytes(fixed_width_value(data, index, 8).try_into().unwrap());
+            let value = if value.is_nan() {
+                f64::NAN
+            } else if value == 0.0 {
+                0.0
+            } else {
+                value
+            };
+            out.extend_from_slice(&value.to_ne_bytes());
+        }
+        DataType::Utf8
+        | DataType::Binary
+        | DataType::LargeUtf8
+        | DataType::LargeBinary => {
+            let large = matches!(data_type, DataType::LargeUtf8 | DataType::LargeBinary);
+            let start = offset_at(&data.buffers()[0], index, large);
+            let end = offset_at(&data.buffers()[0], index + 1, large);
+            out.extend_from_slice(&((end - start) as u64).to_le_bytes());
+            out.extend_from_slice(&data.buffers()[1].as_slice()[start..end]);
+        }
+        DataType::List(_) | DataType::LargeList(_) => {
+            let large = matches!(data_type, DataType::LargeList(_));
+            let start = offset_at(&data.buffers()[0], index, large);
+            let end = offset_at(&data.buffers()[0], index + 1, large);
+            out.extend_from_slice(&((end - start) as u64).to_le_bytes());
+            for j in start..end {
+                encode_value(&data.child_data()[0], j, out)?;
+            }
+        }
+        DataType::FixedSizeList(_, size) => {
+            let size = *size as usize;
+            for j in index * size..(index + 1) * size {
+                encode_value(&data.child_data()[0], j, out)?;
+            }
+        }
+        DataType::Struct(_) => {
+            for field in data.child_data() {
+                encode_value(field, index, out)?;
+            }
+        }
+        DataType::Dictionary(key_type, _) => {
+            let key = dictionary_key(data, key_type, index)?;
+            encode_value(&data.child_data()[0], key, out)?;
+        }
+        data_type => {
+            let width = fixed_width(data_type)?;
+            out.extend_from_slice(fixed_width_value(data, index, width));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the width of the values of the fixed width type `data_type`
+fn fixed_width(data_type: &DataType) -> Result<usize> {
+    Ok(match data_type {
+        DataType::Int8 | DataType::UInt8 => 1,
+        DataType::Int16 | DataType::UInt16 => 2,
+        DataType::Int32
+        | DataType::UInt32
+        | DataType::Float32
+        | DataType::Date32
+        | DataType::Time32(_)
+        | DataType::Interval(IntervalUnit::YearMonth) => 4,
+        DataType::Int64
+        | DataType::UInt64
+        | DataType::Float64
+        | DataType::Date64
+        | DataType::Time64(_)
+        | DataType::Timestamp(_, _)
+        | DataType::Duration(_)
+        | DataType::Interval(IntervalUnit::DayTime) => 8,
+        DataType::Decimal(_, _) => 16,
+        DataType::FixedSizeBinary(width) => *width as usize,
+        _ => {
+            return Err(DataFusionError::NotImplemented(format!(
+                "Row format not supported for type {}",
+                data_type
+            )))
+        }
+    })
+}
+
+/// Returns the bytes of the value at `index` of the fixed width array `data`
+fn fixed_width_value(data: &ArrayData, index: usize, width: usize) -> &[u8] {
+    &data.buffers()[0].as_slice()[index * width..(index + 1) * width]
+}
+
+/// Returns the `i`th offset of the `i32`, or `i64` when `large`, offsets of
+/// `buffer`
+fn offset_at(buffer: &Buffer, i: usize, large: bool) -> usize {
+    let bytes = buffer.as_slice();
+    if large {
+        i64::from_ne_bytes(bytes[i * 8..(i + 1) * 8].try_into().unwrap()) as usize
+    } else {
+        i32::from_ne_bytes(bytes[i * 4..(i + 1) * 4].try_into().unwrap()) as usize
+    }
+}
+
+/// Returns the index in the values of the dictionary `data` of its value at
+/// `index`
+fn dictionary_key(data: &ArrayData, key_type: &DataType, index: usize) -> Result<usize> {
+    let keys = data.buffers()[0].as_slice();
+    macro_rules! key {
+        ($native_type:ty) => {{
+            let width = std::mem::size_of::<$native_type>();
+            <$native_type>::from_ne_bytes(
+                keys[index * width..(index + 1) * width].try_into().unwrap(),
+            )
+            .to_usize()
+        }};
+    }
+    let key = match key_type {
+        DataType::Int8 => key!(i8),
+        DataType::Int16 => key!(i16),
+        DataType::Int32 => key!(i32),
+        DataType::Int64 => key!(i64),
+        DataType::UInt8 => key!(u8),
+        DataType::UInt16 => key!(u16),
+        DataType::UInt32 => key!(u32),
+        DataType::UInt64 => key!(u64),
+        _ => {
+            return Err(DataFusionError::Internal(format!(
+                "Unsupported dictionary key type {}",
+                key_type
+            )))
+        }
+    };
+    key.ok_or_else(|| {
+        DataFusionError::Internal(format!(
+            "Can not convert index to usize in dictionary of type {}",
+            data.data_type()
+        ))
+    })
+}
+
+/// Removes the first `n` bytes of `row` and returns them
+fn take_bytes<'a>(row: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {
+    if row.len() < n {
+        return Err(DataFusionError::Internal(
+            "Unexpected end of row decoding a value".to_string(),
+        ));
+    }
+    let (bytes, rest) = row.split_at(n);
+    *row = rest;
+    Ok(bytes)
+}
+
+/// Removes the length that prefixes strings, binaries and lists from `row`
+/// and returns it
+fn take_length(row: &mut &[u8]) -> Result<usize> {
+    Ok(u64::from_le_bytes(take_bytes(row, 8)?.try_into().unwrap()) as usize)
+}
+
+/// Builds the array of a column from the encoded values of its rows
+struct Decoder {
+    /// The type of the decoded array, without dictionaries
+    data_type: DataType,
+    /// The number of decoded values
+    len: usize,
+    /// The validity of the decoded values
+    validity: BooleanBufferBuilder,
+    /// The number of decoded null values
+    null_count: usize,
+    /// The bytes of the fixed width values, strings and binaries, or one byte
+    /// per boolean
+    values: Vec<u8>,
+    /// The offsets of the strings, binaries and lists
+    offsets: Vec<usize>,
+    /// The decoders of the elements of lists and of the fields of structs
+    children: Vec<Decoder>,
+}
+
+impl Decoder {
+    fn try_new(data_type: &DataType) -> Result<Self> {
+        let with_type = |field: &Field, child: &Decoder| {
+            Box::new(Field::new(
+                field.name(),
+                child.data_type.clone(),
+                field.is_nullable(),
+            ))
+        };
+        let (data_type, children) = match data_type {
+            DataType::Dictionary(_, value_type) => return Self::try_new(value_type),
+            DataType::List(field) => {
+                let child = Self::try_new(field.data_type())?;
+                (DataType::List(with_type(field, &child)), vec![child])
+            }
+            DataType::LargeList(field) => {
+                let child = Self::try_new(field.data_type())?;
+                (DataType::LargeList(with_type(field, &child)), vec![child])
+            }
+            DataType::FixedSizeList(field, size) => {
+                let child = Self::try_new(field.data_type())?;
+                (
+                    DataType::FixedSizeList(with_type(field, &child), *size),
+                    vec![child],
+                )
+            }
+            DataType::Struct(fields) => {
+                let children = fields
+                    .iter()
+                    .map(|field| Self::try_new(field.data_type()))
+                    .collect::<Result<Vec<_>>>()?;
+                let fields = fields
+                    .iter()
+                    .zip(children.iter())
+                    .map(|(field, child)| *with_type(field, child))
+                    .collect();
+                (DataType::Struct(fields), children)
+            }
+            DataType::Union(_) => {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Row format not supported for type {}",
+                    data_type
+                )))
+            }
+            data_type => (data_type.clone(), vec![]),
+        };
+        Ok(Self {
+            data_type,
+            len: 0,
+            validity: BooleanBufferBuilder::new(0),
+            null_count: 0,
+            values: vec![],
+            offsets: vec![0],
+            children,
+        })
+    }
+
+    /// Decodes the value at the start of `row`, and removes it from `row`
+    fn decode_value(&mut self, row: &mut &[u8]) -> Result<()> {
+        if take_bytes(row, 1)?[0] == 0 {
+            self.append_null()?;
+            return Ok(());
+        }
+        self.len += 1;
+        self.validity.append(true);
+
+        match &self.data_type {
+            DataType::Boolean => self.values.push(take_bytes(row, 1)?[0]),
+            DataType::Utf8
+            | DataType::Binary
+            | DataType::LargeUtf8
+            | DataType::LargeBinary => {
+                let length = take_length(row)?;
+                self.values.extend_from_slice(take_bytes(row, length)?);
+                self.offsets.push(self.values.len());
+            }
+            DataType::List(_) | DataType::LargeList(_) => {
+                let length = take_length(row)?;
+                for _ in 0..length {
+                    self.children[0].decode_value(row)?;
+                }
+                self.offsets.push(self.children[0].len);
+            }
+            DataType::FixedSizeList(_, size) => {
+                for _ in 0..*size {
+                    self.children[0].decode_value(row)?;
+                }
+            }
+            DataType::Struct(_) => {
+                for child in self.children.iter_mut() {
+                    child.decode_value(row)?;
+                }
+            }
+            DataType::Null => {
+                return Err(DataFusionError::Internal(
+                    "Unexpected valid value decoding a row of type Null".to_string(),
+                ))
+            }
+            data_type => {
+                let width = fixed_width(data_type)?;
+                self.values.extend_from_slice(take_bytes(row, width)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends a null value, along with the placeholders of its value
+    fn append_null(&mut self) -> Result<()> {
+        self.len += 1;
+        self.null_count += 1;
+        self.validity.append(false);
+
+        match &self.data_type {
+            DataType::Null => {}
+            DataType::Boolean => self.values.push(0),
+            DataType::Utf8
+            | DataType::Binary
+            | DataType::LargeUtf8
+            | DataType::LargeBinary => self.offsets.push(self.values.len()),
+            DataType::List(_) | DataType::LargeList(_) => {
+                self.offsets.push(self.children[0].len)
+            }
+            DataType::FixedSizeList(_, size) => {
+                for _ in 0..*size {
+                    self.children[0].append_null()?;
+                }
+            }
+            DataType::Struct(_) => {
+                for child in self.children.iter_mut() {
+                    child.append_null()?;
+                }
+            }
+            data_type => {
+                let width = fixed_width(data_type)?;
+                self.values.resize(self.values.len() + width, 0);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the array of the decoded values
+    fn finish(self) -> ArrayData {
+        let Decoder {
+            data_type,
+            len,
+            mut validity,
+            null_count,
+            values,
+            offsets,
+            children,
+        } = self;
+
+        let offsets_buffer = |large: bool| {
+            if large {
+                let offsets = offsets.iter().map(|o| *o as i64).collect::<Vec<_>>();
+                Buffer::from_slice_ref(&offsets)
+            } else {
+                let offsets = offsets.iter().map(|o| *o as i32).collect::<Vec<_>>();
+                Buffer::from_slice_ref(&offsets)
+            }
+        };
+
+        let mut builder = ArrayData::builder(data_type.clone()).len(len);
+        if null_count > 0 {
+            builder = builder.null_bit_buffer(validity.finish());
+        }
+        let builder = match &data_type {
+            DataType::Null => return new_null_array(&DataType::Null, len).data().clone(),
+            DataType::Boolean => {
+                let mut bits = BooleanBufferBuilder::new(len);
+                values.iter().for_each(|value| bits.append(*value == 1));
+                builder.add_buffer(bits.finish())
+            }
+            DataType::Utf8 | DataType::Binary => builder
+                .add_buffer(offsets_buffer(false))
+                .add_buffer(Buffer::from(values)),
+            DataType::LargeUtf8 | DataType::LargeBinary => builder
+                .add_buffer(offsets_buffer(true))
+                .add_buffer(Buffer::from(values)),
+            DataType::List(_) | DataType::LargeList(_) => {
+                let large = matches!(data_type, DataType::LargeList(_));
+                builder
+                    .add_buffer(offsets_buffer(large))
+                    .child_data(children.into_iter().map(Decoder::finish).collect())
+            }
+            DataType::FixedSizeList(_, _) | DataType::Struct(_) => {
+                builder.child_data(children.into_iter().map(Decoder::finish).collect())
+            }
+            _ => builder.add_buffer(Buffer::from(values)),
+        };
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{
+        Array, BinaryArray, BooleanArray, Date64Array, DecimalBuilder, DictionaryArray,
+        FixedSizeBinaryArray, Float64Array, Int32Array, Int64Builder, LargeStringArray,
+        ListBuilder, StringArray, StructArray, TimestampNanosecondArray, UInt8Array,
+    };
+    use arrow::datatypes::Int8Type;
+    use std::sync::Arc;
+
+    /// Asserts that decoding the rows of `columns` returns `columns`
+    fn assert_round_trip(columns: Vec<ArrayRef>) -> Result<()> {
+        let rows = Rows::try_new(&columns)?;
+        assert_eq!(rows.num_rows(), columns[0].len());
+        let data_types = columns
+            .iter()
+            .map(|column| column.data_type().clone())
+            .collect::<Vec<_>>();
+        let decoded = decode_rows(rows.iter(), &data_types)?;
+        for (column, decoded) in columns.iter().zip(decoded.iter()) {
+            assert_eq!(column.data_type(), decoded.data_type());
+            assert_eq!(column.len(), decoded.len());
+            for i in 0..column.len() {
+                assert_eq!(column.is_null(i), decoded.is_null(i));
+            }
+            // compares the values through their encoding
+            assert_eq!(
+                Rows::try_new(&[column.clone()])?.data,
+                Rows::try_new(&[decoded.clone()])?.data
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn equal_rows() -> Result<()> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![
+                Some(1),
+                Some(1),
+                None,
+                None,
+                Some(1),
+            ])),
+            Arc::new(StringArray::from(vec![
+                Some("a"),
+                Some("a"),
+                None,
+                None,
+                Some("b"),
+            ])),
+        ];
+        let rows = Rows::try_new(&columns)?;
+        assert_eq!(rows.num_rows(), 5);
+        assert_eq!(rows.row(0), rows.row(1));
+        assert_eq!(rows.row(2), rows.row(3));
+        assert_ne!(rows.row(0), rows.row(2));
+        assert_ne!(rows.row(0), rows.row(4));
+        Ok(())
+    }
+
+    #[test]
+    fn variable_length_values_are_delimited() -> Result<()> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["ab", "a"])),
+            Arc::new(StringArray::from(vec!["c", "bc"])),
+        ];
+        let rows = Rows::try_new(&columns)?;
+        assert_ne!(rows.row(0), rows.row(1));
+        Ok(())
+    }
+
+    #[test]
+    fn normalized_floats() -> Result<()> {
+        let column: ArrayRef = Arc::new(Float64Array::from(vec![
+            0.0,
+            -0.0,
+            f64::NAN,
+            -f64::NAN,
+            1.0,
+        ]));
+        let rows = Rows::try_new(&[column])?;
+        assert_eq!(rows.row(0), rows.row(1));
+        assert_eq!(rows.row(2), rows.row(3));
+        assert_ne!(rows.row(0), rows.row(4));
+        Ok(())
+    }
+
+    #[test]
+    fn dictionary_is_encoded_as_its_values() -> Result<()> {
+        let dictionary: DictionaryArray<Int8Type> =
+            vec!["b", "a", "b"].into_iter().collect();
+        let strings = StringArray::from(vec!["b", "a", "b"]);
+        assert_eq!(
+            Rows::try_new(&[Arc::new(dictionary) as ArrayRef])?.data,
+            Rows::try_new(&[Arc::new(strings) as ArrayRef])?.data
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sliced_arrays() -> Result<()> {
+        let column = StringArray::from(vec![Some("a"), None, Some("b"), Some("c")]);
+        let sliced = column.slice(1, 3);
+        let expected: ArrayRef =
+            Arc::new(StringArray::from(vec![None, Some("b"), Some("c")]));
+        assert_eq!(
+            Rows::try_new(&[sliced])?.data,
+            Rows::try_new(&[expected])?.data
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let mut decimals = DecimalBuilder::new(3, 10, 2);
+        decimals.append_value(100)?;
+        decimals.append_null()?;
+        decimals.append_value(-1)?;
+
+        let mut lists = ListBuilder::new(Int64Builder::new(0));
+        lists.values().append_value(1)?;
+        lists.values().append_null()?;
+        lists.append(true)?;
+        lists.append(false)?;
+        lists.append(true)?;
+
+        let structs = StructArray::from(vec![
+            (
+                Field::new("a", DataType::Boolean, true),
+                Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)]))
+                    as ArrayRef,
+            ),
+            (
+                Field::new("b", DataType::UInt8, false),
+                Arc::new(UInt8Array::from(vec![1, 2, 3])) as ArrayRef,
+            ),
+        ]);
+
+        assert_round_trip(vec![
+            Arc::new(Date64Array::from(vec![Some(1), None, Some(3)])),
+            Arc::new(TimestampNanosecondArray::from_opt_vec(
+                vec![Some(1), Some(2), None],
+                Some("+00:00".to_string()),
+            )),
+            Arc::new(LargeStringArray::from(vec![Some("a"), None, Some("")])),
+            Arc::new(BinaryArray::from(vec![
+                b"a".as_ref(),
+                b"bc".as_ref(),
+                b"".as_ref(),
+            ])),
+            Arc::new(FixedSizeBinaryArray::try_from_iter(
+                vec![vec![1u8, 2], vec![3, 4], vec![5, 6]].into_iter(),
+            )?),
+            Arc::new(decimals.finish()),
+            Arc::new(lists.finish()),
+            Arc::new(structs),
+            new_null_array(&DataType::Null, 3),
+        ])
+    }
+}
@@ -22,22 +22,28 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use futures::channel::mpsc;
 use futures::stream::Stream;
-use futures::Future;
+use futures::{SinkExt, StreamExt};
 
 use pin_project_lite::pin_project;
 
+use arrow::array::ArrayRef;
 pub use arrow::compute::SortOptions;
 use arrow::compute::{concat, lexsort_to_indices, take, SortColumn, TakeOptions};
 use arrow::datatypes::SchemaRef;
 use arrow::error::Result as ArrowResult;
 use arrow::record_batch::RecordBatch;
-use arrow::{array::ArrayRef, error::ArrowError};
 
 use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::datasource::datasource::Statistics;
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::expressions::PhysicalSortExpr;
-use crate::physical_plan::{common, Distribution, ExecutionPlan, Partitioning};
+use crate::physical_plan::memory::MemoryStream;
+use crate::physical_plan::spill::{
+    batch_memory_size, merge_sorted_runs, spill, SortedRun,
+};
+use crate::physical_plan::{Distribution, ExecutionPlan, Partitioning};
 
 use async_trait::async_trait;
 
@@ -48,6 +54,11 @@ pub struct SortExec {
     input: Arc<dyn ExecutionPlan>,
     /// Sort expressions
     expr: Vec<PhysicalSortExpr>,
+    /// Number of bytes of buffered input above which sorted runs are spilled to disk
+    memory_limit: Option<usize>,
+    /// Sort each partition of the input separately instead of requiring a single
+    /// input partition
+    preserve_partitioning: bool,
 }
 
 impl SortExec {
@@ -56,7 +67,25 @@ impl SortExec {
         expr: Vec<PhysicalSortExpr>,
         input: Arc<dyn ExecutionPlan>,
     ) -> Result<Self> {
-        Ok(Self { expr, input })
+        Ok(Self {
+            expr,
+            input,
+            memory_limit: None,
+            preserve_partitioning: false,
+        })
+    }
+
+    /// Spill sorted runs to disk when the buffered input exceeds `memory_limit` bytes
+    pub fn with_memory_limit(mut self, memory_limit: Option<usize>) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// Sort each partition of the input separately, e.g. to merge them with a
+    /// `SortPreservingMergeExec`, instead of requiring a single input partition
+    pub fn with_preserve_partitioning(mut self, preserve_partitioning: bool) -> Self {
+        self.preserve_partitioning = preserve_partitioning;
+        self
     }
 
     /// Input schema
@@ -68,6 +97,16 @@ impl SortExec {
     pub fn expr(&self) -> &[PhysicalSortExpr] {
         &self.expr
     }
+
+    /// Number of bytes of buffered input above which sorted runs are spilled to disk
+    pub fn memory_limit(&self) -> Option<usize> {
+        self.memory_limit
+    }
+
+    /// Whether each partition of the input is sorted separately
+    pub fn preserve_partitioning(&self) -> bool {
+        self.preserve_partitioning
+    }
 }
 
 #[async_trait]
@@ -87,11 +126,19 @@ impl ExecutionPlan for SortExec {
 
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(1)
+        if self.preserve_partitioning {
+            self.input.output_partitioning()
+        } else {
+            Partitioning::UnknownPartitioning(1)
+        }
     }
 
     fn required_child_distribution(&self) -> Distribution {
-        Distribution::SinglePartition
+        if self.preserve_partitioning {
+            Distribution::UnspecifiedDistribution
+        } else {
+            Distribution::SinglePartition
+        }
     }
 
     fn with_new_children(
@@ -99,10 +146,11 @@ impl ExecutionPlan for SortExec {
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         match children.len() {
-            1 => Ok(Arc::new(SortExec::try_new(
-                self.expr.clone(),
-                children[0].clone(),
-            )?)),
+            1 => Ok(Arc::new(
+                SortExec::try_new(self.expr.clone(), children[0].clone())?
+                    .with_memory_limit(self.memory_limit)
+                    .with_preserve_partitioning(self.preserve_partitioning),
+            )),
             _ => Err(DataFusionError::Internal(
                 "SortExec wrong number of children".to_string(),
             )),
@@ -110,29 +158,42 @@ impl ExecutionPlan for SortExec {
     }
 
     async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
-        if 0 != partition {
-            return Err(DataFusionError::Internal(format!(
-                "SortExec invalid partition {}",
-                partition
-            )));
-        }
+        if !self.preserve_partitioning {
+            if 0 != partition {
+                return Err(DataFusionError::Internal(format!(
+                    "SortExec invalid partition {}",
+                    partition
+                )));
+            }
 
-        // sort needs to operate on a single partition currently
-        if 1 != self.input.output_partitioning().partition_count() {
-            return Err(DataFusionError::Internal(
-                "SortExec requires a single input partition".to_owned(),
-            ));
+            // sort needs to operate on a single partition currently
+            if 1 != self.input.output_partitioning().partition_count() {
+                return Err(DataFusionError::Internal(
+                    "SortExec requires a single input partition".to_owned(),
+                ));
+            }
         }
-        let input = self.input.execute(0).await?;
+        let input = self.input.execute(partition).await?;
 
-        Ok(Box::pin(SortStream::new(input, self.expr.clone())))
+        Ok(Box::pin(SortStream::new(
+            input,
+            self.expr.clone(),
+            self.memory_limit,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
     }
 }
 
-fn sort_batches(
+/// Sorts `batches` into a single batch, keeping only the first `limit` rows if
+/// a limit is given
+pub(crate) fn sort_batches(
     batches: &[RecordBatch],
     schema: &SchemaRef,
     expr: &[PhysicalSortExpr],
+    limit: Option<usize>,
 ) -> ArrowResult<Option<RecordBatch>> {
     if batches.is_empty() {
         return Ok(None);
@@ -156,14 +217,13 @@ fn sort_batches(
     )?;
 
     // sort combined record batch
-    // TODO: pushup the limit expression to sort
     let indices = lexsort_to_indices(
         &expr
             .iter()
             .map(|e| e.evaluate_to_sort_column(&combined_batch))
             .collect::<Result<Vec<SortColumn>>>()
             .map_err(DataFusionError::into_arrow_external_error)?,
-        None,
+        limit,
     )?;
 
     // reorder all rows based on sorted indices
@@ -188,35 +248,89 @@ fn sort_batches(
     sorted_batch.map(Some)
 }
 
+/// Sorts the batches of `input`. When the batches buffered in memory exceed
+/// `memory_limit` bytes, they are sorted and spilled to disk, and the sorted
+/// runs are merged back once the input is exhausted.
+async fn sort_stream(
+    mut input: SendableRecordBatchStream,
+    expr: Vec<PhysicalSortExpr>,
+    memory_limit: Option<usize>,
+    sender: &mut mpsc::Sender<ArrowResult<RecordBatch>>,
+) -> ArrowResult<()> {
+    let schema = input.schema();
+    let mut batches = vec![];
+    let mut batches_size = 0;
+    let mut max_rows = 0;
+    let mut runs: Vec<SortedRun> = vec![];
+
+    while let Some(batch) = input.next().await {
+        let batch = batch?;
+        batches_size += batch_memory_size(&batch);
+        max_rows = max_rows.max(batch.num_rows());
+        batches.push(batch);
+
+        if matches!(memory_limit, Some(limit) if batches_size > limit) {
+            if let Some(sorted) = sort_batches(&batches, &schema, &expr, None)? {
+                runs.push(
+                    spill(sorted, max_rows)
+                        .await
+                        .map_err(DataFusionError::into_arrow_external_error)?,
+                );
+            }
+            batches.clear();
+            batches_size = 0;
+        }
+    }
+
+    let sorted = sort_batches(&batches, &schema, &expr, None)?;
+    if runs.is_empty() {
+        if let Some(sorted) = sorted {
+            sender.send(Ok(sorted)).await.ok();
+        }
+        return Ok(());
+    }
+
+    if let Some(sorted) = sorted {
+        runs.push(Box::pin(
+            MemoryStream::try_new(vec![sorted], schema.clone(), None)
+                .map_err(DataFusionError::into_arrow_external_error)?,
+        ));
+    }
+    let mut merged = merge_sorted_runs(runs, expr, schema, max_rows);
+    while let Some(batch) = merged.next().await {
+        // If send fails, plan being torn down, there is no place to send
+        // the rest of the batches
+        if sender.send(batch).await.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
 pin_project! {
     struct SortStream {
         #[pin]
-        output: futures::channel::oneshot::Receiver<ArrowResult<Option<RecordBatch>>>,
-        finished: bool,
+        output: mpsc::Receiver<ArrowResult<RecordBatch>>,
         schema: SchemaRef,
     }
 }
 
 impl SortStream {
-    fn new(input: SendableRecordBatchStream, expr: Vec<PhysicalSortExpr>) -> Self {
-        let (tx, rx) = futures::channel::oneshot::channel();
+    fn new(
+        input: SendableRecordBatchStream,
+        expr: Vec<PhysicalSortExpr>,
+        memory_limit: Option<usize>,
+    ) -> Self {
+        let (mut tx, rx) = mpsc::channel(1);
 
         let schema = input.schema();
         tokio::spawn(async move {
-            let schema = input.schema();
-            let sorted_batch = common::collect(input)
-                .await
-                .map_err(DataFusionError::into_arrow_external_error)
-                .and_then(move |batches| sort_batches(&batches, &schema, &expr));
-
-            tx.send(sorted_batch)
+            if let Err(e) = sort_stream(input, expr, memory_limit, &mut tx).await {
+                tx.send(Err(e)).await.ok();
+            }
         });
 
-        Self {
-            output: rx,
-            finished: false,
-            schema,
-        }
+        Self { output: rx, schema }
     }
 }
 
@@ -224,27 +338,7 @@ impl Stream for SortStream {
     type Item = ArrowResult<RecordBatch>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if self.finished {
-            return Poll::Ready(None);
-        }
-
-        // is the output ready?
-        let this = self.project();
-        let output_poll = this.output.poll(cx);
-
-        match output_poll {
-            Poll::Ready(result) => {
-                *this.finished = true;
-
-                // check for error in receiving channel and unwrap actual result
-                let result = match result {
-                    Err(e) => Some(Err(ArrowError::ExternalError(Box::new(e)))), // error receiving
-                    Ok(result) => result.transpose(),
-                };
-                Poll::Ready(result)
-            }
-            Poll::Pending => Poll::Pending,
-        }
+        self.project().output.poll_next(cx)
     }
 }
 
@@ -422,4 +516,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_sort_spill() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+
+        // 10 batches of 100 rows, with duplicates and nulls
+        let values = (0..1000)
+            .map(|i| Some((i * 7919) % 300).filter(|v| v % 17 != 0))
+            .collect::<Vec<_>>();
+        let batches = values
+            .chunks(100)
+            .map(|chunk| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from(chunk.to_vec())),
+                        Arc::new(
+                            chunk
+                                .iter()
+                                .map(|v| Some(format!("{:?}", v)))
+                                .collect::<StringArray>(),
+                        ),
+                    ],
+                )
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+
+        let sort_exec = Arc::new(
+            SortExec::try_new(
+                vec![PhysicalSortExpr {
+                    expr: col("a"),
+                    options: SortOptions {
+                        descending: true,
+                        nulls_first: false,
+                    },
+                }],
+                Arc::new(MemoryExec::try_new(&[batches], schema, None)?),
+            )?
+            // spill every batch
+            .with_memory_limit(Some(1)),
+        );
+
+        let result: Vec<RecordBatch> = collect(sort_exec).await?;
+        assert!(result.len() > 1);
+
+        let actual = result
+            .iter()
+            .flat_map(|batch| {
+                let a = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                let b = as_string_array(batch.column(1));
+                (0..batch.num_rows())
+                    .map(|i| {
+                        let v = Some(a.value(i)).filter(|_| a.is_valid(i));
+                        assert_eq!(b.value(i), format!("{:?}", v));
+                        v
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut expected = values;
+        expected.sort_by(|a, b| match (a, b) {
+            (Some(a), Some(b)) => b.cmp(a),
+            _ => b.is_some().cmp(&a.is_some()),
+        });
+        assert_eq!(expected, actual);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,866 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the sort-merge join plan, which joins two inputs sorted on the
+//! join keys while holding in memory only the rows of the current key
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc;
+use futures::stream::Stream;
+use futures::{SinkExt, StreamExt};
+
+use pin_project_lite::pin_project;
+
+use arrow::array::{new_null_array, ArrayRef, UInt32Array, UInt64Array};
+use arrow::compute::{concat, SortOptions};
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+
+use super::expressions::col;
+use super::hash_join::{
+    build_batch_from_indices, coalesced_keys, column_indices_from_schema, ColumnIndex,
+};
+use super::hash_utils::{
+    build_join_schema, check_join_is_valid, estimate_join_statistics, JoinOn, JoinType,
+};
+use super::sort_utils::{compare_rows, supports_type};
+use super::{
+    Distribution, ExecutionPlan, Partitioning, RecordBatchStream,
+    SendableRecordBatchStream,
+};
+use crate::datasource::datasource::Statistics;
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::coalesce_batches::concat_batches;
+
+/// Join execution plan that merges two inputs sorted on the join keys. Unlike
+/// [HashJoinExec](super::hash_join::HashJoinExec), it does not materialize
+/// any of its sides: only the rows that share the current join key are kept
+/// in memory.
+#[derive(Debug)]
+pub struct SortMergeJoinExec {
+    /// left side, sorted on the left join columns
+    left: Arc<dyn ExecutionPlan>,
+    /// right side, sorted on the right join columns
+    right: Arc<dyn ExecutionPlan>,
+    /// Set of common columns used to join on
+    on: Vec<(String, String)>,
+    /// How the join is performed
+    join_type: JoinType,
+    /// How both sides are sorted on each of the join columns
+    sort_options: Vec<SortOptions>,
+    /// Whether null values of the join columns match each other
+    null_equals_null: bool,
+    /// Number of rows of the batches produced by the join
+    batch_size: usize,
+    /// The schema once the join is applied
+    schema: SchemaRef,
+}
+
+impl SortMergeJoinExec {
+    /// Tries to create a new [SortMergeJoinExec]. Both sides must be sorted
+    /// on their join columns with the same `sort_options`.
+    /// # Error
+    /// This function errors when it is not possible to join the left and right sides on keys `on`.
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: &JoinOn,
+        join_type: &JoinType,
+        sort_options: Vec<SortOptions>,
+        null_equals_null: bool,
+        batch_size: usize,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if sort_options.len() != on.len() {
+            return Err(DataFusionError::Plan(format!(
+                "Sort-merge join on {} columns requires as many sort options, got {}",
+                on.len(),
+                sort_options.len()
+            )));
+        }
+
+        let schema = Arc::new(build_join_schema(
+            &left_schema,
+            &right_schema,
+            on,
+            &join_type,
+        ));
+
+        let on = on
+            .iter()
+            .map(|(l, r)| (l.to_string(), r.to_string()))
+            .collect();
+
+        Ok(SortMergeJoinExec {
+            left,
+            right,
+            on,
+            join_type: *join_type,
+            sort_options,
+            null_equals_null,
+            batch_size,
+            schema,
+        })
+    }
+
+    /// left side, sorted on the left join columns
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
+
+    /// right side, sorted on the right join columns
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
+    }
+
+    /// Set of common columns used to join on
+    pub fn on(&self) -> &[(String, String)] {
+        &self.on
+    }
+
+    /// How the join is performed
+    pub fn join_type(&self) -> &JoinType {
+        &self.join_type
+    }
+
+    /// How both sides are sorted on each of the join columns
+    pub fn sort_options(&self) -> &[SortOptions] {
+        &self.sort_options
+    }
+
+    /// Whether null values of the join columns match each other
+    pub fn null_equals_null(&self) -> bool {
+        self.null_equals_null
+    }
+
+    /// Number of rows of the batches produced by the join
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for SortMergeJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            2 => Ok(Arc::new(SortMergeJoinExec::try_new(
+                children[0].clone(),
+                children[1].clone(),
+                &self.on,
+                &self.join_type,
+                self.sort_options.clone(),
+                self.null_equals_null,
+                self.batch_size,
+            )?)),
+            _ => Err(DataFusionError::Internal(
+                "SortMergeJoinExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::SinglePartition
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "SortMergeJoinExec invalid partition {}",
+                partition
+            )));
+        }
+
+        let left = SortedInput::new(
+            self.left.execute(0).await?,
+            self.on.iter().map(|on| on.0.clone()).collect(),
+        );
+        let right = SortedInput::new(
+            self.right.execute(0).await?,
+            self.on.iter().map(|on| on.1.clone()).collect(),
+        );
+        let output = JoinOutput {
+            schema: self.schema.clone(),
+            join_type: self.join_type,
+            column_indices: column_indices_from_schema(
+                self.join_type,
+                &self.left.schema(),
+                &self.right.schema(),
+                &self.schema,
+            )?,
+            coalesced_keys: coalesced_keys(
+                &self.on,
+                self.join_type,
+                &self.schema,
+                &self.right.schema(),
+            )?,
+            batch_size: self.batch_size,
+            batches: vec![],
+            num_rows: 0,
+        };
+
+        Ok(Box::pin(SortMergeJoinStream::new(
+            left,
+            right,
+            output,
+            self.sort_options.clone(),
+            self.null_equals_null,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        estimate_join_statistics(
+            (&self.left.schema(), self.left.statistics()),
+            (&self.right.schema(), self.right.statistics()),
+            &self.on,
+            &self.join_type,
+        )
+    }
+}
+
+/// Consecutive rows of one side of the join that have the same keys. As the
+/// rows may span several batches, they are kept as ranges of these batches.
+struct KeyGroup {
+    /// Batches of the group, with the range of rows of the group
+    ranges: Vec<(RecordBatch, usize, usize)>,
+    /// Key columns of the batch of the first row of the group
+    keys: Vec<ArrayRef>,
+    /// Index of the first row of the group
+    row: usize,
+}
+
+impl KeyGroup {
+    /// Whether one of the keys of the group is null
+    fn has_null_key(&self) -> bool {
+        self.keys.iter().any(|key| key.is_null(self.row))
+    }
+
+    /// Returns the rows of the group as a single batch
+    fn batch(&self) -> ArrowResult<RecordBatch> {
+        let (first, start, end) = &self.ranges[0];
+        if self.ranges.len() == 1 {
+            let columns = first
+                .columns()
+                .iter()
+                .map(|array| array.slice(*start, end - start))
+                .collect();
+            return RecordBatch::try_new(first.schema(), columns);
+        }
+
+        let columns = (0..first.num_columns())
+            .map(|i| {
+                let arrays = self
+                    .ranges
+                    .iter()
+                    .map(|(batch, start, end)| batch.column(i).slice(*start, end - start))
+                    .collect::<Vec<_>>();
+                concat(
+                    &arrays
+                        .iter()
+                        .map(|array| array.as_ref())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        RecordBatch::try_new(first.schema(), columns)
+    }
+}
+
+/// One side of the join, read one group of rows with the same keys at a time
+struct SortedInput {
+    stream: SendableRecordBatchStream,
+    /// Join columns of this side
+    on: Vec<String>,
+    /// Current batch, with its key columns
+    batch: Option<(RecordBatch, Vec<ArrayRef>)>,
+    /// Next row of the current batch
+    row: usize,
+}
+
+impl SortedInput {
+    fn new(stream: SendableRecordBatchStream, on: Vec<String>) -> Self {
+        Self {
+            stream,
+            on,
+            batch: None,
+            row: 0,
+        }
+    }
+
+    /// Makes sure that the current batch has rows left to read, reading the
+    /// next non empty batch if needed. Returns false once the input is exhausted.
+    async fn fill(&mut self) -> ArrowResult<bool> {
+        if matches!(&self.batch, Some((batch, _)) if self.row < batch.num_rows()) {
+            return Ok(true);
+        }
+        self.batch = None;
+        while let Some(batch) = self.stream.next().await {
+            let batch = batch?;
+            if batch.num_rows() > 0 {
+                let keys = self
+                    .on
+                    .iter()
+                    .map(|name| {
+                        Ok(col(name).evaluate(&batch)?.into_array(batch.num_rows()))
+                    })
+                    .collect::<Result<Vec<_>>>()
+                    .map_err(DataFusionError::into_arrow_external_error)?;
+                self.batch = Some((batch, keys));
+                self.row = 0;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Reads the next group of rows with the same keys
+    async fn next_group(
+        &mut self,
+        sort_options: &[SortOptions],
+    ) -> ArrowResult<Option<KeyGroup>> {
+        if !self.fill().await? {
+            return Ok(None);
+        }
+        let (_, keys) = self.batch.as_ref().unwrap();
+        let mut group = KeyGroup {
+            ranges: vec![],
+            keys: keys.clone(),
+            row: self.row,
+        };
+
+        loop {
+            let (batch, keys) = self.batch.as_ref().unwrap();
+            let start = self.row;
+            while self.row < batch.num_rows()
+                && compare_rows(&group.keys, group.row, keys, self.row, sort_options)
+                    .map_err(DataFusionError::into_arrow_external_error)?
+                    == Ordering::Equal
+            {
+                self.row += 1;
+            }
+            if self.row > start {
+                group.ranges.push((batch.clone(), start, self.row));
+            }
+            // the group continues in the next batch when it ends this one
+            if self.row < batch.num_rows() || !self.fill().await? {
+                return Ok(Some(group));
+            }
+        }
+    }
+}
+
+/// Builds the batches produced by the join, buffering them until they reach
+/// `batch_size` rows
+struct JoinOutput {
+    schema: SchemaRef,
+    join_type: JoinType,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// Output columns of the join keys that are taken from the right side
+    /// when the left side is null, with the index of the right column
+    coalesced_keys: Vec<(usize, usize)>,
+    batch_size: usize,
+    /// Batches produced but not sent yet
+    batches: Vec<RecordBatch>,
+    /// Number of rows of `batches`
+    num_rows: usize,
+}
+
+impl JoinOutput {
+    /// Produces the rows of two groups with the same keys
+    fn matched(&mut self, left: &KeyGroup, right: &KeyGroup) -> ArrowResult<()> {
+        match self.join_type {
+            JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full => {
+                let left = left.batch()?;
+                let right = right.batch()?;
+                let (left_rows, right_rows) = (left.num_rows(), right.num_rows());
+                let left_indices = (0..left_rows)
+                    .flat_map(|l| std::iter::repeat(l as u64).take(right_rows))
+                    .collect::<Vec<_>>();
+                let right_indices = (0..left_rows)
+                    .flat_map(|_| 0..right_rows as u32)
+                    .collect::<Vec<_>>();
+                let batch = build_batch_from_indices(
+                    &self.schema,
+                    &left,
+                    &right,
+                    &UInt64Array::from(left_indices),
+                    &UInt32Array::from(right_indices),
+                    &self.column_indices,
+                    &self.coalesced_keys,
+                )?;
+                self.push(batch)
+            }
+            JoinType::Semi => self.push_side(left, true),
+            JoinType::Anti => Ok(()),
+        }
+    }
+
+    /// Produces the rows of a group of the left side without a match
+    fn unmatched_left(&mut self, left: &KeyGroup) -> ArrowResult<()> {
+        match self.join_type {
+            JoinType::Left | JoinType::Full | JoinType::Anti => {
+                self.push_side(left, true)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Produces the rows of a group of the right side without a match
+    fn unmatched_right(&mut self, right: &KeyGroup) -> ArrowResult<()> {
+        match self.join_type {
+            JoinType::Right | JoinType::Full => self.push_side(right, false),
+            _ => Ok(()),
+        }
+    }
+
+    /// Produces the rows of a group of one side, with nulls for the columns
+    /// of the other side
+    fn push_side(&mut self, group: &KeyGroup, is_left: bool) -> ArrowResult<()> {
+        let batch = group.batch()?;
+        let num_rows = batch.num_rows();
+        let mut columns = self
+            .column_indices
+            .iter()
+            .zip(self.schema.fields())
+            .map(|(column_index, field)| {
+                if column_index.is_left == is_left {
+                    batch.column(column_index.index).clone()
+                } else {
+                    new_null_array(field.data_type(), num_rows)
+                }
+            })
+            .collect::<Vec<_>>();
+        if !is_left {
+            for (i, right_index) in &self.coalesced_keys {
+                columns[*i] = batch.column(*right_index).clone();
+            }
+        }
+        self.push(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+
+    fn push(&mut self, batch: RecordBatch) -> ArrowResult<()> {
+        self.num_rows += batch.num_rows();
+        self.batches.push(batch);
+        Ok(())
+    }
+
+    /// Returns the buffered rows as a single batch when there are at least
+    /// `batch_size` of them, or when `flush` is true
+    fn take(&mut self, flush: bool) -> ArrowResult<Option<RecordBatch>> {
+        if self.num_rows == 0 || (self.num_rows < self.batch_size && !flush) {
+            return Ok(None);
+        }
+        let batch = concat_batches(&self.schema, &self.batches, self.num_rows)?;
+        self.batches.clear();
+        self.num_rows = 0;
+        Ok(Some(batch))
+    }
+}
+
+/// Merges the groups of rows of both sides, in the order of their keys
+async fn merge_join(
+    mut left: SortedInput,
+    mut right: SortedInput,
+    mut output: JoinOutput,
+    sort_options: Vec<SortOptions>,
+    null_equals_null: bool,
+    sender: &mut mpsc::Sender<ArrowResult<RecordBatch>>,
+) -> ArrowResult<()> {
+    let mut left_group = left.next_group(&sort_options).await?;
+    let mut right_group = right.next_group(&sort_options).await?;
+
+    loop {
+        let ordering = match (&left_group, &right_group) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(l), Some(r)) => {
+                match compare_rows(&l.keys, l.row, &r.keys, r.row, &sort_options)
+                    .map_err(DataFusionError::into_arrow_external_error)?
+                {
+                    // null keys never match, unless `null_equals_null`: the
+                    // left group is then unmatched, and the right group is
+                    // compared with the next left group
+                    Ordering::Equal if !null_equals_null && l.has_null_key() => {
+                        Ordering::Less
+                    }
+                    ordering => ordering,
+                }
+            }
+        };
+
+        match ordering {
+            Ordering::Less => {
+                output.unmatched_left(left_group.as_ref().unwrap())?;
+                left_group = left.next_group(&sort_options).await?;
+            }
+            Ordering::Greater => {
+                output.unmatched_right(right_group.as_ref().unwrap())?;
+                right_group = right.next_group(&sort_options).await?;
+            }
+            Ordering::Equal => {
+                output.matched(
+                    left_group.as_ref().unwrap(),
+                    right_group.as_ref().unwrap(),
+                )?;
+                left_group = left.next_group(&sort_options).await?;
+                right_group = right.next_group(&sort_options).await?;
+            }
+        }
+
+        if let Some(batch) = output.take(false)? {
+            // If send fails, plan being torn down, there is no place to send
+            // the rest of the batches
+            if sender.send(Ok(batch)).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    if let Some(batch) = output.take(true)? {
+        sender.send(Ok(batch)).await.ok();
+    }
+    Ok(())
+}
+
+pin_project! {
+    struct SortMergeJoinStream {
+        #[pin]
+        output: mpsc::Receiver<ArrowResult<RecordBatch>>,
+        schema: SchemaRef,
+    }
+}
+
+impl SortMergeJoinStream {
+    fn new(
+        left: SortedInput,
+        right: SortedInput,
+        output: JoinOutput,
+        sort_options: Vec<SortOptions>,
+        null_equals_null: bool,
+    ) -> Self {
+        let (mut tx, rx) = mpsc::channel(1);
+
+        let schema = output.schema.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                merge_join(left, right, output, sort_options, null_equals_null, &mut tx)
+                    .await
+            {
+                tx.send(Err(e)).await.ok();
+            }
+        });
+
+        Self { output: rx, schema }
+    }
+}
+
+impl Stream for SortMergeJoinStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().output.poll_next(cx)
+    }
+}
+
+impl RecordBatchStream for SortMergeJoinStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Returns the sort options of the join columns `on` of `plan`, when `plan`
+/// is sorted on them (and possibly on more columns after them).
+pub(crate) fn join_sort_options(
+    plan: &Arc<dyn ExecutionPlan>,
+    on: &[String],
+) -> Option<Vec<SortOptions>> {
+    let sort = plan.as_any().downcast_ref::<super::sort::SortExec>()?;
+    let expr = sort.expr();
+    if expr.len() < on.len() {
+        return None;
+    }
+    on.iter()
+        .zip(expr)
+        .map(|(name, sort_expr)| {
+            match sort_expr
+                .expr
+                .as_any()
+                .downcast_ref::<super::expressions::Column>()
+            {
+                Some(column) if column.name() == name => Some(sort_expr.options),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Whether the rows of `schema` can be compared by the sort-merge join on `on`
+pub(crate) fn supports_join_columns(schema: &Schema, on: &[String]) -> bool {
+    on.iter().all(|name| {
+        schema
+            .field_with_name(name)
+            .map_or(false, |field| supports_type(field.data_type()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assert_batches_eq, assert_batches_sorted_eq,
+        physical_plan::{common, memory::MemoryExec},
+        test::{build_table_i32, columns},
+    };
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field};
+
+    fn build_table(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<i32>),
+        c: (&str, &Vec<i32>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let batch = build_table_i32(a, b, c);
+        let schema = batch.schema();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    fn join(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: &[(&str, &str)],
+        join_type: &JoinType,
+    ) -> Result<SortMergeJoinExec> {
+        let on: Vec<_> = on
+            .iter()
+            .map(|(l, r)| (l.to_string(), r.to_string()))
+            .collect();
+        let sort_options = vec![SortOptions::default(); on.len()];
+        SortMergeJoinExec::try_new(left, right, &on, join_type, sort_options, false, 2)
+    }
+
+    async fn join_collect(
+        join_type: &JoinType,
+        on: &[(&str, &str)],
+    ) -> Result<(Vec<String>, Vec<RecordBatch>)> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3, 4]),
+            ("b1", &vec![4, 5, 5, 7]), // this has a repetition
+            ("c1", &vec![7, 8, 9, 10]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b1", &vec![3, 5, 5, 6]),
+            ("c2", &vec![70, 80, 90, 100]),
+        );
+        let join = join(left, right, on, join_type)?;
+        let columns = columns(&join.schema());
+        let stream = join.execute(0).await?;
+        let batches = common::collect(stream).await?;
+        Ok((columns, batches))
+    }
+
+    #[tokio::test]
+    async fn join_inner_one() -> Result<()> {
+        let (columns, batches) = join_collect(&JoinType::Inner, &[("b1", "b1")]).await?;
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "c2"]);
+
+        // the output is sorted on the join keys
+        let expected = vec![
+            "+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | c2 |",
+            "+----+----+----+----+----+",
+            "| 2  | 5  | 8  | 20 | 80 |",
+            "| 2  | 5  | 8  | 30 | 90 |",
+            "| 3  | 5  | 9  | 20 | 80 |",
+            "| 3  | 5  | 9  | 30 | 90 |",
+            "+----+----+----+----+----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_full_one() -> Result<()> {
+        let (columns, batches) = join_collect(&JoinType::Full, &[("b1", "b1")]).await?;
+        assert_eq!(columns, vec!["a1", "b1", "c1", "a2", "c2"]);
+
+        let expected = vec![
+            "+----+----+----+----+-----+",
+            "| a1 | b1 | c1 | a2 | c2  |",
+            "+----+----+----+----+-----+",
+            "|    | 3  |    | 10 | 70  |",
+            "| 1  | 4  | 7  |    |     |",
+            "| 2  | 5  | 8  | 20 | 80  |",
+            "| 2  | 5  | 8  | 30 | 90  |",
+            "| 3  | 5  | 9  | 20 | 80  |",
+            "| 3  | 5  | 9  | 30 | 90  |",
+            "|    | 6  |    | 40 | 100 |",
+            "| 4  | 7  | 10 |    |     |",
+            "+----+----+----+----+-----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_right_one() -> Result<()> {
+        let (columns, batches) = join_collect(&JoinType::Right, &[("b1", "b1")]).await?;
+        assert_eq!(columns, vec!["a1", "c1", "a2", "b1", "c2"]);
+
+        let expected = vec![
+            "+----+----+----+----+-----+",
+            "| a1 | c1 | a2 | b1 | c2  |",
+            "+----+----+----+----+-----+",
+            "|    |    | 10 | 3  | 70  |",
+            "| 2  | 8  | 20 | 5  | 80  |",
+            "| 2  | 8  | 30 | 5  | 90  |",
+            "| 3  | 9  | 20 | 5  | 80  |",
+            "| 3  | 9  | 30 | 5  | 90  |",
+            "|    |    | 40 | 6  | 100 |",
+            "+----+----+----+----+-----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_semi_and_anti() -> Result<()> {
+        let (_, batches) = join_collect(&JoinType::Semi, &[("b1", "b1")]).await?;
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 2  | 5  | 8  |",
+            "| 3  | 5  | 9  |",
+            "+----+----+----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        let (_, batches) = join_collect(&JoinType::Anti, &[("b1", "b1")]).await?;
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 1  | 4  | 7  |",
+            "| 4  | 7  | 10 |",
+            "+----+----+----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    /// Groups of rows with the same key span several batches, and null keys
+    /// never match
+    #[tokio::test]
+    async fn join_left_groups_across_batches() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = |a: Vec<Option<i32>>, b: Vec<i32>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+            )
+            .unwrap()
+        };
+        let left = Arc::new(MemoryExec::try_new(
+            &[vec![
+                batch(vec![None, Some(1)], vec![1, 2]),
+                batch(vec![Some(1)], vec![3]),
+                batch(vec![], vec![]),
+                batch(vec![Some(1), Some(2)], vec![4, 5]),
+            ]],
+            schema.clone(),
+            None,
+        )?);
+        let right_schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("c", DataType::Int32, false),
+        ]));
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![
+                RecordBatch::try_new(
+                    right_schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from(vec![None, Some(1)])),
+                        Arc::new(Int32Array::from(vec![10, 20])),
+                    ],
+                )?,
+                RecordBatch::try_new(
+                    right_schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from(vec![Some(1), Some(3)])),
+                        Arc::new(Int32Array::from(vec![30, 40])),
+                    ],
+                )?,
+            ]],
+            right_schema,
+            None,
+        )?);
+
+        let join = join(left, right, &[("a", "a")], &JoinType::Left)?;
+        let batches = common::collect(join.execute(0).await?).await?;
+
+        let expected = vec![
+            "+---+---+----+",
+            "| a | b | c  |",
+            "+---+---+----+",
+            "|   | 1 |    |",
+            "| 1 | 2 | 20 |",
+            "| 1 | 2 | 30 |",
+            "| 1 | 3 | 20 |",
+            "| 1 | 3 | 30 |",
+            "| 1 | 4 | 20 |",
+            "| 1 | 4 | 30 |",
+            "| 2 | 5 |    |",
+            "+---+---+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+}
@@ -0,0 +1,651 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the sort preserving merge plan, that merges sorted partitions into a
+//! single sorted partition
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc;
+use futures::stream::Stream;
+use futures::{SinkExt, StreamExt};
+
+use pin_project_lite::pin_project;
+
+use arrow::array::{build_compare, ArrayRef, DynComparator};
+use arrow::compute::{concat, SortOptions};
+use arrow::datatypes::SchemaRef;
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::datasource::datasource::Statistics;
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::{ExecutionPlan, Partitioning};
+
+use async_trait::async_trait;
+
+/// Sort preserving merge execution plan, that merges partitions that are each sorted
+/// on the same expressions into a single sorted partition.
+///
+/// The partitions are merged by repeatedly picking the smallest of the next row of
+/// each partition, comparing rows with the comparators of `arrow::array::ord`. Rows
+/// that compare equal are taken from the partition with the lowest index first.
+#[derive(Debug)]
+pub struct SortPreservingMergeExec {
+    /// Input execution plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Sort expressions the partitions of the input are sorted on
+    expr: Vec<PhysicalSortExpr>,
+    /// Number of rows of the output batches
+    target_batch_size: usize,
+}
+
+impl SortPreservingMergeExec {
+    /// Create a new SortPreservingMergeExec
+    pub fn new(
+        expr: Vec<PhysicalSortExpr>,
+        input: Arc<dyn ExecutionPlan>,
+        target_batch_size: usize,
+    ) -> Self {
+        Self {
+            input,
+            expr,
+            target_batch_size,
+        }
+    }
+
+    /// Input execution plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Sort expressions
+    pub fn expr(&self) -> &[PhysicalSortExpr] {
+        &self.expr
+    }
+
+    /// Number of rows of the output batches
+    pub fn target_batch_size(&self) -> usize {
+        self.target_batch_size
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for SortPreservingMergeExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(SortPreservingMergeExec::new(
+                self.expr.clone(),
+                children[0].clone(),
+                self.target_batch_size,
+            ))),
+            _ => Err(DataFusionError::Internal(
+                "SortPreservingMergeExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "SortPreservingMergeExec invalid partition {}",
+                partition
+            )));
+        }
+
+        let input_partitions = self.input.output_partitioning().partition_count();
+        match input_partitions {
+            0 => Err(DataFusionError::Internal(
+                "SortPreservingMergeExec requires at least one input partition"
+                    .to_owned(),
+            )),
+            // a single partition is already sorted
+            1 => self.input.execute(0).await,
+            _ => {
+                let mut streams = Vec::with_capacity(input_partitions);
+                for part_i in 0..input_partitions {
+                    streams.push(self.input.execute(part_i).await?);
+                }
+                Ok(Box::pin(SortPreservingMergeStream::new(
+                    streams,
+                    self.expr.clone(),
+                    self.schema(),
+                    self.target_batch_size,
+                )))
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+/// The current batch of an input partition and the position of its next row
+struct Cursor {
+    batch: RecordBatch,
+    /// Values of the sort expressions for `batch`
+    sort_columns: Vec<ArrayRef>,
+    row: usize,
+}
+
+/// Returns a cursor on the next non empty batch of `stream`, if any
+async fn next_cursor(
+    stream: &mut SendableRecordBatchStream,
+    expr: &[PhysicalSortExpr],
+) -> ArrowResult<Option<Cursor>> {
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        if batch.num_rows() > 0 {
+            let sort_columns = expr
+                .iter()
+                .map(|e| e.evaluate_to_sort_column(&batch).map(|c| c.values))
+                .collect::<Result<Vec<_>>>()
+                .map_err(DataFusionError::into_arrow_external_error)?;
+            return Ok(Some(Cursor {
+                batch,
+                sort_columns,
+                row: 0,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// A slice of rows merged but not yet output
+struct Slice {
+    /// Index of the cursor the rows were read from
+    cursor: usize,
+    batch: RecordBatch,
+    offset: usize,
+    len: usize,
+}
+
+/// Why merging rows stopped
+enum MergeState {
+    /// The batch of the cursor at this index has been entirely merged
+    Exhausted(usize),
+    /// All the cursors have been merged
+    Done,
+}
+
+/// Merges the rows of `cursors` until the batch of a cursor is exhausted. The
+/// rows merged are added to `output`, and every `max_rows` rows of `output` are
+/// moved to a batch of `batches`.
+///
+/// The comparators of the sort columns are built once for the current batches
+/// of the cursors, which are kept in a binary heap ordered on their next row:
+/// merging `n` rows of `k` cursors takes `O(n log k)` comparisons.
+fn merge_rows(
+    cursors: &mut [Option<Cursor>],
+    options: &[SortOptions],
+    schema: &SchemaRef,
+    output: &mut Vec<Slice>,
+    batches: &mut Vec<RecordBatch>,
+    max_rows: usize,
+) -> ArrowResult<MergeState> {
+    let active = (0..cursors.len())
+        .filter(|i| cursors[*i].is_some())
+        .collect::<Vec<_>>();
+    if active.is_empty() {
+        return Ok(MergeState::Done);
+    }
+
+    let mut rows = vec![0; cursors.len()];
+    let exhausted;
+    {
+        let cursors = cursors.iter().map(|c| c.as_ref()).collect::<Vec<_>>();
+        // comparators[a][b][c] compares column c of cursor a with cursor b
+        let mut comparators: Vec<Vec<Vec<DynComparator>>> = vec![];
+        for a in 0..cursors.len() {
+            let mut row = vec![];
+            for b in 0..cursors.len() {
+                let mut columns = vec![];
+                if let (Some(left), Some(right)) = (cursors[a], cursors[b]) {
+                    if a != b {
+                        for c in 0..options.len() {
+                            columns.push(build_compare(
+                                left.sort_columns[c].as_ref(),
+                                right.sort_columns[c].as_ref(),
+                            )?);
+                        }
+                    }
+                }
+                row.push(columns);
+            }
+            comparators.push(row);
+        }
+        for i in &active {
+            rows[*i] = cursors[*i].unwrap().row;
+        }
+
+        // whether the next row of cursor a comes before the next row of cursor
+        // b, the rows that compare equal coming from the cursor of lowest index
+        let less = |a: usize, b: usize, rows: &[usize]| -> bool {
+            let (left, right) = (cursors[a].unwrap(), cursors[b].unwrap());
+            for (c, options) in options.iter().enumerate() {
+                let order = match (
+                    left.sort_columns[c].is_valid(rows[a]),
+                    right.sort_columns[c].is_valid(rows[b]),
+                ) {
+                    (true, true) => {
+                        let order = comparators[a][b][c](rows[a], rows[b]);
+                        if options.descending {
+                            order.reverse()
+                        } else {
+                            order
+                        }
+                    }
+                    (false, true) if options.nulls_first => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (true, false) if options.nulls_first => Ordering::Greater,
+                    (true, false) => Ordering::Less,
+                    (false, false) => Ordering::Equal,
+                };
+                if order != Ordering::Equal {
+                    return order == Ordering::Less;
+                }
+            }
+            a < b
+        };
+
+        let mut heap = active.clone();
+        for i in (0..heap.len() / 2).rev() {
+            sift_down(&mut heap, i, |a, b| less(a, b, &rows));
+        }
+
+        let mut num_rows = output.iter().map(|slice| slice.len).sum::<usize>();
+        loop {
+            let smallest = heap[0];
+            let cursor = cursors[smallest].unwrap();
+            match output.last_mut() {
+                // extend the last slice if the row follows it in the same batch
+                Some(slice)
+                    if slice.cursor == smallest
+                        && slice.offset + slice.len == rows[smallest] =>
+                {
+                    slice.len += 1
+                }
+                _ => output.push(Slice {
+                    cursor: smallest,
+                    batch: cursor.batch.clone(),
+                    offset: rows[smallest],
+                    len: 1,
+                }),
+            }
+            num_rows += 1;
+            rows[smallest] += 1;
+
+            if num_rows == max_rows {
+                batches.push(output_batch(schema, output)?);
+                output.clear();
+                num_rows = 0;
+            }
+            if rows[smallest] == cursor.batch.num_rows() {
+                exhausted = smallest;
+                break;
+            }
+            sift_down(&mut heap, 0, |a, b| less(a, b, &rows));
+        }
+    }
+
+    for i in active {
+        if let Some(cursor) = cursors[i].as_mut() {
+            cursor.row = rows[i];
+        }
+    }
+    Ok(MergeState::Exhausted(exhausted))
+}
+
+/// Moves the cursor at position `i` of the binary heap `heap` down to its place,
+/// where `less` tells whether the next row of a cursor comes before another's
+fn sift_down(heap: &mut [usize], mut i: usize, less: impl Fn(usize, usize) -> bool) {
+    loop {
+        let mut smallest = i;
+        for child in &[2 * i + 1, 2 * i + 2] {
+            if *child < heap.len() && less(heap[*child], heap[smallest]) {
+                smallest = *child;
+            }
+        }
+        if smallest == i {
+            return;
+        }
+        heap.swap(i, smallest);
+        i = smallest;
+    }
+}
+
+/// Concatenates the slices of `output` into a single batch
+fn output_batch(schema: &SchemaRef, output: &[Slice]) -> ArrowResult<RecordBatch> {
+    let columns = (0..schema.fields().len())
+        .map(|i| {
+            let slices = output
+                .iter()
+                .map(|slice| slice.batch.column(i).slice(slice.offset, slice.len))
+                .collect::<Vec<_>>();
+            concat(&slices.iter().map(|a| a.as_ref()).collect::<Vec<_>>())
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+    RecordBatch::try_new(schema.clone(), columns)
+}
+
+/// Merges the sorted `streams` and sends the merged batches
+async fn merge_streams(
+    mut streams: Vec<SendableRecordBatchStream>,
+    expr: Vec<PhysicalSortExpr>,
+    schema: SchemaRef,
+    target_batch_size: usize,
+    sender: &mut mpsc::Sender<ArrowResult<RecordBatch>>,
+) -> ArrowResult<()> {
+    let options = expr.iter().map(|e| e.options).collect::<Vec<_>>();
+    let max_rows = target_batch_size.max(1);
+
+    let mut cursors = Vec::with_capacity(streams.len());
+    for stream in streams.iter_mut() {
+        cursors.push(next_cursor(stream, &expr).await?);
+    }
+
+    let mut output = vec![];
+    loop {
+        let mut batches = vec![];
+        let state = merge_rows(
+            &mut cursors,
+            &options,
+            &schema,
+            &mut output,
+            &mut batches,
+            max_rows,
+        )?;
+        if matches!(state, MergeState::Done) && !output.is_empty() {
+            batches.push(output_batch(&schema, &output)?);
+            output.clear();
+        }
+        for batch in batches {
+            // If send fails, plan being torn down, there is no place to send
+            // the rest of the batches
+            if sender.send(Ok(batch)).await.is_err() {
+                return Ok(());
+            }
+        }
+        match state {
+            MergeState::Exhausted(i) => {
+                cursors[i] = next_cursor(&mut streams[i], &expr).await?;
+            }
+            MergeState::Done => return Ok(()),
+        }
+    }
+}
+
+pin_project! {
+    /// Stream of the batches merged from sorted streams
+    pub(crate) struct SortPreservingMergeStream {
+        #[pin]
+        output: mpsc::Receiver<ArrowResult<RecordBatch>>,
+        schema: SchemaRef,
+    }
+}
+
+impl SortPreservingMergeStream {
+    /// Merges `streams`, that are each sorted on `expr`, into batches of
+    /// `target_batch_size` rows
+    pub(crate) fn new(
+        streams: Vec<SendableRecordBatchStream>,
+        expr: Vec<PhysicalSortExpr>,
+        schema: SchemaRef,
+        target_batch_size: usize,
+    ) -> Self {
+        let (mut tx, rx) = mpsc::channel(1);
+
+        let output_schema = schema.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                merge_streams(streams, expr, output_schema, target_batch_size, &mut tx)
+                    .await
+            {
+                tx.send(Err(e)).await.ok();
+            }
+        });
+
+        Self { output: rx, schema }
+    }
+}
+
+impl Stream for SortPreservingMergeStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().output.poll_next(cx)
+    }
+}
+
+impl RecordBatchStream for SortPreservingMergeStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::sort::SortExec;
+    use arrow::array::{Array, Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn batch(schema: &SchemaRef, a: Vec<Option<i32>>, b: Vec<&str>) -> RecordBatch {
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(a)),
+                Arc::new(StringArray::from(b)),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn values(batches: &[RecordBatch]) -> Vec<(Option<i32>, String)> {
+        batches
+            .iter()
+            .flat_map(|batch| {
+                let a = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                let b = batch
+                    .column(1)
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .unwrap();
+                (0..batch.num_rows())
+                    .map(|i| {
+                        (
+                            Some(a.value(i)).filter(|_| a.is_valid(i)),
+                            b.value(i).to_string(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn sort_expr(descending: bool) -> Vec<PhysicalSortExpr> {
+        vec![
+            PhysicalSortExpr {
+                expr: col("a"),
+                options: SortOptions {
+                    descending,
+                    nulls_first: true,
+                },
+            },
+            PhysicalSortExpr {
+                expr: col("b"),
+                options: SortOptions::default(),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn merge_sorted_partitions() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        let partitions = vec![
+            vec![
+                batch(&schema, vec![None, Some(1), Some(3)], vec!["a", "b", "c"]),
+                batch(&schema, vec![], vec![]),
+                batch(&schema, vec![Some(3), Some(8)], vec!["d", "e"]),
+            ],
+            vec![batch(&schema, vec![Some(2), Some(3)], vec!["f", "a"])],
+            vec![],
+            vec![batch(
+                &schema,
+                vec![None, Some(0), Some(9)],
+                vec!["b", "g", "h"],
+            )],
+        ];
+        let input = Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None)?);
+
+        let merge = Arc::new(SortPreservingMergeExec::new(sort_expr(false), input, 3));
+        let batches = collect(merge).await?;
+        assert!(batches.iter().all(|batch| batch.num_rows() <= 3));
+        let expected = vec![
+            (None, "a"),
+            (None, "b"),
+            (Some(0), "g"),
+            (Some(1), "b"),
+            (Some(2), "f"),
+            (Some(3), "a"),
+            (Some(3), "c"),
+            (Some(3), "d"),
+            (Some(8), "e"),
+            (Some(9), "h"),
+        ];
+        let expected = expected
+            .into_iter()
+            .map(|(a, b)| (a, b.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, values(&batches));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn parallel_sort() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        let partitions = vec![
+            vec![
+                batch(&schema, vec![Some(4), None, Some(7)], vec!["a", "b", "c"]),
+                batch(&schema, vec![Some(5)], vec!["d"]),
+            ],
+            vec![batch(&schema, vec![Some(7), Some(1)], vec!["a", "e"])],
+        ];
+        let input = Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None)?);
+
+        let sort = Arc::new(
+            SortExec::try_new(sort_expr(true), input)?.with_preserve_partitioning(true),
+        );
+        assert_eq!(2, sort.output_partitioning().partition_count());
+        let merge = Arc::new(SortPreservingMergeExec::new(sort_expr(true), sort, 1024));
+        let batches = collect(merge).await?;
+        assert_eq!(1, batches.len());
+        let expected = vec![
+            (None, "b"),
+            (Some(7), "a"),
+            (Some(7), "c"),
+            (Some(5), "d"),
+            (Some(4), "a"),
+            (Some(1), "e"),
+        ];
+        let expected = expected
+            .into_iter()
+            .map(|(a, b)| (a, b.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, values(&batches));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn merge_equal_rows_in_partition_order() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        // partition p holds the values of a from p % 2 to 20 by steps of 2, in
+        // batches of p + 1 rows, and p as b
+        let mut expected = vec![];
+        let partitions = (0..6)
+            .map(|p| {
+                let values = (p % 2..20).step_by(2).collect::<Vec<i32>>();
+                expected.extend(values.iter().map(|a| (Some(*a), p.to_string())));
+                values
+                    .chunks(p as usize + 1)
+                    .map(|a| {
+                        let a: Vec<Option<i32>> = a.iter().map(|a| Some(*a)).collect();
+                        let b = vec![p.to_string(); a.len()];
+                        batch(&schema, a, b.iter().map(|b| b.as_str()).collect())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let input = Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None)?);
+
+        let expr = vec![PhysicalSortExpr {
+            expr: col("a"),
+            options: SortOptions::default(),
+        }];
+        let merge = Arc::new(SortPreservingMergeExec::new(expr, input, 4));
+        let batches = collect(merge).await?;
+        assert!(batches.iter().all(|batch| batch.num_rows() <= 4));
+        // the rows with equal values are taken from the partitions in order
+        expected.sort();
+        assert_eq!(expected, values(&batches));
+        Ok(())
+    }
+}
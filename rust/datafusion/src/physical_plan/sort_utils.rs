@@ -0,0 +1,278 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Comparison of the rows of sorted columns, shared by the operators that order
+//! rows like the sort kernel, e.g. the top-k and the sort-merge join
+
+use std::cmp::Ordering;
+
+use arrow::array::{
+    build_compare, ArrayRef, BooleanArray, Date32Array, Date64Array, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, LargeStringArray,
+    StringArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+    TimestampNanosecondArray, TimestampSecondArray, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
+};
+use arrow::compute::SortOptions;
+use arrow::datatypes::{DataType, TimeUnit};
+
+use crate::error::{DataFusionError, Result};
+
+macro_rules! compare_rows_elem {
+    ($array_type:ident, $l: ident, $r: ident, $left: ident, $right: ident) => {{
+        let left_array = $l.as_any().downcast_ref::<$array_type>().unwrap();
+        let right_array = $r.as_any().downcast_ref::<$array_type>().unwrap();
+        let (left_value, right_value) =
+            (left_array.value($left), right_array.value($right));
+        Ord::cmp(&left_value, &right_value)
+    }};
+}
+
+// compares floats with NaN greater than any other value and equal to itself, like
+// the sort kernel
+macro_rules! compare_floats_elem {
+    ($array_type:ident, $l: ident, $r: ident, $left: ident, $right: ident) => {{
+        let left_array = $l.as_any().downcast_ref::<$array_type>().unwrap();
+        let right_array = $r.as_any().downcast_ref::<$array_type>().unwrap();
+        let (left_value, right_value) =
+            (left_array.value($left), right_array.value($right));
+        match (left_value.is_nan(), right_value.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => left_value.partial_cmp(&right_value).unwrap(),
+        }
+    }};
+}
+
+/// Compares the keys of the left and right rows, in the order given by
+/// `sort_options`. Null values are equal to each other.
+///
+/// The rows are ordered like the sort kernel orders them: the most common types are
+/// compared directly, and the other types with the comparators of
+/// [`build_compare`].
+pub(crate) fn compare_rows(
+    left_arrays: &[ArrayRef],
+    left: usize,
+    right_arrays: &[ArrayRef],
+    right: usize,
+    sort_options: &[SortOptions],
+) -> Result<Ordering> {
+    for ((l, r), options) in left_arrays.iter().zip(right_arrays).zip(sort_options) {
+        let ordering = match (l.is_null(left), r.is_null(right)) {
+            (true, true) => Ordering::Equal,
+            (true, false) if options.nulls_first => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            (false, true) if options.nulls_first => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => {
+                let ordering = match l.data_type() {
+                    DataType::Boolean => {
+                        compare_rows_elem!(BooleanArray, l, r, left, right)
+                    }
+                    DataType::Int8 => compare_rows_elem!(Int8Array, l, r, left, right),
+                    DataType::Int16 => compare_rows_elem!(Int16Array, l, r, left, right),
+                    DataType::Int32 => compare_rows_elem!(Int32Array, l, r, left, right),
+                    DataType::Int64 => compare_rows_elem!(Int64Array, l, r, left, right),
+                    DataType::UInt8 => compare_rows_elem!(UInt8Array, l, r, left, right),
+                    DataType::UInt16 => {
+                        compare_rows_elem!(UInt16Array, l, r, left, right)
+                    }
+                    DataType::UInt32 => {
+                        compare_rows_elem!(UInt32Array, l, r, left, right)
+                    }
+                    DataType::UInt64 => {
+                        compare_rows_elem!(UInt64Array, l, r, left, right)
+                    }
+                    DataType::Float32 => {
+                        compare_floats_elem!(Float32Array, l, r, left, right)
+                    }
+                    DataType::Float64 => {
+                        compare_floats_elem!(Float64Array, l, r, left, right)
+                    }
+                    DataType::Date32 => {
+                        compare_rows_elem!(Date32Array, l, r, left, right)
+                    }
+                    DataType::Date64 => {
+                        compare_rows_elem!(Date64Array, l, r, left, right)
+                    }
+                    DataType::Timestamp(TimeUnit::Second, _) => {
+                        compare_rows_elem!(TimestampSecondArray, l, r, left, right)
+                    }
+                    DataType::Timestamp(TimeUnit::Millisecond, _) => {
+                        compare_rows_elem!(TimestampMillisecondArray, l, r, left, right)
+                    }
+                    DataType::Timestamp(TimeUnit::Microsecond, _) => {
+                        compare_rows_elem!(TimestampMicrosecondArray, l, r, left, right)
+                    }
+                    DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+                        compare_rows_elem!(TimestampNanosecondArray, l, r, left, right)
+                    }
+                    DataType::Utf8 => compare_rows_elem!(StringArray, l, r, left, right),
+                    DataType::LargeUtf8 => {
+                        compare_rows_elem!(LargeStringArray, l, r, left, right)
+                    }
+                    other if supports_type(other) => {
+                        build_compare(l.as_ref(), r.as_ref())?(left, right)
+                    }
+                    other => {
+                        return Err(DataFusionError::NotImplemented(format!(
+                            "Comparing the rows of columns of type {:?}",
+                            other
+                        )))
+                    }
+                };
+                if options.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+        };
+        if ordering != Ordering::Equal {
+            return Ok(ordering);
+        }
+    }
+    Ok(Ordering::Equal)
+}
+
+/// Whether the values of `data_type` can be compared by [compare_rows], which are
+/// the types that the sort kernel can sort on several columns
+pub(crate) fn supports_type(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Dictionary(key_type, value_type) => {
+            matches!(
+                key_type.as_ref(),
+                DataType::Int8
+                    | DataType::Int16
+                    | DataType::Int32
+                    | DataType::Int64
+                    | DataType::UInt8
+                    | DataType::UInt16
+                    | DataType::UInt32
+                    | DataType::UInt64
+            ) && value_type.as_ref() == &DataType::Utf8
+        }
+        _ => matches!(
+            data_type,
+            DataType::Boolean
+                | DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+                | DataType::UInt64
+                | DataType::Float32
+                | DataType::Float64
+                | DataType::Date32
+                | DataType::Date64
+                | DataType::Time32(TimeUnit::Second)
+                | DataType::Time32(TimeUnit::Millisecond)
+                | DataType::Time64(TimeUnit::Microsecond)
+                | DataType::Time64(TimeUnit::Nanosecond)
+                | DataType::Timestamp(_, _)
+                | DataType::Interval(_)
+                | DataType::Duration(_)
+                | DataType::Decimal(_, _)
+                | DataType::Utf8
+                | DataType::LargeUtf8
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::DecimalBuilder;
+    use std::sync::Arc;
+
+    #[test]
+    fn compare_rows_with_sort_options() -> Result<()> {
+        let left: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None]));
+        let right: ArrayRef = Arc::new(StringArray::from(vec!["a"]));
+        let options = SortOptions {
+            descending: true,
+            nulls_first: false,
+        };
+
+        let arrays = vec![left.clone()];
+        assert_eq!(
+            compare_rows(
+                &arrays,
+                0,
+                &[Arc::new(Int32Array::from(vec![2]))],
+                0,
+                &[options]
+            )?,
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_rows(&arrays, 1, &arrays, 0, &[options])?,
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_rows(&arrays, 1, &arrays, 1, &[options])?,
+            Ordering::Equal
+        );
+        assert_eq!(
+            compare_rows(&[right.clone()], 0, &[right], 0, &[options])?,
+            Ordering::Equal
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compare_rows_with_nans() -> Result<()> {
+        let arrays: Vec<ArrayRef> =
+            vec![Arc::new(Float64Array::from(vec![f64::NAN, 1.0, f64::NAN]))];
+        let options = SortOptions::default();
+
+        // NaN is greater than any other value, like in the sort kernel
+        assert_eq!(
+            compare_rows(&arrays, 0, &arrays, 1, &[options])?,
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_rows(&arrays, 1, &arrays, 0, &[options])?,
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_rows(&arrays, 0, &arrays, 2, &[options])?,
+            Ordering::Equal
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compare_rows_of_decimals() -> Result<()> {
+        let mut builder = DecimalBuilder::new(3, 10, 2);
+        builder.append_value(150)?;
+        builder.append_value(-20)?;
+        let arrays: Vec<ArrayRef> = vec![Arc::new(builder.finish())];
+        assert!(supports_type(arrays[0].data_type()));
+
+        let options = SortOptions::default();
+        assert_eq!(
+            compare_rows(&arrays, 0, &arrays, 1, &[options])?,
+            Ordering::Greater
+        );
+        assert!(!supports_type(&DataType::Binary));
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines how operators that exceed their memory limit spill sorted runs of
+//! record batches to disk, and how the runs are merged back
+
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use pin_project_lite::pin_project;
+use tokio::task;
+
+use arrow::array::UInt32Array;
+use arrow::compute::take;
+use arrow::datatypes::SchemaRef;
+use arrow::error::{ArrowError, Result as ArrowResult};
+use arrow::ipc::{reader::FileReader, writer::FileWriter};
+use arrow::record_batch::RecordBatch;
+
+use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::sort_preserving_merge::SortPreservingMergeStream;
+
+/// A run of record batches, sorted on the same expressions across batches
+pub(crate) type SortedRun = SendableRecordBatchStream;
+
+/// Returns the number of bytes of memory occupied by the arrays of `batch`
+pub(crate) fn batch_memory_size(batch: &RecordBatch) -> usize {
+    batch
+        .columns()
+        .iter()
+        .map(|array| array.get_array_memory_size())
+        .sum()
+}
+
+/// Writes the rows of `batch` to a temporary Arrow IPC file, in batches of
+/// at most `max_rows` rows, and returns the run of the batches written, that
+/// are read back as the run is polled. The file is written and read on the
+/// blocking threads of the runtime, and is deleted once the run is dropped.
+pub(crate) async fn spill(batch: RecordBatch, max_rows: usize) -> Result<SortedRun> {
+    let schema = batch.schema();
+    let reader = task::spawn_blocking(move || write_run(&batch, max_rows))
+        .await
+        .map_err(|e| {
+            DataFusionError::Execution(format!("Spilling to disk failed: {}", e))
+        })??;
+    Ok(read_run(reader, schema))
+}
+
+/// Writes the rows of `batch` to a temporary file and opens it for reading
+fn write_run(batch: &RecordBatch, max_rows: usize) -> Result<FileReader<File>> {
+    let mut file = tempfile::tempfile()?;
+    let mut writer = FileWriter::try_new(file.try_clone()?, &batch.schema())?;
+    let num_rows = batch.num_rows();
+    for start in (0..num_rows).step_by(max_rows.max(1)) {
+        let end = num_rows.min(start + max_rows.max(1));
+        // the rows are taken instead of sliced, as the IPC writer does not
+        // support the offsets of sliced arrays
+        let indices = UInt32Array::from((start as u32..end as u32).collect::<Vec<_>>());
+        writer.write(&take_batch(batch, &indices)?)?;
+    }
+    writer.finish()?;
+
+    file.seek(SeekFrom::Start(0))?;
+    Ok(FileReader::try_new(file)?)
+}
+
+/// Returns the run of the batches of `reader`, each read on a blocking thread
+fn read_run(reader: FileReader<File>, schema: SchemaRef) -> SortedRun {
+    let batches = stream::unfold(Some(reader), |reader| async move {
+        let mut reader = reader?;
+        let read =
+            task::spawn_blocking(move || reader.next().map(|batch| (batch, reader)));
+        match read.await {
+            // stop reading after an error
+            Ok(Some((batch, reader))) => {
+                let reader = Some(reader).filter(|_| batch.is_ok());
+                Some((batch, reader))
+            }
+            Ok(None) => None,
+            Err(e) => Some((Err(ArrowError::ExternalError(Box::new(e))), None)),
+        }
+    });
+    Box::pin(SpilledRunStream {
+        batches: batches.boxed(),
+        schema,
+    })
+}
+
+/// Takes the rows of `batch` at `indices`
+fn take_batch(batch: &RecordBatch, indices: &UInt32Array) -> ArrowResult<RecordBatch> {
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|array| take(array.as_ref(), indices, None))
+        .collect::<ArrowResult<Vec<_>>>()?;
+    RecordBatch::try_new(batch.schema(), columns)
+}
+
+/// Merges sorted runs of record batches into a single sorted run of batches of
+/// at most `max_rows` rows, with the k-way merge of the
+/// [SortPreservingMergeExec](super::sort_preserving_merge::SortPreservingMergeExec)
+pub(crate) fn merge_sorted_runs(
+    runs: Vec<SortedRun>,
+    expr: Vec<PhysicalSortExpr>,
+    schema: SchemaRef,
+    max_rows: usize,
+) -> SortedRun {
+    Box::pin(SortPreservingMergeStream::new(runs, expr, schema, max_rows))
+}
+
+pin_project! {
+    /// The batches of a run spilled to disk
+    struct SpilledRunStream {
+        #[pin]
+        batches: BoxStream<'static, ArrowResult<RecordBatch>>,
+        schema: SchemaRef,
+    }
+}
+
+impl Stream for SpilledRunStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().batches.poll_next(cx)
+    }
+}
+
+impl RecordBatchStream for SpilledRunStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryStream;
+    use arrow::array::{Array, Int32Array};
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn batch(schema: &SchemaRef, values: Vec<Option<i32>>) -> RecordBatch {
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(values))])
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn spill_and_merge() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let expr = vec![PhysicalSortExpr {
+            expr: col("a"),
+            options: SortOptions::default(),
+        }];
+
+        let runs = vec![
+            spill(batch(&schema, vec![Some(1), Some(4), Some(4), Some(9)]), 2).await?,
+            spill(batch(&schema, vec![None, Some(2), Some(4), Some(5)]), 3).await?,
+            Box::pin(MemoryStream::try_new(
+                vec![batch(&schema, vec![Some(3)])],
+                schema.clone(),
+                None,
+            )?) as SortedRun,
+            spill(batch(&schema, vec![]), 2).await?,
+        ];
+        let batches = merge_sorted_runs(runs, expr, schema, 2)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<ArrowResult<Vec<_>>>()?;
+        assert!(batches.iter().all(|batch| batch.num_rows() <= 2));
+
+        let values = batches
+            .iter()
+            .flat_map(|batch| {
+                let array = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                (0..array.len())
+                    .map(|i| Some(array.value(i)).filter(|_| array.is_valid(i)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                None,
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(4),
+                Some(4),
+                Some(4),
+                Some(5),
+                Some(9)
+            ]
+        );
+
+        Ok(())
+    }
+}
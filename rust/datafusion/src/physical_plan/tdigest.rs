@@ -0,0 +1,284 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! t-digest sketch, used to estimate the quantiles of a column in bounded memory.
+//!
+//! See "Computing extremely accurate quantiles using t-digests" by Dunning and
+//! Ertl. This is the merging variant of the algorithm with the `k1` scale
+//! function, which keeps the centroids small near the extreme quantiles.
+
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::f64::consts::PI;
+
+use crate::error::{DataFusionError, Result};
+
+/// Compression of the digests, bounding their number of centroids
+pub const DEFAULT_COMPRESSION: f64 = 100.0;
+
+/// A cluster of values, summarized by their mean and their number
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// A t-digest of a set of values.
+///
+/// Values are first buffered and then merged into the centroids in batches. Two
+/// digests can be merged, which is how partial digests are combined.
+#[derive(Clone, Debug)]
+pub struct TDigest {
+    compression: f64,
+    /// centroids, sorted by mean
+    centroids: Vec<Centroid>,
+    /// values not yet merged into the centroids
+    buffer: Vec<f64>,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    /// Creates an empty digest of the given compression
+    pub fn new(compression: f64) -> Self {
+        Self {
+            compression,
+            centroids: vec![],
+            buffer: vec![],
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Number of bytes of memory held by the digest
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.centroids.capacity() * std::mem::size_of::<Centroid>()
+            + self.buffer.capacity() * std::mem::size_of::<f64>()
+    }
+
+    /// Number of values in the digest
+    pub fn count(&self) -> f64 {
+        self.centroids.iter().map(|c| c.weight).sum::<f64>() + self.buffer.len() as f64
+    }
+
+    /// Adds a value to the digest. NaN values are ignored.
+    pub fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.buffer.push(value);
+        if self.buffer.len() >= 10 * self.compression as usize {
+            self.compress();
+        }
+    }
+
+    /// Merges another digest into this one
+    pub fn merge(&mut self, other: &TDigest) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.buffer.extend_from_slice(&other.buffer);
+        self.compress_with(&other.centroids);
+    }
+
+    /// Merges the buffered values into the centroids
+    fn compress(&mut self) {
+        self.compress_with(&[]);
+    }
+
+    /// Merges the buffered values and `others` into the centroids
+    fn compress_with(&mut self, others: &[Centroid]) {
+        if self.buffer.is_empty() && others.is_empty() {
+            return;
+        }
+        let mut centroids = std::mem::take(&mut self.centroids);
+        centroids.extend_from_slice(others);
+        centroids.extend(
+            self.buffer
+                .drain(..)
+                .map(|mean| Centroid { mean, weight: 1.0 }),
+        );
+        centroids.sort_by(|a, b| a.mean.partial_cmp(&b.mean).unwrap_or(Ordering::Equal));
+
+        let total = centroids.iter().map(|c| c.weight).sum::<f64>();
+        let k = |q: f64| self.compression / (2.0 * PI) * (2.0 * q - 1.0).asin();
+
+        let mut merged = Vec::with_capacity(centroids.len());
+        let mut current = centroids[0];
+        // total weight of the centroids before `current`
+        let mut weight_before = 0.0;
+        for next in centroids.into_iter().skip(1) {
+            let q0 = weight_before / total;
+            let q2 = ((weight_before + current.weight + next.weight) / total).min(1.0);
+            if k(q2) - k(q0) <= 1.0 {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_before += current.weight;
+                merged.push(current);
+                current = next;
+            }
+        }
+        merged.push(current);
+        self.centroids = merged;
+    }
+
+    /// Estimates the `q` quantile of the values, with `0 <= q <= 1`, interpolating
+    /// between the centroids. Returns `None` if the digest is empty.
+    ///
+    /// While all the centroids are single values, this is the same as a
+    /// continuous percentile.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let mut digest = self.clone();
+        digest.compress();
+        let centroids = &digest.centroids;
+        if centroids.is_empty() {
+            return None;
+        }
+        if centroids.len() == 1 {
+            return Some(centroids[0].mean);
+        }
+
+        // the position of every centroid is the middle of its weight, and the
+        // extreme values are single values at both ends
+        let total = digest.count();
+        let target = q * (total - 1.0) + 0.5;
+        let interpolate = |(x0, y0): (f64, f64), (x1, y1): (f64, f64)| {
+            if x1 <= x0 {
+                y0
+            } else {
+                y0 + (target - x0) / (x1 - x0) * (y1 - y0)
+            }
+        };
+
+        let mut previous = (0.5, digest.min);
+        let mut weight_before = 0.0;
+        for centroid in centroids {
+            let position = (weight_before + centroid.weight / 2.0).max(0.5);
+            if target < position {
+                return Some(interpolate(previous, (position, centroid.mean)));
+            }
+            previous = (position, centroid.mean);
+            weight_before += centroid.weight;
+        }
+        Some(interpolate(previous, (total - 0.5, digest.max)))
+    }
+
+    /// Serializes the digest, to be merged with [`TDigest::merge`] after
+    /// [`TDigest::try_from_bytes`]: the compression, the minimum and maximum
+    /// values and the mean and weight of every centroid, as little endian f64
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut digest = self.clone();
+        digest.compress();
+        let mut bytes = Vec::with_capacity(8 * (3 + 2 * digest.centroids.len()));
+        bytes.extend_from_slice(&digest.compression.to_le_bytes());
+        bytes.extend_from_slice(&digest.min.to_le_bytes());
+        bytes.extend_from_slice(&digest.max.to_le_bytes());
+        for centroid in &digest.centroids {
+            bytes.extend_from_slice(&centroid.mean.to_le_bytes());
+            bytes.extend_from_slice(&centroid.weight.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserializes a digest serialized by [`TDigest::to_bytes`]
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 24 || bytes.len() % 16 != 8 {
+            return Err(DataFusionError::Internal(format!(
+                "Invalid t-digest state of {} bytes",
+                bytes.len()
+            )));
+        }
+        let values = bytes
+            .chunks(8)
+            .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        Ok(Self {
+            compression: values[0],
+            min: values[1],
+            max: values[2],
+            centroids: values[3..]
+                .chunks(2)
+                .map(|c| Centroid {
+                    mean: c[0],
+                    weight: c[1],
+                })
+                .collect(),
+            buffer: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(values: impl Iterator<Item = f64>) -> TDigest {
+        let mut digest = TDigest::new(DEFAULT_COMPRESSION);
+        values.for_each(|v| digest.add(v));
+        digest
+    }
+
+    #[test]
+    fn exact_quantiles() {
+        let digest = digest(vec![3.0, 1.0, 4.0, 2.0].into_iter());
+        assert_eq!(Some(1.0), digest.quantile(0.0));
+        assert_eq!(Some(1.75), digest.quantile(0.25));
+        assert_eq!(Some(2.5), digest.quantile(0.5));
+        assert_eq!(Some(4.0), digest.quantile(1.0));
+
+        assert_eq!(Some(7.0), self::digest(std::iter::once(7.0)).quantile(0.3));
+        assert_eq!(None, TDigest::new(DEFAULT_COMPRESSION).quantile(0.5));
+    }
+
+    #[test]
+    fn approximate_quantiles() {
+        let digest = digest((0..100_000).map(|v| ((v * 7919) % 100_000) as f64));
+        assert!(digest.centroids.len() + digest.buffer.len() < 2000);
+        for q in &[0.01, 0.1, 0.5, 0.9, 0.99] {
+            let actual = digest.quantile(*q).unwrap();
+            let expected = q * 99_999.0;
+            assert!(
+                (actual - expected).abs() < 200.0,
+                "quantile {}: {} is not close to {}",
+                q,
+                actual,
+                expected
+            );
+        }
+        assert_eq!(Some(0.0), digest.quantile(0.0));
+        assert_eq!(Some(99_999.0), digest.quantile(1.0));
+    }
+
+    #[test]
+    fn merge() -> Result<()> {
+        let mut merged = TDigest::new(DEFAULT_COMPRESSION);
+        for i in 0..10 {
+            let partial = digest((0..10_000).map(|v| (i * 10_000 + v) as f64));
+            merged.merge(&TDigest::try_from_bytes(&partial.to_bytes())?);
+        }
+        assert_eq!(100_000.0, merged.count());
+        let median = merged.quantile(0.5).unwrap();
+        assert!((median - 49_999.5).abs() < 200.0, "{}", median);
+
+        assert!(TDigest::try_from_bytes(&[0; 20]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,553 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the TopK plan, that returns the first rows of a sort without sorting all
+//! of its input
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc;
+use futures::stream::Stream;
+use futures::{SinkExt, StreamExt};
+
+use pin_project_lite::pin_project;
+
+use arrow::array::ArrayRef;
+use arrow::compute::SortOptions;
+use arrow::datatypes::SchemaRef;
+use arrow::error::{ArrowError, Result as ArrowResult};
+use arrow::record_batch::RecordBatch;
+
+use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::datasource::datasource::Statistics;
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::limit::limit_statistics;
+use crate::physical_plan::sort::sort_batches;
+use crate::physical_plan::sort_utils::compare_rows;
+use crate::physical_plan::spill::batch_memory_size;
+use crate::physical_plan::{ExecutionPlan, Partitioning};
+
+use async_trait::async_trait;
+
+/// TopK execution plan, equivalent to a sort followed by a limit of `k` rows.
+///
+/// Each input partition is read in parallel and only its first `k` rows are kept, in a
+/// bounded heap. The rows kept for every partition are then sorted together into a
+/// single output partition. Unlike a sort, the rows cannot be spilled to disk: the
+/// execution fails if the first `k` rows of a partition exceed `memory_limit` bytes.
+#[derive(Debug)]
+pub struct TopKExec {
+    /// Input execution plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Sort expressions
+    expr: Vec<PhysicalSortExpr>,
+    /// Maximum number of rows to return
+    k: usize,
+    /// Number of bytes of memory that the rows kept for each partition may use
+    memory_limit: Option<usize>,
+}
+
+impl TopKExec {
+    /// Create a new TopKExec
+    pub fn new(
+        expr: Vec<PhysicalSortExpr>,
+        input: Arc<dyn ExecutionPlan>,
+        k: usize,
+    ) -> Self {
+        Self {
+            input,
+            expr,
+            k,
+            memory_limit: None,
+        }
+    }
+
+    /// Fail when the rows kept for a partition use more than `memory_limit` bytes
+    pub fn with_memory_limit(mut self, memory_limit: Option<usize>) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// Input execution plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Sort expressions
+    pub fn expr(&self) -> &[PhysicalSortExpr] {
+        &self.expr
+    }
+
+    /// Maximum number of rows to return
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Number of bytes of memory that the rows kept for each partition may use
+    pub fn memory_limit(&self) -> Option<usize> {
+        self.memory_limit
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for TopKExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(
+                TopKExec::new(self.expr.clone(), children[0].clone(), self.k)
+                    .with_memory_limit(self.memory_limit),
+            )),
+            _ => Err(DataFusionError::Internal(
+                "TopKExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "TopKExec invalid partition {}",
+                partition
+            )));
+        }
+
+        Ok(Box::pin(TopKStream::new(
+            self.input.clone(),
+            self.expr.clone(),
+            self.k,
+            self.memory_limit,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        limit_statistics(self.input.statistics(), self.k)
+    }
+}
+
+/// The first `k` rows of the batches pushed so far, in a binary heap whose root is
+/// the last of these rows, i.e. the first row to be replaced by a better row
+struct TopKHeap<'a> {
+    schema: SchemaRef,
+    expr: &'a [PhysicalSortExpr],
+    options: Vec<SortOptions>,
+    k: usize,
+    /// Batches holding the rows of the heap, with their sort keys
+    batches: Vec<(RecordBatch, Vec<ArrayRef>)>,
+    /// Number of rows of `batches`
+    num_rows: usize,
+    /// Memory used by `batches`, in bytes
+    memory_size: usize,
+    /// (batch, row) indices of the rows of the heap
+    heap: Vec<(usize, usize)>,
+}
+
+impl<'a> TopKHeap<'a> {
+    fn new(schema: SchemaRef, expr: &'a [PhysicalSortExpr], k: usize) -> Self {
+        Self {
+            schema,
+            expr,
+            options: expr.iter().map(|e| e.options).collect(),
+            k,
+            batches: vec![],
+            num_rows: 0,
+            memory_size: 0,
+            heap: vec![],
+        }
+    }
+
+    /// Compares the rows `left` and `right`, given by their (batch, row) indices
+    fn compare(
+        &self,
+        left: (usize, usize),
+        right: (usize, usize),
+    ) -> ArrowResult<Ordering> {
+        compare_rows(
+            &self.batches[left.0].1,
+            left.1,
+            &self.batches[right.0].1,
+            right.1,
+            &self.options,
+        )
+        .map_err(DataFusionError::into_arrow_external_error)
+    }
+
+    /// Moves the row at position `i` of the heap up to its place
+    fn sift_up(&mut self, mut i: usize) -> ArrowResult<()> {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.compare(self.heap[i], self.heap[parent])? != Ordering::Greater {
+                break;
+            }
+            self.heap.swap(i, parent);
+            i = parent;
+        }
+        Ok(())
+    }
+
+    /// Moves the row at position `i` of the heap down to its place
+    fn sift_down(&mut self, mut i: usize) -> ArrowResult<()> {
+        loop {
+            let mut largest = i;
+            for child in &[2 * i + 1, 2 * i + 2] {
+                if *child < self.heap.len()
+                    && self.compare(self.heap[*child], self.heap[largest])?
+                        == Ordering::Greater
+                {
+                    largest = *child;
+                }
+            }
+            if largest == i {
+                return Ok(());
+            }
+            self.heap.swap(i, largest);
+            i = largest;
+        }
+    }
+
+    /// Keeps a sorted batch, along with its sort keys
+    fn add_batch(&mut self, batch: RecordBatch) -> ArrowResult<usize> {
+        let keys = self
+            .expr
+            .iter()
+            .map(|e| {
+                e.expr
+                    .evaluate(&batch)
+                    .map(|value| value.into_array(batch.num_rows()))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map_err(DataFusionError::into_arrow_external_error)?;
+        self.num_rows += batch.num_rows();
+        self.memory_size += batch_memory_size(&batch);
+        self.batches.push((batch, keys));
+        Ok(self.batches.len() - 1)
+    }
+
+    /// Adds the rows of `batch` that are among the first `k` rows to the heap
+    fn push(&mut self, batch: &RecordBatch) -> ArrowResult<()> {
+        // only the first `k` rows of the batch may be among the first `k` rows
+        let batch = match sort_batches(
+            &[batch.clone()],
+            &self.schema,
+            self.expr,
+            Some(self.k),
+        )? {
+            Some(batch) => batch,
+            None => return Ok(()),
+        };
+        let num_rows = batch.num_rows();
+        let index = self.add_batch(batch)?;
+
+        for row in 0..num_rows {
+            if self.heap.len() < self.k {
+                self.heap.push((index, row));
+                self.sift_up(self.heap.len() - 1)?;
+            } else if self.compare((index, row), self.heap[0])? == Ordering::Less {
+                self.heap[0] = (index, row);
+                self.sift_down(0)?;
+            } else {
+                // the next rows of the sorted batch are not better either
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the batches by a single batch of the rows of the heap, dropping
+    /// the rows that have been replaced
+    fn compact(&mut self) -> ArrowResult<()> {
+        let batches = self
+            .batches
+            .drain(..)
+            .map(|(batch, _)| batch)
+            .collect::<Vec<_>>();
+        self.heap.clear();
+        self.num_rows = 0;
+        self.memory_size = 0;
+        if let Some(batch) =
+            sort_batches(&batches, &self.schema, self.expr, Some(self.k))?
+        {
+            // sorted rows in reverse order are a valid heap
+            let num_rows = batch.num_rows();
+            let index = self.add_batch(batch)?;
+            self.heap = (0..num_rows).rev().map(|row| (index, row)).collect();
+        }
+        Ok(())
+    }
+
+    /// Returns the rows of the heap, sorted
+    fn finish(self) -> ArrowResult<Option<RecordBatch>> {
+        let batches = self
+            .batches
+            .into_iter()
+            .map(|(batch, _)| batch)
+            .collect::<Vec<_>>();
+        sort_batches(&batches, &self.schema, self.expr, Some(self.k))
+    }
+}
+
+/// Returns the first `k` rows of `input` sorted on `expr`
+async fn top_k_partition(
+    mut input: SendableRecordBatchStream,
+    expr: &[PhysicalSortExpr],
+    k: usize,
+    memory_limit: Option<usize>,
+) -> ArrowResult<Option<RecordBatch>> {
+    let mut heap = TopKHeap::new(input.schema(), expr, k);
+
+    while let Some(batch) = input.next().await {
+        heap.push(&batch?)?;
+
+        // drop the replaced rows once they are as many as the rows of the heap, or
+        // when they exceed the memory limit
+        let over_limit = matches!(memory_limit, Some(limit) if heap.memory_size > limit);
+        if heap.num_rows > 2 * k || over_limit {
+            heap.compact()?;
+            if let Some(limit) = memory_limit.filter(|limit| heap.memory_size > *limit) {
+                return Err(DataFusionError::Execution(format!(
+                    "The first {} rows of a sort use more than the memory limit of {} \
+                    bytes",
+                    k, limit
+                ))
+                .into_arrow_external_error());
+            }
+        }
+    }
+
+    heap.finish()
+}
+
+/// Computes the first `k` rows of every partition of `input` in parallel and sends
+/// the first `k` rows of their union
+async fn top_k(
+    input: Arc<dyn ExecutionPlan>,
+    expr: Vec<PhysicalSortExpr>,
+    k: usize,
+    memory_limit: Option<usize>,
+    sender: &mut mpsc::Sender<ArrowResult<RecordBatch>>,
+) -> ArrowResult<()> {
+    if k == 0 {
+        return Ok(());
+    }
+
+    let handles = (0..input.output_partitioning().partition_count())
+        .map(|partition| {
+            let input = input.clone();
+            let expr = expr.clone();
+            tokio::spawn(async move {
+                let stream = input
+                    .execute(partition)
+                    .await
+                    .map_err(DataFusionError::into_arrow_external_error)?;
+                top_k_partition(stream, &expr, k, memory_limit).await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut batches = vec![];
+    for handle in handles {
+        let batch = handle
+            .await
+            .map_err(|e| ArrowError::ExternalError(Box::new(e)))??;
+        batches.extend(batch);
+    }
+
+    if let Some(batch) = sort_batches(&batches, &input.schema(), &expr, Some(k))? {
+        // If send fails, plan being torn down, there is no place to send the batch
+        sender.send(Ok(batch)).await.ok();
+    }
+    Ok(())
+}
+
+pin_project! {
+    struct TopKStream {
+        #[pin]
+        output: mpsc::Receiver<ArrowResult<RecordBatch>>,
+        schema: SchemaRef,
+    }
+}
+
+impl TopKStream {
+    fn new(
+        input: Arc<dyn ExecutionPlan>,
+        expr: Vec<PhysicalSortExpr>,
+        k: usize,
+        memory_limit: Option<usize>,
+    ) -> Self {
+        let (mut tx, rx) = mpsc::channel(1);
+
+        let schema = input.schema();
+        tokio::spawn(async move {
+            if let Err(e) = top_k(input, expr, k, memory_limit, &mut tx).await {
+                tx.send(Err(e)).await.ok();
+            }
+        });
+
+        Self { output: rx, schema }
+    }
+}
+
+impl Stream for TopKStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().output.poll_next(cx)
+    }
+}
+
+impl RecordBatchStream for TopKStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::sort::SortOptions;
+    use arrow::array::{Array, Int32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn batch(schema: &SchemaRef, values: Vec<Option<i32>>) -> RecordBatch {
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(values))])
+            .unwrap()
+    }
+
+    async fn top_k_values(
+        partitions: &[Vec<RecordBatch>],
+        schema: &SchemaRef,
+        options: SortOptions,
+        k: usize,
+    ) -> Result<Vec<Option<i32>>> {
+        let input = Arc::new(MemoryExec::try_new(partitions, schema.clone(), None)?);
+        let expr = vec![PhysicalSortExpr {
+            expr: col("a"),
+            options,
+        }];
+        let top_k = Arc::new(TopKExec::new(expr, input, k));
+        assert_eq!(1, top_k.output_partitioning().partition_count());
+
+        let batches = collect(top_k).await?;
+        Ok(batches
+            .iter()
+            .flat_map(|batch| {
+                let array = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                (0..array.len())
+                    .map(|i| Some(array.value(i)).filter(|_| array.is_valid(i)))
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn top_k() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let partitions = vec![
+            vec![
+                batch(&schema, vec![Some(5), Some(1), None, Some(8)]),
+                batch(&schema, vec![Some(7), Some(3)]),
+                batch(&schema, vec![Some(2), Some(9), Some(0)]),
+            ],
+            vec![batch(&schema, vec![Some(4), Some(6)])],
+            vec![],
+        ];
+
+        let values =
+            top_k_values(&partitions, &schema, SortOptions::default(), 3).await?;
+        assert_eq!(vec![None, Some(0), Some(1)], values);
+
+        let options = SortOptions {
+            descending: true,
+            nulls_first: false,
+        };
+        let values = top_k_values(&partitions, &schema, options, 4).await?;
+        assert_eq!(vec![Some(9), Some(8), Some(7), Some(6)], values);
+
+        // fewer rows than k
+        let values = top_k_values(&partitions, &schema, options, 100).await?;
+        assert_eq!(11, values.len());
+        assert_eq!(None, values[10]);
+
+        let values = top_k_values(&partitions, &schema, options, 0).await?;
+        assert!(values.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn top_k_of_many_batches() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let partitions = vec![(0..100)
+            .map(|i| batch(&schema, vec![Some(i * 7 % 100), Some(i)]))
+            .collect::<Vec<_>>()];
+
+        // the replaced rows are dropped as the batches are read
+        let values =
+            top_k_values(&partitions, &schema, SortOptions::default(), 3).await?;
+        assert_eq!(vec![Some(0), Some(0), Some(1)], values);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn top_k_memory_limit() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let partitions = vec![vec![batch(&schema, vec![Some(1), Some(2), Some(3)])]];
+        let input = Arc::new(MemoryExec::try_new(&partitions, schema, None)?);
+        let expr = vec![PhysicalSortExpr {
+            expr: col("a"),
+            options: SortOptions::default(),
+        }];
+        let top_k = Arc::new(TopKExec::new(expr, input, 2).with_memory_limit(Some(1)));
+
+        let err = collect(top_k).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("The first 2 rows of a sort use more than the memory limit"));
+        Ok(())
+    }
+}
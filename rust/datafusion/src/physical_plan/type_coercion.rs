@@ -123,10 +123,19 @@ fn get_valid_types(
         }
         Signature::OneOf(types) => {
             let mut r = vec![];
+            let mut last_error = None;
             for s in types {
-                r.extend(get_valid_types(s, current_types)?);
+                // a signature that cannot accept the arguments (e.g. a different
+                // number of arguments) does not invalidate the other signatures
+                match get_valid_types(s, current_types) {
+                    Ok(valid_types) => r.extend(valid_types),
+                    Err(e) => last_error = Some(e),
+                }
+            }
+            match (r.is_empty(), last_error) {
+                (true, Some(e)) => return Err(e),
+                _ => r,
             }
-            r
         }
     };
 
@@ -26,7 +26,9 @@ use std::{any::Any, sync::Arc};
 use arrow::datatypes::SchemaRef;
 
 use super::{ExecutionPlan, Partitioning, SendableRecordBatchStream};
+use crate::datasource::datasource::{scalar_to_f64, ColumnStatistics, Statistics};
 use crate::error::Result;
+use crate::scalar::ScalarValue;
 use async_trait::async_trait;
 
 /// UNION ALL execution plan
@@ -94,6 +96,42 @@ impl ExecutionPlan for UnionExec {
             partition
         )))
     }
+
+    /// The sums of the statistics of the inputs
+    fn statistics(&self) -> Statistics {
+        self.inputs
+            .iter()
+            .map(|input| input.statistics())
+            .reduce(|a, b| Statistics {
+                num_rows: a.num_rows.zip(b.num_rows).map(|(a, b)| a + b),
+                total_byte_size: a
+                    .total_byte_size
+                    .zip(b.total_byte_size)
+                    .map(|(a, b)| a + b),
+                column_statistics: a.column_statistics.zip(b.column_statistics).map(
+                    |(a, b)| a.into_iter().zip(b).map(union_column_statistics).collect(),
+                ),
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Combines the statistics of the same column of two inputs
+fn union_column_statistics(
+    (a, b): (ColumnStatistics, ColumnStatistics),
+) -> ColumnStatistics {
+    // min/max values can only be compared if they are numeric
+    let pick = |a: Option<ScalarValue>, b: Option<ScalarValue>, min: bool| {
+        let (a, b) = (a?, b?);
+        let (x, y) = (scalar_to_f64(&a)?, scalar_to_f64(&b)?);
+        Some(if (x < y) == min { a } else { b })
+    };
+    ColumnStatistics {
+        null_count: a.null_count.zip(b.null_count).map(|(a, b)| a + b),
+        max_value: pick(a.max_value, b.max_value, false),
+        min_value: pick(a.min_value, b.min_value, true),
+        distinct_count: None,
+    }
 }
 
 #[cfg(test)]
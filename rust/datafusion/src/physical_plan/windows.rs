@@ -0,0 +1,1065 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan for window functions, e.g. `ROW_NUMBER() OVER (PARTITION BY a ORDER BY b)`
+
+use std::any::Any;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::datasource::datasource::{ColumnStatistics, Statistics};
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::window_frames::{
+    WindowFrame, WindowFrameBound, WindowFrameUnits,
+};
+use crate::physical_plan::{
+    aggregates, common,
+    expressions::{Literal, PhysicalSortExpr},
+    window_functions::{return_type_for_built_in, BuiltInWindowFunction, WindowFunction},
+    Accumulator, AggregateExpr, Distribution, ExecutionPlan, Partitioning, PhysicalExpr,
+    RecordBatchStream, SendableRecordBatchStream, WindowExpr,
+};
+use crate::scalar::ScalarValue;
+
+use arrow::array::{build_compare, new_empty_array, ArrayRef, UInt32Array, UInt64Array};
+use arrow::compute::{
+    cast, concat, is_not_null, kernels::zip::zip, lexsort_to_indices, shift, take,
+    SortColumn, SortOptions,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::{ArrowError, Result as ArrowResult};
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+use futures::{Future, Stream};
+use pin_project_lite::pin_project;
+
+/// Create a physical expression for a window function
+pub fn create_window_expr(
+    fun: &WindowFunction,
+    name: String,
+    args: &[Arc<dyn PhysicalExpr>],
+    partition_by: &[Arc<dyn PhysicalExpr>],
+    order_by: &[PhysicalSortExpr],
+    window_frame: Option<WindowFrame>,
+    input_schema: &Schema,
+) -> Result<Arc<dyn WindowExpr>> {
+    let window = WindowSpec {
+        partition_by: partition_by.to_vec(),
+        order_by: order_by.to_vec(),
+        window_frame: window_frame.unwrap_or_default(),
+    };
+    Ok(match fun {
+        WindowFunction::AggregateFunction(fun) => Arc::new(AggregateWindowExpr {
+            aggregate: aggregates::create_aggregate_expr(
+                fun,
+                false,
+                args,
+                input_schema,
+                name.clone(),
+            )?,
+            name,
+            window,
+        }),
+        WindowFunction::BuiltInWindowFunction(fun) => Arc::new(
+            BuiltInWindowExpr::try_new(fun, name, args, window, input_schema)?,
+        ),
+    })
+}
+
+/// The `OVER (...)` clause of a window function
+#[derive(Debug)]
+struct WindowSpec {
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    order_by: Vec<PhysicalSortExpr>,
+    window_frame: WindowFrame,
+}
+
+/// The rows of a batch, ordered by the PARTITION BY and ORDER BY clauses of a
+/// window. All the ranges are expressed as positions in that order.
+struct SortedPartitions {
+    /// `indices[i]` is the row of the batch at position `i`, or `None` when
+    /// the batch did not need to be sorted
+    indices: Option<UInt32Array>,
+    /// the rows of each partition
+    partitions: Vec<Range<usize>>,
+    /// the rows of each group of peers, i.e. rows of the same partition
+    /// that are equal on all the ORDER BY expressions
+    peers: Vec<Range<usize>>,
+}
+
+impl WindowSpec {
+    /// Sorts the rows of `batch` by partition and order, and finds the
+    /// boundaries of the partitions and of the groups of peers
+    fn sort_partitions(&self, batch: &RecordBatch) -> Result<SortedPartitions> {
+        let num_rows = batch.num_rows();
+
+        let mut sort_columns = self
+            .partition_by
+            .iter()
+            .map(|e| {
+                Ok(SortColumn {
+                    values: e.evaluate(batch)?.into_array(num_rows),
+                    options: Some(SortOptions::default()),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let num_partition_columns = sort_columns.len();
+        for e in &self.order_by {
+            sort_columns.push(e.evaluate_to_sort_column(batch)?);
+        }
+
+        if sort_columns.is_empty() {
+            let all_rows = std::iter::once(0..num_rows)
+                .filter(|rows| !rows.is_empty())
+                .collect::<Vec<_>>();
+            return Ok(SortedPartitions {
+                indices: None,
+                partitions: all_rows.clone(),
+                peers: all_rows,
+            });
+        }
+
+        let indices = lexsort_to_indices(&sort_columns, None)?;
+        let sorted_columns = sort_columns
+            .iter()
+            .map(|c| take(c.values.as_ref(), &indices, None))
+            .collect::<ArrowResult<Vec<_>>>()?;
+
+        Ok(SortedPartitions {
+            indices: Some(indices),
+            partitions: find_ranges(&sorted_columns[..num_partition_columns], num_rows)?,
+            // partition columns are a prefix of the sort columns, so groups of
+            // peers never cross the boundary of a partition
+            peers: find_ranges(&sorted_columns, num_rows)?,
+        })
+    }
+}
+
+/// Splits `0..num_rows` into ranges of consecutive rows that are equal on all
+/// `columns`
+fn find_ranges(columns: &[ArrayRef], num_rows: usize) -> Result<Vec<Range<usize>>> {
+    let comparators = columns
+        .iter()
+        .map(|c| build_compare(c.as_ref(), c.as_ref()))
+        .collect::<ArrowResult<Vec<_>>>()?;
+
+    let mut ranges = vec![];
+    let mut start = 0;
+    for row in 1..num_rows {
+        let equal = columns.iter().zip(comparators.iter()).all(|(column, cmp)| {
+            match (column.is_valid(row - 1), column.is_valid(row)) {
+                (true, true) => cmp(row - 1, row) == std::cmp::Ordering::Equal,
+                (false, false) => true,
+                _ => false,
+            }
+        });
+        if !equal {
+            ranges.push(start..row);
+            start = row;
+        }
+    }
+    if start < num_rows {
+        ranges.push(start..num_rows);
+    }
+    Ok(ranges)
+}
+
+impl SortedPartitions {
+    /// Reorders `array`, aligned with the rows of the batch, in the sorted order
+    fn sort(&self, array: ArrayRef) -> Result<ArrayRef> {
+        match &self.indices {
+            Some(indices) => Ok(take(array.as_ref(), indices, None)?),
+            None => Ok(array),
+        }
+    }
+
+    /// Reorders `array`, aligned with the sorted order, back in the order of
+    /// the rows of the batch
+    fn unsort(&self, array: ArrayRef) -> Result<ArrayRef> {
+        match &self.indices {
+            Some(indices) => {
+                let mut positions = vec![0_u32; indices.len()];
+                for (position, row) in indices.values().iter().enumerate() {
+                    positions[*row as usize] = position as u32;
+                }
+                Ok(take(array.as_ref(), &UInt32Array::from(positions), None)?)
+            }
+            None => Ok(array),
+        }
+    }
+
+    /// Calls `f(partition, peers, row)` for each row, in the sorted order
+    fn for_each_row<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&Range<usize>, &Range<usize>, usize) -> Result<()>,
+    {
+        let mut partitions = self.partitions.iter();
+        let mut partition = match partitions.next() {
+            Some(partition) => partition,
+            None => return Ok(()),
+        };
+        for peers in &self.peers {
+            while partition.end <= peers.start {
+                partition = partitions.next().ok_or_else(|| {
+                    DataFusionError::Internal(
+                        "Window peers are not within a partition".to_owned(),
+                    )
+                })?;
+            }
+            for row in peers.clone() {
+                f(partition, peers, row)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the positions of the rows in the frame of the row at `row`
+fn frame_range(
+    window_frame: &WindowFrame,
+    row: usize,
+    partition: &Range<usize>,
+    peers: &Range<usize>,
+) -> Range<usize> {
+    let start = match (window_frame.units, window_frame.start_bound) {
+        (_, WindowFrameBound::Preceding(None)) => partition.start,
+        (_, WindowFrameBound::Following(None)) => partition.end,
+        (WindowFrameUnits::Rows, WindowFrameBound::Preceding(Some(n))) => {
+            row.saturating_sub(n as usize).max(partition.start)
+        }
+        (WindowFrameUnits::Rows, WindowFrameBound::CurrentRow) => row,
+        (WindowFrameUnits::Rows, WindowFrameBound::Following(Some(n))) => {
+            (row + n as usize).min(partition.end)
+        }
+        // for RANGE and GROUPS, the peers of the current row are in the frame
+        _ => peers.start,
+    };
+    let end = match (window_frame.units, window_frame.end_bound) {
+        (_, WindowFrameBound::Preceding(None)) => partition.start,
+        (_, WindowFrameBound::Following(None)) => partition.end,
+        (WindowFrameUnits::Rows, WindowFrameBound::Preceding(Some(n))) => {
+            (row + 1).saturating_sub(n as usize).max(partition.start)
+        }
+        (WindowFrameUnits::Rows, WindowFrameBound::CurrentRow) => row + 1,
+        (WindowFrameUnits::Rows, WindowFrameBound::Following(Some(n))) => {
+            (row + n as usize + 1).min(partition.end)
+        }
+        _ => peers.end,
+    };
+    start..end.max(start)
+}
+
+/// A window expression backed by a built-in window function, e.g. `RANK()`
+#[derive(Debug)]
+pub struct BuiltInWindowExpr {
+    fun: BuiltInWindowFunction,
+    name: String,
+    data_type: DataType,
+    args: Vec<Arc<dyn PhysicalExpr>>,
+    /// number of rows to look back (LAG) or ahead (LEAD)
+    offset: i64,
+    /// value of LAG and LEAD when the row at `offset` is outside the partition
+    default_value: Option<ScalarValue>,
+    window: WindowSpec,
+}
+
+/// Returns the value of an argument that must be a literal
+fn literal_arg<'a>(
+    fun: &BuiltInWindowFunction,
+    expr: &'a Arc<dyn PhysicalExpr>,
+) -> Result<&'a ScalarValue> {
+    expr.as_any()
+        .downcast_ref::<Literal>()
+        .map(|literal| literal.value())
+        .ok_or_else(|| {
+            DataFusionError::NotImplemented(format!(
+                "{} only supports literal values as offset and default arguments",
+                fun
+            ))
+        })
+}
+
+impl BuiltInWindowExpr {
+    /// Create a new built-in window expression
+    fn try_new(
+        fun: &BuiltInWindowFunction,
+        name: String,
+        args: &[Arc<dyn PhysicalExpr>],
+        window: WindowSpec,
+        input_schema: &Schema,
+    ) -> Result<Self> {
+        let arg_types = args
+            .iter()
+            .map(|e| e.data_type(input_schema))
+            .collect::<Result<Vec<_>>>()?;
+        let data_type = return_type_for_built_in(fun, &arg_types)?;
+
+        let offset = match args.get(1).map(|e| literal_arg(fun, e)).transpose()? {
+            None => 1,
+            Some(ScalarValue::Int8(Some(v))) => *v as i64,
+            Some(ScalarValue::Int16(Some(v))) => *v as i64,
+            Some(ScalarValue::Int32(Some(v))) => *v as i64,
+            Some(ScalarValue::Int64(Some(v))) => *v,
+            Some(ScalarValue::UInt8(Some(v))) => *v as i64,
+            Some(ScalarValue::UInt16(Some(v))) => *v as i64,
+            Some(ScalarValue::UInt32(Some(v))) => *v as i64,
+            Some(other) => {
+                return Err(DataFusionError::Plan(format!(
+                    "The offset of {} must be an integer, found {:?}",
+                    fun, other
+                )))
+            }
+        };
+        let default_value = args
+            .get(2)
+            .map(|e| {
+                let value = literal_arg(fun, e)?.to_array();
+                ScalarValue::try_from_array(&cast(&value, &data_type)?, 0)
+            })
+            .transpose()?;
+
+        Ok(Self {
+            fun: fun.clone(),
+            name,
+            data_type,
+            args: args.to_vec(),
+            offset,
+            default_value,
+            window,
+        })
+    }
+
+    /// Evaluates LAG (positive `offset`) or LEAD (negative `offset`) over the
+    /// sorted `values`
+    fn evaluate_shift(
+        &self,
+        sorted: &SortedPartitions,
+        values: &ArrayRef,
+        offset: i64,
+    ) -> Result<ArrayRef> {
+        // shift the positions of the rows within each partition: positions that
+        // fall outside of the partition become null
+        let shifted_positions = sorted
+            .partitions
+            .iter()
+            .map(|partition| {
+                let positions = UInt32Array::from(
+                    (partition.start as u32..partition.end as u32).collect::<Vec<_>>(),
+                );
+                let len = partition.len() as i64;
+                shift(&positions, offset.max(-len).min(len))
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        let positions = concat(
+            &shifted_positions
+                .iter()
+                .map(|a| a.as_ref())
+                .collect::<Vec<_>>(),
+        )?;
+        let positions = positions
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .ok_or_else(|| {
+                DataFusionError::Internal("Expected shifted UInt32Array".to_owned())
+            })?;
+
+        let shifted = take(values.as_ref(), positions, None)?;
+        match &self.default_value {
+            Some(default_value) => Ok(zip(
+                &is_not_null(positions)?,
+                shifted.as_ref(),
+                default_value.to_array_of_size(shifted.len()).as_ref(),
+            )?),
+            None => Ok(shifted),
+        }
+    }
+}
+
+impl WindowExpr for BuiltInWindowExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, self.data_type.clone(), true))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let num_rows = batch.num_rows();
+        if num_rows == 0 {
+            return Ok(new_empty_array(&self.data_type));
+        }
+        let sorted = self.window.sort_partitions(batch)?;
+
+        let result: ArrayRef = match self.fun {
+            BuiltInWindowFunction::RowNumber => {
+                let mut values = Vec::with_capacity(num_rows);
+                for partition in &sorted.partitions {
+                    values.extend(1..=partition.len() as u64);
+                }
+                Arc::new(UInt64Array::from(values))
+            }
+            BuiltInWindowFunction::Rank => {
+                let mut values = Vec::with_capacity(num_rows);
+                sorted.for_each_row(|partition, peers, _| {
+                    values.push((peers.start - partition.start + 1) as u64);
+                    Ok(())
+                })?;
+                Arc::new(UInt64Array::from(values))
+            }
+            BuiltInWindowFunction::DenseRank => {
+                let mut values = Vec::with_capacity(num_rows);
+                let mut rank = 0_u64;
+                sorted.for_each_row(|partition, peers, row| {
+                    if row == partition.start {
+                        rank = 0;
+                    }
+                    if row == peers.start {
+                        rank += 1;
+                    }
+                    values.push(rank);
+                    Ok(())
+                })?;
+                Arc::new(UInt64Array::from(values))
+            }
+            BuiltInWindowFunction::Lag | BuiltInWindowFunction::Lead => {
+                let values =
+                    sorted.sort(self.args[0].evaluate(batch)?.into_array(num_rows))?;
+                let offset = match self.fun {
+                    BuiltInWindowFunction::Lag => self.offset,
+                    _ => -self.offset,
+                };
+                self.evaluate_shift(&sorted, &values, offset)?
+            }
+            BuiltInWindowFunction::FirstValue | BuiltInWindowFunction::LastValue => {
+                let values =
+                    sorted.sort(self.args[0].evaluate(batch)?.into_array(num_rows))?;
+                let mut positions = Vec::with_capacity(num_rows);
+                sorted.for_each_row(|partition, peers, row| {
+                    let frame =
+                        frame_range(&self.window.window_frame, row, partition, peers);
+                    positions.push(match (&self.fun, frame.is_empty()) {
+                        (_, true) => None,
+                        (BuiltInWindowFunction::FirstValue, false) => {
+                            Some(frame.start as u32)
+                        }
+                        (_, false) => Some(frame.end as u32 - 1),
+                    });
+                    Ok(())
+                })?;
+                take(values.as_ref(), &UInt32Array::from(positions), None)?
+            }
+        };
+
+        sorted.unsort(result)
+    }
+}
+
+/// A window expression backed by an aggregate function, e.g. `SUM(c1) OVER (...)`,
+/// evaluated over the window frame of each row
+#[derive(Debug)]
+pub struct AggregateWindowExpr {
+    aggregate: Arc<dyn AggregateExpr>,
+    name: String,
+    window: WindowSpec,
+}
+
+impl WindowExpr for AggregateWindowExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        self.aggregate.field()
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let num_rows = batch.num_rows();
+        let data_type = self.aggregate.field()?.data_type().clone();
+        if num_rows == 0 {
+            return Ok(new_empty_array(&data_type));
+        }
+        let sorted = self.window.sort_partitions(batch)?;
+
+        let values = self
+            .aggregate
+            .expressions()
+            .iter()
+            .map(|e| sorted.sort(e.evaluate(batch)?.into_array(num_rows)))
+            .collect::<Result<Vec<_>>>()?;
+        let slice = |range: Range<usize>| {
+            values
+                .iter()
+                .map(|v| v.slice(range.start, range.len()))
+                .collect::<Vec<_>>()
+        };
+
+        // the accumulator of the previous row, with the frame it has accumulated.
+        // Frames that share their start with the previous row's frame (e.g.
+        // `UNBOUNDED PRECEDING`) only accumulate the new rows.
+        let mut state: Option<(Range<usize>, Box<dyn Accumulator>)> = None;
+        let mut results = Vec::with_capacity(num_rows);
+        sorted.for_each_row(|partition, peers, row| {
+            let frame = frame_range(&self.window.window_frame, row, partition, peers);
+            match &mut state {
+                Some((accumulated, accumulator))
+                    if accumulated.start == frame.start
+                        && accumulated.end <= frame.end =>
+                {
+                    if accumulated.end < frame.end {
+                        accumulator.update_batch(&slice(accumulated.end..frame.end))?;
+                        accumulated.end = frame.end;
+                    }
+                    results.push(accumulator.evaluate()?);
+                }
+                _ => {
+                    let mut accumulator = self.aggregate.create_accumulator()?;
+                    accumulator.update_batch(&slice(frame.clone()))?;
+                    results.push(accumulator.evaluate()?);
+                    state = Some((frame, accumulator));
+                }
+            }
+            Ok(())
+        })?;
+
+        let results = results.iter().map(|v| v.to_array()).collect::<Vec<_>>();
+        let result = concat(&results.iter().map(|a| a.as_ref()).collect::<Vec<_>>())?;
+        sorted.unsort(cast(&result, &data_type)?)
+    }
+}
+
+/// Window execution plan: evaluates window functions over all the rows of its
+/// input, appending one column per window function
+#[derive(Debug)]
+pub struct WindowAggExec {
+    /// Input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Window function expressions
+    window_expr: Vec<Arc<dyn WindowExpr>>,
+    /// Schema after the window is run
+    schema: SchemaRef,
+    /// Schema before the window
+    input_schema: SchemaRef,
+}
+
+fn create_schema(
+    input_schema: &Schema,
+    window_expr: &[Arc<dyn WindowExpr>],
+) -> Result<Schema> {
+    let mut fields = input_schema.fields().clone();
+    for expr in window_expr {
+        fields.push(expr.field()?);
+    }
+    Ok(Schema::new(fields))
+}
+
+impl WindowAggExec {
+    /// Create a new window execution plan
+    pub fn try_new(
+        window_expr: Vec<Arc<dyn WindowExpr>>,
+        input: Arc<dyn ExecutionPlan>,
+        input_schema: SchemaRef,
+    ) -> Result<Self> {
+        let schema = Arc::new(create_schema(&input.schema(), &window_expr)?);
+        Ok(WindowAggExec {
+            input,
+            window_expr,
+            schema,
+            input_schema,
+        })
+    }
+
+    /// Window expressions
+    pub fn window_expr(&self) -> &[Arc<dyn WindowExpr>] {
+        &self.window_expr
+    }
+
+    /// Input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Get the input schema before any window functions are applied
+    pub fn input_schema(&self) -> SchemaRef {
+        self.input_schema.clone()
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for WindowAggExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::SinglePartition
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(WindowAggExec::try_new(
+                self.window_expr.clone(),
+                children[0].clone(),
+                self.input_schema.clone(),
+            )?)),
+            _ => Err(DataFusionError::Internal(
+                "WindowAggExec wrong number of children".to_owned(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "WindowAggExec invalid partition {}",
+                partition
+            )));
+        }
+
+        // window needs to operate on a single partition currently
+        if 1 != self.input.output_partitioning().partition_count() {
+            return Err(DataFusionError::Internal(
+                "WindowAggExec requires a single input partition".to_owned(),
+            ));
+        }
+
+        let input = self.input.execute(0).await?;
+
+        Ok(Box::pin(WindowAggStream::new(
+            self.schema.clone(),
+            self.window_expr.clone(),
+            input,
+        )))
+    }
+
+    /// The statistics of the input, as the window functions produce one
+    /// value per input row
+    fn statistics(&self) -> Statistics {
+        let input_statistics = self.input.statistics();
+        let window_columns =
+            self.schema.fields().len() - self.input_schema.fields().len();
+        let column_statistics = input_statistics.column_statistics.map(|columns| {
+            columns
+                .into_iter()
+                .chain(vec![ColumnStatistics::default(); window_columns])
+                .collect()
+        });
+        Statistics {
+            num_rows: input_statistics.num_rows,
+            total_byte_size: None,
+            column_statistics,
+        }
+    }
+}
+
+/// Evaluates all the window expressions over the concatenated `batches`
+fn compute_window_aggregates(
+    batches: &[RecordBatch],
+    input_schema: &SchemaRef,
+    schema: &SchemaRef,
+    window_expr: &[Arc<dyn WindowExpr>],
+) -> ArrowResult<Option<RecordBatch>> {
+    if batches.is_empty() {
+        return Ok(None);
+    }
+    // combine all record batches into one for each column
+    let combined_batch = RecordBatch::try_new(
+        input_schema.clone(),
+        (0..input_schema.fields().len())
+            .map(|i| {
+                concat(
+                    &batches
+                        .iter()
+                        .map(|batch| batch.column(i).as_ref())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<ArrowResult<Vec<ArrayRef>>>()?,
+    )?;
+
+    let mut columns = combined_batch.columns().to_vec();
+    for expr in window_expr {
+        columns.push(
+            expr.evaluate(&combined_batch)
+                .map_err(DataFusionError::into_arrow_external_error)?,
+        );
+    }
+    RecordBatch::try_new(schema.clone(), columns).map(Some)
+}
+
+pin_project! {
+    struct WindowAggStream {
+        #[pin]
+        output: futures::channel::oneshot::Receiver<ArrowResult<Option<RecordBatch>>>,
+        finished: bool,
+        schema: SchemaRef,
+    }
+}
+
+impl WindowAggStream {
+    fn new(
+        schema: SchemaRef,
+        window_expr: Vec<Arc<dyn WindowExpr>>,
+        input: SendableRecordBatchStream,
+    ) -> Self {
+        let (tx, rx) = futures::channel::oneshot::channel();
+
+        let schema_clone = schema.clone();
+        tokio::spawn(async move {
+            let input_schema = input.schema();
+            let result = common::collect(input)
+                .await
+                .map_err(DataFusionError::into_arrow_external_error)
+                .and_then(|batches| {
+                    compute_window_aggregates(
+                        &batches,
+                        &input_schema,
+                        &schema_clone,
+                        &window_expr,
+                    )
+                });
+
+            tx.send(result)
+        });
+
+        Self {
+            output: rx,
+            finished: false,
+            schema,
+        }
+    }
+}
+
+impl Stream for WindowAggStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        // is the output ready?
+        let this = self.project();
+        let output_poll = this.output.poll(cx);
+
+        match output_poll {
+            Poll::Ready(result) => {
+                *this.finished = true;
+
+                // check for error in receiving channel and unwrap actual result
+                let result = match result {
+                    Err(e) => Some(Err(ArrowError::ExternalError(Box::new(e)))), // error receiving
+                    Ok(result) => result.transpose(),
+                };
+                Poll::Ready(result)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl RecordBatchStream for WindowAggStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::physical_plan::aggregates::AggregateFunction;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::expressions::{col, lit};
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::test::build_table_i32;
+
+    fn build_table(
+        a: (&str, &Vec<i32>),
+        b: (&str, &Vec<i32>),
+        c: (&str, &Vec<i32>),
+    ) -> Arc<dyn ExecutionPlan> {
+        let batch = build_table_i32(a, b, c);
+        let schema = batch.schema();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    fn window(
+        fun: WindowFunction,
+        name: &str,
+        args: &[Arc<dyn PhysicalExpr>],
+        window_frame: Option<WindowFrame>,
+        input: &Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn WindowExpr>> {
+        create_window_expr(
+            &fun,
+            name.to_owned(),
+            args,
+            &[col("a")],
+            &[PhysicalSortExpr {
+                expr: col("b"),
+                options: SortOptions::default(),
+            }],
+            window_frame,
+            input.schema().as_ref(),
+        )
+    }
+
+    #[tokio::test]
+    async fn window_ranking_functions() -> Result<()> {
+        let input = build_table(
+            ("a", &vec![1, 2, 1, 1, 2, 1]),
+            ("b", &vec![30, 20, 10, 10, 10, 20]),
+            ("c", &vec![1, 2, 3, 4, 5, 6]),
+        );
+        let window_expr = vec![
+            window(
+                WindowFunction::BuiltInWindowFunction(BuiltInWindowFunction::RowNumber),
+                "row_number",
+                &[],
+                None,
+                &input,
+            )?,
+            window(
+                WindowFunction::BuiltInWindowFunction(BuiltInWindowFunction::Rank),
+                "rank",
+                &[],
+                None,
+                &input,
+            )?,
+            window(
+                WindowFunction::BuiltInWindowFunction(BuiltInWindowFunction::DenseRank),
+                "dense_rank",
+                &[],
+                None,
+                &input,
+            )?,
+        ];
+        let window = Arc::new(WindowAggExec::try_new(
+            window_expr,
+            input.clone(),
+            input.schema(),
+        )?);
+
+        let columns = window
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec!["a", "b", "c", "row_number", "rank", "dense_rank"]
+        );
+
+        // results are in the order of the input
+        let batches = collect(window).await?;
+        let expected = vec![
+            "+---+----+---+------------+------+------------+",
+            "| a | b  | c | row_number | rank | dense_rank |",
+            "+---+----+---+------------+------+------------+",
+            "| 1 | 30 | 1 | 4          | 4    | 3          |",
+            "| 2 | 20 | 2 | 2          | 2    | 2          |",
+            "| 1 | 10 | 3 | 1          | 1    | 1          |",
+            "| 1 | 10 | 4 | 2          | 1    | 1          |",
+            "| 2 | 10 | 5 | 1          | 1    | 1          |",
+            "| 1 | 20 | 6 | 3          | 3    | 2          |",
+            "+---+----+---+------------+------+------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn window_value_functions() -> Result<()> {
+        let input = build_table(
+            ("a", &vec![1, 1, 2, 1, 2]),
+            ("b", &vec![10, 20, 10, 30, 20]),
+            ("c", &vec![1, 2, 3, 4, 5]),
+        );
+        let built_in = WindowFunction::BuiltInWindowFunction;
+        let window_expr = vec![
+            window(
+                built_in(BuiltInWindowFunction::Lag),
+                "lag",
+                &[col("c")],
+                None,
+                &input,
+            )?,
+            window(
+                built_in(BuiltInWindowFunction::Lead),
+                "lead",
+                &[
+                    col("c"),
+                    lit(ScalarValue::Int64(Some(2))),
+                    lit(ScalarValue::Int64(Some(0))),
+                ],
+                None,
+                &input,
+            )?,
+            window(
+                built_in(BuiltInWindowFunction::FirstValue),
+                "first_value",
+                &[col("c")],
+                None,
+                &input,
+            )?,
+            window(
+                built_in(BuiltInWindowFunction::LastValue),
+                "last_value",
+                &[col("c")],
+                None,
+                &input,
+            )?,
+        ];
+        let window = Arc::new(WindowAggExec::try_new(
+            window_expr,
+            input.clone(),
+            input.schema(),
+        )?);
+
+        let batches = collect(window).await?;
+        let expected = vec![
+            "+---+----+---+-----+------+-------------+------------+",
+            "| a | b  | c | lag | lead | first_value | last_value |",
+            "+---+----+---+-----+------+-------------+------------+",
+            "| 1 | 10 | 1 |     | 4    | 1           | 1          |",
+            "| 1 | 20 | 2 | 1   | 0    | 1           | 2          |",
+            "| 2 | 10 | 3 |     | 0    | 3           | 3          |",
+            "| 1 | 30 | 4 | 2   | 0    | 1           | 4          |",
+            "| 2 | 20 | 5 | 3   | 0    | 3           | 5          |",
+            "+---+----+---+-----+------+-------------+------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn window_aggregate_functions() -> Result<()> {
+        let input = build_table(
+            ("a", &vec![1, 1, 2, 1, 2]),
+            ("b", &vec![10, 20, 10, 30, 20]),
+            ("c", &vec![1, 2, 3, 4, 5]),
+        );
+        let window_expr = vec![
+            // running sum, i.e. RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+            window(
+                WindowFunction::AggregateFunction(AggregateFunction::Sum),
+                "sum",
+                &[col("c")],
+                None,
+                &input,
+            )?,
+            // moving window over the previous, current and next rows
+            window(
+                WindowFunction::AggregateFunction(AggregateFunction::Max),
+                "max",
+                &[col("c")],
+                Some(WindowFrame {
+                    units: WindowFrameUnits::Rows,
+                    start_bound: WindowFrameBound::Preceding(Some(1)),
+                    end_bound: WindowFrameBound::Following(Some(1)),
+                }),
+                &input,
+            )?,
+            window(
+                WindowFunction::AggregateFunction(AggregateFunction::Count),
+                "count",
+                &[col("c")],
+                Some(WindowFrame {
+                    units: WindowFrameUnits::Rows,
+                    start_bound: WindowFrameBound::Preceding(None),
+                    end_bound: WindowFrameBound::Following(None),
+                }),
+                &input,
+            )?,
+        ];
+        let window = Arc::new(WindowAggExec::try_new(
+            window_expr,
+            input.clone(),
+            input.schema(),
+        )?);
+
+        let batches = collect(window).await?;
+        let expected = vec![
+            "+---+----+---+-----+-----+-------+",
+            "| a | b  | c | sum | max | count |",
+            "+---+----+---+-----+-----+-------+",
+            "| 1 | 10 | 1 | 1   | 2   | 3     |",
+            "| 1 | 20 | 2 | 3   | 4   | 3     |",
+            "| 2 | 10 | 3 | 3   | 5   | 2     |",
+            "| 1 | 30 | 4 | 7   | 4   | 3     |",
+            "| 2 | 20 | 5 | 8   | 5   | 2     |",
+            "+---+----+---+-----+-----+-------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn window_empty_input() -> Result<()> {
+        let input = build_table(("a", &vec![]), ("b", &vec![]), ("c", &vec![]));
+        let window_expr = vec![window(
+            WindowFunction::BuiltInWindowFunction(BuiltInWindowFunction::RowNumber),
+            "row_number",
+            &[],
+            None,
+            &input,
+        )?];
+        let window = Arc::new(WindowAggExec::try_new(
+            window_expr,
+            input.clone(),
+            input.schema(),
+        )?);
+
+        let batches = collect(window).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+
+        Ok(())
+    }
+}
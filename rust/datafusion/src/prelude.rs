@@ -28,10 +28,11 @@
 pub use crate::dataframe::DataFrame;
 pub use crate::execution::context::{ExecutionConfig, ExecutionContext};
 pub use crate::logical_plan::{
-    array, ascii, avg, bit_length, btrim, character_length, chr, col, concat, concat_ws,
-    count, create_udf, in_list, initcap, left, length, lit, lower, lpad, ltrim, max, md5,
-    min, octet_length, regexp_replace, repeat, replace, reverse, right, rpad, rtrim,
-    sha224, sha256, sha384, sha512, split_part, starts_with, strpos, substr, sum, to_hex,
-    translate, trim, upper, JoinType, Partitioning,
+    array, array_agg, ascii, avg, bit_length, btrim, character_length, chr, col, concat,
+    concat_ws, count, create_udf, in_list, initcap, left, length, lit, lower, lpad,
+    ltrim, max, md5, min, octet_length, regexp_replace, repeat, replace, reverse, right,
+    rpad, rtrim, sha224, sha256, sha384, sha512, split_part, starts_with, string_agg,
+    strpos, substr, sum, to_hex, translate, trim, upper, JoinType, Partitioning,
 };
 pub use crate::physical_plan::csv::CsvReadOptions;
+pub use crate::physical_plan::json::NdJsonReadOptions;
@@ -24,6 +24,8 @@ use arrow::array::{
     TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
     UInt16Builder, UInt32Builder, UInt64Builder, UInt8Builder,
 };
+use arrow::buffer::Buffer;
+use arrow::compute::concat;
 use arrow::datatypes::{DataType, Field, IntervalUnit, TimeUnit};
 use arrow::{
     array::*,
@@ -42,6 +44,8 @@ pub enum ScalarValue {
     Float32(Option<f32>),
     /// 64bit float
     Float64(Option<f64>),
+    /// 128bit decimal, using the i128 to represent the decimal, precision and scale
+    Decimal128(Option<i128>, usize, usize),
     /// signed 8bit int
     Int8(Option<i8>),
     /// signed 16bit int
@@ -156,6 +160,9 @@ impl ScalarValue {
             }
             ScalarValue::Float32(_) => DataType::Float32,
             ScalarValue::Float64(_) => DataType::Float64,
+            ScalarValue::Decimal128(_, precision, scale) => {
+                DataType::Decimal(*precision, *scale)
+            }
             ScalarValue::Utf8(_) => DataType::Utf8,
             ScalarValue::LargeUtf8(_) => DataType::LargeUtf8,
             ScalarValue::Binary(_) => DataType::Binary,
@@ -187,6 +194,9 @@ impl ScalarValue {
             ScalarValue::Int16(Some(v)) => ScalarValue::Int16(Some(-v)),
             ScalarValue::Int32(Some(v)) => ScalarValue::Int32(Some(-v)),
             ScalarValue::Int64(Some(v)) => ScalarValue::Int64(Some(-v)),
+            ScalarValue::Decimal128(v, precision, scale) => {
+                ScalarValue::Decimal128(v.map(|v| -v), *precision, *scale)
+            }
             _ => panic!("Cannot run arithmetic negate on scalar value: {:?}", self),
         }
     }
@@ -206,6 +216,7 @@ impl ScalarValue {
                 | ScalarValue::Int64(None)
                 | ScalarValue::Float32(None)
                 | ScalarValue::Float64(None)
+                | ScalarValue::Decimal128(None, _, _)
                 | ScalarValue::Utf8(None)
                 | ScalarValue::LargeUtf8(None)
                 | ScalarValue::List(None, _)
@@ -215,6 +226,26 @@ impl ScalarValue {
         )
     }
 
+    /// Number of bytes of memory held by this value, including the memory it
+    /// allocates
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + match self {
+                ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => {
+                    v.capacity()
+                }
+                ScalarValue::Binary(Some(v)) | ScalarValue::LargeBinary(Some(v)) => {
+                    v.capacity()
+                }
+                ScalarValue::List(Some(values), _) => {
+                    values.iter().map(|v| v.size()).sum::<usize>()
+                        + (values.capacity() - values.len())
+                            * std::mem::size_of::<ScalarValue>()
+                }
+                _ => 0,
+            }
+    }
+
     /// Converts a scalar value into an 1-row array.
     pub fn to_array(&self) -> ArrayRef {
         self.to_array_of_size(1)
@@ -234,6 +265,14 @@ impl ScalarValue {
                 Some(value) => Arc::new(Float32Array::from_value(*value, size)),
                 None => new_null_array(&DataType::Float32, size),
             },
+            ScalarValue::Decimal128(e, precision, scale) => match e {
+                Some(value) => Arc::new(DecimalArray::from_sparse_iter(
+                    vec![Some(*value); size],
+                    *precision,
+                    *scale,
+                )),
+                None => new_null_array(&DataType::Decimal(*precision, *scale), size),
+            },
             ScalarValue::Int8(e) => match e {
                 Some(value) => Arc::new(Int8Array::from_value(*value, size)),
                 None => new_null_array(&DataType::Int8, size),
@@ -339,7 +378,27 @@ impl ScalarValue {
                 DataType::LargeUtf8 => {
                     build_list!(LargeStringBuilder, LargeUtf8, values, size)
                 }
-                _ => panic!("Unexpected DataType for list"),
+                // the lists of other types are built from the arrays of their values
+                _ => match values {
+                    Some(values) => {
+                        let len = values.len();
+                        let values = ScalarValue::iter_to_array(
+                            repeat(values).take(size).flatten(),
+                            data_type,
+                        )
+                        .expect("Incompatible ScalarValue for list");
+                        let offsets =
+                            (0..=size).map(|i| (i * len) as i32).collect::<Vec<_>>();
+                        ListArray::from(
+                            ArrayData::builder(self.get_datatype())
+                                .len(size)
+                                .add_buffer(Buffer::from_slice_ref(&offsets))
+                                .add_child_data(values.data().clone())
+                                .build(),
+                        )
+                    }
+                    None => return new_null_array(&self.get_datatype(), size),
+                },
             }),
             ScalarValue::Date32(e) => match e {
                 Some(value) => Arc::new(Date32Array::from_value(*value, size)),
@@ -362,12 +421,33 @@ impl ScalarValue {
         }
     }
 
+    /// Converts `values`, which must all be of type `data_type`, into an array
+    pub fn iter_to_array<'a>(
+        values: impl IntoIterator<Item = &'a ScalarValue>,
+        data_type: &DataType,
+    ) -> Result<ArrayRef> {
+        let arrays = values.into_iter().map(|v| v.to_array()).collect::<Vec<_>>();
+        if arrays.is_empty() {
+            return Ok(new_empty_array(data_type));
+        }
+        let arrays = arrays.iter().map(|a| a.as_ref()).collect::<Vec<_>>();
+        Ok(concat(&arrays)?)
+    }
+
     /// Converts a value in `array` at `index` into a ScalarValue
     pub fn try_from_array(array: &ArrayRef, index: usize) -> Result<Self> {
         Ok(match array.data_type() {
             DataType::Boolean => typed_cast!(array, index, BooleanArray, Boolean),
             DataType::Float64 => typed_cast!(array, index, Float64Array, Float64),
             DataType::Float32 => typed_cast!(array, index, Float32Array, Float32),
+            DataType::Decimal(precision, scale) => {
+                let array = array.as_any().downcast_ref::<DecimalArray>().unwrap();
+                let value = match array.is_null(index) {
+                    true => None,
+                    false => Some(array.value(index)),
+                };
+                ScalarValue::Decimal128(value, *precision, *scale)
+            }
             DataType::UInt64 => typed_cast!(array, index, UInt64Array, UInt64),
             DataType::UInt32 => typed_cast!(array, index, UInt32Array, UInt32),
             DataType::UInt16 => typed_cast!(array, index, UInt16Array, UInt16),
@@ -551,6 +631,9 @@ impl TryFrom<&DataType> for ScalarValue {
             DataType::Boolean => ScalarValue::Boolean(None),
             DataType::Float64 => ScalarValue::Float64(None),
             DataType::Float32 => ScalarValue::Float32(None),
+            DataType::Decimal(precision, scale) => {
+                ScalarValue::Decimal128(None, *precision, *scale)
+            }
             DataType::Int8 => ScalarValue::Int8(None),
             DataType::Int16 => ScalarValue::Int16(None),
             DataType::Int32 => ScalarValue::Int32(None),
@@ -589,6 +672,15 @@ impl fmt::Display for ScalarValue {
             ScalarValue::Boolean(e) => format_option!(f, e)?,
             ScalarValue::Float32(e) => format_option!(f, e)?,
             ScalarValue::Float64(e) => format_option!(f, e)?,
+            ScalarValue::Decimal128(e, precision, scale) => match e {
+                Some(v) => write!(
+                    f,
+                    "{}",
+                    DecimalArray::from_sparse_iter(vec![Some(*v)], *precision, *scale)
+                        .value_as_string(0)
+                )?,
+                None => write!(f, "NULL")?,
+            },
             ScalarValue::Int8(e) => format_option!(f, e)?,
             ScalarValue::Int16(e) => format_option!(f, e)?,
             ScalarValue::Int32(e) => format_option!(f, e)?,
@@ -650,6 +742,9 @@ impl fmt::Debug for ScalarValue {
             ScalarValue::Boolean(_) => write!(f, "Boolean({})", self),
             ScalarValue::Float32(_) => write!(f, "Float32({})", self),
             ScalarValue::Float64(_) => write!(f, "Float64({})", self),
+            ScalarValue::Decimal128(_, precision, scale) => {
+                write!(f, "Decimal128({}, {}, {})", self, precision, scale)
+            }
             ScalarValue::Int8(_) => write!(f, "Int8({})", self),
             ScalarValue::Int16(_) => write!(f, "Int16({})", self),
             ScalarValue::Int32(_) => write!(f, "Int32({})", self),
@@ -740,4 +835,42 @@ mod tests {
         assert!(prim_array.is_null(1));
         assert_eq!(prim_array.value(2), 101);
     }
+
+    #[test]
+    fn scalar_list_of_floats_to_array() -> Result<()> {
+        let scalar = ScalarValue::List(
+            Some(vec![ScalarValue::from(1.5), ScalarValue::Float64(None)]),
+            DataType::Float64,
+        );
+        let list_array_ref = scalar.to_array_of_size(2);
+        let list_array = list_array_ref.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(list_array.len(), 2);
+        assert_eq!(list_array.values().len(), 4);
+        assert_eq!(ScalarValue::try_from_array(&list_array_ref, 1)?, scalar);
+
+        let empty = ScalarValue::iter_to_array(vec![], &DataType::Float64)?;
+        assert_eq!(empty.len(), 0);
+        assert_eq!(empty.data_type(), &DataType::Float64);
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_decimal_roundtrip() -> Result<()> {
+        let scalar = ScalarValue::Decimal128(Some(-12345), 10, 2);
+        assert_eq!(scalar.get_datatype(), DataType::Decimal(10, 2));
+        assert_eq!(format!("{}", scalar), "-123.45");
+        assert_eq!(format!("{:?}", scalar), "Decimal128(-123.45, 10, 2)");
+
+        let array = scalar.to_array_of_size(3);
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.data_type(), &DataType::Decimal(10, 2));
+        assert_eq!(ScalarValue::try_from_array(&array, 2)?, scalar);
+
+        let null = ScalarValue::try_from(&DataType::Decimal(10, 2))?;
+        assert!(null.is_null());
+        let array = null.to_array_of_size(2);
+        assert_eq!(array.null_count(), 2);
+        assert_eq!(ScalarValue::try_from_array(&array, 0)?, null);
+        Ok(())
+    }
 }
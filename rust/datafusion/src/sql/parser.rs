@@ -20,7 +20,10 @@
 //! Declares a SQL parser based on sqlparser that handles custom formats that we need.
 
 use sqlparser::{
-    ast::{ColumnDef, ColumnOptionDef, Statement as SQLStatement, TableConstraint},
+    ast::{
+        ColumnDef, ColumnOptionDef, ObjectName, Query, Statement as SQLStatement,
+        TableConstraint,
+    },
     dialect::{keywords::Keyword, Dialect, GenericDialect},
     parser::{Parser, ParserError},
     tokenizer::{Token, Tokenizer},
@@ -59,6 +62,26 @@ pub struct CreateExternalTable {
     pub location: String,
 }
 
+/// The rows written by a `COPY ... TO` statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum CopyToSource {
+    /// All the rows of a table: `COPY table_name TO ...`
+    Relation(ObjectName),
+    /// The results of a query: `COPY (query) TO ...`
+    Query(Box<Query>),
+}
+
+/// DataFusion extension DML for `COPY ... TO`, that writes rows to files
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyTo {
+    /// The rows to write
+    pub source: CopyToSource,
+    /// Path of the directory to write the files to
+    pub target: String,
+    /// Options such as `FORMAT`, with their names in upper case
+    pub options: Vec<(String, String)>,
+}
+
 /// DataFusion Statement representations.
 ///
 /// Tokens parsed by `DFParser` are converted into these values.
@@ -68,6 +91,8 @@ pub enum Statement {
     Statement(SQLStatement),
     /// Extension: `CREATE EXTERNAL TABLE`
     CreateExternalTable(CreateExternalTable),
+    /// Extension: `COPY ... TO`
+    CopyTo(CopyTo),
 }
 
 /// SQL Parser
@@ -88,7 +113,7 @@ impl<'a> DFParser<'a> {
         dialect: &'a dyn Dialect,
     ) -> Result<Self, ParserError> {
         let mut tokenizer = Tokenizer::new(dialect, sql);
-        let tokens = tokenizer.tokenize()?;
+        let tokens = rewrite_grouping_sets(tokenizer.tokenize()?);
 
         Ok(DFParser {
             parser: Parser::new(tokens, dialect),
@@ -145,6 +170,10 @@ impl<'a> DFParser<'a> {
                         // use custom parsing
                         self.parse_create()
                     }
+                    Keyword::COPY => {
+                        self.parser.next_token();
+                        self.parse_copy_to()
+                    }
                     _ => {
                         // use the native parser
                         Ok(Statement::Statement(self.parser.parse_statement()?))
@@ -167,6 +196,51 @@ impl<'a> DFParser<'a> {
         }
     }
 
+    /// Parse a SQL `COPY ... TO 'path' [WITH] [(option value [, ...])]`
+    /// statement
+    pub fn parse_copy_to(&mut self) -> Result<Statement, ParserError> {
+        let source = if self.parser.consume_token(&Token::LParen) {
+            let query = self.parser.parse_query()?;
+            self.parser.expect_token(&Token::RParen)?;
+            CopyToSource::Query(Box::new(query))
+        } else {
+            CopyToSource::Relation(self.parser.parse_object_name()?)
+        };
+        self.parser.expect_keyword(Keyword::TO)?;
+        let target = self.parser.parse_literal_string()?;
+
+        let mut options = vec![];
+        let with = self.parser.parse_keyword(Keyword::WITH);
+        if self.parser.consume_token(&Token::LParen) {
+            loop {
+                let name = self.parser.parse_identifier()?.value.to_uppercase();
+                let value = match self.parser.next_token() {
+                    Token::Word(w) => w.value,
+                    Token::SingleQuotedString(s) => s,
+                    Token::Number(n, _) => n,
+                    unexpected => return self.expected("option value", unexpected),
+                };
+                options.push((name, value));
+
+                let comma = self.parser.consume_token(&Token::Comma);
+                if self.parser.consume_token(&Token::RParen) {
+                    break;
+                } else if !comma {
+                    return self
+                        .expected("',' or ')' after option", self.parser.peek_token());
+                }
+            }
+        } else if with {
+            return self.expected("'(' after WITH", self.parser.peek_token());
+        }
+
+        Ok(Statement::CopyTo(CopyTo {
+            source,
+            target,
+            options,
+        }))
+    }
+
     // This is a copy of the equivalent implementation in sqlparser.
     fn parse_columns(
         &mut self,
@@ -270,11 +344,15 @@ impl<'a> DFParser<'a> {
         match self.parser.next_token() {
             Token::Word(w) => match &*w.value {
                 "PARQUET" => Ok(FileType::Parquet),
-                "NDJSON" => Ok(FileType::NdJson),
+                "NDJSON" | "JSON" => Ok(FileType::NdJson),
                 "CSV" => Ok(FileType::CSV),
-                _ => self.expected("one of PARQUET, NDJSON, or CSV", Token::Word(w)),
+                _ => {
+                    self.expected("one of PARQUET, NDJSON, JSON, or CSV", Token::Word(w))
+                }
             },
-            unexpected => self.expected("one of PARQUET, NDJSON, or CSV", unexpected),
+            unexpected => {
+                self.expected("one of PARQUET, NDJSON, JSON, or CSV", unexpected)
+            }
         }
     }
 
@@ -294,6 +372,98 @@ impl<'a> DFParser<'a> {
     }
 }
 
+/// The name of the function that `GROUPING SETS (...)` is parsed as
+pub const GROUPING_SETS_FUNCTION: &str = "GROUPING_SETS";
+
+/// The name of the function that a parenthesized list of expressions in
+/// `GROUPING SETS`, `ROLLUP` and `CUBE` is parsed as
+pub const GROUPING_SET_FUNCTION: &str = "GROUPING_SET";
+
+/// Whether `token` is the unquoted word `word`
+fn is_word(token: Option<&Token>, word: &str) -> bool {
+    matches!(token, Some(Token::Word(w)) if w.quote_style.is_none()
+        && w.value.eq_ignore_ascii_case(word))
+}
+
+/// Rewrites the tokens of `GROUPING SETS (...)` into the function call
+/// `GROUPING_SETS(...)`, and the parenthesized lists of expressions that are
+/// elements of `GROUPING SETS`, `ROLLUP` and `CUBE`, e.g. `(a, b)` or `()`,
+/// into calls of `GROUPING_SET(...)`, as sqlparser parses neither of them.
+fn rewrite_grouping_sets(tokens: Vec<Token>) -> Vec<Token> {
+    // the indices of the tokens other than whitespaces
+    let significant = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| !matches!(t, Token::Whitespace(_)))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let token = |n: usize| significant.get(n).map(|i| &tokens[*i]);
+
+    // the matching parenthesis of every opening parenthesis
+    let mut closing = vec![None; significant.len()];
+    let mut open = vec![];
+    for n in 0..significant.len() {
+        match token(n) {
+            Some(Token::LParen) => open.push(n),
+            Some(Token::RParen) => {
+                if let Some(o) = open.pop() {
+                    closing[o] = Some(n);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // the tokens replacing some of the significant tokens
+    let mut replacements = vec![None; tokens.len()];
+    for n in 0..significant.len() {
+        let list_start = if is_word(token(n), "grouping")
+            && is_word(token(n + 1), "sets")
+            && token(n + 2) == Some(&Token::LParen)
+        {
+            replacements[significant[n]] =
+                Some(vec![Token::make_word(GROUPING_SETS_FUNCTION, None)]);
+            replacements[significant[n + 1]] = Some(vec![]);
+            n + 2
+        } else if (is_word(token(n), "rollup") || is_word(token(n), "cube"))
+            && token(n + 1) == Some(&Token::LParen)
+        {
+            n + 1
+        } else {
+            continue;
+        };
+        let list_end = match closing[list_start] {
+            Some(list_end) => list_end,
+            None => continue,
+        };
+
+        let mut element_start = list_start + 1;
+        while element_start < list_end {
+            // the end of the element is the next comma of the list
+            let mut element_end = element_start;
+            while element_end < list_end && token(element_end) != Some(&Token::Comma) {
+                element_end = closing[element_end].unwrap_or(element_end) + 1;
+            }
+            // an element that is entirely parenthesized is a list of expressions
+            if token(element_start) == Some(&Token::LParen)
+                && closing[element_start] == Some(element_end - 1)
+            {
+                replacements[significant[element_start]] = Some(vec![
+                    Token::make_word(GROUPING_SET_FUNCTION, None),
+                    Token::LParen,
+                ]);
+            }
+            element_start = element_end + 1;
+        }
+    }
+
+    tokens
+        .into_iter()
+        .zip(replacements)
+        .flat_map(|(token, replacement)| replacement.unwrap_or_else(|| vec![token]))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -367,14 +537,107 @@ mod tests {
         });
         expect_parse_ok(sql, expected)?;
 
+        // positive case: JSON is an alias for NDJSON and columns are optional
+        let sql = "CREATE EXTERNAL TABLE t STORED AS JSON LOCATION 'foo.json'";
+        let expected = Statement::CreateExternalTable(CreateExternalTable {
+            name: "t".into(),
+            columns: vec![],
+            file_type: FileType::NdJson,
+            has_header: false,
+            location: "foo.json".into(),
+        });
+        expect_parse_ok(sql, expected)?;
+
         // Error cases: Invalid type
         let sql =
             "CREATE EXTERNAL TABLE t(c1 int) STORED AS UNKNOWN_TYPE LOCATION 'foo.csv'";
         expect_parse_error(
             sql,
-            "Expected one of PARQUET, NDJSON, or CSV, found: UNKNOWN_TYPE",
+            "Expected one of PARQUET, NDJSON, JSON, or CSV, found: UNKNOWN_TYPE",
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_to() -> Result<(), ParserError> {
+        let sql = "COPY t TO 'out'";
+        let expected = Statement::CopyTo(CopyTo {
+            source: CopyToSource::Relation(ObjectName(vec![Ident::new("t")])),
+            target: "out".into(),
+            options: vec![],
+        });
+        expect_parse_ok(sql, expected)?;
+
+        let sql = "COPY (SELECT 1) TO 'out' (format csv, HEADER false, delimiter '|')";
+        let statements = DFParser::parse_sql(sql)?;
+        match &statements[0] {
+            Statement::CopyTo(CopyTo {
+                source: CopyToSource::Query(_),
+                target,
+                options,
+            }) => {
+                assert_eq!("out", target);
+                let expected = vec![
+                    ("FORMAT".to_string(), "csv".to_string()),
+                    ("HEADER".to_string(), "false".to_string()),
+                    ("DELIMITER".to_string(), "|".to_string()),
+                ];
+                assert_eq!(&expected, options);
+            }
+            other => panic!("Expected COPY (query) TO, got {:?}", other),
+        }
+
+        // Error cases
+        expect_parse_error("COPY t FROM 'out'", "Expected TO, found: FROM");
+        expect_parse_error("COPY t TO 'out' WITH FORMAT csv", "Expected '(' after WITH");
+        expect_parse_error(
+            "COPY t TO 'out' (FORMAT csv HEADER true)",
+            "Expected ',' or ')' after option, found: HEADER",
         );
 
         Ok(())
     }
+
+    #[test]
+    fn grouping_sets() -> Result<(), ParserError> {
+        let cases = vec![
+            (
+                "SELECT a FROM t GROUP BY GROUPING SETS ((a), (a, b), ())",
+                "GROUP BY GROUPING_SETS(GROUPING_SET(a), GROUPING_SET(a, b), GROUPING_SET())",
+            ),
+            (
+                "SELECT a FROM t GROUP BY grouping sets (a, (b + 1) * 2)",
+                "GROUP BY GROUPING_SETS(a, (b + 1) * 2)",
+            ),
+            (
+                "SELECT a FROM t GROUP BY a, ROLLUP ((a, b), c), CUBE (d)",
+                "GROUP BY a, ROLLUP(GROUPING_SET(a, b), c), CUBE(d)",
+            ),
+        ];
+        for (sql, expected) in cases {
+            let statements = DFParser::parse_sql(sql)?;
+            match &statements[0] {
+                Statement::Statement(statement) => {
+                    let actual = statement.to_string();
+                    assert!(
+                        actual.ends_with(expected),
+                        "Expected '{}' to end with '{}'",
+                        actual,
+                        expected
+                    );
+                }
+                other => panic!("Expected a query, got {:?}", other),
+            }
+        }
+
+        // GROUPING is a function otherwise
+        let sql = "SELECT GROUPING(a) FROM t GROUP BY a";
+        match &DFParser::parse_sql(sql)?[0] {
+            Statement::Statement(statement) => assert_eq!(sql, statement.to_string()),
+            other => panic!("Expected a query, got {:?}", other),
+        }
+
+        Ok(())
+    }
 }
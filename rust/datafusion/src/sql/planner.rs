@@ -17,17 +17,22 @@
 
 //! SQL Query Planner (produces logical plan from SQL AST)
 
+use std::collections::HashSet;
 use std::convert::TryInto;
+use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::catalog::TableReference;
-use crate::datasource::TableProvider;
+use crate::datasource::{FileFormat, MemTable, TableProvider, ViewTable};
 use crate::logical_plan::Expr::Alias;
 use crate::logical_plan::{
-    and, lit, DFSchema, Expr, LogicalPlan, LogicalPlanBuilder, Operator, PlanType,
-    StringifiedPlan, ToDFSchema,
+    and, col, combine_filters, grouping_set_to_exprlist, grouping_sets, lit, DFField,
+    DFSchema, Expr, GroupingSet, LogicalPlan, LogicalPlanBuilder, Operator, PlanType,
+    StringifiedPlan, Subquery, ToDFSchema, GROUPING_ID_COLUMN,
 };
+use crate::optimizer::utils;
+use crate::physical_plan::file_sink::{FileCompression, FileWriteOptions};
 use crate::scalar::ScalarValue;
 use crate::{
     error::{DataFusionError, Result},
@@ -35,18 +40,22 @@ use crate::{
 };
 use crate::{
     physical_plan::udf::ScalarUDF,
-    physical_plan::{aggregates, functions},
-    sql::parser::{CreateExternalTable, FileType, Statement as DFStatement},
+    physical_plan::{aggregates, functions, window_functions},
+    sql::parser::{
+        CopyTo, CopyToSource, CreateExternalTable, FileType, Statement as DFStatement,
+    },
 };
 
 use arrow::datatypes::*;
 use hashbrown::HashMap;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
 
 use crate::prelude::JoinType;
 use sqlparser::ast::{
-    BinaryOperator, DataType as SQLDataType, DateTimeField, Expr as SQLExpr, FunctionArg,
-    Ident, Join, JoinConstraint, JoinOperator, ObjectName, Query, Select, SelectItem,
-    SetExpr, SetOperator, ShowStatementFilter, TableFactor, TableWithJoins,
+    BinaryOperator, DataType as SQLDataType, DateTimeField, Expr as SQLExpr, Function,
+    FunctionArg, Ident, Join, JoinConstraint, JoinOperator, ObjectName, Query, Select,
+    SelectItem, SetExpr, SetOperator, ShowStatementFilter, TableFactor, TableWithJoins,
     UnaryOperator, Value,
 };
 use sqlparser::ast::{ColumnDef as SQLColumnDef, ColumnOption};
@@ -54,10 +63,11 @@ use sqlparser::ast::{OrderByExpr, Statement};
 use sqlparser::parser::ParserError::ParserError;
 
 use super::{
-    parser::DFParser,
+    parser::{DFParser, GROUPING_SETS_FUNCTION, GROUPING_SET_FUNCTION},
     utils::{
         can_columns_satisfy_exprs, expand_wildcard, expr_as_column_expr, extract_aliases,
-        find_aggregate_exprs, find_column_exprs, rebase_expr, resolve_aliases_to_exprs,
+        find_aggregate_exprs, find_column_exprs, find_window_exprs, grouping_set_exprs,
+        rebase_expr, resolve_aliases_to_exprs, resolve_grouping_function,
     },
 };
 
@@ -87,6 +97,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
     pub fn statement_to_plan(&self, statement: &DFStatement) -> Result<LogicalPlan> {
         match statement {
             DFStatement::CreateExternalTable(s) => self.external_table_to_plan(&s),
+            DFStatement::CopyTo(s) => self.copy_to_plan(s),
             DFStatement::Statement(s) => self.sql_statement_to_plan(&s),
         }
     }
@@ -97,8 +108,8 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             Statement::Explain {
                 verbose,
                 statement,
-                analyze: _,
-            } => self.explain_statement_to_plan(*verbose, &statement),
+                analyze,
+            } => self.explain_statement_to_plan(*verbose, *analyze, statement),
             Statement::Query(query) => self.query_to_plan(&query),
             Statement::ShowVariable { variable } => self.show_variable_to_plan(&variable),
             Statement::ShowColumns {
@@ -107,9 +118,61 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 table_name,
                 filter,
             } => self.show_columns_to_plan(*extended, *full, table_name, filter.as_ref()),
-            _ => Err(DataFusionError::NotImplemented(
-                "Only SELECT statements are implemented".to_string(),
-            )),
+            Statement::CreateTable {
+                or_replace,
+                if_not_exists,
+                name,
+                columns,
+                constraints,
+                query,
+                ..
+            } if constraints.is_empty() => self.create_table_to_plan(
+                name,
+                columns,
+                query.as_deref(),
+                *or_replace,
+                *if_not_exists,
+            ),
+            Statement::CreateView {
+                or_replace,
+                materialized,
+                name,
+                columns,
+                query,
+                with_options,
+            } => {
+                if *materialized {
+                    return Err(DataFusionError::NotImplemented(
+                        "Materialized views are not supported".to_string(),
+                    ));
+                }
+                if !with_options.is_empty() {
+                    return Err(DataFusionError::NotImplemented(
+                        "Options are not supported for views".to_string(),
+                    ));
+                }
+                self.create_view_to_plan(name, columns, query, *or_replace)
+            }
+            Statement::Insert {
+                table_name,
+                columns,
+                overwrite,
+                source,
+                partitioned,
+                ..
+            } => {
+                if *overwrite || partitioned.is_some() {
+                    return Err(DataFusionError::NotImplemented(
+                        "INSERT OVERWRITE and partitioned inserts are not supported"
+                            .to_string(),
+                    ));
+                }
+                self.insert_to_plan(table_name, columns, source)
+            }
+            _ => Err(DataFusionError::NotImplemented(format!(
+                "Unsupported SQL statement: {}",
+                sql
+            ))),
         }
     }
 
@@ -124,6 +187,19 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         query: &Query,
         alias: Option<String>,
         ctes: &mut HashMap<String, LogicalPlan>,
+    ) -> Result<LogicalPlan> {
+        self.query_to_plan_with_context(query, alias, ctes, None)
+    }
+
+    /// Generate a logic plan from an SQL query with optional alias, where
+    /// `outer_query_schema` is the schema of the enclosing query if this query
+    /// is a subquery
+    fn query_to_plan_with_context(
+        &self,
+        query: &Query,
+        alias: Option<String>,
+        ctes: &mut HashMap<String, LogicalPlan>,
+        outer_query_schema: Option<&DFSchema>,
     ) -> Result<LogicalPlan> {
         let set_expr = &query.body;
         if let Some(with) = &query.with {
@@ -139,7 +215,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 ctes.insert(cte.alias.name.value.clone(), logical_plan);
             }
         }
-        let plan = self.set_expr_to_plan(set_expr, alias, ctes)?;
+        let plan = self.set_expr_to_plan(set_expr, alias, ctes, outer_query_schema)?;
 
         let plan = self.order_by(&plan, &query.order_by)?;
 
@@ -151,47 +227,64 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         set_expr: &SetExpr,
         alias: Option<String>,
         ctes: &mut HashMap<String, LogicalPlan>,
+        outer_query_schema: Option<&DFSchema>,
     ) -> Result<LogicalPlan> {
         match set_expr {
-            SetExpr::Select(s) => self.select_to_plan(s.as_ref(), ctes),
+            SetExpr::Select(s) => {
+                self.select_to_plan(s.as_ref(), ctes, outer_query_schema)
+            }
             SetExpr::SetOperation {
                 op,
                 left,
                 right,
                 all,
-            } => match (op, all) {
-                (SetOperator::Union, true) => {
-                    let left_plan = self.set_expr_to_plan(left.as_ref(), None, ctes)?;
-                    let right_plan = self.set_expr_to_plan(right.as_ref(), None, ctes)?;
-                    let inputs = vec![left_plan, right_plan]
-                        .into_iter()
-                        .flat_map(|p| match p {
-                            LogicalPlan::Union { inputs, .. } => inputs,
-                            x => vec![x],
-                        })
-                        .collect::<Vec<_>>();
-                    if inputs.is_empty() {
-                        return Err(DataFusionError::Plan(format!(
-                            "Empty UNION: {}",
-                            set_expr
-                        )));
+            } => {
+                let left_plan =
+                    self.set_expr_to_plan(left.as_ref(), None, ctes, outer_query_schema)?;
+                let right_plan = self.set_expr_to_plan(
+                    right.as_ref(),
+                    None,
+                    ctes,
+                    outer_query_schema,
+                )?;
+                match op {
+                    SetOperator::Union => {
+                        let inputs = vec![left_plan, right_plan]
+                            .into_iter()
+                            .flat_map(|p| match p {
+                                LogicalPlan::Union { inputs, .. } => inputs,
+                                x => vec![x],
+                            })
+                            .collect::<Vec<_>>();
+                        if inputs.is_empty() {
+                            return Err(DataFusionError::Plan(format!(
+                                "Empty UNION: {}",
+                                set_expr
+                            )));
+                        }
+                        if !inputs.iter().all(|s| s.schema() == inputs[0].schema()) {
+                            return Err(DataFusionError::Plan(format!(
+                                "{} schemas are expected to be the same",
+                                if *all { "UNION ALL" } else { "UNION" }
+                            )));
+                        }
+                        let union = LogicalPlan::Union {
+                            schema: inputs[0].schema().clone(),
+                            inputs,
+                            alias,
+                        };
+                        if *all {
+                            Ok(union)
+                        } else {
+                            distinct(&union)
+                        }
                     }
-                    if !inputs.iter().all(|s| s.schema() == inputs[0].schema()) {
-                        return Err(DataFusionError::Plan(
-                            "UNION ALL schemas are expected to be the same".to_string(),
-                        ));
+                    SetOperator::Intersect | SetOperator::Except => {
+                        intersect_or_except(op, *all, &left_plan, &right_plan)
                     }
-                    Ok(LogicalPlan::Union {
-                        schema: inputs[0].schema().clone(),
-                        inputs,
-                        alias,
-                    })
                 }
-                _ => Err(DataFusionError::NotImplemented(format!(
-                    "Only UNION ALL is supported, found {}",
-                    op
-                ))),
-            },
+            }
+            SetExpr::Values(values) => self.values_to_plan(&values.0, alias),
             _ => Err(DataFusionError::NotImplemented(format!(
                 "Query {} not implemented yet",
                 set_expr
@@ -199,6 +292,277 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         }
     }
 
+    /// Generate a logical plan from the rows of a VALUES list: the union of
+    /// a single-row projection per row. The columns are named `column1`,
+    /// `column2`, ... and have the type of their first non-NULL value.
+    fn values_to_plan(
+        &self,
+        rows: &[Vec<SQLExpr>],
+        alias: Option<String>,
+    ) -> Result<LogicalPlan> {
+        let empty_schema = DFSchema::empty();
+        let rows = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| self.sql_to_rex(value, &empty_schema))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let width = rows.first().map(|row| row.len()).unwrap_or_default();
+        if width == 0 || rows.iter().any(|row| row.len() != width) {
+            return Err(DataFusionError::Plan(
+                "All rows of VALUES must have the same, non-zero, number of values"
+                    .to_string(),
+            ));
+        }
+
+        let types = (0..width)
+            .map(|i| {
+                rows.iter()
+                    .map(|row| &row[i])
+                    .find(|value| !matches!(value, Expr::Literal(v) if v.is_null()))
+                    .unwrap_or(&rows[0][i])
+                    .get_type(&empty_schema)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut inputs = rows
+            .into_iter()
+            .map(|row| {
+                let exprs = row
+                    .into_iter()
+                    .zip(types.iter())
+                    .enumerate()
+                    .map(|(i, (value, data_type))| {
+                        Ok(value
+                            .cast_to(data_type, &empty_schema)?
+                            .alias(&format!("column{}", i + 1)))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                LogicalPlanBuilder::empty(true).project(exprs)?.build()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if inputs.len() == 1 {
+            return Ok(inputs.remove(0));
+        }
+
+        // a column is nullable if it is NULL in any of the rows
+        let fields = (0..width)
+            .map(|i| {
+                let field = inputs[0].schema().field(i);
+                DFField::new(
+                    None,
+                    field.name(),
+                    field.data_type().clone(),
+                    inputs.iter().any(|p| p.schema().field(i).is_nullable()),
+                )
+            })
+            .collect::<Vec<_>>();
+        Ok(LogicalPlan::Union {
+            schema: Arc::new(DFSchema::new(fields)?),
+            inputs,
+            alias,
+        })
+    }
+
+    /// Generate a logical plan from a CREATE TABLE statement, that creates
+    /// an in-memory table either from the results of a query or, empty,
+    /// from column definitions
+    fn create_table_to_plan(
+        &self,
+        name: &ObjectName,
+        columns: &[SQLColumnDef],
+        query: Option<&Query>,
+        or_replace: bool,
+        if_not_exists: bool,
+    ) -> Result<LogicalPlan> {
+        if !or_replace && self.table_exists(name)? {
+            return if if_not_exists {
+                LogicalPlanBuilder::empty(false).build()
+            } else {
+                Err(DataFusionError::Plan(format!(
+                    "Table '{}' already exists",
+                    name
+                )))
+            };
+        }
+
+        let input = match (query, columns.is_empty()) {
+            (Some(query), true) => self.query_to_plan(query)?,
+            (None, false) => LogicalPlan::EmptyRelation {
+                produce_one_row: false,
+                schema: self.build_schema(columns)?.to_dfschema_ref()?,
+            },
+            (Some(_), false) => {
+                return Err(DataFusionError::NotImplemented(
+                    "Column definitions are not supported for CREATE TABLE AS SELECT"
+                        .to_string(),
+                ))
+            }
+            (None, true) => {
+                return Err(DataFusionError::Plan(
+                    "CREATE TABLE requires either column definitions or a query"
+                        .to_string(),
+                ))
+            }
+        };
+
+        Ok(LogicalPlan::CreateMemoryTable {
+            name: name.to_string(),
+            input: Arc::new(input),
+        })
+    }
+
+    /// Generate a logical plan from a CREATE VIEW statement
+    fn create_view_to_plan(
+        &self,
+        name: &ObjectName,
+        columns: &[Ident],
+        query: &Query,
+        or_replace: bool,
+    ) -> Result<LogicalPlan> {
+        if let Some(provider) = self.schema_provider.get_table_provider(name.try_into()?)
+        {
+            if !or_replace {
+                return Err(DataFusionError::Plan(format!(
+                    "Table or view '{}' already exists",
+                    name
+                )));
+            }
+            if provider.as_any().downcast_ref::<ViewTable>().is_none() {
+                return Err(DataFusionError::Plan(format!(
+                    "'{}' is a table, not a view",
+                    name
+                )));
+            }
+        }
+
+        let plan = self.query_to_plan(query)?;
+        let plan = if columns.is_empty() {
+            plan
+        } else {
+            let fields = plan.schema().fields();
+            if fields.len() != columns.len() {
+                return Err(DataFusionError::Plan(format!(
+                    "View '{}' has {} columns but its query produces {}",
+                    name,
+                    columns.len(),
+                    fields.len()
+                )));
+            }
+            let exprs = fields
+                .iter()
+                .zip(columns)
+                .map(|(field, column)| col(field.name()).alias(&normalize_ident(column)))
+                .collect::<Vec<_>>();
+            LogicalPlanBuilder::from(&plan).project(exprs)?.build()?
+        };
+
+        Ok(LogicalPlan::CreateView {
+            name: name.to_string(),
+            input: Arc::new(plan),
+        })
+    }
+
+    /// Generate a logical plan from an INSERT INTO statement. The rows to
+    /// insert are projected to the schema of the table: values are cast to
+    /// the types of their columns, and columns that aren't listed are NULL.
+    fn insert_to_plan(
+        &self,
+        table_name: &ObjectName,
+        columns: &[Ident],
+        source: &Query,
+    ) -> Result<LogicalPlan> {
+        let table = self
+            .schema_provider
+            .get_table_provider(table_name.try_into()?)
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!("Table '{}' not found", table_name))
+            })?;
+        if table.as_any().downcast_ref::<MemTable>().is_none() {
+            return Err(DataFusionError::NotImplemented(format!(
+                "INSERT INTO is only supported for in-memory tables, '{}' is not one",
+                table_name
+            )));
+        }
+        let table_schema = table.schema();
+
+        // the index in the table of each column that values are given for
+        let targets = if columns.is_empty() {
+            (0..table_schema.fields().len()).collect::<Vec<_>>()
+        } else {
+            let mut targets = Vec::with_capacity(columns.len());
+            for column in columns {
+                let name = normalize_ident(column);
+                let index = table_schema.index_of(&name).map_err(|_| {
+                    DataFusionError::Plan(format!(
+                        "Column '{}' not found in table '{}'",
+                        name, table_name
+                    ))
+                })?;
+                if targets.contains(&index) {
+                    return Err(DataFusionError::Plan(format!(
+                        "Column '{}' is specified more than once",
+                        name
+                    )));
+                }
+                targets.push(index);
+            }
+            targets
+        };
+
+        let input = self.query_to_plan(source)?;
+        let input_schema = input.schema();
+        if input_schema.fields().len() != targets.len() {
+            return Err(DataFusionError::Plan(format!(
+                "INSERT INTO '{}' has {} target columns but {} values",
+                table_name,
+                targets.len(),
+                input_schema.fields().len()
+            )));
+        }
+
+        let exprs = table_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let value = match targets.iter().position(|target| *target == i) {
+                    Some(j) => col(input_schema.field(j).name()),
+                    None if field.is_nullable() => lit(ScalarValue::Utf8(None)),
+                    None => {
+                        return Err(DataFusionError::Plan(format!(
+                            "Column '{}' is not nullable and needs a value",
+                            field.name()
+                        )))
+                    }
+                };
+                Ok(value
+                    .cast_to(field.data_type(), input_schema)?
+                    .alias(field.name()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let input = LogicalPlanBuilder::from(&input).project(exprs)?.build()?;
+
+        Ok(LogicalPlan::Insert {
+            table_name: table_name.to_string(),
+            table,
+            input: Arc::new(input),
+            schema: LogicalPlan::count_schema().to_dfschema_ref()?,
+        })
+    }
+
+    /// Returns whether a table or view of this name is registered
+    fn table_exists(&self, name: &ObjectName) -> Result<bool> {
+        Ok(self
+            .schema_provider
+            .get_table_provider(name.try_into()?)
+            .is_some())
+    }
+
     /// Generate a logical plan from a CREATE EXTERNAL TABLE statement
     pub fn external_table_to_plan(
         &self,
@@ -243,15 +607,49 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         })
     }
 
-    /// Generate a plan for EXPLAIN ... that will print out a plan
+    /// Generate a logical plan from a COPY ... TO statement
+    pub fn copy_to_plan(&self, statement: &CopyTo) -> Result<LogicalPlan> {
+        let input = match &statement.source {
+            CopyToSource::Relation(name) => {
+                let relation = TableFactor::Table {
+                    name: name.clone(),
+                    alias: None,
+                    args: vec![],
+                    with_hints: vec![],
+                };
+                self.create_relation(&relation, &mut HashMap::new())?
+            }
+            CopyToSource::Query(query) => self.query_to_plan(query)?,
+        };
+        let options = file_write_options(&statement.target, &statement.options)?;
+
+        Ok(LogicalPlan::CopyTo {
+            input: Arc::new(input),
+            path: statement.target.clone(),
+            options,
+            schema: LogicalPlan::count_schema().to_dfschema_ref()?,
+        })
+    }
+
+    /// Generate a plan for EXPLAIN ... that will print out a plan, or for
+    /// EXPLAIN ANALYZE ... that will run the plan and print out its metrics
     ///
     pub fn explain_statement_to_plan(
         &self,
         verbose: bool,
+        analyze: bool,
         statement: &Statement,
     ) -> Result<LogicalPlan> {
         let plan = self.sql_statement_to_plan(&statement)?;
 
+        if analyze {
+            return Ok(LogicalPlan::Analyze {
+                verbose,
+                input: Arc::new(plan),
+                schema: LogicalPlan::explain_schema().to_dfschema_ref()?,
+            });
+        }
+
         let stringified_plans = vec![StringifiedPlan::new(
             PlanType::LogicalPlan,
             format!("{:#?}", plan),
@@ -292,7 +690,9 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             SQLDataType::Char(_) | SQLDataType::Varchar(_) | SQLDataType::Text => {
                 Ok(DataType::Utf8)
             }
-            SQLDataType::Decimal(_, _) => Ok(DataType::Float64),
+            SQLDataType::Decimal(precision, scale) => {
+                make_decimal_type(*precision, *scale)
+            }
             SQLDataType::Float(_) => Ok(DataType::Float32),
             SQLDataType::Real | SQLDataType::Double => Ok(DataType::Float64),
             SQLDataType::Boolean => Ok(DataType::Boolean),
@@ -355,6 +755,12 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             JoinOperator::Inner(constraint) => {
                 self.parse_join(left, &right, constraint, JoinType::Inner)
             }
+            JoinOperator::FullOuter(constraint) => {
+                self.parse_join(left, &right, constraint, JoinType::Full)
+            }
+            JoinOperator::CrossJoin => {
+                LogicalPlanBuilder::from(left).cross_join(&right)?.build()
+            }
             other => Err(DataFusionError::NotImplemented(format!(
                 "Unsupported JOIN operator {:?}",
                 other
@@ -372,18 +778,81 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         match constraint {
             JoinConstraint::On(sql_expr) => {
                 let mut keys: Vec<(String, String)> = vec![];
+                let mut filters: Vec<Expr> = vec![];
                 let join_schema = left.schema().join(&right.schema())?;
 
                 // parse ON expression
                 let expr = self.sql_to_rex(sql_expr, &join_schema)?;
 
                 // extract join keys
-                extract_join_keys(&expr, &mut keys)?;
+                extract_join_keys(
+                    &expr,
+                    left.schema(),
+                    right.schema(),
+                    &mut keys,
+                    &mut filters,
+                );
                 let left_keys: Vec<&str> =
                     keys.iter().map(|pair| pair.0.as_str()).collect();
                 let right_keys: Vec<&str> =
                     keys.iter().map(|pair| pair.1.as_str()).collect();
 
+                if let JoinType::Inner = join_type {
+                    // the other predicates of an inner join filter its output
+                    let builder = if keys.is_empty() {
+                        LogicalPlanBuilder::from(left).cross_join(right)?
+                    } else {
+                        LogicalPlanBuilder::from(left).join(
+                            right,
+                            join_type,
+                            &left_keys,
+                            &right_keys,
+                        )?
+                    };
+                    return match combine_filters(&filters) {
+                        Some(filter) => builder.filter(filter)?.build(),
+                        None => builder.build(),
+                    };
+                }
+
+                // the other predicates of an outer join can only filter the
+                // side that is padded with nulls, before joining
+                let mut left = left.clone();
+                let mut right = right.clone();
+                for filter in filters {
+                    let mut columns = HashSet::new();
+                    utils::expr_to_column_names(&filter, &mut columns)?;
+                    let is_resolved_by = |plan: &LogicalPlan| {
+                        columns
+                            .iter()
+                            .all(|c| plan.schema().field_with_unqualified_name(c).is_ok())
+                    };
+                    match join_type {
+                        JoinType::Left if is_resolved_by(&right) => {
+                            right = LogicalPlanBuilder::from(&right)
+                                .filter(filter)?
+                                .build()?;
+                        }
+                        JoinType::Right if is_resolved_by(&left) => {
+                            left = LogicalPlanBuilder::from(&left)
+                                .filter(filter)?
+                                .build()?;
+                        }
+                        _ => {
+                            return Err(DataFusionError::NotImplemented(format!(
+                                "Unsupported expression '{:?}' in {:?} JOIN condition",
+                                filter, join_type
+                            )))
+                        }
+                    }
+                }
+                if keys.is_empty() {
+                    return Err(DataFusionError::NotImplemented(format!(
+                        "{:?} JOIN without equality condition is not supported",
+                        join_type
+                    )));
+                }
+
                 // return the logical plan representing the join
                 LogicalPlanBuilder::from(&left)
                     .join(&right, join_type, &left_keys, &right_keys)?
@@ -421,6 +890,14 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                     self.schema_provider.get_table_provider(name.try_into()?),
                 ) {
                     (Some(cte_plan), _) => Ok(cte_plan.clone()),
+                    // views are inlined, so that they are optimized as part
+                    // of the query
+                    (_, Some(provider))
+                        if provider.as_any().downcast_ref::<ViewTable>().is_some() =>
+                    {
+                        let view = provider.as_any().downcast_ref::<ViewTable>().unwrap();
+                        Ok(view.logical_plan().clone())
+                    }
                     (_, Some(provider)) => {
                         LogicalPlanBuilder::scan(&table_name, provider, None)?.build()
                     }
@@ -453,6 +930,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         &self,
         select: &Select,
         ctes: &mut HashMap<String, LogicalPlan>,
+        outer_query_schema: Option<&DFSchema>,
     ) -> Result<LogicalPlan> {
         let plans = self.plan_from_tables(&select.from, ctes)?;
 
@@ -465,7 +943,28 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 }
                 let join_schema = DFSchema::new(fields)?;
 
-                let filter_expr = self.sql_to_rex(predicate_expr, &join_schema)?;
+                // the predicate of a correlated subquery may also reference the
+                // columns of the enclosing query that are not shadowed by its own
+                let filter_schema = match outer_query_schema {
+                    Some(outer_query_schema) => {
+                        let mut fields = join_schema.fields().clone();
+                        fields.extend(
+                            outer_query_schema
+                                .fields()
+                                .iter()
+                                .filter(|f| {
+                                    join_schema
+                                        .field_with_unqualified_name(f.name())
+                                        .is_err()
+                                })
+                                .cloned(),
+                        );
+                        DFSchema::new(fields)?
+                    }
+                    None => join_schema,
+                };
+
+                let filter_expr = self.sql_to_rex(predicate_expr, &filter_schema)?;
 
                 // look for expressions of the form `<column> = <column>`
                 let mut possible_join_keys = vec![];
@@ -489,9 +988,8 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                         }
                     }
                     if join_keys.is_empty() {
-                        return Err(DataFusionError::NotImplemented(
-                            "Cartesian joins are not supported".to_string(),
-                        ));
+                        left =
+                            LogicalPlanBuilder::from(&left).cross_join(right)?.build()?;
                     } else {
                         let left_keys: Vec<_> =
                             join_keys.iter().map(|(l, _)| *l).collect();
@@ -514,13 +1012,11 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 }
             }
             None => {
-                if plans.len() == 1 {
-                    Ok(plans[0].clone())
-                } else {
-                    Err(DataFusionError::NotImplemented(
-                        "Cartesian joins are not supported".to_string(),
-                    ))
+                let mut left = plans[0].clone();
+                for right in plans.iter().skip(1) {
+                    left = LogicalPlanBuilder::from(&left).cross_join(right)?.build()?;
                 }
+                Ok(left)
             }
         };
         let plan = plan?;
@@ -533,7 +1029,8 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             .having
             .as_ref()
             .map::<Result<Expr>, _>(|having_expr| {
-                let having_expr = self.sql_expr_to_logical_expr(having_expr)?;
+                let having_expr =
+                    self.sql_expr_to_logical_expr(having_expr, plan.schema())?;
 
                 // This step "dereferences" any aliases in the HAVING clause.
                 //
@@ -611,7 +1108,36 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             plan
         };
 
-        self.project(&plan, select_exprs_post_aggr, false)
+        // window functions are evaluated after the aggregation and HAVING
+        let window_func_exprs = find_window_exprs(&select_exprs_post_aggr);
+
+        let (plan, select_exprs_post_window) = if window_func_exprs.is_empty() {
+            (plan, select_exprs_post_aggr)
+        } else {
+            self.window(&plan, window_func_exprs, &select_exprs_post_aggr)?
+        };
+
+        self.project(&plan, select_exprs_post_window, false)
+    }
+
+    /// Wrap a plan in a window, returning the SELECT expressions rewritten to
+    /// use the columns produced by the window
+    fn window(
+        &self,
+        input: &LogicalPlan,
+        window_exprs: Vec<Expr>,
+        select_exprs: &[Expr],
+    ) -> Result<(LogicalPlan, Vec<Expr>)> {
+        let plan = LogicalPlanBuilder::from(input)
+            .window(window_exprs.clone())?
+            .build()?;
+
+        let select_exprs = select_exprs
+            .iter()
+            .map(|expr| rebase_expr(expr, &window_exprs, &plan))
+            .collect::<Result<Vec<Expr>>>()?;
+
+        Ok((plan, select_exprs))
     }
 
     /// Returns the `Expr`'s corresponding to a SQL query's SELECT expressions.
@@ -669,27 +1195,51 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         group_by: &[SQLExpr],
         aggr_exprs: Vec<Expr>,
     ) -> Result<(LogicalPlan, Vec<Expr>, Option<Expr>)> {
-        let group_by_exprs = group_by
+        let group_by_exprs = self.group_by_to_rex(group_by, &input.schema())?;
+
+        // GROUPING calls are not aggregated, but computed from the grouping id
+        // of the groups
+        let aggr_exprs = aggr_exprs
+            .into_iter()
+            .filter(|expr| {
+                !matches!(
+                    expr,
+                    Expr::AggregateFunction {
+                        fun: aggregates::AggregateFunction::Grouping,
+                        ..
+                    }
+                )
+            })
+            .collect::<Vec<Expr>>();
+        let select_exprs = select_exprs
             .iter()
-            .map(|e| self.sql_to_rex(e, &input.schema()))
+            .map(|expr| resolve_grouping_function(expr, &group_by_exprs, input))
             .collect::<Result<Vec<Expr>>>()?;
+        let having_expr_opt = having_expr_opt
+            .as_ref()
+            .map(|expr| resolve_grouping_function(expr, &group_by_exprs, input))
+            .transpose()?;
 
-        let aggr_projection_exprs = group_by_exprs
-            .iter()
-            .chain(aggr_exprs.iter())
-            .cloned()
+        let aggr_projection_exprs = grouping_set_to_exprlist(&group_by_exprs)?
+            .into_iter()
+            .chain(aggr_exprs.iter().cloned())
             .collect::<Vec<Expr>>();
 
+        let has_grouping_sets =
+            matches!(group_by_exprs.as_slice(), [Expr::GroupingSet(_)]);
         let plan = LogicalPlanBuilder::from(&input)
             .aggregate(group_by_exprs, aggr_exprs)?
             .build()?;
 
         // After aggregation, these are all of the columns that will be
         // available to next phases of planning.
-        let column_exprs_post_aggr = aggr_projection_exprs
+        let mut column_exprs_post_aggr = aggr_projection_exprs
             .iter()
             .map(|expr| expr_as_column_expr(expr, input))
             .collect::<Result<Vec<Expr>>>()?;
+        if has_grouping_sets {
+            column_exprs_post_aggr.push(col(GROUPING_ID_COLUMN));
+        }
 
         // Rewrite the SELECT expression to use the columns produced by the
         // aggregation.
@@ -706,7 +1256,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 
         // Rewrite the HAVING expression to use the columns produced by the
         // aggregation.
-        let having_expr_post_aggr_opt = if let Some(having_expr) = having_expr_opt {
+        let having_expr_post_aggr_opt = if let Some(having_expr) = &having_expr_opt {
             let having_expr_post_aggr =
                 rebase_expr(having_expr, &aggr_projection_exprs, input)?;
 
@@ -727,6 +1277,139 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         Ok((plan, select_exprs_post_aggr, having_expr_post_aggr_opt))
     }
 
+    /// Generate the grouping expressions of a GROUP BY clause. When it has
+    /// grouping sets, they are combined with its other items into a single
+    /// [`Expr::GroupingSet`].
+    fn group_by_to_rex(
+        &self,
+        group_by: &[SQLExpr],
+        schema: &DFSchema,
+    ) -> Result<Vec<Expr>> {
+        let group_by_exprs = group_by
+            .iter()
+            .map(|e| match grouping_set_kind(e) {
+                Some((kind, args)) => self.grouping_set_to_rex(kind, args, schema),
+                None => self.sql_to_rex(e, schema),
+            })
+            .collect::<Result<Vec<Expr>>>()?;
+
+        if group_by_exprs.len() < 2
+            || !group_by_exprs
+                .iter()
+                .any(|e| matches!(e, Expr::GroupingSet(_)))
+        {
+            return Ok(group_by_exprs);
+        }
+
+        // the grouping sets of several items are the concatenations of a
+        // grouping set of every item
+        let mut sets: Vec<Vec<Expr>> = vec![vec![]];
+        for expr in group_by_exprs {
+            let item_sets = match expr {
+                Expr::GroupingSet(grouping_set) => grouping_set_exprs(&grouping_set)?,
+                expr => vec![vec![expr]],
+            };
+            sets = sets
+                .iter()
+                .flat_map(|set| {
+                    item_sets.iter().map(move |item_set| {
+                        set.iter().chain(item_set).cloned().collect()
+                    })
+                })
+                .collect();
+        }
+        Ok(vec![grouping_sets(sets)])
+    }
+
+    /// Generate a [`Expr::GroupingSet`] from the arguments of `ROLLUP`, `CUBE`
+    /// or `GROUPING SETS`, whose parenthesized lists of expressions are calls
+    /// of [`GROUPING_SET_FUNCTION`]
+    fn grouping_set_to_rex(
+        &self,
+        kind: GroupingSetKind,
+        args: &[FunctionArg],
+        schema: &DFSchema,
+    ) -> Result<Expr> {
+        let mut elements = vec![];
+        for arg in args {
+            let arg = match arg {
+                FunctionArg::Unnamed(arg) => arg,
+                FunctionArg::Named { .. } => {
+                    return Err(DataFusionError::Plan(
+                        "Grouping sets do not support named arguments".to_string(),
+                    ))
+                }
+            };
+            match grouping_set_kind(arg) {
+                // ROLLUP and CUBE nested in GROUPING SETS list their grouping sets
+                Some((nested_kind, nested_args))
+                    if kind == GroupingSetKind::GroupingSets
+                        && nested_kind != GroupingSetKind::GroupingSets =>
+                {
+                    match self.grouping_set_to_rex(nested_kind, nested_args, schema)? {
+                        Expr::GroupingSet(grouping_set) => {
+                            elements.extend(grouping_set_exprs(&grouping_set)?)
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                Some(_) => {
+                    return Err(DataFusionError::Plan(format!(
+                        "Grouping sets cannot be nested in {:?}",
+                        kind
+                    )))
+                }
+                None => match arg {
+                    SQLExpr::Function(function)
+                        if is_function(function, GROUPING_SET_FUNCTION) =>
+                    {
+                        let exprs = function
+                            .args
+                            .iter()
+                            .map(|a| self.sql_fn_arg_to_logical_expr(a, schema))
+                            .collect::<Result<Vec<Expr>>>()?;
+                        self.validate_schema_satisfies_exprs(schema, &exprs)?;
+                        elements.push(exprs)
+                    }
+                    _ => elements.push(vec![self.sql_to_rex(arg, schema)?]),
+                },
+            }
+        }
+
+        let grouping_set = match kind {
+            GroupingSetKind::GroupingSets => GroupingSet::GroupingSets(elements),
+            // ROLLUP and CUBE of single expressions keep their structure
+            GroupingSetKind::Rollup if elements.iter().all(|e| e.len() == 1) => {
+                GroupingSet::Rollup(elements.into_iter().flatten().collect())
+            }
+            GroupingSetKind::Cube if elements.iter().all(|e| e.len() == 1) => {
+                GroupingSet::Cube(elements.into_iter().flatten().collect())
+            }
+            // otherwise, their grouping sets are expanded from the ones of the
+            // indices of their elements
+            GroupingSetKind::Rollup | GroupingSetKind::Cube => {
+                let indices = (0..elements.len()).map(|i| lit(i as u32)).collect();
+                let indices = if kind == GroupingSetKind::Rollup {
+                    GroupingSet::Rollup(indices)
+                } else {
+                    GroupingSet::Cube(indices)
+                };
+                GroupingSet::GroupingSets(
+                    indices
+                        .sets()?
+                        .into_iter()
+                        .map(|set| {
+                            set.into_iter()
+                                .flat_map(|i| elements[i].iter().cloned())
+                                .collect()
+                        })
+                        .collect(),
+                )
+            }
+        };
+        Ok(Expr::GroupingSet(grouping_set))
+    }
+
     /// Wrap a plan in a limit
     fn limit(&self, input: &LogicalPlan, limit: &Option<SQLExpr>) -> Result<LogicalPlan> {
         match *limit {
@@ -755,20 +1438,25 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         }
 
         let input_schema = plan.schema();
-        let order_by_rex: Result<Vec<Expr>> = order_by
+        let order_by_rex = order_by
             .iter()
-            .map(|e| {
-                Ok(Expr::Sort {
-                    expr: Box::new(self.sql_to_rex(&e.expr, &input_schema)?),
-                    // by default asc
-                    asc: e.asc.unwrap_or(true),
-                    // by default nulls first to be consistent with spark
-                    nulls_first: e.nulls_first.unwrap_or(true),
-                })
-            })
-            .collect();
+            .map(|e| self.order_by_to_sort_expr(e, &input_schema))
+            .collect::<Result<Vec<Expr>>>()?;
+
+        self.validate_schema_satisfies_exprs(&input_schema, &order_by_rex)?;
+
+        LogicalPlanBuilder::from(&plan).sort(order_by_rex)?.build()
+    }
 
-        LogicalPlanBuilder::from(&plan).sort(order_by_rex?)?.build()
+    /// convert sql OrderByExpr to Expr::Sort
+    fn order_by_to_sort_expr(&self, e: &OrderByExpr, schema: &DFSchema) -> Result<Expr> {
+        Ok(Expr::Sort {
+            expr: Box::new(self.sql_expr_to_logical_expr(&e.expr, schema)?),
+            // by default asc
+            asc: e.asc.unwrap_or(true),
+            // by default nulls first to be consistent with spark
+            nulls_first: e.nulls_first.unwrap_or(true),
+        })
     }
 
     /// Validate the schema provides all of the columns referenced in the expressions.
@@ -811,19 +1499,49 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 
     /// Generate a relational expression from a SQL expression
     pub fn sql_to_rex(&self, sql: &SQLExpr, schema: &DFSchema) -> Result<Expr> {
-        let expr = self.sql_expr_to_logical_expr(sql)?;
+        let expr = self.sql_expr_to_logical_expr(sql, schema)?;
         self.validate_schema_satisfies_exprs(schema, &[expr.clone()])?;
         Ok(expr)
     }
 
-    fn sql_fn_arg_to_logical_expr(&self, sql: &FunctionArg) -> Result<Expr> {
+    fn sql_fn_arg_to_logical_expr(
+        &self,
+        sql: &FunctionArg,
+        schema: &DFSchema,
+    ) -> Result<Expr> {
         match sql {
-            FunctionArg::Named { name: _, arg } => self.sql_expr_to_logical_expr(arg),
-            FunctionArg::Unnamed(value) => self.sql_expr_to_logical_expr(value),
+            FunctionArg::Named { name: _, arg } => {
+                self.sql_expr_to_logical_expr(arg, schema)
+            }
+            FunctionArg::Unnamed(value) => self.sql_expr_to_logical_expr(value, schema),
+        }
+    }
+
+    /// Generate the arguments of a built-in aggregate function call
+    fn aggregate_fn_args(
+        &self,
+        fun: &aggregates::AggregateFunction,
+        args: &[FunctionArg],
+        schema: &DFSchema,
+    ) -> Result<Vec<Expr>> {
+        if *fun == aggregates::AggregateFunction::Count {
+            args.iter()
+                .map(|a| match a {
+                    FunctionArg::Unnamed(SQLExpr::Value(Value::Number(_, _))) => {
+                        Ok(lit(1_u8))
+                    }
+                    FunctionArg::Unnamed(SQLExpr::Wildcard) => Ok(lit(1_u8)),
+                    _ => self.sql_fn_arg_to_logical_expr(a, schema),
+                })
+                .collect::<Result<Vec<Expr>>>()
+        } else {
+            args.iter()
+                .map(|a| self.sql_fn_arg_to_logical_expr(a, schema))
+                .collect::<Result<Vec<Expr>>>()
         }
     }
 
-    fn sql_expr_to_logical_expr(&self, sql: &SQLExpr) -> Result<Expr> {
+    fn sql_expr_to_logical_expr(&self, sql: &SQLExpr, schema: &DFSchema) -> Result<Expr> {
         match sql {
             SQLExpr::Value(Value::Number(n, _)) => match n.parse::<i64>() {
                 Ok(n) => Ok(lit(n)),
@@ -838,7 +1556,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 fun: functions::BuiltinScalarFunction::DatePart,
                 args: vec![
                     Expr::Literal(ScalarValue::Utf8(Some(format!("{}", field)))),
-                    self.sql_expr_to_logical_expr(expr)?,
+                    self.sql_expr_to_logical_expr(expr, schema)?,
                 ],
             }),
 
@@ -889,20 +1607,20 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 else_result,
             } => {
                 let expr = if let Some(e) = operand {
-                    Some(Box::new(self.sql_expr_to_logical_expr(e)?))
+                    Some(Box::new(self.sql_expr_to_logical_expr(e, schema)?))
                 } else {
                     None
                 };
                 let when_expr = conditions
                     .iter()
-                    .map(|e| self.sql_expr_to_logical_expr(e))
+                    .map(|e| self.sql_expr_to_logical_expr(e, schema))
                     .collect::<Result<Vec<_>>>()?;
                 let then_expr = results
                     .iter()
-                    .map(|e| self.sql_expr_to_logical_expr(e))
+                    .map(|e| self.sql_expr_to_logical_expr(e, schema))
                     .collect::<Result<Vec<_>>>()?;
                 let else_expr = if let Some(e) = else_result {
-                    Some(Box::new(self.sql_expr_to_logical_expr(e)?))
+                    Some(Box::new(self.sql_expr_to_logical_expr(e, schema)?))
                 } else {
                     None
                 };
@@ -922,7 +1640,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 ref expr,
                 ref data_type,
             } => Ok(Expr::Cast {
-                expr: Box::new(self.sql_expr_to_logical_expr(&expr)?),
+                expr: Box::new(self.sql_expr_to_logical_expr(&expr, schema)?),
                 data_type: convert_data_type(data_type)?,
             }),
 
@@ -930,7 +1648,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 ref expr,
                 ref data_type,
             } => Ok(Expr::TryCast {
-                expr: Box::new(self.sql_expr_to_logical_expr(&expr)?),
+                expr: Box::new(self.sql_expr_to_logical_expr(&expr, schema)?),
                 data_type: convert_data_type(data_type)?,
             }),
 
@@ -942,19 +1660,19 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 data_type: convert_data_type(data_type)?,
             }),
 
-            SQLExpr::IsNull(ref expr) => {
-                Ok(Expr::IsNull(Box::new(self.sql_expr_to_logical_expr(expr)?)))
-            }
+            SQLExpr::IsNull(ref expr) => Ok(Expr::IsNull(Box::new(
+                self.sql_expr_to_logical_expr(expr, schema)?,
+            ))),
 
             SQLExpr::IsNotNull(ref expr) => Ok(Expr::IsNotNull(Box::new(
-                self.sql_expr_to_logical_expr(expr)?,
+                self.sql_expr_to_logical_expr(expr, schema)?,
             ))),
 
             SQLExpr::UnaryOp { ref op, ref expr } => match op {
-                UnaryOperator::Not => {
-                    Ok(Expr::Not(Box::new(self.sql_expr_to_logical_expr(expr)?)))
-                }
-                UnaryOperator::Plus => Ok(self.sql_expr_to_logical_expr(expr)?),
+                UnaryOperator::Not => Ok(Expr::Not(Box::new(
+                    self.sql_expr_to_logical_expr(expr, schema)?,
+                ))),
+                UnaryOperator::Plus => Ok(self.sql_expr_to_logical_expr(expr, schema)?),
                 UnaryOperator::Minus => {
                     match expr.as_ref() {
                         // optimization: if it's a number literal, we applly the negative operator
@@ -970,7 +1688,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                                 })?)),
                         },
                         // not a literal, apply negative operator on expression
-                        _ => Ok(Expr::Negative(Box::new(self.sql_expr_to_logical_expr(expr)?))),
+                        _ => Ok(Expr::Negative(Box::new(self.sql_expr_to_logical_expr(expr, schema)?))),
                     }
                 }
                 _ => Err(DataFusionError::NotImplemented(format!(
@@ -985,10 +1703,10 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 ref low,
                 ref high,
             } => Ok(Expr::Between {
-                expr: Box::new(self.sql_expr_to_logical_expr(&expr)?),
+                expr: Box::new(self.sql_expr_to_logical_expr(&expr, schema)?),
                 negated: *negated,
-                low: Box::new(self.sql_expr_to_logical_expr(&low)?),
-                high: Box::new(self.sql_expr_to_logical_expr(&high)?),
+                low: Box::new(self.sql_expr_to_logical_expr(&low, schema)?),
+                high: Box::new(self.sql_expr_to_logical_expr(&high, schema)?),
             }),
 
             SQLExpr::InList {
@@ -998,11 +1716,11 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             } => {
                 let list_expr = list
                     .iter()
-                    .map(|e| self.sql_expr_to_logical_expr(e))
+                    .map(|e| self.sql_expr_to_logical_expr(e, schema))
                     .collect::<Result<Vec<_>>>()?;
 
                 Ok(Expr::InList {
-                    expr: Box::new(self.sql_expr_to_logical_expr(&expr)?),
+                    expr: Box::new(self.sql_expr_to_logical_expr(&expr, schema)?),
                     list: list_expr,
                     negated: *negated,
                 })
@@ -1036,9 +1754,9 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 }?;
 
                 Ok(Expr::BinaryExpr {
-                    left: Box::new(self.sql_expr_to_logical_expr(&left)?),
+                    left: Box::new(self.sql_expr_to_logical_expr(&left, schema)?),
                     op: operator,
-                    right: Box::new(self.sql_expr_to_logical_expr(&right)?),
+                    right: Box::new(self.sql_expr_to_logical_expr(&right, schema)?),
                 })
             }
 
@@ -1057,12 +1775,58 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                     }
                 };
 
-                // first, scalar built-in
+                // first, window functions
+                if let Some(window) = &function.over {
+                    let partition_by = window
+                        .partition_by
+                        .iter()
+                        .map(|e| self.sql_expr_to_logical_expr(e, schema))
+                        .collect::<Result<Vec<_>>>()?;
+                    let order_by = window
+                        .order_by
+                        .iter()
+                        .map(|e| self.order_by_to_sort_expr(e, schema))
+                        .collect::<Result<Vec<_>>>()?;
+                    let window_frame = window
+                        .window_frame
+                        .as_ref()
+                        .map(|window_frame| window_frame.clone().try_into())
+                        .transpose()?;
+                    let fun = window_functions::WindowFunction::from_str(&name)?;
+                    if function.distinct {
+                        return Err(DataFusionError::NotImplemented(format!(
+                            "DISTINCT is not supported in window function {}",
+                            fun
+                        )));
+                    }
+                    let args = match &fun {
+                        window_functions::WindowFunction::AggregateFunction(
+                            aggregate,
+                        ) => self.aggregate_fn_args(aggregate, &function.args, schema)?,
+                        window_functions::WindowFunction::BuiltInWindowFunction(_) => {
+                            function
+                                .args
+                                .iter()
+                                .map(|a| self.sql_fn_arg_to_logical_expr(a, schema))
+                                .collect::<Result<Vec<Expr>>>()?
+                        }
+                    };
+
+                    return Ok(Expr::WindowFunction {
+                        fun,
+                        args,
+                        partition_by,
+                        order_by,
+                        window_frame,
+                    });
+                }
+
+                // next, scalar built-in
                 if let Ok(fun) = functions::BuiltinScalarFunction::from_str(&name) {
                     let args = function
                         .args
                         .iter()
-                        .map(|a| self.sql_fn_arg_to_logical_expr(a))
+                        .map(|a| self.sql_fn_arg_to_logical_expr(a, schema))
                         .collect::<Result<Vec<Expr>>>()?;
 
                     return Ok(Expr::ScalarFunction { fun, args });
@@ -1070,26 +1834,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 
                 // next, aggregate built-ins
                 if let Ok(fun) = aggregates::AggregateFunction::from_str(&name) {
-                    let args = if fun == aggregates::AggregateFunction::Count {
-                        function
-                            .args
-                            .iter()
-                            .map(|a| match a {
-                                FunctionArg::Unnamed(SQLExpr::Value(Value::Number(
-                                    _,
-                                    _,
-                                ))) => Ok(lit(1_u8)),
-                                FunctionArg::Unnamed(SQLExpr::Wildcard) => Ok(lit(1_u8)),
-                                _ => self.sql_fn_arg_to_logical_expr(a),
-                            })
-                            .collect::<Result<Vec<Expr>>>()?
-                    } else {
-                        function
-                            .args
-                            .iter()
-                            .map(|a| self.sql_fn_arg_to_logical_expr(a))
-                            .collect::<Result<Vec<Expr>>>()?
-                    };
+                    let args = self.aggregate_fn_args(&fun, &function.args, schema)?;
 
                     return Ok(Expr::AggregateFunction {
                         fun,
@@ -1104,7 +1849,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                         let args = function
                             .args
                             .iter()
-                            .map(|a| self.sql_fn_arg_to_logical_expr(a))
+                            .map(|a| self.sql_fn_arg_to_logical_expr(a, schema))
                             .collect::<Result<Vec<Expr>>>()?;
 
                         Ok(Expr::ScalarUDF { fun: fm, args })
@@ -1114,7 +1859,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                             let args = function
                                 .args
                                 .iter()
-                                .map(|a| self.sql_fn_arg_to_logical_expr(a))
+                                .map(|a| self.sql_fn_arg_to_logical_expr(a, schema))
                                 .collect::<Result<Vec<Expr>>>()?;
 
                             Ok(Expr::AggregateUDF { fun: fm, args })
@@ -1127,7 +1872,32 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 }
             }
 
-            SQLExpr::Nested(e) => self.sql_expr_to_logical_expr(&e),
+            SQLExpr::Exists(subquery) => Ok(Expr::Exists {
+                subquery: self.subquery_to_plan(subquery, schema)?,
+                negated: false,
+            }),
+
+            SQLExpr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => {
+                let subquery = self.subquery_to_plan(subquery, schema)?;
+                self.validate_single_column_subquery(&subquery)?;
+                Ok(Expr::InSubquery {
+                    expr: Box::new(self.sql_expr_to_logical_expr(expr, schema)?),
+                    subquery,
+                    negated: *negated,
+                })
+            }
+
+            SQLExpr::Subquery(subquery) => {
+                let subquery = self.subquery_to_plan(subquery, schema)?;
+                self.validate_single_column_subquery(&subquery)?;
+                Ok(Expr::ScalarSubquery(subquery))
+            }
+
+            SQLExpr::Nested(e) => self.sql_expr_to_logical_expr(&e, schema),
 
             _ => Err(DataFusionError::NotImplemented(format!(
                 "Unsupported ast node {:?} in sqltorel",
@@ -1136,6 +1906,33 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         }
     }
 
+    /// Generate a logical plan from a subquery, whose `WHERE` clause may
+    /// reference the columns of the enclosing query in `outer_query_schema`
+    fn subquery_to_plan(
+        &self,
+        query: &Query,
+        outer_query_schema: &DFSchema,
+    ) -> Result<Subquery> {
+        let plan = self.query_to_plan_with_context(
+            query,
+            None,
+            &mut HashMap::new(),
+            Some(outer_query_schema),
+        )?;
+        Ok(Subquery::new(plan))
+    }
+
+    /// Errors if the subquery does not produce exactly one column
+    fn validate_single_column_subquery(&self, subquery: &Subquery) -> Result<()> {
+        match subquery.subquery.schema().fields().len() {
+            1 => Ok(()),
+            n => Err(DataFusionError::Plan(format!(
+                "Subquery used as an expression must return a single column, found {}",
+                n
+            ))),
+        }
+    }
+
     fn sql_interval_to_literal(
         &self,
         value: &str,
@@ -1395,6 +2192,134 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 }
 
 /// Remove join expressions from a filter expression
+/// Returns the options to write files with for the options of a COPY
+/// statement:
+///
+/// * `FORMAT`: `csv`, `parquet` or `json`; by default the extension of the
+///   target directory
+/// * `HEADER` and `DELIMITER`, of CSV files
+/// * `COMPRESSION`: `gzip` or `uncompressed` for CSV and JSON files, or any
+///   Parquet compression codec for Parquet files
+fn file_write_options(
+    target: &str,
+    options: &[(String, String)],
+) -> Result<FileWriteOptions> {
+    let option = |name: &str| {
+        options
+            .iter()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.to_ascii_lowercase())
+    };
+
+    let format = match option("FORMAT") {
+        Some(format) => format,
+        None => match Path::new(target).extension().and_then(|e| e.to_str()) {
+            Some(extension) => extension.to_ascii_lowercase(),
+            None => {
+                return Err(DataFusionError::Plan(
+                    "COPY requires a FORMAT option or a target with a file extension"
+                        .to_string(),
+                ))
+            }
+        },
+    };
+    let format = match format.as_str() {
+        "csv" => FileFormat::Csv {
+            has_header: true,
+            delimiter: b',',
+        },
+        "parquet" => FileFormat::Parquet,
+        "json" | "ndjson" => FileFormat::NdJson,
+        _ => {
+            return Err(DataFusionError::Plan(format!(
+                "Unsupported COPY format '{}', expected one of csv, parquet or json",
+                format
+            )))
+        }
+    };
+    let mut write_options = FileWriteOptions::new(format);
+
+    for (name, value) in options {
+        let value = value.to_ascii_lowercase();
+        match (name.as_str(), &mut write_options.format) {
+            ("FORMAT", _) => {}
+            ("HEADER", FileFormat::Csv { has_header, .. }) => {
+                *has_header = value.parse().map_err(|_| {
+                    DataFusionError::Plan(format!(
+                        "COPY option HEADER must be true or false, not '{}'",
+                        value
+                    ))
+                })?;
+            }
+            ("DELIMITER", FileFormat::Csv { delimiter, .. }) => match value.as_bytes() {
+                [byte] => *delimiter = *byte,
+                _ => {
+                    return Err(DataFusionError::Plan(format!(
+                        "COPY option DELIMITER must be a single character, not '{}'",
+                        value
+                    )))
+                }
+            },
+            ("COMPRESSION", FileFormat::Parquet) => {
+                let compression = match value.as_str() {
+                    "uncompressed" => Compression::UNCOMPRESSED,
+                    "snappy" => Compression::SNAPPY,
+                    "gzip" => Compression::GZIP,
+                    "lzo" => Compression::LZO,
+                    "brotli" => Compression::BROTLI,
+                    "lz4" => Compression::LZ4,
+                    "zstd" => Compression::ZSTD,
+                    _ => {
+                        return Err(DataFusionError::Plan(format!(
+                            "Unsupported Parquet compression '{}'",
+                            value
+                        )))
+                    }
+                };
+                write_options = write_options.writer_properties(
+                    WriterProperties::builder()
+                        .set_compression(compression)
+                        .build(),
+                );
+            }
+            ("COMPRESSION", _) => {
+                let compression = match value.as_str() {
+                    "uncompressed" => FileCompression::Uncompressed,
+                    "gzip" => FileCompression::Gzip,
+                    _ => {
+                        return Err(DataFusionError::Plan(format!(
+                            "Unsupported compression '{}', expected gzip or uncompressed",
+                            value
+                        )))
+                    }
+                };
+                write_options = write_options.compression(compression);
+            }
+            (name, format) => {
+                let format = match format {
+                    FileFormat::Csv { .. } => "CSV",
+                    FileFormat::Parquet => "Parquet",
+                    FileFormat::NdJson => "JSON",
+                };
+                return Err(DataFusionError::Plan(format!(
+                    "Unsupported COPY option {} for {} files",
+                    name, format
+                )));
+            }
+        }
+    }
+    Ok(write_options)
+}
+
+/// Returns the name an identifier refers to: unquoted identifiers are case
+/// insensitive, and normalized to lower case
+fn normalize_ident(ident: &Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value.clone(),
+        None => ident.value.to_ascii_lowercase(),
+    }
+}
+
 fn remove_join_expressions(
     expr: &Expr,
     join_columns: &[(&str, &str)],
@@ -1423,43 +2348,48 @@ fn remove_join_expressions(
             }
             _ => Ok(Some(expr.clone())),
         },
-        _ => Ok(Some(expr.clone())),
-    }
-}
-
-/// Parse equijoin ON condition which could be a single Eq or multiple conjunctive Eqs
-///
-/// Examples
-///
-/// foo = bar
-/// foo = bar AND bar = baz AND ...
-///
-fn extract_join_keys(expr: &Expr, accum: &mut Vec<(String, String)>) -> Result<()> {
-    match expr {
-        Expr::BinaryExpr { left, op, right } => match op {
-            Operator::Eq => match (left.as_ref(), right.as_ref()) {
-                (Expr::Column(l), Expr::Column(r)) => {
-                    accum.push((l.to_owned(), r.to_owned()));
-                    Ok(())
-                }
-                other => Err(DataFusionError::SQL(ParserError(format!(
-                    "Unsupported expression '{:?}' in JOIN condition",
-                    other
-                )))),
-            },
-            Operator::And => {
-                extract_join_keys(left, accum)?;
-                extract_join_keys(right, accum)
+        _ => Ok(Some(expr.clone())),
+    }
+}
+
+/// Extract join keys from an ON expression: the equalities between a column of the
+/// left side and a column of the right side. The other predicates are added to `filters`.
+fn extract_join_keys(
+    expr: &Expr,
+    left_schema: &DFSchema,
+    right_schema: &DFSchema,
+    keys: &mut Vec<(String, String)>,
+    filters: &mut Vec<Expr>,
+) {
+    match expr {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        } => {
+            extract_join_keys(left, left_schema, right_schema, keys, filters);
+            extract_join_keys(right, left_schema, right_schema, keys, filters);
+        }
+        Expr::BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        } => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(l), Expr::Column(r))
+                if left_schema.field_with_unqualified_name(l).is_ok()
+                    && right_schema.field_with_unqualified_name(r).is_ok() =>
+            {
+                keys.push((l.to_owned(), r.to_owned()));
             }
-            other => Err(DataFusionError::SQL(ParserError(format!(
-                "Unsupported expression '{:?}' in JOIN condition",
-                other
-            )))),
+            (Expr::Column(l), Expr::Column(r))
+                if left_schema.field_with_unqualified_name(r).is_ok()
+                    && right_schema.field_with_unqualified_name(l).is_ok() =>
+            {
+                keys.push((r.to_owned(), l.to_owned()));
+            }
+            _ => filters.push(expr.clone()),
         },
-        other => Err(DataFusionError::SQL(ParserError(format!(
-            "Unsupported expression '{:?}' in JOIN condition",
-            other
-        )))),
+        _ => filters.push(expr.clone()),
     }
 }
 
@@ -1496,6 +2426,7 @@ pub fn convert_data_type(sql: &SQLDataType) -> Result<DataType> {
         SQLDataType::BigInt => Ok(DataType::Int64),
         SQLDataType::Float(_) | SQLDataType::Real => Ok(DataType::Float64),
         SQLDataType::Double => Ok(DataType::Float64),
+        SQLDataType::Decimal(precision, scale) => make_decimal_type(*precision, *scale),
         SQLDataType::Char(_) | SQLDataType::Varchar(_) => Ok(DataType::Utf8),
         SQLDataType::Timestamp => Ok(DataType::Timestamp(TimeUnit::Nanosecond, None)),
         SQLDataType::Date => Ok(DataType::Date32),
@@ -1506,12 +2437,164 @@ pub fn convert_data_type(sql: &SQLDataType) -> Result<DataType> {
     }
 }
 
+/// Returns `DataType::Decimal` for SQL `DECIMAL(precision, scale)`. A missing precision
+/// defaults to the maximum precision and a missing scale to zero, as in the SQL standard.
+fn make_decimal_type(precision: Option<u64>, scale: Option<u64>) -> Result<DataType> {
+    let precision = precision.map_or(DECIMAL_MAX_PRECISION, |p| p as usize);
+    let scale = scale.unwrap_or(0) as usize;
+    if precision == 0 || precision > DECIMAL_MAX_PRECISION || scale > precision {
+        Err(DataFusionError::Plan(format!(
+            "Decimal(precision = {}, scale = {}) should satisfy `0 < precision <= {}`, and `scale <= precision`.",
+            precision, scale, DECIMAL_MAX_PRECISION
+        )))
+    } else {
+        Ok(DataType::Decimal(precision, scale))
+    }
+}
+
+/// Name of the column that numbers duplicate rows for INTERSECT ALL and EXCEPT ALL
+const SET_ROW_NUMBER: &str = "__set_row_number";
+
+/// Removes duplicate rows of `plan` by grouping on all of its columns
+fn distinct(plan: &LogicalPlan) -> Result<LogicalPlan> {
+    let columns = plan
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| Expr::Column(f.name().clone()));
+    LogicalPlanBuilder::from(plan)
+        .aggregate(columns, vec![])?
+        .build()
+}
+
+/// Numbers the duplicates of every row of `plan` in a column `SET_ROW_NUMBER`,
+/// so that the n-th duplicate of a row on one side of a set operation only
+/// matches the n-th duplicate on the other side
+fn number_duplicate_rows(plan: &LogicalPlan) -> Result<LogicalPlan> {
+    let columns = plan
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| Expr::Column(f.name().clone()))
+        .collect::<Vec<_>>();
+    let row_number = Expr::WindowFunction {
+        fun: window_functions::WindowFunction::BuiltInWindowFunction(
+            window_functions::BuiltInWindowFunction::RowNumber,
+        ),
+        args: vec![],
+        partition_by: columns.clone(),
+        order_by: vec![],
+        window_frame: None,
+    };
+    let name = row_number.name(plan.schema())?;
+    LogicalPlanBuilder::from(plan)
+        .window(vec![row_number])?
+        .project(columns.into_iter().chain(std::iter::once(Alias(
+            Box::new(Expr::Column(name)),
+            SET_ROW_NUMBER.to_string(),
+        ))))?
+        .build()
+}
+
+/// Generate a logical plan for INTERSECT or EXCEPT, as a semi or anti join of
+/// the left plan with the right plan on all columns, where null values match
+/// each other
+fn intersect_or_except(
+    op: &SetOperator,
+    all: bool,
+    left_plan: &LogicalPlan,
+    right_plan: &LogicalPlan,
+) -> Result<LogicalPlan> {
+    let left_fields = left_plan.schema().fields();
+    let right_fields = right_plan.schema().fields();
+    if left_fields.len() != right_fields.len()
+        || left_fields
+            .iter()
+            .zip(right_fields)
+            .any(|(l, r)| l.data_type() != r.data_type())
+    {
+        return Err(DataFusionError::Plan(format!(
+            "{} queries must have the same number of columns with the same types",
+            op
+        )));
+    }
+    let join_type = match op {
+        SetOperator::Intersect => JoinType::Semi,
+        _ => JoinType::Anti,
+    };
+    let mut left_keys = left_fields
+        .iter()
+        .map(|f| f.name().as_str())
+        .collect::<Vec<_>>();
+    let mut right_keys = right_fields
+        .iter()
+        .map(|f| f.name().as_str())
+        .collect::<Vec<_>>();
+
+    if all {
+        left_keys.push(SET_ROW_NUMBER);
+        right_keys.push(SET_ROW_NUMBER);
+        let columns = left_fields.iter().map(|f| Expr::Column(f.name().clone()));
+        LogicalPlanBuilder::from(&number_duplicate_rows(left_plan)?)
+            .join_detailed(
+                &number_duplicate_rows(right_plan)?,
+                join_type,
+                &left_keys,
+                &right_keys,
+                true,
+            )?
+            .project(columns)?
+            .build()
+    } else {
+        LogicalPlanBuilder::from(&distinct(left_plan)?)
+            .join_detailed(right_plan, join_type, &left_keys, &right_keys, true)?
+            .build()
+    }
+}
+
+/// The SQL constructs that declare grouping sets in a GROUP BY clause
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GroupingSetKind {
+    Rollup,
+    Cube,
+    GroupingSets,
+}
+
+/// Returns whether `function` is the unquoted function `name`
+fn is_function(function: &Function, name: &str) -> bool {
+    match function.name.0.as_slice() {
+        [ident] => ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case(name),
+        _ => false,
+    }
+}
+
+/// Returns the kind and the arguments of the grouping sets declared by `expr`,
+/// if any
+fn grouping_set_kind(expr: &SQLExpr) -> Option<(GroupingSetKind, &[FunctionArg])> {
+    match expr {
+        SQLExpr::Function(function) if function.over.is_none() => {
+            let kind = if is_function(function, "rollup") {
+                GroupingSetKind::Rollup
+            } else if is_function(function, "cube") {
+                GroupingSetKind::Cube
+            } else if is_function(function, GROUPING_SETS_FUNCTION) {
+                GroupingSetKind::GroupingSets
+            } else {
+                return None;
+            };
+            Some((kind, function.args.as_slice()))
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::datasource::empty::EmptyTable;
     use crate::{logical_plan::create_udf, sql::parser::DFParser};
     use functions::ScalarFunctionImplementation;
+    use parquet::schema::types::ColumnPath;
 
     const PERSON_COLUMN_NAMES: &str =
         "id, first_name, last_name, age, state, salary, birth_date";
@@ -1525,6 +2608,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn explain_analyze() {
+        quick_test(
+            "EXPLAIN ANALYZE SELECT 1",
+            "Analyze\
+             \n  Projection: Int64(1)\
+             \n    EmptyRelation",
+        );
+    }
+
     #[test]
     fn select_column_does_not_exist() {
         let sql = "SELECT doesnotexist FROM person";
@@ -1656,6 +2749,25 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn test_decimal_cast() {
+        let sql = "SELECT CAST(age AS DECIMAL(10, 2)), CAST(age AS DECIMAL) FROM person";
+        let expected =
+            "Projection: CAST(#age AS Decimal(10, 2)), CAST(#age AS Decimal(38, 0))\
+            \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn test_decimal_cast_invalid_precision() {
+        let sql = "SELECT CAST(age AS DECIMAL(39, 2)) FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Decimal(precision = 39, scale = 2) should satisfy `0 < precision <= 38`, and `scale <= precision`.\")",
+            format!("{:?}", err)
+        );
+    }
+
     #[test]
     fn select_all_boolean_operators() {
         let sql = "SELECT age, first_name, last_name \
@@ -2538,6 +3650,153 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn create_external_table_json() {
+        let sql = "CREATE EXTERNAL TABLE t(c1 int) STORED AS JSON LOCATION 'foo.json'";
+        let expected = "CreateExternalTable: \"t\"";
+        quick_test(sql, expected);
+
+        let sql = "CREATE EXTERNAL TABLE t STORED AS NDJSON LOCATION 'foo.json'";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn copy_to() {
+        let sql = "COPY (SELECT id FROM person) TO 'out' (FORMAT csv, DELIMITER '|')";
+        let plan = logical_plan(sql).unwrap();
+        let expected = "CopyTo: \"out\" format=Csv { has_header: true, delimiter: 124 }\
+        \n  Projection: #id\
+        \n    TableScan: person projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        // the format defaults to the extension of the target
+        let sql = "COPY person TO 'out.parquet' (COMPRESSION zstd)";
+        match logical_plan(sql).unwrap() {
+            LogicalPlan::CopyTo { options, .. } => {
+                assert_eq!(FileFormat::Parquet, options.format);
+                let properties = options.writer_properties.unwrap();
+                assert_eq!(
+                    Compression::ZSTD,
+                    properties.compression(&ColumnPath::from("id"))
+                );
+            }
+            plan => panic!("Expected CopyTo, got {:?}", plan),
+        }
+
+        let sql = "COPY person TO 'out' (FORMAT json, COMPRESSION gzip)";
+        match logical_plan(sql).unwrap() {
+            LogicalPlan::CopyTo { options, .. } => {
+                assert_eq!(FileFormat::NdJson, options.format);
+                assert_eq!(FileCompression::Gzip, options.compression);
+            }
+            plan => panic!("Expected CopyTo, got {:?}", plan),
+        }
+    }
+
+    #[test]
+    fn copy_to_invalid_options() {
+        let cases = [
+            (
+                "COPY person TO 'out'",
+                "COPY requires a FORMAT option or a target with a file extension",
+            ),
+            (
+                "COPY person TO 'out' (FORMAT avro)",
+                "Unsupported COPY format 'avro', expected one of csv, parquet or json",
+            ),
+            (
+                "COPY person TO 'out.json' (HEADER true)",
+                "Unsupported COPY option HEADER for JSON files",
+            ),
+            (
+                "COPY person TO 'out.csv' (COMPRESSION snappy)",
+                "Unsupported compression 'snappy', expected gzip or uncompressed",
+            ),
+        ];
+        for (sql, expected) in &cases {
+            let err = logical_plan(sql).expect_err("query should have failed");
+            assert_eq!(format!("Plan({:?})", expected), format!("{:?}", err));
+        }
+    }
+
+    #[test]
+    fn create_table_as_select() {
+        let sql = "CREATE TABLE t AS SELECT id, age FROM person WHERE age > 21";
+        let expected = "CreateMemoryTable: \"t\"\
+        \n  Projection: #id, #age\
+        \n    Filter: #age Gt Int64(21)\
+        \n      TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn create_table_with_columns() {
+        let sql = "CREATE TABLE t (c1 INT NOT NULL, c2 VARCHAR NULL)";
+        let plan = logical_plan(sql).unwrap();
+        assert_eq!(
+            "CreateMemoryTable: \"t\"\n  EmptyRelation",
+            format!("{:?}", plan)
+        );
+        let fields = plan.schema().fields();
+        assert_eq!(DataType::Int32, *fields[0].data_type());
+        assert!(!fields[0].is_nullable());
+        assert!(fields[1].is_nullable());
+    }
+
+    #[test]
+    fn create_table_existing() {
+        let sql = "CREATE TABLE person AS SELECT 1";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Table 'person' already exists\")",
+            format!("{:?}", err)
+        );
+
+        let sql = "CREATE TABLE IF NOT EXISTS person AS SELECT 1";
+        quick_test(sql, "EmptyRelation");
+    }
+
+    #[test]
+    fn create_view() {
+        let sql = "CREATE VIEW v (a, b) AS SELECT id, age FROM person";
+        let expected = "CreateView: \"v\"\
+        \n  Projection: #id AS a, #age AS b\
+        \n    Projection: #id, #age\
+        \n      TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        let sql = "CREATE OR REPLACE VIEW person AS SELECT 1";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"'person' is a table, not a view\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn insert_into_non_memory_table() {
+        let sql = "INSERT INTO person SELECT * FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert!(matches!(err, DataFusionError::NotImplemented(_)));
+    }
+
+    #[test]
+    fn select_from_values() {
+        let sql = "SELECT * FROM (VALUES (1, 'a'), (NULL, 'b')) AS t";
+        let plan = logical_plan(sql).unwrap();
+        let fields = plan.schema().fields();
+        assert_eq!("column1", fields[0].name());
+        assert_eq!(DataType::Int64, *fields[0].data_type());
+        assert!(fields[0].is_nullable());
+        assert_eq!("column2", fields[1].name());
+        assert_eq!(DataType::Utf8, *fields[1].data_type());
+        assert!(!fields[1].is_nullable());
+
+        let sql = "VALUES (1, 2), (3)";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert!(matches!(err, DataFusionError::Plan(_)));
+    }
+
     #[test]
     fn equijoin_explicit_syntax() {
         let sql = "SELECT id, order_id \
@@ -2551,6 +3810,99 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn full_outer_join() {
+        let sql = "SELECT id, order_id \
+            FROM person \
+            FULL OUTER JOIN orders \
+            ON id = customer_id";
+        let expected = "Projection: #id, #order_id\
+        \n  Full Join: id = customer_id\
+        \n    TableScan: person projection=None\
+        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn equijoin_with_condition() {
+        let sql = "SELECT id, order_id \
+            FROM person \
+            JOIN orders \
+            ON customer_id = id AND order_id > 10";
+        let expected = "Projection: #id, #order_id\
+        \n  Filter: #order_id Gt Int64(10)\
+        \n    Join: id = customer_id\
+        \n      TableScan: person projection=None\
+        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn left_equijoin_with_condition_on_right() {
+        let sql = "SELECT id, order_id \
+            FROM person \
+            LEFT JOIN orders \
+            ON id = customer_id AND order_id > 10";
+        let expected = "Projection: #id, #order_id\
+        \n  Left Join: id = customer_id\
+        \n    TableScan: person projection=None\
+        \n    Filter: #order_id Gt Int64(10)\
+        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn left_equijoin_with_condition_on_left() {
+        let sql = "SELECT id, order_id \
+            FROM person \
+            LEFT JOIN orders \
+            ON id = customer_id AND age > 10";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "This feature is not implemented: Unsupported expression '#age Gt Int64(10)' in Left JOIN condition",
+            format!("{}", err)
+        );
+    }
+
+    #[test]
+    fn nonequijoin() {
+        let sql = "SELECT id, order_id \
+            FROM person \
+            JOIN orders \
+            ON id < customer_id";
+        let expected = "Projection: #id, #order_id\
+        \n  Filter: #id Lt #customer_id\
+        \n    CrossJoin:\
+        \n      TableScan: person projection=None\
+        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn cross_join() {
+        let sql = "SELECT id, order_id FROM person CROSS JOIN orders";
+        let expected = "Projection: #id, #order_id\
+        \n  CrossJoin:\
+        \n    TableScan: person projection=None\
+        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn cross_join_implicit_syntax() {
+        let sql = "SELECT id, order_id, l_description \
+            FROM person, orders, lineitem \
+            WHERE id = customer_id AND age > l_item_id";
+        let expected = "Projection: #id, #order_id, #l_description\
+        \n  Filter: #age Gt #l_item_id\
+        \n    CrossJoin:\
+        \n      Join: id = customer_id\
+        \n        TableScan: person projection=None\
+        \n        TableScan: orders projection=None\
+        \n      TableScan: lineitem projection=None";
+        quick_test(sql, expected);
+    }
+
     #[test]
     fn equijoin_explicit_syntax_3_tables() {
         let sql = "SELECT id, order_id, l_description \
@@ -2617,15 +3969,193 @@ mod tests {
     }
 
     #[test]
-    fn only_union_all_supported() {
-        let sql = "SELECT order_id from orders EXCEPT SELECT order_id FROM orders";
+    fn union_distinct() {
+        let sql = "SELECT order_id from orders UNION SELECT order_id FROM orders";
+        let expected = "Aggregate: groupBy=[[#order_id]], aggr=[[]]\
+            \n  Union\
+            \n    Projection: #order_id\
+            \n      TableScan: orders projection=None\
+            \n    Projection: #order_id\
+            \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn intersect() {
+        let sql = "SELECT order_id from orders INTERSECT SELECT customer_id FROM orders";
+        let expected = "Semi Join: order_id = customer_id\
+            \n  Aggregate: groupBy=[[#order_id]], aggr=[[]]\
+            \n    Projection: #order_id\
+            \n      TableScan: orders projection=None\
+            \n  Projection: #customer_id\
+            \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn except_all() {
+        let sql = "SELECT order_id from orders EXCEPT ALL SELECT customer_id FROM orders";
+        let expected = "Projection: #order_id\
+            \n  Anti Join: order_id = customer_id, __set_row_number = __set_row_number\
+            \n    Projection: #order_id, #ROW_NUMBER() PARTITION BY [order_id] AS __set_row_number\
+            \n      WindowAggr: windowExpr=[[ROW_NUMBER() PARTITION BY [#order_id]]]\
+            \n        Projection: #order_id\
+            \n          TableScan: orders projection=None\
+            \n    Projection: #customer_id, #ROW_NUMBER() PARTITION BY [customer_id] AS __set_row_number\
+            \n      WindowAggr: windowExpr=[[ROW_NUMBER() PARTITION BY [#customer_id]]]\
+            \n        Projection: #customer_id\
+            \n          TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn except_schemas_should_match() {
+        let sql = "SELECT order_id from orders EXCEPT SELECT customer_id, o_item_id FROM orders";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Error during planning: EXCEPT queries must have the same number of columns with the same types",
+            format!("{}", err)
+        );
+    }
+
+    #[test]
+    fn empty_over() {
+        let sql = "SELECT order_id, MAX(order_id) OVER () from orders";
+        let expected = "Projection: #order_id, #MAX(order_id)\
+            \n  WindowAggr: windowExpr=[[MAX(#order_id)]]\
+            \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn over_partition_by() {
+        let sql = "SELECT order_id, MAX(qty) OVER (PARTITION BY order_id) from orders";
+        let expected = "Projection: #order_id, #MAX(qty) PARTITION BY [order_id]\
+            \n  WindowAggr: windowExpr=[[MAX(#qty) PARTITION BY [#order_id]]]\
+            \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn over_order_by_with_window_frame() {
+        let sql = "SELECT order_id, \
+            SUM(qty) OVER (ORDER BY order_id ROWS BETWEEN 2 PRECEDING AND CURRENT ROW), \
+            ROW_NUMBER() OVER (PARTITION BY customer_id ORDER BY price DESC) \
+            FROM orders";
+        let expected = "Projection: #order_id, \
+            #SUM(qty) ORDER BY [order_id ASC NULLS FIRST] ROWS BETWEEN 2 PRECEDING AND CURRENT ROW, \
+            #ROW_NUMBER() PARTITION BY [customer_id] ORDER BY [price DESC NULLS FIRST]\
+            \n  WindowAggr: windowExpr=[[\
+            SUM(#qty) ORDER BY [#order_id ASC NULLS FIRST] ROWS BETWEEN 2 PRECEDING AND CURRENT ROW, \
+            ROW_NUMBER() PARTITION BY [#customer_id] ORDER BY [#price DESC NULLS FIRST]]]\
+            \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn over_lag_with_offset_and_default() {
+        let sql = "SELECT order_id, LAG(qty, 2, 0) OVER (ORDER BY order_id) AS prev_qty FROM orders";
+        let expected = "Projection: #order_id, \
+            #LAG(qty,Int64(2),Int64(0)) ORDER BY [order_id ASC NULLS FIRST] AS prev_qty\
+            \n  WindowAggr: windowExpr=[[\
+            LAG(#qty, Int64(2), Int64(0)) ORDER BY [#order_id ASC NULLS FIRST]]]\
+            \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn over_invalid_window_frame() {
+        let sql = "SELECT order_id, \
+            MAX(qty) OVER (ORDER BY order_id ROWS BETWEEN 1 FOLLOWING AND 1 PRECEDING) \
+            FROM orders";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Invalid window frame: start bound (1 FOLLOWING) cannot be larger than end bound (1 PRECEDING)\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn over_distinct_not_supported() {
+        let sql = "SELECT order_id, COUNT(DISTINCT qty) OVER () FROM orders";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "NotImplemented(\"DISTINCT is not supported in window function COUNT\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn in_subquery() {
+        let sql = "SELECT order_id FROM orders \
+            WHERE customer_id IN (SELECT id FROM person WHERE age > 21)";
+        let expected = "Projection: #order_id\
+            \n  Filter: #customer_id IN (<subquery>)\
+            \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn not_in_subquery() {
+        let sql = "SELECT order_id FROM orders \
+            WHERE customer_id NOT IN (SELECT id FROM person) AND qty > 1";
+        let expected = "Projection: #order_id\
+            \n  Filter: #customer_id NOT IN (<subquery>) And #qty Gt Int64(1)\
+            \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn correlated_exists_subquery() {
+        let sql = "SELECT id FROM person \
+            WHERE EXISTS (SELECT * FROM orders WHERE customer_id = id)";
+        let expected = "Projection: #id\
+            \n  Filter: EXISTS (<subquery>)\
+            \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        // the subquery references the column of the enclosing query
+        let plan = logical_plan(sql).unwrap();
+        let subquery = match plan.inputs()[0] {
+            LogicalPlan::Filter {
+                predicate: Expr::Exists { subquery, .. },
+                ..
+            } => subquery.subquery.clone(),
+            _ => panic!("expected an EXISTS filter"),
+        };
+        let expected = "Filter: #customer_id Eq #id\
+            \n  TableScan: orders projection=None";
+        assert_eq!(expected, format!("{:?}", subquery));
+    }
+
+    #[test]
+    fn scalar_subquery() {
+        let sql = "SELECT id, (SELECT MAX(qty) FROM orders WHERE customer_id = id) FROM person \
+            WHERE age > (SELECT AVG(age) FROM person)";
+        let expected = "Projection: #id, (<subquery>)\
+            \n  Filter: #age Gt (<subquery>)\
+            \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn subquery_with_multiple_columns() {
+        let sql = "SELECT id FROM person WHERE id IN (SELECT order_id, qty FROM orders)";
         let err = logical_plan(sql).expect_err("query should have failed");
         assert_eq!(
-            "NotImplemented(\"Only UNION ALL is supported, found EXCEPT\")",
+            "Plan(\"Subquery used as an expression must return a single column, found 2\")",
             format!("{:?}", err)
         );
     }
 
+    #[test]
+    fn subquery_unknown_column() {
+        let sql =
+            "SELECT id FROM person WHERE EXISTS (SELECT * FROM orders WHERE x = id)";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert!(matches!(err, DataFusionError::Plan(_)));
+    }
+
     #[test]
     fn select_typedstring() {
         let sql = "SELECT date '2020-12-10' AS date FROM person";
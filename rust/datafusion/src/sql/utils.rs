@@ -15,11 +15,15 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::logical_plan::{DFSchema, Expr, LogicalPlan};
+use crate::logical_plan::{
+    col, lit, DFSchema, Expr, GroupingSet, LogicalPlan, GROUPING_ID_COLUMN,
+};
+use crate::physical_plan::aggregates::AggregateFunction;
 use crate::{
     error::{DataFusionError, Result},
     logical_plan::{ExpressionVisitor, Recursion},
 };
+use arrow::datatypes::DataType;
 use std::collections::HashMap;
 
 /// Resolves an `Expr::Wildcard` to a collection of `Expr::Column`'s.
@@ -46,6 +50,14 @@ pub(crate) fn find_aggregate_exprs(exprs: &[Expr]) -> Vec<Expr> {
     })
 }
 
+/// Collect all deeply nested `Expr::WindowFunction`. They are returned in order
+/// of occurrence (depth first), with duplicates omitted.
+pub(crate) fn find_window_exprs(exprs: &[Expr]) -> Vec<Expr> {
+    find_exprs_in_exprs(exprs, &|nested_expr| {
+        matches!(nested_expr, Expr::WindowFunction { .. })
+    })
+}
+
 /// Collect all deeply nested `Expr::Column`'s. They are returned in order of
 /// appearance (depth first), with duplicates omitted.
 pub(crate) fn find_column_exprs(exprs: &[Expr]) -> Vec<Expr> {
@@ -159,6 +171,77 @@ pub(crate) fn rebase_expr(
     })
 }
 
+/// Returns the grouping sets of `grouping_set` as lists of expressions.
+pub(crate) fn grouping_set_exprs(grouping_set: &GroupingSet) -> Result<Vec<Vec<Expr>>> {
+    let distinct = grouping_set.distinct_expr();
+    Ok(grouping_set
+        .sets()?
+        .into_iter()
+        .map(|set| set.into_iter().map(|i| distinct[i].clone()).collect())
+        .collect())
+}
+
+/// Rebuilds an `Expr` with the calls of `GROUPING(args)` replaced by their
+/// value, computed from the grouping id column of the aggregate grouped by
+/// `group_by_exprs`.
+///
+/// The bit `m - 1 - j` of the value, where `m` is the number of arguments, is
+/// set when the argument `j` is not part of the grouping set of the group.
+/// It is always zero without grouping sets.
+pub(crate) fn resolve_grouping_function(
+    expr: &Expr,
+    group_by_exprs: &[Expr],
+    plan: &LogicalPlan,
+) -> Result<Expr> {
+    let (grouping_exprs, has_grouping_sets) = match group_by_exprs {
+        [Expr::GroupingSet(grouping_set)] => (grouping_set.distinct_expr(), true),
+        _ => (group_by_exprs.to_vec(), false),
+    };
+    let grouping_id = Expr::Cast {
+        expr: Box::new(col(GROUPING_ID_COLUMN)),
+        data_type: DataType::UInt64,
+    };
+
+    clone_with_replacement(expr, &|nested_expr| match nested_expr {
+        Expr::AggregateFunction {
+            fun: AggregateFunction::Grouping,
+            args,
+            ..
+        } => {
+            if args.len() > 31 {
+                return Err(DataFusionError::Plan(
+                    "GROUPING supports at most 31 arguments".to_string(),
+                ));
+            }
+            let mut value = lit(0_u64);
+            for (j, arg) in args.iter().enumerate() {
+                let position = grouping_exprs.iter().position(|e| e == arg);
+                let i = position.ok_or_else(|| {
+                    DataFusionError::Plan(format!(
+                        "Argument {:?} of GROUPING is not a grouping expression",
+                        arg
+                    ))
+                })?;
+                if has_grouping_sets {
+                    // (id >> k) & 1 == (id / 2^k) - (id / 2^(k + 1)) * 2
+                    let k = grouping_exprs.len() - 1 - i;
+                    let shift = |k: usize| grouping_id.clone() / lit(1_u64 << k);
+                    let bit = shift(k) - shift(k + 1) * lit(2_u64);
+                    value = value + bit * lit(1_u64 << (args.len() - 1 - j));
+                }
+            }
+            Ok(Some(Expr::Alias(
+                Box::new(Expr::Cast {
+                    expr: Box::new(value),
+                    data_type: DataType::Int32,
+                }),
+                nested_expr.name(plan.schema())?,
+            )))
+        }
+        _ => Ok(None),
+    })
+}
+
 /// Determines if the set of `Expr`'s are a valid projection on the input
 /// `Expr::Column`'s.
 pub(crate) fn can_columns_satisfy_exprs(
@@ -217,6 +300,28 @@ where
                     .collect::<Result<Vec<Expr>>>()?,
                 distinct: *distinct,
             }),
+            Expr::WindowFunction {
+                fun,
+                args,
+                partition_by,
+                order_by,
+                window_frame,
+            } => Ok(Expr::WindowFunction {
+                fun: fun.clone(),
+                args: args
+                    .iter()
+                    .map(|e| clone_with_replacement(e, replacement_fn))
+                    .collect::<Result<Vec<_>>>()?,
+                partition_by: partition_by
+                    .iter()
+                    .map(|e| clone_with_replacement(e, replacement_fn))
+                    .collect::<Result<Vec<_>>>()?,
+                order_by: order_by
+                    .iter()
+                    .map(|e| clone_with_replacement(e, replacement_fn))
+                    .collect::<Result<Vec<_>>>()?,
+                window_frame: *window_frame,
+            }),
             Expr::AggregateUDF { fun, args } => Ok(Expr::AggregateUDF {
                 fun: fun.clone(),
                 args: args
@@ -335,9 +440,29 @@ where
                 asc: *asc,
                 nulls_first: *nulls_first,
             }),
-            Expr::Column(_) | Expr::Literal(_) | Expr::ScalarVariable(_) => {
-                Ok(expr.clone())
-            }
+            Expr::InSubquery {
+                expr: nested_expr,
+                subquery,
+                negated,
+            } => Ok(Expr::InSubquery {
+                expr: Box::new(clone_with_replacement(&**nested_expr, replacement_fn)?),
+                subquery: subquery.clone(),
+                negated: *negated,
+            }),
+            Expr::Column(_)
+            | Expr::Literal(_)
+            | Expr::ScalarVariable(_)
+            | Expr::Exists { .. }
+            | Expr::ScalarSubquery(_) => Ok(expr.clone()),
+            Expr::GroupingSet(grouping_set) => Ok(Expr::GroupingSet(
+                grouping_set.with_new_exprs(
+                    grouping_set
+                        .exprs()
+                        .into_iter()
+                        .map(|e| clone_with_replacement(e, replacement_fn))
+                        .collect::<Result<Vec<Expr>>>()?,
+                ),
+            )),
             Expr::Wildcard => Ok(Expr::Wildcard),
         },
     }
@@ -19,11 +19,12 @@ use std::sync::Arc;
 
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::{
-    array::{Int32Array, StringArray},
+    array::{Int32Array, ListArray, StringArray},
     record_batch::RecordBatch,
 };
 
 use datafusion::error::Result;
+use datafusion::logical_plan::{array_agg, col};
 use datafusion::{datasource::MemTable, prelude::JoinType};
 
 use datafusion::execution::context::ExecutionContext;
@@ -77,3 +78,42 @@ async fn join() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn array_agg_ordered() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Utf8, false),
+        Field::new("b", DataType::Int32, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec!["p", "q", "r", "s"])),
+            Arc::new(Int32Array::from(vec![2, 5, 3, 1])),
+        ],
+    )?;
+
+    let mut ctx = ExecutionContext::new();
+    let table = MemTable::try_new(schema, vec![vec![batch]])?;
+    ctx.register_table("t", Arc::new(table))?;
+
+    let df = ctx.table("t")?.aggregate(
+        vec![],
+        vec![array_agg(col("a"), vec![col("b").sort(false, false)])],
+    )?;
+    let batches = df.collect().await?;
+    assert_eq!(batches.len(), 1);
+
+    let list = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    let values = list.value(0);
+    let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(
+        values.iter().collect::<Vec<_>>(),
+        vec![Some("q"), Some("r"), Some("p"), Some("s")]
+    );
+    Ok(())
+}
@@ -31,11 +31,14 @@ use arrow::{
     util::display::array_value_to_string,
 };
 
-use datafusion::execution::context::ExecutionContext;
+use datafusion::execution::context::{ExecutionConfig, ExecutionContext};
 use datafusion::logical_plan::LogicalPlan;
 use datafusion::prelude::create_udf;
 use datafusion::{
-    datasource::{csv::CsvReadOptions, MemTable},
+    datasource::{
+        csv::CsvReadOptions, FileFormat, ListingOptions, ListingTable, MemTable,
+        NdJsonReadOptions,
+    },
     physical_plan::collect,
 };
 use datafusion::{
@@ -793,6 +796,98 @@ async fn csv_union_all() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn union_distinct() -> Result<()> {
+    let mut ctx = create_set_context()?;
+    let sql = "SELECT a, b FROM t1 UNION SELECT a, b FROM t2 ORDER BY a";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["1", "x"],
+        vec!["2", "NULL"],
+        vec!["3", "z"],
+        vec!["4", "w"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn intersect() -> Result<()> {
+    let mut ctx = create_set_context()?;
+    let sql = "SELECT a, b FROM t1 INTERSECT SELECT a, b FROM t2 ORDER BY a";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["1", "x"], vec!["2", "NULL"]];
+    assert_eq!(expected, actual);
+
+    let sql =
+        "SELECT a, b FROM t1 INTERSECT ALL SELECT a, b FROM t1 WHERE a < 3 ORDER BY a";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["1", "x"],
+        vec!["1", "x"],
+        vec!["2", "NULL"],
+        vec!["2", "NULL"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT a, b FROM t1 INTERSECT ALL SELECT a, b FROM t2 ORDER BY a";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["1", "x"], vec!["2", "NULL"]];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn except() -> Result<()> {
+    let mut ctx = create_set_context()?;
+    let sql = "SELECT a, b FROM t1 EXCEPT SELECT a, b FROM t2 ORDER BY a";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["3", "z"]];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT a, b FROM t1 EXCEPT ALL SELECT a, b FROM t2 ORDER BY a";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["1", "x"], vec!["2", "NULL"], vec!["3", "z"]];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+fn create_set_context() -> Result<ExecutionContext> {
+    let mut ctx = ExecutionContext::new();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Utf8, true),
+    ]));
+    let t1_data = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 1, 2, 2, 3])),
+            Arc::new(StringArray::from(vec![
+                Some("x"),
+                Some("x"),
+                None,
+                None,
+                Some("z"),
+            ])),
+        ],
+    )?;
+    let t1_table = MemTable::try_new(schema.clone(), vec![vec![t1_data]])?;
+    ctx.register_table("t1", Arc::new(t1_table))?;
+
+    let t2_data = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 4])),
+            Arc::new(StringArray::from(vec![Some("x"), None, Some("w")])),
+        ],
+    )?;
+    let t2_table = MemTable::try_new(schema, vec![vec![t2_data]])?;
+    ctx.register_table("t2", Arc::new(t2_table))?;
+
+    Ok(ctx)
+}
+
 #[tokio::test]
 async fn csv_query_limit() -> Result<()> {
     let mut ctx = ExecutionContext::new();
@@ -1239,6 +1334,69 @@ async fn left_join_using() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn full_join() -> Result<()> {
+    let mut ctx = create_join_context("t1_id", "t2_id")?;
+    let sql = "SELECT t1_id, t1_name, t2_name FROM t1 FULL OUTER JOIN t2 ON t1_id = t2_id ORDER BY t1_id";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["NULL", "NULL", "w"],
+        vec!["11", "a", "z"],
+        vec!["22", "b", "y"],
+        vec!["33", "c", "NULL"],
+        vec!["44", "d", "x"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn full_join_using() -> Result<()> {
+    let mut ctx = create_join_context("id", "id")?;
+    let sql = "SELECT id, t1_name, t2_name FROM t1 FULL JOIN t2 USING (id) ORDER BY id";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["11", "a", "z"],
+        vec!["22", "b", "y"],
+        vec!["33", "c", "NULL"],
+        vec!["44", "d", "x"],
+        vec!["55", "NULL", "w"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn sort_merge_join() -> Result<()> {
+    // the build side of every join is too large for a hash join
+    let mut ctx = create_join_context_with_config(
+        "id",
+        "id",
+        ExecutionConfig::new().with_sort_merge_join_rows(0),
+    )?;
+    let sql = "SELECT id, t1_name, t2_name FROM t1 FULL JOIN t2 USING (id) ORDER BY id";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let plan = ctx.create_physical_plan(&plan)?;
+    assert!(format!("{:?}", plan).contains("SortMergeJoinExec"));
+
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["11", "a", "z"],
+        vec!["22", "b", "y"],
+        vec!["33", "c", "NULL"],
+        vec!["44", "d", "x"],
+        vec!["55", "NULL", "w"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql =
+        "SELECT id, t1_name FROM t1 WHERE id NOT IN (SELECT id FROM t2) ORDER BY id";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["33", "c"]], actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn equijoin_implicit_syntax() -> Result<()> {
     let mut ctx = create_join_context("t1_id", "t2_id")?;
@@ -1290,12 +1448,101 @@ async fn equijoin_implicit_syntax_reversed() -> Result<()> {
 
 #[tokio::test]
 async fn cartesian_join() -> Result<()> {
-    let ctx = create_join_context("t1_id", "t2_id")?;
-    let sql = "SELECT t1_id, t1_name, t2_name FROM t1, t2 ORDER BY t1_id";
-    let maybe_plan = ctx.create_logical_plan(&sql);
-    assert_eq!(
-        "This feature is not implemented: Cartesian joins are not supported",
-        &format!("{}", maybe_plan.err().unwrap())
+    let mut ctx = create_join_context("t1_id", "t2_id")?;
+    let sql = "SELECT t1_id, t2_id FROM t1, t2 WHERE t1_id < 22 ORDER BY t1_id, t2_id";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["11", "11"],
+        vec!["11", "22"],
+        vec!["11", "44"],
+        vec!["11", "55"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn cross_join() -> Result<()> {
+    let mut ctx = create_join_context("t1_id", "t2_id")?;
+    let sql = "SELECT COUNT(*) FROM t1 CROSS JOIN t2";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["16"]], actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn nonequijoin() -> Result<()> {
+    let mut ctx = create_join_context("t1_id", "t2_id")?;
+    let sql = "SELECT t1_id, t2_id FROM t1 JOIN t2 ON t1_id > t2_id AND t2_id > 11 \
+               ORDER BY t1_id, t2_id";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["33", "22"], vec!["44", "22"]];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn left_join_with_condition() -> Result<()> {
+    let mut ctx = create_join_context("t1_id", "t2_id")?;
+    let sql = "SELECT t1_id, t1_name, t2_name FROM t1 LEFT JOIN t2 \
+               ON t1_id = t2_id AND t2_name <> 'y' ORDER BY t1_id";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["11", "a", "z"],
+        vec!["22", "b", "NULL"],
+        vec!["33", "c", "NULL"],
+        vec!["44", "d", "x"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn join_reorder_by_row_counts() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    let table = |columns: Vec<(&str, Vec<u32>)>| -> Result<Arc<MemTable>> {
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|(name, _)| Field::new(name, DataType::UInt32, false))
+                .collect(),
+        ));
+        let arrays = columns
+            .into_iter()
+            .map(|(_, values)| Arc::new(UInt32Array::from(values)) as ArrayRef)
+            .collect();
+        let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+        Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+    };
+    ctx.register_table(
+        "fact",
+        table(vec![
+            ("f_a", (0..20).collect()),
+            ("f_b", (0..20).map(|i| i % 5).collect()),
+        ])?,
+    )?;
+    ctx.register_table("a", table(vec![("a_id", (0..10).collect())])?)?;
+    ctx.register_table("b", table(vec![("b_id", vec![3, 4])])?)?;
+
+    let sql = "SELECT f_a, f_b FROM fact JOIN a ON f_a = a_id \
+               JOIN b ON f_b = b_id ORDER BY f_a";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["3", "3"],
+        vec!["4", "4"],
+        vec!["8", "3"],
+        vec!["9", "4"],
+    ];
+    assert_eq!(expected, actual);
+
+    // the few rows of b are joined first, and the result is joined with a
+    let plan = &execute(&mut ctx, &format!("EXPLAIN {}", sql)).await[0][1];
+    let outer = plan.find("Join: f_a = a_id");
+    let inner = plan.find("Join: b_id = f_b");
+    assert!(
+        outer.is_some() && inner.is_some() && outer < inner,
+        "{}",
+        plan
     );
     Ok(())
 }
@@ -1304,7 +1551,15 @@ fn create_join_context(
     column_left: &str,
     column_right: &str,
 ) -> Result<ExecutionContext> {
-    let mut ctx = ExecutionContext::new();
+    create_join_context_with_config(column_left, column_right, ExecutionConfig::new())
+}
+
+fn create_join_context_with_config(
+    column_left: &str,
+    column_right: &str,
+    config: ExecutionConfig,
+) -> Result<ExecutionContext> {
+    let mut ctx = ExecutionContext::with_config(config);
 
     let t1_schema = Arc::new(Schema::new(vec![
         Field::new(column_left, DataType::UInt32, true),
@@ -1423,6 +1678,41 @@ async fn csv_explain_verbose() {
     assert!(actual.contains("#c2 Gt Int64(10)"), "Actual: '{}'", actual);
 }
 
+#[tokio::test]
+async fn explain_analyze() -> Result<()> {
+    let mut ctx = create_join_context("t1_id", "t2_id")?;
+    let sql = "EXPLAIN ANALYZE SELECT t1_id, COUNT(t2_name) FROM t1 \
+               JOIN t2 ON t1_id = t2_id WHERE t1_name <> 'b' GROUP BY t1_id";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(1, actual.len());
+    assert_eq!("Plan with Metrics", actual[0][0]);
+
+    let plan = &actual[0][1];
+    assert!(
+        plan.contains("FilterExec: metrics=[elapsed_compute="),
+        "Actual: '{}'",
+        plan
+    );
+    // the filter reads the 4 rows of t1 and drops 1 of them
+    assert!(
+        plan.contains("input_rows=4, output_rows=3]"),
+        "Actual: '{}'",
+        plan
+    );
+    assert!(
+        plan.contains("HashJoinExec: metrics=[build_rows=3,"),
+        "Actual: '{}'",
+        plan
+    );
+    assert!(plan.contains("HashAggregateExec"), "Actual: '{}'", plan);
+
+    let sql = "EXPLAIN ANALYZE VERBOSE SELECT t1_id FROM t1 WHERE t1_id > 20";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec!["Output Rows", "3"], actual[1]);
+    assert_eq!("Duration", actual[2][0]);
+    Ok(())
+}
+
 fn aggr_test_schema() -> SchemaRef {
     Arc::new(Schema::new(vec![
         Field::new("c1", DataType::Utf8, false),
@@ -1866,6 +2156,62 @@ async fn query_count_distinct() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn query_with_memory_limit() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("c1", DataType::Int32, true),
+        Field::new("c2", DataType::Utf8, false),
+    ]));
+
+    let batches = (0..4)
+        .map(|i| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(vec![Some(i % 3), None, Some(2 - i)])),
+                    Arc::new(StringArray::from(vec!["a", "b", "c"])),
+                ],
+            )
+        })
+        .collect::<arrow::error::Result<Vec<_>>>()?;
+    let table = MemTable::try_new(schema, vec![batches])?;
+
+    // sorts and aggregations spill each batch to disk
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_memory_limit(1));
+    ctx.register_table("test", Arc::new(table))?;
+
+    let sql = "SELECT c1, c2 FROM test ORDER BY c1 DESC, c2";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["NULL", "b"],
+        vec!["NULL", "b"],
+        vec!["NULL", "b"],
+        vec!["NULL", "b"],
+        vec!["2", "a"],
+        vec!["2", "c"],
+        vec!["1", "a"],
+        vec!["1", "c"],
+        vec!["0", "a"],
+        vec!["0", "a"],
+        vec!["0", "c"],
+        vec!["-1", "c"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT c1, COUNT(c2), MIN(c2) FROM test GROUP BY c1 ORDER BY c1";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["NULL", "4", "b"],
+        vec!["-1", "1", "c"],
+        vec!["0", "3", "a"],
+        vec!["1", "2", "a"],
+        vec!["2", "2", "a"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn query_on_string_dictionary() -> Result<()> {
     // Test to ensure DataFusion can operate on dictionary types
@@ -2705,3 +3051,890 @@ async fn test_cast_expressions_error() -> Result<()> {
 
     Ok(())
 }
+
+fn create_decimal_context() -> Result<ExecutionContext> {
+    let mut ctx = ExecutionContext::new();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("account", DataType::Utf8, false),
+        Field::new("amount", DataType::Decimal(10, 2), true),
+    ]));
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec!["a", "a", "b", "b", "b"])),
+            Arc::new(DecimalArray::from_sparse_iter(
+                vec![Some(1005), Some(-250), Some(33), None, Some(100_000_001)],
+                10,
+                2,
+            )),
+        ],
+    )?;
+    let table = MemTable::try_new(schema, vec![vec![data]])?;
+    ctx.register_table("t", Arc::new(table))?;
+    Ok(ctx)
+}
+
+#[tokio::test]
+async fn decimal_aggregates() -> Result<()> {
+    let mut ctx = create_decimal_context()?;
+    let sql = "SELECT account, SUM(amount), AVG(amount), MIN(amount), MAX(amount) \
+               FROM t GROUP BY account ORDER BY account";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["a", "7.55", "3.775000", "-2.50", "10.05"],
+        vec!["b", "1000000.34", "500000.170000", "0.33", "1000000.01"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT SUM(amount), AVG(amount) FROM t";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["1000007.89", "250001.972500"]];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn decimal_arithmetic_and_comparison() -> Result<()> {
+    let mut ctx = create_decimal_context()?;
+    let sql = "SELECT amount, amount + CAST('0.10' AS DECIMAL(3, 2)), amount * 2, \
+               amount / 4 FROM t WHERE amount > 0 AND amount < 1000 ORDER BY amount";
+    let actual = execute(&mut ctx, sql).await;
+    // integer literals are coerced to Decimal(19, 0), which widens the result types
+    let expected = vec![
+        vec!["0.33", "0.43", "0.6600", "0.082500000000000000000000"],
+        vec!["10.05", "10.15", "20.1000", "2.512500000000000000000000"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT account FROM t WHERE amount = CAST('-2.5' AS DECIMAL(5, 1))";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["a"]], actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_decimal_cast_expressions() -> Result<()> {
+    test_expression!("CAST('1.255' AS DECIMAL(5, 2))", "1.26");
+    test_expression!("CAST(CAST('-12.345' AS DECIMAL(5, 3)) AS INT)", "-12");
+    test_expression!("CAST(CAST(12 AS DECIMAL(4, 1)) AS VARCHAR)", "12.0");
+    test_expression!("CAST(NULL AS DECIMAL(5, 2))", "NULL");
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_json() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    ctx.register_json("t", "tests/jsons/1.json", NdJsonReadOptions::new())?;
+    let sql = "SELECT d, COUNT(a), SUM(b) FROM t WHERE c = false GROUP BY d ORDER BY d";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["4", "1", "2"], vec!["text", "1", "0.6"]];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT a, d FROM t ORDER BY a LIMIT 2";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["-10", "4"], vec!["1", "4"]];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_external_table_json() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    // the schema is inferred when no columns are given
+    ctx.sql("CREATE EXTERNAL TABLE t STORED AS JSON LOCATION 'tests/jsons/1.json'")?;
+    let actual = execute(&mut ctx, "SELECT MAX(a), MIN(b) FROM t").await;
+    assert_eq!(vec![vec!["7", "-3.5"]], actual);
+
+    ctx.sql(
+        "CREATE EXTERNAL TABLE t2 (a BIGINT, d VARCHAR) \
+         STORED AS NDJSON LOCATION 'tests/jsons/1.json'",
+    )?;
+    let actual = execute(&mut ctx, "SELECT a FROM t2 WHERE d = 'text'").await;
+    assert_eq!(vec![vec!["2"]], actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_json() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    let df = ctx.read_json("tests/jsons/1.json", NdJsonReadOptions::new())?;
+    let results = df.select_columns(&["a", "c"])?.limit(3)?.collect().await?;
+    assert_eq!(3, results.iter().map(|b| b.num_rows()).sum::<usize>());
+    assert_eq!(2, results[0].num_columns());
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_partitioned_listing_table() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    for (path, content) in &[
+        ("date=2021-01-01/region=eu/1.csv", "a,b\n1,x\n2,y\n"),
+        ("date=2021-01-01/region=us/1.csv", "a,b\n3,z\n"),
+        ("date=2021-01-02/region=eu/1.csv", "a,b\n4,x\n"),
+        ("date=2021-01-02/region=us/1.csv", "a,b\n5,y\n"),
+    ] {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(path, content)?;
+    }
+
+    let mut ctx = ExecutionContext::new();
+    let options = ListingOptions::new(FileFormat::Csv {
+        has_header: true,
+        delimiter: b',',
+    });
+    let table = ListingTable::try_new(dir.path().to_str().unwrap(), options)?;
+    ctx.register_table("events", Arc::new(table))?;
+
+    let sql = "SELECT date, region, SUM(a) FROM events \
+               WHERE region = 'eu' GROUP BY date, region ORDER BY date";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["2021-01-01", "eu", "3"], vec!["2021-01-02", "eu", "4"]];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT COUNT(*) FROM events WHERE date > '2021-01-01' AND a > 4";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["1"]], actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_table_insert_and_view() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    ctx.sql("CREATE TABLE t (a INT NULL, b VARCHAR NULL)")?
+        .collect()
+        .await?;
+
+    let sql = "INSERT INTO t VALUES (1, 'x'), (2, 'y'), (NULL, 'z')";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["3"]], actual);
+
+    // columns that aren't listed are NULL
+    let sql = "INSERT INTO t (b) SELECT b FROM t WHERE a = 1";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["1"]], actual);
+
+    let actual = execute(&mut ctx, "SELECT a, b FROM t ORDER BY b, a").await;
+    let expected = vec![
+        vec!["NULL", "x"],
+        vec!["1", "x"],
+        vec!["2", "y"],
+        vec!["NULL", "z"],
+    ];
+    assert_eq!(expected, actual);
+
+    // the table is only registered once the rows of the query are inserted
+    let df =
+        ctx.sql("CREATE TABLE counts AS SELECT b, COUNT(a) AS c FROM t GROUP BY b")?;
+    assert!(ctx.table("counts").is_err());
+    df.collect().await?;
+    let df = ctx.sql("CREATE TABLE quotients AS SELECT a / 0 AS q FROM t")?;
+    assert!(df.collect().await.is_err());
+    assert!(ctx.table("quotients").is_err());
+
+    ctx.sql("CREATE VIEW v AS SELECT b FROM counts WHERE c > 0")?
+        .collect()
+        .await?;
+    let actual = execute(&mut ctx, "SELECT b FROM v ORDER BY b").await;
+    assert_eq!(vec![vec!["x"], vec!["y"]], actual);
+
+    // views see the rows inserted after they were created
+    execute(&mut ctx, "INSERT INTO counts VALUES ('w', 5)").await;
+    let actual = execute(&mut ctx, "SELECT b FROM v ORDER BY b").await;
+    assert_eq!(vec![vec!["w"], vec!["x"], vec!["y"]], actual);
+
+    // views can also be read through the DataFrame API
+    let results = ctx.table("v")?.collect().await?;
+    assert_eq!(3, results.iter().map(|b| b.num_rows()).sum::<usize>());
+
+    match ctx.sql("CREATE VIEW v AS SELECT 1") {
+        Err(e) => assert_eq!(
+            "Error during planning: Table or view 'v' already exists",
+            e.to_string()
+        ),
+        Ok(_) => panic!("CREATE VIEW should have failed"),
+    }
+    ctx.sql("CREATE OR REPLACE VIEW v AS SELECT b FROM counts WHERE c > 1")?;
+    let actual = execute(&mut ctx, "SELECT b FROM v").await;
+    assert_eq!(vec![vec!["w"]], actual);
+
+    assert!(matches!(
+        ctx.sql("INSERT INTO v VALUES ('a')"),
+        Err(DataFusionError::NotImplemented(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn view_planned_with_context_config() -> Result<()> {
+    // the limit of the view is too large for a top-k
+    let config = ExecutionConfig::new().with_top_k_rows(1);
+    let mut ctx = create_join_context_with_config("t1_id", "t2_id", config)?;
+    ctx.sql("CREATE VIEW v AS SELECT t1_id FROM t1 ORDER BY t1_id LIMIT 2")?
+        .collect()
+        .await?;
+
+    let df = ctx.table("v")?;
+    let plan = ctx.create_physical_plan(&ctx.optimize(&df.to_logical_plan())?)?;
+    let plan = format!("{:?}", plan);
+    assert!(plan.contains("SortExec"), "{}", plan);
+    assert!(!plan.contains("TopKExec"), "{}", plan);
+
+    let results = df.collect().await?;
+    assert_eq!(2, results.iter().map(|b| b.num_rows()).sum::<usize>());
+    Ok(())
+}
+
+#[tokio::test]
+async fn view_with_subqueries() -> Result<()> {
+    let mut ctx = create_join_context("t1_id", "t2_id")?;
+    ctx.sql(
+        "CREATE VIEW v AS SELECT t1_id, t1_name FROM t1 \
+         WHERE t1_id IN (SELECT t2_id FROM t2) \
+         AND EXISTS (SELECT t2_id FROM t2 WHERE t2_id = t1_id) \
+         AND t1_id < (SELECT MAX(t2_id) FROM t2)",
+    )?
+    .collect()
+    .await?;
+
+    let actual = execute(&mut ctx, "SELECT t1_id, t1_name FROM v ORDER BY t1_id").await;
+    let expected = vec![vec!["11", "a"], vec!["22", "b"], vec!["44", "d"]];
+    assert_eq!(expected, actual);
+
+    // the view is also decorrelated when it is scanned through the DataFrame API
+    let results = ctx.table("v")?.collect().await?;
+    assert_eq!(3, results.iter().map(|b| b.num_rows()).sum::<usize>());
+    Ok(())
+}
+
+async fn register_copy_source(ctx: &mut ExecutionContext) -> Result<()> {
+    ctx.sql("CREATE TABLE src (c1 VARCHAR NULL, c2 INT NULL)")?
+        .collect()
+        .await?;
+    let sql = "INSERT INTO src VALUES ('a', 1), ('b', 2), ('c', 3), ('d', 4), ('e', 5)";
+    ctx.sql(sql)?.collect().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn copy_to_files() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let out = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+
+    let mut ctx = ExecutionContext::new();
+    register_copy_source(&mut ctx).await?;
+
+    let sql = format!(
+        "COPY (SELECT c1, c2 FROM src WHERE c2 > 2) TO '{}' \
+         (FORMAT csv, HEADER false, DELIMITER '|')",
+        out("csv")
+    );
+    let actual = execute(&mut ctx, &sql).await;
+    assert_eq!(vec![vec!["3"]], actual);
+    let content = std::fs::read_to_string(out("csv/part-0.csv"))?;
+    assert_eq!("c|3\nd|4\ne|5\n", content);
+
+    let sql = format!("COPY src TO '{}' (COMPRESSION zstd)", out("copy.parquet"));
+    ctx.sql(&sql)?.collect().await?;
+    ctx.register_parquet("copy", &out("copy.parquet"))?;
+    let actual = execute(&mut ctx, "SELECT COUNT(*), SUM(c2) FROM copy").await;
+    assert_eq!(vec![vec!["5", "15"]], actual);
+
+    let sql = format!(
+        "COPY copy TO '{}' (FORMAT json, COMPRESSION gzip)",
+        out("json")
+    );
+    ctx.sql(&sql)?.collect().await?;
+    assert!(dir.path().join("json/part-0.json.gz").exists());
+
+    // the target directory must not exist
+    let sql = format!("COPY src TO '{}' (FORMAT csv)", out("csv"));
+    assert!(ctx.sql(&sql)?.collect().await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn dataframe_write_files() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let out = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+
+    let mut ctx = ExecutionContext::new();
+    register_copy_source(&mut ctx).await?;
+    let df = ctx.sql("SELECT c1, c2 FROM src")?;
+    df.write_csv(&out("csv")).await?;
+    df.write_json(&out("json")).await?;
+    df.write_parquet(&out("parquet"), None).await?;
+
+    let schema = df.schema().clone().into();
+    let options = CsvReadOptions::new().has_header(true).schema(&schema);
+    ctx.register_csv("from_csv", &out("csv"), options)?;
+    ctx.register_json("from_json", &out("json"), NdJsonReadOptions::new())?;
+    ctx.register_parquet("from_parquet", &out("parquet"))?;
+    for table in &["from_csv", "from_json", "from_parquet"] {
+        let sql = format!("SELECT COUNT(*), SUM(c2), MIN(c1) FROM {}", table);
+        let actual = execute(&mut ctx, &sql).await;
+        assert_eq!(vec![vec!["5", "15", "a"]], actual, "{}", table);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn common_subexpressions() -> Result<()> {
+    let mut ctx = create_join_context("t1_id", "t2_id")?;
+
+    let sql = "SELECT t1_id, t1_id + 1, (t1_id + 1) * 2 FROM t1 \
+               WHERE t1_id + 1 > 20 AND t1_id + 1 < 40 ORDER BY t1_id";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["22", "23", "46"], vec!["33", "34", "68"]];
+    assert_eq!(expected, actual);
+
+    // `t1_id + 1` is computed once below the filter and once below the projection
+    let plan = &execute(&mut ctx, &format!("EXPLAIN {}", sql)).await[0][1];
+    let shared = "#t1_id Plus Int64(1) AS t1_id Plus Int64(1)";
+    assert_eq!(2, plan.matches(shared).count(), "{}", plan);
+    assert!(
+        plan.contains("Filter: #t1_id Plus Int64(1) Gt Int64(20) And #t1_id Plus Int64(1) Lt Int64(40)"),
+        "{}",
+        plan
+    );
+
+    let sql = "SELECT t1_id % 2, SUM(t1_id % 2 + t1_id) FROM t1 GROUP BY t1_id % 2";
+    let mut actual = execute(&mut ctx, sql).await;
+    actual.sort();
+    let expected = vec![vec!["0", "66"], vec!["1", "46"]];
+    assert_eq!(expected, actual);
+
+    let plan = &execute(&mut ctx, &format!("EXPLAIN {}", sql)).await[0][1];
+    assert!(
+        plan.contains("#t1_id Modulus Int64(2) AS t1_id Modulus Int64(2)"),
+        "{}",
+        plan
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn simplify_expressions() -> Result<()> {
+    let mut ctx = create_join_context("t1_id", "t2_id")?;
+
+    let sql = "SELECT t1_id, 1 + 2, concat('a', 'b') FROM t1 \
+               WHERE 1 + 1 < t1_id AND t1_id BETWEEN 1 AND 40 \
+               AND NOT NOT t1_id IS NOT NULL ORDER BY t1_id";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["11", "3", "ab"],
+        vec!["22", "3", "ab"],
+        vec!["33", "3", "ab"],
+    ];
+    assert_eq!(expected, actual);
+
+    let plan = &execute(&mut ctx, &format!("EXPLAIN {}", sql)).await[0][1];
+    let expected = vec![
+        "Projection: #t1_id, Int64(3) AS Int64(1) Plus Int64(2), \
+         Utf8(\"ab\") AS concat(Utf8(\"a\"),Utf8(\"b\"))",
+        "Filter: #t1_id Gt Int64(2) And #t1_id GtEq Int64(1) \
+         And #t1_id LtEq Int64(40) And #t1_id IS NOT NULL",
+        "TableScan: t1 projection=Some([0])",
+    ];
+    for line in expected {
+        assert!(plan.contains(line), "{}", plan);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn top_k() -> Result<()> {
+    let config = ExecutionConfig::new().with_concurrency(4);
+    let mut ctx = create_join_context_with_config("t1_id", "t2_id", config)?;
+    assert_top_k(&mut ctx, "TopKExec").await;
+
+    // more rows than the configured limit are sorted instead
+    let config = ExecutionConfig::new()
+        .with_concurrency(4)
+        .with_top_k_rows(2);
+    let mut ctx = create_join_context_with_config("t1_id", "t2_id", config)?;
+    assert_top_k(&mut ctx, "SortExec").await;
+    Ok(())
+}
+
+async fn assert_top_k(ctx: &mut ExecutionContext, expected_plan: &str) {
+    let sql = "SELECT t1_id, t1_name FROM t1 ORDER BY t1_id DESC NULLS LAST LIMIT 3";
+    let actual = execute(ctx, sql).await;
+    let expected = vec![vec!["44", "d"], vec!["33", "c"], vec!["22", "b"]];
+    assert_eq!(expected, actual);
+
+    let actual = execute(ctx, &format!("EXPLAIN VERBOSE {}", sql)).await;
+    let plan = actual
+        .iter()
+        .find(|row| row[0] == "physical_plan")
+        .map(|row| &row[1])
+        .unwrap();
+    assert!(plan.contains(expected_plan), "{}", plan);
+
+    let sql = "SELECT t2_name FROM t2 ORDER BY t2_name LIMIT 2";
+    let actual = execute(ctx, sql).await;
+    let expected = vec![vec!["w"], vec!["x"]];
+    assert_eq!(expected, actual);
+}
+
+#[tokio::test]
+async fn top_k_with_nans_and_nulls() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Float64, true),
+        Field::new("b", DataType::Utf8, false),
+    ]));
+    let partition = |a: Vec<Option<f64>>, b: Vec<&str>| -> Result<Vec<RecordBatch>> {
+        Ok(vec![RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Float64Array::from(a)),
+                Arc::new(StringArray::from(b)),
+            ],
+        )?])
+    };
+    let partitions = vec![
+        partition(vec![Some(f64::NAN), None, Some(1.5)], vec!["a", "b", "c"])?,
+        partition(vec![Some(-2.0), Some(f64::NAN)], vec!["d", "e"])?,
+        partition(vec![None, Some(0.0), Some(1.5)], vec!["f", "g", "h"])?,
+    ];
+
+    // the top-k rows are the first rows of the plain sort
+    let mut top_k =
+        ExecutionContext::with_config(ExecutionConfig::new().with_concurrency(4));
+    top_k.register_table(
+        "t",
+        Arc::new(MemTable::try_new(schema.clone(), partitions.clone())?),
+    )?;
+    let mut sort = ExecutionContext::with_config(
+        ExecutionConfig::new()
+            .with_concurrency(4)
+            .with_top_k_rows(0),
+    );
+    sort.register_table("t", Arc::new(MemTable::try_new(schema, partitions)?))?;
+
+    for order_by in &[
+        "a, b",
+        "a DESC, b",
+        "a NULLS FIRST, b DESC",
+        "a DESC NULLS LAST, b",
+    ] {
+        let sql = format!("SELECT a, b FROM t ORDER BY {}", order_by);
+        let sorted = execute(&mut sort, &sql).await;
+        for k in 1..=sorted.len() {
+            let sql = format!("{} LIMIT {}", sql, k);
+            let plan = top_k.create_logical_plan(&sql)?;
+            let plan = top_k.create_physical_plan(&top_k.optimize(&plan)?)?;
+            assert!(format!("{:?}", plan).contains("TopKExec"));
+
+            let actual = execute(&mut top_k, &sql).await;
+            assert_eq!(sorted[..k].to_vec(), actual, "{}", sql);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn sort_partitioned_table() -> Result<()> {
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_concurrency(4));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Utf8, false),
+    ]));
+    let partition = |a: Vec<Option<i32>>, b: Vec<&str>| -> Result<Vec<RecordBatch>> {
+        Ok(vec![RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(a)),
+                Arc::new(StringArray::from(b)),
+            ],
+        )?])
+    };
+    let table = MemTable::try_new(
+        schema.clone(),
+        vec![
+            partition(vec![Some(3), None, Some(1)], vec!["a", "b", "c"])?,
+            partition(vec![Some(2), Some(3)], vec!["d", "e"])?,
+            partition(vec![Some(0)], vec!["f"])?,
+        ],
+    )?;
+    ctx.register_table("t", Arc::new(table))?;
+
+    let sql = "SELECT a, b FROM t ORDER BY a DESC NULLS FIRST, b";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.create_physical_plan(&ctx.optimize(&plan)?)?;
+    assert!(format!("{:?}", plan).contains("SortPreservingMergeExec"));
+
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["NULL", "b"],
+        vec!["3", "a"],
+        vec!["3", "e"],
+        vec!["2", "d"],
+        vec!["1", "c"],
+        vec!["0", "f"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn statistical_aggregates() -> Result<()> {
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_concurrency(4));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("g", DataType::Utf8, false),
+        Field::new("x", DataType::Int32, true),
+        Field::new("y", DataType::Float64, true),
+    ]));
+    let partition =
+        |g: Vec<&str>, x: Vec<Option<i32>>, y: Vec<f64>| -> Result<Vec<RecordBatch>> {
+            Ok(vec![RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(StringArray::from(g)),
+                    Arc::new(Int32Array::from(x)),
+                    Arc::new(Float64Array::from(y)),
+                ],
+            )?])
+        };
+    // the states of every group are merged from several partitions
+    let table = MemTable::try_new(
+        schema.clone(),
+        vec![
+            partition(
+                vec!["a", "a", "b"],
+                vec![Some(1), Some(2), Some(10)],
+                vec![2.0, 4.0, 5.0],
+            )?,
+            partition(
+                vec!["a", "a", "b"],
+                vec![Some(3), Some(4), None],
+                vec![6.0, 8.0, 7.0],
+            )?,
+            partition(vec!["b"], vec![Some(20)], vec![1.0])?,
+        ],
+    )?;
+    ctx.register_table("t", Arc::new(table))?;
+
+    let sql = "SELECT g, var_samp(x), var_pop(x), stddev(x), stddev_pop(x), \
+               covar_samp(x, y), covar_pop(x, y), corr(x, y) \
+               FROM t GROUP BY g ORDER BY g";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec![
+            "a",
+            "1.6666666666666667",
+            "1.25",
+            "1.2909944487358056",
+            "1.118033988749895",
+            "3.3333333333333335",
+            "2.5",
+            "1",
+        ],
+        vec![
+            "b",
+            "50",
+            "25",
+            "7.0710678118654755",
+            "5",
+            "-20",
+            "-10",
+            "-1",
+        ],
+    ];
+    assert_eq!(expected, actual);
+
+    // sample statistics of a single value are null
+    let sql = "SELECT stddev(x), var_pop(x), covar_samp(x, y), corr(x, y) \
+               FROM t WHERE y = 2";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["NULL", "0", "NULL", "NULL"]];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT var_samp(DISTINCT x) FROM t";
+    let plan = ctx.create_logical_plan(sql)?;
+    assert!(ctx.create_physical_plan(&ctx.optimize(&plan)?).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn approximate_aggregates() -> Result<()> {
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_concurrency(4));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("g", DataType::Utf8, false),
+        Field::new("x", DataType::Int32, true),
+    ]));
+    let partition = |g: Vec<&str>, x: Vec<Option<i32>>| -> Result<Vec<RecordBatch>> {
+        Ok(vec![RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(g)),
+                Arc::new(Int32Array::from(x)),
+            ],
+        )?])
+    };
+    // the sketches of every group are merged from several partitions
+    let table = MemTable::try_new(
+        schema.clone(),
+        vec![
+            partition(vec!["a", "a", "b"], vec![Some(1), Some(2), Some(10)])?,
+            partition(vec!["a", "a", "b"], vec![Some(4), Some(2), None])?,
+            partition(vec!["b", "a"], vec![Some(20), Some(3)])?,
+        ],
+    )?;
+    ctx.register_table("t", Arc::new(table))?;
+
+    let sql = "SELECT g, approx_distinct(x), approx_median(x), \
+               approx_percentile_cont(x, 0.25) FROM t GROUP BY g ORDER BY g";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["a", "4", "2", "2"], vec!["b", "2", "15", "12.5"]];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT approx_distinct(g), approx_percentile_cont(x, 1) FROM t";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["2", "20"]];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT approx_percentile_cont(x, x) FROM t";
+    let plan = ctx.create_logical_plan(sql)?;
+    assert!(ctx.create_physical_plan(&ctx.optimize(&plan)?).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn array_agg_string_agg() -> Result<()> {
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_concurrency(4));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("g", DataType::Utf8, false),
+        Field::new("x", DataType::Int32, true),
+        Field::new("s", DataType::Utf8, true),
+    ]));
+    let partition = |g: Vec<&str>,
+                     x: Vec<Option<i32>>,
+                     s: Vec<Option<&str>>|
+     -> Result<Vec<RecordBatch>> {
+        Ok(vec![RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(g)),
+                Arc::new(Int32Array::from(x)),
+                Arc::new(StringArray::from(s)),
+            ],
+        )?])
+    };
+    // the lists of every group are merged from several partitions
+    let table = MemTable::try_new(
+        schema.clone(),
+        vec![
+            partition(
+                vec!["a", "a", "b"],
+                vec![Some(3), Some(2), Some(10)],
+                vec![Some("q"), Some("p"), Some("r")],
+            )?,
+            partition(vec!["a", "b"], vec![Some(2), None], vec![Some("q"), None])?,
+            partition(
+                vec!["b", "a"],
+                vec![Some(20), Some(1)],
+                vec![Some("r"), Some("s")],
+            )?,
+        ],
+    )?;
+    ctx.register_table("t", Arc::new(table))?;
+
+    let sql = "SELECT g, array_agg(DISTINCT x), string_agg(DISTINCT s, '-') \
+               FROM t GROUP BY g ORDER BY g";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["a", "[1, 2, 3]", "p-q-s"],
+        vec!["b", "[10, 20, ]", "r"],
+    ];
+    assert_eq!(expected, actual);
+
+    // the order of the values is the one of a single partition
+    let sql = "SELECT array_agg(s), string_agg(x, ', ') FROM t WHERE g = 'b' AND x < 20";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["[r]", "10"]];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT array_agg(x), string_agg(s, '') FROM t WHERE x > 100";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["NULL", "NULL"]];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT string_agg(s, s) FROM t";
+    let plan = ctx.create_logical_plan(sql)?;
+    assert!(ctx.create_physical_plan(&ctx.optimize(&plan)?).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn grouping_sets() -> Result<()> {
+    let mut ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_concurrency(4));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Utf8, false),
+        Field::new("b", DataType::Int32, false),
+        Field::new("x", DataType::Int32, false),
+    ]));
+    let partition =
+        |a: Vec<&str>, b: Vec<i32>, x: Vec<i32>| -> Result<Vec<RecordBatch>> {
+            Ok(vec![RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(StringArray::from(a)),
+                    Arc::new(Int32Array::from(b)),
+                    Arc::new(Int32Array::from(x)),
+                ],
+            )?])
+        };
+    let table = MemTable::try_new(
+        schema.clone(),
+        vec![
+            partition(vec!["a", "a"], vec![1, 2], vec![1, 3])?,
+            partition(vec!["b", "a"], vec![1, 1], vec![4, 2])?,
+        ],
+    )?;
+    ctx.register_table("t", Arc::new(table))?;
+
+    let sql = "SELECT a, b, SUM(x), GROUPING(a, b) FROM t \
+               GROUP BY ROLLUP (a, b) ORDER BY a, b";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["NULL", "NULL", "10", "3"],
+        vec!["a", "NULL", "6", "1"],
+        vec!["a", "1", "3", "0"],
+        vec!["a", "2", "3", "0"],
+        vec!["b", "NULL", "4", "1"],
+        vec!["b", "1", "4", "0"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT b, SUM(x) FROM t GROUP BY CUBE (a, b) \
+               HAVING GROUPING(a) = 1 ORDER BY b";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["NULL", "10"], vec!["1", "7"], vec!["2", "3"]];
+    assert_eq!(expected, actual);
+
+    // the grouping sets of several items are their concatenations
+    let sql = "SELECT a, b, COUNT(*), GROUPING(a) FROM t \
+               GROUP BY a, GROUPING SETS ((b), ()) ORDER BY a, b";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["a", "NULL", "3", "0"],
+        vec!["a", "1", "2", "0"],
+        vec!["a", "2", "1", "0"],
+        vec!["b", "NULL", "1", "0"],
+        vec!["b", "1", "1", "0"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT a, GROUPING(a) FROM t GROUP BY a ORDER BY a";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["a", "0"], vec!["b", "0"]];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT GROUPING(b) FROM t GROUP BY ROLLUP (a)";
+    assert!(ctx.create_logical_plan(sql).is_err());
+    Ok(())
+}
+
+/// The values of a row of the table of `register_row_format_table`
+type RowFormatRow<'a> = (
+    Option<i64>,
+    Option<&'a str>,
+    Option<&'a [u8]>,
+    Option<i64>,
+    i32,
+);
+
+/// Registers a table `name` with a column of each of the types Date64, LargeUtf8,
+/// Binary and Timestamp with a time zone, whose names end with `suffix`, and the
+/// Int32 column `value`
+fn register_row_format_table(
+    ctx: &mut ExecutionContext,
+    name: &str,
+    suffix: &str,
+    value: &str,
+    rows: Vec<RowFormatRow>,
+) -> Result<()> {
+    let timestamp_type =
+        DataType::Timestamp(TimeUnit::Nanosecond, Some("+00:00".to_string()));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(&format!("d{}", suffix), DataType::Date64, true),
+        Field::new(&format!("s{}", suffix), DataType::LargeUtf8, true),
+        Field::new(&format!("b{}", suffix), DataType::Binary, true),
+        Field::new(&format!("ts{}", suffix), timestamp_type, true),
+        Field::new(value, DataType::Int32, false),
+    ]));
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(rows.iter().map(|r| r.0).collect::<Date64Array>()),
+            Arc::new(rows.iter().map(|r| r.1).collect::<LargeStringArray>()),
+            Arc::new(BinaryArray::from(
+                rows.iter().map(|r| r.2).collect::<Vec<_>>(),
+            )),
+            Arc::new(TimestampNanosecondArray::from_opt_vec(
+                rows.iter().map(|r| r.3).collect(),
+                Some("+00:00".to_string()),
+            )),
+            Arc::new(Int32Array::from(
+                rows.iter().map(|r| r.4).collect::<Vec<_>>(),
+            )),
+        ],
+    )?;
+    let table = MemTable::try_new(schema, vec![vec![data]])?;
+    ctx.register_table(name, Arc::new(table))?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn group_by_and_join_on_row_format_types() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    register_row_format_table(
+        &mut ctx,
+        "r1",
+        "1",
+        "x",
+        vec![
+            (Some(1), Some("a"), Some(b"x".as_ref()), Some(10), 1),
+            (Some(1), Some("a"), Some(b"x".as_ref()), Some(10), 2),
+            (Some(2), Some("a"), Some(b"x".as_ref()), Some(10), 4),
+            (Some(1), Some("a"), Some(b"y".as_ref()), Some(10), 8),
+            (None, None, None, None, 16),
+            (None, None, None, None, 32),
+        ],
+    )?;
+    register_row_format_table(
+        &mut ctx,
+        "r2",
+        "2",
+        "y",
+        vec![
+            (Some(1), Some("a"), Some(b"x".as_ref()), Some(10), 100),
+            (Some(1), Some("a"), Some(b"y".as_ref()), Some(10), 200),
+            (None, None, None, None, 300),
+        ],
+    )?;
+
+    let sql = "SELECT s1, SUM(x) AS total FROM r1 \
+               GROUP BY d1, s1, b1, ts1 ORDER BY total";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["a", "3"],
+        vec!["a", "4"],
+        vec!["a", "8"],
+        vec!["NULL", "48"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT x, y FROM r1 JOIN r2 \
+               ON d1 = d2 AND s1 = s2 AND b1 = b2 AND ts1 = ts2 ORDER BY x";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["1", "100"], vec!["2", "100"], vec!["8", "200"]];
+    assert_eq!(expected, actual);
+    Ok(())
+}
@@ -209,19 +209,31 @@ fn write_leaf(
     let indices = levels.filter_array_indices();
     let written = match writer {
         ColumnWriter::Int32ColumnWriter(ref mut typed) => {
-            // If the column is a Date64, we cast it to a Date32, and then interpret that as Int32
-            let array = if let ArrowDataType::Date64 = column.data_type() {
-                let array = arrow::compute::cast(column, &ArrowDataType::Date32)?;
-                arrow::compute::cast(&array, &ArrowDataType::Int32)?
-            } else {
-                arrow::compute::cast(column, &ArrowDataType::Int32)?
+            let values = match column.data_type() {
+                // Decimals of a precision up to 9 are written as their unscaled values
+                ArrowDataType::Decimal(_, _) => {
+                    get_decimal_unscaled_slice(column, &indices)
+                        .into_iter()
+                        .map(|value| value as i32)
+                        .collect()
+                }
+                _ => {
+                    // If the column is a Date64, we cast it to a Date32, and then interpret that as Int32
+                    let array = if let ArrowDataType::Date64 = column.data_type() {
+                        let array = arrow::compute::cast(column, &ArrowDataType::Date32)?;
+                        arrow::compute::cast(&array, &ArrowDataType::Int32)?
+                    } else {
+                        arrow::compute::cast(column, &ArrowDataType::Int32)?
+                    };
+                    let array = array
+                        .as_any()
+                        .downcast_ref::<arrow_array::Int32Array>()
+                        .expect("Unable to get int32 array");
+                    get_numeric_array_slice::<Int32Type, _>(array, &indices)
+                }
             };
-            let array = array
-                .as_any()
-                .downcast_ref::<arrow_array::Int32Array>()
-                .expect("Unable to get int32 array");
             typed.write_batch(
-                get_numeric_array_slice::<Int32Type, _>(&array, &indices).as_slice(),
+                values.as_slice(),
                 Some(levels.definition.as_slice()),
                 levels.repetition.as_deref(),
             )?
@@ -246,6 +258,13 @@ fn write_leaf(
                         .expect("Unable to get i64 array");
                     get_numeric_array_slice::<Int64Type, _>(&array, &indices)
                 }
+                // Decimals of a precision up to 18 are written as their unscaled values
+                ArrowDataType::Decimal(_, _) => {
+                    get_decimal_unscaled_slice(column, &indices)
+                        .into_iter()
+                        .map(|value| value as i64)
+                        .collect()
+                }
                 _ => {
                     let array = arrow::compute::cast(column, &ArrowDataType::Int64)?;
                     let array = array
@@ -422,6 +441,17 @@ where
     values
 }
 
+fn get_decimal_unscaled_slice(
+    column: &arrow_array::ArrayRef,
+    indices: &[usize],
+) -> Vec<i128> {
+    let array = column
+        .as_any()
+        .downcast_ref::<arrow_array::DecimalArray>()
+        .expect("Unable to get decimal array");
+    indices.iter().map(|i| array.value(*i)).collect()
+}
+
 fn get_bool_array_slice(
     array: &arrow_array::BooleanArray,
     indices: &[usize],
@@ -1003,6 +1033,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decimal_single_column() {
+        // precisions that are written as INT32, INT64 and FIXED_LEN_BYTE_ARRAY
+        for (precision, name) in &[(9, "i32"), (18, "i64"), (38, "fixed")] {
+            let max = 10_i128.pow(*precision as u32) - 1;
+            let values = DecimalArray::from_sparse_iter(
+                vec![Some(max), None, Some(-max), Some(0), Some(-12345)],
+                *precision,
+                4,
+            );
+            one_column_roundtrip(
+                &format!("decimal_{}_single_column", name),
+                Arc::new(values),
+                true,
+            );
+        }
+    }
+
     #[test]
     fn i8_single_column() {
         required_and_optional::<Int8Array, _>(0..SMALL_SIZE as i8, "i8_single_column");
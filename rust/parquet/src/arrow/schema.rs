@@ -450,21 +450,19 @@ fn arrow_to_parquet_type(field: &Field) -> Result<Type> {
         }
         DataType::Decimal(precision, scale) => {
             // Decimal precision determines the Parquet physical type to use.
-            // TODO(ARROW-12018): Enable the below after ARROW-10818 Decimal support
-            //
-            // let (physical_type, length) = if *precision > 1 && *precision <= 9 {
-            //     (PhysicalType::INT32, -1)
-            // } else if *precision <= 18 {
-            //     (PhysicalType::INT64, -1)
-            // } else {
-            //     (
-            //         PhysicalType::FIXED_LEN_BYTE_ARRAY,
-            //         decimal_length_from_precision(*precision) as i32,
-            //     )
-            // };
-            Type::primitive_type_builder(name, PhysicalType::FIXED_LEN_BYTE_ARRAY)
+            let (physical_type, length) = if *precision <= 9 {
+                (PhysicalType::INT32, -1)
+            } else if *precision <= 18 {
+                (PhysicalType::INT64, -1)
+            } else {
+                (
+                    PhysicalType::FIXED_LEN_BYTE_ARRAY,
+                    decimal_length_from_precision(*precision) as i32,
+                )
+            };
+            Type::primitive_type_builder(name, physical_type)
                 .with_repetition(repetition)
-                .with_length(decimal_length_from_precision(*precision) as i32)
+                .with_length(length)
                 .with_logical_type(Some(LogicalType::DECIMAL(DecimalType {
                     scale: *scale as i32,
                     precision: *precision as i32,
@@ -1618,6 +1616,9 @@ mod tests {
                 }
             }
             REQUIRED BINARY  dictionary_strings (STRING);
+            REQUIRED INT32   decimal_int32 (DECIMAL(8,2));
+            OPTIONAL INT64   decimal_int64 (DECIMAL(16,2));
+            REQUIRED FIXED_LEN_BYTE_ARRAY (16) decimal_fixed (DECIMAL(38,10));
         }
         ";
         let parquet_group_type = parse_message_type(message_type).unwrap();
@@ -1678,6 +1679,9 @@ mod tests {
                 DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
                 false,
             ),
+            Field::new("decimal_int32", DataType::Decimal(8, 2), false),
+            Field::new("decimal_int64", DataType::Decimal(16, 2), true),
+            Field::new("decimal_fixed", DataType::Decimal(38, 10), false),
         ];
         let arrow_schema = Schema::new(arrow_fields);
         let converted_arrow_schema = arrow_to_parquet_schema(&arrow_schema).unwrap();